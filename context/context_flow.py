@@ -20,6 +20,7 @@ import asyncio
 import json
 import os
 import sys
+from datetime import date
 from pathlib import Path
 
 # CocoIndex is optional — works without it (no memoization).
@@ -67,6 +68,57 @@ def _load_json(path: Path) -> dict | list | None:
         return None
 
 
+def _split_frontmatter(content: str) -> tuple[dict[str, str], str]:
+    """Split `---` frontmatter (flat `key: value` lines) from the body."""
+    if not content.startswith("---\n"):
+        return {}, content
+    end = content.find("\n---", 3)
+    if end == -1:
+        return {}, content
+    meta = {}
+    for line in content[4:end].splitlines():
+        key, sep, value = line.partition(":")
+        if sep:
+            meta[key.strip()] = value.strip()
+    body = content[end + 4:].split("\n", 1)
+    return meta, body[1] if len(body) > 1 else ""
+
+
+def _done_feature_ids() -> set[str]:
+    data = _load_json(PROJECT_DIR / "features.json") or {}
+    features = data if isinstance(data, list) else data.get("features", [])
+    return {f.get("id") for f in features if f.get("status") == "done"}
+
+
+def _entry_scopes(path: Path) -> list[str]:
+    """`scope:` frontmatter of an entry, as a list (empty means global)."""
+    try:
+        meta, _ = _split_frontmatter(path.read_text())
+    except Exception:
+        return []
+    value = meta.get("scope", "").strip("[]")
+    return [s.strip().strip("'\"") for s in value.split(",") if s.strip()]
+
+
+def _read_context_entry(path: Path) -> str:
+    """Body of a context entry, or "" if it is missing or has expired.
+
+    Mirrors `Frontmatter::expiry` in forge: `expires: YYYY-MM-DD` in the past
+    or `valid_until_feature` naming a done feature.
+    """
+    try:
+        meta, body = _split_frontmatter(path.read_text())
+    except Exception:
+        return ""
+    expires = meta.get("expires", "")
+    if expires and expires < date.today().isoformat():
+        return ""
+    until = meta.get("valid_until_feature", "")
+    if until and until in _done_feature_ids():
+        return ""
+    return body.strip()
+
+
 def _collect_source_files() -> list[Path]:
     """Walk the project and return source file paths (relative to PROJECT_DIR)."""
     files = []
@@ -84,6 +136,10 @@ def _load_knowledge_entries() -> str:
     """Load all context knowledge entries as markdown sections."""
     sections = []
     categories = ["decisions", "gotchas", "patterns", "references", "poc"]
+    config = _load_forge_config() or {}
+    for extra in config.get("context", {}).get("categories", []):
+        if extra not in categories and extra != "packages":
+            categories.append(extra)
     for cat in categories:
         cat_dir = CONTEXT_DIR / cat
         if not cat_dir.is_dir():
@@ -91,7 +147,7 @@ def _load_knowledge_entries() -> str:
         for md_file in sorted(cat_dir.glob("*.md")):
             if md_file.name == "INDEX.md":
                 continue
-            content = md_file.read_text().strip()
+            content = _read_context_entry(md_file)
             if content:
                 sections.append(f"### {cat}/{md_file.stem}\n{content}")
     return "\n\n".join(sections)
@@ -148,8 +204,19 @@ def _load_exec_memory(feature_id: str) -> str:
     return "\n".join(lines) if lines else ""
 
 
+def _merge_config(base: dict, over: dict) -> dict:
+    """Tables merge key by key; anything else in `over` replaces `base`."""
+    for key, value in over.items():
+        if isinstance(value, dict) and isinstance(base.get(key), dict):
+            _merge_config(base[key], value)
+        else:
+            base[key] = value
+    return base
+
+
 def _load_forge_config() -> dict | None:
-    """Load forge.toml as dict (requires tomli or tomllib)."""
+    """Load forge.toml as dict (requires tomli or tomllib), with the files
+    its `include` lists merged underneath, as forge does."""
     toml_path = PROJECT_DIR / "forge.toml"
     if not toml_path.exists():
         return None
@@ -160,8 +227,18 @@ def _load_forge_config() -> dict | None:
             import tomli as tomllib  # type: ignore[no-redef]
         except ImportError:
             return None
+
+    def load(path: Path, stack: tuple[Path, ...]) -> dict:
+        if path.resolve() in stack:
+            raise ValueError(f"include loop through {path}")
+        layer = tomllib.loads(path.read_text())
+        merged: dict = {}
+        for include in layer.pop("include", []):
+            _merge_config(merged, load(path.parent / include, stack + (path.resolve(),)))
+        return _merge_config(merged, layer)
+
     try:
-        return tomllib.loads(toml_path.read_text())
+        return load(toml_path, ())
     except Exception:
         return None
 
@@ -288,8 +365,8 @@ def _load_scope_context(scope: str, summarize: bool = False) -> list[str]:
         for md_file in sorted(cat_dir.glob("*.md")):
             if md_file.name == "INDEX.md":
                 continue
-            if scope.lower().replace("-", "") in md_file.stem.lower().replace("-", ""):
-                content = md_file.read_text().strip()
+            if scope in _entry_scopes(md_file) or scope.lower().replace("-", "") in md_file.stem.lower().replace("-", ""):
+                content = _read_context_entry(md_file)
                 if content:
                     if summarize:
                         summary = _summarize_md(content)
@@ -339,7 +416,7 @@ def compile_completed_package(
         for hint in hints:
             hint_path = CONTEXT_DIR / f"{hint}.md"
             if hint_path.exists():
-                content = hint_path.read_text().strip()
+                content = _read_context_entry(hint_path)
                 if content:
                     summary = _summarize_md(content, max_lines=3)
                     linked.append(f"- **{hint}**: {summary}")
@@ -347,14 +424,14 @@ def compile_completed_package(
             lines.append("\n## Linked Context")
             lines.extend(linked)
             paths = [f"`context/{h}.md`" for h in hints
-                     if (CONTEXT_DIR / f"{h}.md").exists()]
+                     if _read_context_entry(CONTEXT_DIR / f"{h}.md")]
             if paths:
                 lines.append(f"\n> **Full details**: {', '.join(paths)}")
 
     # POC Results — summary + pointer
     poc_path = CONTEXT_DIR / "poc" / f"{feature['id']}.md"
     if poc_path.exists():
-        content = poc_path.read_text().strip()
+        content = _read_context_entry(poc_path)
         if content:
             summary = _summarize_md(content, max_lines=5)
             lines.append("\n## POC Results")
@@ -462,7 +539,7 @@ def compile_package(
         for hint in hints:
             hint_path = CONTEXT_DIR / f"{hint}.md"
             if hint_path.exists():
-                content = hint_path.read_text().strip()
+                content = _read_context_entry(hint_path)
                 if content:
                     summary = _summarize_md(content, max_lines=3)
                     linked.append(f"- **{hint}**: {summary}")
@@ -470,18 +547,23 @@ def compile_package(
             lines.append("\n## Relevant Context")
             lines.extend(linked)
             paths = [f"`context/{h}.md`" for h in hints
-                     if (CONTEXT_DIR / f"{h}.md").exists()]
+                     if _read_context_entry(CONTEXT_DIR / f"{h}.md")]
             if paths:
                 lines.append(f"\n> **Full details**: {', '.join(paths)}")
 
-    # Gotchas — always worth knowing (short warnings, <50 lines each)
+    # Gotchas — always worth knowing (short warnings, <50 lines each).
+    # Ones scoped to this feature's scope come first; other scopes' are left out.
     gotchas_dir = CONTEXT_DIR / "gotchas"
     if gotchas_dir.is_dir():
         gotcha_lines = []
-        for md_file in sorted(gotchas_dir.glob("*.md")):
-            if md_file.name == "INDEX.md":
-                continue
-            content = md_file.read_text().strip()
+        scope = feature.get("scope", "")
+        gotcha_files = [
+            f for f in sorted(gotchas_dir.glob("*.md"))
+            if f.name != "INDEX.md" and (not _entry_scopes(f) or scope in _entry_scopes(f))
+        ]
+        gotcha_files.sort(key=lambda f: not _entry_scopes(f))
+        for md_file in gotcha_files:
+            content = _read_context_entry(md_file)
             if content:
                 summary = _summarize_md(content, max_lines=2)
                 gotcha_lines.append(f"- **{md_file.stem}**: {summary}")
@@ -0,0 +1,159 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+
+/// `.forge/logs/<agent>.log`: one agent's output, rewritten each session.
+pub fn log_path(project_dir: &Path, agent: &str) -> PathBuf {
+    project_dir.join(".forge/logs").join(format!("{agent}.log"))
+}
+
+/// Every agent with a log, sorted; forge's own rotated `forge.<date>.log`
+/// files aren't agent logs.
+pub fn agents(project_dir: &Path) -> Vec<String> {
+    let mut agents: Vec<String> = std::fs::read_dir(project_dir.join(".forge/logs"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(".log").map(str::to_string))
+        .filter(|name| !name.starts_with("forge."))
+        .collect();
+    agents.sort();
+    agents
+}
+
+/// Write one line of agent output, stamped with the time and the feature
+/// the session is on, so lines from several agents can be merged later.
+pub fn write_line(out: &mut impl Write, feature: Option<&str>, line: &str) {
+    let ts = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+    let _ = match feature {
+        Some(feature) => writeln!(out, "{ts} [{feature}] {line}"),
+        None => writeln!(out, "{ts} {line}"),
+    };
+}
+
+/// A log line split into its stamp and the rest; lines written before
+/// forge stamped them have no stamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub agent: String,
+    pub ts: Option<DateTime<Utc>>,
+    pub text: String,
+}
+
+impl LogLine {
+    pub fn parse(agent: &str, line: &str) -> Self {
+        let stamped = line.split_once(' ').and_then(|(ts, text)| {
+            let ts = DateTime::parse_from_rfc3339(ts).ok()?;
+            Some((ts.with_timezone(&Utc), text))
+        });
+        let (ts, text) = match stamped {
+            Some((ts, text)) => (Some(ts), text),
+            None => (None, line),
+        };
+        LogLine { agent: agent.to_string(), ts, text: text.to_string() }
+    }
+
+    /// `10:04:31 [f003] text`, with the agent ahead of it when `with_agent`.
+    pub fn display(&self, with_agent: bool, agent_width: usize) -> String {
+        let ts = self.ts.map_or_else(|| " ".repeat(8), |ts| ts.with_timezone(&Local).format("%H:%M:%S").to_string());
+        if with_agent {
+            format!("{:<agent_width$}  {ts} {}", self.agent, self.text)
+        } else {
+            format!("{ts} {}", self.text)
+        }
+    }
+}
+
+/// Every agent's lines in time order, as `forge logs --all` shows them.
+/// An unstamped line takes the stamp of the line before it in its file.
+pub fn interleave(project_dir: &Path, agents: &[String]) -> Vec<LogLine> {
+    let mut lines: Vec<LogLine> = Vec::new();
+    for agent in agents {
+        let Ok(content) = std::fs::read_to_string(log_path(project_dir, agent)) else { continue };
+        let mut last = None;
+        for line in content.lines() {
+            let mut parsed = LogLine::parse(agent, line);
+            last = parsed.ts.or(last);
+            parsed.ts = last;
+            lines.push(parsed);
+        }
+    }
+    // Stable, so each file's own order holds among equal stamps
+    lines.sort_by_key(|l| l.ts);
+    lines
+}
+
+/// Reads what's been added to a log since the last poll, like `tail -f`. A
+/// new session truncates the file; reading starts over from the top then.
+pub struct Follower {
+    pub agent: String,
+    path: PathBuf,
+    pos: u64,
+    partial: String,
+}
+
+impl Follower {
+    /// Follow `agent`'s log from its current end, or from the top for a
+    /// log that appeared after following began.
+    pub fn new(project_dir: &Path, agent: &str, at_end: bool) -> Self {
+        let path = log_path(project_dir, agent);
+        let pos = if at_end { std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0) } else { 0 };
+        Follower { agent: agent.to_string(), path, pos, partial: String::new() }
+    }
+
+    /// Whole lines written since the last call.
+    pub fn poll(&mut self) -> Vec<LogLine> {
+        let Ok(mut file) = std::fs::File::open(&self.path) else { return Vec::new() };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.pos {
+            self.pos = 0;
+            self.partial.clear();
+        }
+        let mut added = String::new();
+        if file.seek(SeekFrom::Start(self.pos)).is_err() || file.read_to_string(&mut added).is_err() {
+            return Vec::new();
+        }
+        self.pos += added.len() as u64;
+        self.partial.push_str(&added);
+        let Some(end) = self.partial.rfind('\n') else { return Vec::new() };
+        let complete: String = self.partial.drain(..=end).collect();
+        complete.lines().map(|line| LogLine::parse(&self.agent, line)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleaves_agents_and_follows_new_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".forge/logs")).unwrap();
+        let write = |agent: &str, text: &str| std::fs::write(log_path(dir.path(), agent), text).unwrap();
+        write("agent-1", "2026-03-01T10:00:00.000Z [f001] start\nno stamp\n2026-03-01T10:00:02.000Z [f001] done\n");
+        write("agent-2", "2026-03-01T10:00:01.000Z [f002] start\n");
+        write("forge.2026-03-01", "not an agent\n");
+
+        let agents = agents(dir.path());
+        assert_eq!(agents, ["agent-1", "agent-2"]);
+        let texts: Vec<String> =
+            interleave(dir.path(), &agents).iter().map(|l| format!("{} {}", l.agent, l.text)).collect();
+        assert_eq!(
+            texts,
+            ["agent-1 [f001] start", "agent-1 no stamp", "agent-2 [f002] start", "agent-1 [f001] done"]
+        );
+
+        let mut follower = Follower::new(dir.path(), "agent-2", true);
+        assert!(follower.poll().is_empty());
+        let mut file = std::fs::OpenOptions::new().append(true).open(log_path(dir.path(), "agent-2")).unwrap();
+        write_line(&mut file, Some("f002"), "half");
+        file.write_all(b"2026-03-01T10:00:03.000Z und").unwrap();
+        assert_eq!(follower.poll()[0].text, "[f002] half");
+        file.write_all(b"one\n").unwrap();
+        assert_eq!(follower.poll()[0].text, "undone", "the rest of the line");
+
+        write("agent-2", "2026-03-01T11:00:00.000Z [f004] next session\n");
+        assert_eq!(follower.poll()[0].text, "[f004] next session");
+    }
+}
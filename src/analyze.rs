@@ -0,0 +1,296 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::config::Scope;
+use crate::design;
+use crate::git;
+
+/// Extension -> language, for counting what a codebase is written in.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("py", "Python"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("scala", "Scala"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("swift", "Swift"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("zig", "Zig"),
+];
+
+/// Directories whose children are the modules: `src/db/`, `crates/parser/`.
+const CONTAINERS: &[&str] =
+    &["src", "lib", "crates", "packages", "apps", "services", "pkg", "internal", "cmd", "modules"];
+/// Top-level directories that hold no module of their own.
+const NOT_MODULES: &[&str] = &["docs", "doc", "examples", "scripts", "benches", "vendor", "third_party", "fixtures"];
+/// Skipped when the project isn't a git repo and there's no .gitignore to go by.
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "vendor", "__pycache__", "venv"];
+/// A language under this share of the source files is left out of the stack.
+const MIN_LANGUAGE_SHARE: f64 = 0.1;
+
+/// What `forge init --from-existing` learned about a codebase.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// Languages by source file count, most first.
+    pub languages: Vec<(String, usize)>,
+    /// Build tools from their manifests: `cargo`, `npm`, `go`, ...
+    pub build_systems: Vec<String>,
+    pub test_command: Option<String>,
+    /// Scope name -> the module directory (or files) it owns.
+    pub modules: BTreeMap<String, Vec<String>>,
+}
+
+/// Files in the project: git's tracked and unignored ones in a repo, else
+/// a walk that skips dependency and build directories.
+fn project_files(dir: &Path) -> Vec<String> {
+    if git::is_git_repo(dir)
+        && let Ok(listing) = git::run(dir, &["ls-files", "--cached", "--others", "--exclude-standard"])
+    {
+        return listing.lines().map(String::from).collect();
+    }
+    let mut files = Vec::new();
+    let mut stack = vec![String::new()];
+    while let Some(rel) = stack.pop() {
+        for entry in std::fs::read_dir(dir.join(&rel)).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = if rel.is_empty() { name.clone() } else { format!("{rel}/{name}") };
+            match entry.file_type() {
+                Ok(t) if t.is_dir() && !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_str()) => {
+                    stack.push(path)
+                }
+                Ok(t) if t.is_file() => files.push(path),
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn language(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?;
+    LANGUAGES.iter().find(|(e, _)| *e == ext).map(|(_, lang)| *lang)
+}
+
+fn count_languages(files: &[String]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for lang in files.iter().filter_map(|f| language(f)) {
+        *counts.entry(lang).or_default() += 1;
+    }
+    let mut languages: Vec<(String, usize)> = counts.into_iter().map(|(l, n)| (l.to_string(), n)).collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    languages
+}
+
+/// Build tools and the test command, from manifests at the root. A
+/// Makefile `test` target wins, being the project's own entry point.
+fn build_and_test(dir: &Path) -> (Vec<String>, Option<String>) {
+    let exists = |name: &str| dir.join(name).exists();
+    let mut builds = Vec::new();
+    let mut tests = Vec::new();
+    let makefile = std::fs::read_to_string(dir.join("Makefile")).unwrap_or_default();
+    if makefile.lines().any(|l| l.starts_with("test:")) {
+        tests.push("make test".to_string());
+    }
+    if exists("Cargo.toml") {
+        builds.push("cargo");
+        tests.push("cargo test".into());
+    }
+    if exists("package.json") {
+        let tool = if exists("pnpm-lock.yaml") {
+            "pnpm"
+        } else if exists("yarn.lock") {
+            "yarn"
+        } else {
+            "npm"
+        };
+        builds.push(tool);
+        let package = std::fs::read_to_string(dir.join("package.json")).unwrap_or_default();
+        let has_test = serde_json::from_str::<serde_json::Value>(&package)
+            .is_ok_and(|p| p.pointer("/scripts/test").is_some_and(serde_json::Value::is_string));
+        if has_test {
+            tests.push(format!("{tool} test"));
+        }
+    }
+    if exists("go.mod") {
+        builds.push("go");
+        tests.push("go test ./...".into());
+    }
+    if exists("pyproject.toml") || exists("setup.py") || exists("requirements.txt") {
+        builds.push(if exists("uv.lock") { "uv" } else if exists("poetry.lock") { "poetry" } else { "pip" });
+        tests.push("pytest".into());
+    }
+    if exists("pom.xml") {
+        builds.push("maven");
+        tests.push("mvn test".into());
+    }
+    if exists("build.gradle") || exists("build.gradle.kts") {
+        builds.push("gradle");
+        tests.push(if exists("gradlew") { "./gradlew test" } else { "gradle test" }.into());
+    }
+    if exists("CMakeLists.txt") {
+        builds.push("cmake");
+        tests.push("ctest --test-dir build".into());
+    }
+    if exists("mix.exs") {
+        builds.push("mix");
+        tests.push("mix test".into());
+    }
+    if exists("Gemfile") {
+        builds.push("bundler");
+        tests.push(if exists("spec") { "bundle exec rspec" } else { "bundle exec rake test" }.into());
+    }
+    (builds.into_iter().map(String::from).collect(), tests.into_iter().next())
+}
+
+/// A scope name from a directory: lowercase, dashes for anything else.
+fn scope_name(dir: &str) -> String {
+    let name: String = dir.to_lowercase().chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+    name.trim_matches('-').into()
+}
+
+/// One module per directory under a container (`src/db/`) and per other
+/// top-level source directory (`mypkg/`); a container's loose source files
+/// make a module of their own (`src/*.rs`).
+fn modules(files: &[String]) -> BTreeMap<String, Vec<String>> {
+    // Directory -> owns, in the order found
+    let mut found: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files.iter().filter(|f| language(f).is_some()) {
+        let parts: Vec<&str> = file.split('/').collect();
+        let owns = match parts.as_slice() {
+            [container, module, _, ..] if CONTAINERS.contains(container) => {
+                (format!("{container}/{module}"), format!("{container}/{module}/"))
+            }
+            [container, file] if CONTAINERS.contains(container) => {
+                let ext = Path::new(file).extension().and_then(|e| e.to_str()).unwrap_or("");
+                (container.to_string(), format!("{container}/*.{ext}"))
+            }
+            [top, _, ..] if !NOT_MODULES.contains(top) && !top.starts_with('.') => (top.to_string(), format!("{top}/")),
+            _ => continue,
+        };
+        let entry = found.entry(owns.0).or_default();
+        if !entry.contains(&owns.1) {
+            entry.push(owns.1);
+        }
+    }
+
+    let mut modules = BTreeMap::new();
+    for (dir, owns) in found {
+        let last = dir.rsplit('/').next().unwrap_or(&dir);
+        let name = if modules.contains_key(&scope_name(last)) { scope_name(&dir) } else { scope_name(last) };
+        modules.insert(name, owns);
+    }
+    modules
+}
+
+/// Look over the codebase in `dir`.
+pub fn analyze(dir: &Path) -> Analysis {
+    let files = project_files(dir);
+    let (build_systems, test_command) = build_and_test(dir);
+    Analysis { languages: count_languages(&files), build_systems, test_command, modules: modules(&files) }
+}
+
+impl Analysis {
+    /// `[project] stack`: the main languages, then the build tools, e.g.
+    /// `Rust, TypeScript, cargo, npm`.
+    pub fn stack(&self) -> String {
+        let total: usize = self.languages.iter().map(|(_, n)| n).sum();
+        self.languages
+            .iter()
+            .filter(|(_, n)| *n as f64 >= total as f64 * MIN_LANGUAGE_SHARE)
+            .take(3)
+            .map(|(lang, _)| lang.as_str())
+            .chain(self.build_systems.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// `[scopes.*]`, one per module, owning its paths.
+    pub fn scopes(&self) -> BTreeMap<String, Scope> {
+        self.modules
+            .iter()
+            .map(|(name, owns)| (name.clone(), Scope { owns: owns.clone(), ..Scope::default() }))
+            .collect()
+    }
+
+    /// The DESIGN.md skeleton with what was found (stack, test command,
+    /// modules) filled in.
+    pub fn design_draft(&self, name: &str) -> String {
+        let mut dependencies = String::new();
+        let languages: Vec<String> = self.languages.iter().map(|(lang, n)| format!("{lang} ({n} files)")).collect();
+        let languages = if languages.is_empty() { "none found".into() } else { languages.join(", ") };
+        let _ = writeln!(dependencies, "- Languages: {languages}");
+        if !self.build_systems.is_empty() {
+            let _ = writeln!(dependencies, "- Build: {}", self.build_systems.join(", "));
+        }
+        let tests = self.test_command.as_ref().map_or("none found".into(), |c| format!("`{c}`"));
+        let _ = writeln!(dependencies, "- Tests: {tests}");
+        let mut api = String::from("Modules, one scope each in forge.toml:\n\n");
+        for (scope, owns) in &self.modules {
+            let owns: Vec<String> = owns.iter().map(|o| format!("`{o}`")).collect();
+            let _ = writeln!(api, "- {scope}: {}", owns.join(", "));
+        }
+        let summary = "Drafted by `forge init --from-existing` from the code as it stands.";
+        design::skeleton(name, summary, &[("Dependencies", dependencies), ("API Surface", api)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn finds_stack_tests_and_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", "[package]\nname = \"app\"\n");
+        write(dir.path(), "Makefile", "build:\n\tcargo build\ntest:\n\tcargo test --all\n");
+        for file in ["src/main.rs", "src/cli.rs", "src/db/mod.rs", "src/db/pool.rs", "src/api/mod.rs", "tests/e2e.rs"] {
+            write(dir.path(), file, "");
+        }
+        write(dir.path(), "scripts/gen.py", "");
+        write(dir.path(), "target/debug/build.rs", "");
+
+        let analysis = analyze(dir.path());
+        assert_eq!(analysis.languages, [("Rust".to_string(), 6), ("Python".to_string(), 1)]);
+        assert_eq!(analysis.stack(), "Rust, Python, cargo");
+        assert_eq!(analysis.test_command.as_deref(), Some("make test"));
+        let modules: Vec<(&str, Vec<&str>)> =
+            analysis.modules.iter().map(|(k, v)| (k.as_str(), v.iter().map(String::as_str).collect())).collect();
+        assert_eq!(
+            modules,
+            [("api", vec!["src/api/"]), ("db", vec!["src/db/"]), ("src", vec!["src/*.rs"]), ("tests", vec!["tests/"])]
+        );
+
+        let design = analysis.design_draft("app");
+        assert!(design.contains("- Tests: `make test`\n"), "{design}");
+        assert!(design.contains("- db: `src/db/`\n"), "{design}");
+    }
+
+    #[test]
+    fn same_named_modules_keep_their_parent() {
+        let files: Vec<String> = ["crates/core/src/lib.rs", "packages/core/index.ts"].map(String::from).to_vec();
+        let modules = modules(&files);
+        assert_eq!(modules.keys().collect::<Vec<_>>(), ["core", "packages-core"]);
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use tracing::{info, warn};
+
+use crate::config::ForgeConfig;
+use crate::events::{self, Event};
+use crate::features::{FeatureError, FeatureList};
+use crate::runner;
+
+/// Requests with a larger body are refused.
+const MAX_BODY: usize = 1 << 20;
+/// How often `/events` looks for new lines, and how long it stays quiet
+/// before sending a keep-alive comment.
+const POLL: Duration = Duration::from_millis(500);
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+/// Held across a claim's load, claim and save: connections get a thread
+/// each, and two claims racing on features.json could both win.
+static CLAIMS: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    /// Header names lowercased.
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// What a route answers with.
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    Json(u16, Value),
+    /// `GET /events`: a text/event-stream, starting after `since` if given,
+    /// else with the next event written.
+    Events { since: Option<DateTime<Utc>> },
+}
+
+fn error(status: u16, message: impl Into<String>) -> Reply {
+    Reply::Json(status, json!({ "error": message.into() }))
+}
+
+/// A query key or value with `+` as a space and `%XX` escapes decoded;
+/// malformed escapes are kept as they are.
+fn decode_query(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if let Some(byte) = text.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) => {
+                out.push(byte);
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Read one HTTP/1.1 request.
+pub fn read_request(reader: &mut impl BufRead) -> std::io::Result<Request> {
+    let bad = |what: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, what.to_string());
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad("malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode_query(k), decode_query(v))
+            })
+            .collect(),
+        ..Request::default()
+    };
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            request.headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    let length: usize = request.headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+    if length > MAX_BODY {
+        return Err(bad("body too large"));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+/// Answer `request` for the project in `project_dir`.
+pub fn route(project_dir: &Path, request: &Request) -> Reply {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["features"]) => match FeatureList::load(project_dir) {
+            Ok(list) => Reply::Json(200, json!(list)),
+            Err(e) => error(500, e.to_string()),
+        },
+        ("POST", ["features", id, "claim"]) => claim(project_dir, id, &request.body),
+        ("POST", ["stop"]) => match runner::request_stop(project_dir) {
+            Ok(()) => Reply::Json(202, json!({ "stop_requested": true })),
+            Err(e) => error(500, e.to_string()),
+        },
+        ("GET", ["status"]) => status(project_dir),
+        ("GET", ["events"]) => match request.query.get("since").map(|s| DateTime::parse_from_rfc3339(s)) {
+            None => Reply::Events { since: None },
+            Some(Ok(since)) => Reply::Events { since: Some(since.with_timezone(&Utc)) },
+            Some(Err(e)) => error(400, format!("since: {e}")),
+        },
+        (_, ["features"] | ["features", _, "claim"] | ["stop"] | ["status"] | ["events"]) => {
+            error(405, format!("{} not allowed on {}", request.method, request.path))
+        }
+        _ => error(404, format!("no route for {}", request.path)),
+    }
+}
+
+/// `POST /features/:id/claim`, with an optional `{"agent": ...}` body.
+fn claim(project_dir: &Path, id: &str, body: &[u8]) -> Reply {
+    let agent = serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("agent").and_then(Value::as_str).map(String::from))
+        .unwrap_or_else(|| "api".to_string());
+    let _claiming = CLAIMS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut list = match FeatureList::load(project_dir) {
+        Ok(list) => list,
+        Err(e) => return error(500, e.to_string()),
+    };
+    match list.claim(id, &agent) {
+        Ok(()) => {}
+        Err(e @ FeatureError::NotFound(_)) => return error(404, e.to_string()),
+        Err(e @ (FeatureError::AlreadyClaimed(..) | FeatureError::DepsNotMet(..))) => return error(409, e.to_string()),
+        Err(e) => return error(500, e.to_string()),
+    }
+    if let Err(e) = list.save(project_dir) {
+        return error(500, e.to_string());
+    }
+    events::emit(project_dir, &agent, events::EventKind::Claim { feature_id: id.to_string() });
+    let feature = list.features.iter().find(|f| f.id == id);
+    Reply::Json(200, json!(feature))
+}
+
+fn status(project_dir: &Path) -> Reply {
+    let list = match FeatureList::load(project_dir) {
+        Ok(list) => list,
+        Err(e) => return error(500, e.to_string()),
+    };
+    let counts = list.status_counts();
+    let project = ForgeConfig::load(project_dir).map(|c| c.project.name).unwrap_or_default();
+    Reply::Json(
+        200,
+        json!({
+            "project": project,
+            "total": counts.total,
+            "pending": counts.pending,
+            "claimed": counts.claimed,
+            "done": counts.done,
+            "blocked": counts.blocked,
+            "waiting": counts.waiting,
+            "all_done": list.all_done(),
+            "stop_requested": runner::stop_requested(project_dir),
+        }),
+    )
+}
+
+fn write_response(stream: &mut impl Write, status: u16, content_type: &str, body: &[u8]) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)
+}
+
+/// A stream's new complete lines since `offset`, moving it past them.
+fn new_lines(path: &Path, offset: &mut u64) -> Vec<String> {
+    let Ok(mut file) = fs::File::open(path) else {
+        return Vec::new();
+    };
+    if file.seek(SeekFrom::Start(*offset)).is_err() {
+        return Vec::new();
+    }
+    let mut buf = String::new();
+    let _ = file.read_to_string(&mut buf);
+    // A torn final write is picked up next time, once it's whole
+    let complete = buf.rfind('\n').map_or(0, |i| i + 1);
+    *offset += complete as u64;
+    buf[..complete].lines().map(String::from).collect()
+}
+
+fn streams(project_dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(events::events_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Send events as server-sent events until the client goes away.
+fn stream_events(stream: &mut TcpStream, project_dir: &Path, since: Option<DateTime<Utc>>) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n"
+    )?;
+    let mut offsets: BTreeMap<PathBuf, u64> = BTreeMap::new();
+    if since.is_none() {
+        for path in streams(project_dir) {
+            let len = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            offsets.insert(path, len);
+        }
+    }
+    let mut quiet_since = Instant::now();
+    loop {
+        let mut batch: Vec<Event> = Vec::new();
+        for path in streams(project_dir) {
+            let offset = offsets.entry(path.clone()).or_insert(0);
+            batch.extend(new_lines(&path, offset).iter().filter_map(|line| serde_json::from_str::<Event>(line).ok()));
+        }
+        batch.retain(|e| since.is_none_or(|since| e.ts > since));
+        batch.sort_by_key(|e| e.ts);
+        for event in &batch {
+            let kind = serde_json::to_value(event).ok().and_then(|v| v["event"].as_str().map(String::from));
+            let data = serde_json::to_string(event).unwrap_or_default();
+            write!(stream, "event: {}\ndata: {data}\n\n", kind.unwrap_or_default())?;
+        }
+        if !batch.is_empty() {
+            stream.flush()?;
+            quiet_since = Instant::now();
+        } else if quiet_since.elapsed() >= KEEP_ALIVE {
+            stream.write_all(b": keep-alive\n\n")?;
+            stream.flush()?;
+            quiet_since = Instant::now();
+        }
+        thread::sleep(POLL);
+    }
+}
+
+fn handle(mut stream: TcpStream, project_dir: &Path, token: Option<&str>) -> std::io::Result<()> {
+    let request = {
+        let mut reader = BufReader::new(&stream);
+        match read_request(&mut reader) {
+            Ok(request) => request,
+            Err(e) => {
+                let body = json!({ "error": e.to_string() }).to_string();
+                return write_response(&mut stream, 400, "application/json", body.as_bytes());
+            }
+        }
+    };
+    let authorized = token.is_none_or(|token| {
+        request.headers.get("authorization").and_then(|h| h.strip_prefix("Bearer ")) == Some(token)
+    });
+    let reply = if authorized { route(project_dir, &request) } else { error(401, "missing or wrong bearer token") };
+    match reply {
+        Reply::Json(status, body) => {
+            if status >= 400 {
+                warn!(status, "{} {}: {}", request.method, request.path, body["error"].as_str().unwrap_or_default());
+            }
+            write_response(&mut stream, status, "application/json", body.to_string().as_bytes())
+        }
+        Reply::Events { since } => stream_events(&mut stream, project_dir, since),
+    }
+}
+
+/// Serve the API on `addr` until the process is killed, a thread per
+/// connection. With `token`, every request needs `Authorization: Bearer <token>`.
+pub fn serve(project_dir: &Path, addr: &str, token: Option<String>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    info!("forge API listening on http://{}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let project_dir = project_dir.to_path_buf();
+        let token = token.clone();
+        thread::spawn(move || {
+            let _ = handle(stream, &project_dir, token.as_deref());
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{Feature, FeatureStatus, FeatureType};
+    use crate::init;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request { method: method.into(), path: path.into(), body: body.as_bytes().to_vec(), ..Request::default() }
+    }
+
+    #[test]
+    fn parses_request_line_headers_and_body() {
+        let raw = "POST /features/f001/claim?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 17\r\n\r\n\
+                   {\"agent\": \"bot\"}\n";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/features/f001/claim");
+        assert_eq!(request.query["x"], "1");
+        assert_eq!(request.headers["host"], "localhost");
+        assert_eq!(request.body, b"{\"agent\": \"bot\"}\n");
+    }
+
+    #[test]
+    fn decodes_query_strings() {
+        let raw = "GET /events?since=2026-10-14T12:00:00%2B00:00&a+b=c%20d+e&bad=%zz HTTP/1.1\r\n\r\n";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.query["since"], "2026-10-14T12:00:00+00:00");
+        assert_eq!(request.query["a b"], "c d e");
+        assert_eq!(request.query["bad"], "%zz");
+        let since = DateTime::parse_from_rfc3339("2026-10-14T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(route(Path::new("."), &request), Reply::Events { since: Some(since) });
+    }
+
+    #[test]
+    fn routes_claim_status_and_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        init::init_project(dir.path(), "api", false).unwrap();
+        let list = FeatureList {
+            features: vec![Feature {
+                id: "f001".into(),
+                feature_type: FeatureType::Implement,
+                scope: "core".into(),
+                description: "One".into(),
+                verify: "scripts/verify/f001.sh".into(),
+                depends_on: vec![],
+                priority: 1,
+                status: FeatureStatus::Pending,
+                claimed_by: None,
+                blocked_reason: None,
+                context_hints: vec![],
+            }],
+        };
+        list.save(dir.path()).unwrap();
+
+        let claim = |body: &str| route(dir.path(), &request("POST", "/features/f001/claim", body));
+        let Reply::Json(200, feature) = claim(r#"{"agent":"bot"}"#) else {
+            panic!("claim refused");
+        };
+        assert_eq!(feature["claimed_by"], "bot");
+        assert!(matches!(claim(""), Reply::Json(409, _)));
+        assert!(matches!(route(dir.path(), &request("POST", "/features/x999/claim", "")), Reply::Json(404, _)));
+
+        let Reply::Json(200, status) = route(dir.path(), &request("GET", "/status", "")) else { unreachable!() };
+        assert_eq!(status["claimed"], 1);
+        assert_eq!(status["stop_requested"], false);
+        assert!(matches!(route(dir.path(), &request("POST", "/stop", "")), Reply::Json(202, _)));
+        assert!(runner::stop_requested(dir.path()));
+
+        assert!(matches!(route(dir.path(), &request("DELETE", "/status", "")), Reply::Json(405, _)));
+        assert!(matches!(route(dir.path(), &request("GET", "/nope", "")), Reply::Json(404, _)));
+        assert_eq!(route(dir.path(), &request("GET", "/events", "")), Reply::Events { since: None });
+    }
+
+    #[test]
+    fn simultaneous_claims_have_one_winner() {
+        let dir = tempfile::tempdir().unwrap();
+        let feature = r#"{"id":"f001","type":"implement","scope":"core","description":"One","verify":"true"}"#;
+        std::fs::write(dir.path().join("features.json"), format!(r#"{{"features":[{feature}]}}"#)).unwrap();
+        let barrier = std::sync::Barrier::new(2);
+        let claim = |agent: &str| {
+            barrier.wait();
+            match route(dir.path(), &request("POST", "/features/f001/claim", &format!(r#"{{"agent":"{agent}"}}"#))) {
+                Reply::Json(status, _) => status,
+                other => panic!("{other:?}"),
+            }
+        };
+        let mut statuses = thread::scope(|s| {
+            let a = s.spawn(|| claim("a"));
+            let b = s.spawn(|| claim("b"));
+            [a.join().unwrap(), b.join().unwrap()]
+        });
+        statuses.sort();
+        assert_eq!(statuses, [200, 409]);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::features::FeatureList;
+
+/// Who changed what in features.json, one JSON object per line. Only ever
+/// appended to.
+pub fn audit_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/audit.jsonl")
+}
+
+/// features.json as last recorded, to tell edits made outside forge (an
+/// agent or a person with an editor) from forge's own.
+fn snapshot_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/audit-snapshot.json")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Added,
+    Removed,
+    Updated,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub ts: DateTime<Utc>,
+    /// `FORGE_AGENT_ID`, else the user; for edits found on disk, who forge
+    /// last handed the project to.
+    pub actor: String,
+    /// The forge command that saved, or `external` for edits found on disk.
+    pub process: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    pub feature_id: String,
+    pub action: Action,
+    /// Fields that changed, for `updated`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub changes: BTreeMap<String, FieldChange>,
+}
+
+/// What changed per feature between two lists, by id, in `after`'s order
+/// with removals last.
+pub fn diff(before: &FeatureList, after: &FeatureList) -> Vec<(String, Action, BTreeMap<String, FieldChange>)> {
+    let fields = |list: &FeatureList| -> BTreeMap<String, serde_json::Map<String, Value>> {
+        list.features
+            .iter()
+            .filter_map(|f| match serde_json::to_value(f) {
+                Ok(Value::Object(map)) => Some((f.id.clone(), map)),
+                _ => None,
+            })
+            .collect()
+    };
+    let (old, new) = (fields(before), fields(after));
+    let mut out = Vec::new();
+    for feature in &after.features {
+        let Some(now) = new.get(&feature.id) else { continue };
+        let Some(was) = old.get(&feature.id) else {
+            out.push((feature.id.clone(), Action::Added, BTreeMap::new()));
+            continue;
+        };
+        let keys: std::collections::BTreeSet<&String> = was.keys().chain(now.keys()).collect();
+        let changes: BTreeMap<String, FieldChange> = keys
+            .into_iter()
+            .filter_map(|key| {
+                let from = was.get(key).cloned().unwrap_or(Value::Null);
+                let to = now.get(key).cloned().unwrap_or(Value::Null);
+                (from != to).then(|| (key.clone(), FieldChange { from, to }))
+            })
+            .collect();
+        if !changes.is_empty() {
+            out.push((feature.id.clone(), Action::Updated, changes));
+        }
+    }
+    for feature in &before.features {
+        if !new.contains_key(&feature.id) {
+            out.push((feature.id.clone(), Action::Removed, BTreeMap::new()));
+        }
+    }
+    out
+}
+
+/// `FORGE_AGENT_ID`, else the login name.
+pub fn current_actor() -> String {
+    ["FORGE_AGENT_ID", "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// `forge <subcommand>` for this process.
+fn current_process() -> String {
+    let args: Vec<String> = std::env::args().skip(1).filter(|a| !a.starts_with('-')).take(1).collect();
+    match args.first() {
+        Some(command) => format!("forge {command}"),
+        None => "forge".to_string(),
+    }
+}
+
+fn append(project_dir: &Path, records: &[AuditRecord]) {
+    if records.is_empty() {
+        return;
+    }
+    let path = audit_path(project_dir);
+    let _ = std::fs::create_dir_all(path.parent().expect("under .forge"));
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+        for record in records {
+            if let Ok(line) = serde_json::to_string(record) {
+                let _ = writeln!(file, "{line}");
+            }
+        }
+    }
+}
+
+/// Append the changes from `before` to `after`, made by `actor` in `process`.
+fn record(project_dir: &Path, before: &FeatureList, after: &FeatureList, actor: &str, process: &str, pid: Option<u32>) {
+    let ts = Utc::now();
+    let records: Vec<AuditRecord> = diff(before, after)
+        .into_iter()
+        .map(|(feature_id, action, changes)| AuditRecord {
+            ts,
+            actor: actor.to_string(),
+            process: process.to_string(),
+            pid,
+            feature_id,
+            action,
+            changes,
+        })
+        .collect();
+    append(project_dir, &records);
+}
+
+fn snapshot(project_dir: &Path) -> Option<FeatureList> {
+    serde_json::from_str(&std::fs::read_to_string(snapshot_path(project_dir)).ok()?).ok()
+}
+
+fn write_snapshot(project_dir: &Path, list: &FeatureList) {
+    if let Ok(json) = serde_json::to_string(list) {
+        let _ = std::fs::create_dir_all(project_dir.join(".forge"));
+        let _ = std::fs::write(snapshot_path(project_dir), json);
+    }
+}
+
+/// Record edits made to features.json outside forge since it last looked,
+/// crediting `actor`: the agent whose session just ended, say.
+pub fn sync(project_dir: &Path, actor: &str) {
+    let Ok(on_disk) = FeatureList::load(project_dir) else {
+        return;
+    };
+    match snapshot(project_dir) {
+        Some(known) if known != on_disk => record(project_dir, &known, &on_disk, actor, "external", None),
+        Some(_) => return,
+        None => {}
+    }
+    write_snapshot(project_dir, &on_disk);
+}
+
+/// Called as `list` is saved: records outside edits still unaccounted for,
+/// then this process's own changes.
+pub fn on_save(project_dir: &Path, list: &FeatureList) {
+    let on_disk = FeatureList::load(project_dir).ok();
+    if let (Some(known), Some(on_disk)) = (snapshot(project_dir), &on_disk)
+        && known != *on_disk
+    {
+        record(project_dir, &known, on_disk, "unknown", "external", None);
+    }
+    let before = on_disk.unwrap_or(FeatureList { features: vec![] });
+    record(project_dir, &before, list, &current_actor(), &current_process(), Some(std::process::id()));
+    write_snapshot(project_dir, list);
+}
+
+/// Every record, oldest first; lines that don't parse are skipped.
+pub fn read(project_dir: &Path) -> Vec<AuditRecord> {
+    std::fs::read_to_string(audit_path(project_dir))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{Feature, FeatureStatus, FeatureType};
+
+    fn feature(id: &str) -> Feature {
+        Feature {
+            id: id.into(),
+            feature_type: FeatureType::Implement,
+            scope: "core".into(),
+            description: format!("Feature {id}"),
+            verify: format!("scripts/verify/{id}.sh"),
+            depends_on: vec![],
+            priority: 1,
+            status: FeatureStatus::Pending,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    #[test]
+    fn saves_and_outside_edits_are_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut list = FeatureList { features: vec![feature("f001"), feature("f002")] };
+        list.save(dir.path()).unwrap();
+        list.claim("f001", "agent-1").unwrap();
+        list.features.remove(1);
+        list.save(dir.path()).unwrap();
+
+        // An agent edits the file by hand
+        let mut edited = list.clone();
+        edited.features[0].status = FeatureStatus::Done;
+        std::fs::write(dir.path().join("features.json"), serde_json::to_string(&edited).unwrap()).unwrap();
+        sync(dir.path(), "agent-1");
+        sync(dir.path(), "agent-1");
+
+        let records = read(dir.path());
+        let summary: Vec<(&str, Action, bool)> =
+            records.iter().map(|r| (r.feature_id.as_str(), r.action, r.process == "external")).collect();
+        assert_eq!(
+            summary,
+            [
+                ("f001", Action::Added, false),
+                ("f002", Action::Added, false),
+                ("f001", Action::Updated, false),
+                ("f002", Action::Removed, false),
+                ("f001", Action::Updated, true),
+            ]
+        );
+        assert_eq!(records[2].changes["claimed_by"], FieldChange { from: Value::Null, to: "agent-1".into() });
+        assert_eq!(records[4].actor, "agent-1");
+        assert_eq!(records[4].changes["status"].to, "done");
+        assert!(records[0].pid.is_some() && records[4].pid.is_none());
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use serde::Serialize;
+
+use crate::config::ForgeConfig;
+use crate::cost;
+use crate::events::{self, Event, EventKind};
+use crate::features::{FeatureError, FeatureList, FeatureStatus};
+use crate::git;
+use crate::runner::{self, RunConfig, RunOutcome};
+use crate::transcripts;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BenchError {
+    #[error("forge bench needs a git repo to check out worktrees from")]
+    NotGit,
+    #[error("no feature '{0}' in features.json")]
+    UnknownFeature(String),
+    #[error("name the features to bench, e.g. --features f010,f011")]
+    NoFeatures,
+    #[error("no revision '{0}'")]
+    UnknownRev(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Features(#[from] FeatureError),
+    #[error("{0}")]
+    Git(String),
+}
+
+/// `.forge/bench/<stamp>/`: one bench's worktrees while it runs, and its
+/// `results.json` after.
+pub fn bench_dir(project_dir: &Path, stamp: &str) -> PathBuf {
+    project_dir.join(".forge/bench").join(stamp)
+}
+
+/// How one configuration did on the benched features.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct BenchResult {
+    /// The profile, or `default` for forge.toml as it is.
+    pub config: String,
+    pub backend: String,
+    pub model: String,
+    /// Where its commits are kept.
+    pub branch: String,
+    pub features: usize,
+    pub done: usize,
+    /// Executor sessions across all the features.
+    pub sessions: usize,
+    /// Verify's verdicts after sessions, and the passes among them.
+    pub verified: usize,
+    pub verify_passed: usize,
+    /// Over its Claude transcripts; None for other backends.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// How the run ended: `all_done`, `max_sessions`, ...
+    pub outcome: String,
+}
+
+impl BenchResult {
+    pub fn sessions_per_done(&self) -> Option<f64> {
+        (self.done > 0).then(|| self.sessions as f64 / self.done as f64)
+    }
+
+    pub fn verify_pass_rate(&self) -> Option<f64> {
+        (self.verified > 0).then(|| self.verify_passed as f64 / self.verified as f64)
+    }
+}
+
+/// `ids` from `list`, pending again, with dependencies on features outside
+/// them dropped: their code is taken to be in the base revision.
+pub fn select(list: &FeatureList, ids: &[String]) -> Result<FeatureList, BenchError> {
+    if ids.is_empty() {
+        return Err(BenchError::NoFeatures);
+    }
+    let mut selected = FeatureList { features: Vec::new() };
+    for id in ids {
+        let feature = list.features.iter().find(|f| &f.id == id).ok_or_else(|| BenchError::UnknownFeature(id.clone()))?;
+        selected.features.push(feature.clone());
+    }
+    for feature in &mut selected.features {
+        feature.status = FeatureStatus::Pending;
+        feature.claimed_by = None;
+        feature.blocked_reason = None;
+        feature.depends_on.retain(|dep| ids.contains(dep));
+    }
+    Ok(selected)
+}
+
+/// Tally a finished bench run from its events.
+fn tally(config: &str, run: &RunConfig, events: &[Event], list: &FeatureList) -> BenchResult {
+    let mut result = BenchResult {
+        config: config.to_string(),
+        backend: run.protocol.backend.clone(),
+        model: run.protocol.model.clone(),
+        features: list.features.len(),
+        done: list.features.iter().filter(|f| f.status == FeatureStatus::Done).count(),
+        ..BenchResult::default()
+    };
+    for event in events {
+        match &event.kind {
+            EventKind::SessionStart { role, .. } if role == "executor" => result.sessions += 1,
+            EventKind::FeatureVerified { passed, .. } => {
+                result.verified += 1;
+                result.verify_passed += usize::from(*passed);
+            }
+            EventKind::RunFinished { outcome, .. } => result.outcome = outcome.clone(),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Run `features` once under each `(name, config)`, one after another,
+/// each single-agent in its own worktree of `base` on branch
+/// `forge/bench/<stamp>/<name>`. The worktrees go afterwards; the branches
+/// stay, so what each configuration wrote can be compared. Results are
+/// saved to `results.json` in the bench's directory.
+pub fn run(
+    project_dir: &Path,
+    features: &FeatureList,
+    configs: &[(String, RunConfig)],
+    base: &str,
+    on_start: &mut dyn FnMut(&str, &RunConfig),
+) -> Result<Vec<BenchResult>, BenchError> {
+    if !git::is_git_repo(project_dir) {
+        return Err(BenchError::NotGit);
+    }
+    let rev = git::rev_parse(project_dir, base).ok_or_else(|| BenchError::UnknownRev(base.to_string()))?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let dir = bench_dir(project_dir, &stamp);
+    std::fs::create_dir_all(&dir)?;
+    // forge.toml as it is now, committed or not
+    let forge_toml = std::fs::read(project_dir.join("forge.toml")).ok();
+
+    let mut results = Vec::new();
+    for (name, config) in configs {
+        let worktree = dir.join(name);
+        let branch = format!("forge/bench/{stamp}/{name}");
+        git::create_worktree(project_dir, &worktree, &branch).map_err(BenchError::Git)?;
+        git::reset_worktree(&worktree, &branch, Some(&rev)).map_err(BenchError::Git)?;
+        if let Some(forge_toml) = &forge_toml {
+            std::fs::write(worktree.join("forge.toml"), forge_toml)?;
+        }
+        features.save(&worktree)?;
+
+        let mut run = config.clone();
+        run.project_dir = worktree.clone();
+        run.num_agents = 1;
+        on_start(name, &run);
+        let outcome = runner::run_single_agent(&run);
+        runner::emit_run_finished(&worktree, &outcome);
+        if let RunOutcome::SpawnError(e) = &outcome {
+            tracing::warn!(config = %name, "bench run failed to start an agent: {e}");
+        }
+
+        let events = events::merged_timeline(&worktree).unwrap_or_default();
+        let list = FeatureList::load(&worktree)?;
+        let mut result = tally(name, &run, &events, &list);
+        result.branch = branch;
+        result.cost_usd =
+            (!transcripts::claude_transcripts(&worktree).is_empty()).then(|| cost::total_spent(&worktree));
+        results.push(result);
+        git::remove_worktree(project_dir, &worktree).map_err(BenchError::Git)?;
+    }
+    let json = serde_json::to_string_pretty(&results).map_err(std::io::Error::other)?;
+    std::fs::write(dir.join("results.json"), json)?;
+    Ok(results)
+}
+
+/// The configurations `--roles-matrix` runs: forge.toml as it is, then
+/// every `[profiles.*]`.
+pub fn matrix(config: &ForgeConfig) -> Vec<String> {
+    std::iter::once("default".to_string()).chain(config.profiles.keys().cloned()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::config::{GitConfig, Permissions, RateLimitConfig, RoleSpec, RunawayConfig};
+
+    #[test]
+    fn selects_features_and_tallies_a_run() {
+        let list: FeatureList = serde_json::from_str(
+            r#"{"features":[
+                {"id":"f001","type":"implement","scope":"a","description":"x","verify":"true","status":"done"},
+                {"id":"f002","type":"implement","scope":"a","description":"y","verify":"true","status":"done",
+                 "claimed_by":"agent-1","depends_on":["f001"]},
+                {"id":"f003","type":"implement","scope":"a","description":"z","verify":"true","depends_on":["f002"]}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(select(&list, &[]), Err(BenchError::NoFeatures)));
+        assert!(matches!(select(&list, &["f009".into()]), Err(BenchError::UnknownFeature(id)) if id == "f009"));
+        let selected = select(&list, &["f002".into(), "f003".into()]).unwrap();
+        assert_eq!(selected.features[0].status, FeatureStatus::Pending);
+        assert_eq!(selected.features[0].claimed_by, None);
+        assert!(selected.features[0].depends_on.is_empty(), "f001 is in the base");
+        assert_eq!(selected.features[1].depends_on, ["f002"]);
+
+        let event = |kind: EventKind| Event { ts: Utc::now(), agent: "agent-1".into(), kind };
+        let start = |role: &str| {
+            let (feature_id, backend, model, variant) = (None, None, None, None);
+            event(EventKind::SessionStart { role: role.into(), feature_id, backend, model, variant })
+        };
+        let verified = |passed| event(EventKind::FeatureVerified { feature_id: "f002".into(), passed });
+        let events = [
+            start("executor"),
+            verified(false),
+            start("orchestrating"),
+            start("executor"),
+            verified(true),
+            start("executor"),
+            event(EventKind::RunFinished { outcome: "max_sessions".into(), sessions: 4, remaining: 1 }),
+        ];
+        let run = RunConfig {
+            project_dir: PathBuf::new(),
+            protocol: RoleSpec { backend: "codex".into(), model: "o3".into(), ..RoleSpec::default() },
+            orchestrating: RoleSpec::default(),
+            protocol_b: None,
+            max_sessions: 4,
+            num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
+        };
+        let mut after = selected.clone();
+        after.features[0].status = FeatureStatus::Done;
+        let result = tally("cheap", &run, &events, &after);
+        assert_eq!((result.backend.as_str(), result.model.as_str()), ("codex", "o3"));
+        assert_eq!((result.features, result.done, result.sessions), (2, 1, 3));
+        assert_eq!(result.sessions_per_done(), Some(3.0));
+        assert_eq!(result.verify_pass_rate(), Some(0.5));
+        assert_eq!(result.outcome, "max_sessions");
+    }
+}
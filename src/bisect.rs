@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use crate::events::{self, EventKind};
+use crate::features::FeatureList;
+use crate::git;
+
+/// The parent of the oldest commit forge made (one with `Forge-*`
+/// trailers): where forge's part of the history starts.
+pub fn before_forge(dir: &Path) -> Result<String, String> {
+    let first = git::run(dir, &["log", "--reverse", "--format=%H", "-E", "--grep=^Forge-(Feature|Agent):"])?;
+    let first = first.lines().next().ok_or("no commits with Forge-* trailers; pass --good")?;
+    git::run(dir, &["rev-parse", "--verify", "--quiet", &format!("{first}^")])
+        .map_err(|_| "forge's first commit is the repository's first; pass --good".to_string())
+}
+
+/// `git bisect run` of `script` between `good` and HEAD; the first commit
+/// it fails on. The script is copied out of the tree first, so commits
+/// from before it existed still run it, and bisect is always reset.
+pub fn find_culprit(dir: &Path, script: &Path, good: &str) -> Result<String, String> {
+    if !git::run(dir, &["status", "--porcelain", "--untracked-files=no"])?.is_empty() {
+        return Err("the working tree has uncommitted changes; commit or stash them first".into());
+    }
+    let source = if script.is_absolute() { script.to_path_buf() } else { dir.join(script) };
+    let copy = std::env::temp_dir().join(format!("forge-bisect-{}.sh", std::process::id()));
+    std::fs::copy(&source, &copy).map_err(|e| format!("cannot read {}: {e}", script.display()))?;
+    let passes_now = Command::new("bash").arg(&copy).current_dir(dir).output().is_ok_and(|o| o.status.success());
+    if passes_now {
+        let _ = std::fs::remove_file(&copy);
+        return Err(format!("{} passes at HEAD; nothing to bisect", script.display()));
+    }
+
+    let result = git::run(dir, &["bisect", "start", "HEAD", good])
+        .and_then(|_| git::run(dir, &["bisect", "run", "bash", &copy.to_string_lossy()]));
+    let _ = git::run(dir, &["bisect", "reset"]);
+    let _ = std::fs::remove_file(&copy);
+    result?
+        .lines()
+        .find_map(|line| line.strip_suffix(" is the first bad commit"))
+        .map(String::from)
+        .ok_or_else(|| "bisect found no first bad commit".into())
+}
+
+/// A commit and the forge work it came from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Culprit {
+    pub commit: String,
+    pub subject: String,
+    pub feature_id: Option<String>,
+    pub feature: Option<String>,
+    pub agent: Option<String>,
+    pub session: Option<String>,
+    /// When the agent session that made it started, from the event log.
+    pub session_started: Option<DateTime<Utc>>,
+}
+
+/// Who made `commit`: the `Forge-*` trailers on it, else the agent session
+/// in the event log that was running when it was authored.
+pub fn attribute(dir: &Path, commit: &str) -> Result<Culprit, String> {
+    let format = "%h%n%aI%n%s%n%(trailers:key=Forge-Feature,valueonly,separator=)%n\
+                  %(trailers:key=Forge-Agent,valueonly,separator=)%n%(trailers:key=Forge-Session,valueonly,separator=)";
+    let out = git::run(dir, &["log", "-1", &format!("--format={format}"), commit])?;
+    let mut lines = out.lines().map(str::trim);
+    let mut next = || lines.next().filter(|l| !l.is_empty()).map(String::from);
+    let short = next().unwrap_or_else(|| commit.to_string());
+    let authored = next().and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok()).map(|ts| ts.with_timezone(&Utc));
+    let mut culprit = Culprit {
+        commit: short,
+        subject: next().unwrap_or_default(),
+        feature_id: next(),
+        agent: next(),
+        session: next(),
+        ..Culprit::default()
+    };
+
+    // The latest session (of the trailer's agent, if any) that had started
+    // by then and hadn't ended before
+    let timeline = events::merged_timeline(dir).unwrap_or_default();
+    let session = authored.and_then(|at| {
+        timeline
+            .iter()
+            .filter(|e| e.ts <= at && culprit.agent.as_ref().is_none_or(|a| *a == e.agent))
+            .filter_map(|e| match &e.kind {
+                EventKind::SessionStart { feature_id: Some(id), .. } => Some((e, id)),
+                _ => None,
+            })
+            .rfind(|(start, _)| {
+                !timeline.iter().any(|e| {
+                    e.agent == start.agent && e.ts > start.ts && e.ts < at && matches!(e.kind, EventKind::SessionEnd { .. })
+                })
+            })
+    });
+    if let Some((start, feature_id)) = session {
+        culprit.agent.get_or_insert_with(|| start.agent.clone());
+        culprit.feature_id.get_or_insert_with(|| feature_id.clone());
+        culprit.session_started = Some(start.ts);
+    }
+    culprit.feature = culprit.feature_id.as_ref().and_then(|id| {
+        let list = FeatureList::load(dir).ok()?;
+        list.features.into_iter().find(|f| f.id == *id).map(|f| f.description.lines().next().unwrap_or("").to_string())
+    });
+    Ok(culprit)
+}
+
+impl fmt::Display for Culprit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "First bad commit: {} {}", self.commit, self.subject)?;
+        match (&self.feature_id, &self.feature) {
+            (Some(id), Some(description)) => writeln!(f, "Feature: {id} ({description})")?,
+            (Some(id), None) => writeln!(f, "Feature: {id}")?,
+            (None, _) => writeln!(f, "Feature: unknown (no Forge-* trailers or agent session for it)")?,
+        }
+        if let Some(agent) = &self.agent {
+            write!(f, "Agent: {agent}")?;
+            if let Some(session) = &self.session {
+                write!(f, ", session {session}")?;
+            }
+            if let Some(started) = self.session_started {
+                write!(f, ", session started {}", started.format("%Y-%m-%d %H:%M UTC"))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Event;
+
+    fn run(dir: &Path, args: &[&str], env: &[(&str, &str)]) {
+        let out = Command::new("git").args(args).envs(env.iter().copied()).current_dir(dir).output().unwrap();
+        assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+    }
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        run(dir.path(), &["init", "-q"], &[]);
+        run(dir.path(), &["config", "user.email", "t@t"], &[]);
+        run(dir.path(), &["config", "user.name", "t"], &[]);
+        dir
+    }
+
+    #[test]
+    fn bisects_to_the_forge_commit_and_its_feature() {
+        let dir = repo();
+        let git = |args: &[&str], env: &[(&str, &str)]| run(dir.path(), args, env);
+        let commit = |content: &str, message: &str| {
+            std::fs::write(dir.path().join("value.txt"), content).unwrap();
+            git(&["add", "-A"], &[]);
+            git(&["commit", "-qm", message], &[]);
+        };
+        commit("good", "initial import");
+        commit("good 1", "feat(f001): one\n\nForge-Feature: f001\nForge-Agent: agent-1");
+        commit("good 2", "feat(f002): two\n\nForge-Feature: f002\nForge-Agent: agent-2\nForge-Session: 4");
+        commit("bad 3", "feat(f003): three\n\nForge-Feature: f003\nForge-Agent: agent-1\nForge-Session: 5");
+        commit("bad 4", "feat(f004): four\n\nForge-Feature: f004");
+        std::fs::write(dir.path().join("check.sh"), "grep -q good value.txt\n").unwrap();
+
+        let good = before_forge(dir.path()).unwrap();
+        let culprit = find_culprit(dir.path(), Path::new("check.sh"), &good).unwrap();
+        let culprit = attribute(dir.path(), &culprit).unwrap();
+        assert_eq!(culprit.subject, "feat(f003): three");
+        assert_eq!((culprit.feature_id.as_deref(), culprit.agent.as_deref()), (Some("f003"), Some("agent-1")));
+        assert!(culprit.to_string().ends_with("Feature: f003\nAgent: agent-1, session 5\n"), "{culprit}");
+        // Bisect was reset
+        assert!(std::fs::read_to_string(dir.path().join("value.txt")).unwrap().starts_with("bad 4"));
+
+        std::fs::write(dir.path().join("check.sh"), "true\n").unwrap();
+        let error = find_culprit(dir.path(), Path::new("check.sh"), &good).unwrap_err();
+        assert_eq!(error, "check.sh passes at HEAD; nothing to bisect");
+    }
+
+    #[test]
+    fn agent_commits_are_matched_to_sessions() {
+        let dir = repo();
+        let git = |args: &[&str], env: &[(&str, &str)]| run(dir.path(), args, env);
+        std::fs::write(dir.path().join("a.rs"), "").unwrap();
+        git(&["add", "-A"], &[]);
+        git(&["commit", "-qm", "wip parser"], &[("GIT_AUTHOR_DATE", "2026-03-01T10:30:00Z")]);
+
+        let at = |hms: &str| format!("2026-03-01T{hms}Z").parse::<DateTime<Utc>>().unwrap();
+        let start = |agent: &str, ts: &str, feature: &str| Event {
+            ts: at(ts),
+            agent: agent.into(),
+            kind: EventKind::SessionStart {
+                role: "executor".into(),
+                feature_id: Some(feature.into()),
+                backend: None,
+                model: None,
+                variant: None,
+            },
+        };
+        let end = |agent: &str, ts: &str| Event {
+            ts: at(ts),
+            agent: agent.into(),
+            kind: EventKind::SessionEnd { feature_id: None, exit_code: Some(0), timed_out: false, output_bytes: None },
+        };
+        let events = [start("agent-1", "09:00:00", "f001"), end("agent-1", "10:00:00"), start("agent-2", "10:00:00", "f002"),
+            start("agent-1", "10:20:00", "f003"), end("agent-2", "10:25:00"), end("agent-1", "11:00:00")];
+        let dir_events = events::events_dir(dir.path());
+        std::fs::create_dir_all(&dir_events).unwrap();
+        let lines: String = events.iter().map(|e| serde_json::to_string(e).unwrap() + "\n").collect();
+        std::fs::write(dir_events.join("all.jsonl"), lines).unwrap();
+
+        let culprit = attribute(dir.path(), "HEAD").unwrap();
+        assert_eq!((culprit.feature_id.as_deref(), culprit.agent.as_deref()), (Some("f003"), Some("agent-1")));
+        assert_eq!(culprit.session_started, Some(at("10:20:00")));
+    }
+}
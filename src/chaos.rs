@@ -0,0 +1,183 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::commit_message::CommitMessage;
+use crate::config::GitConfig;
+use crate::events::{self, EventKind};
+use crate::git;
+use crate::runner::STATE_PATHS;
+
+/// `forge run --chaos`: faults injected on purpose, to see retries, hooks
+/// and reopening hold up before a run that matters. Each chance comes up
+/// with probability `rate`; the same seed makes the same choices.
+pub struct Chaos {
+    pub seed: u64,
+    pub rate: f64,
+    state: Mutex<u64>,
+}
+
+/// The fault names in `chaos_injected` events.
+pub const KILL: &str = "kill_agent";
+pub const DELAY_VERIFY: &str = "delay_verify";
+pub const MERGE_CONFLICT: &str = "merge_conflict";
+
+impl Chaos {
+    pub fn new(seed: u64, rate: f64) -> Self {
+        // xorshift's state must not be zero
+        Chaos { seed, rate: rate.clamp(0.0, 1.0), state: Mutex::new(seed | 1) }
+    }
+
+    /// A seed from the clock, for runs without `--chaos-seed`.
+    pub fn clock_seed() -> u64 {
+        std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(1, |d| d.as_nanos() as u64)
+    }
+
+    fn next(&self) -> u64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    fn roll(&self) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < self.rate
+    }
+
+    fn millis(&self, low: u64, high: u64) -> Duration {
+        Duration::from_millis(low + self.next() % (high - low + 1))
+    }
+
+    /// How long to let an agent run before killing it, if it's to be killed.
+    pub fn kill_after(&self) -> Option<Duration> {
+        self.roll().then(|| self.millis(500, 20_000))
+    }
+
+    /// Sleep before a verify, sometimes.
+    pub fn delay_verify(&self, project_dir: &Path) {
+        if !self.roll() {
+            return;
+        }
+        let delay = self.millis(1_000, 15_000);
+        warn!("chaos: delaying verify {:.1}s", delay.as_secs_f64());
+        record(project_dir, DELAY_VERIFY, None);
+        thread::sleep(delay);
+    }
+
+    /// Sometimes commit a clashing rewrite of a file `branch` changed, so
+    /// merging it conflicts. Returns the commit to undo with
+    /// `undo_conflict` once the merge has been tried.
+    pub fn inject_conflict(
+        &self,
+        project_dir: &Path,
+        branch: &str,
+        feature_id: &str,
+        git_config: &GitConfig,
+    ) -> Option<String> {
+        if !self.roll() {
+            return None;
+        }
+        let base = git::merge_base(project_dir, "HEAD", branch)?;
+        let state = |path: &str| STATE_PATHS.iter().any(|s| path == *s || path.starts_with(&format!("{s}/")));
+        let path = git::changed_paths(project_dir, &base, Some(branch)).into_iter().find(|p| !state(p))?;
+        let file = project_dir.join(&path);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).ok()?;
+        }
+        std::fs::write(&file, format!("forge chaos: clashing edit of {path} while {branch} was out\n")).ok()?;
+        let message = CommitMessage::new("chore", format!("chaos: clash with {branch} in {path}"));
+        match git::commit_paths(project_dir, &[path.as_str()], &message.render(git_config)) {
+            Ok(true) => {}
+            _ => return None,
+        }
+        warn!("chaos: committed a clashing edit of {path} before merging {branch}");
+        record(project_dir, MERGE_CONFLICT, Some(feature_id));
+        git::rev_parse(project_dir, "HEAD")
+    }
+}
+
+/// Put the tree back as it was before `inject_conflict` made `commit`,
+/// keeping whatever was merged since.
+pub fn undo_conflict(project_dir: &Path, commit: &str, git_config: &GitConfig) {
+    let Some(before) = git::rev_parse(project_dir, &format!("{commit}^")) else { return };
+    let result = if git::rev_parse(project_dir, "HEAD").as_deref() == Some(commit) {
+        let message = CommitMessage::new("revert", "chaos: undo the clashing edit");
+        git::revert_to(project_dir, &before, &message.render(git_config))
+    } else {
+        // Merged anyway: undo the edit alone
+        let output = std::process::Command::new("git")
+            .args(["revert", "--no-edit", commit])
+            .current_dir(project_dir)
+            .output()
+            .map_err(|e| e.to_string());
+        match output {
+            Ok(o) if o.status.success() => Ok(()),
+            Ok(o) => Err(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+            Err(e) => Err(e),
+        }
+    };
+    if let Err(e) = result {
+        warn!("chaos: failed to undo the clashing edit {commit}: {e}");
+    }
+}
+
+pub fn record(project_dir: &Path, fault: &str, feature_id: Option<&str>) {
+    let kind = EventKind::ChaosInjected { fault: fault.to_string(), feature_id: feature_id.map(String::from) };
+    events::emit(project_dir, events::FORGE, kind);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn seeded_faults_repeat_and_conflicts_are_undone() {
+        let never = Chaos::new(7, 0.0);
+        assert!((0..100).all(|_| never.kill_after().is_none()));
+        let picks = |chaos: &Chaos| (0..20).map(|_| chaos.kill_after()).collect::<Vec<_>>();
+        let (a, b) = (Chaos::new(42, 0.5), Chaos::new(42, 0.5));
+        let kills = picks(&a);
+        assert_eq!(kills, picks(&b), "same seed, same faults");
+        assert!(kills.iter().any(Option::is_some) && kills.iter().any(Option::is_none), "{kills:?}");
+        assert!(kills.iter().flatten().all(|d| (500..=20_000).contains(&(d.as_millis() as u64))));
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let git = |args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(root).output().unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        std::fs::write(root.join(".gitignore"), ".forge/\n").unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-qm", "base"]);
+        git(&["checkout", "-qb", "forge/agent-1"]);
+        std::fs::write(root.join("lib.rs"), "fn a() -> u8 { 1 }\n").unwrap();
+        std::fs::write(root.join("features.json"), "{\"features\":[]}\n").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-qm", "agent work"]);
+        git(&["checkout", "-q", "main"]);
+
+        let always = Chaos::new(1, 1.0);
+        let commit = always.inject_conflict(root, "forge/agent-1", "f001", &GitConfig::default()).unwrap();
+        assert!(std::fs::read_to_string(root.join("lib.rs")).unwrap().starts_with("forge chaos: clashing edit of lib.rs"));
+        let merge = Command::new("git").args(["merge", "-q", "forge/agent-1"]).current_dir(root).output().unwrap();
+        assert!(!merge.status.success(), "the edit clashes");
+        git(&["merge", "--abort"]);
+
+        undo_conflict(root, &commit, &GitConfig::default());
+        assert_eq!(std::fs::read_to_string(root.join("lib.rs")).unwrap(), "fn a() {}\n");
+        assert_eq!(git(&["status", "--porcelain"]), "");
+        let timeline = events::merged_timeline(root).unwrap();
+        assert!(matches!(&timeline[0].kind, EventKind::ChaosInjected { fault, .. } if fault == MERGE_CONFLICT));
+    }
+}
@@ -0,0 +1,231 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::commit_message::CommitMessage;
+use crate::config::ForgeConfig;
+use crate::export::copy_dir_recursive;
+use crate::git;
+use crate::runner::STATE_PATHS as STATE;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid checkpoint name '{0}' (use letters, digits, '-', '_' and '.')")]
+    InvalidName(String),
+    #[error("checkpoint '{0}' already exists")]
+    Exists(String),
+    #[error("no checkpoint '{0}' (see `forge checkpoint list`)")]
+    NotFound(String),
+    #[error("uncommitted changes to {0}; commit or stash them first")]
+    Dirty(String),
+    #[error("{0}")]
+    Git(String),
+}
+
+/// `.forge/checkpoints/<name>/checkpoint.json`, next to the copied state.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Checkpoint {
+    pub name: String,
+    pub created: DateTime<Utc>,
+    /// HEAD when it was taken; None outside a git repo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+pub fn checkpoints_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/checkpoints")
+}
+
+/// The git ref that keeps a checkpoint's commit alive.
+pub fn ref_name(name: &str) -> String {
+    format!("refs/forge/checkpoints/{name}")
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains("..")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Snapshot the project's state under `name` (a timestamp if None), with
+/// HEAD pinned by a ref.
+pub fn create(project_dir: &Path, name: Option<&str>, note: Option<&str>) -> Result<Checkpoint, CheckpointError> {
+    let name = name.map_or_else(|| Local::now().format("%Y%m%d-%H%M%S").to_string(), str::to_string);
+    if !valid_name(&name) {
+        return Err(CheckpointError::InvalidName(name));
+    }
+    let dir = checkpoints_dir(project_dir).join(&name);
+    if dir.exists() {
+        return Err(CheckpointError::Exists(name));
+    }
+
+    let commit = git::rev_parse(project_dir, "HEAD");
+    if let Some(commit) = &commit {
+        git::update_ref(project_dir, &ref_name(&name), commit).map_err(CheckpointError::Git)?;
+    }
+    let state = dir.join("state");
+    std::fs::create_dir_all(&state)?;
+    for path in STATE {
+        let src = project_dir.join(path);
+        if src.is_dir() {
+            copy_dir_recursive(&src, &state.join(path))?;
+        } else if src.is_file() {
+            std::fs::copy(&src, state.join(path))?;
+        }
+    }
+    let checkpoint = Checkpoint { name, created: Utc::now(), commit, note: note.map(str::to_string) };
+    std::fs::write(dir.join("checkpoint.json"), serde_json::to_string_pretty(&checkpoint)?)?;
+    Ok(checkpoint)
+}
+
+/// Every checkpoint, oldest first.
+pub fn list(project_dir: &Path) -> Vec<Checkpoint> {
+    let mut checkpoints: Vec<Checkpoint> = std::fs::read_dir(checkpoints_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| std::fs::read_to_string(e.path().join("checkpoint.json")).ok())
+        .filter_map(|text| serde_json::from_str(&text).ok())
+        .collect();
+    checkpoints.sort_by_key(|c| c.created);
+    checkpoints
+}
+
+/// What `restore` did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Restored {
+    pub checkpoint: Checkpoint,
+    /// Taken just before, so the restore itself can be undone.
+    pub backup: Checkpoint,
+    /// Whether a commit rolled the code back; false when HEAD was already there.
+    pub reverted: bool,
+}
+
+/// Roll the project back to checkpoint `name`: one commit that puts the
+/// tree back as it was at the checkpoint's commit (history kept), then its
+/// copies of features.json, feedback/ and context/ over the current ones.
+/// Stop any `forge run` first; it would write over the restored state.
+pub fn restore(project_dir: &Path, name: &str) -> Result<Restored, CheckpointError> {
+    let checkpoint =
+        list(project_dir).into_iter().find(|c| c.name == name).ok_or_else(|| CheckpointError::NotFound(name.into()))?;
+    let commit = checkpoint.commit.as_ref().filter(|_| git::is_git_repo(project_dir));
+    if commit.is_some() {
+        let dirty = dirty_outside_state(project_dir);
+        if !dirty.is_empty() {
+            return Err(CheckpointError::Dirty(dirty.join(", ")));
+        }
+    }
+    let backup = create(project_dir, Some(&format!("before-{name}-{}", Local::now().format("%Y%m%d-%H%M%S"))), None)?;
+
+    let git_config = ForgeConfig::load(project_dir).map(|c| c.git).unwrap_or_default();
+    let mut reverted = false;
+    if let Some(commit) = commit {
+        // The revert applies to the index; uncommitted state would stop it,
+        // and the checkpoint's copy replaces it anyway
+        let tracked: Vec<String> =
+            STATE.iter().filter(|p| git::show_file(project_dir, "HEAD", p).is_some()).map(|p| p.to_string()).collect();
+        git::restore_paths(project_dir, "HEAD", &tracked).map_err(CheckpointError::Git)?;
+        let before = git::rev_parse(project_dir, "HEAD");
+        let message = CommitMessage::new("revert", format!("roll back to checkpoint {name}"));
+        git::revert_to(project_dir, commit, &message.render(&git_config)).map_err(CheckpointError::Git)?;
+        reverted = git::rev_parse(project_dir, "HEAD") != before;
+    }
+
+    let state = checkpoints_dir(project_dir).join(name).join("state");
+    for path in STATE {
+        let (src, dst) = (state.join(path), project_dir.join(path));
+        if dst.is_dir() {
+            std::fs::remove_dir_all(&dst)?;
+        } else if dst.is_file() {
+            std::fs::remove_file(&dst)?;
+        }
+        if src.is_dir() {
+            copy_dir_recursive(&src, &dst)?;
+        } else if src.is_file() {
+            std::fs::copy(&src, &dst)?;
+        }
+    }
+    if commit.is_some() {
+        let message = CommitMessage::new("chore", format!("restore forge state from checkpoint {name}"));
+        git::commit_paths(project_dir, STATE, &message.render(&git_config)).map_err(CheckpointError::Git)?;
+    }
+    Ok(Restored { checkpoint, backup, reverted })
+}
+
+/// Tracked files with uncommitted changes, leaving out the state a
+/// checkpoint restores.
+fn dirty_outside_state(project_dir: &Path) -> Vec<String> {
+    let excludes: Vec<String> = STATE.iter().map(|p| format!(":!{p}")).collect();
+    let mut args = vec!["status", "--porcelain", "--untracked-files=no", "--", "."];
+    args.extend(excludes.iter().map(String::as_str));
+    let Ok(status) = git::run(project_dir, &args) else {
+        return Vec::new();
+    };
+    status.lines().filter_map(|l| l.get(3..)).map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn restore_rolls_back_code_and_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let git = |args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(root).output().unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        std::fs::write(root.join(".gitignore"), ".forge/\n").unwrap();
+        std::fs::create_dir_all(root.join("context/gotchas")).unwrap();
+        std::fs::write(root.join("src.rs"), "good\n").unwrap();
+        std::fs::write(root.join("features.json"), "{\"features\":[]}\n").unwrap();
+        std::fs::write(root.join("context/gotchas/a.md"), "keep\n").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "init"]);
+        // Uncommitted at checkpoint time, so only the copy has it
+        std::fs::create_dir_all(root.join("feedback")).unwrap();
+        std::fs::write(root.join("feedback/session-review.md"), "review\n").unwrap();
+
+        let good = create(root, Some("good"), Some("all green")).unwrap();
+        assert!(matches!(create(root, Some("good"), None), Err(CheckpointError::Exists(_))));
+        assert!(matches!(create(root, Some("../x"), None), Err(CheckpointError::InvalidName(_))));
+        assert_eq!(git(&["rev-parse", &ref_name("good")]), good.commit.clone().unwrap());
+
+        // An agent rampage: code broken and committed, state rewritten
+        std::fs::write(root.join("src.rs"), "broken\n").unwrap();
+        std::fs::write(root.join("features.json"), "{\"features\":[{}]}\n").unwrap();
+        std::fs::write(root.join("context/gotchas/b.md"), "noise\n").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "rampage"]);
+        std::fs::remove_dir_all(root.join("feedback")).unwrap();
+
+        std::fs::write(root.join("src.rs"), "wip\n").unwrap();
+        assert!(matches!(restore(root, "good"), Err(CheckpointError::Dirty(p)) if p == "src.rs"));
+        git(&["checkout", "--", "src.rs"]);
+
+        let restored = restore(root, "good").unwrap();
+        assert!(restored.reverted);
+        assert_eq!(std::fs::read_to_string(root.join("src.rs")).unwrap(), "good\n");
+        assert_eq!(std::fs::read_to_string(root.join("features.json")).unwrap(), "{\"features\":[]}\n");
+        assert!(!root.join("context/gotchas/b.md").exists());
+        assert_eq!(std::fs::read_to_string(root.join("feedback/session-review.md")).unwrap(), "review\n");
+        assert_eq!(git(&["status", "--porcelain"]), "");
+        assert_eq!(git(&["log", "-1", "--format=%s"]), "chore: restore forge state from checkpoint good");
+        assert_eq!(list(root).len(), 2, "the backup is a checkpoint too");
+        assert!(restored.backup.name.starts_with("before-good-"));
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::config::{ForgeConfig, Permissions};
+use crate::verify_template;
+
+/// Where Claude Code reads a project's shared settings.
+pub const PATH: &str = ".claude/settings.json";
+
+/// Allowed at every level: reading and editing, and the verify scripts.
+const BASE_ALLOW: &[&str] =
+    &["Read", "Glob", "Grep", "Edit", "Write", "TodoWrite", "Bash(./scripts/verify/*)", "Bash(scripts/verify/*)"];
+
+/// Only at `strict`: local git that records work, and forge commands that
+/// report on it. `standard` allows `git` and `forge` whole.
+const STRICT_ALLOW: &[&str] = &[
+    "Bash(git status:*)",
+    "Bash(git diff:*)",
+    "Bash(git log:*)",
+    "Bash(git show:*)",
+    "Bash(git add:*)",
+    "Bash(git commit:*)",
+    "Bash(forge status:*)",
+    "Bash(forge verify:*)",
+    "Bash(forge logs:*)",
+    "Bash(forge questions list:*)",
+];
+
+const STANDARD_ALLOW: &[&str] = &[
+    "Bash(git:*)",
+    "Bash(forge:*)",
+    "Bash(ls:*)",
+    "Bash(cat:*)",
+    "Bash(head:*)",
+    "Bash(tail:*)",
+    "Bash(wc:*)",
+    "Bash(find:*)",
+    "Bash(grep:*)",
+    "Bash(rg:*)",
+    "Bash(mkdir:*)",
+    "Bash(mv:*)",
+    "Bash(cp:*)",
+    "Bash(chmod +x:*)",
+    "WebSearch",
+    "WebFetch",
+];
+
+const BASE_DENY: &[&str] = &[
+    "Bash(git push --force:*)",
+    "Bash(git push -f:*)",
+    "Bash(git reset --hard:*)",
+    "Bash(rm -rf:*)",
+    "Bash(sudo:*)",
+    "Read(./.env)",
+    "Read(./.env.*)",
+];
+
+const STRICT_DENY: &[&str] = &["Bash(git push:*)", "Bash(curl:*)", "Bash(wget:*)", "WebFetch", "WebSearch"];
+
+/// `(allow, deny)` rules for a level. The stack's toolchain is allowed whole
+/// at `standard` (`Bash(cargo:*)`) and only as its build and test commands at
+/// `strict` (`Bash(cargo test:*)`). `bypass` gets the `standard` rules.
+pub fn rules(level: Permissions, stack: &str) -> (Vec<String>, Vec<String>) {
+    let (build, test) = verify_template::commands(stack);
+    let words = if level == Permissions::Strict { 2 } else { 1 };
+    let mut toolchain: Vec<String> = Vec::new();
+    for command in build.iter().chain(&test).filter(|c| !c.starts_with("test ")) {
+        let prefix: Vec<&str> = command.split_whitespace().take(words).collect();
+        let rule = format!("Bash({}:*)", prefix.join(" "));
+        if !toolchain.contains(&rule) {
+            toolchain.push(rule);
+        }
+    }
+
+    let mut allow: Vec<String> = BASE_ALLOW.iter().map(|r| r.to_string()).collect();
+    let mut deny: Vec<String> = BASE_DENY.iter().map(|r| r.to_string()).collect();
+    if level == Permissions::Strict {
+        allow.extend(STRICT_ALLOW.iter().map(|r| r.to_string()));
+        deny.extend(STRICT_DENY.iter().map(|r| r.to_string()));
+    } else {
+        allow.extend(STANDARD_ALLOW.iter().map(|r| r.to_string()));
+    }
+    allow.extend(toolchain);
+    (allow, deny)
+}
+
+/// Every rule forge writes for `stack` at any level: anything else in the
+/// file was added by hand.
+fn forge_rules(stack: &str) -> Vec<String> {
+    [Permissions::Standard, Permissions::Strict]
+        .into_iter()
+        .flat_map(|level| {
+            let (allow, deny) = rules(level, stack);
+            allow.into_iter().chain(deny)
+        })
+        .collect()
+}
+
+/// `settings` with its `permissions.allow` and `permissions.deny` set to
+/// `config`'s profile. Rules added by hand and every other setting are kept.
+pub fn merge(settings: Value, config: &ForgeConfig) -> Value {
+    let stack = &config.project.stack;
+    let mut settings = match settings {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    let permissions = settings.entry("permissions").or_insert_with(|| Value::Object(Map::new()));
+    if !permissions.is_object() {
+        *permissions = Value::Object(Map::new());
+    }
+    let ours = forge_rules(stack);
+    let (allow, deny) = rules(config.forge.permissions, stack);
+    for (key, level_rules) in [("allow", allow), ("deny", deny)] {
+        let existing = permissions.get(key).and_then(Value::as_array).cloned().unwrap_or_default();
+        let mut list: Vec<Value> = level_rules.into_iter().map(Value::String).collect();
+        for rule in existing {
+            let own = rule.as_str().is_some_and(|r| !ours.iter().any(|o| o == r));
+            if own && !list.contains(&rule) {
+                list.push(rule);
+            }
+        }
+        permissions[key] = Value::Array(list);
+    }
+    Value::Object(settings)
+}
+
+/// Write `.claude/settings.json` for `config`, keeping what's there besides
+/// forge's own rules. With `bypass` agents ignore the file, so it isn't
+/// touched; nor is a file that isn't valid JSON.
+pub fn write(project_dir: &Path, config: &ForgeConfig) -> std::io::Result<()> {
+    if config.forge.permissions == Permissions::Bypass {
+        return Ok(());
+    }
+    let path = project_dir.join(PATH);
+    let settings = match std::fs::read_to_string(&path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                warn!("not updating {PATH}: {e}");
+                return Ok(());
+            }
+        },
+        Err(_) => Value::Object(Map::new()),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&merge(settings, config)).map_err(std::io::Error::other)?;
+    std::fs::write(path, json + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_widen_and_narrow_the_toolchain() {
+        let (allow, deny) = rules(Permissions::Standard, "Rust");
+        assert!(allow.contains(&"Bash(cargo:*)".to_string()), "{allow:?}");
+        assert!(allow.contains(&"WebFetch".to_string()));
+        assert!(allow.contains(&"Bash(forge:*)".to_string()));
+        assert!(!allow.contains(&"Bash(git commit:*)".to_string()), "covered by Bash(git:*)");
+        assert!(deny.contains(&"Bash(git push --force:*)".to_string()));
+
+        let (allow, deny) = rules(Permissions::Strict, "Rust");
+        assert!(allow.contains(&"Bash(cargo test:*)".to_string()), "{allow:?}");
+        assert!(!allow.contains(&"Bash(cargo:*)".to_string()));
+        assert!(!allow.contains(&"Bash(git:*)".to_string()));
+        assert!(!allow.contains(&"Bash(forge:*)".to_string()));
+        assert!(allow.contains(&"Bash(forge status:*)".to_string()));
+        assert!(deny.contains(&"WebFetch".to_string()));
+    }
+
+    #[test]
+    fn merge_keeps_hand_written_settings() {
+        let mut config = ForgeConfig::scaffold("app", "Go");
+        let existing = serde_json::json!({
+            "env": {"GOFLAGS": "-mod=mod"},
+            "permissions": {"allow": ["Bash(make:*)", "Bash(go:*)"], "deny": []},
+        });
+        config.forge.permissions = Permissions::Strict;
+        let merged = merge(existing, &config);
+        assert_eq!(merged["env"]["GOFLAGS"], "-mod=mod");
+        let allow: Vec<&str> =
+            merged["permissions"]["allow"].as_array().unwrap().iter().filter_map(Value::as_str).collect();
+        assert!(allow.contains(&"Bash(make:*)"));
+        assert!(allow.contains(&"Bash(go test:*)"));
+        // standard's rule, dropped at strict
+        assert!(!allow.contains(&"Bash(go:*)"), "{allow:?}");
+    }
+}
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::Utc;
+
+use crate::context_flow;
+use crate::doctor;
+use crate::events;
+use crate::features::FeatureList;
+use crate::git;
+use crate::runs;
+
+/// What `forge clean` prunes, and from when.
+#[derive(Debug, Clone, Copy)]
+pub struct CleanOptions {
+    /// Logs, exports and the cocoindex database untouched for this long go.
+    pub older_than_days: u64,
+    /// Report what would go without touching anything.
+    pub dry_run: bool,
+    /// Also set expired claims back to pending; refused while a run is
+    /// recorded as unfinished, since its claims only look expired.
+    pub release_claims: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Log,
+    /// A worktree directory git doesn't know.
+    Worktree,
+    CocoindexDb,
+    Export,
+    /// A claimed feature no session is working on, set back to pending.
+    Claim,
+}
+
+/// One thing removed or released (or that would be, with `dry_run`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cleaned {
+    pub kind: Kind,
+    /// Relative to the project; the feature id for a claim.
+    pub what: String,
+    pub bytes: u64,
+}
+
+/// When `path`, or anything under it, last changed.
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    let own = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let children = std::fs::read_dir(path).into_iter().flatten().flatten().filter_map(|e| last_modified(&e.path()));
+    own.into_iter().chain(children).max()
+}
+
+fn remove(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) }
+}
+
+/// Prune `.forge/` of old logs and exports, worktrees left by crashed
+/// runs, a cocoindex database that's stale or has no cocoindex to use it,
+/// and, with `release_claims`, release claims whose agent is gone.
+pub fn clean(project_dir: &Path, options: CleanOptions) -> std::io::Result<Vec<Cleaned>> {
+    // A live run's claims look expired between sessions
+    if options.release_claims
+        && let Some(live) = runs::list(project_dir).into_iter().rev().find(|r| r.finished_at.is_none())
+    {
+        return Err(std::io::Error::other(format!(
+            "run {} hasn't finished, so its claims may be live; leave out --release-claims until it has \
+             (if it crashed, delete .forge/runs/{}.json)",
+            live.id, live.id
+        )));
+    }
+    let runtime = project_dir.join(".forge");
+    let cutoff = SystemTime::now() - std::time::Duration::from_secs(options.older_than_days * 24 * 60 * 60);
+    let old = |path: &Path| last_modified(path).is_some_and(|t| t < cutoff);
+    let mut doomed: Vec<(Kind, PathBuf)> = Vec::new();
+
+    // forge's own `forge.<date>.log` files rotate out by themselves
+    let logs = std::fs::read_dir(runtime.join("logs")).into_iter().flatten().flatten().map(|e| e.path());
+    let agent_log = |p: &Path| p.is_file() && !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("forge."));
+    doomed.extend(logs.filter(|p| agent_log(p) && old(p)).map(|p| (Kind::Log, p)));
+
+    let scan = doctor::scan_worktrees(project_dir);
+    doomed.extend(scan.unregistered.into_iter().map(|p| (Kind::Worktree, p)));
+
+    let db = runtime.join("cocoindex-db");
+    if db.exists() && (old(&db) || !context_flow::cocoindex_available()) {
+        doomed.push((Kind::CocoindexDb, db));
+    }
+
+    // `.forge/export`, `.forge/export-<id>`, `.forge/export.tar.gz`, ...
+    let exports = std::fs::read_dir(&runtime).into_iter().flatten().flatten().map(|e| e.path());
+    doomed.extend(
+        exports
+            .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("export")) && old(p))
+            .map(|p| (Kind::Export, p)),
+    );
+
+    let mut cleaned = Vec::new();
+    for (kind, path) in doomed {
+        let bytes = if path.is_dir() { doctor::dir_size(&path) } else { path.metadata().map_or(0, |m| m.len()) };
+        if !options.dry_run {
+            remove(&path)?;
+        }
+        let what = path.strip_prefix(project_dir).unwrap_or(&path).display().to_string();
+        cleaned.push(Cleaned { kind, what, bytes });
+    }
+    if !options.dry_run && scan.prunable + cleaned.iter().filter(|c| c.kind == Kind::Worktree).count() > 0 {
+        let _ = git::run(project_dir, &["worktree", "prune"]);
+    }
+
+    if options.release_claims
+        && let Ok(mut list) = FeatureList::load(project_dir)
+    {
+        let timeline = events::merged_timeline(project_dir).unwrap_or_default();
+        let expired: Vec<String> =
+            doctor::expired_claims(&list, &timeline, Utc::now()).iter().map(|f| f.id.clone()).collect();
+        for id in &expired {
+            if !options.dry_run {
+                let _ = list.reopen(id);
+            }
+            cleaned.push(Cleaned { kind: Kind::Claim, what: id.clone(), bytes: 0 });
+        }
+        if !options.dry_run && !expired.is_empty() {
+            list.save(project_dir).map_err(std::io::Error::other)?;
+        }
+    }
+    Ok(cleaned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::FeatureStatus;
+
+    #[test]
+    fn prunes_old_state_and_releases_expired_claims() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".forge/logs")).unwrap();
+        std::fs::create_dir_all(root.join(".forge/worktrees/agent-2")).unwrap();
+        std::fs::create_dir_all(root.join(".forge/export")).unwrap();
+        std::fs::write(root.join(".forge/logs/agent-1.log"), "old\n").unwrap();
+        std::fs::write(root.join(".forge/logs/forge.2026-03-01.log"), "rotated\n").unwrap();
+        std::fs::write(root.join(".forge/export/manifest.json"), "{}").unwrap();
+        std::fs::write(
+            root.join("features.json"),
+            r#"{"features":[{"id":"f001","type":"implement","scope":"a","description":"x","verify":"true",
+                "status":"claimed","claimed_by":"agent-1"}]}"#,
+        )
+        .unwrap();
+
+        let kinds = |cleaned: &[Cleaned]| cleaned.iter().map(|c| (c.kind, c.what.clone())).collect::<Vec<_>>();
+        let fresh = clean(root, CleanOptions { older_than_days: 1, dry_run: true, release_claims: true }).unwrap();
+        assert_eq!(
+            kinds(&fresh),
+            [(Kind::Worktree, ".forge/worktrees/agent-2".into()), (Kind::Claim, "f001".into())],
+            "nothing is a day old yet"
+        );
+
+        let dry = clean(root, CleanOptions { older_than_days: 0, dry_run: true, release_claims: true }).unwrap();
+        assert_eq!(dry.len(), 4, "{dry:?}");
+        assert!(root.join(".forge/logs/agent-1.log").exists(), "dry run");
+
+        let kept = clean(root, CleanOptions { older_than_days: 1, dry_run: true, release_claims: false }).unwrap();
+        assert_eq!(kinds(&kept), [(Kind::Worktree, ".forge/worktrees/agent-2".into())], "claims need the flag");
+
+        let live = runs::start(root, serde_json::json!({})).unwrap();
+        let refused = clean(root, CleanOptions { older_than_days: 0, dry_run: false, release_claims: true });
+        assert!(refused.unwrap_err().to_string().contains(&format!("run {} hasn't finished", live.id)));
+        assert!(root.join(".forge/logs/agent-1.log").exists(), "refused before removing anything");
+        runs::finish(root, live, "all_done").unwrap();
+
+        let done = clean(root, CleanOptions { older_than_days: 0, dry_run: false, release_claims: true }).unwrap();
+        assert_eq!(kinds(&done), kinds(&dry));
+        assert!(!root.join(".forge/logs/agent-1.log").exists());
+        assert!(!root.join(".forge/worktrees/agent-2").exists());
+        assert!(!root.join(".forge/export").exists());
+        let list = FeatureList::load(root).unwrap();
+        assert_eq!(list.features[0].status, FeatureStatus::Pending);
+        assert_eq!(list.features[0].claimed_by, None);
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+
+use crate::config::GitConfig;
+use crate::features::{Feature, FeatureList, FeatureType};
+
+/// A commit forge makes on an agent's behalf, rendered through
+/// `[git] commit_template` into a conventional-commit subject, e.g.
+/// `feat(f042): add INSERT support`, plus `Forge-*` trailers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitMessage {
+    /// Conventional-commit type: `feat`, `chore`, `docs`, ...
+    pub commit_type: String,
+    pub summary: String,
+    pub feature_id: Option<String>,
+    pub scope: Option<String>,
+    pub agent_id: Option<String>,
+    /// Run session number, in headless runs.
+    pub session: Option<usize>,
+}
+
+/// The conventional-commit type for work on a feature.
+pub fn commit_type(feature_type: &FeatureType) -> &'static str {
+    match feature_type {
+        FeatureType::Implement | FeatureType::Poc => "feat",
+        FeatureType::Review => "chore",
+    }
+}
+
+/// A description's first line as a subject: lowercase first letter,
+/// unless the first word is an acronym like `INSERT` or `API`.
+fn subject(description: &str) -> String {
+    let line = description.lines().next().unwrap_or("").trim().trim_end_matches('.');
+    let first_word = line.split_whitespace().next().unwrap_or("");
+    let acronym = first_word.chars().filter(|c| c.is_alphabetic()).count() > 1
+        && first_word.chars().filter(|c| c.is_alphabetic()).all(char::is_uppercase);
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(first) if !acronym => first.to_lowercase().chain(chars).collect(),
+        _ => line.to_string(),
+    }
+}
+
+impl CommitMessage {
+    pub fn new(commit_type: &str, summary: impl Into<String>) -> Self {
+        Self { commit_type: commit_type.into(), summary: summary.into(), ..Self::default() }
+    }
+
+    /// The feature's own work: type from its kind, summary from its description.
+    pub fn for_feature(feature: &Feature) -> Self {
+        Self {
+            feature_id: Some(feature.id.clone()),
+            scope: Some(feature.scope.clone()),
+            ..Self::new(commit_type(&feature.feature_type), subject(&feature.description))
+        }
+    }
+
+    /// `for_feature` for an id in the project's features.json; just the id
+    /// if it isn't there.
+    pub fn for_feature_id(project_dir: &Path, feature_id: &str) -> Self {
+        let list = FeatureList::load(project_dir).ok();
+        match list.as_ref().and_then(|l| l.features.iter().find(|f| f.id == feature_id)) {
+            Some(feature) => Self::for_feature(feature),
+            None => Self { feature_id: Some(feature_id.into()), ..Self::new("feat", feature_id) },
+        }
+    }
+
+    /// The same feature, agent and session with another type and summary.
+    pub fn retitled(&self, commit_type: &str, summary: impl Into<String>) -> Self {
+        Self { commit_type: commit_type.into(), summary: summary.into(), ..self.clone() }
+    }
+
+    pub fn agent(mut self, agent_id: &str) -> Self {
+        self.agent_id = Some(agent_id.into());
+        self
+    }
+
+    pub fn session(mut self, session: usize) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// The full message: the templated subject, then trailers if enabled.
+    pub fn render(&self, config: &GitConfig) -> String {
+        let mut subject = config.commit_template.clone();
+        for (name, value) in [
+            ("type", Some(&self.commit_type)),
+            ("feature", self.feature_id.as_ref()),
+            ("scope", self.scope.as_ref()),
+            ("agent", self.agent_id.as_ref()),
+            ("summary", Some(&self.summary)),
+        ] {
+            let placeholder = format!("{{{name}}}");
+            match value.filter(|v| !v.is_empty()) {
+                Some(value) => subject = subject.replace(&placeholder, value),
+                // `chore({feature}): x` → `chore: x` when there's no feature
+                None => {
+                    for wrapped in [format!("({placeholder})"), format!("[{placeholder}]"), placeholder] {
+                        subject = subject.replace(&wrapped, "");
+                    }
+                }
+            }
+        }
+        let subject = subject.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !config.commit_trailers {
+            return subject;
+        }
+
+        let trailers: Vec<String> = [
+            ("Forge-Feature", self.feature_id.clone()),
+            ("Forge-Scope", self.scope.clone()),
+            ("Forge-Agent", self.agent_id.clone()),
+            ("Forge-Session", self.session.map(|n| n.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some(format!("{key}: {}", value?)))
+        .collect();
+        if trailers.is_empty() {
+            subject
+        } else {
+            format!("{subject}\n\n{}", trailers.join("\n"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::FeatureStatus;
+
+    fn feature(description: &str) -> Feature {
+        Feature {
+            id: "f042".into(),
+            feature_type: FeatureType::Implement,
+            scope: "storage".into(),
+            description: description.into(),
+            verify: "true".into(),
+            depends_on: vec![],
+            priority: 1,
+            status: FeatureStatus::Claimed,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    #[test]
+    fn conventional_subject_with_trailers() {
+        let message = CommitMessage::for_feature(&feature("Add INSERT support.\nWith RETURNING.")).agent("agent-2").session(3);
+        assert_eq!(
+            message.render(&GitConfig::default()),
+            "feat(f042): add INSERT support\n\n\
+             Forge-Feature: f042\nForge-Scope: storage\nForge-Agent: agent-2\nForge-Session: 3"
+        );
+        assert_eq!(subject("API client for orders"), "API client for orders");
+
+        let claim = message.retitled("chore", "claim for agent-2");
+        let config = GitConfig { commit_trailers: false, ..GitConfig::default() };
+        assert_eq!(claim.render(&config), "chore(f042): claim for agent-2");
+    }
+
+    #[test]
+    fn custom_template_drops_empty_parts() {
+        let config = GitConfig {
+            commit_template: "{type}({scope}): {summary} [{feature}]".into(),
+            commit_trailers: false,
+            ..GitConfig::default()
+        };
+        let message = CommitMessage::for_feature(&feature("Add INSERT support"));
+        assert_eq!(message.render(&config), "feat(storage): add INSERT support [f042]");
+        assert_eq!(CommitMessage::new("chore", "sync feature state").render(&config), "chore: sync feature state");
+        assert_eq!(CommitMessage::new("chore", "sync feature state").render(&GitConfig::default()), "chore: sync feature state");
+    }
+}
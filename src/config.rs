@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ForgeConfig {
@@ -11,6 +11,26 @@ pub struct ForgeConfig {
     pub principles: Principles,
     #[serde(default)]
     pub scopes: BTreeMap<String, Scope>,
+    #[serde(default, skip_serializing_if = "TuiConfig::is_default")]
+    pub tui: TuiConfig,
+    #[serde(default, skip_serializing_if = "ExportConfig::is_default")]
+    pub export: ExportConfig,
+    #[serde(default, skip_serializing_if = "ContextConfig::is_default")]
+    pub context: ContextConfig,
+    #[serde(default, skip_serializing_if = "GitConfig::is_default")]
+    pub git: GitConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub backends: BTreeMap<String, BackendDef>,
+    /// Credentials by the env var name agents get them as.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub secrets: BTreeMap<String, SecretRef>,
+    #[serde(default, skip_serializing_if = "NotificationsConfig::is_default")]
+    pub notifications: NotificationsConfig,
+    /// `forge-<name>` executables by name, with the events they hook.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugins: BTreeMap<String, PluginConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -24,10 +44,142 @@ pub struct ProjectConfig {
 pub struct ForgeSettings {
     #[serde(default = "default_max_agents")]
     pub max_agents: usize,
+    /// Defaults for roles that don't set `budget`, `timeout_secs` or
+    /// `retries` themselves.
     #[serde(default = "default_budget")]
     pub budget_per_session: f64,
+    /// USD the whole project may spend, by its Claude transcripts. Past
+    /// it, `forge run` lets running sessions finish and starts no more.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub retries: u32,
+    /// What claude agents may do unasked: `bypass` skips every permission
+    /// check; `standard` and `strict` follow the profile forge writes to
+    /// `.claude/settings.json`.
+    #[serde(default)]
+    pub permissions: Permissions,
     #[serde(default)]
     pub roles: RoleConfig,
+    #[serde(default, skip_serializing_if = "RunawayConfig::is_default")]
+    pub runaway: RunawayConfig,
+    #[serde(default, skip_serializing_if = "RateLimitConfig::is_default")]
+    pub rate_limits: RateLimitConfig,
+}
+
+/// `[forge.runaway]`: when a headless session has gone wrong and is killed,
+/// its feature blocked, before it spends the rest of its budget.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunawayConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Output past this many times the median of recent sessions' output.
+    #[serde(default = "default_output_factor")]
+    pub output_factor: f64,
+    /// Output under this many KiB never counts as too much.
+    #[serde(default = "default_min_output_kb")]
+    pub min_output_kb: u64,
+    /// The same error this many times in one session.
+    #[serde(default = "default_repeated_errors")]
+    pub repeated_errors: usize,
+    /// The same tool call, or the same output line, this many times in a row.
+    #[serde(default = "default_repeated_calls")]
+    pub repeated_calls: usize,
+}
+
+impl Default for RunawayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            output_factor: default_output_factor(),
+            min_output_kb: default_min_output_kb(),
+            repeated_errors: default_repeated_errors(),
+            repeated_calls: default_repeated_calls(),
+        }
+    }
+}
+
+impl RunawayConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[forge.rate_limits]`: a backend that answers with rate-limit errors is
+/// left alone for a while, and roles on it run on its fallback meanwhile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RateLimitConfig {
+    /// Seconds to leave a backend alone after its first rate-limit error;
+    /// doubled with each one after, up to `max_backoff_secs`. A retry-after
+    /// the backend gives wins.
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: u64,
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    /// Where roles on a backed-off backend go, by backend name:
+    /// `claude = { backend = "codex", model = "gpt-5-codex" }`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fallback: BTreeMap<String, Fallback>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Fallback {
+    pub backend: String,
+    /// The role's own model if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            backoff_secs: default_backoff_secs(),
+            max_backoff_secs: default_max_backoff_secs(),
+            fallback: BTreeMap::new(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_backoff_secs() -> u64 {
+    60
+}
+fn default_max_backoff_secs() -> u64 {
+    1800
+}
+
+fn default_output_factor() -> f64 {
+    10.0
+}
+fn default_min_output_kb() -> u64 {
+    512
+}
+fn default_repeated_errors() -> usize {
+    5
+}
+fn default_repeated_calls() -> usize {
+    8
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permissions {
+    /// `--dangerously-skip-permissions`.
+    #[default]
+    Bypass,
+    /// Edits, git, the stack's toolchain, common shell tools and the web;
+    /// no force-pushes, `rm -rf`, `sudo` or reading `.env` files.
+    Standard,
+    /// Edits, local git, verify scripts and the stack's build and test
+    /// commands only: no pushing and no network.
+    Strict,
 }
 
 impl Default for ForgeSettings {
@@ -35,11 +187,33 @@ impl Default for ForgeSettings {
         Self {
             max_agents: default_max_agents(),
             budget_per_session: default_budget(),
+            max_total_cost: None,
+            session_timeout_secs: None,
+            retries: 0,
+            permissions: Permissions::default(),
             roles: RoleConfig::default(),
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+        }
+    }
+}
+
+impl ForgeSettings {
+    /// `role` with the session limits it leaves unset filled in from here.
+    pub fn with_limits(&self, role: &RoleSpec) -> RoleSpec {
+        RoleSpec {
+            budget: role.budget.or(Some(self.budget_per_session)),
+            timeout_secs: role.timeout_secs.or(self.session_timeout_secs),
+            retries: role.retries.or(Some(self.retries)),
+            ..role.clone()
         }
     }
 }
 
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
 /// Each role independently picks its backend and model.
 /// Mix Claude and Codex freely across roles.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -56,6 +230,11 @@ pub struct RoleConfig {
     /// Replanning: context-aware plan modification, POC pivot handling.
     #[serde(default = "default_role_adjusting")]
     pub adjusting: RoleSpec,
+    /// Executor variant B, for comparing two models on one project: in
+    /// multi-agent runs the even-numbered agents use it instead of
+    /// `protocol`, and their sessions are recorded as variant `b`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_b: Option<RoleSpec>,
 }
 
 impl Default for RoleConfig {
@@ -65,6 +244,7 @@ impl Default for RoleConfig {
             orchestrating: default_role_orchestrating(),
             planning: default_role_planning(),
             adjusting: default_role_adjusting(),
+            protocol_b: None,
         }
     }
 }
@@ -77,6 +257,362 @@ pub struct RoleSpec {
     pub backend: String,
     #[serde(default = "default_model_sonnet")]
     pub model: String,
+    /// Most tokens of pre-compiled context to embed in this role's prompt;
+    /// lower-value package sections are cut to fit.
+    #[serde(default = "default_context_tokens")]
+    pub context_tokens: usize,
+    /// Most agentic turns per session before the agent is stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+    /// Sampling temperature, for backends that take one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Cap on tokens in each model response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    /// USD one session may spend; `[forge] budget_per_session` if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<f64>,
+    /// Seconds before a headless session is killed; no limit if unset here
+    /// and in `[forge] session_timeout_secs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Times a failed or timed-out session is started again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl Default for RoleSpec {
+    fn default() -> Self {
+        Self {
+            backend: default_backend(),
+            model: default_model_sonnet(),
+            context_tokens: default_context_tokens(),
+            max_turns: None,
+            temperature: None,
+            max_output_tokens: None,
+            budget: None,
+            timeout_secs: None,
+            retries: None,
+        }
+    }
+}
+
+impl RoleConfig {
+    pub fn get(&self, role: &str) -> Option<&RoleSpec> {
+        match role {
+            "protocol" => Some(&self.protocol),
+            "orchestrating" => Some(&self.orchestrating),
+            "planning" => Some(&self.planning),
+            "adjusting" => Some(&self.adjusting),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, role: &str) -> Option<&mut RoleSpec> {
+        match role {
+            "protocol" => Some(&mut self.protocol),
+            "orchestrating" => Some(&mut self.orchestrating),
+            "planning" => Some(&mut self.planning),
+            "adjusting" => Some(&mut self.adjusting),
+            _ => None,
+        }
+    }
+}
+
+/// Named overrides picked with `forge run --profile <name>`, e.g. haiku for
+/// every role, or opus for protocol only. Anything unset keeps its
+/// `[forge]` value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Profile {
+    /// Applied to every role before `roles`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_agents: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_per_session: Option<f64>,
+    /// Role name -> the fields to change for it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub roles: BTreeMap<String, RoleOverride>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RoleOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_tokens: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl RoleOverride {
+    fn apply(&self, spec: &mut RoleSpec) {
+        if let Some(backend) = &self.backend {
+            spec.backend = backend.clone();
+        }
+        if let Some(model) = &self.model {
+            spec.model = model.clone();
+        }
+        if let Some(tokens) = self.context_tokens {
+            spec.context_tokens = tokens;
+        }
+        if self.max_turns.is_some() {
+            spec.max_turns = self.max_turns;
+        }
+        if self.temperature.is_some() {
+            spec.temperature = self.temperature;
+        }
+        if self.max_output_tokens.is_some() {
+            spec.max_output_tokens = self.max_output_tokens;
+        }
+        if self.budget.is_some() {
+            spec.budget = self.budget;
+        }
+        if self.timeout_secs.is_some() {
+            spec.timeout_secs = self.timeout_secs;
+        }
+        if self.retries.is_some() {
+            spec.retries = self.retries;
+        }
+    }
+}
+
+/// How to run an agent CLI forge has no built-in support for (or other
+/// flags for one it has), named by `backend = "<name>"` in a role. In the
+/// args, `{prompt}` and `{model}` are filled in; args without `{prompt}`
+/// get the prompt appended.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackendDef {
+    /// Executable to run; defaults to the table name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Args for headless sessions (`forge run` without --watch).
+    #[serde(default = "default_backend_args")]
+    pub headless_args: Vec<String>,
+    /// Args for TUI panes; empty means the same as `headless_args`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interactive_args: Vec<String>,
+    /// e.g. "--model"; passed with the role's model ahead of the args.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_flag: Option<String>,
+    /// Flags for the role's `max_turns`, `temperature` and
+    /// `max_output_tokens`, passed the same way when those are set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns_flag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_flag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens_flag: Option<String>,
+    /// Passed with the role's `budget` (USD).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_flag: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl BackendDef {
+    pub fn command<'a>(&'a self, name: &'a str) -> &'a str {
+        self.command.as_deref().unwrap_or(name)
+    }
+}
+
+/// `[git]`: how finished work leaves the run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitConfig {
+    #[serde(default)]
+    pub workflow: GitWorkflow,
+    /// With `workflow = "pr"`: one pull request per feature, or one per
+    /// milestone once its review feature is done.
+    #[serde(default)]
+    pub pr_per: PrUnit,
+    /// Branch pull requests target; the branch forge runs on if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_base: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pr_draft: bool,
+    /// Subject of the commits forge makes itself (claims, merges, context
+    /// reconciles). Placeholders: `{type}`, `{feature}`, `{scope}`,
+    /// `{summary}`, `{agent}`; parentheses left empty are dropped.
+    #[serde(default = "default_commit_template")]
+    pub commit_template: String,
+    /// `Forge-Feature:`, `Forge-Agent:` and the like under the subject.
+    #[serde(default = "default_true")]
+    pub commit_trailers: bool,
+    /// How agent branches land on the project branch in multi-agent runs.
+    #[serde(default)]
+    pub merge_strategy: MergeStrategy,
+    /// Delete an agent's branch once it merged, so each session branches
+    /// afresh from HEAD. Watch-mode panes always do.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub delete_merged_branches: bool,
+    /// Paths agents may not change, as globs (`migrations/**`) or
+    /// directories. A branch touching one isn't merged; in single-agent
+    /// runs the change is reverted. Either way the feature is reopened.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected: Vec<String>,
+    /// Look for keys and tokens in what forge commits and merges.
+    #[serde(default = "default_true")]
+    pub secret_scan: bool,
+    /// Paths (globs or directories) the secret scan skips, e.g. test
+    /// fixtures full of fake keys. Lockfiles are always skipped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_scan_skip: Vec<String>,
+    /// Files bigger than this many MB are left out of forge's commits, and
+    /// agent branches adding them aren't merged; 0 for no limit. Files
+    /// Git LFS stores count at their pointer's size.
+    #[serde(default = "default_max_file_mb")]
+    pub max_file_mb: u64,
+    /// Commit what the runner changed in features.json, feedback/ and
+    /// context/ (reopens, verify reports) at the end of each session.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub commit_state: bool,
+    /// Push after that commit, rebasing onto the remote if it moved.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub push_state: bool,
+    /// Entries directly under `.forge/` that are committed anyway, such as
+    /// `events`; the rest of `.forge/` is gitignored.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commit_runtime: Vec<String>,
+    /// Keep each agent slot's worktree between multi-agent sessions,
+    /// resetting it onto HEAD instead of removing it, so build output
+    /// (`target/`, `node_modules/`) survives.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reuse_worktrees: bool,
+    /// Name of an agent's branch in multi-agent runs and watch-mode
+    /// worktrees. Placeholders: `{agent}` (`agent-2`) and `{feature_id}`;
+    /// one of them keeps agents working at once apart.
+    #[serde(default = "default_branch_template")]
+    pub branch_template: String,
+    /// In multi-agent runs, verify after each merge; if a feature that
+    /// passed before fails after, revert the merge and reopen its feature.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub revert_regressions: bool,
+    /// Tag HEAD when a milestone's review feature is done and its verify
+    /// passes. Pushed along with `push_state`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tag_milestones: bool,
+    /// Name for those tags; `{milestone}` is the label, lowercased (`m4`).
+    #[serde(default = "default_tag_template")]
+    pub tag_template: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// `git merge`: fast-forward when possible, else a merge commit.
+    #[default]
+    Merge,
+    /// Always a merge commit (`--no-ff`), so each agent's work stays grouped.
+    MergeCommit,
+    /// One commit per agent branch, with forge's commit message.
+    Squash,
+    /// Rebase the branch onto the project branch, then fast-forward. A
+    /// rebase that conflicts falls back to a merge.
+    Rebase,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            workflow: GitWorkflow::default(),
+            pr_per: PrUnit::default(),
+            pr_base: None,
+            pr_draft: false,
+            commit_template: default_commit_template(),
+            commit_trailers: true,
+            merge_strategy: MergeStrategy::default(),
+            delete_merged_branches: false,
+            protected: Vec::new(),
+            secret_scan: true,
+            secret_scan_skip: Vec::new(),
+            max_file_mb: default_max_file_mb(),
+            commit_state: false,
+            push_state: false,
+            commit_runtime: Vec::new(),
+            reuse_worktrees: false,
+            branch_template: default_branch_template(),
+            revert_regressions: false,
+            tag_milestones: false,
+            tag_template: default_tag_template(),
+        }
+    }
+}
+
+fn default_commit_template() -> String {
+    "{type}({feature}): {summary}".into()
+}
+
+fn default_branch_template() -> String {
+    "forge/{agent}".into()
+}
+
+fn default_max_file_mb() -> u64 {
+    50
+}
+
+fn default_tag_template() -> String {
+    "{milestone}-complete".into()
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitWorkflow {
+    /// Merge agent work into the current branch and stop there.
+    #[default]
+    Merge,
+    /// Also push each finished unit to a branch and open a pull request
+    /// with `gh`.
+    Pr,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrUnit {
+    #[default]
+    Feature,
+    Milestone,
+}
+
+impl GitConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Where a `[secrets]` value lives. forge.toml only names it: exactly one
+/// of `env` and `keychain` is set, and there's no field for the value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SecretRef {
+    /// Environment variable of the shell running forge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    /// Service name in the OS keychain (macOS Keychain, or the Secret
+    /// Service via `secret-tool` elsewhere).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keychain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+fn default_backend_args() -> Vec<String> {
+    vec!["{prompt}".into()]
 }
 
 fn default_max_agents() -> usize {
@@ -85,23 +621,32 @@ fn default_max_agents() -> usize {
 fn default_budget() -> f64 {
     5.0
 }
+fn default_true() -> bool {
+    true
+}
+fn default_idle_secs() -> u64 {
+    300
+}
 fn default_backend() -> String {
     "claude".into()
 }
 fn default_model_sonnet() -> String {
     "sonnet".into()
 }
+fn default_context_tokens() -> usize {
+    20_000
+}
 fn default_role_protocol() -> RoleSpec {
-    RoleSpec { backend: "claude".into(), model: "sonnet".into() }
+    RoleSpec::default()
 }
 fn default_role_orchestrating() -> RoleSpec {
-    RoleSpec { backend: "claude".into(), model: "sonnet".into() }
+    RoleSpec::default()
 }
 fn default_role_planning() -> RoleSpec {
-    RoleSpec { backend: "claude".into(), model: "opus".into() }
+    RoleSpec { model: "opus".into(), ..RoleSpec::default() }
 }
 fn default_role_adjusting() -> RoleSpec {
-    RoleSpec { backend: "claude".into(), model: "sonnet".into() }
+    RoleSpec::default()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -116,14 +661,387 @@ pub struct Principles {
     pub boundaries: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Scope {
+    /// Subdirectory the scope lives in, for a monorepo: agents and verify
+    /// run there, and `owns` is relative to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
     #[serde(default)]
     pub owns: Vec<String>,
     #[serde(default)]
     pub api: String,
     #[serde(default)]
     pub upstream: Vec<String>,
+    /// Name -> rule, on top of `[principles]` for work in this scope.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub principles: BTreeMap<String, String>,
+}
+
+/// Watch-mode (`forge run --watch`) settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TuiConfig {
+    /// Action name -> key(s), e.g. `prefix = "ctrl+a"`, `next = ["j", "down"]`.
+    /// Validated when the TUI starts; unlisted actions keep their defaults.
+    #[serde(default)]
+    pub keys: BTreeMap<String, KeyList>,
+    /// Initial pane layout; toggle at runtime with the `layout` key.
+    #[serde(default)]
+    pub layout: TuiLayout,
+    #[serde(default)]
+    pub alerts: AlertConfig,
+}
+
+/// When watch mode pings you (terminal bell and/or desktop notification).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertConfig {
+    #[serde(default = "default_true")]
+    pub bell: bool,
+    /// Posted the way `[notifications.desktop]` posts; nothing shows without a notification daemon.
+    #[serde(default)]
+    pub desktop: bool,
+    #[serde(default = "default_true")]
+    pub pane_exited: bool,
+    #[serde(default = "default_true")]
+    pub verify_failed: bool,
+    /// A live agent that printed nothing for `idle_secs` is probably waiting for input.
+    #[serde(default = "default_true")]
+    pub agent_idle: bool,
+    #[serde(default = "default_idle_secs")]
+    pub idle_secs: u64,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            bell: true,
+            desktop: false,
+            pane_exited: true,
+            verify_failed: true,
+            agent_idle: true,
+            idle_secs: default_idle_secs(),
+        }
+    }
+}
+
+/// `grid` shows every pane at once; `tabs` shows one full-size pane with a
+/// tab bar, which stays readable with many agents.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TuiLayout {
+    #[default]
+    Grid,
+    Tabs,
+}
+
+impl TuiLayout {
+    pub fn toggled(self) -> Self {
+        match self {
+            TuiLayout::Grid => TuiLayout::Tabs,
+            TuiLayout::Tabs => TuiLayout::Grid,
+        }
+    }
+}
+
+impl TuiConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `forge export` settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportConfig {
+    /// Scrub API keys and tokens from logs, transcripts and git data.
+    #[serde(default = "default_true")]
+    pub redact: bool,
+    /// Extra regexes to scrub on top of the built-in token formats.
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// Where `forge export --upload` sends the archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload: Option<UploadConfig>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            redact: true,
+            redact_patterns: Vec::new(),
+            upload: None,
+        }
+    }
+}
+
+/// Object storage target. Credentials are never stored here: the provider's
+/// CLI reads them from its standard environment variables.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadConfig {
+    pub provider: UploadProvider,
+    pub bucket: String,
+    /// Key prefix inside the bucket, e.g. "forge/nightly".
+    #[serde(default)]
+    pub prefix: String,
+    /// S3-compatible endpoint (MinIO, R2); S3 only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadProvider {
+    S3,
+    Gcs,
+}
+
+impl ExportConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `context/` settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContextConfig {
+    /// Extra categories, e.g. "benchmarks" or "security-notes", alongside
+    /// decisions, gotchas, patterns, poc and references.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Similarity search over entries, built by `forge context reindex`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingsConfig>,
+    /// Rules for `forge context lint` and the post-session check.
+    #[serde(default, skip_serializing_if = "ContextLintConfig::is_default")]
+    pub lint: ContextLintConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContextLintConfig {
+    /// Longest body, in lines, for everything but references (0 = no limit).
+    #[serde(default = "default_lint_max_lines")]
+    pub max_lines: usize,
+    #[serde(default = "default_lint_max_reference_lines")]
+    pub max_reference_lines: usize,
+    /// Frontmatter keys every entry must set, e.g. ["author", "tags"].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<String>,
+}
+
+fn default_lint_max_lines() -> usize {
+    50
+}
+
+fn default_lint_max_reference_lines() -> usize {
+    300
+}
+
+impl Default for ContextLintConfig {
+    fn default() -> Self {
+        Self {
+            max_lines: default_lint_max_lines(),
+            max_reference_lines: default_lint_max_reference_lines(),
+            required: Vec::new(),
+        }
+    }
+}
+
+impl ContextLintConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl ContextConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The default categories followed by configured ones. Names that
+    /// aren't directory-safe, or that clash with `packages/`, are skipped.
+    pub fn categories(&self) -> Vec<String> {
+        let mut all: Vec<String> = crate::context::DEFAULT_CATEGORIES.iter().map(|c| c.to_string()).collect();
+        for name in &self.categories {
+            let valid = !name.is_empty()
+                && name != "packages"
+                && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+            if valid && !all.contains(name) {
+                all.push(name.clone());
+            }
+        }
+        all
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmbeddingsConfig {
+    pub provider: EmbeddingProvider,
+    /// e.g. "text-embedding-3-small" or "nomic-embed-text".
+    pub model: String,
+    /// Base URL; defaults to the provider's public API or local server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Environment variable holding the API key (OpenAI-compatible only).
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+    /// Entries added to each agent prompt.
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+}
+
+fn default_api_key_env() -> String {
+    "OPENAI_API_KEY".into()
+}
+
+fn default_top_k() -> usize {
+    5
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// Any OpenAI-compatible `/v1/embeddings` API.
+    Openai,
+    /// A local Ollama server.
+    Ollama,
+}
+
+/// `[plugins.<name>]`: which events `forge-<name> hook` hears about.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PluginConfig {
+    /// Event names as in `.forge/events/` (`session_end`, `run_finished`,
+    /// ...), or `*` for every one.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// `[notifications]`: where run events are sent as they happen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NotificationsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desktop: Option<DesktopConfig>,
+}
+
+impl NotificationsConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    /// Where events are POSTed as JSON.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+    /// A `[secrets]` entry or environment variable holding the URL instead,
+    /// since a Slack or Discord webhook URL is itself a credential.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_env: Option<String>,
+    /// The payload's shape; from the URL's host if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<WebhookFormat>,
+    /// Which events are sent.
+    #[serde(default = "default_notify_events")]
+    pub events: Vec<NotifyEvent>,
+}
+
+/// `[notifications.email]`: the same events by SMTP, sent through curl.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailConfig {
+    /// `smtps://host:465`, or `smtp://host:587` to upgrade with STARTTLS
+    /// when the server offers it.
+    pub smtp_url: String,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// A `[secrets]` entry or environment variable holding the SMTP password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<String>,
+    /// Which events are mailed.
+    #[serde(default = "default_notify_events")]
+    pub events: Vec<NotifyEvent>,
+    /// Batch events into one summary per this many executor sessions, and
+    /// a last one when the run finishes. 0 mails every event on its own.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub digest: u32,
+}
+
+/// `[notifications.desktop]`: the machine running forge shows a
+/// notification, for local runs watched from another window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DesktopConfig {
+    /// Which events pop up; fewer than the webhook's by default, as each
+    /// one interrupts.
+    #[serde(default = "default_desktop_events")]
+    pub events: Vec<NotifyEvent>,
+}
+
+fn default_desktop_events() -> Vec<NotifyEvent> {
+    vec![NotifyEvent::VerifyFailed, NotifyEvent::FeatureBlocked, NotifyEvent::QuestionAsked, NotifyEvent::RunFinished]
+}
+
+fn default_notify_events() -> Vec<NotifyEvent> {
+    vec![
+        NotifyEvent::SessionEnd,
+        NotifyEvent::FeatureBlocked,
+        NotifyEvent::QuestionAsked,
+        NotifyEvent::MilestoneDone,
+        NotifyEvent::BudgetExceeded,
+        NotifyEvent::RunFinished,
+    ]
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{"text": ...}` for Slack incoming webhooks.
+    Slack,
+    /// `{"content": ...}` for Discord webhooks.
+    Discord,
+    /// The event as forge records it, with the project and a `text` summary.
+    Generic,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// An executor session ended.
+    SessionEnd,
+    /// An agent marked its feature blocked.
+    FeatureBlocked,
+    /// An agent asked a question; its feature waits on `forge questions answer`.
+    QuestionAsked,
+    /// A review feature was done and passed verify.
+    MilestoneDone,
+    /// A session stopped at its budget.
+    BudgetExceeded,
+    /// Verify ran and at least one feature failed.
+    VerifyFailed,
+    /// `forge run` finished, with how many features are left.
+    RunFinished,
+}
+
+/// A single key or a list of alternatives.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(k) => vec![k.as_str()],
+            KeyList::Many(ks) => ks.iter().map(|k| k.as_str()).collect(),
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -134,14 +1052,34 @@ pub enum ConfigError {
     Parse(#[from] toml::de::Error),
     #[error("failed to serialize forge.toml: {0}")]
     Serialize(#[from] toml::ser::Error),
+    #[error("included config {}: {error}", path.display())]
+    Include { path: PathBuf, error: String },
+    #[error("config include loop through {}", .0.display())]
+    IncludeCycle(PathBuf),
+    #[error("no [profiles.{0}] in forge.toml (defined: {1})")]
+    UnknownProfile(String, String),
+    #[error("[profiles.{0}.roles.{1}]: unknown role (expected protocol, orchestrating, planning, adjusting)")]
+    UnknownProfileRole(String, String),
 }
 
 impl ForgeConfig {
     pub fn load(project_dir: &Path) -> Result<Self, ConfigError> {
         let path = project_dir.join("forge.toml");
         let content = std::fs::read_to_string(&path)?;
-        let config: ForgeConfig = toml::from_str(&content)?;
-        Ok(config)
+        let value: toml::Value = toml::from_str(&content)?;
+        if value.get("include").is_none() {
+            // Parsing the text itself keeps line numbers in type errors
+            return Ok(toml::from_str(&content)?);
+        }
+        Ok(Self::load_value(project_dir)?.try_into()?)
+    }
+
+    /// forge.toml as TOML, with the files its `include` lists merged in
+    /// underneath: each include in order, then forge.toml itself on top.
+    /// Tables merge key by key, anything else is replaced, and include paths
+    /// are relative to the file naming them.
+    pub fn load_value(project_dir: &Path) -> Result<toml::Value, ConfigError> {
+        read_layered(&project_dir.join("forge.toml"), &mut Vec::new())
     }
 
     pub fn save(&self, project_dir: &Path) -> Result<(), ConfigError> {
@@ -158,7 +1096,7 @@ impl ForgeConfig {
                 name: name.into(),
                 stack: stack.into(),
             },
-            forge: ForgeSettings::default(),
+            forge: ForgeSettings { permissions: Permissions::Standard, ..ForgeSettings::default() },
             principles: Principles {
                 readability: "Code understood in one read after an all nighter".into(),
                 proof: "Tests prove code works, not test that it works".into(),
@@ -166,7 +1104,44 @@ impl ForgeConfig {
                 boundaries: "Divide at abstraction boundaries. APIs guide communication.".into(),
             },
             scopes: BTreeMap::new(),
+            tui: TuiConfig::default(),
+            export: ExportConfig::default(),
+            context: ContextConfig::default(),
+            git: GitConfig::default(),
+            profiles: BTreeMap::new(),
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            notifications: NotificationsConfig::default(),
+            plugins: BTreeMap::new(),
+        }
+    }
+
+    /// This config with `[profiles.<name>]` applied over `[forge]`.
+    pub fn with_profile(mut self, name: &str) -> Result<Self, ConfigError> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let defined: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            let defined = if defined.is_empty() { "none".to_string() } else { defined.join(", ") };
+            return Err(ConfigError::UnknownProfile(name.into(), defined));
+        };
+        let all = RoleOverride { backend: profile.backend, model: profile.model, ..RoleOverride::default() };
+        for role in ["protocol", "orchestrating", "planning", "adjusting"] {
+            all.apply(self.forge.roles.get_mut(role).expect("built-in role"));
+        }
+        for (role, change) in &profile.roles {
+            let spec = self
+                .forge
+                .roles
+                .get_mut(role)
+                .ok_or_else(|| ConfigError::UnknownProfileRole(name.into(), role.clone()))?;
+            change.apply(spec);
+        }
+        if let Some(max) = profile.max_agents {
+            self.forge.max_agents = max;
+        }
+        if let Some(budget) = profile.budget_per_session {
+            self.forge.budget_per_session = budget;
         }
+        Ok(self)
     }
 
     /// List scope names sorted.
@@ -174,9 +1149,175 @@ impl ForgeConfig {
         self.scopes.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Get files owned by a scope.
-    pub fn scope_owns(&self, scope: &str) -> Option<&[String]> {
-        self.scopes.get(scope).map(|s| s.owns.as_slice())
+    /// Get files owned by a scope, relative to the project root.
+    pub fn scope_owns(&self, scope: &str) -> Option<Vec<String>> {
+        let scope = self.scopes.get(scope)?;
+        let dir = scope.dir.as_deref().map(|d| d.trim_end_matches('/'));
+        Some(
+            scope
+                .owns
+                .iter()
+                .map(|owned| match dir {
+                    Some(dir) => format!("{dir}/{}", owned.trim_start_matches("./")),
+                    None => owned.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    /// The scope's `dir`, if it has one.
+    pub fn scope_dir(&self, scope: &str) -> Option<&str> {
+        self.scopes.get(scope).and_then(|s| s.dir.as_deref()).filter(|d| !d.is_empty())
+    }
+
+    /// Where work in `scope` happens under `base`: the project or one of
+    /// its worktrees.
+    pub fn scope_root(&self, base: &Path, scope: &str) -> PathBuf {
+        self.scope_dir(scope).map_or_else(|| base.to_path_buf(), |dir| base.join(dir))
+    }
+}
+
+/// Keys a forge.toml table accepts, by dotted path with `*` standing for a
+/// name the user picks (`scopes.*`). None means the table's own keys are
+/// free-form: scope names, key bindings. Keep in step with the structs
+/// above; serde ignores anything not listed here.
+pub fn known_keys(table: &str) -> Option<&'static [&'static str]> {
+    Some(match table {
+        "" => &[
+            "include", "project", "forge", "principles", "scopes", "tui", "export", "context", "profiles", "backends",
+            "git", "secrets", "notifications", "plugins",
+        ],
+        "project" => &["name", "stack"],
+        "forge" => &[
+            "max_agents",
+            "budget_per_session",
+            "max_total_cost",
+            "session_timeout_secs",
+            "retries",
+            "permissions",
+            "roles",
+            "runaway",
+            "rate_limits",
+        ],
+        "forge.runaway" => &["enabled", "output_factor", "min_output_kb", "repeated_errors", "repeated_calls"],
+        "forge.rate_limits" => &["backoff_secs", "max_backoff_secs", "fallback"],
+        "forge.rate_limits.fallback.*" => &["backend", "model"],
+        "forge.roles" => &["protocol", "orchestrating", "planning", "adjusting", "protocol_b"],
+        "forge.roles.protocol"
+        | "forge.roles.orchestrating"
+        | "forge.roles.planning"
+        | "forge.roles.adjusting"
+        | "forge.roles.protocol_b" => {
+            &[
+                "backend",
+                "model",
+                "context_tokens",
+                "max_turns",
+                "temperature",
+                "max_output_tokens",
+                "budget",
+                "timeout_secs",
+                "retries",
+            ]
+        }
+        "principles" => &["readability", "proof", "style", "boundaries"],
+        "scopes.*" => &["dir", "owns", "api", "upstream", "principles"],
+        "tui" => &["keys", "layout", "alerts"],
+        "tui.alerts" => &["bell", "desktop", "pane_exited", "verify_failed", "agent_idle", "idle_secs"],
+        "export" => &["redact", "redact_patterns", "upload"],
+        "export.upload" => &["provider", "bucket", "prefix", "endpoint", "region"],
+        "context" => &["categories", "embeddings", "lint"],
+        "context.embeddings" => &["provider", "model", "endpoint", "api_key_env", "top_k"],
+        "context.lint" => &["max_lines", "max_reference_lines", "required"],
+        "notifications" => &["webhook", "email", "desktop"],
+        "notifications.desktop" => &["events"],
+        "notifications.webhook" => &["url", "url_env", "format", "events"],
+        "notifications.email" => &["smtp_url", "from", "to", "username", "password_env", "events", "digest"],
+        "plugins.*" => &["events"],
+        "profiles.*" => &["backend", "model", "max_agents", "budget_per_session", "roles"],
+        "profiles.*.roles" => &["protocol", "orchestrating", "planning", "adjusting"],
+        "backends.*" => &[
+            "command",
+            "headless_args",
+            "interactive_args",
+            "model_flag",
+            "max_turns_flag",
+            "temperature_flag",
+            "max_output_tokens_flag",
+            "budget_flag",
+            "env",
+        ],
+        "git" => &[
+            "workflow",
+            "pr_per",
+            "pr_base",
+            "pr_draft",
+            "commit_template",
+            "commit_trailers",
+            "merge_strategy",
+            "delete_merged_branches",
+            "protected",
+            "secret_scan",
+            "secret_scan_skip",
+            "max_file_mb",
+            "commit_state",
+            "push_state",
+            "commit_runtime",
+            "reuse_worktrees",
+            "branch_template",
+            "revert_regressions",
+            "tag_milestones",
+            "tag_template",
+        ],
+        "secrets.*" => &["env", "keychain", "account"],
+        t if t.starts_with("profiles.*.roles.") => return known_keys(&t.replacen("profiles.*.", "forge.", 1)),
+        _ => return None,
+    })
+}
+
+fn read_layered(path: &Path, stack: &mut Vec<PathBuf>) -> Result<toml::Value, ConfigError> {
+    let id = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&id) {
+        return Err(ConfigError::IncludeCycle(path.to_path_buf()));
+    }
+    let content = std::fs::read_to_string(path)?;
+    let mut value: toml::Value = toml::from_str(&content)?;
+    let includes = value.as_table_mut().and_then(|t| t.remove("include"));
+    let mut merged = toml::Value::Table(toml::Table::new());
+    if let Some(includes) = includes {
+        let Some(includes) = includes.as_array().filter(|a| a.iter().all(toml::Value::is_str)) else {
+            return Err(ConfigError::Include { path: path.to_path_buf(), error: "`include` must be a list of paths".into() });
+        };
+        stack.push(id);
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for include in includes.iter().filter_map(toml::Value::as_str) {
+            let included = dir.join(include);
+            let layer = read_layered(&included, stack).map_err(|e| match e {
+                ConfigError::Io(e) => ConfigError::Include { path: included.clone(), error: e.to_string() },
+                ConfigError::Parse(e) => ConfigError::Include { path: included.clone(), error: e.to_string() },
+                other => other,
+            })?;
+            merge(&mut merged, layer);
+        }
+        stack.pop();
+    }
+    merge(&mut merged, value);
+    Ok(merged)
+}
+
+fn merge(base: &mut toml::Value, over: toml::Value) {
+    match (base, over) {
+        (toml::Value::Table(base), toml::Value::Table(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over,
     }
 }
 
@@ -277,6 +1418,25 @@ name = "bare"
         assert!(dm.upstream.is_empty());
     }
 
+    #[test]
+    fn scope_principles() {
+        let toml_str = r#"
+[project]
+name = "p"
+
+[scopes.auth]
+owns = ["src/auth/"]
+
+[scopes.auth.principles]
+secrets = "Never log tokens or passwords"
+errors = "Auth failures return 401, never 500"
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        let principles = &config.scopes["auth"].principles;
+        assert_eq!(principles.len(), 2);
+        assert_eq!(principles["secrets"], "Never log tokens or passwords");
+    }
+
     #[test]
     fn scaffold_creates_default() {
         let config = ForgeConfig::scaffold("test-app", "Rust");
@@ -303,6 +1463,7 @@ model = "o3"
 [forge.roles.orchestrating]
 backend = "claude"
 model = "haiku"
+context_tokens = 8000
 "#;
         let config: ForgeConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.forge.roles.protocol.backend, "codex");
@@ -310,11 +1471,120 @@ model = "haiku"
         // Explicitly overridden to haiku — user choice is respected
         assert_eq!(config.forge.roles.orchestrating.backend, "claude");
         assert_eq!(config.forge.roles.orchestrating.model, "haiku");
+        assert_eq!(config.forge.roles.orchestrating.context_tokens, 8000);
+        assert_eq!(config.forge.roles.protocol.context_tokens, 20_000);
         // Unspecified roles get defaults
         assert_eq!(config.forge.roles.planning.backend, "claude");
         assert_eq!(config.forge.roles.planning.model, "opus");
     }
 
+    #[test]
+    fn parse_tui_keys() {
+        let toml_str = r#"
+[project]
+name = "keys"
+
+[tui]
+layout = "tabs"
+
+[tui.keys]
+prefix = "ctrl+a"
+next = ["j", "down"]
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.tui.layout, TuiLayout::Tabs);
+        assert_eq!(config.tui.keys["prefix"].keys(), vec!["ctrl+a"]);
+        assert_eq!(config.tui.keys["next"].keys(), vec!["j", "down"]);
+    }
+
+    #[test]
+    fn parse_tui_alerts_with_defaults() {
+        let toml_str = r#"
+[project]
+name = "alerts"
+
+[tui.alerts]
+desktop = true
+pane_exited = false
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        let alerts = &config.tui.alerts;
+        assert!(alerts.desktop);
+        assert!(!alerts.pane_exited);
+        // Unset fields keep their defaults
+        assert!(alerts.bell);
+        assert!(alerts.verify_failed);
+        assert_eq!(alerts.idle_secs, 300);
+    }
+
+    #[test]
+    fn default_tui_section_not_serialized() {
+        let config = ForgeConfig::scaffold("quiet", "Rust");
+        let content = toml::to_string_pretty(&config).unwrap();
+        assert!(!content.contains("[tui"), "got: {content}");
+        assert!(!content.contains("[export"), "got: {content}");
+    }
+
+    #[test]
+    fn parse_export_redaction() {
+        let config: ForgeConfig = toml::from_str("[project]\nname = \"x\"\n").unwrap();
+        assert!(config.export.redact);
+        assert!(config.export.redact_patterns.is_empty());
+
+        let toml_str = r#"
+[project]
+name = "x"
+
+[export]
+redact = false
+redact_patterns = ["ACME-[0-9a-f]{32}"]
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        assert!(!config.export.redact);
+        assert_eq!(config.export.redact_patterns, vec!["ACME-[0-9a-f]{32}"]);
+        assert!(config.export.upload.is_none());
+    }
+
+    #[test]
+    fn parse_export_upload() {
+        let toml_str = r#"
+[project]
+name = "x"
+
+[export.upload]
+provider = "gcs"
+bucket = "team-exports"
+prefix = "forge/nightly"
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        let upload = config.export.upload.unwrap();
+        assert_eq!(upload.provider, UploadProvider::Gcs);
+        assert_eq!(upload.bucket, "team-exports");
+        assert_eq!(upload.prefix, "forge/nightly");
+        assert!(upload.endpoint.is_none());
+
+        let bad = "[project]\nname = \"x\"\n[export.upload]\nprovider = \"ftp\"\nbucket = \"b\"\n";
+        assert!(toml::from_str::<ForgeConfig>(bad).is_err());
+    }
+
+    #[test]
+    fn parse_context_embeddings() {
+        let toml_str = r#"
+[project]
+name = "x"
+
+[context.embeddings]
+provider = "ollama"
+model = "nomic-embed-text"
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        let emb = config.context.embeddings.unwrap();
+        assert_eq!(emb.provider, EmbeddingProvider::Ollama);
+        assert_eq!(emb.top_k, 5);
+        assert_eq!(emb.api_key_env, "OPENAI_API_KEY");
+        assert!(ForgeConfig::scaffold("x", "y").context.embeddings.is_none());
+    }
+
     #[test]
     fn roundtrip_save_load() {
         let dir = tempfile::tempdir().unwrap();
@@ -330,4 +1600,284 @@ model = "haiku"
         let result = ForgeConfig::load(dir.path());
         assert!(result.is_err());
     }
+    #[test]
+    fn known_keys_cover_every_field() {
+        fn check(table: &str, value: &toml::Value) {
+            let toml::Value::Table(t) = value else { return };
+            let known = known_keys(table);
+            for (key, child) in t {
+                if let Some(known) = known {
+                    assert!(known.contains(&key.as_str()), "`{key}` missing from known_keys({table:?})");
+                }
+                let path = match (table, known) {
+                    ("", _) => key.clone(),
+                    (_, Some(_)) => format!("{table}.{key}"),
+                    (_, None) => format!("{table}.*"),
+                };
+                check(&path, child);
+            }
+        }
+        let mut config: ForgeConfig = toml::from_str(SAMPLE_TOML).unwrap();
+        config.tui.layout = TuiLayout::Tabs;
+        config.tui.alerts.desktop = true;
+        config.export.redact_patterns = vec!["x".into()];
+        config.export.upload = Some(UploadConfig {
+            provider: UploadProvider::S3,
+            bucket: "b".into(),
+            prefix: "p".into(),
+            endpoint: Some("e".into()),
+            region: Some("r".into()),
+        });
+        config.context.categories = vec!["runbooks".into()];
+        config.context.embeddings = Some(EmbeddingsConfig {
+            provider: EmbeddingProvider::Ollama,
+            model: "m".into(),
+            endpoint: Some("e".into()),
+            api_key_env: "K".into(),
+            top_k: 3,
+        });
+        config.context.lint.required = vec!["author".into()];
+        config.scopes.get_mut("auth").unwrap().principles.insert("secrets".into(), "Never log tokens".into());
+        config.scopes.get_mut("auth").unwrap().dir = Some("services/auth".into());
+        config.forge.session_timeout_secs = Some(1800);
+        config.forge.max_total_cost = Some(200.0);
+        config.forge.retries = 1;
+        config.forge.runaway.repeated_calls = 12;
+        config.forge.rate_limits.max_backoff_secs = 600;
+        config.forge.rate_limits.fallback.insert("claude".into(), Fallback { backend: "codex".into(), model: None });
+        config.forge.roles.protocol = RoleSpec {
+            max_turns: Some(40),
+            temperature: Some(0.0),
+            max_output_tokens: Some(8000),
+            budget: Some(2.0),
+            timeout_secs: Some(600),
+            retries: Some(2),
+            ..RoleSpec::default()
+        };
+        config.forge.roles.protocol_b = Some(RoleSpec { model: "opus".into(), ..RoleSpec::default() });
+        config.profiles.insert(
+            "cheap".into(),
+            Profile {
+                backend: Some("claude".into()),
+                model: Some("haiku".into()),
+                max_agents: Some(2),
+                budget_per_session: Some(1.0),
+                roles: BTreeMap::from([(
+                    "protocol".into(),
+                    RoleOverride {
+                        context_tokens: Some(1),
+                        max_turns: Some(2),
+                        temperature: Some(0.2),
+                        max_output_tokens: Some(3),
+                        budget: Some(0.5),
+                        timeout_secs: Some(60),
+                        retries: Some(0),
+                        ..Default::default()
+                    },
+                )]),
+            },
+        );
+        config.backends.insert(
+            "acme".into(),
+            BackendDef {
+                command: Some("acme-agent".into()),
+                headless_args: vec!["run".into()],
+                interactive_args: vec!["chat".into()],
+                model_flag: Some("-m".into()),
+                max_turns_flag: Some("--turns".into()),
+                temperature_flag: Some("-t".into()),
+                max_output_tokens_flag: Some("--max-tokens".into()),
+                budget_flag: Some("--max-usd".into()),
+                env: BTreeMap::from([("ACME_CI".into(), "1".into())]),
+            },
+        );
+        config.secrets.insert(
+            "ACME_KEY".into(),
+            SecretRef { env: None, keychain: Some("acme".into()), account: Some("ci".into()) },
+        );
+        config.git = GitConfig {
+            workflow: GitWorkflow::Pr,
+            pr_per: PrUnit::Milestone,
+            pr_base: Some("main".into()),
+            pr_draft: true,
+            merge_strategy: MergeStrategy::Squash,
+            delete_merged_branches: true,
+            protected: vec!["migrations/**".into()],
+            secret_scan_skip: vec!["tests/fixtures".into()],
+            max_file_mb: 10,
+            commit_state: true,
+            push_state: true,
+            commit_runtime: vec!["events".into()],
+            reuse_worktrees: true,
+            revert_regressions: true,
+            tag_milestones: true,
+            ..GitConfig::default()
+        };
+        config.notifications.webhook = Some(WebhookConfig {
+            url: "https://hooks.example.com/x".into(),
+            url_env: Some("SLACK_WEBHOOK".into()),
+            format: Some(WebhookFormat::Slack),
+            events: vec![NotifyEvent::RunFinished],
+        });
+        config.notifications.email = Some(EmailConfig {
+            smtp_url: "smtps://smtp.example.com:465".into(),
+            from: "forge@example.com".into(),
+            to: vec!["team@example.com".into()],
+            username: Some("forge".into()),
+            password_env: Some("SMTP_PASSWORD".into()),
+            events: vec![NotifyEvent::FeatureBlocked],
+            digest: 5,
+        });
+        config.notifications.desktop = Some(DesktopConfig { events: vec![NotifyEvent::VerifyFailed] });
+        config.plugins.insert("lint".into(), PluginConfig { events: vec!["session_end".into()] });
+        let value = toml::Value::try_from(&config).unwrap();
+        check("", &value);
+        for table in ["tui", "export", "context", "git", "notifications"] {
+            assert!(value.get(table).is_some(), "{table} not serialized");
+        }
+    }
+
+    #[test]
+    fn role_limits_default_to_forge_settings() {
+        let config: ForgeConfig = toml::from_str(
+            r#"
+[project]
+name = "limits"
+
+[forge]
+budget_per_session = 5.0
+session_timeout_secs = 1800
+retries = 1
+
+[forge.roles.orchestrating]
+model = "haiku"
+budget = 0.5
+timeout_secs = 300
+retries = 0
+"#,
+        )
+        .unwrap();
+        let protocol = config.forge.with_limits(&config.forge.roles.protocol);
+        assert_eq!((protocol.budget, protocol.timeout_secs, protocol.retries), (Some(5.0), Some(1800), Some(1)));
+        let review = config.forge.with_limits(&config.forge.roles.orchestrating);
+        assert_eq!((review.budget, review.timeout_secs, review.retries), (Some(0.5), Some(300), Some(0)));
+        // No global timeout means none
+        assert_eq!(ForgeSettings::default().with_limits(&RoleSpec::default()).timeout_secs, None);
+    }
+
+    #[test]
+    fn secrets_name_a_source_not_a_value() {
+        let config: ForgeConfig = toml::from_str(
+            "[project]\nname = \"s\"\n[secrets]\nANTHROPIC_API_KEY = { keychain = \"forge-anthropic\" }\n",
+        )
+        .unwrap();
+        assert_eq!(config.secrets["ANTHROPIC_API_KEY"].keychain.as_deref(), Some("forge-anthropic"));
+
+        let plaintext = "[project]\nname = \"s\"\n[secrets]\nOPENAI_API_KEY = { value = \"sk-abc\" }\n";
+        assert!(toml::from_str::<ForgeConfig>(plaintext).unwrap_err().to_string().contains("unknown field `value`"));
+        assert!(toml::from_str::<ForgeConfig>("[project]\nname = \"s\"\n[secrets]\nK = \"sk-abc\"\n").is_err());
+    }
+
+    #[test]
+    fn profiles_override_roles_and_budget() {
+        let toml_str = r#"
+[project]
+name = "profiles"
+
+[forge]
+budget_per_session = 5.0
+
+[forge.roles.orchestrating]
+model = "sonnet"
+context_tokens = 8000
+
+[profiles.cheap]
+model = "haiku"
+budget_per_session = 1.0
+
+[profiles.quality.roles.protocol]
+model = "opus"
+"#;
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        let cheap = config.clone().with_profile("cheap").unwrap();
+        assert_eq!(cheap.forge.roles.protocol.model, "haiku");
+        assert_eq!(cheap.forge.roles.planning.model, "haiku");
+        // Only what the profile names changes
+        assert_eq!(cheap.forge.roles.orchestrating.context_tokens, 8000);
+        assert_eq!(cheap.forge.budget_per_session, 1.0);
+
+        let quality = config.clone().with_profile("quality").unwrap();
+        assert_eq!(quality.forge.roles.protocol.model, "opus");
+        assert_eq!(quality.forge.roles.orchestrating.model, "sonnet");
+        assert_eq!(quality.forge.budget_per_session, 5.0);
+
+        let err = config.with_profile("fast").unwrap_err();
+        assert_eq!(err.to_string(), "no [profiles.fast] in forge.toml (defined: cheap, quality)");
+    }
+
+    #[test]
+    fn profile_with_unknown_role_is_an_error() {
+        let toml_str = "[project]\nname = \"p\"\n\n[profiles.x.roles.protocl]\nmodel = \"opus\"\n";
+        let config: ForgeConfig = toml::from_str(toml_str).unwrap();
+        assert!(matches!(config.with_profile("x"), Err(ConfigError::UnknownProfileRole(_, role)) if role == "protocl"));
+    }
+
+    #[test]
+    fn includes_merge_under_local_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("apps/web");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            dir.path().join("shared-forge.toml"),
+            r#"
+[forge.roles.protocol]
+backend = "codex"
+model = "o3"
+
+[forge.roles.planning]
+model = "opus"
+
+[principles]
+readability = "Shared rule"
+proof = "Shared proof"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            project.join("forge.toml"),
+            r#"
+include = ["../../shared-forge.toml"]
+
+[project]
+name = "web"
+
+[forge.roles.protocol]
+model = "o4-mini"
+
+[principles]
+proof = "Local proof"
+"#,
+        )
+        .unwrap();
+        let config = ForgeConfig::load(&project).unwrap();
+        assert_eq!(config.project.name, "web");
+        // Local keys win; the rest of each shared table comes through
+        assert_eq!(config.forge.roles.protocol.backend, "codex");
+        assert_eq!(config.forge.roles.protocol.model, "o4-mini");
+        assert_eq!(config.forge.roles.planning.model, "opus");
+        assert_eq!(config.principles.readability, "Shared rule");
+        assert_eq!(config.principles.proof, "Local proof");
+    }
+
+    #[test]
+    fn include_errors_name_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("forge.toml"), "include = [\"missing.toml\"]\n[project]\nname = \"p\"\n").unwrap();
+        let err = ForgeConfig::load(dir.path()).unwrap_err().to_string();
+        assert!(err.starts_with("included config ") && err.contains("missing.toml"), "{err}");
+
+        std::fs::write(dir.path().join("a.toml"), "include = [\"forge.toml\"]\n").unwrap();
+        std::fs::write(dir.path().join("forge.toml"), "include = [\"a.toml\"]\n[project]\nname = \"p\"\n").unwrap();
+        assert!(matches!(ForgeConfig::load(dir.path()), Err(ConfigError::IncludeCycle(_))));
+    }
 }
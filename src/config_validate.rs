@@ -0,0 +1,539 @@
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::{self, ConfigError, ForgeConfig, RoleSpec};
+use crate::context_meta;
+use crate::git;
+use crate::secrets::{self, SecretError};
+use crate::tui_keys::KeyBindings;
+
+pub(crate) const ROLES: &[&str] = &["protocol", "orchestrating", "planning", "adjusting"];
+const SKIP_DIRS: &[&str] = &[".git", ".forge", "node_modules", "target"];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "problem", rename_all = "snake_case")]
+pub enum Problem {
+    /// Bad TOML, or a value of the wrong type.
+    Parse { error: String },
+    /// Ignored by forge; usually a typo.
+    UnknownKey { key: String, suggestion: Option<String> },
+    /// Neither a built-in backend nor a binary on PATH. `role` is where
+    /// it's set, e.g. `forge.roles.protocol`.
+    UnknownBackend { role: String, backend: String },
+    /// A `[backends.<name>]` whose command isn't on PATH.
+    CommandNotFound { backend: String, command: String },
+    /// A `[secrets]` entry that can't be read here; `forge run` would refuse
+    /// to start.
+    SecretUnavailable { error: String },
+    /// A built-in backend whose CLI isn't installed here.
+    BackendNotInstalled { role: String, backend: String },
+    /// Doesn't look like a model name the backend takes.
+    UnknownModel { role: String, backend: String, model: String },
+    /// `max_turns`, `temperature` or `max_output_tokens` the backend has no
+    /// flag for, so it's ignored.
+    UnsupportedSetting { role: String, backend: String, setting: String },
+    /// Not in `[forge.roles]`, so it runs on the defaults.
+    MissingRole { role: String },
+    /// A scope `dir` that isn't a directory in the project.
+    ScopeDirMissing { scope: String, dir: String },
+    /// An `owns` path or glob that matches nothing in the project.
+    OwnsNothing { scope: String, pattern: String },
+    UnknownUpstream { scope: String, upstream: String },
+    /// `[tui.keys]` the TUI would refuse to start with.
+    KeyBindings { error: String },
+    InvalidValue { key: String, error: String },
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub problem: Problem,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{level}: ")?;
+        match &self.problem {
+            Problem::Parse { error } => write!(f, "{}", error.trim_end()),
+            Problem::UnknownKey { key, suggestion: Some(s) } => write!(f, "unknown key `{key}` (did you mean `{s}`?)"),
+            Problem::UnknownKey { key, suggestion: None } => write!(f, "unknown key `{key}`"),
+            Problem::UnknownBackend { role, backend } => {
+                write!(f, "{role}: backend '{backend}' is not claude, codex or a command on PATH")
+            }
+            Problem::CommandNotFound { backend, command } => {
+                write!(f, "backends.{backend}: command '{command}' not found in PATH")
+            }
+            Problem::SecretUnavailable { error } => write!(f, "{error}"),
+            Problem::BackendNotInstalled { role, backend } => {
+                write!(f, "{role}: backend '{backend}' not found in PATH")
+            }
+            Problem::UnknownModel { role, backend, model } if model.is_empty() => {
+                write!(f, "{role}: no model set for {backend}")
+            }
+            Problem::UnknownModel { role, backend, model } => {
+                write!(f, "{role}: '{model}' doesn't look like a {backend} model")
+            }
+            Problem::UnsupportedSetting { role, backend, setting } => {
+                write!(f, "{role}: {backend} has no flag for {setting}; it's ignored")
+            }
+            Problem::MissingRole { role } => {
+                write!(f, "[forge.roles.{role}] not set, using the default backend and model")
+            }
+            Problem::ScopeDirMissing { scope, dir } => write!(f, "scopes.{scope}: dir '{dir}' is not a directory"),
+            Problem::OwnsNothing { scope, pattern } => write!(f, "scopes.{scope}: owns '{pattern}' matches nothing"),
+            Problem::UnknownUpstream { scope, upstream } => {
+                write!(f, "scopes.{scope}: upstream '{upstream}' is not a scope")
+            }
+            Problem::KeyBindings { error } => write!(f, "{error}"),
+            Problem::InvalidValue { key, error } => write!(f, "{key}: {error}"),
+        }
+    }
+}
+
+fn issue(severity: Severity, problem: Problem) -> Issue {
+    Issue { severity, problem }
+}
+
+/// Edit distance, for "did you mean".
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            row.push((prev[j] + usize::from(ca != *cb)).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+fn suggest(key: &str, known: &[&str]) -> Option<String> {
+    known
+        .iter()
+        .map(|k| (distance(key, k), *k))
+        .filter(|(d, k)| *d <= 2.max(k.len() / 4))
+        .min()
+        .map(|(_, k)| k.to_string())
+}
+
+/// Keys serde would silently ignore, with their dotted paths.
+fn unknown_keys(table: &str, value: &toml::Value, out: &mut Vec<Problem>) {
+    let toml::Value::Table(entries) = value else { return };
+    let known = config::known_keys(table);
+    for (key, child) in entries {
+        let path = if table.is_empty() { key.clone() } else { format!("{table}.{key}") };
+        match known {
+            Some(known) if !known.contains(&key.as_str()) => {
+                out.push(Problem::UnknownKey { key: path, suggestion: suggest(key, known) });
+            }
+            Some(_) => unknown_keys(&path, child, out),
+            None => unknown_keys(&format!("{table}.*"), child, out),
+        }
+    }
+}
+
+pub(crate) fn on_path(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// Loose check: aliases and the usual prefixes, so new releases pass.
+fn plausible_model(backend: &str, model: &str) -> bool {
+    match backend {
+        "claude" => ["sonnet", "opus", "haiku"].contains(&model) || model.starts_with("claude-"),
+        "codex" => ["gpt-", "o1", "o3", "o4", "codex"].iter().any(|p| model.starts_with(p)),
+        _ => !model.is_empty(),
+    }
+}
+
+/// `owns` entries are paths (files or directories) or git-style globs.
+pub(crate) fn glob_regex(pattern: &str) -> Regex {
+    let mut re = String::from("^");
+    let mut chars = pattern.trim_end_matches('/').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re).expect("escaped glob is a valid regex")
+}
+
+fn any_path_matches(root: &Path, dir: &Path, re: &Regex) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else { return false };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if SKIP_DIRS.contains(&name.as_str()) {
+            return false;
+        }
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        re.is_match(&rel) || (path.is_dir() && any_path_matches(root, &path, re))
+    })
+}
+
+fn owns_matches(project_dir: &Path, pattern: &str) -> bool {
+    if pattern.contains(['*', '?']) {
+        any_path_matches(project_dir, project_dir, &glob_regex(pattern))
+    } else {
+        project_dir.join(pattern).exists()
+    }
+}
+
+/// A role's backend must be runnable and its model plausible for it.
+/// Backends defined in `[backends]` are checked once, on their own.
+fn check_role(role: &str, spec: &RoleSpec, config: &ForgeConfig, issues: &mut Vec<Issue>) {
+    let backend = spec.backend.clone();
+    let builtin = matches!(backend.as_str(), "claude" | "codex");
+    if !config.backends.contains_key(&backend) && !on_path(&backend) {
+        let problem = if builtin {
+            Problem::BackendNotInstalled { role: role.into(), backend: backend.clone() }
+        } else {
+            Problem::UnknownBackend { role: role.into(), backend: backend.clone() }
+        };
+        issues.push(issue(if builtin { Severity::Warning } else { Severity::Error }, problem));
+    }
+    if !plausible_model(&backend, &spec.model) {
+        let severity = if spec.model.is_empty() { Severity::Error } else { Severity::Warning };
+        let model = spec.model.clone();
+        issues.push(issue(severity, Problem::UnknownModel { role: role.into(), backend: backend.clone(), model }));
+    }
+    // Whether the backend takes each setting: its [backends] flags, else what
+    // the built-in CLIs accept (claude: turns, output and budget; codex: output)
+    let def = config.backends.get(&backend);
+    for (setting, set, supported) in [
+        ("max_turns", spec.max_turns.is_some(), def.map_or(backend == "claude", |d| d.max_turns_flag.is_some())),
+        ("temperature", spec.temperature.is_some(), def.is_some_and(|d| d.temperature_flag.is_some())),
+        ("max_output_tokens", spec.max_output_tokens.is_some(), def.map_or(builtin, |d| d.max_output_tokens_flag.is_some())),
+        ("budget", spec.budget.is_some(), def.map_or(backend == "claude", |d| d.budget_flag.is_some())),
+    ] {
+        if set && !supported {
+            issues.push(issue(
+                Severity::Warning,
+                Problem::UnsupportedSetting { role: role.into(), backend: backend.clone(), setting: setting.into() },
+            ));
+        }
+    }
+}
+
+/// Check forge.toml beyond what parsing catches: unknown keys, backends and
+/// models, scopes, key bindings and `[context]` names. Unknown keys are
+/// warnings unless `strict`, which treats them like serde's
+/// `deny_unknown_fields`.
+pub fn validate(project_dir: &Path, strict: bool) -> Result<Vec<Issue>, ConfigError> {
+    // With includes, keys are checked on the merged result
+    let raw = match ForgeConfig::load_value(project_dir) {
+        Ok(raw) => raw,
+        Err(ConfigError::Io(e)) => return Err(ConfigError::Io(e)),
+        Err(e) => return Ok(vec![issue(Severity::Error, Problem::Parse { error: e.to_string() })]),
+    };
+    let mut unknown = Vec::new();
+    unknown_keys("", &raw, &mut unknown);
+    let unknown_severity = if strict { Severity::Error } else { Severity::Warning };
+    let mut issues: Vec<Issue> = unknown.into_iter().map(|p| issue(unknown_severity, p)).collect();
+    let config = match ForgeConfig::load(project_dir) {
+        Ok(config) => config,
+        Err(e) => {
+            issues.push(issue(Severity::Error, Problem::Parse { error: e.to_string() }));
+            return Ok(issues);
+        }
+    };
+
+    let defined = raw.get("forge").and_then(|f| f.get("roles"));
+    for role in ROLES {
+        if defined.and_then(|r| r.get(role)).is_none() {
+            issues.push(issue(Severity::Warning, Problem::MissingRole { role: role.to_string() }));
+        }
+        if let Some(spec) = config.forge.roles.get(role) {
+            check_role(&format!("forge.roles.{role}"), spec, &config, &mut issues);
+        }
+    }
+    if let Some(spec) = &config.forge.roles.protocol_b {
+        check_role("forge.roles.protocol_b", spec, &config, &mut issues);
+    }
+    // A fallback without a model runs the executor's
+    for (name, fallback) in &config.forge.rate_limits.fallback {
+        let model = fallback.model.clone().unwrap_or_else(|| config.forge.roles.protocol.model.clone());
+        let spec = RoleSpec { backend: fallback.backend.clone(), model, ..RoleSpec::default() };
+        check_role(&format!("forge.rate_limits.fallback.{name}"), &spec, &config, &mut issues);
+    }
+    for name in config.profiles.keys() {
+        match config.clone().with_profile(name) {
+            // Only roles the profile changes, so [forge.roles] problems aren't repeated
+            Ok(profiled) => {
+                for role in ROLES {
+                    if let Some(spec) = profiled.forge.roles.get(role)
+                        && config.forge.roles.get(role) != Some(spec)
+                    {
+                        check_role(&format!("profiles.{name}: {role}"), spec, &config, &mut issues);
+                    }
+                }
+            }
+            Err(e) => issues.push(issue(
+                Severity::Error,
+                Problem::InvalidValue { key: format!("profiles.{name}"), error: e.to_string() },
+            )),
+        }
+    }
+
+    for (name, def) in &config.backends {
+        if !on_path(def.command(name)) {
+            issues.push(issue(
+                Severity::Error,
+                Problem::CommandNotFound { backend: name.clone(), command: def.command(name).to_string() },
+            ));
+        }
+    }
+
+    for (name, secret) in &config.secrets {
+        if let Err(e) = secrets::resolve(name, secret) {
+            // The machine validating may not be the one running agents
+            let severity = if matches!(e, SecretError::NoSource(_)) { Severity::Error } else { Severity::Warning };
+            issues.push(issue(severity, Problem::SecretUnavailable { error: e.to_string() }));
+        }
+    }
+
+    for (name, scope) in &config.scopes {
+        let root = config.scope_root(project_dir, name);
+        if let Some(dir) = config.scope_dir(name)
+            && !root.is_dir()
+        {
+            issues.push(issue(Severity::Warning, Problem::ScopeDirMissing { scope: name.clone(), dir: dir.into() }));
+        }
+        for pattern in &scope.owns {
+            if !owns_matches(&root, pattern) {
+                issues.push(issue(
+                    Severity::Warning,
+                    Problem::OwnsNothing { scope: name.clone(), pattern: pattern.clone() },
+                ));
+            }
+        }
+        for upstream in &scope.upstream {
+            if !config.scopes.contains_key(upstream) {
+                issues.push(issue(
+                    Severity::Error,
+                    Problem::UnknownUpstream { scope: name.clone(), upstream: upstream.clone() },
+                ));
+            }
+        }
+    }
+
+    if let Err(e) = KeyBindings::from_config(&config.tui.keys) {
+        issues.push(issue(Severity::Error, Problem::KeyBindings { error: e.to_string() }));
+    }
+    let mut invalid = |key: &str, error: String| {
+        issues.push(issue(Severity::Error, Problem::InvalidValue { key: key.into(), error }));
+    };
+    let categories = config.context.categories();
+    for name in &config.context.categories {
+        if !categories.contains(name) {
+            invalid(
+                "context.categories",
+                format!("'{name}' is not a valid category (lowercase letters, digits, - and _; not `packages`)"),
+            );
+        }
+    }
+    for field in &config.context.lint.required {
+        if !context_meta::KEYS.contains(&field.as_str()) {
+            invalid(
+                "context.lint.required",
+                format!("'{field}' is not a frontmatter key (expected one of {})", context_meta::KEYS.join(", ")),
+            );
+        }
+    }
+    for pattern in &config.export.redact_patterns {
+        if let Err(e) = Regex::new(pattern) {
+            invalid("export.redact_patterns", format!("'{pattern}': {e}"));
+        }
+    }
+    let template = &config.git.branch_template;
+    if !template.contains("{agent}") && !template.contains("{feature_id}") {
+        invalid("git.branch_template", format!("'{template}' needs {{agent}} or {{feature_id}} so agents get their own branches"));
+    } else {
+        let example = template.replace("{agent}", "agent-1").replace("{feature_id}", "f001");
+        // Without git there's nothing to ask
+        if on_path("git") && git::run(project_dir, &["check-ref-format", "--branch", &example]).is_err() {
+            invalid("git.branch_template", format!("'{example}' is not a valid branch name"));
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(toml: &str, strict: bool) -> Vec<Issue> {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src/db")).unwrap();
+        std::fs::write(tmp.path().join("src/db/pool.rs"), "").unwrap();
+        std::fs::write(tmp.path().join("forge.toml"), toml).unwrap();
+        validate(tmp.path(), strict).unwrap()
+    }
+
+    fn problems(issues: &[Issue]) -> Vec<&Problem> {
+        issues
+            .iter()
+            .map(|i| &i.problem)
+            // Depends on what's installed where the tests run
+            .filter(|p| !matches!(p, Problem::BackendNotInstalled { .. }))
+            .collect()
+    }
+
+    const ROLES_TOML: &str = "[forge.roles.protocol]\n[forge.roles.orchestrating]\n[forge.roles.planning]\n[forge.roles.adjusting]\n";
+
+    #[test]
+    fn clean_config_has_no_issues() {
+        let toml = format!(
+            "[project]\nname = \"t\"\n\n{ROLES_TOML}\n[scopes.db]\nowns = [\"src/db/\", \"src/**/*.rs\"]\n\n\
+             [scopes.core]\ndir = \"src\"\nowns = [\"db/\"]\n\n[context]\ncategories = [\"runbooks\"]\n"
+        );
+        assert_eq!(problems(&check(&toml, true)), Vec::<&Problem>::new());
+    }
+
+    #[test]
+    fn reports_typos_and_bad_values() {
+        let toml = r#"[project]
+name = "t"
+
+[forge]
+max_agent = 2
+
+[forge.roles.protocol]
+backend = "claude"
+model = "gpt-4o"
+temperature = 0.2
+max_turns = 40
+
+[forge.roles.orchestrating]
+backend = "no-such-agent-cli"
+
+[forge.roles.planning]
+backend = "acme"
+
+[forge.roles.adjusting]
+
+[profiles.cheap.roles.adjusting]
+model = "haku"
+
+[backends.acme]
+command = "no-such-acme-agent"
+
+[secrets]
+ACME_KEY = { env = "FORGE_TEST_UNSET_KEY" }
+
+[scopes.api]
+owns = ["src/api/", "src/*.py"]
+upstream = ["db"]
+
+[scopes.web]
+dir = "apps/web"
+owns = ["src/"]
+
+[context.lint]
+required = ["autor"]
+"#;
+        let issues = check(toml, false);
+        assert_eq!(
+            problems(&issues),
+            vec![
+                &Problem::UnknownKey { key: "forge.max_agent".into(), suggestion: Some("max_agents".into()) },
+                &Problem::UnknownModel { role: "forge.roles.protocol".into(), backend: "claude".into(), model: "gpt-4o".into() },
+                &Problem::UnsupportedSetting {
+                    role: "forge.roles.protocol".into(),
+                    backend: "claude".into(),
+                    setting: "temperature".into(),
+                },
+                &Problem::UnknownBackend { role: "forge.roles.orchestrating".into(), backend: "no-such-agent-cli".into() },
+                &Problem::UnknownModel {
+                    role: "profiles.cheap: adjusting".into(),
+                    backend: "claude".into(),
+                    model: "haku".into(),
+                },
+                &Problem::CommandNotFound { backend: "acme".into(), command: "no-such-acme-agent".into() },
+                &Problem::SecretUnavailable { error: "secret ACME_KEY: $FORGE_TEST_UNSET_KEY is not set".into() },
+                &Problem::OwnsNothing { scope: "api".into(), pattern: "src/api/".into() },
+                &Problem::OwnsNothing { scope: "api".into(), pattern: "src/*.py".into() },
+                &Problem::UnknownUpstream { scope: "api".into(), upstream: "db".into() },
+                &Problem::ScopeDirMissing { scope: "web".into(), dir: "apps/web".into() },
+                &Problem::OwnsNothing { scope: "web".into(), pattern: "src/".into() },
+                &Problem::InvalidValue {
+                    key: "context.lint.required".into(),
+                    error: format!("'autor' is not a frontmatter key (expected one of {})", context_meta::KEYS.join(", ")),
+                },
+            ]
+        );
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].to_string(), "warning: unknown key `forge.max_agent` (did you mean `max_agents`?)");
+        // Strict mode turns ignored keys into errors
+        assert_eq!(check(toml, true)[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn missing_roles_and_type_errors() {
+        let issues = check("[project]\nname = \"t\"\n\n[forge.roles.protocol]\nmodel = \"opus\"\n", false);
+        assert_eq!(
+            problems(&issues),
+            vec![
+                &Problem::MissingRole { role: "orchestrating".into() },
+                &Problem::MissingRole { role: "planning".into() },
+                &Problem::MissingRole { role: "adjusting".into() },
+            ]
+        );
+        let issues = check("[project]\nname = \"t\"\n[forge]\nmax_agents = \"four\"\n", false);
+        assert!(matches!(&issues[..], [Issue { severity: Severity::Error, problem: Problem::Parse { .. } }]));
+    }
+
+    #[test]
+    fn branch_templates() {
+        let with = |template: &str| format!("[project]\nname = \"t\"\n[git]\nbranch_template = \"{template}\"\n{ROLES_TOML}");
+        assert!(problems(&check(&with("PROJ/forge-{feature_id}"), false)).is_empty());
+        assert_eq!(
+            problems(&check(&with("forge/work"), false)),
+            vec![&Problem::InvalidValue {
+                key: "git.branch_template".into(),
+                error: "'forge/work' needs {agent} or {feature_id} so agents get their own branches".into(),
+            }]
+        );
+        assert_eq!(
+            problems(&check(&with("forge/{agent} x"), false)),
+            vec![&Problem::InvalidValue {
+                key: "git.branch_template".into(),
+                error: "'forge/agent-1 x' is not a valid branch name".into(),
+            }]
+        );
+        let git = config::GitConfig { branch_template: "PROJ/{feature_id}-{agent}".into(), ..Default::default() };
+        assert_eq!(crate::runner::agent_branch(&git, "agent-2", "f007"), "PROJ/f007-agent-2");
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::{BackendDef, ForgeConfig, Scope};
+use crate::config_validate;
+
+/// Directories that hold a project's modules, one scope each.
+const SCOPE_PARENTS: &[&str] = &["src", "crates", "packages", "apps", "services"];
+/// Top-level directories that are never a scope: forge's own and build output.
+const NOT_SCOPES: &[&str] = &["context", "feedback", "scripts", "docs", "target", "node_modules", "dist", "build"];
+
+/// Questions on `output`, answers read line by line from `input`. An empty
+/// answer (or end of input) takes the default.
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    fn ask(&mut self, question: &str, default: &str) -> io::Result<String> {
+        if default.is_empty() {
+            write!(self.output, "{question}: ")?;
+        } else {
+            write!(self.output, "{question} [{default}]: ")?;
+        }
+        self.output.flush()?;
+        let mut line = String::new();
+        self.input.read_line(&mut line)?;
+        let answer = line.trim();
+        Ok(if answer.is_empty() { default.to_string() } else { answer.to_string() })
+    }
+
+    pub(crate) fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        let hint = if default { "[Y/n]" } else { "[y/N]" };
+        loop {
+            match self.ask(&format!("{question} {hint}"), "")?.to_ascii_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "  Answer y or n.")?,
+            }
+        }
+    }
+
+    fn number<T: FromStr + ToString>(&mut self, question: &str, default: T) -> io::Result<T> {
+        let default = default.to_string();
+        loop {
+            let answer = self.ask(question, &default)?;
+            match answer.parse() {
+                Ok(n) => return Ok(n),
+                Err(_) => writeln!(self.output, "  '{answer}' is not a number.")?,
+            }
+        }
+    }
+}
+
+/// A model to offer for a role on a backend forge knows.
+fn suggested_model(backend: &str, role: &str) -> &'static str {
+    match (backend, role) {
+        ("claude", "planning") => "opus",
+        ("claude", "orchestrating") => "haiku",
+        ("claude", _) => "sonnet",
+        ("codex", _) => "o3",
+        _ => "",
+    }
+}
+
+/// Scope candidates from the directory layout: each directory under `src/`,
+/// `crates/` and the like, else each top-level directory. Name and `owns` path.
+pub fn candidate_scopes(project_dir: &Path) -> Vec<(String, String)> {
+    let dirs = |dir: &Path| -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        names.sort();
+        names
+    };
+    let mut scopes = Vec::new();
+    for parent in SCOPE_PARENTS {
+        for name in dirs(&project_dir.join(parent)) {
+            scopes.push((name.clone(), format!("{parent}/{name}/")));
+        }
+    }
+    if scopes.is_empty() {
+        for name in dirs(project_dir) {
+            if !NOT_SCOPES.contains(&name.as_str()) && !SCOPE_PARENTS.contains(&name.as_str()) {
+                scopes.push((name.clone(), format!("{name}/")));
+            }
+        }
+    }
+    scopes
+}
+
+/// Walk through backends, models per role, budget and scopes, starting from
+/// `config`'s values. `installed` lists the backends found on this machine,
+/// offered first. Returns the config to write, or None if the user declined.
+pub fn run<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    project_dir: &Path,
+    mut config: ForgeConfig,
+    installed: &[String],
+) -> io::Result<Option<ForgeConfig>> {
+    let out = |p: &mut Prompter<R, W>, line: &str| writeln!(p.output, "{line}");
+    out(prompter, "forge config wizard (Enter keeps the value in brackets)")?;
+    out(prompter, "")?;
+    config.project.name = prompter.ask("Project name", &config.project.name)?;
+    config.project.stack = prompter.ask("Stack (e.g. Rust, axum, sqlx)", &config.project.stack)?;
+
+    out(prompter, "")?;
+    if installed.is_empty() {
+        out(prompter, "No agent CLI found on PATH (claude, codex); roles default to claude.")?;
+    } else {
+        out(prompter, &format!("Backends found: {}", installed.join(", ")))?;
+    }
+    for (role, what) in [
+        ("protocol", "executor, implements features"),
+        ("orchestrating", "reviewer, runs after each session"),
+        ("planning", "architect, decomposes the design"),
+        ("adjusting", "replans from new context"),
+    ] {
+        let spec = config.forge.roles.get(role).expect("built-in role");
+        // An uninstalled default backend gives way to one that's here
+        let default_backend = match installed.first() {
+            Some(first) if !installed.contains(&spec.backend) => first.clone(),
+            _ => spec.backend.clone(),
+        };
+        out(prompter, &format!("\n{role} ({what})"))?;
+        let backend = prompter.ask("  Backend", &default_backend)?;
+        let default_model = if backend == spec.backend { spec.model.as_str() } else { suggested_model(&backend, role) };
+        let model = prompter.ask("  Model", default_model)?;
+        let spec = config.forge.roles.get_mut(role).expect("built-in role");
+        spec.backend = backend;
+        spec.model = model;
+    }
+
+    out(prompter, "")?;
+    config.forge.budget_per_session = prompter.number("Budget per session (USD)", config.forge.budget_per_session)?;
+    config.forge.max_agents = prompter.number("Max parallel agents", config.forge.max_agents)?;
+
+    let candidates: Vec<(String, String)> =
+        candidate_scopes(project_dir).into_iter().filter(|(name, _)| !config.scopes.contains_key(name)).collect();
+    if !candidates.is_empty() {
+        out(prompter, "\nScopes give agents ownership boundaries. From the directory layout:")?;
+        for (name, owns) in candidates {
+            if prompter.confirm(&format!("  Add scope `{name}` owning {owns}?"), true)? {
+                config.scopes.insert(name, Scope { owns: vec![owns], ..Scope::default() });
+            }
+        }
+    }
+
+    out(prompter, "")?;
+    let target = project_dir.join("forge.toml");
+    let question = if target.exists() { "Overwrite forge.toml? (comments are not kept)" } else { "Write forge.toml?" };
+    Ok(prompter.confirm(question, true)?.then_some(config))
+}
+
+/// `installed` for `run`: built-in CLIs on PATH, then `[backends]` names.
+pub fn installed_backends(backends: &BTreeMap<String, BackendDef>) -> Vec<String> {
+    ["claude", "codex"]
+        .into_iter()
+        .filter(|b| config_validate::on_path(b))
+        .map(String::from)
+        .chain(backends.keys().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(lines: &[&str]) -> Prompter<io::Cursor<String>, Vec<u8>> {
+        Prompter::new(io::Cursor::new(lines.join("\n") + "\n"), Vec::new())
+    }
+
+    #[test]
+    fn scopes_from_layout() {
+        let tmp = tempfile::tempdir().unwrap();
+        for dir in ["context", "web", ".git", "target"] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        assert_eq!(candidate_scopes(tmp.path()), vec![("web".to_string(), "web/".to_string())]);
+        for dir in ["src/db", "src/api", "crates/cli"] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        assert_eq!(
+            candidate_scopes(tmp.path()),
+            vec![
+                ("api".to_string(), "src/api/".to_string()),
+                ("db".to_string(), "src/db/".to_string()),
+                ("cli".to_string(), "crates/cli/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn walks_roles_budget_and_scopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src/api")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("src/db")).unwrap();
+        let mut prompter = answers(&[
+            "shop",   // name
+            "",       // stack
+            "",       // protocol backend: codex, the only one installed
+            "",       // model: o3
+            "claude", // orchestrating
+            "",       // model kept: sonnet
+            "", "", "", "", // planning, adjusting
+            "lots",   // budget: re-asked
+            "2.5",
+            "",       // max agents
+            "",       // scope api
+            "n",      // scope db
+            "y",      // write
+        ]);
+        let config = ForgeConfig::scaffold("demo", "");
+        let config = run(&mut prompter, tmp.path(), config, &["codex".to_string()]).unwrap().unwrap();
+        assert_eq!(config.project.name, "shop");
+        let protocol = &config.forge.roles.protocol;
+        assert_eq!((protocol.backend.as_str(), protocol.model.as_str()), ("codex", "o3"));
+        assert_eq!(config.forge.roles.orchestrating.model, "sonnet");
+        assert_eq!(config.forge.roles.planning.backend, "codex");
+        assert_eq!(config.forge.budget_per_session, 2.5);
+        assert_eq!(config.scopes.keys().collect::<Vec<_>>(), ["api"]);
+        assert_eq!(config.scopes["api"].owns, ["src/api/"]);
+        let shown = String::from_utf8(prompter.output).unwrap();
+        assert!(shown.contains("'lots' is not a number."), "{shown}");
+    }
+
+    #[test]
+    fn declining_writes_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut prompter = answers(&["", "", "", "", "", "", "", "", "", "", "", "", "n"]);
+        let config = run(&mut prompter, tmp.path(), ForgeConfig::scaffold("demo", ""), &[]).unwrap();
+        assert!(config.is_none());
+    }
+}
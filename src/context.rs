@@ -1,8 +1,11 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 
-/// The five context categories.
-const CATEGORIES: &[&str] = &["decisions", "gotchas", "patterns", "poc", "references"];
+use crate::context_meta::{self, Frontmatter};
+use crate::features::{FeatureList, FeatureStatus};
+
+/// Categories every project has; `[context] categories` in forge.toml adds more.
+pub const DEFAULT_CATEGORIES: &[&str] = &["decisions", "gotchas", "patterns", "poc", "references"];
 
 #[derive(Debug, thiserror::Error)]
 pub enum ContextError {
@@ -10,6 +13,14 @@ pub enum ContextError {
     Io(#[from] std::io::Error),
     #[error("unknown context category: {0}")]
     UnknownCategory(String),
+    #[error("invalid context slug '{0}' (use letters, digits, '-' and '_')")]
+    InvalidSlug(String),
+    #[error("no context entry {0}")]
+    NotFound(String),
+    #[error("invalid frontmatter: {0}")]
+    InvalidFrontmatter(String),
+    #[error("context entry {0} already exists")]
+    AlreadyExists(String),
 }
 
 /// A single context entry (one markdown file).
@@ -18,23 +29,127 @@ pub struct ContextEntry {
     pub category: String,
     pub slug: String,
     pub path: PathBuf,
+    /// Empty when the entry has no frontmatter or it doesn't parse.
+    pub meta: Frontmatter,
+}
+
+/// One ranked match from [`ContextManager::search`].
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub category: String,
+    pub slug: String,
+    pub score: f64,
+    /// The best-matching line, trimmed to one line of output.
+    pub snippet: String,
+}
+
+/// Two entries in one category that say nearly the same thing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicatePair {
+    pub category: String,
+    /// The entry to keep: the longer one, so merging loses the least.
+    pub keep: String,
+    pub remove: String,
+    /// Jaccard similarity of word 3-shingles, 0.0–1.0.
+    pub similarity: f64,
+}
+
+/// How one entry points at another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Related,
+    Supersedes,
+}
+
+impl LinkKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkKind::Related => "related",
+            LinkKind::Supersedes => "supersedes",
+        }
+    }
+}
+
+/// A frontmatter link between two entries, both as `category/slug`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct Link {
+    pub from: String,
+    pub to: String,
+    pub kind: LinkKind,
+    /// `to` doesn't exist (renamed, deleted, or a typo).
+    pub dangling: bool,
+}
+
+/// Graphviz source for `forge context graph --dot`. Entries are grouped by
+/// category; missing targets are drawn dashed.
+pub fn links_to_dot(links: &[Link]) -> String {
+    let mut out = String::from("digraph context {\n    rankdir=LR;\n    node [shape=box];\n");
+    for link in links {
+        let style = match link.kind {
+            LinkKind::Related => "",
+            LinkKind::Supersedes => ", style=bold",
+        };
+        if link.dangling {
+            out.push_str(&format!("    \"{}\" [style=dashed];\n", link.to));
+        }
+        out.push_str(&format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"{style}];\n",
+            link.from,
+            link.to,
+            link.kind.as_str()
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// An earlier version of an entry, saved when it was overwritten or removed.
+#[derive(Debug, Clone)]
+pub struct Revision {
+    /// File stem under `.history/`, a local timestamp like `20260301T142210.123`.
+    pub id: String,
+    pub path: PathBuf,
 }
 
 /// Manages the context/ directory.
 pub struct ContextManager {
     root: PathBuf,
+    categories: Vec<String>,
 }
 
 impl ContextManager {
+    /// Categories come from the project's forge.toml, or the defaults without one.
     pub fn new(project_dir: &Path) -> Self {
+        let categories = crate::config::ForgeConfig::load(project_dir)
+            .map(|c| c.context.categories())
+            .unwrap_or_else(|_| DEFAULT_CATEGORIES.iter().map(|c| c.to_string()).collect());
         Self {
             root: project_dir.join("context"),
+            categories,
+        }
+    }
+
+    /// The defaults, then any from forge.toml.
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    pub fn is_category(&self, category: &str) -> bool {
+        self.categories.iter().any(|c| c == category)
+    }
+
+    fn validate_category(&self, category: &str) -> Result<(), ContextError> {
+        if self.is_category(category) {
+            Ok(())
+        } else {
+            Err(ContextError::UnknownCategory(category.into()))
         }
     }
 
     /// Create context/ with all subdirectories.
     pub fn init(&self) -> Result<(), ContextError> {
-        for cat in CATEGORIES {
+        for cat in &self.categories {
             std::fs::create_dir_all(self.root.join(cat))?;
         }
         Ok(())
@@ -43,7 +158,7 @@ impl ContextManager {
     /// List all entries across all categories.
     pub fn list_all(&self) -> Result<Vec<ContextEntry>, ContextError> {
         let mut entries = Vec::new();
-        for cat in CATEGORIES {
+        for cat in &self.categories {
             entries.extend(self.list_category(cat)?);
         }
         Ok(entries)
@@ -51,7 +166,7 @@ impl ContextManager {
 
     /// List entries in a single category.
     pub fn list_category(&self, category: &str) -> Result<Vec<ContextEntry>, ContextError> {
-        validate_category(category)?;
+        self.validate_category(category)?;
         let dir = self.root.join(category);
         if !dir.exists() {
             return Ok(Vec::new());
@@ -67,10 +182,15 @@ impl ContextManager {
                     .unwrap_or_default()
                     .to_string_lossy()
                     .into_owned();
+                let meta = std::fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|c| context_meta::parse_entry(&c).ok().map(|(meta, _)| meta))
+                    .unwrap_or_default();
                 entries.push(ContextEntry {
                     category: category.into(),
                     slug,
                     path,
+                    meta,
                 });
             }
         }
@@ -80,31 +200,340 @@ impl ContextManager {
 
     /// Read the content of a context entry.
     pub fn read_entry(&self, category: &str, slug: &str) -> Result<String, ContextError> {
-        validate_category(category)?;
+        self.validate_category(category)?;
         let path = self.root.join(category).join(format!("{slug}.md"));
         let content = std::fs::read_to_string(&path)?;
         Ok(content)
     }
 
-    /// Write a context entry. Overwrites if exists.
+    /// Write a context entry. Overwrites if exists. Frontmatter, if any,
+    /// must follow the schema in [`Frontmatter`].
     pub fn write_entry(
         &self,
         category: &str,
         slug: &str,
         content: &str,
     ) -> Result<PathBuf, ContextError> {
-        validate_category(category)?;
+        self.validate_category(category)?;
+        validate_slug(slug)?;
+        let (meta, _) = context_meta::parse_entry(content)?;
+        for link in meta.supersedes.iter().chain(&meta.related) {
+            let cat = link.split_once('/').map_or("", |(cat, _)| cat);
+            if !self.is_category(cat) {
+                return Err(ContextError::InvalidFrontmatter(format!("`{link}` is in unknown category `{cat}`")));
+            }
+        }
         let dir = self.root.join(category);
         std::fs::create_dir_all(&dir)?;
         let path = dir.join(format!("{slug}.md"));
+        if let Ok(previous) = std::fs::read_to_string(&path)
+            && previous != content
+        {
+            self.save_revision(category, slug, &previous)?;
+        }
         std::fs::write(&path, content)?;
         Ok(path)
     }
 
+    /// Write an entry with `meta` as its frontmatter. Fields `meta` leaves
+    /// unset are taken from frontmatter already in `content`; `created_at`
+    /// defaults to today.
+    pub fn write_entry_with_meta(
+        &self,
+        category: &str,
+        slug: &str,
+        mut meta: Frontmatter,
+        content: &str,
+    ) -> Result<PathBuf, ContextError> {
+        let (existing, body) = context_meta::parse_entry(content)?;
+        meta.merge_defaults(existing);
+        meta.created_at
+            .get_or_insert_with(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+        self.write_entry(category, slug, &format!("{}{body}", meta.render()))
+    }
+
+    /// Delete a context entry.
+    pub fn remove_entry(&self, category: &str, slug: &str) -> Result<(), ContextError> {
+        self.validate_category(category)?;
+        validate_slug(slug)?;
+        let path = self.root.join(category).join(format!("{slug}.md"));
+        if let Ok(previous) = std::fs::read_to_string(&path) {
+            self.save_revision(category, slug, &previous)?;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(ContextError::NotFound(format!("{category}/{slug}")))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Rank entries against a free-text query. Every query term must appear
+    /// somewhere (slug, tags or body); hits in the slug, tags and headings
+    /// weigh more than body text, and the exact phrase earns a bonus.
+    pub fn search(&self, query: &str, category: Option<&str>) -> Result<Vec<SearchHit>, ContextError> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|t| t.to_lowercase())
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let phrase = terms.join(" ");
+        let entries = match category {
+            Some(cat) => self.list_category(cat)?,
+            None => self.list_all()?,
+        };
+
+        let mut hits = Vec::new();
+        for entry in entries {
+            let Ok(content) = std::fs::read_to_string(&entry.path) else {
+                continue;
+            };
+            let (front, body) = context_meta::split(&content);
+            let tags = front
+                .and_then(|f| Frontmatter::parse(f).ok())
+                .map(|m| m.tags.join(" "))
+                .unwrap_or_default();
+            let slug = entry.slug.replace(['-', '_'], " ").to_lowercase();
+            let tags = tags.to_lowercase();
+            let body_lower = body.to_lowercase();
+
+            let mut score = 0.0;
+            let mut all_found = true;
+            for term in &terms {
+                let in_slug = slug.contains(term.as_str());
+                let in_tags = tags.contains(term.as_str());
+                let in_body = body_lower.matches(term.as_str()).count();
+                if !in_slug && !in_tags && in_body == 0 {
+                    all_found = false;
+                    break;
+                }
+                let in_headings = body_lower
+                    .lines()
+                    .filter(|l| l.starts_with('#') && l.contains(term.as_str()))
+                    .count();
+                score += 3.0 * f64::from(u8::from(in_slug))
+                    + 3.0 * f64::from(u8::from(in_tags))
+                    + 2.0 * in_headings as f64
+                    // Diminishing returns so long entries don't win on bulk
+                    + (1.0 + in_body as f64).ln();
+            }
+            if !all_found {
+                continue;
+            }
+            if terms.len() > 1 && (body_lower.contains(&phrase) || slug.contains(&phrase)) {
+                score += 5.0;
+            }
+            hits.push(SearchHit {
+                snippet: best_line(body, &terms, &phrase),
+                category: entry.category,
+                slug: entry.slug,
+                score,
+            });
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.slug.cmp(&b.slug)));
+        Ok(hits)
+    }
+
+    /// Pairs of entries in the same category whose bodies overlap at least
+    /// `threshold` (Jaccard over word 3-shingles), most similar first.
+    pub fn near_duplicates(&self, threshold: f64) -> Result<Vec<DuplicatePair>, ContextError> {
+        let mut pairs = Vec::new();
+        for cat in &self.categories {
+            let entries: Vec<(String, usize, HashSet<String>)> = self
+                .list_category(cat)?
+                .into_iter()
+                .filter_map(|e| {
+                    let content = std::fs::read_to_string(&e.path).ok()?;
+                    let body = context_meta::split(&content).1;
+                    Some((e.slug, body.len(), shingles(body)))
+                })
+                .collect();
+            for (i, (slug_a, len_a, sh_a)) in entries.iter().enumerate() {
+                for (slug_b, len_b, sh_b) in &entries[i + 1..] {
+                    let union = sh_a.union(sh_b).count();
+                    if union == 0 {
+                        continue;
+                    }
+                    let similarity = sh_a.intersection(sh_b).count() as f64 / union as f64;
+                    if similarity < threshold {
+                        continue;
+                    }
+                    let (keep, remove) = if len_b > len_a { (slug_b, slug_a) } else { (slug_a, slug_b) };
+                    pairs.push(DuplicatePair {
+                        category: (*cat).to_string(),
+                        keep: keep.clone(),
+                        remove: remove.clone(),
+                        similarity,
+                    });
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        Ok(pairs)
+    }
+
+    /// Fold `remove` into `keep`: lines only `remove` has are appended under
+    /// a note, then `remove` is deleted.
+    pub fn merge_entries(&self, category: &str, keep: &str, remove: &str) -> Result<(), ContextError> {
+        let kept = self.read_entry(category, keep)?;
+        let removed = self.read_entry(category, remove)?;
+        let (mut meta, kept_body) = context_meta::parse_entry(&kept)?;
+        let (removed_meta, removed_body) = context_meta::parse_entry(&removed)?;
+        let existing: HashSet<&str> = kept_body.lines().map(str::trim).collect();
+        let extra: Vec<&str> = removed_body
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !existing.contains(l.trim()))
+            .collect();
+        let mut body = kept_body.trim_end().to_string();
+        if !extra.is_empty() {
+            body = format!("{body}\n\n<!-- merged from {category}/{remove} -->\n{}", extra.join("\n"));
+        }
+        // Keep the removed entry's tags and features; the kept one supersedes it
+        meta.merge_defaults(removed_meta);
+        let removed_ref = format!("{category}/{remove}");
+        if !meta.supersedes.contains(&removed_ref) {
+            meta.supersedes.push(removed_ref);
+        }
+        self.write_entry(category, keep, &format!("{}{body}\n", meta.render()))?;
+        self.remove_entry(category, remove)
+    }
+
+    /// Move `from_category/slug` to `to_category` (as `to_slug`, default the
+    /// same slug), pointing every other entry's `related` and `supersedes`
+    /// links at the new place. Returns the entries whose links were rewritten;
+    /// features.json hints are the caller's to update.
+    pub fn promote(
+        &self,
+        from_category: &str,
+        slug: &str,
+        to_category: &str,
+        to_slug: Option<&str>,
+    ) -> Result<Vec<String>, ContextError> {
+        let to_slug = to_slug.unwrap_or(slug);
+        let (old, new) = (format!("{from_category}/{slug}"), format!("{to_category}/{to_slug}"));
+        let content = self.read_entry(from_category, slug)?;
+        self.validate_category(to_category)?;
+        validate_slug(to_slug)?;
+        if self.root.join(to_category).join(format!("{to_slug}.md")).exists() {
+            return Err(ContextError::AlreadyExists(new));
+        }
+        self.write_entry(to_category, to_slug, &content)?;
+        self.remove_entry(from_category, slug)?;
+
+        let mut rewritten = Vec::new();
+        for entry in self.list_all()? {
+            if !entry.meta.related.contains(&old) && !entry.meta.supersedes.contains(&old) {
+                continue;
+            }
+            let content = std::fs::read_to_string(&entry.path)?;
+            let (mut meta, body) = context_meta::parse_entry(&content)?;
+            for link in meta.related.iter_mut().chain(meta.supersedes.iter_mut()) {
+                if *link == old {
+                    link.clone_from(&new);
+                }
+            }
+            self.write_entry(&entry.category, &entry.slug, &format!("{}{body}", meta.render()))?;
+            rewritten.push(format!("{}/{}", entry.category, entry.slug));
+        }
+        Ok(rewritten)
+    }
+
+    /// Entries whose `expires` date has passed or whose `valid_until_feature`
+    /// is done, each with the reason.
+    pub fn expired_entries(&self) -> Result<Vec<(ContextEntry, String)>, ContextError> {
+        let project_dir = self.root.parent().unwrap_or(&self.root);
+        let done: HashSet<String> = FeatureList::load(project_dir)
+            .map(|list| {
+                list.features
+                    .into_iter()
+                    .filter(|f| f.status == FeatureStatus::Done)
+                    .map(|f| f.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let today = chrono::Local::now().date_naive();
+        Ok(self
+            .list_all()?
+            .into_iter()
+            .filter_map(|e| {
+                let reason = e.meta.expiry(today, |id| done.contains(id))?;
+                Some((e, reason))
+            })
+            .collect())
+    }
+
+    /// Every `related` and `supersedes` link, sorted.
+    pub fn links(&self) -> Result<Vec<Link>, ContextError> {
+        let entries = self.list_all()?;
+        let ids: HashSet<String> = entries.iter().map(|e| format!("{}/{}", e.category, e.slug)).collect();
+        let mut links = Vec::new();
+        for entry in &entries {
+            let from = format!("{}/{}", entry.category, entry.slug);
+            let typed = entry
+                .meta
+                .related
+                .iter()
+                .map(|to| (to, LinkKind::Related))
+                .chain(entry.meta.supersedes.iter().map(|to| (to, LinkKind::Supersedes)));
+            for (to, kind) in typed {
+                links.push(Link {
+                    from: from.clone(),
+                    to: to.clone(),
+                    kind,
+                    dangling: !ids.contains(to),
+                });
+            }
+        }
+        links.sort();
+        Ok(links)
+    }
+
+    fn history_dir(&self, category: &str, slug: &str) -> PathBuf {
+        self.root.join(".history").join(category).join(slug)
+    }
+
+    fn save_revision(&self, category: &str, slug: &str, content: &str) -> Result<(), ContextError> {
+        let dir = self.history_dir(category, slug);
+        std::fs::create_dir_all(&dir)?;
+        let stamp = chrono::Local::now().format("%Y%m%dT%H%M%S%.3f").to_string();
+        let mut path = dir.join(format!("{stamp}.md"));
+        // Two writes in the same millisecond keep both versions
+        let mut n = 1;
+        while path.exists() {
+            path = dir.join(format!("{stamp}-{n}.md"));
+            n += 1;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Saved versions of an entry, newest first.
+    pub fn history(&self, category: &str, slug: &str) -> Result<Vec<Revision>, ContextError> {
+        self.validate_category(category)?;
+        validate_slug(slug)?;
+        let dir = self.history_dir(category, slug);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut revisions: Vec<Revision> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+            .map(|path| Revision {
+                id: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+                path,
+            })
+            .collect();
+        revisions.sort_by(|a, b| natural_key(&b.id).cmp(&natural_key(&a.id)));
+        Ok(revisions)
+    }
+
     /// Count entries per category.
     pub fn counts(&self) -> Result<BTreeMap<String, usize>, ContextError> {
         let mut map = BTreeMap::new();
-        for cat in CATEGORIES {
+        for cat in &self.categories {
             let count = self.list_category(cat)?.len();
             map.insert((*cat).to_string(), count);
         }
@@ -139,20 +568,48 @@ impl ContextManager {
 
     /// Generate context/INDEX.md — one-line-per-entry table of contents.
     /// Agents scan this (~1 token/entry) to decide what to read in full.
+    /// Expired entries are left out.
     pub fn generate_index(&self) -> Result<String, ContextError> {
         let mut index = String::from("# Context Index\n\n");
         let mut total = 0usize;
+        let superseded_by: BTreeMap<String, String> = self
+            .list_all()?
+            .into_iter()
+            .flat_map(|e| {
+                let by = format!("{}/{}", e.category, e.slug);
+                e.meta.supersedes.into_iter().map(move |old| (old, by.clone()))
+            })
+            .collect();
+        let expired: HashSet<String> = self
+            .expired_entries()?
+            .into_iter()
+            .map(|(e, _)| format!("{}/{}", e.category, e.slug))
+            .collect();
 
-        for cat in CATEGORIES {
-            let entries = self.list_category(cat)?;
+        for cat in &self.categories {
+            let entries: Vec<ContextEntry> = self
+                .list_category(cat)?
+                .into_iter()
+                .filter(|e| !expired.contains(&format!("{cat}/{}", e.slug)))
+                .collect();
             if entries.is_empty() {
                 continue;
             }
             let label = capitalize(cat);
             index.push_str(&format!("## {label} ({} entries)\n", entries.len()));
             for entry in &entries {
-                let summary = self.first_heading(&entry.path);
-                index.push_str(&format!("- {}: {}\n", entry.slug, summary));
+                let mut line = format!("- {}: {}", entry.slug, self.first_heading(&entry.path));
+                if !entry.meta.tags.is_empty() {
+                    line.push_str(&format!(" [{}]", entry.meta.tags.join(", ")));
+                }
+                if !entry.meta.scope.is_empty() {
+                    line.push_str(&format!(" (scope: {})", entry.meta.scope.join(", ")));
+                }
+                if let Some(by) = superseded_by.get(&format!("{cat}/{}", entry.slug)) {
+                    line.push_str(&format!(" (superseded by {by})"));
+                }
+                index.push_str(&line);
+                index.push('\n');
             }
             index.push('\n');
             total += entries.len();
@@ -165,10 +622,11 @@ impl ContextManager {
         Ok(index)
     }
 
-    /// Write INDEX.md to context/.
+    /// Write INDEX.md to context/. With no entries left, a stale index is removed.
     pub fn write_index(&self) -> Result<(), ContextError> {
         let index = self.generate_index()?;
         if index.is_empty() {
+            let _ = std::fs::remove_file(self.root.join("INDEX.md"));
             return Ok(());
         }
         std::fs::write(self.root.join("INDEX.md"), &index)?;
@@ -176,20 +634,13 @@ impl ContextManager {
     }
 
     /// Extract first heading or first non-empty line from a file.
-    fn first_heading(&self, path: &Path) -> String {
+    pub fn first_heading(&self, path: &Path) -> String {
         let content = match std::fs::read_to_string(path) {
             Ok(c) => c,
             Err(_) => return "(unreadable)".into(),
         };
         // Skip YAML frontmatter
-        let body = if content.starts_with("---") {
-            content
-                .splitn(3, "---")
-                .nth(2)
-                .unwrap_or(&content)
-        } else {
-            &content
-        };
+        let body = context_meta::split(&content).1;
         for line in body.lines() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
@@ -217,23 +668,65 @@ impl ContextManager {
         tags: &[&str],
         body: &str,
     ) -> Result<PathBuf, ContextError> {
-        let tags_str = tags
-            .iter()
-            .map(|t| format!("{t}"))
-            .collect::<Vec<_>>()
-            .join(", ");
-        let content = format!(
-            "---\nsource: {source_url}\ntags: [{tags_str}]\n---\n\n{body}"
-        );
-        self.write_entry("references", slug, &content)
+        let meta = Frontmatter {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            source: Some(source_url.to_string()),
+            ..Frontmatter::default()
+        };
+        self.write_entry_with_meta("references", slug, meta, &format!("\n{body}"))
+    }
+}
+
+/// Lowercased word 3-shingles; texts under three words use their words.
+fn shingles(text: &str) -> HashSet<String> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < 3 {
+        return words.into_iter().collect();
     }
+    words.windows(3).map(|w| w.join(" ")).collect()
 }
 
-fn validate_category(category: &str) -> Result<(), ContextError> {
-    if CATEGORIES.contains(&category) {
+/// The line with the phrase, else the one matching the most terms.
+fn best_line(body: &str, terms: &[String], phrase: &str) -> String {
+    // Reversed so ties go to the earliest line
+    let lines = body.lines().rev().map(str::trim).filter(|l| !l.is_empty());
+    let best = lines
+        .max_by_key(|line| {
+            let lower = line.to_lowercase();
+            let matched = terms.iter().filter(|t| lower.contains(t.as_str())).count();
+            (lower.contains(phrase), matched)
+        })
+        .unwrap_or("");
+    let line = best.trim_start_matches('#').trim();
+    match line.char_indices().nth(120) {
+        Some((cut, _)) => format!("{}...", &line[..cut]),
+        None => line.to_string(),
+    }
+}
+
+/// Slugs become file names, so keep them to one path component.
+fn validate_slug(slug: &str) -> Result<(), ContextError> {
+    let ok = !slug.is_empty()
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !slug.starts_with('.');
+    if ok {
         Ok(())
     } else {
-        Err(ContextError::UnknownCategory(category.into()))
+        Err(ContextError::InvalidSlug(slug.into()))
+    }
+}
+
+/// Sort key for revision ids so `…123-10` follows `…123-9`.
+fn natural_key(id: &str) -> (&str, u32) {
+    match id.split_once('-') {
+        Some((stamp, n)) => (stamp, n.parse().unwrap_or(0)),
+        None => (id, 0),
     }
 }
 
@@ -259,11 +752,35 @@ mod tests {
     #[test]
     fn init_creates_directories() {
         let (dir, _mgr) = setup();
-        for cat in CATEGORIES {
+        for cat in DEFAULT_CATEGORIES {
             assert!(dir.path().join("context").join(cat).is_dir());
         }
     }
 
+    #[test]
+    fn custom_categories_from_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("forge.toml"),
+            "[project]\nname = \"t\"\n\n[context]\ncategories = [\"benchmarks\", \"gotchas\", \"packages\"]\n",
+        )
+        .unwrap();
+        let mgr = ContextManager::new(dir.path());
+        assert_eq!(
+            mgr.categories(),
+            ["decisions", "gotchas", "patterns", "poc", "references", "benchmarks"]
+        );
+        mgr.init().unwrap();
+        assert!(dir.path().join("context/benchmarks").is_dir());
+        mgr.write_entry("benchmarks", "insert-1m", "---\nrelated: [benchmarks/baseline]\n---\n# Insert 1M rows\n")
+            .unwrap();
+        assert!(mgr.generate_index().unwrap().contains("## Benchmarks (1 entries)\n- insert-1m: Insert 1M rows\n"));
+        assert!(matches!(
+            mgr.write_entry("recipes", "x", "# X"),
+            Err(ContextError::UnknownCategory(_))
+        ));
+    }
+
     #[test]
     fn write_and_read_entry() {
         let (_dir, mgr) = setup();
@@ -434,4 +951,197 @@ mod tests {
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].slug, "d1");
     }
+
+    #[test]
+    fn remove_entry_and_reindex() {
+        let (dir, mgr) = setup();
+        mgr.write_entry("gotchas", "sqlx-nullable", "# Nullable columns").unwrap();
+        mgr.write_index().unwrap();
+        assert!(dir.path().join("context/INDEX.md").exists());
+
+        mgr.remove_entry("gotchas", "sqlx-nullable").unwrap();
+        let err = mgr.remove_entry("gotchas", "sqlx-nullable").unwrap_err();
+        assert!(matches!(err, ContextError::NotFound(e) if e == "gotchas/sqlx-nullable"));
+
+        mgr.write_index().unwrap();
+        assert!(!dir.path().join("context/INDEX.md").exists());
+    }
+
+    #[test]
+    fn slugs_stay_inside_category() {
+        let (_dir, mgr) = setup();
+        for bad in ["../escape", "a/b", "", ".hidden"] {
+            let result = mgr.write_entry("gotchas", bad, "x");
+            assert!(matches!(result, Err(ContextError::InvalidSlug(_))), "{bad}");
+        }
+        mgr.write_entry("gotchas", "v1.2_note", "x").unwrap();
+    }
+
+    #[test]
+    fn search_ranks_and_snippets() {
+        let (_dir, mgr) = setup();
+        mgr.write_entry(
+            "gotchas",
+            "pool-exhaustion",
+            "# Connection pool exhaustion\n\nTests hang when the connection pool is smaller than the worker count.",
+        )
+        .unwrap();
+        mgr.write_entry("decisions", "use-sqlx", "# Use sqlx\nOne connection per request; no pool needed yet.")
+            .unwrap();
+        mgr.write_reference("deadpool", "https://example.com", &["pool", "connection"], "Async object pools.")
+            .unwrap();
+        mgr.write_entry("patterns", "unrelated", "# Error types\nthiserror everywhere.").unwrap();
+
+        let hits = mgr.search("connection pool", None).unwrap();
+        let slugs: Vec<&str> = hits.iter().map(|h| h.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["pool-exhaustion", "deadpool", "use-sqlx"]);
+        assert_eq!(hits[0].snippet, "Connection pool exhaustion");
+        assert_eq!(hits[1].snippet, "Async object pools.");
+
+        let hits = mgr.search("POOL", Some("decisions")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(mgr.search("   ", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn promote_moves_entry_and_rewrites_links() {
+        let (_dir, mgr) = setup();
+        mgr.write_entry("gotchas", "retry-backoff", "---\ntags: [http]\n---\n# Retry with backoff\nJitter too.")
+            .unwrap();
+        mgr.write_entry("gotchas", "timeouts", "---\nrelated: [gotchas/retry-backoff, decisions/x]\n---\n# Timeouts")
+            .unwrap();
+        mgr.write_entry("decisions", "x", "# X").unwrap();
+        mgr.write_entry("patterns", "taken", "# Taken").unwrap();
+
+        let rewritten = mgr.promote("gotchas", "retry-backoff", "patterns", None).unwrap();
+        assert_eq!(rewritten, vec!["gotchas/timeouts"]);
+        assert!(mgr.read_entry("gotchas", "retry-backoff").is_err());
+        let moved = mgr.read_entry("patterns", "retry-backoff").unwrap();
+        assert!(moved.contains("tags: [http]") && moved.contains("# Retry with backoff"));
+        let timeouts = mgr.read_entry("gotchas", "timeouts").unwrap();
+        assert!(timeouts.contains("related: [patterns/retry-backoff, decisions/x]"), "{timeouts}");
+        assert!(timeouts.ends_with("# Timeouts"));
+        assert_eq!(mgr.history("gotchas", "retry-backoff").unwrap().len(), 1);
+
+        assert!(matches!(
+            mgr.promote("patterns", "retry-backoff", "patterns", Some("taken")),
+            Err(ContextError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            mgr.promote("patterns", "retry-backoff", "recipes", None),
+            Err(ContextError::UnknownCategory(_))
+        ));
+    }
+
+    #[test]
+    fn near_duplicates_and_merge() {
+        let (_dir, mgr) = setup();
+        mgr.write_entry(
+            "gotchas",
+            "sqlx-nullable",
+            "# sqlx nullable columns\nsqlx requires Option<T> for nullable columns or the query panics at runtime.\nRun cargo sqlx prepare after.",
+        )
+        .unwrap();
+        mgr.write_entry(
+            "gotchas",
+            "sqlx-null",
+            "# sqlx nullable columns\nsqlx requires Option<T> for nullable columns or the query panics at runtime.",
+        )
+        .unwrap();
+        mgr.write_entry("gotchas", "tokio", "# Blocking in async\nNever call std::thread::sleep in async code.")
+            .unwrap();
+        mgr.write_entry("decisions", "sqlx-null", "# sqlx nullable columns\nsqlx requires Option<T> for nullable columns.")
+            .unwrap();
+
+        let pairs = mgr.near_duplicates(0.6).unwrap();
+        assert_eq!(pairs.len(), 1, "{pairs:?}");
+        assert_eq!(pairs[0].keep, "sqlx-nullable");
+        assert_eq!(pairs[0].remove, "sqlx-null");
+
+        mgr.write_entry("gotchas", "sqlx-null", "# sqlx nullable columns\nAlso applies to LEFT JOIN columns.")
+            .unwrap();
+        mgr.merge_entries("gotchas", "sqlx-nullable", "sqlx-null").unwrap();
+        let merged = mgr.read_entry("gotchas", "sqlx-nullable").unwrap();
+        assert!(merged.ends_with("<!-- merged from gotchas/sqlx-null -->\nAlso applies to LEFT JOIN columns.\n"));
+        assert_eq!(merged.matches("# sqlx nullable columns").count(), 1);
+        assert!(mgr.read_entry("gotchas", "sqlx-null").is_err());
+    }
+
+    #[test]
+    fn frontmatter_validated_and_indexed() {
+        let (_dir, mgr) = setup();
+        let err = mgr.write_entry("gotchas", "bad", "---\nsupersedes: [nope]\n---\nx");
+        assert!(matches!(err, Err(ContextError::InvalidFrontmatter(_))));
+        let err = mgr.write_entry("gotchas", "bad", "---\nsupersedes: [recipes/x]\n---\nx");
+        assert!(matches!(err, Err(ContextError::InvalidFrontmatter(_))));
+
+        let meta = Frontmatter {
+            author: Some("agent-2".into()),
+            features: vec!["f003".into()],
+            tags: vec!["sqlx".into()],
+            supersedes: vec!["gotchas/sqlx-null".into()],
+            ..Frontmatter::default()
+        };
+        mgr.write_entry_with_meta("gotchas", "sqlx-nullable", meta, "---\ntags: [db]\n---\n# Nullable columns\n")
+            .unwrap();
+        mgr.write_entry("gotchas", "sqlx-null", "# Old nullable note").unwrap();
+
+        let entries = mgr.list_category("gotchas").unwrap();
+        let entry = entries.iter().find(|e| e.slug == "sqlx-nullable").unwrap();
+        assert_eq!(entry.meta.tags, vec!["sqlx", "db"]);
+        assert_eq!(entry.meta.author.as_deref(), Some("agent-2"));
+        assert!(entry.meta.created_at.is_some());
+        assert_eq!(mgr.first_heading(&entry.path), "Nullable columns");
+
+        let index = mgr.generate_index().unwrap();
+        assert!(index.contains("- sqlx-nullable: Nullable columns [sqlx, db]\n"), "{index}");
+        assert!(index.contains("- sqlx-null: Old nullable note (superseded by gotchas/sqlx-nullable)\n"));
+    }
+
+    #[test]
+    fn links_between_entries() {
+        let (_dir, mgr) = setup();
+        mgr.write_entry("gotchas", "head-tail", "---\nrelated: [decisions/use-vec, decisions/gone]\n---\n# Head/tail")
+            .unwrap();
+        mgr.write_entry("decisions", "use-vec", "---\nsupersedes: [decisions/ring-buffer]\n---\n# Use Vec")
+            .unwrap();
+
+        let links: Vec<(String, String, LinkKind, bool)> = mgr
+            .links()
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.from, l.to, l.kind, l.dangling))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("decisions/use-vec".into(), "decisions/ring-buffer".into(), LinkKind::Supersedes, true),
+                ("gotchas/head-tail".into(), "decisions/gone".into(), LinkKind::Related, true),
+                ("gotchas/head-tail".into(), "decisions/use-vec".into(), LinkKind::Related, false),
+            ]
+        );
+        let dot = links_to_dot(&mgr.links().unwrap());
+        assert!(dot.starts_with("digraph context {"));
+        assert!(dot.contains("    \"gotchas/head-tail\" -> \"decisions/use-vec\" [label=\"related\"];\n"));
+        assert!(dot.contains("    \"decisions/ring-buffer\" [style=dashed];\n"));
+    }
+
+    #[test]
+    fn overwrites_and_removals_keep_history() {
+        let (dir, mgr) = setup();
+        mgr.write_entry("decisions", "use-vec", "v1").unwrap();
+        mgr.write_entry("decisions", "use-vec", "v1").unwrap();
+        assert!(mgr.history("decisions", "use-vec").unwrap().is_empty(), "unchanged writes aren't revisions");
+
+        mgr.write_entry("decisions", "use-vec", "v2").unwrap();
+        mgr.write_entry("decisions", "use-vec", "v3").unwrap();
+        mgr.remove_entry("decisions", "use-vec").unwrap();
+        let history = mgr.history("decisions", "use-vec").unwrap();
+        let contents: Vec<String> = history.iter().map(|r| std::fs::read_to_string(&r.path).unwrap()).collect();
+        assert_eq!(contents, vec!["v3", "v2", "v1"]);
+
+        // History lives outside the category dirs, so it never shows up as entries
+        assert!(dir.path().join("context/.history/decisions/use-vec").is_dir());
+        assert!(mgr.list_all().unwrap().is_empty());
+    }
 }
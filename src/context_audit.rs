@@ -0,0 +1,350 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+use crate::context::{ContextEntry, ContextError, ContextManager};
+use crate::git;
+
+/// Backticked or bare relative paths with an extension, e.g. `src/db/pool.rs`.
+static PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[\s`(\[])((?:\.{0,2}/)?[A-Za-z0-9_.-]+(?:/[A-Za-z0-9_.-]+)+\.[A-Za-z0-9]{1,8})\b")
+        .expect("valid path regex")
+});
+/// `Type::method`, `module::func()` or `func()` in backticks.
+static SYMBOL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"`((?:[A-Za-z_][A-Za-z0-9_]*::)*[A-Za-z_][A-Za-z0-9_]*)(\(\))?`").expect("valid symbol regex")
+});
+static USE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(don'?t use|do not use|never use|avoid|use)\s+`?([A-Za-z0-9_:.+-]+)`?").expect("valid use regex")
+});
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    MissingPath { path: String },
+    MissingSymbol { symbol: String },
+    /// Older than `sessions` agent sessions, none of which mention it.
+    Unused { sessions: usize },
+    /// This decision says to use `term`, `other` says to avoid it.
+    Contradicts { other: String, term: String },
+    /// Past its `expires` date or `valid_until_feature`; already left out of
+    /// INDEX.md and packages, so it can be deleted.
+    Expired { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AuditFinding {
+    /// `category/slug`.
+    pub entry: String,
+    #[serde(flatten)]
+    pub finding: Finding,
+}
+
+impl fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.finding {
+            Finding::MissingPath { path } => write!(f, "{}: references missing file {path}", self.entry),
+            Finding::MissingSymbol { symbol } => write!(f, "{}: references unknown symbol {symbol}", self.entry),
+            Finding::Unused { sessions } => write!(
+                f,
+                "{}: not mentioned in the {sessions} sessions since it was written",
+                self.entry
+            ),
+            Finding::Contradicts { other, term } => {
+                write!(f, "{}: says use {term}, but {other} says avoid it", self.entry)
+            }
+            Finding::Expired { reason } => write!(f, "{}: {reason}", self.entry),
+        }
+    }
+}
+
+pub struct AuditOptions {
+    /// An entry is flagged as unused once this many sessions have run since
+    /// it was written without any of them mentioning it.
+    pub unused_after_sessions: usize,
+    /// Transcript files to check for mentions.
+    pub transcripts: Vec<PathBuf>,
+}
+
+fn entry_id(entry: &ContextEntry) -> String {
+    format!("{}/{}", entry.category, entry.slug)
+}
+
+fn missing_paths(project_dir: &Path, content: &str) -> BTreeSet<String> {
+    PATH_RE
+        .captures_iter(content)
+        .map(|c| c[1].trim_start_matches("./").to_string())
+        // URLs and references/ (gitignored clones) aren't repo files
+        .filter(|p| !p.contains("://") && !p.starts_with("references/") && !p.starts_with('/'))
+        .filter(|p| !project_dir.join(p).exists())
+        .collect()
+}
+
+/// Only qualified names and calls count; a bare backticked word is too often
+/// prose (`true`, `cargo`).
+fn missing_symbols(project_dir: &Path, content: &str) -> BTreeSet<String> {
+    let mut missing = BTreeSet::new();
+    for caps in SYMBOL_RE.captures_iter(content) {
+        let symbol = &caps[1];
+        if !symbol.contains("::") && caps.get(2).is_none() {
+            continue;
+        }
+        let name = symbol.rsplit("::").next().unwrap_or(symbol);
+        if !symbol_exists(project_dir, name) {
+            missing.insert(format!("{symbol}{}", caps.get(2).map_or("", |m| m.as_str())));
+        }
+    }
+    missing
+}
+
+/// Searches tracked files outside context/. Outside a git repo nothing can
+/// be checked, so everything is assumed to exist.
+fn symbol_exists(project_dir: &Path, name: &str) -> bool {
+    !git::is_git_repo(project_dir)
+        || git::run(project_dir, &["grep", "-q", "-w", "-F", name, "--", ".", ":!context"]).is_ok()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn unused(entry: &ContextEntry, transcripts: &[(SystemTime, String)], after: usize) -> Option<usize> {
+    let written = modified(&entry.path)?;
+    let since: Vec<&String> = transcripts
+        .iter()
+        .filter(|(at, _)| *at > written)
+        .map(|(_, text)| text)
+        .collect();
+    if since.len() < after {
+        return None;
+    }
+    let id = entry_id(entry);
+    let mentioned = since.iter().any(|text| text.contains(&id) || text.contains(&entry.slug));
+    (!mentioned).then_some(since.len())
+}
+
+/// (term, says_use) for each "use X" / "avoid X" statement.
+fn stances(content: &str) -> Vec<(String, bool)> {
+    USE_RE
+        .captures_iter(content)
+        .map(|c| {
+            let verb = c[1].to_lowercase();
+            let term = c[2].trim_end_matches(['.', ',']).to_lowercase();
+            (term, verb == "use")
+        })
+        .filter(|(term, _)| term.len() > 2 && !["the", "a", "an", "it", "this", "that"].contains(&term.as_str()))
+        .collect()
+}
+
+fn contradictions(decisions: &[(String, String)]) -> Vec<AuditFinding> {
+    let stances: Vec<(&String, Vec<(String, bool)>)> =
+        decisions.iter().map(|(id, content)| (id, stances(content))).collect();
+    let mut findings = Vec::new();
+    for (id, mine) in &stances {
+        for (other, theirs) in &stances {
+            if id == other {
+                continue;
+            }
+            for (term, uses) in mine {
+                if *uses && theirs.iter().any(|(t, u)| t == term && !*u) {
+                    findings.push(AuditFinding {
+                        entry: (*id).clone(),
+                        finding: Finding::Contradicts {
+                            other: (*other).clone(),
+                            term: term.clone(),
+                        },
+                    });
+                }
+            }
+        }
+    }
+    findings.dedup();
+    findings
+}
+
+pub fn audit(project_dir: &Path, options: &AuditOptions) -> Result<Vec<AuditFinding>, ContextError> {
+    let ctx = ContextManager::new(project_dir);
+    let transcripts: Vec<(SystemTime, String)> = options
+        .transcripts
+        .iter()
+        .filter_map(|p| Some((modified(p)?, std::fs::read_to_string(p).ok()?)))
+        .collect();
+
+    let mut findings = Vec::new();
+    let mut decisions = Vec::new();
+    for entry in ctx.list_all()? {
+        let content = std::fs::read_to_string(&entry.path)?;
+        let id = entry_id(&entry);
+        for path in missing_paths(project_dir, &content) {
+            findings.push(AuditFinding {
+                entry: id.clone(),
+                finding: Finding::MissingPath { path },
+            });
+        }
+        for symbol in missing_symbols(project_dir, &content) {
+            findings.push(AuditFinding {
+                entry: id.clone(),
+                finding: Finding::MissingSymbol { symbol },
+            });
+        }
+        if let Some(sessions) = unused(&entry, &transcripts, options.unused_after_sessions) {
+            findings.push(AuditFinding {
+                entry: id.clone(),
+                finding: Finding::Unused { sessions },
+            });
+        }
+        if entry.category == "decisions" {
+            decisions.push((id, content));
+        }
+    }
+    findings.extend(contradictions(&decisions));
+    for (entry, reason) in ctx.expired_entries()? {
+        findings.push(AuditFinding {
+            entry: entry_id(&entry),
+            finding: Finding::Expired { reason },
+        });
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    fn options(transcripts: Vec<PathBuf>) -> AuditOptions {
+        AuditOptions {
+            unused_after_sessions: 2,
+            transcripts,
+        }
+    }
+
+    #[test]
+    fn flags_missing_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("src/db")).unwrap();
+        fs::write(tmp.path().join("src/db/pool.rs"), "").unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry(
+            "gotchas",
+            "pool",
+            "See `src/db/pool.rs` and src/db/old_pool.rs, not https://example.com/a/b.html or references/repo/x.rs",
+        )
+        .unwrap();
+
+        let findings = audit(tmp.path(), &options(vec![])).unwrap();
+        assert_eq!(
+            findings,
+            vec![AuditFinding {
+                entry: "gotchas/pool".into(),
+                finding: Finding::MissingPath {
+                    path: "src/db/old_pool.rs".into()
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn flags_missing_symbols_in_git_repo() {
+        let tmp = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git").args(args).current_dir(tmp.path()).output().unwrap();
+        };
+        git(&["init", "-q"]);
+        fs::write(tmp.path().join("lib.rs"), "fn open_pool() {}\nstruct Pool;\n").unwrap();
+        git(&["add", "lib.rs"]);
+        ContextManager::new(tmp.path())
+            .write_entry("patterns", "pools", "Call `open_pool()` or `Pool::acquire`, never `close_pool()`. Plain `cargo` is fine.")
+            .unwrap();
+
+        let symbols: Vec<String> = audit(tmp.path(), &options(vec![]))
+            .unwrap()
+            .into_iter()
+            .filter_map(|f| match f.finding {
+                Finding::MissingSymbol { symbol } => Some(symbol),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(symbols, vec!["Pool::acquire", "close_pool()"]);
+    }
+
+    #[test]
+    fn flags_unused_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("gotchas", "ignored", "x").unwrap();
+        ctx.write_entry("gotchas", "used", "y").unwrap();
+        let old = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        for slug in ["ignored", "used"] {
+            fs::File::options()
+                .write(true)
+                .open(tmp.path().join(format!("context/gotchas/{slug}.md")))
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+        let t1 = tmp.path().join("s1.jsonl");
+        let t2 = tmp.path().join("s2.jsonl");
+        fs::write(&t1, "read context/gotchas/used.md").unwrap();
+        fs::write(&t2, "nothing").unwrap();
+
+        let findings = audit(tmp.path(), &options(vec![t1.clone(), t2.clone()])).unwrap();
+        assert_eq!(
+            findings,
+            vec![AuditFinding {
+                entry: "gotchas/ignored".into(),
+                finding: Finding::Unused { sessions: 2 },
+            }]
+        );
+        // Too few sessions since: not flagged yet
+        assert!(audit(tmp.path(), &options(vec![t1])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn flags_contradicting_decisions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("decisions", "orm", "# ORM\nUse `sqlx` for queries.").unwrap();
+        ctx.write_entry("decisions", "raw-sql", "# Raw SQL\nAvoid sqlx; use the driver directly.")
+            .unwrap();
+
+        let findings = audit(tmp.path(), &options(vec![])).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].to_string(),
+            "decisions/orm: says use sqlx, but decisions/raw-sql says avoid it"
+        );
+    }
+
+    #[test]
+    fn flags_expired_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join("features.json"),
+            r#"{"features":[{"id":"f050","type":"implement","scope":"a","description":"x","verify":"true","status":"done"}]}"#,
+        )
+        .unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("gotchas", "old-api", "---\nexpires: 2020-01-01\n---\n# Old API").unwrap();
+        ctx.write_entry("gotchas", "workaround", "---\nvalid_until_feature: f050\n---\n# Workaround").unwrap();
+        ctx.write_entry("gotchas", "current", "---\nexpires: 2999-01-01\n---\n# Current").unwrap();
+
+        let findings: Vec<String> = audit(tmp.path(), &options(vec![]))
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            findings,
+            vec!["gotchas/old-api: expired 2020-01-01", "gotchas/workaround: f050 is done"]
+        );
+        let index = ctx.generate_index().unwrap();
+        assert!(index.contains("- current: Current"));
+        assert!(!index.contains("old-api") && !index.contains("workaround"), "{index}");
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+/// Rough token count: ~4 characters per token for English and code. Good
+/// enough to keep a package inside a budget without a tokenizer per backend.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// A section that didn't fit the budget whole.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CutSection {
+    pub heading: String,
+    pub tokens: usize,
+    /// Kept in part rather than dropped.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetReport {
+    pub budget: usize,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+    pub cut: Vec<CutSection>,
+}
+
+impl BudgetReport {
+    /// "Gotchas (truncated), Scope Files" for prompts and logs.
+    pub fn cut_summary(&self) -> String {
+        self.cut
+            .iter()
+            .map(|c| {
+                if c.truncated {
+                    format!("{} (truncated)", c.heading)
+                } else {
+                    c.heading.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// How much each package section is worth when space runs out. Unknown
+/// sections sit in the middle; relevance to the feature moves them up.
+fn heading_weight(heading: &str) -> f64 {
+    match heading {
+        "Previous Attempts" => 5.0,
+        "Relevant Context" | "Linked Context" => 4.0,
+        "Dependencies" => 3.5,
+        "Decisions & Patterns" | "POC Results" => 3.0,
+        "Scope Files" => 2.5,
+        "Gotchas" => 1.5,
+        _ => 2.0,
+    }
+}
+
+/// Fraction of the description's words (4+ letters) that appear in `text`.
+fn relevance(text: &str, description: &str) -> f64 {
+    let text = text.to_lowercase();
+    let words: Vec<String> = description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 4)
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return 0.0;
+    }
+    words.iter().filter(|w| text.contains(w.as_str())).count() as f64 / words.len() as f64
+}
+
+struct Section<'a> {
+    heading: &'a str,
+    text: &'a str,
+}
+
+/// Split on `## ` headings. The preamble (title, description, scope) comes
+/// back with an empty heading.
+fn sections(package: &str) -> Vec<Section<'_>> {
+    let mut starts: Vec<usize> = package
+        .match_indices("\n## ")
+        .map(|(i, _)| i + 1)
+        .collect();
+    if package.starts_with("## ") {
+        starts.insert(0, 0);
+    }
+    let mut out = Vec::new();
+    let first = starts.first().copied().unwrap_or(package.len());
+    if first > 0 {
+        out.push(Section {
+            heading: "",
+            text: &package[..first],
+        });
+    }
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(package.len());
+        let text = &package[start..end];
+        let heading = text.lines().next().unwrap_or("").trim_start_matches('#').trim();
+        out.push(Section { heading, text });
+    }
+    out
+}
+
+/// The leading lines of `text` that fit in `tokens`.
+fn truncate_lines(text: &str, tokens: usize) -> &str {
+    let mut end = 0;
+    for line in text.split_inclusive('\n') {
+        if estimate_tokens(&text[..end + line.len()]) > tokens {
+            break;
+        }
+        end += line.len();
+    }
+    &text[..end]
+}
+
+/// A truncated section is only worth keeping if this much of it fits.
+const MIN_PARTIAL_TOKENS: usize = 200;
+
+/// Fit a context package into `budget` tokens. The preamble is always kept;
+/// other sections are taken whole by score (heading weight plus overlap with
+/// the feature description), and the first that doesn't fit is truncated if
+/// enough room is left. Sections keep their original order. Returns the
+/// package unchanged, and no report, when it already fits.
+pub fn fit_package(package: &str, description: &str, budget: usize) -> (String, Option<BudgetReport>) {
+    let tokens_before = estimate_tokens(package);
+    if tokens_before <= budget {
+        return (package.to_string(), None);
+    }
+    let sections = sections(package);
+    let mut order: Vec<usize> = (0..sections.len()).filter(|&i| !sections[i].heading.is_empty()).collect();
+    let score = |s: &Section| heading_weight(s.heading) + 2.0 * relevance(s.text, description);
+    order.sort_by(|&a, &b| score(&sections[b]).total_cmp(&score(&sections[a])));
+
+    let mut kept: Vec<Option<&str>> = sections
+        .iter()
+        .map(|s| s.heading.is_empty().then_some(s.text))
+        .collect();
+    let mut used: usize = kept.iter().flatten().map(|t| estimate_tokens(t)).sum();
+    let mut cut = Vec::new();
+    for i in order {
+        let section = &sections[i];
+        let tokens = estimate_tokens(section.text);
+        let left = budget.saturating_sub(used);
+        if tokens <= left {
+            kept[i] = Some(section.text);
+            used += tokens;
+            continue;
+        }
+        let partial = truncate_lines(section.text, left.saturating_sub(10));
+        let truncated = left >= MIN_PARTIAL_TOKENS && partial.lines().count() > 1;
+        if truncated {
+            kept[i] = Some(partial);
+            used += estimate_tokens(partial);
+        }
+        cut.push(CutSection {
+            heading: section.heading.to_string(),
+            tokens,
+            truncated,
+        });
+    }
+
+    let mut out = String::new();
+    for (i, text) in kept.iter().enumerate() {
+        let Some(text) = text else { continue };
+        out.push_str(text);
+        if cut.iter().any(|c| c.truncated && c.heading == sections[i].heading) {
+            out.push_str("\n_(truncated to fit the context budget)_\n\n");
+        }
+    }
+    let report = BudgetReport {
+        budget,
+        tokens_before,
+        tokens_after: estimate_tokens(&out),
+        cut,
+    };
+    (out, Some(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package() -> String {
+        let mut p = String::from("# Context Package: f001\n\n**Description**: add connection pool retries\n");
+        p.push_str("\n## Scope Files\n");
+        p.push_str(&"- src/unrelated.rs: big file map line\n".repeat(60));
+        p.push_str("\n## Relevant Context\n- **gotchas/pool**: connection pool retries need backoff\n");
+        p.push_str("\n## Gotchas\n");
+        p.push_str(&"- **misc**: something else entirely\n".repeat(40));
+        p
+    }
+
+    #[test]
+    fn fits_unchanged_under_budget() {
+        let (out, report) = fit_package("# small", "x", 100);
+        assert_eq!(out, "# small");
+        assert!(report.is_none());
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+    }
+
+    #[test]
+    fn drops_and_truncates_by_score() {
+        let package = package();
+        let (out, report) = fit_package(&package, "add connection pool retries", 450);
+        let report = report.unwrap();
+        assert!(report.tokens_after <= 450, "{report:?}");
+        assert!(out.starts_with("# Context Package: f001"));
+        assert!(out.contains("gotchas/pool"), "relevant context kept whole");
+        // Scope files outrank gotchas: truncated, while gotchas had no room left
+        assert_eq!(report.cut_summary(), "Scope Files (truncated), Gotchas");
+        assert!(out.find("## Scope Files").unwrap() < out.find("## Relevant Context").unwrap());
+        assert!(out.contains("_(truncated to fit the context budget)_"));
+        assert!(!out.contains("## Gotchas"));
+    }
+}
@@ -0,0 +1,270 @@
+use regex::Regex;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::LazyLock;
+
+use crate::context::{ContextError, ContextManager};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+    #[error("curl not found — install it to fetch references")]
+    CurlMissing,
+    #[error("fetching {0} failed: {1}")]
+    Request(String, String),
+    #[error("{0} has no readable text")]
+    Empty(String),
+    #[error("references/{0} already exists (use --force to replace it)")]
+    Exists(String),
+    #[error("context error: {0}")]
+    Context(#[from] ContextError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A page reduced to its title and readable markdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+fn re(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("static regex")
+}
+
+/// Elements that never hold the article text.
+static BOILERPLATE: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    ["script", "style", "noscript", "svg", "nav", "header", "footer", "aside", "form", "iframe"]
+        .iter()
+        .map(|tag| re(&format!(r"(?is)<{tag}\b.*?</{tag}\s*>")))
+        .collect()
+});
+static COMMENT: LazyLock<Regex> = LazyLock::new(|| re(r"(?s)<!--.*?-->"));
+static TITLE: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<title[^>]*>(.*?)</title\s*>"));
+static ARTICLE: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<article\b[^>]*>(.*)</article\s*>"));
+static MAIN: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<main\b[^>]*>(.*)</main\s*>"));
+static BODY: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<body\b[^>]*>(.*)</body\s*>"));
+static PRE: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<pre\b[^>]*>(.*?)</pre\s*>"));
+static HEADING: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<h([1-6])\b[^>]*>(.*?)</h[1-6]\s*>"));
+static LINK: LazyLock<Regex> = LazyLock::new(|| re(r#"(?is)<a\b[^>]*?href\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a\s*>"#));
+static CODE: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<code\b[^>]*>(.*?)</code\s*>"));
+static STRONG: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<(?:strong|b)\b[^>]*>(.*?)</(?:strong|b)\s*>"));
+static EM: LazyLock<Regex> = LazyLock::new(|| re(r"(?is)<(?:em|i)\b[^>]*>(.*?)</(?:em|i)\s*>"));
+static LIST_ITEM: LazyLock<Regex> = LazyLock::new(|| re(r"(?i)<li\b[^>]*>"));
+static BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| re(r"(?i)</?(?:p|div|section|ul|ol|table|tr|blockquote|dl|dd|dt|figure)\b[^>]*>|<br\s*/?>"));
+static WHITESPACE: LazyLock<Regex> = LazyLock::new(|| re(r"\s+"));
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| re("\u{0}[0-9]+\u{0}"));
+static TAG: LazyLock<Regex> = LazyLock::new(|| re(r"(?s)<[^>]*>"));
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| re(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);"));
+
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let decoded = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                match name {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "mdash" => Some('—'),
+                    "ndash" => Some('–'),
+                    "hellip" => Some('…'),
+                    _ => None,
+                }
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Tags stripped and whitespace collapsed, for text that sits on one line.
+/// Entities stay encoded so a decoded `&lt;` can't be mistaken for a tag.
+fn inline_text(html: &str) -> String {
+    TAG.replace_all(html, "").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Readability-style extraction: drop scripts, navigation and page chrome,
+/// keep the `<article>` (or `<main>`, or `<body>`), and turn what's left
+/// into markdown headings, lists, links, emphasis and code blocks.
+pub fn html_to_markdown(html: &str) -> Page {
+    let title = TITLE
+        .captures(html)
+        .map(|c| decode_entities(&inline_text(&c[1])))
+        .filter(|t| !t.is_empty());
+    let mut doc = COMMENT.replace_all(html, "").into_owned();
+    for re in BOILERPLATE.iter() {
+        doc = re.replace_all(&doc, "").into_owned();
+    }
+    let content = [&*ARTICLE, &*MAIN, &*BODY]
+        .iter()
+        .find_map(|re| re.captures(&doc).map(|c| c[1].to_string()))
+        .unwrap_or(doc);
+
+    // Code blocks keep their whitespace, so set them aside until the end
+    let mut blocks = Vec::new();
+    let content = PRE.replace_all(&content, |caps: &regex::Captures| {
+        let code = decode_entities(&TAG.replace_all(&caps[1], ""));
+        blocks.push(format!("```\n{}\n```\n", code.trim_matches('\n')));
+        format!("\u{0}{}\u{0}", blocks.len() - 1)
+    });
+    // Outside code blocks, source line breaks mean nothing
+    let content = WHITESPACE.replace_all(&content, " ");
+    let content = PLACEHOLDER.replace_all(&content, "\n$0\n");
+    let content = LINK.replace_all(&content, |caps: &regex::Captures| {
+        let text = inline_text(&caps[2]);
+        let href = &caps[1];
+        if href.starts_with("http://") || href.starts_with("https://") {
+            format!("[{text}]({href})")
+        } else {
+            text
+        }
+    });
+    let content = CODE.replace_all(&content, |caps: &regex::Captures| format!("`{}`", inline_text(&caps[1])));
+    let content = STRONG.replace_all(&content, |caps: &regex::Captures| format!("**{}**", inline_text(&caps[1])));
+    let content = EM.replace_all(&content, |caps: &regex::Captures| format!("_{}_", inline_text(&caps[1])));
+    let content = HEADING.replace_all(&content, |caps: &regex::Captures| {
+        let level: usize = caps[1].parse().unwrap_or(2);
+        format!("\n\n{} {}\n\n", "#".repeat(level), inline_text(&caps[2]))
+    });
+    let content = LIST_ITEM.replace_all(&content, "\n- ");
+    let content = BLOCK.replace_all(&content, "\n");
+    let content = decode_entities(&TAG.replace_all(&content, ""));
+
+    let mut markdown = String::new();
+    let mut blank = true;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(i) = line.strip_prefix('\u{0}').and_then(|l| l.strip_suffix('\u{0}')) {
+            if !blank {
+                markdown.push('\n');
+            }
+            markdown.push_str(&blocks[i.parse::<usize>().unwrap_or(0)]);
+            markdown.push('\n');
+            blank = true;
+        } else if line.is_empty() || line == "-" {
+            if !blank {
+                markdown.push('\n');
+                blank = true;
+            }
+        } else {
+            markdown.push_str(line);
+            markdown.push('\n');
+            blank = false;
+        }
+    }
+    Page {
+        title,
+        markdown: markdown.trim().to_string(),
+    }
+}
+
+/// A slug from a page title: lowercase words joined with `-`.
+pub fn slugify(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(8)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "reference".into() } else { slug }
+}
+
+fn download(url: &str) -> Result<String, FetchError> {
+    let output = Command::new("curl")
+        .args(["-sSL", "--fail", "--max-time", "30", "-A", "forge", url])
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => FetchError::CurlMissing,
+            _ => FetchError::Io(e),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(FetchError::Request(url.into(), stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Download `url` and store it as `references/<slug>`, the slug defaulting
+/// to one made from the page title.
+pub fn fetch_reference(
+    ctx: &ContextManager,
+    url: &str,
+    slug: Option<&str>,
+    tags: &[&str],
+    force: bool,
+) -> Result<PathBuf, FetchError> {
+    let html = download(url)?;
+    let page = html_to_markdown(&html);
+    if page.markdown.is_empty() {
+        return Err(FetchError::Empty(url.into()));
+    }
+    let slug = slug.map_or_else(|| slugify(page.title.as_deref().unwrap_or(url)), String::from);
+    if !force && ctx.read_entry("references", &slug).is_ok() {
+        return Err(FetchError::Exists(slug));
+    }
+    let body = match &page.title {
+        Some(title) if !page.markdown.starts_with("# ") => format!("# {title}\n\n{}", page.markdown),
+        _ => page.markdown,
+    };
+    Ok(ctx.write_reference(&slug, url, tags, &format!("{body}\n"))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!doctype html><html><head><title>Bf-Tree &amp; friends</title>
+<style>body { color: red }</style><script>var x = "<p>no</p>";</script></head>
+<body><nav><a href="/">Home</a></nav>
+<article>
+<h2 id="why">Why <em>mini-pages</em>?</h2>
+<p>Pages are <strong>variable</strong> length,
+   see <a href="https://example.com/paper">the paper</a> and <a href="/local">this</a>.</p>
+<ul><li>Circular buffer</li><li>Uses <code>Vec&lt;u8&gt;</code></li></ul>
+<pre><code>fn main() {
+    println!("hi");
+}</code></pre>
+</article>
+<footer>Copyright</footer></body></html>"#;
+
+    #[test]
+    fn extracts_article_as_markdown() {
+        let page = html_to_markdown(PAGE);
+        assert_eq!(page.title.as_deref(), Some("Bf-Tree & friends"));
+        assert_eq!(
+            page.markdown,
+            "## Why _mini-pages_?\n\nPages are **variable** length, see [the paper](https://example.com/paper) and this.\n\n- Circular buffer\n- Uses `Vec<u8>`\n\n```\nfn main() {\n    println!(\"hi\");\n}\n```"
+        );
+        assert_eq!(slugify("Bf-Tree & friends"), "bf-tree-friends");
+    }
+
+    #[test]
+    fn fetches_into_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("page.html");
+        std::fs::write(&page, PAGE).unwrap();
+        let url = format!("file://{}", page.display());
+        let ctx = ContextManager::new(dir.path());
+
+        fetch_reference(&ctx, &url, None, &["rust", "btree"], false).unwrap();
+        let entry = ctx.read_entry("references", "bf-tree-friends").unwrap();
+        assert!(entry.contains(&format!("source: {url}")));
+        assert!(entry.contains("tags: [rust, btree]"));
+        assert!(entry.contains("# Bf-Tree & friends\n\n## Why _mini-pages_?"));
+
+        assert!(matches!(
+            fetch_reference(&ctx, &url, Some("bf-tree-friends"), &[], false),
+            Err(FetchError::Exists(_))
+        ));
+        fetch_reference(&ctx, &url, Some("bf-tree-friends"), &[], true).unwrap();
+    }
+}
@@ -29,13 +29,17 @@ pub fn sync_context_flow(project_dir: &Path) {
     let _ = std::fs::write(forge_dir.join("requirements.txt"), REQUIREMENTS_TXT);
 }
 
-/// Run `cocoindex update` to refresh context packages.
+/// Refresh context packages: `cocoindex update` when it's installed (adds
+/// LLM-extracted file maps), otherwise the native builder in
+/// [`crate::context_package`].
 ///
-/// Returns `Ok(true)` if cocoindex ran successfully, `Ok(false)` if cocoindex
-/// is not available, and `Err` on execution failure (non-fatal).
+/// Returns `Ok(true)` if packages were written, `Ok(false)` if there was
+/// nothing to package, and `Err` on failure (non-fatal).
 pub fn refresh_context(project_dir: &Path) -> Result<bool, String> {
     if !cocoindex_available() {
-        return Ok(false);
+        return crate::context_package::build_packages(project_dir)
+            .map(|written| !written.is_empty())
+            .map_err(|e| format!("context package build failed: {e}"));
     }
 
     let flow_path = project_dir.join(".forge/context_flow.py");
@@ -117,10 +121,8 @@ mod tests {
 
     #[test]
     fn refresh_graceful_when_unavailable() {
-        // If cocoindex is not installed, refresh should return Ok(false)
-        // This test works regardless of whether cocoindex is installed:
-        // - If not installed: returns Ok(false)
-        // - If installed but no flow file: returns Err (which is also acceptable)
+        // Without cocoindex the native builder runs and, with no features.json,
+        // returns Ok(false). With cocoindex but no flow file it returns Err.
         let dir = tempfile::tempdir().unwrap();
         let result = refresh_context(dir.path());
         // Either Ok(false) if cocoindex not available, or Err if available but no flow file
@@ -0,0 +1,378 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::config::{EmbeddingProvider, EmbeddingsConfig, ForgeConfig, SecretRef};
+use crate::context::ContextManager;
+use crate::features::Feature;
+use crate::secrets;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IndexError {
+    #[error("${0} is not set (nor resolvable through [secrets]); it should hold the embeddings API key")]
+    MissingKey(String),
+    #[error("curl not found — install it to build the embeddings index")]
+    CurlMissing,
+    #[error("embeddings request failed: {0}")]
+    Request(String),
+    #[error("context error: {0}")]
+    Context(#[from] crate::context::ContextError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Turns text into vectors. One batch per call keeps reindexing to a
+/// single request.
+pub trait Embedder {
+    fn model(&self) -> &str;
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, IndexError>;
+}
+
+/// Calls the configured provider over HTTP via `curl`.
+pub struct HttpEmbedder {
+    config: EmbeddingsConfig,
+    /// `[secrets]`, consulted for `api_key_env` before the environment.
+    secrets: BTreeMap<String, SecretRef>,
+    /// Request bodies go through files here so large batches don't hit argv limits.
+    scratch_dir: PathBuf,
+}
+
+impl HttpEmbedder {
+    pub fn new(config: &EmbeddingsConfig, secrets: &BTreeMap<String, SecretRef>, project_dir: &Path) -> Self {
+        Self {
+            config: config.clone(),
+            secrets: secrets.clone(),
+            scratch_dir: project_dir.join(".forge"),
+        }
+    }
+
+    fn post(&self, url: &str, body: &Value, auth: Option<String>) -> Result<Value, IndexError> {
+        // Agents in parallel threads build prompts at the same time
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let scratch = self.scratch_dir.join(format!(
+            "embed-request-{}-{}.json",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&self.scratch_dir)?;
+        std::fs::write(&scratch, serde_json::to_vec(body)?)?;
+        // Headers come from stdin so the key never shows up in `ps`
+        let mut child = Command::new("curl")
+            .args(["-sS", "--fail-with-body", "-X", "POST", url])
+            .args(["-H", "Content-Type: application/json", "-H", "@-"])
+            .arg("--data-binary")
+            .arg(format!("@{}", scratch.display()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => IndexError::CurlMissing,
+                _ => IndexError::Io(e),
+            })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", auth.unwrap_or_default())?;
+        }
+        let output = child.wait_with_output()?;
+        let _ = std::fs::remove_file(&scratch);
+        if !output.status.success() {
+            let detail = [output.stderr, output.stdout]
+                .iter()
+                .map(|b| String::from_utf8_lossy(b).trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(": ");
+            return Err(IndexError::Request(detail));
+        }
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, IndexError> {
+        let (response, pointer) = match self.config.provider {
+            EmbeddingProvider::Openai => {
+                let key = secrets::env_or_secret(&self.secrets, &self.config.api_key_env)
+                    .ok_or_else(|| IndexError::MissingKey(self.config.api_key_env.clone()))?;
+                let base = self.config.endpoint.as_deref().unwrap_or("https://api.openai.com");
+                let url = format!("{}/v1/embeddings", base.trim_end_matches('/'));
+                let body = json!({ "model": self.config.model, "input": texts });
+                (self.post(&url, &body, Some(format!("Authorization: Bearer {key}")))?, "/data")
+            }
+            EmbeddingProvider::Ollama => {
+                let base = self.config.endpoint.as_deref().unwrap_or("http://localhost:11434");
+                let url = format!("{}/api/embed", base.trim_end_matches('/'));
+                let body = json!({ "model": self.config.model, "input": texts });
+                (self.post(&url, &body, None)?, "/embeddings")
+            }
+        };
+        let items = response
+            .pointer(pointer)
+            .and_then(Value::as_array)
+            .ok_or_else(|| IndexError::Request(format!("no {pointer} in response")))?;
+        let vectors: Vec<Vec<f32>> = items
+            .iter()
+            .map(|item| {
+                item.get("embedding")
+                    .unwrap_or(item)
+                    .as_array()
+                    .map(|v| v.iter().filter_map(Value::as_f64).map(|x| x as f32).collect())
+                    .unwrap_or_default()
+            })
+            .collect();
+        if vectors.len() != texts.len() {
+            return Err(IndexError::Request(format!(
+                "asked for {} embeddings, got {}",
+                texts.len(),
+                vectors.len()
+            )));
+        }
+        Ok(vectors)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexedEntry {
+    /// `category/slug`, as in `context_hints`.
+    pub entry: String,
+    /// Content hash; unchanged entries keep their vector on reindex.
+    pub hash: String,
+    pub vector: Vec<f32>,
+}
+
+/// `.forge/context-index.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContextIndex {
+    pub model: String,
+    pub entries: Vec<IndexedEntry>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReindexStats {
+    pub embedded: usize,
+    pub unchanged: usize,
+}
+
+fn index_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/context-index.json")
+}
+
+/// FNV-1a; stable across builds, unlike `DefaultHasher`.
+pub(crate) fn content_hash(bytes: impl AsRef<[u8]>) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in bytes.as_ref() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 { 0.0 } else { dot / denom }
+}
+
+impl ContextIndex {
+    pub fn load(project_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(index_path(project_dir)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self, project_dir: &Path) -> Result<(), IndexError> {
+        let path = index_path(project_dir);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The `k` entries closest to `query`, best first.
+    pub fn nearest(&self, query: &[f32], k: usize) -> Vec<(&str, f32)> {
+        let mut scored: Vec<(&str, f32)> = self
+            .entries
+            .iter()
+            .map(|e| (e.entry.as_str(), cosine(query, &e.vector)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(k);
+        scored
+    }
+}
+
+/// Bring the index in line with `context/`: embed new and edited entries,
+/// drop deleted ones. Switching models re-embeds everything.
+pub fn reindex(project_dir: &Path, embedder: &dyn Embedder) -> Result<ReindexStats, IndexError> {
+    let previous = ContextIndex::load(project_dir).filter(|i| i.model == embedder.model());
+    let ctx = ContextManager::new(project_dir);
+
+    let mut entries = Vec::new();
+    let mut pending = Vec::new();
+    for entry in ctx.list_all()? {
+        let content = std::fs::read_to_string(&entry.path)?;
+        let id = format!("{}/{}", entry.category, entry.slug);
+        let hash = content_hash(&content);
+        let reused = previous
+            .as_ref()
+            .and_then(|p| p.entries.iter().find(|e| e.entry == id && e.hash == hash));
+        match reused {
+            Some(existing) => entries.push(existing.clone()),
+            None => pending.push((id, hash, content)),
+        }
+    }
+
+    let stats = ReindexStats {
+        embedded: pending.len(),
+        unchanged: entries.len(),
+    };
+    if !pending.is_empty() {
+        let texts: Vec<String> = pending
+            .iter()
+            .map(|(id, _, content)| format!("{id}\n\n{content}"))
+            .collect();
+        let vectors = embedder.embed(&texts)?;
+        for ((entry, hash, _), vector) in pending.into_iter().zip(vectors) {
+            entries.push(IndexedEntry { entry, hash, vector });
+        }
+    }
+    entries.sort_by(|a, b| a.entry.cmp(&b.entry));
+
+    ContextIndex {
+        model: embedder.model().to_string(),
+        entries,
+    }
+    .save(project_dir)?;
+    Ok(stats)
+}
+
+/// Entries most similar to the feature, skipping ones it already hints at.
+pub fn similar_entries(
+    project_dir: &Path,
+    feature: &Feature,
+    embedder: &dyn Embedder,
+    k: usize,
+) -> Result<Vec<String>, IndexError> {
+    let Some(index) = ContextIndex::load(project_dir).filter(|i| i.model == embedder.model()) else {
+        return Ok(Vec::new());
+    };
+    let expired: Vec<String> = ContextManager::new(project_dir)
+        .expired_entries()?
+        .into_iter()
+        .map(|(e, _)| format!("{}/{}", e.category, e.slug))
+        .collect();
+    let query = format!("{} (scope: {})", feature.description, feature.scope);
+    let vector = embedder.embed(&[query])?.pop().unwrap_or_default();
+    Ok(index
+        .nearest(&vector, k + feature.context_hints.len() + expired.len())
+        .into_iter()
+        .map(|(entry, _)| entry.to_string())
+        .filter(|entry| !feature.context_hints.contains(entry) && !expired.contains(entry))
+        .take(k)
+        .collect())
+}
+
+/// The embedder from `[context.embeddings]`, if configured.
+pub fn configured_embedder(project_dir: &Path) -> Option<(HttpEmbedder, usize)> {
+    let config = ForgeConfig::load(project_dir).ok()?;
+    let embeddings = config.context.embeddings?;
+    Some((HttpEmbedder::new(&embeddings, &config.secrets, project_dir), embeddings.top_k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Counts a few keywords; enough to make "pool" texts land near each other.
+    struct FakeEmbedder {
+        calls: Cell<usize>,
+    }
+
+    impl Embedder for FakeEmbedder {
+        fn model(&self) -> &str {
+            "fake"
+        }
+        fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, IndexError> {
+            self.calls.set(self.calls.get() + texts.len());
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let t = t.to_lowercase();
+                    ["pool", "auth", "cache"]
+                        .iter()
+                        .map(|w| t.matches(w).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    fn feature(description: &str, hints: &[&str]) -> Feature {
+        serde_json::from_value(json!({
+            "id": "f1", "type": "implement", "scope": "db", "description": description,
+            "verify": "true", "context_hints": hints,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reindex_reuses_unchanged_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("gotchas", "pool-size", "connection pool sizing").unwrap();
+        ctx.write_entry("decisions", "auth", "auth tokens").unwrap();
+        let embedder = FakeEmbedder { calls: Cell::new(0) };
+
+        let stats = reindex(tmp.path(), &embedder).unwrap();
+        assert_eq!(stats, ReindexStats { embedded: 2, unchanged: 0 });
+
+        ctx.write_entry("decisions", "auth", "auth tokens, cache them").unwrap();
+        ctx.remove_entry("gotchas", "pool-size").unwrap();
+        let stats = reindex(tmp.path(), &embedder).unwrap();
+        assert_eq!(stats, ReindexStats { embedded: 1, unchanged: 0 });
+        assert_eq!(embedder.calls.get(), 3);
+
+        let index = ContextIndex::load(tmp.path()).unwrap();
+        assert_eq!(index.entries.len(), 1);
+        assert_eq!(reindex(tmp.path(), &embedder).unwrap().unchanged, 1);
+    }
+
+    #[test]
+    fn similar_entries_skip_hints() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("gotchas", "pool-size", "pool pool").unwrap();
+        ctx.write_entry("patterns", "pool-retry", "retry the pool").unwrap();
+        ctx.write_entry("decisions", "auth", "auth").unwrap();
+        let embedder = FakeEmbedder { calls: Cell::new(0) };
+        reindex(tmp.path(), &embedder).unwrap();
+
+        let f = feature("Size the connection pool", &["gotchas/pool-size"]);
+        let similar = similar_entries(tmp.path(), &f, &embedder, 1).unwrap();
+        assert_eq!(similar, vec!["patterns/pool-retry"]);
+
+        // No index yet for another model: nothing, and no request made
+        let other = tempfile::tempdir().unwrap();
+        assert!(similar_entries(other.path(), &f, &embedder, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn hash_and_cosine() {
+        assert_eq!(content_hash("abc"), content_hash("abc"));
+        assert_ne!(content_hash("abc"), content_hash("abd"));
+        assert!((cosine(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+}
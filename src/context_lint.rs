@@ -0,0 +1,184 @@
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+use crate::config::{ContextLintConfig, ForgeConfig};
+use crate::context::{ContextEntry, ContextError, ContextManager};
+use crate::context_meta::{self, Frontmatter};
+use crate::redact::Redactor;
+
+/// Paths that only exist on the machine that wrote the entry.
+static ABSOLUTE_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:^|[\s`(\["'=])((?:/(?:home|Users|root|tmp|private|var/folders)/|[A-Za-z]:\\(?:Users|Windows)\\)[^\s`)\]"']*)"#)
+        .expect("valid absolute path regex")
+});
+static REDACTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[REDACTED:([a-z-]+)\]").expect("valid redaction marker regex"));
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Rule {
+    TooLong { lines: usize, max: usize },
+    MissingField { field: String },
+    /// The body should open with a `# Title` (INDEX.md uses it).
+    NoHeading,
+    AbsolutePath { path: String },
+    /// Names of the redaction rules that matched.
+    Secret { kinds: Vec<String> },
+    InvalidFrontmatter { error: String },
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LintFinding {
+    /// `category/slug`.
+    pub entry: String,
+    #[serde(flatten)]
+    pub rule: Rule,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.rule {
+            Rule::TooLong { lines, max } => write!(f, "{}: {lines} lines (max {max})", self.entry),
+            Rule::MissingField { field } => write!(f, "{}: frontmatter has no `{field}`", self.entry),
+            Rule::NoHeading => write!(f, "{}: should start with a `# Title` line", self.entry),
+            Rule::AbsolutePath { path } => write!(f, "{}: absolute local path {path}", self.entry),
+            Rule::Secret { kinds } => write!(f, "{}: looks like it contains a secret ({})", self.entry, kinds.join(", ")),
+            Rule::InvalidFrontmatter { error } => write!(f, "{}: {error}", self.entry),
+        }
+    }
+}
+
+fn has_field(meta: &Frontmatter, field: &str) -> bool {
+    match field {
+        "created_at" => meta.created_at.is_some(),
+        "author" => meta.author.is_some(),
+        "features" => !meta.features.is_empty(),
+        "scope" => !meta.scope.is_empty(),
+        "tags" => !meta.tags.is_empty(),
+        "supersedes" => !meta.supersedes.is_empty(),
+        "related" => !meta.related.is_empty(),
+        "source" => meta.source.is_some(),
+        "expires" => meta.expires.is_some(),
+        "valid_until_feature" => meta.valid_until_feature.is_some(),
+        // Unknown names can't be satisfied; flag them rather than pass silently
+        _ => false,
+    }
+}
+
+/// Rules broken by one entry's content.
+pub fn lint_content(category: &str, content: &str, config: &ContextLintConfig, redactor: &Redactor) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    let (meta, body) = match context_meta::parse_entry(content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            rules.push(Rule::InvalidFrontmatter { error: e.to_string() });
+            (Frontmatter::default(), context_meta::split(content).1)
+        }
+    };
+
+    let max = if category == "references" { config.max_reference_lines } else { config.max_lines };
+    let lines = body.lines().count();
+    if max > 0 && lines > max {
+        rules.push(Rule::TooLong { lines, max });
+    }
+    for field in &config.required {
+        if !has_field(&meta, field) {
+            rules.push(Rule::MissingField { field: field.clone() });
+        }
+    }
+    if !body.lines().find(|l| !l.trim().is_empty()).is_some_and(|l| l.starts_with("# ")) {
+        rules.push(Rule::NoHeading);
+    }
+    for caps in ABSOLUTE_PATH_RE.captures_iter(body) {
+        let path = caps[1].to_string();
+        if !rules.contains(&Rule::AbsolutePath { path: path.clone() }) {
+            rules.push(Rule::AbsolutePath { path });
+        }
+    }
+    let (redacted, count) = redactor.redact(content);
+    if count > 0 {
+        let mut kinds: Vec<String> = REDACTED_RE.captures_iter(&redacted).map(|c| c[1].to_string()).collect();
+        kinds.dedup();
+        rules.push(Rule::Secret { kinds });
+    }
+    rules
+}
+
+/// Lint every entry, or only those modified after `since`.
+pub fn lint(project_dir: &Path, since: Option<SystemTime>) -> Result<Vec<LintFinding>, ContextError> {
+    let ctx = ContextManager::new(project_dir);
+    let config = ForgeConfig::load(project_dir).map(|c| c.context.lint).unwrap_or_default();
+    let redactor = Redactor::new(&[]).unwrap_or_default();
+    let changed = |entry: &ContextEntry| {
+        since.is_none_or(|since| {
+            std::fs::metadata(&entry.path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|at| at >= since)
+        })
+    };
+    let mut findings = Vec::new();
+    for entry in ctx.list_all()?.into_iter().filter(changed) {
+        let content = std::fs::read_to_string(&entry.path)?;
+        let id = format!("{}/{}", entry.category, entry.slug);
+        for rule in lint_content(&entry.category, &content, &config, &redactor) {
+            findings.push(LintFinding { entry: id.clone(), rule });
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(category: &str, content: &str, config: &ContextLintConfig) -> Vec<Rule> {
+        lint_content(category, content, config, &Redactor::new(&[]).unwrap())
+    }
+
+    #[test]
+    fn clean_entry_passes() {
+        let config = ContextLintConfig::default();
+        assert!(rules("gotchas", "---\nauthor: agent-1\n---\n# Pool\nSee src/db/pool.rs.\n", &config).is_empty());
+    }
+
+    #[test]
+    fn flags_each_rule() {
+        let config = ContextLintConfig {
+            max_lines: 3,
+            required: vec!["author".into(), "tags".into()],
+            ..ContextLintConfig::default()
+        };
+        let content = "---\nauthor: agent-1\n---\nNo title\nsee /Users/alice/proj/src/x.rs\nOPENAI_API_KEY=sk-abcdefghijklmnopqrstuvwxyz\nfour\n";
+        assert_eq!(
+            rules("gotchas", content, &config),
+            vec![
+                Rule::TooLong { lines: 4, max: 3 },
+                Rule::MissingField { field: "tags".into() },
+                Rule::NoHeading,
+                Rule::AbsolutePath { path: "/Users/alice/proj/src/x.rs".into() },
+                Rule::Secret { kinds: vec!["openai-key".into()] },
+            ]
+        );
+        // References get the longer limit
+        assert!(!rules("references", content, &config).contains(&Rule::TooLong { lines: 4, max: 3 }));
+        assert!(matches!(
+            rules("gotchas", "---\nowner: me\n---\n# X\n", &config)[0],
+            Rule::InvalidFrontmatter { .. }
+        ));
+    }
+
+    #[test]
+    fn lint_only_changed_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("gotchas", "old", "no heading").unwrap();
+        let since = SystemTime::now() + std::time::Duration::from_secs(3600);
+        assert_eq!(lint(tmp.path(), None).unwrap().len(), 1);
+        assert!(lint(tmp.path(), Some(since)).unwrap().is_empty());
+        assert_eq!(lint(tmp.path(), None).unwrap()[0].to_string(), "gotchas/old: should start with a `# Title` line");
+    }
+}
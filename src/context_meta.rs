@@ -0,0 +1,319 @@
+use chrono::{DateTime, NaiveDate};
+use std::fmt::Write as _;
+
+use crate::context::ContextError;
+
+/// Keys allowed in an entry's frontmatter, in the order they're written.
+pub const KEYS: &[&str] = &[
+    "created_at",
+    "author",
+    "features",
+    "scope",
+    "tags",
+    "supersedes",
+    "related",
+    "source",
+    "expires",
+    "valid_until_feature",
+];
+
+/// Parsed YAML frontmatter of a context entry. Only the flat subset forge
+/// writes is understood: `key: value` scalars and `[a, b]` or `- item` lists.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    /// `YYYY-MM-DD` or RFC 3339.
+    pub created_at: Option<String>,
+    /// Agent id (FORGE_AGENT_ID) or user that wrote the entry.
+    pub author: Option<String>,
+    /// Related feature ids.
+    pub features: Vec<String>,
+    /// forge.toml scopes the entry is about. Packages for features in those
+    /// scopes list it first; other scopes leave it out. Empty means global.
+    pub scope: Vec<String>,
+    pub tags: Vec<String>,
+    /// `category/slug` entries this one replaces.
+    pub supersedes: Vec<String>,
+    /// `category/slug` entries worth reading alongside this one, e.g. the
+    /// decision behind a gotcha.
+    pub related: Vec<String>,
+    /// Where a reference was distilled from.
+    pub source: Option<String>,
+    /// `YYYY-MM-DD` after which the entry no longer applies.
+    pub expires: Option<String>,
+    /// Feature id whose completion makes the entry obsolete, e.g. a
+    /// workaround until the real fix lands.
+    pub valid_until_feature: Option<String>,
+}
+
+impl Frontmatter {
+    pub fn is_empty(&self) -> bool {
+        *self == Frontmatter::default()
+    }
+
+    /// Parse and validate the text between the `---` fences.
+    pub fn parse(front: &str) -> Result<Self, ContextError> {
+        let invalid = |msg: String| ContextError::InvalidFrontmatter(msg);
+        let mut meta = Frontmatter::default();
+        let mut lines = front.lines().peekable();
+        while let Some(line) = lines.next() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("expected `key: value`, got `{}`", line.trim())))?;
+            let key = key.trim();
+            let value = value.trim();
+            let list = || -> Vec<String> {
+                if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    inner.split(',').map(unquote).filter(|s| !s.is_empty()).collect()
+                } else if value.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![unquote(value)]
+                }
+            };
+            let mut items = list();
+            // Block lists: `tags:` followed by `  - item` lines
+            if value.is_empty() {
+                while let Some(item) = lines.peek().and_then(|l| l.trim().strip_prefix("- ")) {
+                    items.push(unquote(item));
+                    lines.next();
+                }
+            }
+            let scalar = || (!value.is_empty()).then(|| unquote(value));
+            match key {
+                "created_at" => meta.created_at = scalar(),
+                "author" => meta.author = scalar(),
+                "features" => meta.features = items,
+                "scope" => meta.scope = items,
+                "tags" => meta.tags = items,
+                "supersedes" => meta.supersedes = items,
+                "related" => meta.related = items,
+                "source" => meta.source = scalar(),
+                "expires" => meta.expires = scalar(),
+                "valid_until_feature" => meta.valid_until_feature = scalar(),
+                other => {
+                    return Err(invalid(format!("unknown key `{other}` (expected one of {})", KEYS.join(", "))));
+                }
+            }
+        }
+        meta.validate()?;
+        Ok(meta)
+    }
+
+    fn validate(&self) -> Result<(), ContextError> {
+        let invalid = |msg: String| Err(ContextError::InvalidFrontmatter(msg));
+        if let Some(at) = &self.created_at
+            && NaiveDate::parse_from_str(at, "%Y-%m-%d").is_err()
+            && DateTime::parse_from_rfc3339(at).is_err()
+        {
+            return invalid(format!("created_at `{at}` is not YYYY-MM-DD or RFC 3339"));
+        }
+        if let Some(author) = &self.author
+            && author.contains(char::is_whitespace)
+        {
+            return invalid(format!("author `{author}` should be a single agent id"));
+        }
+        if let Some(at) = &self.expires
+            && NaiveDate::parse_from_str(at, "%Y-%m-%d").is_err()
+        {
+            return invalid(format!("expires `{at}` is not YYYY-MM-DD"));
+        }
+        for id in self.features.iter().chain(&self.valid_until_feature) {
+            if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return invalid(format!("feature id `{id}` has unexpected characters"));
+            }
+        }
+        for scope in &self.scope {
+            if scope.contains(|c: char| c.is_whitespace() || c == '/') {
+                return invalid(format!("scope `{scope}` should be a forge.toml scope name"));
+            }
+        }
+        for (key, entries) in [("supersedes", &self.supersedes), ("related", &self.related)] {
+            for entry in entries {
+                // Whether the category exists depends on forge.toml; ContextManager checks it
+                let ok = entry.split_once('/').is_some_and(|(cat, slug)| {
+                    !cat.is_empty()
+                        && cat.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                        && !slug.is_empty()
+                        && !slug.contains('/')
+                });
+                if !ok {
+                    return invalid(format!("{key} `{entry}` is not a category/slug"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The frontmatter block including fences, or "" if there's nothing to say.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("---\n");
+        let scalar = |out: &mut String, key: &str, value: &Option<String>| {
+            if let Some(v) = value {
+                let _ = writeln!(out, "{key}: {v}");
+            }
+        };
+        let list = |out: &mut String, key: &str, items: &[String]| {
+            if !items.is_empty() {
+                let _ = writeln!(out, "{key}: [{}]", items.join(", "));
+            }
+        };
+        scalar(&mut out, "created_at", &self.created_at);
+        scalar(&mut out, "author", &self.author);
+        list(&mut out, "features", &self.features);
+        list(&mut out, "scope", &self.scope);
+        list(&mut out, "tags", &self.tags);
+        list(&mut out, "supersedes", &self.supersedes);
+        list(&mut out, "related", &self.related);
+        scalar(&mut out, "source", &self.source);
+        scalar(&mut out, "expires", &self.expires);
+        scalar(&mut out, "valid_until_feature", &self.valid_until_feature);
+        out.push_str("---\n");
+        out
+    }
+
+    /// Fill fields the caller left unset from `other`, merging lists.
+    pub fn merge_defaults(&mut self, other: Frontmatter) {
+        self.created_at = self.created_at.take().or(other.created_at);
+        self.author = self.author.take().or(other.author);
+        self.source = self.source.take().or(other.source);
+        self.expires = self.expires.take().or(other.expires);
+        self.valid_until_feature = self.valid_until_feature.take().or(other.valid_until_feature);
+        for (mine, theirs) in [
+            (&mut self.features, other.features),
+            (&mut self.scope, other.scope),
+            (&mut self.tags, other.tags),
+            (&mut self.supersedes, other.supersedes),
+            (&mut self.related, other.related),
+        ] {
+            for item in theirs {
+                if !mine.contains(&item) {
+                    mine.push(item);
+                }
+            }
+        }
+    }
+
+    /// Whether the entry is for `scope`: Some(true) if it names it, Some(false)
+    /// if it names only other scopes, None if it's global.
+    pub fn in_scope(&self, scope: &str) -> Option<bool> {
+        (!self.scope.is_empty()).then(|| self.scope.iter().any(|s| s == scope))
+    }
+
+    /// Why the entry has expired, if it has: `expires` is before `today`, or
+    /// `valid_until_feature` is done.
+    pub fn expiry(&self, today: NaiveDate, is_done: impl Fn(&str) -> bool) -> Option<String> {
+        if let Some(at) = &self.expires
+            && NaiveDate::parse_from_str(at, "%Y-%m-%d").is_ok_and(|d| d < today)
+        {
+            return Some(format!("expired {at}"));
+        }
+        self.valid_until_feature
+            .as_deref()
+            .filter(|id| is_done(id))
+            .map(|id| format!("{id} is done"))
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s.trim().trim_matches(['"', '\'']).to_string()
+}
+
+/// The raw frontmatter (without fences) and the body after it. Content
+/// without a closed `---` block has no frontmatter.
+pub fn split(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) else {
+        return (None, content);
+    };
+    let (front, body) = if let Some(body) = rest.strip_prefix("---") {
+        ("", body)
+    } else {
+        match rest.split_once("\n---") {
+            Some(parts) => parts,
+            None => return (None, content),
+        }
+    };
+    // Drop the rest of the closing fence line and the blank line after it
+    let body = body.split_once('\n').map_or("", |(_, b)| b);
+    (Some(front), body.trim_start_matches('\n'))
+}
+
+/// Parse an entry's frontmatter; content without any parses as empty.
+pub fn parse_entry(content: &str) -> Result<(Frontmatter, &str), ContextError> {
+    match split(content) {
+        (Some(front), body) => Ok((Frontmatter::parse(front)?, body)),
+        (None, body) => Ok((Frontmatter::default(), body)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_inline_and_block_lists() {
+        let content = "---\ncreated_at: 2026-03-01\nauthor: agent-1\nfeatures: [f001, f002]\nscope: auth\ntags:\n  - sqlx\n  - \"db\"\nsupersedes: [gotchas/old-null]\nrelated: [decisions/use-option]\n---\n\n# Body\n";
+        let (meta, body) = parse_entry(content).unwrap();
+        assert_eq!(meta.created_at.as_deref(), Some("2026-03-01"));
+        assert_eq!(meta.author.as_deref(), Some("agent-1"));
+        assert_eq!(meta.features, vec!["f001", "f002"]);
+        assert_eq!(meta.scope, vec!["auth"]);
+        assert_eq!(meta.in_scope("auth"), Some(true));
+        assert_eq!(meta.in_scope("api"), Some(false));
+        assert_eq!(Frontmatter::default().in_scope("api"), None);
+        assert_eq!(meta.tags, vec!["sqlx", "db"]);
+        assert_eq!(meta.supersedes, vec!["gotchas/old-null"]);
+        assert_eq!(meta.related, vec!["decisions/use-option"]);
+        assert_eq!(body, "# Body\n");
+
+        let (reparsed, _) = parse_entry(&format!("{}body", meta.render())).unwrap();
+        assert_eq!(reparsed, meta);
+    }
+
+    #[test]
+    fn rejects_invalid_frontmatter() {
+        for front in [
+            "created_at: last tuesday",
+            "author: agent one",
+            "supersedes: [old-null]",
+            "related: [decisions]",
+            "features: [f 1]",
+            "scope: [auth/tokens]",
+            "owner: me",
+            "just text",
+        ] {
+            let content = format!("---\n{front}\n---\nbody");
+            assert!(
+                matches!(parse_entry(&content), Err(ContextError::InvalidFrontmatter(_))),
+                "{front}"
+            );
+        }
+    }
+
+    #[test]
+    fn expiry_by_date_or_feature() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let (meta, _) = parse_entry("---\nexpires: 2026-03-09\n---\nx").unwrap();
+        assert_eq!(meta.expiry(today, |_| false).as_deref(), Some("expired 2026-03-09"));
+        let (meta, _) = parse_entry("---\nexpires: 2026-03-10\n---\nx").unwrap();
+        assert_eq!(meta.expiry(today, |_| false), None);
+
+        let (meta, _) = parse_entry("---\nvalid_until_feature: f050\n---\nx").unwrap();
+        assert_eq!(meta.expiry(today, |_| false), None);
+        assert_eq!(meta.expiry(today, |id| id == "f050").as_deref(), Some("f050 is done"));
+        assert!(parse_entry("---\nexpires: soon\n---\nx").is_err());
+    }
+
+    #[test]
+    fn content_without_frontmatter() {
+        let (meta, body) = parse_entry("# Title\n---\nmore").unwrap();
+        assert!(meta.is_empty());
+        assert_eq!(body, "# Title\n---\nmore");
+        assert_eq!(Frontmatter::default().render(), "");
+    }
+}
@@ -0,0 +1,597 @@
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::config::ForgeConfig;
+use crate::context::{ContextError, ContextManager};
+use crate::context_meta;
+use crate::features::{Feature, FeatureList, FeatureStatus};
+use crate::git;
+
+/// Declarations worth listing as a file's API surface, per language.
+static SIGNATURE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(?:pub(?:\([^)]*\))?\s+(?:async\s+)?(?:unsafe\s+)?(?:fn|struct|enum|trait|type)\s+\w+|(?:async\s+)?def\s+[A-Za-z]\w*|class\s+\w+|export\s+(?:default\s+)?(?:async\s+)?(?:function|class|interface|type)\s+\w+|func\s+(?:\([^)]*\)\s*)?[A-Z]\w*)",
+    )
+    .expect("valid signature regex")
+});
+
+const SOURCE_EXTS: &[&str] = &[
+    "rs", "py", "ts", "tsx", "js", "jsx", "go", "java", "c", "cpp", "h", "hpp", "rb", "swift", "kt", "scala",
+];
+const SKIP_DIRS: &[&str] = &[".git", ".forge", "node_modules", "target", "dist", "build", "__pycache__", ".venv"];
+/// Per scope, so a scope owning `src/` doesn't turn into a file listing.
+const MAX_SCOPE_FILES: usize = 30;
+const MAX_SIGNATURES: usize = 15;
+const RECENT_COMMITS: usize = 5;
+
+/// Everything packages are built from, loaded once per refresh.
+struct Inputs {
+    project_dir: PathBuf,
+    config: Option<ForgeConfig>,
+    features: Vec<Feature>,
+    /// `category/slug` entries that have expired.
+    expired: HashSet<String>,
+    /// `category/slug` → related feature ids from frontmatter.
+    tagged: BTreeMap<String, Vec<String>>,
+    /// `category/slug` → its `related` entries.
+    related: BTreeMap<String, Vec<String>>,
+    /// `category/slug` → frontmatter, for scope matching.
+    meta: BTreeMap<String, context_meta::Frontmatter>,
+}
+
+impl Inputs {
+    fn load(project_dir: &Path) -> Result<Option<Self>, ContextError> {
+        let Ok(list) = FeatureList::load(project_dir) else {
+            return Ok(None);
+        };
+        let ctx = ContextManager::new(project_dir);
+        let expired = ctx
+            .expired_entries()?
+            .into_iter()
+            .map(|(e, _)| format!("{}/{}", e.category, e.slug))
+            .collect();
+        let mut tagged = BTreeMap::new();
+        let mut related = BTreeMap::new();
+        let mut meta = BTreeMap::new();
+        for entry in ctx.list_all()? {
+            let id = format!("{}/{}", entry.category, entry.slug);
+            if !entry.meta.features.is_empty() {
+                tagged.insert(id.clone(), entry.meta.features.clone());
+            }
+            if !entry.meta.related.is_empty() {
+                related.insert(id.clone(), entry.meta.related.clone());
+            }
+            meta.insert(id, entry.meta);
+        }
+        Ok(Some(Self {
+            project_dir: project_dir.to_path_buf(),
+            config: ForgeConfig::load(project_dir).ok(),
+            features: list.features,
+            expired,
+            tagged,
+            related,
+            meta,
+        }))
+    }
+
+    /// Whether entry `id` is for `scope`; None if it's global.
+    fn in_scope(&self, id: &str, scope: &str) -> Option<bool> {
+        self.meta.get(id).and_then(|m| m.in_scope(scope))
+    }
+
+    /// Entries of `category` for `scope`: those naming it first, then global
+    /// ones. Entries scoped elsewhere are left out.
+    fn scoped_entries_in(&self, category: &str, scope: &str) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .entries_in(category)
+            .into_iter()
+            .filter(|id| self.in_scope(id, scope) != Some(false))
+            .collect();
+        ids.sort_by_key(|id| self.in_scope(id, scope).is_none());
+        ids
+    }
+
+    /// Body of `context/<id>.md` without frontmatter, unless missing or expired.
+    fn entry(&self, id: &str) -> Option<String> {
+        if self.expired.contains(id) {
+            return None;
+        }
+        let content = std::fs::read_to_string(self.project_dir.join("context").join(format!("{id}.md"))).ok()?;
+        let body = context_meta::split(&content).1.trim();
+        (!body.is_empty()).then(|| body.to_string())
+    }
+
+    fn entries_in(&self, category: &str) -> Vec<String> {
+        ContextManager::new(&self.project_dir)
+            .list_category(category)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| format!("{category}/{}", e.slug))
+            .collect()
+    }
+
+    fn scope_paths(&self, feature: &Feature) -> Vec<String> {
+        self.config
+            .as_ref()
+            .and_then(|c| c.scope_owns(&feature.scope))
+            .unwrap_or_default()
+    }
+
+    fn scope_dir(&self, feature: &Feature) -> Option<&str> {
+        self.config.as_ref().and_then(|c| c.scope_dir(&feature.scope)).map(|d| d.trim_end_matches('/'))
+    }
+
+    /// Source files under the scope's owned paths (files or directories).
+    fn scope_files(&self, feature: &Feature) -> Vec<String> {
+        let mut files = Vec::new();
+        for owned in self.scope_paths(feature) {
+            let path = self.project_dir.join(&owned);
+            if path.is_dir() {
+                walk_sources(&self.project_dir, &path, &mut files);
+            } else {
+                files.push(owned.trim_start_matches("./").to_string());
+            }
+        }
+        files.sort();
+        files.dedup();
+        files.truncate(MAX_SCOPE_FILES);
+        files
+    }
+}
+
+fn walk_sources(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            if !SKIP_DIRS.contains(&name.as_str()) {
+                walk_sources(root, &path, out);
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| SOURCE_EXTS.contains(&ext.to_string_lossy().as_ref()))
+            && let Ok(rel) = path.strip_prefix(root)
+        {
+            out.push(rel.to_string_lossy().into_owned());
+        }
+    }
+}
+
+/// First `max_lines` non-empty, non-heading lines.
+fn summarize(content: &str, max_lines: usize) -> String {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .take(max_lines)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn signatures(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter(|l| SIGNATURE_RE.is_match(l))
+        .map(|l| l.trim().trim_end_matches(['{', ':']).trim().to_string())
+        .take(MAX_SIGNATURES)
+        .collect()
+}
+
+/// `## Scope Files`: each owned file with its line count and declarations.
+fn render_scope_files(inputs: &Inputs, feature: &Feature, lines: &mut Vec<String>) {
+    let files = inputs.scope_files(feature);
+    if files.is_empty() {
+        return;
+    }
+    lines.push("\n## Scope Files".into());
+    let dir = inputs.scope_dir(feature).map(|d| format!("{d}/"));
+    for path in files {
+        // As the agent sees it from the scope's dir
+        let file = dir.as_deref().and_then(|d| path.strip_prefix(d)).unwrap_or(&path);
+        match std::fs::read_to_string(inputs.project_dir.join(&path)) {
+            Ok(source) => {
+                lines.push(format!("\n### {file} ({} lines)", source.lines().count()));
+                for sig in signatures(&source) {
+                    lines.push(format!("- `{sig}`"));
+                }
+            }
+            Err(_) => {
+                lines.push(format!("\n### {file}"));
+                lines.push("_(not found)_".into());
+            }
+        }
+    }
+}
+
+/// `## Recent Changes`: the last few commits touching the scope.
+fn render_history(inputs: &Inputs, feature: &Feature, lines: &mut Vec<String>) {
+    let paths = inputs.scope_paths(feature);
+    if paths.is_empty() {
+        return;
+    }
+    let recent = format!("-{RECENT_COMMITS}");
+    let mut args = vec!["log", "--format=- %h %ad %s", "--date=short", &recent, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let Ok(log) = git::run(&inputs.project_dir, &args) else {
+        return;
+    };
+    if !log.trim().is_empty() {
+        lines.push("\n## Recent Changes".into());
+        lines.extend(log.lines().map(String::from));
+    }
+}
+
+/// Summaries of the hinted entries plus any whose frontmatter names this
+/// feature or scopes a decision or pattern to its scope, then one hop of
+/// their `related` links (the decision behind a gotcha), with a pointer to
+/// the full files.
+fn render_linked_context(inputs: &Inputs, feature: &Feature, heading: &str, lines: &mut Vec<String>) {
+    let mut ids: Vec<&str> = feature.context_hints.iter().map(String::as_str).collect();
+    for (id, features) in &inputs.tagged {
+        if features.contains(&feature.id) && !ids.contains(&id.as_str()) {
+            ids.push(id);
+        }
+    }
+    for id in inputs.meta.keys() {
+        let kind = id.split('/').next().unwrap_or("");
+        if matches!(kind, "decisions" | "patterns")
+            && inputs.in_scope(id, &feature.scope) == Some(true)
+            && !ids.contains(&id.as_str())
+        {
+            ids.push(id);
+        }
+    }
+    let mut linked: Vec<(&str, Option<&str>, String)> = ids
+        .into_iter()
+        .filter_map(|id| Some((id, None, inputs.entry(id)?)))
+        .collect();
+    let direct: Vec<&str> = linked.iter().map(|(id, _, _)| *id).collect();
+    for from in direct {
+        for to in inputs.related.get(from).into_iter().flatten() {
+            if linked.iter().any(|(id, _, _)| id == to) {
+                continue;
+            }
+            if let Some(content) = inputs.entry(to) {
+                linked.push((to, Some(from), content));
+            }
+        }
+    }
+    if linked.is_empty() {
+        return;
+    }
+    lines.push(format!("\n## {heading}"));
+    for (id, via, content) in &linked {
+        let via = via.map(|v| format!(" _(via {v})_")).unwrap_or_default();
+        lines.push(format!("- **{id}**{via}: {}", summarize(content, 3)));
+    }
+    let paths: Vec<String> = linked.iter().map(|(id, _, _)| format!("`context/{id}.md`")).collect();
+    lines.push(format!("\n> **Full details**: {}", paths.join(", ")));
+}
+
+/// `feedback/exec-memory/<id>.json` as previous attempts and tactics.
+fn render_exec_memory(inputs: &Inputs, feature_id: &str, lines: &mut Vec<String>) {
+    let path = inputs.project_dir.join(format!("feedback/exec-memory/{feature_id}.json"));
+    let Some(data) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+    else {
+        return;
+    };
+    let text = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    let list = |v: &Value, key: &str| -> Vec<String> {
+        v.get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|s| s.as_str().map(String::from))
+            .collect()
+    };
+
+    let attempts = data.get("attempts").and_then(Value::as_array).cloned().unwrap_or_default();
+    if !attempts.is_empty() {
+        lines.push("\n## Previous Attempts".into());
+        for attempt in &attempts {
+            let number = attempt.get("number").map_or("?".to_string(), Value::to_string);
+            lines.push(format!("- Attempt {number}: {}", text(attempt, "summary")));
+            let reason = text(attempt, "failed_reason");
+            if !reason.is_empty() {
+                lines.push(format!("  Failed: {reason}"));
+            }
+            for discovery in list(attempt, "discoveries") {
+                lines.push(format!("  - Discovered: {discovery}"));
+            }
+        }
+    }
+
+    let Some(tactics) = data.get("tactics").filter(|t| t.is_object()) else {
+        return;
+    };
+    lines.push("\n## Session Tactics".into());
+    for (key, label) in [
+        ("approach", "Approach"),
+        ("test_strategy", "Test strategy"),
+        ("verify_result", "Verify result"),
+        ("performance_notes", "Performance"),
+    ] {
+        let value = text(tactics, key);
+        if !value.is_empty() {
+            lines.push(format!("**{label}**: {value}"));
+        }
+    }
+    for (key, label) in [("context_used", "Context used"), ("key_files_read", "Key files")] {
+        let values = list(tactics, key);
+        if !values.is_empty() {
+            lines.push(format!("**{label}**: {}", values.join(", ")));
+        }
+    }
+    let insights = list(tactics, "insights");
+    if !insights.is_empty() {
+        lines.push("**Insights:**".into());
+        lines.extend(insights.iter().map(|i| format!("- {i}")));
+    }
+}
+
+fn header(inputs: &Inputs, feature: &Feature, title: &str) -> Vec<String> {
+    let scope = match inputs.scope_dir(feature) {
+        Some(dir) => format!("{} (in `{dir}/`; file paths below are relative to it)", feature.scope),
+        None => feature.scope.clone(),
+    };
+    vec![
+        format!("# {title}: {}", feature.id),
+        format!("\n**Description**: {}", feature.description),
+        format!("**Scope**: {scope}"),
+    ]
+}
+
+/// Package for a done feature that pending work depends on: its API
+/// surface and what was learned building it.
+fn compile_completed(inputs: &Inputs, feature: &Feature) -> String {
+    let mut lines = header(inputs, feature, "Completed");
+    lines.push("**Status**: done".into());
+    render_scope_files(inputs, feature, &mut lines);
+    render_history(inputs, feature, &mut lines);
+
+    // Entries scoped to it in frontmatter, or named after the scope, e.g.
+    // gotchas/auth-tokens for scope auth
+    let scope = feature.scope.to_lowercase().replace('-', "");
+    if !scope.is_empty() {
+        let related: Vec<String> = ["decisions", "gotchas", "patterns"]
+            .iter()
+            .flat_map(|cat| inputs.entries_in(cat))
+            .filter(|id| {
+                inputs.in_scope(id, &feature.scope) == Some(true)
+                    || id.rsplit('/').next().unwrap_or("").to_lowercase().replace('-', "").contains(&scope)
+            })
+            .filter_map(|id| Some(format!("- **{id}**: {}", summarize(&inputs.entry(&id)?, 5))))
+            .collect();
+        if !related.is_empty() {
+            lines.push("\n## Decisions & Patterns".into());
+            lines.extend(related);
+        }
+    }
+
+    render_linked_context(inputs, feature, "Linked Context", &mut lines);
+
+    let poc = format!("poc/{}", feature.id);
+    if let Some(content) = inputs.entry(&poc) {
+        lines.push("\n## POC Results".into());
+        lines.push(summarize(&content, 5));
+        lines.push(format!("\n> **Full POC**: `context/{poc}.md`"));
+    }
+
+    render_exec_memory(inputs, &feature.id, &mut lines);
+    lines.join("\n") + "\n"
+}
+
+/// Package for a pending feature: dependency summaries and APIs, its scope,
+/// linked context, gotchas and any earlier attempts.
+fn compile_pending(inputs: &Inputs, feature: &Feature) -> String {
+    let mut lines = header(inputs, feature, "Context Package");
+    let by_id: BTreeMap<&str, &Feature> = inputs.features.iter().map(|f| (f.id.as_str(), f)).collect();
+    let deps: Vec<&Feature> = feature
+        .depends_on
+        .iter()
+        .filter_map(|id| by_id.get(id.as_str()).copied())
+        .collect();
+    if !deps.is_empty() {
+        lines.push("\n## Dependencies".into());
+        lines.push(String::new());
+        lines.push("| Dep | Description | Scope | Status |".into());
+        lines.push("|-----|-------------|-------|--------|".into());
+        for dep in &deps {
+            let status = if dep.status == FeatureStatus::Done { "done" } else { "**pending**" };
+            lines.push(format!("| {} | {} | {} | {status} |", dep.id, dep.description, dep.scope));
+        }
+        let done: Vec<&&Feature> = deps.iter().filter(|d| d.status == FeatureStatus::Done).collect();
+        for dep in &done {
+            let api: Vec<String> = inputs
+                .scope_files(dep)
+                .iter()
+                .filter_map(|f| std::fs::read_to_string(inputs.project_dir.join(f)).ok())
+                .flat_map(|source| signatures(&source))
+                .map(|sig| format!("- `{sig}`"))
+                .collect();
+            if !api.is_empty() {
+                lines.push(format!("\n### {} — API Surface", dep.id));
+                lines.extend(api);
+            }
+        }
+        let full: Vec<String> = done
+            .iter()
+            .filter(|d| inputs.project_dir.join(format!("context/packages/{}.md", d.id)).exists())
+            .map(|d| format!("`context/packages/{}.md`", d.id))
+            .collect();
+        if !full.is_empty() {
+            lines.push(format!(
+                "\n> **Deep dive**: For full tactics, decisions, and test strategy read {}",
+                full.join(", ")
+            ));
+        }
+    }
+
+    render_scope_files(inputs, feature, &mut lines);
+    render_history(inputs, feature, &mut lines);
+    render_linked_context(inputs, feature, "Relevant Context", &mut lines);
+
+    let gotchas: Vec<String> = inputs
+        .scoped_entries_in("gotchas", &feature.scope)
+        .into_iter()
+        .filter_map(|id| {
+            let summary = summarize(&inputs.entry(&id)?, 2);
+            Some(format!("- **{}**: {summary}", id.trim_start_matches("gotchas/")))
+        })
+        .collect();
+    if !gotchas.is_empty() {
+        lines.push("\n## Gotchas".into());
+        lines.extend(gotchas);
+    }
+
+    if inputs.project_dir.join("context/INDEX.md").exists() {
+        lines.push("\n> **More context**: Scan `context/INDEX.md` for decisions, patterns, references".into());
+    }
+
+    render_exec_memory(inputs, &feature.id, &mut lines);
+    lines.join("\n") + "\n"
+}
+
+/// Write `context/packages/<id>.md` for every pending or claimed feature, and
+/// for done features that one of them depends on. Done dependencies go first
+/// so pending packages can point at them. Without features.json nothing is
+/// written.
+pub fn build_packages(project_dir: &Path) -> Result<Vec<PathBuf>, ContextError> {
+    let Some(inputs) = Inputs::load(project_dir)? else {
+        return Ok(Vec::new());
+    };
+    let pending: Vec<&Feature> = inputs
+        .features
+        .iter()
+        .filter(|f| matches!(f.status, FeatureStatus::Pending | FeatureStatus::Claimed))
+        .collect();
+    let needed: HashSet<&str> = pending
+        .iter()
+        .flat_map(|f| f.depends_on.iter().map(String::as_str))
+        .collect();
+    let done: Vec<&Feature> = inputs
+        .features
+        .iter()
+        .filter(|f| f.status == FeatureStatus::Done && needed.contains(f.id.as_str()))
+        .collect();
+
+    let dir = project_dir.join("context/packages");
+    std::fs::create_dir_all(&dir)?;
+    let mut written = Vec::new();
+    for (feature, completed) in done.iter().map(|f| (f, true)).chain(pending.iter().map(|f| (f, false))) {
+        let package = if completed {
+            compile_completed(&inputs, feature)
+        } else {
+            compile_pending(&inputs, feature)
+        };
+        let path = dir.join(format!("{}.md", feature.id));
+        std::fs::write(&path, package)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::write(
+            dir.join("forge.toml"),
+            "[project]\nname = \"t\"\n\n[scopes.db]\nowns = [\"src/db/\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("features.json"),
+            r#"{"features":[
+                {"id":"f001","type":"implement","scope":"db","description":"pool","verify":"true","status":"done"},
+                {"id":"f002","type":"implement","scope":"db","description":"retries","verify":"true",
+                 "depends_on":["f001"],"context_hints":["decisions/pool-size","gotchas/gone"]},
+                {"id":"f003","type":"implement","scope":"api","description":"x","verify":"true","status":"done"}
+            ]}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("src/db")).unwrap();
+        fs::write(
+            dir.join("src/db/pool.rs"),
+            "pub struct Pool {\n}\n\nimpl Pool {\n    pub fn acquire(&self) -> Conn {\n    }\n    fn private() {}\n}\n",
+        )
+        .unwrap();
+        let ctx = ContextManager::new(dir);
+        ctx.write_entry("decisions", "pool-size", "---\nrelated: [decisions/why-bounded]\n---\n# Pool size\nUse 10 connections.")
+            .unwrap();
+        ctx.write_entry("decisions", "why-bounded", "# Bounded pools\nUnbounded pools exhaust the DB.").unwrap();
+        ctx.write_entry("gotchas", "timeouts", "---\nfeatures: [f002]\n---\n# Timeouts\nSet a connect timeout.")
+            .unwrap();
+        ctx.write_entry("gotchas", "old", "---\nexpires: 2020-01-01\n---\n# Old\nStale advice.").unwrap();
+        fs::create_dir_all(dir.join("feedback/exec-memory")).unwrap();
+        fs::write(
+            dir.join("feedback/exec-memory/f002.json"),
+            r#"{"attempts":[{"number":1,"summary":"naive retry","failed_reason":"thundering herd","discoveries":["need jitter"]}]}"#,
+        )
+        .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn builds_pending_and_dependency_packages() {
+        let tmp = setup();
+        let written = build_packages(tmp.path()).unwrap();
+        let names: Vec<String> = written
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["f001.md", "f002.md"], "f003 is done and nothing needs it");
+
+        let pending = fs::read_to_string(tmp.path().join("context/packages/f002.md")).unwrap();
+        assert!(pending.starts_with("# Context Package: f002\n"));
+        assert!(pending.contains("| f001 | pool | db | done |"));
+        assert!(pending.contains("### f001 — API Surface\n- `pub struct Pool`\n- `pub fn acquire(&self) -> Conn`"));
+        assert!(pending.contains("### src/db/pool.rs (8 lines)"));
+        assert!(pending.contains("- **decisions/pool-size**: Use 10 connections."));
+        assert!(pending.contains("- **decisions/why-bounded** _(via decisions/pool-size)_: Unbounded pools exhaust the DB."));
+        assert!(pending.contains("- **gotchas/timeouts**: Set a connect timeout."), "tagged via frontmatter");
+        assert!(!pending.contains("gotchas/gone"));
+        assert!(!pending.contains("Stale advice"), "expired entries are left out");
+        assert!(pending.contains("- Attempt 1: naive retry\n  Failed: thundering herd\n  - Discovered: need jitter"));
+        assert!(!pending.contains("private"));
+
+        let completed = fs::read_to_string(tmp.path().join("context/packages/f001.md")).unwrap();
+        assert!(completed.starts_with("# Completed: f001\n"));
+        assert!(completed.contains("**Status**: done"));
+    }
+
+    #[test]
+    fn scoped_entries_first_other_scopes_left_out() {
+        let tmp = setup();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("gotchas", "zz-db-locks", "---\nscope: db\n---\n# Locks\nTake row locks in id order.")
+            .unwrap();
+        ctx.write_entry("gotchas", "cors", "---\nscope: [api]\n---\n# CORS\nAllow the dev origin.").unwrap();
+        ctx.write_entry("decisions", "sharding", "---\nscope: db\n---\n# Sharding\nShard by tenant.").unwrap();
+        build_packages(tmp.path()).unwrap();
+
+        let pending = fs::read_to_string(tmp.path().join("context/packages/f002.md")).unwrap();
+        let gotchas = &pending[pending.find("## Gotchas").unwrap()..];
+        assert!(gotchas.find("zz-db-locks").unwrap() < gotchas.find("timeouts").unwrap(), "{gotchas}");
+        assert!(!pending.contains("CORS"), "api gotchas stay out of db packages");
+        assert!(pending.contains("- **decisions/sharding**: Shard by tenant."));
+        let completed = fs::read_to_string(tmp.path().join("context/packages/f001.md")).unwrap();
+        assert!(completed.contains("## Decisions & Patterns\n- **decisions/sharding**: Shard by tenant."));
+    }
+
+    #[test]
+    fn no_features_no_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(build_packages(tmp.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::context::{ContextError, ContextManager};
+use crate::git;
+
+/// What reconciling one worktree's context/ into the project did, by
+/// `category/slug`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    /// New in the worktree.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    /// Changed only in the worktree, so its version was taken.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updated: Vec<String>,
+    /// Changed on both sides: the worktree's extra lines were appended.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub appended: Vec<String>,
+}
+
+impl ReconcileReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.appended.is_empty()
+    }
+}
+
+/// `ours` plus the lines `theirs` added since `base` that `ours` doesn't
+/// already have, under a marker naming where they came from. None if
+/// `theirs` adds nothing.
+fn append_missing(ours: &str, theirs: &str, base: Option<&str>, source: &str) -> Option<String> {
+    let existing: HashSet<&str> = ours.lines().chain(base.unwrap_or("").lines()).map(str::trim).collect();
+    let body = crate::context_meta::split(theirs).1;
+    let extra: Vec<&str> = body
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !existing.contains(l.trim()))
+        .collect();
+    if extra.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}\n\n<!-- appended from {source}: both sides changed this entry -->\n{}\n",
+        ours.trim_end(),
+        extra.join("\n")
+    ))
+}
+
+/// Bring context entries written in `worktree_dir` into `project_dir`,
+/// committed or not. `base` is the commit the worktree branched from; with
+/// it, an entry changed on one side only takes that side's version. Entries
+/// changed on both sides (or with no base to tell) are never overwritten:
+/// the worktree's new lines are appended instead. Deletions aren't carried
+/// over; git merges those.
+pub fn reconcile(
+    project_dir: &Path,
+    worktree_dir: &Path,
+    base: Option<&str>,
+    source: &str,
+) -> Result<ReconcileReport, ContextError> {
+    let ours_ctx = ContextManager::new(project_dir);
+    let theirs_ctx = ContextManager::new(worktree_dir);
+    let mut report = ReconcileReport::default();
+    for entry in theirs_ctx.list_all()? {
+        let id = format!("{}/{}", entry.category, entry.slug);
+        let theirs = std::fs::read_to_string(&entry.path)?;
+        let ours = ours_ctx.read_entry(&entry.category, &entry.slug).ok();
+        let base_content = base.and_then(|rev| git::show_file(project_dir, rev, &format!("context/{id}.md")));
+        if ours.as_deref() == Some(theirs.as_str()) || base_content.as_deref() == Some(theirs.as_str()) {
+            // Same on both sides, or only our side changed it
+            continue;
+        }
+        match ours {
+            None => {
+                ours_ctx.write_entry(&entry.category, &entry.slug, &theirs)?;
+                report.added.push(id);
+            }
+            Some(ours) if base_content.as_deref() == Some(ours.as_str()) => {
+                ours_ctx.write_entry(&entry.category, &entry.slug, &theirs)?;
+                report.updated.push(id);
+            }
+            Some(ours) => {
+                if let Some(merged) = append_missing(&ours, &theirs, base_content.as_deref(), source) {
+                    ours_ctx.write_entry(&entry.category, &entry.slug, &merged)?;
+                    report.appended.push(id);
+                }
+            }
+        }
+    }
+    if !report.is_empty() {
+        ours_ctx.write_index()?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let out = Command::new("git").args(args).current_dir(dir).output().unwrap();
+        assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+    }
+
+    #[test]
+    fn unions_updates_and_appends() {
+        let tmp = tempfile::tempdir().unwrap();
+        let main = tmp.path().join("main");
+        std::fs::create_dir_all(&main).unwrap();
+        git(&main, &["init", "-q"]);
+        git(&main, &["config", "user.email", "t@t"]);
+        git(&main, &["config", "user.name", "t"]);
+        let ctx = ContextManager::new(&main);
+        ctx.write_entry("decisions", "use-vec", "# Use Vec\nSimpler.").unwrap();
+        ctx.write_entry("gotchas", "pool", "# Pool\nSize 10.").unwrap();
+        ctx.write_entry("gotchas", "ours-only", "# Ours\nx").unwrap();
+        git(&main, &["add", "-A"]);
+        git(&main, &["commit", "-qm", "base"]);
+        let base = git::merge_base(&main, "HEAD", "HEAD").unwrap();
+
+        let wt = tmp.path().join("wt");
+        git(&main, &["worktree", "add", "-q", "-b", "forge/agent-1", wt.to_str().unwrap()]);
+        let theirs = ContextManager::new(&wt);
+        theirs.write_entry("decisions", "use-vec", "# Use Vec\nSimpler.\nAlso faster.").unwrap();
+        theirs.write_entry("gotchas", "pool", "# Pool\nSize 10.\nAlways set a timeout.").unwrap();
+        theirs.write_entry("gotchas", "new", "# New\nFound in the worktree.").unwrap();
+
+        // Meanwhile on main: pool changed too, ours-only changed only here
+        ctx.write_entry("gotchas", "pool", "# Pool\nSize 20 since f010.").unwrap();
+        ctx.write_entry("gotchas", "ours-only", "# Ours\ny").unwrap();
+
+        let report = reconcile(&main, &wt, Some(&base), "forge/agent-1").unwrap();
+        assert_eq!(report.added, vec!["gotchas/new"]);
+        assert_eq!(report.updated, vec!["decisions/use-vec"]);
+        assert_eq!(report.appended, vec!["gotchas/pool"]);
+
+        assert_eq!(ctx.read_entry("decisions", "use-vec").unwrap(), "# Use Vec\nSimpler.\nAlso faster.");
+        assert_eq!(
+            ctx.read_entry("gotchas", "pool").unwrap(),
+            "# Pool\nSize 20 since f010.\n\n<!-- appended from forge/agent-1: both sides changed this entry -->\nAlways set a timeout.\n"
+        );
+        assert_eq!(ctx.read_entry("gotchas", "ours-only").unwrap(), "# Ours\ny");
+        assert!(std::fs::read_to_string(main.join("context/INDEX.md")).unwrap().contains("- new: New"));
+
+        // Running again changes nothing
+        assert!(reconcile(&main, &wt, Some(&base), "forge/agent-1").unwrap().appended.is_empty());
+    }
+}
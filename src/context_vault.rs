@@ -0,0 +1,235 @@
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::context::{ContextError, ContextManager, LinkKind};
+use crate::context_meta::{self, Frontmatter};
+
+/// `context/<cat>/<slug>.md` mentioned in an entry body, backticked or not.
+static ENTRY_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"`?context/([a-z0-9_-]+)/([A-Za-z0-9_.-]+)\.md`?").expect("valid entry path regex")
+});
+
+/// One entry with what both formats need to link it up.
+struct Note {
+    id: String,
+    category: String,
+    slug: String,
+    title: String,
+    meta: Frontmatter,
+    body: String,
+    superseded_by: Vec<String>,
+}
+
+fn load_notes(ctx: &ContextManager) -> Result<Vec<Note>, ContextError> {
+    let mut superseded_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for link in ctx.links()? {
+        if link.kind == LinkKind::Supersedes {
+            superseded_by.entry(link.to).or_default().push(link.from);
+        }
+    }
+    let mut notes = Vec::new();
+    for entry in ctx.list_all()? {
+        let content = std::fs::read_to_string(&entry.path)?;
+        let body = context_meta::split(&content).1.trim().to_string();
+        let id = format!("{}/{}", entry.category, entry.slug);
+        notes.push(Note {
+            title: ctx.first_heading(&entry.path),
+            superseded_by: superseded_by.remove(&id).unwrap_or_default(),
+            id,
+            category: entry.category,
+            slug: entry.slug,
+            meta: entry.meta,
+            body,
+        });
+    }
+    Ok(notes)
+}
+
+/// `body` with mentions of existing entries' files turned into links.
+fn link_mentions(body: &str, known: &HashSet<&str>, link: impl Fn(&str) -> String) -> String {
+    ENTRY_PATH_RE
+        .replace_all(body, |caps: &regex::Captures| {
+            let id = format!("{}/{}", &caps[1], &caps[2]);
+            if known.contains(id.as_str()) { link(&id) } else { caps[0].to_string() }
+        })
+        .into_owned()
+}
+
+fn links_section(note: &Note, link: impl Fn(&str) -> String) -> String {
+    let mut out = String::new();
+    for (label, ids) in [
+        ("Related", &note.meta.related),
+        ("Supersedes", &note.meta.supersedes),
+        ("Superseded by", &note.superseded_by),
+    ] {
+        if !ids.is_empty() {
+            let links: Vec<String> = ids.iter().map(|id| link(id)).collect();
+            let _ = writeln!(out, "- {label}: {}", links.join(", "));
+        }
+    }
+    if out.is_empty() { out } else { format!("\n\n## Links\n\n{}", out.trim_end()) }
+}
+
+fn group_by_category<'a>(ctx: &ContextManager, notes: &'a [Note]) -> Vec<(String, Vec<&'a Note>)> {
+    ctx.categories()
+        .iter()
+        .map(|cat| (cat.clone(), notes.iter().filter(|n| &n.category == cat).collect::<Vec<_>>()))
+        .filter(|(_, notes)| !notes.is_empty())
+        .collect()
+}
+
+fn heading_for(category: &str) -> String {
+    let mut chars = category.chars();
+    chars.next().map_or_else(String::new, |c| c.to_uppercase().to_string() + chars.as_str())
+}
+
+/// An Obsidian vault: every entry keeps its frontmatter (tags show up in
+/// Obsidian's tag pane), links become `[[category/slug]]` wikilinks, and
+/// `INDEX.md` links every note. Returns the number of notes written.
+pub fn export_obsidian(project_dir: &Path, out: &Path) -> Result<usize, ContextError> {
+    let ctx = ContextManager::new(project_dir);
+    let notes = load_notes(&ctx)?;
+    let known: HashSet<&str> = notes.iter().map(|n| n.id.as_str()).collect();
+    let wikilink = |id: &str| format!("[[{id}]]");
+    for note in &notes {
+        let dir = out.join(&note.category);
+        std::fs::create_dir_all(&dir)?;
+        let body = link_mentions(&note.body, &known, wikilink);
+        let content = format!("{}{body}{}\n", note.meta.render(), links_section(note, wikilink));
+        std::fs::write(dir.join(format!("{}.md", note.slug)), content)?;
+    }
+
+    let mut index = String::from("# Context Index\n");
+    for (cat, notes) in group_by_category(&ctx, &notes) {
+        let _ = write!(index, "\n## {}\n\n", heading_for(&cat));
+        for note in notes {
+            let _ = writeln!(index, "- [[{}|{}]]: {}", note.id, note.slug, note.title);
+        }
+    }
+    std::fs::create_dir_all(out)?;
+    std::fs::write(out.join("INDEX.md"), index)?;
+    Ok(notes.len())
+}
+
+/// An mdBook: `book.toml`, `src/SUMMARY.md` with a part per category, and
+/// each entry as a chapter. Frontmatter would render as a rule and a
+/// paragraph, so it's shown as a quoted line of metadata instead.
+pub fn export_mdbook(project_dir: &Path, out: &Path, title: &str) -> Result<usize, ContextError> {
+    let ctx = ContextManager::new(project_dir);
+    let notes = load_notes(&ctx)?;
+    let known: HashSet<&str> = notes.iter().map(|n| n.id.as_str()).collect();
+    // Chapters sit one directory down, so links to siblings go through ..
+    let link = |id: &str| format!("[{id}](../{id}.md)");
+    let src = out.join("src");
+    for note in &notes {
+        let dir = src.join(&note.category);
+        std::fs::create_dir_all(&dir)?;
+        let mut meta = Vec::new();
+        if let Some(author) = &note.meta.author {
+            meta.push(format!("by {author}"));
+        }
+        if let Some(at) = &note.meta.created_at {
+            meta.push(at.clone());
+        }
+        for (label, items) in [
+            ("tags", &note.meta.tags),
+            ("features", &note.meta.features),
+            ("scope", &note.meta.scope),
+        ] {
+            if !items.is_empty() {
+                meta.push(format!("{label}: {}", items.join(", ")));
+            }
+        }
+        if let Some(source) = &note.meta.source {
+            meta.push(format!("source: <{source}>"));
+        }
+        let (heading, body) = match note.body.strip_prefix("# ") {
+            Some(rest) => rest.split_once('\n').unwrap_or((rest, "")),
+            None => (note.title.as_str(), note.body.as_str()),
+        };
+        let mut content = format!("# {heading}\n\n");
+        if !meta.is_empty() {
+            let _ = writeln!(content, "> {}\n", meta.join(" · "));
+        }
+        content.push_str(&link_mentions(body.trim_start(), &known, link));
+        content.push_str(&links_section(note, link));
+        content.push('\n');
+        std::fs::write(dir.join(format!("{}.md", note.slug)), content)?;
+    }
+
+    let groups = group_by_category(&ctx, &notes);
+    let mut intro = format!("# {title}\n\nWhat agents have recorded while building the project.\n\n");
+    let mut summary = String::from("# Summary\n\n[Introduction](README.md)\n");
+    for (cat, notes) in &groups {
+        let _ = writeln!(intro, "- {}: {} entries", heading_for(cat), notes.len());
+        let _ = write!(summary, "\n# {}\n\n", heading_for(cat));
+        for note in notes {
+            let _ = writeln!(summary, "- [{}]({}.md)", note.title, note.id);
+        }
+    }
+    std::fs::create_dir_all(&src)?;
+    std::fs::write(src.join("README.md"), intro)?;
+    std::fs::write(src.join("SUMMARY.md"), summary)?;
+    std::fs::write(
+        out.join("book.toml"),
+        format!("[book]\ntitle = {}\nsrc = \"src\"\n", toml::Value::String(title.to_string())),
+    )?;
+    Ok(notes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = ContextManager::new(tmp.path());
+        ctx.write_entry("decisions", "use-vec", "---\ntags: [perf]\n---\n# Use Vec\nSee `context/gotchas/head-tail.md`.")
+            .unwrap();
+        ctx.write_entry("decisions", "ring-buffer", "---\nsupersedes: [decisions/use-vec]\n---\n# Ring buffer\nx")
+            .unwrap();
+        ctx.write_entry("gotchas", "head-tail", "---\nrelated: [decisions/ring-buffer]\n---\n# Head and tail\ny")
+            .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn obsidian_wikilinks() {
+        let tmp = setup();
+        let out = tmp.path().join("vault");
+        assert_eq!(export_obsidian(tmp.path(), &out).unwrap(), 3);
+
+        let use_vec = fs::read_to_string(out.join("decisions/use-vec.md")).unwrap();
+        assert!(use_vec.starts_with("---\ntags: [perf]\n---\n# Use Vec\nSee [[gotchas/head-tail]]."), "{use_vec}");
+        assert!(use_vec.ends_with("## Links\n\n- Superseded by: [[decisions/ring-buffer]]\n"), "{use_vec}");
+        let head_tail = fs::read_to_string(out.join("gotchas/head-tail.md")).unwrap();
+        assert!(head_tail.contains("- Related: [[decisions/ring-buffer]]"));
+        let index = fs::read_to_string(out.join("INDEX.md")).unwrap();
+        assert!(index.contains("## Decisions\n\n- [[decisions/ring-buffer|ring-buffer]]: Ring buffer\n"));
+    }
+
+    #[test]
+    fn mdbook_summary_and_chapters() {
+        let tmp = setup();
+        let out = tmp.path().join("book");
+        assert_eq!(export_mdbook(tmp.path(), &out, "demo context").unwrap(), 3);
+
+        let summary = fs::read_to_string(out.join("src/SUMMARY.md")).unwrap();
+        assert_eq!(
+            summary,
+            "# Summary\n\n[Introduction](README.md)\n\n# Decisions\n\n- [Ring buffer](decisions/ring-buffer.md)\n- [Use Vec](decisions/use-vec.md)\n\n# Gotchas\n\n- [Head and tail](gotchas/head-tail.md)\n"
+        );
+        let use_vec = fs::read_to_string(out.join("src/decisions/use-vec.md")).unwrap();
+        assert!(
+            use_vec.starts_with("# Use Vec\n\n> tags: perf\n\nSee [gotchas/head-tail](../gotchas/head-tail.md)."),
+            "{use_vec}"
+        );
+        assert!(!use_vec.contains("---"));
+        let book = fs::read_to_string(out.join("book.toml")).unwrap();
+        assert_eq!(book, "[book]\ntitle = \"demo context\"\nsrc = \"src\"\n");
+    }
+}
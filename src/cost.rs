@@ -0,0 +1,391 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::features::{FeatureList, FeatureType};
+use crate::transcripts::{self, SessionMetrics, SessionRole, TokenUsage, TranscriptMetrics};
+
+/// USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, tokens: &TokenUsage) -> f64 {
+        (tokens.input as f64 * self.input
+            + tokens.output as f64 * self.output
+            + tokens.cache_creation as f64 * self.cache_write
+            + tokens.cache_read as f64 * self.cache_read)
+            / 1_000_000.0
+    }
+}
+
+/// List prices by model family, matched against the model id in the transcript
+/// (e.g. `claude-sonnet-4-5-20250929`). Newer generations first where the
+/// family name alone is ambiguous.
+const PRICES: &[(&str, ModelPrice)] = &[
+    ("opus-4-5", ModelPrice { input: 5.0, output: 25.0, cache_write: 6.25, cache_read: 0.5 }),
+    ("opus", ModelPrice { input: 15.0, output: 75.0, cache_write: 18.75, cache_read: 1.5 }),
+    ("sonnet", ModelPrice { input: 3.0, output: 15.0, cache_write: 3.75, cache_read: 0.3 }),
+    ("haiku-4", ModelPrice { input: 1.0, output: 5.0, cache_write: 1.25, cache_read: 0.1 }),
+    ("haiku", ModelPrice { input: 0.8, output: 4.0, cache_write: 1.0, cache_read: 0.08 }),
+];
+
+pub fn model_price(model: &str) -> Option<ModelPrice> {
+    let model = model.to_ascii_lowercase();
+    PRICES
+        .iter()
+        .find(|(family, _)| model.contains(family))
+        .map(|(_, price)| *price)
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SessionCost {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub tokens: TokenUsage,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct FeatureCost {
+    pub cost_usd: f64,
+    pub tokens: TokenUsage,
+    pub sessions: Vec<SessionCost>,
+}
+
+impl FeatureCost {
+    fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+/// Feature → sessions → tokens/dollars. Executor sessions are attributed by
+/// the feature named in their prompt; review sessions are kept apart since
+/// one review covers several features.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CostReport {
+    pub total_usd: f64,
+    pub features: BTreeMap<String, FeatureCost>,
+    /// Review feature label (e.g. "M4") → cost of it and everything it depends on.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub milestones: BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "FeatureCost::is_empty")]
+    pub orchestration: FeatureCost,
+    #[serde(skip_serializing_if = "FeatureCost::is_empty")]
+    pub unattributed: FeatureCost,
+    /// Models with no known price; their tokens are counted but cost $0.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub unpriced_models: BTreeSet<String>,
+}
+
+pub fn build_cost_report(metrics: &TranscriptMetrics, features: Option<&FeatureList>) -> CostReport {
+    let mut report = CostReport::default();
+    for session in &metrics.sessions {
+        let entry = SessionCost {
+            session_id: session.session_id.clone(),
+            model: session.model.clone(),
+            tokens: session.tokens.clone(),
+            cost_usd: session.cost_usd,
+        };
+        let bucket = match (&session.role, &session.feature_id) {
+            (SessionRole::Executor, Some(id)) => report.features.entry(id.clone()).or_default(),
+            (SessionRole::Orchestrating, _) => &mut report.orchestration,
+            _ => &mut report.unattributed,
+        };
+        bucket.cost_usd += entry.cost_usd;
+        bucket.tokens.add(&entry.tokens);
+        bucket.sessions.push(entry);
+
+        report.total_usd += session.cost_usd;
+        report.unpriced_models.extend(session.unpriced_models.iter().cloned());
+    }
+    if let Some(list) = features {
+        report.milestones = milestone_costs(list, &report.features);
+    }
+    report
+}
+
+fn milestone_costs(list: &FeatureList, costs: &BTreeMap<String, FeatureCost>) -> BTreeMap<String, f64> {
+    milestone_members(list)
+        .into_iter()
+        .map(|(label, members)| {
+            let total: f64 = members.iter().filter_map(|id| costs.get(id)).map(|c| c.cost_usd).sum();
+            (label, total)
+        })
+        .collect()
+}
+
+/// Review feature label → the review and every feature it depends on,
+/// directly or not.
+fn milestone_members(list: &FeatureList) -> BTreeMap<String, BTreeSet<String>> {
+    let deps: HashMap<&str, &[String]> = list
+        .features
+        .iter()
+        .map(|f| (f.id.as_str(), f.depends_on.as_slice()))
+        .collect();
+
+    let mut milestones = BTreeMap::new();
+    for review in list.features.iter().filter(|f| f.feature_type == FeatureType::Review) {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![review.id.as_str()];
+        while let Some(id) = stack.pop() {
+            if seen.insert(id.to_string()) {
+                stack.extend(deps.get(id).into_iter().flat_map(|d| d.iter().map(String::as_str)));
+            }
+        }
+        milestones.insert(FeatureList::milestone_label(review), seen);
+    }
+    milestones
+}
+
+/// What `forge cost` breaks spend down by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CostGroup {
+    /// The UTC day the session started
+    Day,
+    /// Executor, orchestrating, or unknown
+    Role,
+    /// The model most of the session's messages used
+    Model,
+    /// Review features and everything they depend on
+    Milestone,
+    /// The feature an executor session was assigned
+    Feature,
+}
+
+impl CostGroup {
+    pub fn label(self) -> &'static str {
+        match self {
+            CostGroup::Day => "day",
+            CostGroup::Role => "role",
+            CostGroup::Model => "model",
+            CostGroup::Milestone => "milestone",
+            CostGroup::Feature => "feature",
+        }
+    }
+}
+
+/// One line of a breakdown: the sessions sharing a day, a model, ...
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct SpendRow {
+    pub key: String,
+    pub sessions: usize,
+    pub tokens: TokenUsage,
+    pub cost_usd: f64,
+}
+
+impl SpendRow {
+    fn add(&mut self, session: &SessionMetrics) {
+        self.sessions += 1;
+        self.tokens.add(&session.tokens);
+        self.cost_usd += session.cost_usd;
+    }
+}
+
+/// Spend per `group`, sorted by key. A milestone's row counts its
+/// features' executor sessions, so milestones that share features overlap;
+/// every other grouping adds up to the total.
+pub fn spend_by(metrics: &TranscriptMetrics, features: Option<&FeatureList>, group: CostGroup) -> Vec<SpendRow> {
+    let mut rows: BTreeMap<String, SpendRow> = BTreeMap::new();
+    let mut add = |key: &str, session: &SessionMetrics| {
+        let row = rows.entry(key.to_string()).or_default();
+        row.add(session);
+    };
+    let members = match (group, features) {
+        (CostGroup::Milestone, Some(list)) => milestone_members(list),
+        _ => BTreeMap::new(),
+    };
+    for session in &metrics.sessions {
+        let executor_feature = match (&session.role, &session.feature_id) {
+            (SessionRole::Executor, Some(id)) => Some(id.as_str()),
+            _ => None,
+        };
+        match group {
+            CostGroup::Day => add(session_day(session).unwrap_or("unknown"), session),
+            CostGroup::Role => add(role_label(&session.role), session),
+            CostGroup::Model => add(session.model.as_deref().unwrap_or("unknown"), session),
+            CostGroup::Feature => add(
+                executor_feature.unwrap_or(match session.role {
+                    SessionRole::Orchestrating => "(orchestration)",
+                    _ => "(unattributed)",
+                }),
+                session,
+            ),
+            CostGroup::Milestone => {
+                for (label, ids) in &members {
+                    if executor_feature.is_some_and(|id| ids.contains(id)) {
+                        add(label, session);
+                    }
+                }
+            }
+        }
+    }
+    rows.into_iter().map(|(key, row)| SpendRow { key, ..row }).collect()
+}
+
+/// What the project has spent so far over all its Claude transcripts: the
+/// ledger `[forge] max_total_cost` is held to. A transcript that can't be
+/// read counts for nothing.
+pub fn total_spent(project_dir: &Path) -> f64 {
+    transcripts::claude_transcripts(project_dir)
+        .iter()
+        .filter_map(|path| transcripts::analyze_session(path).ok())
+        .fold(0.0, |total, session| total + session.cost_usd)
+}
+
+/// `YYYY-MM-DD` of the session's first message.
+pub fn session_day(session: &SessionMetrics) -> Option<&str> {
+    session.started_at.as_deref().and_then(|t| t.get(..10))
+}
+
+fn role_label(role: &SessionRole) -> &'static str {
+    match role {
+        SessionRole::Executor => "executor",
+        SessionRole::Orchestrating => "orchestrating",
+        SessionRole::Unknown => "unknown",
+    }
+}
+
+/// `group,key,sessions,...` with one line per row, for spreadsheets.
+pub fn spend_csv(groups: &[(CostGroup, Vec<SpendRow>)]) -> String {
+    let mut out = String::from(
+        "group,key,sessions,input_tokens,output_tokens,cache_write_tokens,cache_read_tokens,cost_usd\n",
+    );
+    for (group, rows) in groups {
+        for row in rows {
+            let key = if row.key.contains([',', '"', '\n']) {
+                format!("\"{}\"", row.key.replace('"', "\"\""))
+            } else {
+                row.key.clone()
+            };
+            let t = &row.tokens;
+            out.push_str(&format!(
+                "{},{key},{},{},{},{},{},{:.4}\n",
+                group.label(),
+                row.sessions,
+                t.input,
+                t.output,
+                t.cache_creation,
+                t.cache_read,
+                row.cost_usd
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_by_family() {
+        let sonnet = model_price("claude-sonnet-4-5-20250929").unwrap();
+        assert_eq!(sonnet.input, 3.0);
+        assert_eq!(model_price("claude-opus-4-5-20251101").unwrap().input, 5.0);
+        assert_eq!(model_price("claude-opus-4-1-20250805").unwrap().input, 15.0);
+        assert_eq!(model_price("claude-haiku-4-5").unwrap().output, 5.0);
+        assert!(model_price("gpt-5-codex").is_none());
+
+        let tokens = TokenUsage {
+            input: 1_000_000,
+            output: 100_000,
+            cache_creation: 0,
+            cache_read: 2_000_000,
+        };
+        // 3.00 + 1.50 + 0.60
+        assert!((sonnet.cost(&tokens) - 5.1).abs() < 1e-9);
+    }
+
+    fn session(id: &str, role: SessionRole, feature: Option<&str>, cost: f64) -> SessionMetrics {
+        SessionMetrics {
+            session_id: id.into(),
+            role,
+            feature_id: feature.map(String::from),
+            cost_usd: cost,
+            tokens: TokenUsage {
+                output: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn attributes_sessions_and_rolls_up_milestones() {
+        let metrics = TranscriptMetrics {
+            sessions: vec![
+                session("s1", SessionRole::Executor, Some("f1"), 2.0),
+                session("s2", SessionRole::Executor, Some("f1"), 1.0),
+                session("s3", SessionRole::Executor, Some("f2"), 4.0),
+                session("s4", SessionRole::Orchestrating, None, 0.5),
+                session("s5", SessionRole::Unknown, None, 0.25),
+            ],
+            ..Default::default()
+        };
+        let list: FeatureList = serde_json::from_str(
+            r#"{"features":[
+                {"id":"f1","type":"implement","scope":"a","description":"x","verify":"true"},
+                {"id":"f2","type":"implement","scope":"a","description":"y","verify":"true","depends_on":["f1"]},
+                {"id":"r1","type":"review","scope":"a","description":"M1 review","verify":"true","depends_on":["f2"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let report = build_cost_report(&metrics, Some(&list));
+        assert_eq!(report.total_usd, 7.75);
+        assert_eq!(report.features["f1"].cost_usd, 3.0);
+        assert_eq!(report.features["f1"].sessions.len(), 2);
+        assert_eq!(report.features["f1"].tokens.output, 20);
+        assert_eq!(report.features["f2"].cost_usd, 4.0);
+        assert_eq!(report.orchestration.cost_usd, 0.5);
+        assert_eq!(report.unattributed.sessions[0].session_id, "s5");
+        assert_eq!(report.milestones.get("M1"), Some(&7.0));
+    }
+
+    #[test]
+    fn spend_groups_and_csv() {
+        let mut sessions = vec![
+            session("s1", SessionRole::Executor, Some("f1"), 2.0),
+            session("s2", SessionRole::Executor, Some("f2"), 1.0),
+            session("s3", SessionRole::Orchestrating, None, 0.5),
+        ];
+        for (s, (day, model)) in sessions.iter_mut().zip([
+            ("2026-03-01T10:00:00+00:00", "claude-sonnet-4-5"),
+            ("2026-03-02T09:00:00+00:00", "claude-opus-4-5"),
+            ("2026-03-02T11:00:00+00:00", "claude-sonnet-4-5"),
+        ]) {
+            s.started_at = Some(day.into());
+            s.model = Some(model.into());
+        }
+        let metrics = TranscriptMetrics { sessions, ..Default::default() };
+        let list: FeatureList = serde_json::from_str(
+            r#"{"features":[
+                {"id":"f1","type":"implement","scope":"a","description":"x","verify":"true"},
+                {"id":"r1","type":"review","scope":"a","description":"M1 review","verify":"true","depends_on":["f1"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let keys = |group| -> Vec<(String, usize, f64)> {
+            spend_by(&metrics, Some(&list), group).into_iter().map(|r| (r.key, r.sessions, r.cost_usd)).collect()
+        };
+        assert_eq!(keys(CostGroup::Day), [("2026-03-01".into(), 1, 2.0), ("2026-03-02".into(), 2, 1.5)]);
+        assert_eq!(keys(CostGroup::Role), [("executor".into(), 2, 3.0), ("orchestrating".into(), 1, 0.5)]);
+        assert_eq!(keys(CostGroup::Model)[1], ("claude-sonnet-4-5".into(), 2, 2.5));
+        assert_eq!(keys(CostGroup::Milestone), [("M1".into(), 1, 2.0)]);
+        assert_eq!(keys(CostGroup::Feature)[0], ("(orchestration)".into(), 1, 0.5));
+
+        let csv = spend_csv(&[(CostGroup::Role, spend_by(&metrics, None, CostGroup::Role))]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("group,key,sessions,input_tokens,") && lines[0].ends_with(",cost_usd"));
+        assert_eq!(lines[1], "role,executor,2,0,20,0,0,3.0000");
+        assert_eq!(lines.len(), 3);
+    }
+}
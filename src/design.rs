@@ -0,0 +1,64 @@
+/// DESIGN.md's sections in order, with what each should hold. Goals through
+/// Milestones frame the plan; the rest are the planning skill's
+/// COVERAGE.md checklist.
+pub const SECTIONS: &[(&str, &str)] = &[
+    ("Goals", "What must be true when this is done, each checkable by a verify script."),
+    ("Non-Goals", "What this project won't do, so no agent builds it."),
+    (
+        "Milestones",
+        "M1, M2, ...: what works after each. Each becomes a review feature gating the features before it.",
+    ),
+    ("Data Model", "Structs and enums with their fields, in concrete types."),
+    ("API Surface", "Function signatures, endpoints, traits: the contracts between scopes."),
+    ("Error Strategy", "The error type, how errors propagate, what gets logged and what gets returned."),
+    ("State & Storage", "Where data lives: schema, migrations, connection handling."),
+    ("Dependencies", "Libraries with versions, and why each one."),
+    ("Constraints", "What not to do: limits on complexity, approaches ruled out."),
+    ("Examples", "One complete request/response cycle and one complete test, in the project's style."),
+    (
+        "Unknowns",
+        "Open questions that need a POC or research, as checkboxes (`[ ]` open, `[x]` resolved, \
+         `[!]` pivoted), each pointing at its POC feature: `[ ] Can nom parse our IDL? → p001`.",
+    ),
+];
+
+/// Marks what's left to write; the planning skill treats a DESIGN.md that
+/// still has these as unfinished.
+pub const TODO: &str = "TODO:";
+
+/// A DESIGN.md skeleton: every section, with a TODO saying what it needs.
+/// `known` puts content already known (by section title) ahead of its TODO.
+pub fn skeleton(name: &str, summary: &str, known: &[(&str, String)]) -> String {
+    let summary = summary.trim();
+    let mut out = format!("# {name}\n\n");
+    if summary.is_empty() {
+        out.push_str(&format!("{TODO} one paragraph on what this is and who it's for.\n"));
+    } else {
+        out.push_str(summary);
+        out.push('\n');
+    }
+    for (title, needs) in SECTIONS {
+        out.push_str(&format!("\n## {title}\n\n"));
+        for (_, content) in known.iter().filter(|(t, _)| t == title) {
+            out.push_str(content.trim_end());
+            out.push_str("\n\n");
+        }
+        out.push_str(&format!("{TODO} {needs}\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skeleton_has_every_section_in_order() {
+        let design = skeleton("orders", "Order service for the shop.", &[("Dependencies", "- axum 0.7".into())]);
+        assert!(design.starts_with("# orders\n\nOrder service for the shop.\n\n## Goals\n\nTODO: "), "{design}");
+        let headings: Vec<&str> = design.lines().filter_map(|l| l.strip_prefix("## ")).collect();
+        assert_eq!(headings, SECTIONS.iter().map(|(t, _)| *t).collect::<Vec<_>>());
+        assert!(design.contains("## Dependencies\n\n- axum 0.7\n\nTODO: Libraries"), "{design}");
+        assert_eq!(design.matches(TODO).count(), SECTIONS.len());
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::ForgeConfig;
+use crate::config_validate;
+use crate::context_flow;
+use crate::events::{self, Event, EventKind};
+use crate::features::{Feature, FeatureList, FeatureStatus};
+use crate::git;
+use crate::init;
+
+/// The outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// One thing `forge doctor` looked at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to run or change when it isn't `Ok`
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn problem(name: &'static str, status: Status, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// Check that the project in `project_dir` has what forge needs.
+pub fn check_project(project_dir: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    checks.push(match ForgeConfig::load(project_dir) {
+        Ok(config) => Check::ok("forge.toml", format!("project {}", config.project.name)),
+        Err(e) => Check::problem(
+            "forge.toml",
+            Status::Fail,
+            e.to_string(),
+            "fix the file, or `forge init --force <description>` to rewrite it",
+        ),
+    });
+
+    checks.push(match FeatureList::load(project_dir) {
+        Ok(list) => match feature_problems(&list).as_slice() {
+            [] => Check::ok("features.json", format!("{} features", list.features.len())),
+            problems => Check::problem("features.json", Status::Fail, problems.join("; "), "fix the list by hand"),
+        },
+        Err(e) => Check::problem("features.json", Status::Fail, e.to_string(), "fix the JSON by hand"),
+    });
+
+    let missing = init::missing_dirs(project_dir);
+    checks.push(if missing.is_empty() {
+        Check::ok("directories", "all present")
+    } else {
+        let names: Vec<String> = missing.iter().map(|d| d.display().to_string()).collect();
+        Check::problem("directories", Status::Fail, format!("missing {}", names.join(", ")), "`forge doctor --fix`")
+    });
+
+    let skills = [".claude/skills", ".agents/skills"];
+    let absent: Vec<&str> =
+        skills.into_iter().filter(|base| !project_dir.join(base).join("forge-protocol/SKILL.md").exists()).collect();
+    checks.push(if absent.is_empty() {
+        Check::ok("skills", "installed")
+    } else {
+        Check::problem("skills", Status::Warn, format!("not installed in {}", absent.join(", ")), "`forge install`")
+    });
+
+    if let Some(check) = tracked_runtime(project_dir) {
+        checks.push(check);
+    }
+
+    for file in ["CLAUDE.md", "AGENTS.md"] {
+        checks.push(if project_dir.join(file).exists() {
+            Check::ok(file, "present")
+        } else {
+            Check::problem(file, Status::Warn, "missing", "`forge install`")
+        });
+    }
+
+    checks
+}
+
+/// What would stop the list from ever finishing: ids used twice,
+/// dependencies on ids that don't exist, and dependency cycles.
+pub(crate) fn feature_problems(list: &FeatureList) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut ids = BTreeSet::new();
+    let duplicates: BTreeSet<&str> =
+        list.features.iter().map(|f| f.id.as_str()).filter(|id| !ids.insert(*id)).collect();
+    if !duplicates.is_empty() {
+        problems.push(format!("duplicate ids {}", duplicates.into_iter().collect::<Vec<_>>().join(", ")));
+    }
+    let unknown: BTreeSet<String> = list
+        .features
+        .iter()
+        .flat_map(|f| {
+            let unknown = f.depends_on.iter().filter(|d| !ids.contains(d.as_str()));
+            unknown.map(move |d| format!("{} -> {d}", f.id))
+        })
+        .collect();
+    if !unknown.is_empty() {
+        problems.push(format!("unknown dependencies {}", unknown.into_iter().collect::<Vec<_>>().join(", ")));
+    }
+
+    // Peel off features whose dependencies are all peeled; what's left waits on a cycle
+    let mut resolved: BTreeSet<&str> = BTreeSet::new();
+    loop {
+        let next: Vec<&str> = list
+            .features
+            .iter()
+            .filter(|f| !resolved.contains(f.id.as_str()))
+            .filter(|f| f.depends_on.iter().all(|d| resolved.contains(d.as_str()) || !ids.contains(d.as_str())))
+            .map(|f| f.id.as_str())
+            .collect();
+        if next.is_empty() {
+            break;
+        }
+        resolved.extend(next);
+    }
+    let cyclic: Vec<&str> = ids.iter().copied().filter(|id| !resolved.contains(id)).collect();
+    if !cyclic.is_empty() {
+        problems.push(format!("dependency cycle through {}", cyclic.join(", ")));
+    }
+    problems
+}
+
+/// Check what forge runs on: the role backends, git, worktrees and claims
+/// left behind by earlier runs, disk space under `.forge/` and cocoindex.
+pub fn check_environment(project_dir: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let config = ForgeConfig::load(project_dir).ok();
+
+    if let Some(config) = &config {
+        let mut backends: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for &role in config_validate::ROLES {
+            if let Some(spec) = config.forge.roles.get(role) {
+                backends.entry(spec.backend.as_str()).or_default().push(role);
+            }
+        }
+        for (backend, roles) in backends {
+            let command = config.backends.get(backend).map_or(backend, |def| def.command(backend));
+            checks.push(backend_check(backend, command, &roles));
+        }
+    }
+
+    let Some(version) = version("git") else {
+        checks.push(Check::problem(
+            "git",
+            Status::Fail,
+            "not found",
+            "install git (https://git-scm.com/downloads)",
+        ));
+        return checks;
+    };
+    checks.push(Check::ok("git", version));
+    let git_dir = git::run(project_dir, &["rev-parse", "--absolute-git-dir"]).ok();
+    checks.push(match &git_dir {
+        None => Check::problem(
+            "repository",
+            Status::Warn,
+            "not a git repository; agents work in worktrees and merge back",
+            "`git init && git add -A && git commit -m \"Initial commit\"`",
+        ),
+        Some(dir) => repo_health(Path::new(dir.trim())),
+    });
+    if git_dir.is_some() {
+        checks.push(worktrees(project_dir));
+    }
+
+    checks.push(stale_claims(project_dir, Utc::now()));
+    checks.push(disk(project_dir));
+    checks.push(if context_flow::cocoindex_available() {
+        Check::ok("cocoindex", "installed")
+    } else {
+        Check::problem(
+            "cocoindex",
+            Status::Warn,
+            "not installed; context packages use the built-in builder, without file maps",
+            "`pip install cocoindex`",
+        )
+    });
+    checks
+}
+
+/// A claim with no event from its agent for this long is stale, even if
+/// the last one wasn't the end of a session (forge itself was killed).
+const STALE_CLAIM_HOURS: i64 = 2;
+
+/// Warn once `.forge/` grows past this, or the disk has less than it free.
+const DISK_WARN_BYTES: u64 = 1 << 30;
+
+fn backend_check(backend: &str, command: &str, roles: &[&str]) -> Check {
+    let used_by = roles.join(", ");
+    match version(command) {
+        Some(version) => Check::ok("backend", format!("{backend}: {version} ({used_by})")),
+        None => {
+            let fix = match backend {
+                "claude" => "`npm install -g @anthropic-ai/claude-code`".to_string(),
+                "codex" => "`npm install -g @openai/codex`".to_string(),
+                _ => format!("install `{command}`, or fix `command` in [backends.{backend}]"),
+            };
+            Check::problem("backend", Status::Fail, format!("{backend}: `{command}` not found ({used_by})"), fix)
+        }
+    }
+}
+
+/// First line of `<program> --version`, or None if it won't run.
+fn version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(text.lines().next().map(str::trim).filter(|l| !l.is_empty()).unwrap_or("installed").to_string())
+}
+
+/// A merge or rebase left half done blocks every merge back.
+fn repo_health(git_dir: &Path) -> Check {
+    let pending = [
+        ("MERGE_HEAD", "a merge", "finish it with `git commit`, or `git merge --abort`"),
+        ("rebase-merge", "a rebase", "`git rebase --continue` or `git rebase --abort`"),
+        ("rebase-apply", "a rebase", "`git rebase --continue` or `git rebase --abort`"),
+        ("CHERRY_PICK_HEAD", "a cherry-pick", "`git cherry-pick --continue` or `git cherry-pick --abort`"),
+    ];
+    match pending.into_iter().find(|(marker, ..)| git_dir.join(marker).exists()) {
+        Some((_, what, fix)) => Check::problem("repository", Status::Fail, format!("{what} is in progress"), fix),
+        None => Check::ok("repository", "clean of merges and rebases"),
+    }
+}
+
+/// What's under `.forge/worktrees/` against what git has registered.
+pub(crate) struct WorktreeScan {
+    pub agents: usize,
+    /// Directories git doesn't know: a crashed run left them.
+    pub unregistered: Vec<PathBuf>,
+    /// Worktrees git knows whose directory is gone.
+    pub prunable: usize,
+}
+
+pub(crate) fn scan_worktrees(project_dir: &Path) -> WorktreeScan {
+    let base = project_dir.join(".forge/worktrees");
+    let listing = git::run(project_dir, &["worktree", "list", "--porcelain"]).unwrap_or_default();
+    let mut registered = BTreeSet::new();
+    let mut prunable = 0;
+    for line in listing.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            registered.insert(Path::new(path).canonicalize().unwrap_or_else(|_| PathBuf::from(path)));
+        } else if line.starts_with("prunable") {
+            prunable += 1;
+        }
+    }
+    let agents: Vec<PathBuf> =
+        std::fs::read_dir(&base).into_iter().flatten().flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+    let unregistered = agents
+        .iter()
+        .filter(|p| !registered.contains(&p.canonicalize().unwrap_or_else(|_| p.to_path_buf())))
+        .cloned()
+        .collect();
+    WorktreeScan { agents: agents.len(), unregistered, prunable }
+}
+
+fn worktrees(project_dir: &Path) -> Check {
+    let scan = scan_worktrees(project_dir);
+    let dangling: Vec<String> = scan
+        .unregistered
+        .iter()
+        .map(|p| format!(".forge/worktrees/{}", p.file_name().unwrap_or_default().to_string_lossy()))
+        .collect();
+    if !dangling.is_empty() {
+        Check::problem(
+            "worktrees",
+            Status::Warn,
+            format!("not registered with git: {}", dangling.join(", ")),
+            "`forge clean`",
+        )
+    } else if scan.prunable > 0 {
+        Check::problem(
+            "worktrees",
+            Status::Warn,
+            format!("{} registered with their directory gone", scan.prunable),
+            "`git worktree prune`",
+        )
+    } else {
+        Check::ok("worktrees", format!("{} agent worktrees", scan.agents))
+    }
+}
+
+/// Claimed features whose agent is done with them: its last event in
+/// `timeline` ended a session, or was more than `STALE_CLAIM_HOURS` before
+/// `now`, or it has none.
+pub(crate) fn expired_claims<'a>(list: &'a FeatureList, timeline: &[Event], now: DateTime<Utc>) -> Vec<&'a Feature> {
+    list.features
+        .iter()
+        .filter(|f| f.status == FeatureStatus::Claimed)
+        .filter(|f| {
+            let agent = f.claimed_by.as_deref().unwrap_or_default();
+            match timeline.iter().rev().find(|e| e.agent == agent) {
+                Some(last) => {
+                    matches!(last.kind, EventKind::SessionEnd { .. })
+                        || now - last.ts > Duration::hours(STALE_CLAIM_HOURS)
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+fn stale_claims(project_dir: &Path, now: DateTime<Utc>) -> Check {
+    let Ok(list) = FeatureList::load(project_dir) else {
+        return Check::ok("claims", "no feature list");
+    };
+    let timeline = events::merged_timeline(project_dir).unwrap_or_default();
+    let stale: Vec<String> = expired_claims(&list, &timeline, now)
+        .iter()
+        .map(|f| format!("{} ({})", f.id, f.claimed_by.as_deref().unwrap_or("no agent")))
+        .collect();
+    if stale.is_empty() {
+        Check::ok("claims", "none stale")
+    } else {
+        Check::problem(
+            "claims",
+            Status::Warn,
+            format!("claimed with no session working on them: {}", stale.join(", ")),
+            "with no `forge run` going, `forge clean --release-claims` sets them back to pending",
+        )
+    }
+}
+
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Free bytes on the disk holding `path`, from `df -Pk`.
+fn free_space(path: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok().filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let kib: u64 = text.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn disk(project_dir: &Path) -> Check {
+    let runtime = project_dir.join(".forge");
+    let used = dir_size(&runtime);
+    let free = free_space(if runtime.exists() { &runtime } else { project_dir });
+    let mut detail = format!(".forge/ holds {}", human_bytes(used));
+    if let Some(free) = free {
+        detail.push_str(&format!(", {} free", human_bytes(free)));
+    }
+    if free.is_some_and(|f| f < DISK_WARN_BYTES) {
+        Check::problem("disk", Status::Warn, detail, "free up space; `forge clean` prunes old runtime state")
+    } else if used > DISK_WARN_BYTES {
+        Check::problem("disk", Status::Warn, detail, "`forge clean --dry-run` to see what can go")
+    } else {
+        Check::ok("disk", detail)
+    }
+}
+
+pub(crate) fn human_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GiB", b as f64 / (1u64 << 30) as f64),
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1u64 << 20) as f64),
+        b => format!("{} KiB", b / 1024),
+    }
+}
+
+/// Files under `.forge/` that git tracks though `[git] commit_runtime`
+/// doesn't list them; `None` outside a git repo.
+fn tracked_runtime(project_dir: &Path) -> Option<Check> {
+    let listing = git::run(project_dir, &["ls-files", "-z", "--", ".forge"]).ok()?;
+    let keep = ForgeConfig::load(project_dir).map(|c| c.git.commit_runtime).unwrap_or_default();
+    // Top-level entries, as commit_runtime names them
+    let tracked: BTreeSet<&str> = listing
+        .split('\0')
+        .filter_map(|path| path.strip_prefix(".forge/"))
+        .filter_map(|path| path.split('/').next())
+        .filter(|top| !keep.iter().any(|k| k.trim_matches('/') == *top))
+        .collect();
+    Some(if tracked.is_empty() {
+        Check::ok("runtime state", "not committed")
+    } else {
+        let paths: Vec<String> = tracked.iter().map(|t| format!(".forge/{t}")).collect();
+        Check::problem(
+            "runtime state",
+            Status::Warn,
+            format!("committed: {}", paths.join(", ")),
+            format!("`git rm -r --cached {}`", paths.join(" ")),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_dirs_and_broken_features() {
+        let dir = tempfile::tempdir().unwrap();
+        init::init_project(dir.path(), "test", false).unwrap();
+        assert!(check_project(dir.path()).iter().all(|c| c.status == Status::Ok), "{:?}", check_project(dir.path()));
+
+        std::fs::remove_dir_all(dir.path().join("feedback")).unwrap();
+        std::fs::write(dir.path().join("features.json"), "{").unwrap();
+        let checks = check_project(dir.path());
+        let failing: Vec<&str> = checks.iter().filter(|c| c.status == Status::Fail).map(|c| c.name).collect();
+        assert_eq!(failing, ["features.json", "directories"]);
+        let dirs = checks.iter().find(|c| c.name == "directories").unwrap();
+        assert_eq!(dirs.detail, "missing feedback, feedback/exec-memory");
+
+        init::create_missing_dirs(dir.path()).unwrap();
+        assert!(init::missing_dirs(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn flags_bad_dependencies_stale_claims_and_dangling_worktrees() {
+        let dir = tempfile::tempdir().unwrap();
+        init::init_project(dir.path(), "test", false).unwrap();
+        let list: FeatureList = serde_json::from_str(
+            r#"{"features":[
+                {"id":"f001","type":"implement","scope":"a","description":"x","verify":"true","depends_on":["f002"]},
+                {"id":"f002","type":"implement","scope":"a","description":"x","verify":"true","depends_on":["f001"]},
+                {"id":"f003","type":"implement","scope":"a","description":"x","verify":"true","depends_on":["f009"],
+                 "status":"claimed","claimed_by":"agent-1"},
+                {"id":"f004","type":"implement","scope":"a","description":"x","verify":"true",
+                 "status":"claimed","claimed_by":"agent-2"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            feature_problems(&list),
+            ["unknown dependencies f003 -> f009", "dependency cycle through f001, f002"]
+        );
+
+        list.save(dir.path()).unwrap();
+        events::emit(dir.path(), "agent-1", EventKind::Claim { feature_id: "f003".into() });
+        events::emit(dir.path(), "agent-2", EventKind::Claim { feature_id: "f004".into() });
+        let end = EventKind::SessionEnd {
+            feature_id: Some("f004".into()),
+            exit_code: Some(0),
+            timed_out: false,
+            output_bytes: None,
+        };
+        events::emit(dir.path(), "agent-2", end);
+        let claims = stale_claims(dir.path(), Utc::now());
+        assert_eq!(claims.detail, "claimed with no session working on them: f004 (agent-2)");
+        let later = stale_claims(dir.path(), Utc::now() + Duration::hours(3));
+        assert!(later.detail.ends_with("f003 (agent-1), f004 (agent-2)"), "{}", later.detail);
+
+        let run = |args: &[&str]| Command::new("git").args(args).current_dir(dir.path()).output().unwrap();
+        run(&["init", "-q"]);
+        run(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "--allow-empty", "-m", "init"]);
+        run(&["worktree", "add", "-q", ".forge/worktrees/agent-1"]);
+        assert_eq!(worktrees(dir.path()).status, Status::Ok);
+        std::fs::create_dir_all(dir.path().join(".forge/worktrees/agent-3")).unwrap();
+        assert_eq!(worktrees(dir.path()).detail, "not registered with git: .forge/worktrees/agent-3");
+    }
+
+    #[test]
+    fn warns_about_committed_runtime_state() {
+        let dir = tempfile::tempdir().unwrap();
+        init::init_project(dir.path(), "test", false).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git").args(args).current_dir(dir.path()).output().unwrap()
+        };
+        git(&["init", "-q"]);
+        for file in [".forge/logs/agent-1.log", ".forge/events/forge.jsonl"] {
+            std::fs::create_dir_all(dir.path().join(file).parent().unwrap()).unwrap();
+            std::fs::write(dir.path().join(file), "x\n").unwrap();
+            git(&["add", "-f", file]);
+        }
+        let runtime = |checks: Vec<Check>| checks.into_iter().find(|c| c.name == "runtime state").unwrap();
+        assert_eq!(runtime(check_project(dir.path())).detail, "committed: .forge/events, .forge/logs");
+
+        let mut config = ForgeConfig::load(dir.path()).unwrap();
+        config.git.commit_runtime = vec!["events".into()];
+        config.save(dir.path()).unwrap();
+        let check = runtime(check_project(dir.path()));
+        assert_eq!(check.fix.as_deref(), Some("`git rm -r --cached .forge/logs`"));
+    }
+}
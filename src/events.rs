@@ -0,0 +1,380 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::context_budget::BudgetReport;
+use crate::context_reconcile::ReconcileReport;
+
+/// Who records events that no agent owns (verify passes, merges back).
+pub const FORGE: &str = "forge";
+
+/// One line of `.forge/events/<agent>.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Event {
+    pub ts: DateTime<Utc>,
+    pub agent: String,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    SessionStart {
+        role: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+        /// The role's backend and model, for `forge stats models`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backend: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        /// `a` or `b` in an A/B run (`[forge.roles.protocol_b]`).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        variant: Option<String>,
+    },
+    SessionEnd {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        /// Killed for running past the role's `timeout_secs`.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        timed_out: bool,
+        /// Bytes the agent wrote to stdout, the baseline for runaway checks.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_bytes: Option<u64>,
+    },
+    Claim {
+        feature_id: String,
+    },
+    Verify {
+        passed: usize,
+        failed: usize,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        reopened: Vec<String>,
+    },
+    Merge {
+        branch: String,
+        ok: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A branch was pushed and a pull request opened (or reused) for it.
+    PullRequest {
+        branch: String,
+        features: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// An agent changed `[git] protected` paths: its work wasn't merged (or
+    /// was reverted) and the feature reopened.
+    ProtectedPaths {
+        feature_id: String,
+        paths: Vec<String>,
+    },
+    /// A milestone's review feature passed and HEAD was tagged.
+    MilestoneTagged {
+        tag: String,
+        feature_id: String,
+    },
+    /// A merged feature made verify fail for features that passed before
+    /// it: the merge was reverted and the feature reopened.
+    MergeReverted {
+        feature_id: String,
+        regressed: Vec<String>,
+    },
+    /// The secret scan flagged an agent's work (as `path:line (rule)`): it
+    /// wasn't merged and the feature reopened.
+    SecretsFound {
+        feature_id: String,
+        findings: Vec<String>,
+    },
+    /// An agent's work added files over `[git] max_file_mb` (as `path (size)`):
+    /// it wasn't merged and the feature reopened.
+    LargeFiles {
+        feature_id: String,
+        files: Vec<String>,
+    },
+    /// The feature's context package was cut to fit the role's budget.
+    ContextTrimmed {
+        feature_id: String,
+        #[serde(flatten)]
+        report: BudgetReport,
+    },
+    /// Context entries from a merged worktree were carried over to context/.
+    ContextReconciled {
+        branch: String,
+        #[serde(flatten)]
+        report: ReconcileReport,
+    },
+    /// The agent marked its feature blocked.
+    FeatureBlocked {
+        feature_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// The session's exec-memory was missing or short of the schema.
+    HandoffInvalid {
+        feature_id: String,
+        problems: Vec<String>,
+    },
+    /// Verify's verdict on a feature a session just worked on.
+    FeatureVerified {
+        feature_id: String,
+        passed: bool,
+    },
+    /// A milestone's review feature is done and passed verify.
+    MilestoneDone {
+        feature_id: String,
+        milestone: String,
+    },
+    /// The session looked stuck in a loop and was killed, its feature blocked.
+    RunawayKilled {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+        reason: String,
+    },
+    /// The session stopped because it hit its spending limit.
+    BudgetExceeded {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        budget: Option<f64>,
+    },
+    /// The project's spend passed `[forge] max_total_cost`; no new sessions
+    /// start until it's raised.
+    CostLimitReached {
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+    /// `forge run` returned: `all_done`, `max_sessions`, `stopped`,
+    /// `cost_limit` or `error`.
+    RunFinished {
+        outcome: String,
+        sessions: usize,
+        remaining: usize,
+    },
+    /// A backend answered with a rate-limit error: sessions leave it alone
+    /// for `backoff_secs`.
+    RateLimited {
+        backend: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+        backoff_secs: u64,
+    },
+    /// A session whose role is on a backed-off backend ran on its
+    /// `[forge.rate_limits.fallback]` instead.
+    BackendRerouted {
+        from: String,
+        to: String,
+        role: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+    },
+    /// `forge run --chaos` injected a fault: `kill_agent`, `delay_verify`
+    /// or `merge_conflict`.
+    ChaosInjected {
+        fault: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feature_id: Option<String>,
+    },
+    /// An agent left a question in `feedback/questions/`; its feature waits
+    /// on the answer.
+    QuestionAsked {
+        feature_id: String,
+        question: String,
+    },
+    /// `forge questions answer`: the feature is pending again.
+    QuestionAnswered {
+        feature_id: String,
+    },
+}
+
+impl EventKind {
+    /// Features this event is about, for filtering.
+    pub fn feature_ids(&self) -> Vec<&str> {
+        match self {
+            EventKind::SessionStart { feature_id, .. }
+            | EventKind::SessionEnd { feature_id, .. }
+            | EventKind::BudgetExceeded { feature_id, .. }
+            | EventKind::RunawayKilled { feature_id, .. }
+            | EventKind::RateLimited { feature_id, .. }
+            | EventKind::BackendRerouted { feature_id, .. }
+            | EventKind::ChaosInjected { feature_id, .. } => feature_id.iter().map(String::as_str).collect(),
+            EventKind::Claim { feature_id }
+            | EventKind::ContextTrimmed { feature_id, .. }
+            | EventKind::ProtectedPaths { feature_id, .. }
+            | EventKind::SecretsFound { feature_id, .. }
+            | EventKind::LargeFiles { feature_id, .. }
+            | EventKind::MergeReverted { feature_id, .. }
+            | EventKind::MilestoneTagged { feature_id, .. }
+            | EventKind::FeatureBlocked { feature_id, .. }
+            | EventKind::HandoffInvalid { feature_id, .. }
+            | EventKind::FeatureVerified { feature_id, .. }
+            | EventKind::MilestoneDone { feature_id, .. }
+            | EventKind::QuestionAsked { feature_id, .. }
+            | EventKind::QuestionAnswered { feature_id } => {
+                vec![feature_id.as_str()]
+            }
+            EventKind::Verify { reopened, .. } => reopened.iter().map(String::as_str).collect(),
+            EventKind::PullRequest { features, .. } => features.iter().map(String::as_str).collect(),
+            EventKind::Merge { .. }
+            | EventKind::ContextReconciled { .. }
+            | EventKind::CostLimitReached { .. }
+            | EventKind::RunFinished { .. } => Vec::new(),
+        }
+    }
+}
+
+pub fn events_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/events")
+}
+
+/// Append an event to the agent's stream. Best effort: a failed write must
+/// never take down the run it is describing.
+pub fn emit(project_dir: &Path, agent: &str, kind: EventKind) {
+    let event = Event {
+        ts: Utc::now(),
+        agent: agent.to_string(),
+        kind,
+    };
+    let dir = events_dir(project_dir);
+    let _ = std::fs::create_dir_all(&dir);
+    let Ok(line) = serde_json::to_string(&event) else {
+        return;
+    };
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(format!("{agent}.jsonl")))
+    {
+        let _ = writeln!(file, "{line}");
+    }
+    crate::notify::notify(project_dir, &event);
+    crate::plugins::on_event(project_dir, &event);
+}
+
+pub fn emit_merge(project_dir: &Path, branch: &str, result: &Result<(), String>) {
+    emit(
+        project_dir,
+        FORGE,
+        EventKind::Merge {
+            branch: branch.to_string(),
+            ok: result.is_ok(),
+            error: result.as_ref().err().cloned(),
+        },
+    );
+}
+
+/// Read one stream, skipping lines that don't parse (a torn final write).
+pub fn read_stream(path: &Path) -> std::io::Result<Vec<Event>> {
+    let file = std::fs::File::open(path)?;
+    let mut events = Vec::new();
+    for line in std::io::BufReader::new(file).lines() {
+        if let Ok(event) = serde_json::from_str(&line?) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Every agent's stream merged into one list ordered by time. Events with
+/// equal timestamps keep their per-stream order.
+pub fn merged_timeline(project_dir: &Path) -> std::io::Result<Vec<Event>> {
+    let dir = events_dir(project_dir);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect();
+    paths.sort();
+
+    let mut events = Vec::new();
+    for path in paths {
+        events.extend(read_stream(&path)?);
+    }
+    events.sort_by_key(|e| e.ts);
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emit_appends_per_agent() {
+        let tmp = tempfile::tempdir().unwrap();
+        emit(tmp.path(), "agent-1", EventKind::Claim { feature_id: "f1".into() });
+        emit(
+            tmp.path(),
+            "agent-1",
+            EventKind::SessionEnd {
+                feature_id: Some("f1".into()),
+                exit_code: Some(0),
+                timed_out: false,
+                output_bytes: None,
+            },
+        );
+        emit(
+            tmp.path(),
+            FORGE,
+            EventKind::Verify {
+                passed: 1,
+                failed: 0,
+                reopened: vec![],
+            },
+        );
+
+        let stream = read_stream(&events_dir(tmp.path()).join("agent-1.jsonl")).unwrap();
+        assert_eq!(stream.len(), 2);
+        assert_eq!(stream[0].kind, EventKind::Claim { feature_id: "f1".into() });
+
+        let line = std::fs::read_to_string(events_dir(tmp.path()).join("forge.jsonl")).unwrap();
+        assert!(line.contains(r#""event":"verify""#), "{line}");
+        assert!(!line.contains("reopened"));
+    }
+
+    #[test]
+    fn timeline_is_time_ordered_across_agents() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = events_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("agent-1.jsonl"),
+            concat!(
+                r#"{"ts":"2026-03-01T10:00:00Z","agent":"agent-1","event":"claim","feature_id":"f1"}"#, "\n",
+                r#"{"ts":"2026-03-01T10:05:00Z","agent":"agent-1","event":"session_end","feature_id":"f1"}"#, "\n",
+                "{torn",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("agent-2.jsonl"),
+            r#"{"ts":"2026-03-01T10:02:00Z","agent":"agent-2","event":"claim","feature_id":"f2"}"#,
+        )
+        .unwrap();
+
+        let timeline = merged_timeline(tmp.path()).unwrap();
+        let order: Vec<(&str, Vec<&str>)> = timeline
+            .iter()
+            .map(|e| (e.agent.as_str(), e.kind.feature_ids()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![("agent-1", vec!["f1"]), ("agent-2", vec!["f2"]), ("agent-1", vec!["f1"])]
+        );
+    }
+
+    #[test]
+    fn no_events_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(merged_timeline(tmp.path()).unwrap().is_empty());
+    }
+}
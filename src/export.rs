@@ -1,9 +1,15 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::SystemTime;
 
+use crate::cost::CostReport;
 use crate::features::{FeatureList, FeatureStatus};
+use crate::packet::FeaturePacket;
+use crate::redact::Redactor;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ExportError {
@@ -13,6 +19,20 @@ pub enum ExportError {
     Json(#[from] serde_json::Error),
     #[error("not a forge project (missing forge.toml)")]
     NotInitialized,
+    #[error("unsupported archive '{0}' (use .tar.gz, .tgz or .zip)")]
+    UnsupportedArchive(String),
+    #[error("archive '{0}' must not be inside the export directory")]
+    ArchiveInsideExport(String),
+    #[error("zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("invalid date '{0}' (use YYYY-MM-DD or RFC 3339)")]
+    InvalidDate(String),
+    #[error("--since is after --until")]
+    EmptyWindow,
+    #[error("unknown features: {0}")]
+    UnknownFeatures(String),
+    #[error("parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
 }
 
 #[derive(Debug, Serialize)]
@@ -27,6 +47,20 @@ pub struct ExportManifest {
     pub transcripts: Vec<TranscriptInfo>,
     pub git: Option<GitInfo>,
     pub sections: Vec<String>,
+    #[serde(skip_serializing_if = "ExportFilter::is_empty")]
+    pub filter: ExportFilter,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packet: Option<FeaturePacket>,
+    /// Token and dollar spend per feature, from the exported transcripts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<CostReport>,
+    /// Secrets scrubbed from logs, transcripts and git data.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub redactions: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 #[derive(Debug, Serialize)]
@@ -36,11 +70,15 @@ pub struct FeatureSummary {
     pub pending: usize,
     pub claimed: usize,
     pub blocked: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub waiting: usize,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TranscriptInfo {
     pub session_id: String,
+    /// Agent backend that recorded the session, e.g. "claude" or "codex".
+    pub backend: String,
     pub size_bytes: u64,
     pub path: String,
 }
@@ -52,17 +90,225 @@ pub struct GitInfo {
     pub latest_commit: String,
 }
 
+/// Narrows an export to one incident: a time window and/or a set of features.
+/// Applies to transcripts, agent logs, git commits and exec-memory; config and
+/// context are always exported whole.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExportFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+}
+
+impl ExportFilter {
+    /// Build from CLI values. A bare date for `until` covers that whole day.
+    pub fn new(
+        since: Option<&str>,
+        until: Option<&str>,
+        features: Vec<String>,
+    ) -> Result<Self, ExportError> {
+        let since = since.map(|s| parse_time_bound(s, false)).transpose()?;
+        let until = until.map(|s| parse_time_bound(s, true)).transpose()?;
+        if let (Some(s), Some(u)) = (since, until)
+            && s > u
+        {
+            return Err(ExportError::EmptyWindow);
+        }
+        let features = features
+            .into_iter()
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .collect();
+        Ok(ExportFilter {
+            since,
+            until,
+            features,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none() && self.until.is_none() && self.features.is_empty()
+    }
+
+    fn wants_feature(&self, id: &str) -> bool {
+        self.features.is_empty() || self.features.iter().any(|f| f == id)
+    }
+
+    /// Whether free text (a log, a transcript) refers to any selected feature.
+    fn mentions_feature(&self, text: &str) -> bool {
+        self.features.is_empty() || self.features.iter().any(|f| contains_word(text, f))
+    }
+
+    /// A file was touched during the window if it was last written after
+    /// `since` and created before `until`.
+    fn file_in_window(&self, path: &Path) -> bool {
+        let Ok(meta) = std::fs::metadata(path) else {
+            return false;
+        };
+        let at = |t: std::io::Result<SystemTime>| t.ok().map(DateTime::<Utc>::from);
+        if let Some(since) = self.since
+            && at(meta.modified()).is_some_and(|m| m < since)
+        {
+            return false;
+        }
+        if let Some(until) = self.until
+            && at(meta.created()).or(at(meta.modified())).is_some_and(|c| c > until)
+        {
+            return false;
+        }
+        true
+    }
+
+    /// For files whose content has to be checked for feature mentions.
+    fn keeps_text_file(&self, path: &Path) -> bool {
+        if !self.file_in_window(path) {
+            return false;
+        }
+        if self.features.is_empty() {
+            return true;
+        }
+        std::fs::read(path)
+            .map(|bytes| self.mentions_feature(&String::from_utf8_lossy(&bytes)))
+            .unwrap_or(false)
+    }
+
+    /// `feedback/exec-memory/<feature>.json`; other feedback passes through.
+    fn keeps_feedback_file(&self, path: &Path) -> bool {
+        let in_exec_memory = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|n| n == "exec-memory");
+        if !in_exec_memory {
+            return true;
+        }
+        let id = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        self.wants_feature(&id) && self.file_in_window(path)
+    }
+
+    /// Events outside the window are dropped; with a feature filter, so are
+    /// events not tied to a selected feature (merges, verify passes).
+    fn keeps_event(&self, event: &crate::events::Event) -> bool {
+        if self.since.is_some_and(|since| event.ts < since) || self.until.is_some_and(|until| event.ts > until) {
+            return false;
+        }
+        self.features.is_empty() || event.kind.feature_ids().iter().any(|id| self.wants_feature(id))
+    }
+
+    fn git_log_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(since) = self.since {
+            args.push(format!("--since={}", since.to_rfc3339()));
+        }
+        if let Some(until) = self.until {
+            args.push(format!("--until={}", until.to_rfc3339()));
+        }
+        if !self.features.is_empty() {
+            args.push("--fixed-strings".to_string());
+            for f in &self.features {
+                args.push(format!("--grep={f}"));
+            }
+        }
+        args
+    }
+}
+
+impl std::fmt::Display for ExportFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(since) = self.since {
+            parts.push(format!("since {}", since.to_rfc3339()));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("until {}", until.to_rfc3339()));
+        }
+        if !self.features.is_empty() {
+            parts.push(format!("features {}", self.features.join(", ")));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+fn parse_time_bound(s: &str, end_of_day: bool) -> Result<DateTime<Utc>, ExportError> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| ExportError::InvalidDate(s.to_string()))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.expect("valid time of day").and_utc())
+}
+
+/// `id` appears in `text` not as part of a longer identifier (f001 vs f0012).
+fn contains_word(text: &str, id: &str) -> bool {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    text.match_indices(id).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + id.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// What goes into an export.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub include_transcripts: bool,
+    /// Git commits to include in `git/log.txt`.
+    pub git_commits: usize,
+    pub filter: ExportFilter,
+    /// Applied to logs, transcripts and git data; an empty redactor disables it.
+    pub redactor: Redactor,
+    /// Also write a `feature/` debugging packet for this feature. The caller
+    /// narrows `filter` to it so the rest of the export matches.
+    pub packet: Option<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_transcripts: true,
+            git_commits: 100,
+            filter: ExportFilter::default(),
+            redactor: Redactor::new(&[]).expect("built-in redact rules compile"),
+            packet: None,
+        }
+    }
+}
+
 pub fn export_project(
     project_dir: &Path,
     output_dir: &Path,
-    include_transcripts: bool,
-    git_commits: usize,
+    options: &ExportOptions,
 ) -> Result<ExportManifest, ExportError> {
+    let filter = &options.filter;
+    let redactor = &options.redactor;
+    let mut redactions = 0;
+
     // Verify this is a forge project
     if !project_dir.join("forge.toml").exists() {
         return Err(ExportError::NotInitialized);
     }
 
+    // Catch typos before producing a misleadingly empty export
+    let feature_list = FeatureList::load(project_dir).ok();
+    if let Some(list) = &feature_list {
+        let unknown: Vec<&str> = filter
+            .features
+            .iter()
+            .filter(|id| !list.features.iter().any(|f| &f.id == *id))
+            .map(|s| s.as_str())
+            .collect();
+        if !unknown.is_empty() {
+            return Err(ExportError::UnknownFeatures(unknown.join(", ")));
+        }
+    }
+
     // Clean and create output directory
     if output_dir.exists() {
         std::fs::remove_dir_all(output_dir)?;
@@ -76,11 +322,29 @@ pub fn export_project(
         sections.push("config".to_string());
     }
 
-    // Copy features.json
-    copy_if_exists(
-        &project_dir.join("features.json"),
-        &output_dir.join("features.json"),
-    );
+    // Copy features.json, narrowed to the selected features
+    match &feature_list {
+        Some(list) if !filter.features.is_empty() => {
+            let selected = FeatureList {
+                features: list
+                    .features
+                    .iter()
+                    .filter(|f| filter.wants_feature(&f.id))
+                    .cloned()
+                    .collect(),
+            };
+            std::fs::write(
+                output_dir.join("features.json"),
+                serde_json::to_string_pretty(&selected)?,
+            )?;
+        }
+        _ => {
+            copy_if_exists(
+                &project_dir.join("features.json"),
+                &output_dir.join("features.json"),
+            );
+        }
+    }
 
     // Copy agent instruction files
     copy_if_exists(&project_dir.join("CLAUDE.md"), &output_dir.join("CLAUDE.md"));
@@ -90,7 +354,9 @@ pub fn export_project(
     let feedback_src = project_dir.join("feedback");
     if feedback_src.is_dir() {
         let feedback_dst = output_dir.join("feedback");
-        let count = copy_dir_recursive(&feedback_src, &feedback_dst)?;
+        let count = copy_dir_filtered(&feedback_src, &feedback_dst, &|p| {
+            filter.keeps_feedback_file(p)
+        })?;
         if count > 0 {
             sections.push("feedback".to_string());
         }
@@ -123,9 +389,9 @@ pub fn export_project(
         if let Ok(entries) = std::fs::read_dir(&logs_src) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.is_file() {
+                if path.is_file() && filter.keeps_text_file(&path) {
                     let name = entry.file_name().to_string_lossy().to_string();
-                    std::fs::copy(&path, logs_dst.join(&name))?;
+                    redactions += redactor.copy_file(&path, &logs_dst.join(&name))?;
                     log_names.push(name);
                 }
             }
@@ -137,44 +403,87 @@ pub fn export_project(
     log_names.sort();
 
     // Git data
-    let git_info = capture_git_info(project_dir, output_dir, git_commits)?;
+    let git_info = capture_git_info(project_dir, output_dir, options, &mut redactions)?;
     if git_info.is_some() {
         sections.push("git".to_string());
     }
 
+    // Per-agent event streams, merged into one ordered timeline
+    let timeline: Vec<_> = crate::events::merged_timeline(project_dir)?
+        .into_iter()
+        .filter(|e| filter.keeps_event(e))
+        .collect();
+    if !timeline.is_empty() {
+        let mut out = String::new();
+        for event in &timeline {
+            let line = serde_json::to_string(event)?;
+            let (clean, count) = redactor.redact(&line);
+            redactions += count;
+            out.push_str(&clean);
+            out.push('\n');
+        }
+        std::fs::write(output_dir.join("timeline.jsonl"), out)?;
+        sections.push("timeline".to_string());
+    }
+
     // Transcripts
     let mut transcripts = Vec::new();
-    if include_transcripts
-        && let Some(transcript_dir) = find_transcript_dir(project_dir)
-    {
+    let mut cost = None;
+    if options.include_transcripts {
         let transcripts_dst = output_dir.join("transcripts");
-        std::fs::create_dir_all(&transcripts_dst)?;
-
-        if let Ok(entries) = std::fs::read_dir(&transcript_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|e| e.to_str()) == Some("jsonl") && path.is_file() {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
-                    std::fs::copy(&path, transcripts_dst.join(&name))?;
-
-                    let session_id = name.trim_end_matches(".jsonl").to_string();
-                    transcripts.push(TranscriptInfo {
-                        session_id,
-                        size_bytes: size,
-                        path: format!("transcripts/{name}"),
-                    });
+        for source in crate::transcripts::sources() {
+            // Claude sessions stay at the top level, where metrics are read
+            // from; other backends get their own subdirectory
+            let rel = match source.backend() {
+                "claude" => PathBuf::from("transcripts"),
+                backend => Path::new("transcripts").join(backend),
+            };
+            for path in source.discover(project_dir) {
+                if !filter.keeps_text_file(&path) {
+                    continue;
                 }
+                let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                    continue;
+                };
+                std::fs::create_dir_all(output_dir.join(&rel))?;
+                let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                redactions += redactor.copy_file(&path, &output_dir.join(&rel).join(&name))?;
+
+                transcripts.push(TranscriptInfo {
+                    session_id: name.trim_end_matches(".jsonl").to_string(),
+                    backend: source.backend().to_string(),
+                    size_bytes: size,
+                    path: rel.join(&name).to_string_lossy().to_string(),
+                });
             }
         }
         if !transcripts.is_empty() {
             sections.push("transcripts".to_string());
         }
+        // Token accounting understands the Claude transcript format only
+        if transcripts.iter().any(|t| t.backend == "claude") {
+            let metrics = crate::transcripts::analyze_dir(&transcripts_dst)?;
+            std::fs::write(
+                output_dir.join("metrics.json"),
+                serde_json::to_string_pretty(&metrics)?,
+            )?;
+            sections.push("metrics".to_string());
+            cost = Some(crate::cost::build_cost_report(&metrics, feature_list.as_ref()));
+        }
     }
     transcripts.sort_by(|a, b| a.session_id.cmp(&b.session_id));
 
+    let packet = match &options.packet {
+        Some(id) => {
+            let packet = crate::packet::write_feature_packet(project_dir, output_dir, id, options, &mut redactions)?;
+            sections.push("feature".to_string());
+            Some(packet)
+        }
+        None => None,
+    };
+
     // Build feature summary
-    let feature_summary = build_feature_summary(project_dir);
+    let feature_summary = build_feature_summary(project_dir, filter);
 
     // Build context counts
     let context_counts = count_context_entries(project_dir);
@@ -200,6 +509,10 @@ pub fn export_project(
         transcripts,
         git: git_info,
         sections,
+        filter: filter.clone(),
+        packet,
+        cost,
+        redactions,
     };
 
     // Write manifest
@@ -209,6 +522,108 @@ pub fn export_project(
     Ok(manifest)
 }
 
+/// Compressed formats for `forge export --archive`, picked by file extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Result<Self, ExportError> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(ArchiveFormat::TarGz)
+        } else if name.ends_with(".zip") {
+            Ok(ArchiveFormat::Zip)
+        } else {
+            Err(ExportError::UnsupportedArchive(path.display().to_string()))
+        }
+    }
+}
+
+/// Top-level folder inside the archive: the archive's name minus extension,
+/// so extracting `bug-123.tar.gz` yields `bug-123/`.
+fn archive_root(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let lower = name.to_ascii_lowercase();
+    let stem_len = [".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| name.len() - ext.len())
+        .unwrap_or(name.len());
+    match &name[..stem_len] {
+        "" => "forge-export".to_string(),
+        stem => stem.to_string(),
+    }
+}
+
+/// Pack a finished export directory (manifest included) into one archive.
+/// Returns the archive size in bytes.
+pub fn write_archive(export_dir: &Path, archive: &Path) -> Result<u64, ExportError> {
+    let format = ArchiveFormat::from_path(archive)?;
+
+    if let Some(parent) = archive.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        // Checked on the deepest dir that exists yet, so a refusal creates nothing
+        let existing = parent.ancestors().find(|a| !a.as_os_str().is_empty() && a.exists()).unwrap_or(Path::new("."));
+        if let (Ok(existing), Ok(export)) = (existing.canonicalize(), export_dir.canonicalize())
+            && existing.starts_with(&export)
+        {
+            return Err(ExportError::ArchiveInsideExport(archive.display().to_string()));
+        }
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let root = archive_root(archive);
+    let file = std::fs::File::create(archive)?;
+    match format {
+        ArchiveFormat::TarGz => {
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            builder.append_dir_all(&root, export_dir)?;
+            builder.into_inner()?.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+            add_dir_to_zip(&mut zip, export_dir, &root, options)?;
+            zip.finish()?;
+        }
+    }
+    Ok(std::fs::metadata(archive)?.len())
+}
+
+fn add_dir_to_zip(
+    zip: &mut zip::ZipWriter<std::fs::File>,
+    dir: &Path,
+    prefix: &str,
+    options: zip::write::SimpleFileOptions,
+) -> Result<(), ExportError> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)?.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let name = format!("{prefix}/{}", entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            zip.add_directory(name.as_str(), options)?;
+            add_dir_to_zip(zip, &path, &name, options)?;
+        } else if path.is_file() {
+            zip.start_file(name.as_str(), options)?;
+            zip.write_all(&std::fs::read(&path)?)?;
+        }
+    }
+    Ok(())
+}
+
 fn copy_if_exists(src: &Path, dst: &Path) -> bool {
     if src.is_file() {
         if let Some(parent) = dst.parent() {
@@ -220,7 +635,15 @@ fn copy_if_exists(src: &Path, dst: &Path) -> bool {
     }
 }
 
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<usize, std::io::Error> {
+pub(crate) fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<usize, std::io::Error> {
+    copy_dir_filtered(src, dst, &|_| true)
+}
+
+fn copy_dir_filtered(
+    src: &Path,
+    dst: &Path,
+    keep: &dyn Fn(&Path) -> bool,
+) -> Result<usize, std::io::Error> {
     let mut count = 0;
     if !src.is_dir() {
         return Ok(0);
@@ -231,8 +654,8 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<usize, std::io::Error> {
         let path = entry.path();
         let dest = dst.join(entry.file_name());
         if path.is_dir() {
-            count += copy_dir_recursive(&path, &dest)?;
-        } else if path.is_file() {
+            count += copy_dir_filtered(&path, &dest, keep)?;
+        } else if path.is_file() && keep(&path) {
             std::fs::copy(&path, &dest)?;
             count += 1;
         }
@@ -240,21 +663,14 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<usize, std::io::Error> {
     Ok(count)
 }
 
-fn find_transcript_dir(project_dir: &Path) -> Option<PathBuf> {
-    let canonical = project_dir.canonicalize().ok()?;
-    let dir_name = canonical.to_string_lossy().replace('/', "-");
-    let home = std::env::var("HOME").ok()?;
-    let path = PathBuf::from(home)
-        .join(".claude/projects")
-        .join(&dir_name);
-    if path.is_dir() { Some(path) } else { None }
-}
-
 fn capture_git_info(
     project_dir: &Path,
     output_dir: &Path,
-    commits: usize,
+    options: &ExportOptions,
+    redactions: &mut usize,
 ) -> Result<Option<GitInfo>, ExportError> {
+    let commits = options.git_commits;
+    let filter = &options.filter;
     // Check if this is a git repo
     let status = Command::new("git")
         .args(["rev-parse", "--git-dir"])
@@ -278,23 +694,37 @@ fn capture_git_info(
             "--format=%H %aI %an %s",
             &format!("-{commits}"),
         ])
+        .args(filter.git_log_args())
         .current_dir(project_dir)
         .output()?;
     let log_text = String::from_utf8_lossy(&log_output.stdout).to_string();
-    std::fs::write(git_dst.join("log.txt"), &log_text)?;
-
-    // git diff --stat
-    let diff_output = Command::new("git")
-        .args([
-            "diff",
-            "--stat",
-            &format!("HEAD~{commits}..HEAD"),
-        ])
-        .current_dir(project_dir)
-        .output()?;
+    let (clean, count) = options.redactor.redact(&log_text);
+    *redactions += count;
+    std::fs::write(git_dst.join("log.txt"), clean.as_bytes())?;
+
+    // git diff --stat; a filtered selection isn't a contiguous range, so
+    // use per-commit stats for it instead
+    let diff_output = if filter.is_empty() {
+        Command::new("git")
+            .args([
+                "diff",
+                "--stat",
+                &format!("HEAD~{commits}..HEAD"),
+            ])
+            .current_dir(project_dir)
+            .output()?
+    } else {
+        Command::new("git")
+            .args(["log", "--stat", "--format=%h %s", &format!("-{commits}")])
+            .args(filter.git_log_args())
+            .current_dir(project_dir)
+            .output()?
+    };
     let diff_text = String::from_utf8_lossy(&diff_output.stdout).to_string();
     if !diff_text.is_empty() {
-        std::fs::write(git_dst.join("diff-stat.txt"), &diff_text)?;
+        let (clean, count) = options.redactor.redact(&diff_text);
+        *redactions += count;
+        std::fs::write(git_dst.join("diff-stat.txt"), clean.as_bytes())?;
     }
 
     // Extract info for manifest
@@ -321,10 +751,11 @@ fn capture_git_info(
     }))
 }
 
-fn build_feature_summary(project_dir: &Path) -> FeatureSummary {
+fn build_feature_summary(project_dir: &Path, filter: &ExportFilter) -> FeatureSummary {
     let list = FeatureList::load(project_dir).ok();
     match list {
-        Some(fl) => {
+        Some(mut fl) => {
+            fl.features.retain(|f| filter.wants_feature(&f.id));
             let total = fl.features.len();
             let done = fl
                 .features
@@ -346,12 +777,18 @@ fn build_feature_summary(project_dir: &Path) -> FeatureSummary {
                 .iter()
                 .filter(|f| f.status == FeatureStatus::Blocked)
                 .count();
+            let waiting = fl
+                .features
+                .iter()
+                .filter(|f| f.status == FeatureStatus::Waiting)
+                .count();
             FeatureSummary {
                 total,
                 done,
                 pending,
                 claimed,
                 blocked,
+                waiting,
             }
         }
         None => FeatureSummary {
@@ -360,6 +797,7 @@ fn build_feature_summary(project_dir: &Path) -> FeatureSummary {
             pending: 0,
             claimed: 0,
             blocked: 0,
+            waiting: 0,
         },
     }
 }
@@ -394,6 +832,14 @@ mod tests {
     use super::*;
     use std::fs;
 
+    fn test_options() -> ExportOptions {
+        ExportOptions {
+            include_transcripts: false,
+            git_commits: 10,
+            ..ExportOptions::default()
+        }
+    }
+
     fn setup_test_project(dir: &Path) {
         fs::write(dir.join("forge.toml"), "[project]\nname = \"test\"\n").unwrap();
         fs::write(
@@ -428,7 +874,7 @@ mod tests {
     fn test_export_not_initialized() {
         let tmp = tempfile::tempdir().unwrap();
         let out = tmp.path().join("out");
-        let err = export_project(tmp.path(), &out, false, 10).unwrap_err();
+        let err = export_project(tmp.path(), &out, &test_options()).unwrap_err();
         assert!(matches!(err, ExportError::NotInitialized));
     }
 
@@ -440,7 +886,7 @@ mod tests {
         setup_test_project(&project);
 
         let out = tmp.path().join("export");
-        let manifest = export_project(&project, &out, false, 10).unwrap();
+        let manifest = export_project(&project, &out, &test_options()).unwrap();
 
         // Check manifest
         assert_eq!(manifest.features.total, 1);
@@ -477,12 +923,12 @@ mod tests {
         let out = tmp.path().join("export");
 
         // First export
-        export_project(&project, &out, false, 10).unwrap();
+        export_project(&project, &out, &test_options()).unwrap();
         // Place a stale file
         fs::write(out.join("stale.txt"), "old").unwrap();
 
         // Second export should remove stale file
-        export_project(&project, &out, false, 10).unwrap();
+        export_project(&project, &out, &test_options()).unwrap();
         assert!(!out.join("stale.txt").exists());
     }
 
@@ -517,10 +963,345 @@ mod tests {
         assert_eq!(fs::read_to_string(dst.join("sub/b.txt")).unwrap(), "b");
     }
 
+    fn export_fixture(tmp: &Path) -> PathBuf {
+        let project = tmp.join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_test_project(&project);
+        let out = tmp.join("export");
+        export_project(&project, &out, &test_options()).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_archive_format_from_extension() {
+        assert_eq!(ArchiveFormat::from_path(Path::new("a/out.tar.gz")).unwrap(), ArchiveFormat::TarGz);
+        assert_eq!(ArchiveFormat::from_path(Path::new("out.TGZ")).unwrap(), ArchiveFormat::TarGz);
+        assert_eq!(ArchiveFormat::from_path(Path::new("out.zip")).unwrap(), ArchiveFormat::Zip);
+        assert!(matches!(
+            ArchiveFormat::from_path(Path::new("out.rar")),
+            Err(ExportError::UnsupportedArchive(_))
+        ));
+        assert_eq!(archive_root(Path::new("dir/bug-123.tar.gz")), "bug-123");
+        assert_eq!(archive_root(Path::new(".zip")), "forge-export");
+    }
+
+    #[test]
+    fn test_write_tar_gz_archive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = export_fixture(tmp.path());
+        let archive = tmp.path().join("dist/report.tar.gz");
+
+        let size = write_archive(&out, &archive).unwrap();
+        assert!(size > 0);
+
+        let decoder = flate2::read::GzDecoder::new(fs::File::open(&archive).unwrap());
+        let mut tar = tar::Archive::new(decoder);
+        let names: Vec<String> = tar
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert!(names.contains(&"report/manifest.json".to_string()), "got: {names:?}");
+        assert!(names.contains(&"report/context/decisions/arch.md".to_string()), "got: {names:?}");
+    }
+
+    #[test]
+    fn test_write_zip_archive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = export_fixture(tmp.path());
+        let archive = tmp.path().join("report.zip");
+
+        write_archive(&out, &archive).unwrap();
+
+        let mut zip = zip::ZipArchive::new(fs::File::open(&archive).unwrap()).unwrap();
+        let mut manifest = String::new();
+        std::io::Read::read_to_string(&mut zip.by_name("report/manifest.json").unwrap(), &mut manifest)
+            .unwrap();
+        assert!(manifest.contains("\"forge_version\""));
+        assert!(zip.by_name("report/logs/agent-1.log").is_ok());
+    }
+
+    #[test]
+    fn test_archive_inside_export_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = export_fixture(tmp.path());
+        let err = write_archive(&out, &out.join("self.tar.gz")).unwrap_err();
+        assert!(matches!(err, ExportError::ArchiveInsideExport(_)));
+        let err = write_archive(&out, &out.join("nested/dirs/self.zip")).unwrap_err();
+        assert!(matches!(err, ExportError::ArchiveInsideExport(_)));
+        assert!(!out.join("nested").exists(), "a refused archive leaves no directories behind");
+        write_archive(&out, &tmp.path().join("new/dir/report.zip")).unwrap();
+    }
+
+    #[test]
+    fn test_filter_parses_bounds() {
+        let f = ExportFilter::new(Some("2026-03-01"), Some("2026-03-02"), vec![" f1 ".into(), "".into()]).unwrap();
+        assert_eq!(f.since.unwrap().to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert_eq!(f.until.unwrap().to_rfc3339(), "2026-03-02T23:59:59+00:00");
+        assert_eq!(f.features, vec!["f1"]);
+        assert_eq!(
+            f.to_string(),
+            "since 2026-03-01T00:00:00+00:00; until 2026-03-02T23:59:59+00:00; features f1"
+        );
+
+        let f = ExportFilter::new(Some("2026-03-01T12:00:00+02:00"), None, vec![]).unwrap();
+        assert_eq!(f.since.unwrap().to_rfc3339(), "2026-03-01T10:00:00+00:00");
+
+        assert!(matches!(
+            ExportFilter::new(Some("yesterday"), None, vec![]),
+            Err(ExportError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            ExportFilter::new(Some("2026-03-02"), Some("2026-03-01"), vec![]),
+            Err(ExportError::EmptyWindow)
+        ));
+        assert!(ExportFilter::default().is_empty());
+    }
+
+    #[test]
+    fn test_contains_word() {
+        assert!(contains_word("claims f001 now", "f001"));
+        assert!(contains_word("feat(f001): x", "f001"));
+        assert!(!contains_word("claims f0012", "f001"));
+        assert!(!contains_word("xf001", "f001"));
+        assert!(contains_word("f0012 then f001", "f001"));
+    }
+
+    fn setup_two_features(project: &Path) {
+        setup_test_project(project);
+        fs::write(
+            project.join("features.json"),
+            r#"{"features":[
+                {"id":"f1","type":"implement","scope":"a","description":"one","verify":"true","status":"done"},
+                {"id":"f2","type":"implement","scope":"b","description":"two","verify":"true","status":"pending"}
+            ]}"#,
+        )
+        .unwrap();
+        let mem = project.join("feedback/exec-memory");
+        fs::create_dir_all(&mem).unwrap();
+        fs::write(mem.join("f1.json"), "{}").unwrap();
+        fs::write(mem.join("f2.json"), "{}").unwrap();
+        fs::write(project.join(".forge/logs/agent-2.log"), "working on f2\n").unwrap();
+        fs::write(project.join(".forge/logs/agent-1.log"), "claimed f1\n").unwrap();
+    }
+
+    #[test]
+    fn test_export_filtered_by_feature() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_two_features(&project);
+
+        let out = tmp.path().join("export");
+        let options = ExportOptions {
+            filter: ExportFilter::new(None, None, vec!["f2".into()]).unwrap(),
+            ..test_options()
+        };
+        let manifest = export_project(&project, &out, &options).unwrap();
+
+        assert_eq!(manifest.features.total, 1);
+        assert_eq!(manifest.features.pending, 1);
+        let list = FeatureList::load(&out).unwrap();
+        assert_eq!(list.features.len(), 1);
+        assert_eq!(list.features[0].id, "f2");
+
+        assert!(out.join("feedback/exec-memory/f2.json").exists());
+        assert!(!out.join("feedback/exec-memory/f1.json").exists());
+        assert!(out.join("feedback/last-verify.json").exists());
+        assert_eq!(manifest.logs, vec!["agent-2.log"]);
+
+        let json = fs::read_to_string(out.join("manifest.json")).unwrap();
+        assert!(json.contains("\"filter\""));
+    }
+
+    #[test]
+    fn test_export_feature_packet() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_two_features(&project);
+        fs::write(
+            project.join("forge.toml"),
+            "[project]\nname = \"test\"\n\n[scopes.b]\nowns = [\"src/b/\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join("features.json"),
+            r#"{"features":[
+                {"id":"f1","type":"implement","scope":"a","description":"one","verify":"true","status":"done"},
+                {"id":"f2","type":"implement","scope":"b","description":"two","verify":"cargo test b","status":"pending",
+                 "context_hints":["decisions/arch","gotchas/gone"]}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(
+            project.join("feedback/last-verify.json"),
+            r#"{"pass":1,"fail":1,"total":2,"passed":["f1"],"failures":[{"feature_id":"f2","output":"token=supersecretvalue"}]}"#,
+        )
+        .unwrap();
+
+        let out = tmp.path().join("export");
+        let options = ExportOptions {
+            filter: ExportFilter::new(None, None, vec!["f2".into()]).unwrap(),
+            packet: Some("f2".into()),
+            ..test_options()
+        };
+        let manifest = export_project(&project, &out, &options).unwrap();
+        let packet = manifest.packet.unwrap();
+        assert_eq!(packet.last_verify, "failed");
+        assert_eq!(packet.context, vec!["decisions/arch"]);
+        assert_eq!(packet.missing_context, vec!["gotchas/gone"]);
+        assert_eq!(packet.scope_paths, vec!["src/b/"]);
+        assert!(manifest.sections.contains(&"feature".to_string()));
+        assert_eq!(manifest.logs, vec!["agent-2.log"]);
+
+        let feature = fs::read_to_string(out.join("feature/feature.json")).unwrap();
+        assert!(feature.contains("cargo test b"));
+        let verify = fs::read_to_string(out.join("feature/verify.json")).unwrap();
+        assert!(verify.contains("token=[REDACTED:assignment]"), "{verify}");
+        assert!(out.join("feature/context/decisions/arch.md").is_file());
+
+        let options = ExportOptions {
+            packet: Some("f9".into()),
+            ..test_options()
+        };
+        let err = export_project(&project, &tmp.path().join("other"), &options).unwrap_err();
+        assert!(matches!(err, ExportError::UnknownFeatures(_)));
+    }
+
+    #[test]
+    fn test_export_unknown_feature_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_test_project(&project);
+
+        let options = ExportOptions {
+            filter: ExportFilter::new(None, None, vec!["f9".into()]).unwrap(),
+            ..test_options()
+        };
+        let err = export_project(&project, &tmp.path().join("out"), &options).unwrap_err();
+        assert!(matches!(err, ExportError::UnknownFeatures(ids) if ids == "f9"));
+    }
+
+    #[test]
+    fn test_export_filtered_by_window() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_two_features(&project);
+
+        // agent-1's log was last written long before the window
+        let old = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        fs::File::options()
+            .write(true)
+            .open(project.join(".forge/logs/agent-1.log"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let out = tmp.path().join("export");
+        let options = ExportOptions {
+            filter: ExportFilter::new(Some("2020-01-01"), None, vec![]).unwrap(),
+            ..test_options()
+        };
+        let manifest = export_project(&project, &out, &options).unwrap();
+        assert_eq!(manifest.logs, vec!["agent-2.log"]);
+        assert_eq!(manifest.features.total, 2);
+    }
+
+    #[test]
+    fn test_export_merges_timeline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_two_features(&project);
+        let events = project.join(".forge/events");
+        fs::create_dir_all(&events).unwrap();
+        fs::write(
+            events.join("agent-1.jsonl"),
+            concat!(
+                r#"{"ts":"2026-03-01T10:00:00Z","agent":"agent-1","event":"claim","feature_id":"f1"}"#, "\n",
+                r#"{"ts":"2026-03-01T10:09:00Z","agent":"agent-1","event":"session_end","feature_id":"f1","exit_code":0}"#, "\n",
+            ),
+        )
+        .unwrap();
+        fs::write(
+            events.join("forge.jsonl"),
+            concat!(
+                r#"{"ts":"2026-03-01T10:05:00Z","agent":"forge","event":"verify","passed":0,"failed":1,"reopened":["f2"]}"#, "\n",
+                r#"{"ts":"2026-03-01T10:10:00Z","agent":"forge","event":"merge","branch":"forge/agent-1","ok":true}"#, "\n",
+            ),
+        )
+        .unwrap();
+
+        let out = tmp.path().join("export");
+        let manifest = export_project(&project, &out, &test_options()).unwrap();
+        assert!(manifest.sections.contains(&"timeline".to_string()));
+        let timeline = fs::read_to_string(out.join("timeline.jsonl")).unwrap();
+        let kinds: Vec<&str> = timeline
+            .lines()
+            .map(|l| l.split(r#""event":""#).nth(1).unwrap().split('"').next().unwrap())
+            .collect();
+        assert_eq!(kinds, vec!["claim", "verify", "session_end", "merge"]);
+
+        // A feature filter keeps only that feature's events
+        let filtered = tmp.path().join("filtered");
+        let options = ExportOptions {
+            filter: ExportFilter::new(None, Some("2026-03-01T10:06:00Z"), vec!["f1".into()]).unwrap(),
+            ..test_options()
+        };
+        export_project(&project, &filtered, &options).unwrap();
+        let timeline = fs::read_to_string(filtered.join("timeline.jsonl")).unwrap();
+        assert_eq!(timeline.lines().count(), 1, "{timeline}");
+        assert!(timeline.contains(r#""event":"claim""#));
+    }
+
+    #[test]
+    fn test_git_log_args() {
+        let filter = ExportFilter::new(Some("2026-03-01"), None, vec!["f1".into(), "f2".into()]).unwrap();
+        assert_eq!(
+            filter.git_log_args(),
+            vec![
+                "--since=2026-03-01T00:00:00+00:00",
+                "--fixed-strings",
+                "--grep=f1",
+                "--grep=f2"
+            ]
+        );
+        assert!(ExportFilter::default().git_log_args().is_empty());
+    }
+
+    #[test]
+    fn test_export_redacts_logs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        setup_test_project(&project);
+        let secret = "ANTHROPIC_API_KEY=sk-ant-REDACTED\n";
+        fs::write(project.join(".forge/logs/agent-1.log"), secret).unwrap();
+
+        let out = tmp.path().join("export");
+        let manifest = export_project(&project, &out, &test_options()).unwrap();
+        assert_eq!(manifest.redactions, 1);
+        let log = fs::read_to_string(out.join("logs/agent-1.log")).unwrap();
+        assert!(!log.contains("sk-ant-"), "got: {log}");
+        assert!(log.starts_with("ANTHROPIC_API_KEY=[REDACTED:"));
+
+        // Disabled: copied verbatim
+        let options = ExportOptions {
+            redactor: Redactor::default(),
+            ..test_options()
+        };
+        let manifest = export_project(&project, &out, &options).unwrap();
+        assert_eq!(manifest.redactions, 0);
+        assert_eq!(fs::read_to_string(out.join("logs/agent-1.log")).unwrap(), secret);
+    }
+
     #[test]
     fn test_feature_summary_no_features() {
         let tmp = tempfile::tempdir().unwrap();
-        let summary = build_feature_summary(tmp.path());
+        let summary = build_feature_summary(tmp.path(), &ExportFilter::default());
         assert_eq!(summary.total, 0);
     }
 }
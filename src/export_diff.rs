@@ -0,0 +1,302 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+use crate::features::{FeatureList, FeatureStatus};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiffError {
+    #[error("{0} is not a forge export (missing manifest.json)")]
+    NotAnExport(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Verify pass count out of total, from `feedback/last-verify.json`.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct VerifySnapshot {
+    pub pass: u64,
+    pub total: u64,
+}
+
+/// What changed between two `forge export` directories.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ExportDiff {
+    pub old_exported_at: String,
+    pub new_exported_at: String,
+    /// Not done before, done now.
+    pub completed: Vec<String>,
+    /// Done before, not done now.
+    pub reopened: Vec<String>,
+    pub newly_blocked: Vec<String>,
+    pub unblocked: Vec<String>,
+    pub added_features: Vec<String>,
+    pub removed_features: Vec<String>,
+    /// `category/entry` paths under context/.
+    pub context_added: Vec<String>,
+    pub context_removed: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_old: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_new: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_old: Option<VerifySnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_new: Option<VerifySnapshot>,
+}
+
+struct Snapshot {
+    exported_at: String,
+    statuses: BTreeMap<String, FeatureStatus>,
+    context: BTreeSet<String>,
+    cost: Option<f64>,
+    verify: Option<VerifySnapshot>,
+}
+
+fn load_snapshot(dir: &Path) -> Result<Snapshot, DiffError> {
+    let manifest_path = dir.join("manifest.json");
+    if !manifest_path.is_file() {
+        return Err(DiffError::NotAnExport(dir.display().to_string()));
+    }
+    // Read as plain JSON so exports from older forge versions still compare
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(manifest_path)?)?;
+    let exported_at = manifest
+        .get("exported_at")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let cost = manifest
+        .pointer("/cost/total_usd")
+        .and_then(|v| v.as_f64());
+
+    let statuses = FeatureList::load(dir)
+        .map(|list| list.features.into_iter().map(|f| (f.id, f.status)).collect())
+        .unwrap_or_default();
+
+    let verify = std::fs::read_to_string(dir.join("feedback/last-verify.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|v| {
+            Some(VerifySnapshot {
+                pass: v.get("pass")?.as_u64()?,
+                total: v.get("total")?.as_u64()?,
+            })
+        });
+
+    Ok(Snapshot {
+        exported_at,
+        statuses,
+        context: context_entries(&dir.join("context")),
+        cost,
+        verify,
+    })
+}
+
+/// Entries are the files inside each category directory; INDEX.md and
+/// other top-level files aren't entries.
+fn context_entries(context_dir: &Path) -> BTreeSet<String> {
+    let mut entries = BTreeSet::new();
+    for category in std::fs::read_dir(context_dir).into_iter().flatten().flatten() {
+        if !category.path().is_dir() {
+            continue;
+        }
+        let cat = category.file_name().to_string_lossy().to_string();
+        for entry in std::fs::read_dir(category.path()).into_iter().flatten().flatten() {
+            if entry.path().is_file() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let slug = name.strip_suffix(".md").unwrap_or(&name);
+                entries.insert(format!("{cat}/{slug}"));
+            }
+        }
+    }
+    entries
+}
+
+pub fn diff_exports(old_dir: &Path, new_dir: &Path) -> Result<ExportDiff, DiffError> {
+    let old = load_snapshot(old_dir)?;
+    let new = load_snapshot(new_dir)?;
+
+    let mut diff = ExportDiff {
+        old_exported_at: old.exported_at,
+        new_exported_at: new.exported_at,
+        cost_old: old.cost,
+        cost_new: new.cost,
+        verify_old: old.verify,
+        verify_new: new.verify,
+        ..Default::default()
+    };
+
+    for (id, status) in &new.statuses {
+        let Some(before) = old.statuses.get(id) else {
+            diff.added_features.push(id.clone());
+            if *status == FeatureStatus::Done {
+                diff.completed.push(id.clone());
+            }
+            continue;
+        };
+        let was_done = *before == FeatureStatus::Done;
+        let is_done = *status == FeatureStatus::Done;
+        if is_done && !was_done {
+            diff.completed.push(id.clone());
+        } else if was_done && !is_done {
+            diff.reopened.push(id.clone());
+        }
+        let was_blocked = *before == FeatureStatus::Blocked;
+        let is_blocked = *status == FeatureStatus::Blocked;
+        if is_blocked && !was_blocked {
+            diff.newly_blocked.push(id.clone());
+        } else if was_blocked && !is_blocked {
+            diff.unblocked.push(id.clone());
+        }
+    }
+    diff.removed_features = old
+        .statuses
+        .keys()
+        .filter(|id| !new.statuses.contains_key(*id))
+        .cloned()
+        .collect();
+    diff.context_added = new.context.difference(&old.context).cloned().collect();
+    diff.context_removed = old.context.difference(&new.context).cloned().collect();
+    Ok(diff)
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, label: &str, items: &[String]) -> fmt::Result {
+    if items.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{label} ({}): {}", items.len(), items.join(", "))
+}
+
+impl fmt::Display for ExportDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Export diff: {} → {}", self.old_exported_at, self.new_exported_at)?;
+        writeln!(f)?;
+        write_list(f, "Completed", &self.completed)?;
+        write_list(f, "Reopened", &self.reopened)?;
+        write_list(f, "Newly blocked", &self.newly_blocked)?;
+        write_list(f, "Unblocked", &self.unblocked)?;
+        write_list(f, "Added features", &self.added_features)?;
+        write_list(f, "Removed features", &self.removed_features)?;
+        write_list(f, "Context added", &self.context_added)?;
+        write_list(f, "Context removed", &self.context_removed)?;
+        let lists = [
+            &self.completed,
+            &self.reopened,
+            &self.newly_blocked,
+            &self.unblocked,
+            &self.added_features,
+            &self.removed_features,
+            &self.context_added,
+            &self.context_removed,
+        ];
+        if lists.iter().all(|l| l.is_empty()) {
+            writeln!(f, "No feature or context changes")?;
+        }
+
+        match (self.cost_old, self.cost_new) {
+            (Some(old), Some(new)) => writeln!(f, "Cost: ${old:.2} → ${new:.2} ({:+.2})", new - old)?,
+            (None, Some(new)) => writeln!(f, "Cost: ${new:.2} (no cost data in old export)")?,
+            _ => {}
+        }
+        if let Some(new) = self.verify_new {
+            let trend = match self.verify_old {
+                Some(old) => format!("{}/{} → ", old.pass, old.total),
+                None => String::new(),
+            };
+            writeln!(f, "Verify: {trend}{}/{} passing", new.pass, new.total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_export(dir: &Path, features: &[(&str, &str)], context: &[&str], verify: (u64, u64), cost: f64) {
+        fs::create_dir_all(dir.join("feedback")).unwrap();
+        fs::write(
+            dir.join("manifest.json"),
+            format!(r#"{{"exported_at":"{}","cost":{{"total_usd":{cost}}}}}"#, dir.display()),
+        )
+        .unwrap();
+        let list: Vec<String> = features
+            .iter()
+            .map(|(id, status)| {
+                format!(
+                    r#"{{"id":"{id}","type":"implement","scope":"a","description":"d","verify":"true","status":"{status}"}}"#
+                )
+            })
+            .collect();
+        fs::write(dir.join("features.json"), format!(r#"{{"features":[{}]}}"#, list.join(","))).unwrap();
+        for entry in context {
+            let path = dir.join("context").join(format!("{entry}.md"));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x").unwrap();
+        }
+        fs::create_dir_all(dir.join("context")).unwrap();
+        fs::write(dir.join("context/INDEX.md"), "index").unwrap();
+        fs::write(
+            dir.join("feedback/last-verify.json"),
+            format!(r#"{{"pass":{},"fail":0,"total":{}}}"#, verify.0, verify.1),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn diff_reports_progress() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old = tmp.path().join("old");
+        let new = tmp.path().join("new");
+        write_export(
+            &old,
+            &[("f1", "done"), ("f2", "pending"), ("f3", "blocked"), ("f4", "pending"), ("f9", "pending")],
+            &["decisions/arch"],
+            (1, 4),
+            10.0,
+        );
+        write_export(
+            &new,
+            &[("f1", "pending"), ("f2", "done"), ("f3", "pending"), ("f4", "blocked"), ("f5", "done")],
+            &["decisions/arch", "gotchas/sqlx"],
+            (3, 5),
+            32.5,
+        );
+
+        let diff = diff_exports(&old, &new).unwrap();
+        assert_eq!(diff.completed, vec!["f2", "f5"]);
+        assert_eq!(diff.reopened, vec!["f1"]);
+        assert_eq!(diff.newly_blocked, vec!["f4"]);
+        assert_eq!(diff.unblocked, vec!["f3"]);
+        assert_eq!(diff.added_features, vec!["f5"]);
+        assert_eq!(diff.removed_features, vec!["f9"]);
+        assert_eq!(diff.context_added, vec!["gotchas/sqlx"]);
+        assert!(diff.context_removed.is_empty());
+
+        let text = diff.to_string();
+        assert!(text.contains("Completed (2): f2, f5"), "{text}");
+        assert!(!text.contains("No feature or context changes"));
+        assert!(text.contains("Cost: $10.00 → $32.50 (+22.50)"), "{text}");
+        assert!(text.contains("Verify: 1/4 → 3/5 passing"), "{text}");
+        assert!(!text.contains("Context removed"));
+    }
+
+    #[test]
+    fn not_an_export() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = diff_exports(tmp.path(), tmp.path()).unwrap_err();
+        assert!(matches!(err, DiffError::NotAnExport(_)));
+    }
+
+    #[test]
+    fn identical_exports() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_export(tmp.path(), &[("f1", "done")], &[], (1, 1), 1.0);
+        let diff = diff_exports(tmp.path(), tmp.path()).unwrap();
+        assert!(diff.to_string().contains("No feature or context changes"));
+    }
+}
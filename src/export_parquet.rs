@@ -0,0 +1,284 @@
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::export::ExportError;
+use crate::features::FeatureList;
+
+/// One column of a table. Every column is OPTIONAL so a field missing from
+/// an older export reads as NULL instead of failing the write.
+enum Column {
+    Utf8(Vec<Option<String>>),
+    Int64(Vec<Option<i64>>),
+    Double(Vec<Option<f64>>),
+    Bool(Vec<Option<bool>>),
+}
+
+impl Column {
+    fn schema_type(&self) -> &'static str {
+        match self {
+            Column::Utf8(_) => "BYTE_ARRAY",
+            Column::Int64(_) => "INT64",
+            Column::Double(_) => "DOUBLE",
+            Column::Bool(_) => "BOOLEAN",
+        }
+    }
+}
+
+struct Table {
+    name: &'static str,
+    columns: Vec<(&'static str, Column)>,
+}
+
+impl Table {
+    fn schema(&self) -> String {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(name, col)| match col {
+                Column::Utf8(_) => format!("OPTIONAL BYTE_ARRAY {name} (UTF8);"),
+                _ => format!("OPTIONAL {} {name};", col.schema_type()),
+            })
+            .collect();
+        format!("message {} {{ {} }}", self.name, fields.join(" "))
+    }
+
+    fn write(&self, path: &Path) -> Result<(), ExportError> {
+        let schema = Arc::new(parse_message_type(&self.schema())?);
+        let props = Arc::new(WriterProperties::builder().build());
+        let file = std::fs::File::create(path)?;
+        let mut writer = SerializedFileWriter::new(file, schema, props)?;
+        let mut row_group = writer.next_row_group()?;
+        let mut columns = self.columns.iter();
+        while let Some(mut out) = row_group.next_column()? {
+            let (_, column) = columns.next().expect("schema has one field per column");
+            match column {
+                Column::Utf8(values) => {
+                    let (levels, present) = split(values);
+                    let present: Vec<ByteArray> = present.into_iter().map(|s| ByteArray::from(s.as_str())).collect();
+                    out.typed::<ByteArrayType>().write_batch(&present, Some(&levels), None)?;
+                }
+                Column::Int64(values) => {
+                    let (levels, present) = split(values);
+                    out.typed::<Int64Type>().write_batch(&present, Some(&levels), None)?;
+                }
+                Column::Double(values) => {
+                    let (levels, present) = split(values);
+                    out.typed::<DoubleType>().write_batch(&present, Some(&levels), None)?;
+                }
+                Column::Bool(values) => {
+                    let (levels, present) = split(values);
+                    out.typed::<BoolType>().write_batch(&present, Some(&levels), None)?;
+                }
+            }
+            out.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Definition levels (1 = present, 0 = null) and the non-null values.
+fn split<T: Clone>(values: &[Option<T>]) -> (Vec<i16>, Vec<T>) {
+    let levels = values.iter().map(|v| i16::from(v.is_some())).collect();
+    let present = values.iter().flatten().cloned().collect();
+    (levels, present)
+}
+
+/// How a unit enum variant is spelled in features.json, e.g. "in_progress".
+fn serde_name<T: serde::Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok()?.as_str().map(String::from)
+}
+
+fn features_table(list: &FeatureList) -> Table {
+    let f = &list.features;
+    let text = |get: fn(&crate::features::Feature) -> Option<String>| Column::Utf8(f.iter().map(get).collect());
+    Table {
+        name: "features",
+        columns: vec![
+            ("id", text(|f| Some(f.id.clone()))),
+            ("type", Column::Utf8(f.iter().map(|f| serde_name(&f.feature_type)).collect())),
+            ("status", Column::Utf8(f.iter().map(|f| serde_name(&f.status)).collect())),
+            ("scope", text(|f| Some(f.scope.clone()))),
+            ("description", text(|f| Some(f.description.clone()))),
+            ("priority", Column::Int64(f.iter().map(|f| Some(i64::from(f.priority))).collect())),
+            // Comma-separated so the table stays flat for spreadsheet-style tools
+            ("depends_on", text(|f| Some(f.depends_on.join(",")))),
+            ("claimed_by", text(|f| f.claimed_by.clone())),
+            ("blocked_reason", text(|f| f.blocked_reason.clone())),
+            ("verify", text(|f| Some(f.verify.clone()))),
+        ],
+    }
+}
+
+/// From `metrics.json`, read as plain JSON like `export diff` does.
+fn sessions_table(metrics: &Value) -> Table {
+    let sessions: &[Value] = metrics
+        .get("sessions")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let text = |ptr: &str| Column::Utf8(sessions.iter().map(|s| s.pointer(ptr).and_then(Value::as_str).map(String::from)).collect());
+    let int = |ptr: &str| Column::Int64(sessions.iter().map(|s| s.pointer(ptr).and_then(Value::as_i64)).collect());
+    let tool_calls = sessions
+        .iter()
+        .map(|s| {
+            s.get("tool_calls")
+                .and_then(Value::as_object)
+                .map(|calls| calls.values().filter_map(Value::as_i64).sum())
+        })
+        .collect();
+    Table {
+        name: "sessions",
+        columns: vec![
+            ("session_id", text("/session_id")),
+            ("role", text("/role")),
+            ("feature_id", text("/feature_id")),
+            ("model", text("/model")),
+            ("started_at", text("/started_at")),
+            ("ended_at", text("/ended_at")),
+            ("duration_secs", int("/duration_secs")),
+            ("turns", int("/turns")),
+            ("assistant_messages", int("/assistant_messages")),
+            ("input_tokens", int("/tokens/input")),
+            ("output_tokens", int("/tokens/output")),
+            ("cache_creation_tokens", int("/tokens/cache_creation")),
+            ("cache_read_tokens", int("/tokens/cache_read")),
+            (
+                "cost_usd",
+                Column::Double(sessions.iter().map(|s| s.get("cost_usd").and_then(Value::as_f64)).collect()),
+            ),
+            ("tool_calls", Column::Int64(tool_calls)),
+        ],
+    }
+}
+
+/// From `feedback/last-verify.json`: one row per feature checked. Reports
+/// written before `passed` was recorded only yield their failures.
+fn verify_table(report: &Value) -> Table {
+    let mut rows: Vec<(String, bool, Option<String>)> = Vec::new();
+    for id in report.get("passed").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(id) = id.as_str() {
+            rows.push((id.to_string(), true, None));
+        }
+    }
+    for failure in report.get("failures").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(id) = failure.get("feature_id").and_then(Value::as_str) {
+            let output = failure.get("output").and_then(Value::as_str).map(String::from);
+            rows.push((id.to_string(), false, output));
+        }
+    }
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+    Table {
+        name: "verify",
+        columns: vec![
+            ("feature_id", Column::Utf8(rows.iter().map(|r| Some(r.0.clone())).collect())),
+            ("passed", Column::Bool(rows.iter().map(|r| Some(r.1)).collect())),
+            ("output", Column::Utf8(rows.into_iter().map(|r| r.2).collect())),
+        ],
+    }
+}
+
+/// Write `features.parquet`, `sessions.parquet` and `verify.parquet` into an
+/// export directory, each only when its source data was exported.
+pub fn write_parquet_tables(export_dir: &Path) -> Result<Vec<PathBuf>, ExportError> {
+    let read_json = |rel: &str| -> Result<Option<Value>, ExportError> {
+        let path = export_dir.join(rel);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    };
+
+    let mut tables = Vec::new();
+    if let Some(features) = read_json("features.json")? {
+        tables.push(features_table(&serde_json::from_value::<FeatureList>(features)?));
+    }
+    if let Some(metrics) = read_json("metrics.json")? {
+        tables.push(sessions_table(&metrics));
+    }
+    if let Some(report) = read_json("feedback/last-verify.json")? {
+        tables.push(verify_table(&report));
+    }
+
+    let mut written = Vec::new();
+    for table in tables {
+        let path = export_dir.join(format!("{}.parquet", table.name));
+        table.write(&path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::{Row, RowAccessor};
+    use std::fs;
+
+    fn read_rows(path: &Path) -> Vec<Row> {
+        let reader = SerializedFileReader::new(fs::File::open(path).unwrap()).unwrap();
+        reader.get_row_iter(None).unwrap().map(|r| r.unwrap()).collect()
+    }
+
+    #[test]
+    fn writes_tables_from_export() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("feedback")).unwrap();
+        fs::write(
+            dir.join("features.json"),
+            r#"{"features":[
+                {"id":"f1","type":"implement","scope":"a","description":"x","verify":"true","status":"done"},
+                {"id":"f2","type":"review","scope":"a","description":"y","verify":"true","depends_on":["f1","f0"],"claimed_by":"agent-1"}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("metrics.json"),
+            r#"{"sessions":[{"session_id":"s1","role":"executor","feature_id":"f1","turns":3,
+                "tokens":{"input":10,"output":20,"cache_creation":0,"cache_read":5},"cost_usd":0.25,
+                "tool_calls":{"Bash":2,"Edit":1}}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("feedback/last-verify.json"),
+            r#"{"pass":1,"fail":1,"total":2,"passed":["f1"],"failures":[{"feature_id":"f2","output":"boom"}]}"#,
+        )
+        .unwrap();
+
+        let written = write_parquet_tables(dir).unwrap();
+        assert_eq!(written.len(), 3);
+
+        let features = read_rows(&dir.join("features.parquet"));
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0].get_string(2).unwrap(), "done");
+        assert_eq!(features[1].get_string(1).unwrap(), "review");
+        assert_eq!(features[1].get_string(6).unwrap(), "f1,f0");
+        assert_eq!(features[1].get_string(7).unwrap(), "agent-1");
+        assert!(features[0].get_string(7).is_err(), "claimed_by is null");
+
+        let sessions = read_rows(&dir.join("sessions.parquet"));
+        assert_eq!(sessions[0].get_string(2).unwrap(), "f1");
+        assert_eq!(sessions[0].get_long(10).unwrap(), 20);
+        assert_eq!(sessions[0].get_double(13).unwrap(), 0.25);
+        assert_eq!(sessions[0].get_long(14).unwrap(), 3);
+
+        let verify = read_rows(&dir.join("verify.parquet"));
+        assert!(verify[0].get_bool(1).unwrap());
+        assert!(!verify[1].get_bool(1).unwrap());
+        assert_eq!(verify[1].get_string(2).unwrap(), "boom");
+    }
+
+    #[test]
+    fn skips_missing_sources() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(write_parquet_tables(tmp.path()).unwrap().is_empty());
+    }
+}
@@ -50,6 +50,8 @@ pub enum FeatureStatus {
     Claimed,
     Done,
     Blocked,
+    /// Waiting on an answer to a question in `feedback/questions/`.
+    Waiting,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -77,6 +79,10 @@ impl FeatureList {
     pub fn save(&self, project_dir: &Path) -> Result<(), FeatureError> {
         let path = project_dir.join("features.json");
         let content = serde_json::to_string_pretty(self)?;
+        if let Err(e) = crate::features_backup::backup(project_dir) {
+            tracing::warn!("failed to back up features.json: {e}");
+        }
+        crate::audit::on_save(project_dir, self);
         std::fs::write(&path, content)?;
         Ok(())
     }
@@ -179,6 +185,18 @@ impl FeatureList {
         Ok(())
     }
 
+    /// Park a feature until its question is answered.
+    pub fn mark_waiting(&mut self, feature_id: &str) -> Result<(), FeatureError> {
+        let feature = self
+            .features
+            .iter_mut()
+            .find(|f| f.id == feature_id)
+            .ok_or_else(|| FeatureError::NotFound(feature_id.into()))?;
+        feature.status = FeatureStatus::Waiting;
+        feature.claimed_by = None;
+        Ok(())
+    }
+
     /// Reopen a feature (verify failed after agent said done).
     pub fn reopen(&mut self, feature_id: &str) -> Result<(), FeatureError> {
         let feature = self
@@ -192,6 +210,27 @@ impl FeatureList {
         Ok(())
     }
 
+    /// Point every `context_hints` entry at `old` to `new` instead, e.g. after
+    /// two context entries are merged. Returns how many features changed.
+    pub fn replace_context_hint(&mut self, old: &str, new: &str) -> usize {
+        let mut changed = 0;
+        for feature in &mut self.features {
+            if !feature.context_hints.iter().any(|h| h == old) {
+                continue;
+            }
+            let mut hints = Vec::new();
+            for hint in feature.context_hints.drain(..) {
+                let hint = if hint == old { new.to_string() } else { hint };
+                if !hints.contains(&hint) {
+                    hints.push(hint);
+                }
+            }
+            feature.context_hints = hints;
+            changed += 1;
+        }
+        changed
+    }
+
     /// Summary counts by status.
     pub fn status_counts(&self) -> StatusCounts {
         let mut counts = StatusCounts::default();
@@ -201,6 +240,7 @@ impl FeatureList {
                 FeatureStatus::Claimed => counts.claimed += 1,
                 FeatureStatus::Done => counts.done += 1,
                 FeatureStatus::Blocked => counts.blocked += 1,
+                FeatureStatus::Waiting => counts.waiting += 1,
             }
         }
         counts.total = self.features.len();
@@ -360,6 +400,23 @@ impl FeatureList {
     pub fn all_done(&self) -> bool {
         self.features.iter().all(|f| f.status == FeatureStatus::Done)
     }
+
+    /// The id for a new feature of a type: its prefix (`f`, `r`, `p`) and
+    /// one past the highest number in use with it, e.g. `f007`.
+    pub fn next_id(&self, feature_type: &FeatureType) -> String {
+        let prefix = match feature_type {
+            FeatureType::Implement => 'f',
+            FeatureType::Review => 'r',
+            FeatureType::Poc => 'p',
+        };
+        let highest = self
+            .features
+            .iter()
+            .filter_map(|f| f.id.strip_prefix(prefix)?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0);
+        format!("{prefix}{:03}", highest + 1)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -369,6 +426,7 @@ pub struct StatusCounts {
     pub claimed: usize,
     pub done: usize,
     pub blocked: usize,
+    pub waiting: usize,
 }
 
 #[cfg(test)]
@@ -710,7 +768,7 @@ mod tests {
 
     #[test]
     fn milestone_claimable_groups_by_review() {
-        let mut list = FeatureList {
+        let list = FeatureList {
             features: vec![
                 Feature {
                     id: "f030".into(),
@@ -896,4 +954,37 @@ mod tests {
         assert!(k("M4") < k("M4-writes"));
         assert!(k("M4-writes") < k("M5"));
     }
+
+    #[test]
+    fn replace_context_hint_dedupes() {
+        let mut list: FeatureList = serde_json::from_str(
+            r#"{"features":[
+                {"id":"f1","type":"implement","scope":"a","description":"x","verify":"true",
+                 "context_hints":["gotchas/sqlx-null","gotchas/sqlx-nullable"]},
+                {"id":"f2","type":"implement","scope":"a","description":"y","verify":"true",
+                 "context_hints":["decisions/orm","gotchas/sqlx-null"]},
+                {"id":"f3","type":"implement","scope":"a","description":"z","verify":"true"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(list.replace_context_hint("gotchas/sqlx-null", "gotchas/sqlx-nullable"), 2);
+        assert_eq!(list.features[0].context_hints, vec!["gotchas/sqlx-nullable"]);
+        assert_eq!(list.features[1].context_hints, vec!["decisions/orm", "gotchas/sqlx-nullable"]);
+        assert_eq!(list.replace_context_hint("gotchas/sqlx-null", "gotchas/sqlx-nullable"), 0);
+    }
+
+    #[test]
+    fn next_id_per_type() {
+        let list: FeatureList = serde_json::from_str(
+            r#"{"features":[
+                {"id":"f002","type":"implement","scope":"a","description":"x","verify":"true"},
+                {"id":"f010","type":"implement","scope":"a","description":"y","verify":"true"},
+                {"id":"r001","type":"review","scope":"a","description":"M1","verify":"true"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(list.next_id(&FeatureType::Implement), "f011");
+        assert_eq!(list.next_id(&FeatureType::Review), "r002");
+        assert_eq!(list.next_id(&FeatureType::Poc), "p001");
+    }
 }
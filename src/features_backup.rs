@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::features::{FeatureError, FeatureList};
+
+/// Backups kept; older ones are deleted as new ones are written.
+pub const KEEP: usize = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("no features.json backup '{0}' (see `forge restore-features`)")]
+    NotFound(String),
+    #[error("'{0}' matches more than one backup: {1}")]
+    Ambiguous(String, String),
+    #[error("backup {0} isn't a valid feature list: {1}")]
+    Invalid(String, serde_json::Error),
+    #[error("{0}")]
+    Features(#[from] FeatureError),
+}
+
+/// `.forge/backups/features/<timestamp>.json`: features.json as it was
+/// before each save and each agent session.
+pub fn backups_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/backups/features")
+}
+
+/// One saved copy, named by when it was taken (local time).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub timestamp: String,
+    pub path: PathBuf,
+    /// Features in it; None if it doesn't parse.
+    pub features: Option<usize>,
+}
+
+/// Every backup, oldest first.
+pub fn list(project_dir: &Path) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = std::fs::read_dir(backups_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path();
+            let timestamp = path.file_name()?.to_str()?.strip_suffix(".json")?.to_string();
+            let features = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str::<FeatureList>(&text).ok())
+                .map(|list| list.features.len());
+            Some(Backup { timestamp, path, features })
+        })
+        .collect();
+    backups.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    backups
+}
+
+/// Copy features.json as it is now, unless the newest backup already
+/// has it, then drop all but the newest `KEEP`. Taken as found, broken or
+/// not: whatever is there is about to be written over.
+pub fn backup(project_dir: &Path) -> Result<Option<Backup>, BackupError> {
+    let Ok(current) = std::fs::read(project_dir.join("features.json")) else {
+        return Ok(None);
+    };
+    let mut backups = list(project_dir);
+    if backups.last().is_some_and(|b| std::fs::read(&b.path).is_ok_and(|last| last == current)) {
+        return Ok(None);
+    }
+    let dir = backups_dir(project_dir);
+    std::fs::create_dir_all(&dir)?;
+    let mut timestamp = Local::now().format("%Y%m%d-%H%M%S%.6f").to_string();
+    while dir.join(format!("{timestamp}.json")).exists() {
+        timestamp.push('+');
+    }
+    let path = dir.join(format!("{timestamp}.json"));
+    std::fs::write(&path, &current)?;
+    let features = serde_json::from_slice::<FeatureList>(&current).ok().map(|list| list.features.len());
+    let backup = Backup { timestamp, path, features };
+    backups.retain(|b| b.timestamp != backup.timestamp);
+    backups.push(backup.clone());
+    for old in &backups[..backups.len().saturating_sub(KEEP)] {
+        let _ = std::fs::remove_file(&old.path);
+    }
+    Ok(Some(backup))
+}
+
+/// Put backup `timestamp` (or the one it's the start of) back as
+/// features.json. It's saved like any other change: the current file is
+/// backed up first, and the audit log records what the restore changed.
+pub fn restore(project_dir: &Path, timestamp: &str) -> Result<(Backup, FeatureList), BackupError> {
+    let matching: Vec<Backup> = list(project_dir).into_iter().filter(|b| b.timestamp.starts_with(timestamp)).collect();
+    let exact = matching.iter().find(|b| b.timestamp == timestamp);
+    let backup = match (matching.as_slice(), exact) {
+        ([], _) => return Err(BackupError::NotFound(timestamp.to_string())),
+        ([only], _) => only.clone(),
+        (_, Some(exact)) => exact.clone(),
+        (many, None) => {
+            let names: Vec<&str> = many.iter().map(|b| b.timestamp.as_str()).collect();
+            return Err(BackupError::Ambiguous(timestamp.to_string(), names.join(", ")));
+        }
+    };
+    let text = std::fs::read_to_string(&backup.path)?;
+    let list: FeatureList =
+        serde_json::from_str(&text).map_err(|e| BackupError::Invalid(backup.timestamp.clone(), e))?;
+    list.save(project_dir)?;
+    Ok((backup, list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_up_before_saves_and_restores() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let good = r#"{"features":[{"id":"f001","type":"implement","scope":"a","description":"x","verify":"true"}]}"#;
+        std::fs::write(root.join("features.json"), good).unwrap();
+        let features = FeatureList::load(root).unwrap();
+        features.save(root).unwrap();
+        features.save(root).unwrap();
+        assert_eq!(list(root).len(), 2, "the original, then as forge formats it");
+
+        // An agent mangles it
+        std::fs::write(root.join("features.json"), "{\"features\": [").unwrap();
+        assert!(backup(root).unwrap().is_some());
+        assert!(backup(root).unwrap().is_none(), "unchanged since the last one");
+        let backups = list(root);
+        assert_eq!(backups.iter().map(|b| b.features).collect::<Vec<_>>(), [Some(1), Some(1), None]);
+
+        assert!(matches!(restore(root, &backups[2].timestamp), Err(BackupError::Invalid(..))));
+        assert!(matches!(restore(root, "1999"), Err(BackupError::NotFound(_))));
+        assert!(matches!(restore(root, "2"), Err(BackupError::Ambiguous(..))));
+        let (restored, restored_list) = restore(root, &backups[0].timestamp).unwrap();
+        assert_eq!(restored, backups[0]);
+        assert_eq!(FeatureList::load(root).unwrap(), restored_list);
+        assert_eq!(restored_list.features[0].id, "f001");
+        assert_eq!(list(root).len(), 3, "the broken file was already backed up");
+
+        for i in 0..KEEP + 5 {
+            std::fs::write(root.join("features.json"), format!("{{\"features\":[]}} {i}")).unwrap();
+            backup(root).unwrap();
+        }
+        assert_eq!(list(root).len(), KEEP);
+    }
+}
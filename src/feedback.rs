@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FeedbackError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("not valid exec-memory: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// `feedback/exec-memory/<id>.json`: what an executor tried, what proves
+/// the feature is delivered, and how it went about it. The schema is the
+/// one skills/forge-protocol/CONTEXT-WRITING.md tells agents to write;
+/// fields it doesn't name are ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecMemory {
+    #[serde(default)]
+    pub feature_id: String,
+    #[serde(default)]
+    pub attempts: Vec<Attempt>,
+    #[serde(default)]
+    pub delivery: Vec<Delivery>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tactics: Option<Tactics>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Attempt {
+    #[serde(default)]
+    pub number: u32,
+    #[serde(default)]
+    pub summary: String,
+    /// Empty when the attempt succeeded.
+    #[serde(default)]
+    pub failed_reason: String,
+    #[serde(default)]
+    pub discoveries: Vec<String>,
+}
+
+/// One requirement of the description, mapped to the code, test and
+/// verify line that prove it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Delivery {
+    #[serde(default)]
+    pub requirement: String,
+    #[serde(default)]
+    pub implemented_in: String,
+    #[serde(default)]
+    pub tested_by: String,
+    #[serde(default)]
+    pub verified_by: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Tactics {
+    #[serde(default)]
+    pub context_used: Vec<String>,
+    #[serde(default)]
+    pub key_files_read: Vec<String>,
+    #[serde(default)]
+    pub approach: String,
+    #[serde(default)]
+    pub test_strategy: String,
+    /// `pass` or `fail`.
+    #[serde(default)]
+    pub verify_result: String,
+    #[serde(default)]
+    pub insights: Vec<String>,
+    #[serde(default)]
+    pub performance_notes: String,
+}
+
+pub fn exec_memory_path(project_dir: &Path, feature_id: &str) -> PathBuf {
+    project_dir.join(format!("feedback/exec-memory/{feature_id}.json"))
+}
+
+/// The feature's exec-memory; None when there isn't one.
+pub fn load(project_dir: &Path, feature_id: &str) -> Result<Option<ExecMemory>, FeedbackError> {
+    let path = exec_memory_path(project_dir, feature_id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+}
+
+impl ExecMemory {
+    /// Where this falls short of a handoff for `feature_id`.
+    pub fn problems(&self, feature_id: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.feature_id.is_empty() {
+            problems.push("no feature_id".to_string());
+        } else if self.feature_id != feature_id {
+            problems.push(format!("feature_id is '{}', not '{feature_id}'", self.feature_id));
+        }
+
+        if self.delivery.is_empty() {
+            problems.push("no delivery proof: requirements not mapped to code/tests".to_string());
+        }
+        // A requirement with no test or verify line is fine if the insights say why
+        let explained = self.tactics.as_ref().is_some_and(|t| !t.insights.is_empty());
+        for (i, entry) in self.delivery.iter().enumerate() {
+            let mut missing = Vec::new();
+            for (field, value, required) in [
+                ("requirement", &entry.requirement, true),
+                ("implemented_in", &entry.implemented_in, true),
+                ("tested_by", &entry.tested_by, !explained),
+                ("verified_by", &entry.verified_by, !explained),
+            ] {
+                if required && value.trim().is_empty() {
+                    missing.push(field);
+                }
+            }
+            if !missing.is_empty() {
+                problems.push(format!("delivery[{i}] has no {}", missing.join(", ")));
+            }
+        }
+
+        match &self.tactics {
+            None => problems.push("no tactics".to_string()),
+            Some(tactics) => {
+                if tactics.approach.trim().is_empty() {
+                    problems.push("tactics has no approach".to_string());
+                }
+                if !matches!(tactics.verify_result.as_str(), "pass" | "fail") {
+                    problems.push(format!("tactics.verify_result is '{}', not pass or fail", tactics.verify_result));
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// What's wrong with the handoff a session left for `feature_id`: a
+/// missing or unreadable exec-memory, or one short of the schema.
+pub fn check_handoff(project_dir: &Path, feature_id: &str) -> Vec<String> {
+    match load(project_dir, feature_id) {
+        Ok(Some(memory)) => memory.problems(feature_id),
+        Ok(None) => vec!["no exec-memory: the agent skipped the handoff".to_string()],
+        Err(e) => vec![e.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_handoffs_short_of_the_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |json: &str| {
+            std::fs::create_dir_all(root.join("feedback/exec-memory")).unwrap();
+            std::fs::write(exec_memory_path(root, "f001"), json).unwrap();
+        };
+        assert_eq!(check_handoff(root, "f001"), ["no exec-memory: the agent skipped the handoff"]);
+
+        write(r#"{"feature_id":"f001","delivery":"see commit"}"#);
+        let problems = check_handoff(root, "f001");
+        assert!(problems[0].starts_with("not valid exec-memory: invalid type"), "{problems:?}");
+
+        write(r#"{"feature_id":"f002","delivery":[{"requirement":"parse","implemented_in":"src/p.rs:1"}],
+                  "tactics":{"approach":"","verify_result":"ok"}}"#);
+        assert_eq!(
+            check_handoff(root, "f001"),
+            [
+                "feature_id is 'f002', not 'f001'",
+                "delivery[0] has no tested_by, verified_by",
+                "tactics has no approach",
+                "tactics.verify_result is 'ok', not pass or fail",
+            ]
+        );
+
+        write(
+            r#"{"feature_id":"f001","attempts":[{"number":1,"summary":"did it","failed_reason":""}],
+                "delivery":[{"requirement":"parse","implemented_in":"src/p.rs:1"}],
+                "tactics":{"approach":"recursive descent","verify_result":"pass",
+                           "insights":["no test: parse is covered by verify's smoke run"]},
+                "extra":"ignored"}"#,
+        );
+        assert!(check_handoff(root, "f001").is_empty());
+        let memory = load(root, "f001").unwrap().unwrap();
+        assert_eq!(memory.attempts[0].summary, "did it");
+    }
+}
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::process::Command;
 
+use crate::config::GitConfig;
+use crate::large_files;
+use crate::secret_scan::{self, SecretScanner};
+
 /// Check if directory is inside a git work tree.
 pub fn is_git_repo(dir: &Path) -> bool {
     Command::new("git")
@@ -13,6 +18,21 @@ pub fn is_git_repo(dir: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// `git <args>` in `dir`: its output less the trailing newline, or its
+/// stderr if it fails.
+pub fn run(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git {} failed: {e}", args.first().unwrap_or(&"")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.join(" "), stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
 /// Check if the repo has a remote configured.
 pub fn has_remote(dir: &Path) -> bool {
     Command::new("git")
@@ -40,8 +60,32 @@ pub fn pull(dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
-/// Stage all changes and commit. Returns false if nothing to commit.
-pub fn add_and_commit(dir: &Path, message: &str) -> Result<bool, String> {
+/// What `add_and_commit` checks staged changes for.
+pub struct CommitGuard {
+    pub scanner: SecretScanner,
+    /// Files bigger than this are left out of the commit; 0 for no limit.
+    pub max_file_bytes: u64,
+}
+
+impl Default for CommitGuard {
+    fn default() -> Self {
+        Self::new(&GitConfig::default(), &BTreeMap::new())
+    }
+}
+
+impl CommitGuard {
+    /// From `[git] secret_scan` and `max_file_mb`, with the resolved `[secrets]`.
+    pub fn new(config: &GitConfig, secrets: &BTreeMap<String, String>) -> Self {
+        Self { scanner: SecretScanner::new(config, secrets), max_file_bytes: large_files::limit_bytes(config.max_file_mb) }
+    }
+}
+
+/// Stage all changes and commit. Returns false if nothing to commit. Files
+/// over the guard's size limit are unstaged and noted in the
+/// `gotchas/large-files` context entry, which is committed instead. If the
+/// scanner finds a secret in what was staged, nothing is committed and the
+/// changes are left unstaged.
+pub fn add_and_commit(dir: &Path, message: &str, guard: &CommitGuard) -> Result<bool, String> {
     // Stage all
     Command::new("git")
         .args(["add", "-A"])
@@ -49,6 +93,14 @@ pub fn add_and_commit(dir: &Path, message: &str) -> Result<bool, String> {
         .output()
         .map_err(|e| format!("git add failed: {e}"))?;
 
+    let oversized = large_files::staged(dir, guard.max_file_bytes);
+    if !oversized.is_empty() {
+        let paths: Vec<&str> = oversized.iter().map(|f| f.path.as_str()).collect();
+        let _ = Command::new("git").args(["reset", "-q", "--"]).args(&paths).current_dir(dir).output();
+        large_files::record_gotcha(dir, &oversized);
+        let _ = Command::new("git").args(["add", "--", "context/gotchas"]).current_dir(dir).output();
+    }
+
     // Check for staged changes
     let status = Command::new("git")
         .args(["diff", "--cached", "--quiet"])
@@ -60,6 +112,12 @@ pub fn add_and_commit(dir: &Path, message: &str) -> Result<bool, String> {
         return Ok(false); // nothing staged
     }
 
+    let findings = guard.scanner.scan_staged(dir)?;
+    if !findings.is_empty() {
+        let _ = Command::new("git").args(["reset", "-q"]).current_dir(dir).output();
+        return Err(format!("secret scan blocked the commit: {}", secret_scan::summary(&findings)));
+    }
+
     let output = Command::new("git")
         .args(["commit", "-m", message])
         .current_dir(dir)
@@ -73,6 +131,188 @@ pub fn add_and_commit(dir: &Path, message: &str) -> Result<bool, String> {
     Ok(true)
 }
 
+/// Stage and commit only the given paths, leaving other working-tree changes
+/// alone. Returns false if none of them changed.
+pub fn commit_paths(dir: &Path, paths: &[&str], message: &str) -> Result<bool, String> {
+    Command::new("git")
+        .arg("add")
+        .arg("--")
+        .args(paths)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git add failed: {e}"))?;
+
+    let status = Command::new("git")
+        .args(["diff", "--cached", "--quiet", "--"])
+        .args(paths)
+        .current_dir(dir)
+        .status()
+        .map_err(|e| format!("git diff failed: {e}"))?;
+
+    if status.success() {
+        return Ok(false);
+    }
+
+    let output = Command::new("git")
+        .args(["commit", "-m", message, "--"])
+        .args(paths)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git commit failed: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git commit failed: {stderr}"));
+    }
+    Ok(true)
+}
+
+/// Common ancestor of two revisions, if they share history.
+pub fn merge_base(dir: &Path, a: &str, b: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["merge-base", a, b])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A file's content at a revision; None if it didn't exist there.
+pub fn show_file(dir: &Path, rev: &str, path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["show", &format!("{rev}:{path}")])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Paths left unmerged by a conflicted merge.
+pub fn conflicted_paths(dir: &Path) -> Vec<String> {
+    Command::new("git")
+        .args(["diff", "--name-only", "--diff-filter=U"])
+        .current_dir(dir)
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Paths changed from `from` to `to`. Without `to`, against the working tree,
+/// untracked files included.
+pub fn changed_paths(dir: &Path, from: &str, to: Option<&str>) -> Vec<String> {
+    let lines = |args: &[&str]| -> Vec<String> {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(String::from).collect())
+            .unwrap_or_default()
+    };
+    let mut paths = match to {
+        Some(to) => lines(&["diff", "--name-only", from, to]),
+        None => {
+            let mut paths = lines(&["diff", "--name-only", from]);
+            paths.extend(untracked_files(dir));
+            paths
+        }
+    };
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// `git diff` with no context lines, as the secret scan reads it, unless
+/// `args` ask for some.
+pub fn diff(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(["diff", "-U0", "--no-color", "--no-ext-diff"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git diff failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git diff failed: {stderr}"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Values of a commit trailer (`Forge-Feature`) in `range`, for commits
+/// touching `paths`; sorted, without duplicates.
+pub fn trailer_values(dir: &Path, key: &str, range: &str, paths: &[String]) -> Vec<String> {
+    let format = format!("--format=%(trailers:key={key},valueonly)");
+    let mut values: Vec<String> = Command::new("git")
+        .args(["log", &format, range, "--"])
+        .args(paths)
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect())
+        .unwrap_or_default();
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// Files git doesn't track and doesn't ignore.
+pub fn untracked_files(dir: &Path) -> Vec<String> {
+    Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard"])
+        .current_dir(dir)
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// The `paths` matching a protected glob. A pattern naming a directory
+/// (`migrations` or `migrations/`) covers everything under it.
+pub fn protected_paths(paths: &[String], patterns: &[String]) -> Vec<String> {
+    let patterns: Vec<regex::Regex> = patterns.iter().map(|p| crate::config_validate::glob_regex(p)).collect();
+    paths
+        .iter()
+        .filter(|path| {
+            let mut prefixes = path.match_indices('/').map(|(i, _)| &path[..i]).chain([path.as_str()]);
+            prefixes.any(|prefix| patterns.iter().any(|re| re.is_match(prefix)))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Put `paths` back to how they were at `rev` in the working tree and index:
+/// restored if they existed there, removed if not.
+pub fn restore_paths(dir: &Path, rev: &str, paths: &[String]) -> Result<(), String> {
+    for path in paths {
+        let args: Vec<&str> = if show_file(dir, rev, path).is_some() {
+            vec!["checkout", rev, "--", path]
+        } else {
+            vec!["rm", "-q", "-r", "-f", "--ignore-unmatch", "--", path]
+        };
+        let output = Command::new("git")
+            .args(&args)
+            .current_dir(dir)
+            .output()
+            .map_err(|e| format!("git {} failed: {e}", args[0]))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("git {} failed: {stderr}", args[0]));
+        }
+        // Untracked files git rm doesn't know about
+        if args[0] == "rm" {
+            let _ = std::fs::remove_file(dir.join(path));
+        }
+    }
+    Ok(())
+}
+
 /// Push to remote. No-op if no remote. Returns false if push fails (e.g. conflict).
 pub fn push(dir: &Path) -> Result<bool, String> {
     if !has_remote(dir) {
@@ -86,6 +326,205 @@ pub fn push(dir: &Path) -> Result<bool, String> {
     Ok(output.status.success())
 }
 
+/// Push; if the remote moved on, `pull --rebase` and try again, up to
+/// `attempts` pushes in all.
+pub fn push_with_retry(dir: &Path, attempts: usize) -> Result<(), String> {
+    for attempt in 1..=attempts {
+        if push(dir)? {
+            return Ok(());
+        }
+        if attempt < attempts {
+            pull(dir)?;
+        }
+    }
+    Err(format!("git push rejected {attempts} times"))
+}
+
+/// The checked-out branch; None on a detached HEAD.
+pub fn current_branch(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The commit a revision points at.
+pub fn rev_parse(dir: &Path, rev: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Point `branch` at `rev`, creating it or moving it.
+pub fn set_branch(dir: &Path, branch: &str, rev: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["branch", "-f", branch, rev])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git branch failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git branch failed: {stderr}"));
+    }
+    Ok(())
+}
+
+/// Push `branch` to origin. forge owns its PR branches, so a rerun replaces
+/// what it pushed before, unless someone else pushed there since.
+pub fn push_branch(dir: &Path, branch: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["push", "--force-with-lease", "-u", "origin", branch])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git push failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git push failed: {stderr}"));
+    }
+    Ok(())
+}
+
+/// Open a pull request from `head` into `base` with the GitHub CLI and return
+/// its URL. A PR already open for `head` is reused: pushing updated it.
+pub fn open_pr(dir: &Path, head: &str, base: &str, title: &str, body: &str, draft: bool) -> Result<String, String> {
+    let existing = Command::new("gh")
+        .args(["pr", "view", head, "--json", "url,state", "-q", "select(.state == \"OPEN\") | .url"])
+        .current_dir(dir)
+        .output();
+    if let Ok(output) = existing
+        && output.status.success()
+    {
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !url.is_empty() {
+            return Ok(url);
+        }
+    }
+
+    let mut command = Command::new("gh");
+    command
+        .args(["pr", "create", "--head", head, "--base", base, "--title", title, "--body-file", "-"])
+        .current_dir(dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    if draft {
+        command.arg("--draft");
+    }
+    let mut child = command.spawn().map_err(|e| format!("gh pr create failed: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        let _ = stdin.write_all(body.as_bytes());
+    }
+    let output = child.wait_with_output().map_err(|e| format!("gh pr create failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("gh pr create failed: {stderr}"));
+    }
+    // gh prints the new PR's URL last
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().last().unwrap_or_default().trim().to_string())
+}
+
+/// Put the tree back as it was at `rev`, as one new commit on top of HEAD
+/// that undoes everything since, merges included. Uncommitted changes
+/// elsewhere are left alone.
+pub fn revert_to(dir: &Path, rev: &str, message: &str) -> Result<(), String> {
+    let diff = Command::new("git")
+        .args(["diff", "--binary", "HEAD", rev])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git diff failed: {e}"))?;
+    if !diff.status.success() {
+        let stderr = String::from_utf8_lossy(&diff.stderr);
+        return Err(format!("git diff failed: {stderr}"));
+    }
+    if diff.stdout.is_empty() {
+        return Ok(());
+    }
+    let mut child = Command::new("git")
+        .args(["apply", "--index", "-"])
+        .current_dir(dir)
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("git apply failed: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        let _ = stdin.write_all(&diff.stdout);
+    }
+    let output = child.wait_with_output().map_err(|e| format!("git apply failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git apply failed: {stderr}"));
+    }
+    let output = Command::new("git")
+        .args(["commit", "-q", "-m", message])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git commit failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git commit failed: {stderr}"));
+    }
+    Ok(())
+}
+
+/// Point the ref `name` (e.g. `refs/forge/checkpoints/good`) at `rev`, so
+/// the commit outlives branch moves and gc.
+pub fn update_ref(dir: &Path, name: &str, rev: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["update-ref", name, rev])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git update-ref failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git update-ref failed: {stderr}"));
+    }
+    Ok(())
+}
+
+/// Create an annotated tag at HEAD. False if the tag already exists.
+pub fn create_tag(dir: &Path, name: &str, message: &str) -> Result<bool, String> {
+    if rev_parse(dir, &format!("refs/tags/{name}")).is_some() {
+        return Ok(false);
+    }
+    let output = Command::new("git")
+        .args(["tag", "-a", name, "-m", message])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git tag failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git tag failed: {stderr}"));
+    }
+    Ok(true)
+}
+
+pub fn push_tag(dir: &Path, name: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["push", "origin", &format!("refs/tags/{name}")])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("git push failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git push failed: {stderr}"));
+    }
+    Ok(())
+}
+
 /// Create a git worktree for an agent.
 pub fn create_worktree(repo_dir: &Path, worktree_dir: &Path, branch: &str) -> Result<(), String> {
     // Create branch if it doesn't exist
@@ -132,6 +571,44 @@ pub fn remove_worktree(repo_dir: &Path, worktree_dir: &Path) -> Result<(), Strin
     Ok(())
 }
 
+/// Throw away a worktree's uncommitted changes and untracked files, keeping
+/// ignored ones like build output. With `rev`, also point `branch` there
+/// and check it out.
+pub fn reset_worktree(worktree_dir: &Path, branch: &str, rev: Option<&str>) -> Result<(), String> {
+    let mut steps = vec![vec!["reset", "-q", "--hard"], vec!["clean", "-q", "-fd"]];
+    if let Some(rev) = rev {
+        steps.push(vec!["checkout", "-q", "-B", branch, rev]);
+    }
+    for args in steps {
+        let output = Command::new("git")
+            .args(&args)
+            .current_dir(worktree_dir)
+            .output()
+            .map_err(|e| format!("git {} failed: {e}", args[0]))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("git {} failed: {stderr}", args[0]));
+        }
+    }
+    Ok(())
+}
+
+/// Delete a local branch. Unless `force`, git refuses to delete unmerged work.
+pub fn delete_branch(repo_dir: &Path, branch: &str, force: bool) -> Result<(), String> {
+    let flag = if force { "-D" } else { "-d" };
+    let output = Command::new("git")
+        .args(["branch", flag, branch])
+        .current_dir(repo_dir)
+        .output()
+        .map_err(|e| format!("git branch {flag} failed: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git branch {flag} failed: {stderr}"));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,14 +664,30 @@ mod tests {
         init_repo(dir.path());
 
         // Nothing to commit
-        assert!(!add_and_commit(dir.path(), "empty").unwrap());
+        assert!(!add_and_commit(dir.path(), "empty", &CommitGuard::default()).unwrap());
 
         // Create file and commit
         std::fs::write(dir.path().join("test.txt"), "hello").unwrap();
-        assert!(add_and_commit(dir.path(), "add test").unwrap());
+        assert!(add_and_commit(dir.path(), "add test", &CommitGuard::default()).unwrap());
 
         // Nothing to commit again
-        assert!(!add_and_commit(dir.path(), "empty again").unwrap());
+        assert!(!add_and_commit(dir.path(), "empty again", &CommitGuard::default()).unwrap());
+    }
+
+    #[test]
+    fn add_and_commit_leaves_out_large_files() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("model.bin"), vec![0u8; 4_096]).unwrap();
+        let guard = CommitGuard { max_file_bytes: 1_000, ..CommitGuard::default() };
+        assert!(add_and_commit(dir.path(), "work", &guard).unwrap());
+
+        let committed = Command::new("git").args(["ls-files"]).current_dir(dir.path()).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&committed.stdout), "README.md\ncontext/gotchas/large-files.md\nmain.rs\n");
+        assert_eq!(untracked_files(dir.path()), ["model.bin"]);
+        let gotcha = std::fs::read_to_string(dir.path().join("context/gotchas/large-files.md")).unwrap();
+        assert!(gotcha.contains("- `model.bin` (0.0 MB)"), "{gotcha}");
     }
 
     #[test]
@@ -224,4 +717,167 @@ mod tests {
         remove_worktree(dir.path(), &wt).unwrap();
         assert!(!wt.exists());
     }
-}
+
+    #[test]
+    fn commit_paths_ignores_other_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+
+        std::fs::write(dir.path().join("features.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("scratch.txt"), "wip").unwrap();
+        assert!(commit_paths(dir.path(), &["features.json"], "claim").unwrap());
+        assert!(!commit_paths(dir.path(), &["features.json"], "again").unwrap());
+
+        // scratch.txt is still untracked
+        let output = Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        let status = String::from_utf8_lossy(&output.stdout);
+        assert!(status.contains("?? scratch.txt"), "got: {status}");
+    }
+
+    #[test]
+    fn set_branch_points_at_rev() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        let main = current_branch(dir.path()).unwrap();
+        let head = rev_parse(dir.path(), "HEAD").unwrap();
+
+        set_branch(dir.path(), "forge/f001", "HEAD").unwrap();
+        std::fs::write(dir.path().join("more.txt"), "more").unwrap();
+        add_and_commit(dir.path(), "more", &CommitGuard::default()).unwrap();
+        assert_eq!(rev_parse(dir.path(), "forge/f001").unwrap(), head);
+        // Moving it along doesn't check it out
+        set_branch(dir.path(), "forge/f001", "HEAD").unwrap();
+        assert_eq!(rev_parse(dir.path(), "forge/f001"), rev_parse(dir.path(), "HEAD"));
+        assert_eq!(current_branch(dir.path()).unwrap(), main);
+        assert_eq!(rev_parse(dir.path(), "no-such-branch"), None);
+    }
+
+    #[test]
+    fn protected_changes_are_found_and_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::create_dir_all(dir.path().join("migrations")).unwrap();
+        std::fs::write(dir.path().join("migrations/001.sql"), "create table t;").unwrap();
+        add_and_commit(dir.path(), "schema", &CommitGuard::default()).unwrap();
+        let start = rev_parse(dir.path(), "HEAD").unwrap();
+
+        std::fs::write(dir.path().join("migrations/001.sql"), "drop table t;").unwrap();
+        add_and_commit(dir.path(), "agent edit", &CommitGuard::default()).unwrap();
+        std::fs::create_dir_all(dir.path().join(".github/workflows")).unwrap();
+        std::fs::write(dir.path().join(".github/workflows/ci.yml"), "on: push").unwrap();
+        std::fs::write(dir.path().join("src.rs"), "fn main() {}").unwrap();
+
+        let changed = changed_paths(dir.path(), &start, None);
+        assert_eq!(changed, [".github/workflows/ci.yml", "migrations/001.sql", "src.rs"]);
+        assert_eq!(changed_paths(dir.path(), &start, Some("HEAD")), ["migrations/001.sql"]);
+        let protected = protected_paths(&changed, &["migrations".into(), ".github/**".into()]);
+        assert_eq!(protected, [".github/workflows/ci.yml", "migrations/001.sql"]);
+        assert!(protected_paths(&changed, &["*.sql".into()]).is_empty(), "globs match whole paths");
+
+        restore_paths(dir.path(), &start, &protected).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("migrations/001.sql")).unwrap(), "create table t;");
+        assert!(!dir.path().join(".github/workflows/ci.yml").exists());
+        assert!(dir.path().join("src.rs").exists());
+    }
+
+    #[test]
+    fn delete_branch_refuses_unmerged() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+
+        let wt = dir.path().join("worktree-agent-1");
+        create_worktree(dir.path(), &wt, "forge/agent-1").unwrap();
+        std::fs::write(wt.join("work.txt"), "agent work").unwrap();
+        add_and_commit(&wt, "agent work", &CommitGuard::default()).unwrap();
+        remove_worktree(dir.path(), &wt).unwrap();
+
+        assert!(delete_branch(dir.path(), "forge/agent-1", false).is_err());
+        delete_branch(dir.path(), "forge/agent-1", true).unwrap();
+    }
+
+    #[test]
+    fn reset_worktree_keeps_ignored_files() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        add_and_commit(dir.path(), "ignore target", &CommitGuard::default()).unwrap();
+        let wt = dir.path().join("worktree-agent-1");
+        create_worktree(dir.path(), &wt, "forge/agent-1").unwrap();
+        std::fs::create_dir_all(wt.join("target")).unwrap();
+        std::fs::write(wt.join("target/cache"), "built").unwrap();
+        std::fs::write(wt.join("stray.rs"), "").unwrap();
+        std::fs::write(wt.join("README.md"), "edited").unwrap();
+
+        // Meanwhile the project moved on
+        std::fs::write(dir.path().join("main.rs"), "").unwrap();
+        add_and_commit(dir.path(), "main work", &CommitGuard::default()).unwrap();
+        let head = rev_parse(dir.path(), "HEAD").unwrap();
+
+        reset_worktree(&wt, "forge/agent-1", Some(&head)).unwrap();
+        assert_eq!(rev_parse(&wt, "HEAD").unwrap(), head);
+        assert_eq!(current_branch(&wt).as_deref(), Some("forge/agent-1"));
+        assert!(wt.join("target/cache").exists() && wt.join("main.rs").exists());
+        assert!(!wt.join("stray.rs").exists());
+        assert_eq!(std::fs::read_to_string(wt.join("README.md")).unwrap(), "# test\n");
+        remove_worktree(dir.path(), &wt).unwrap();
+    }
+
+    #[test]
+    fn revert_to_undoes_a_merge_in_one_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        let guard = CommitGuard::default();
+        let before = rev_parse(dir.path(), "HEAD").unwrap();
+        create_worktree(dir.path(), &dir.path().join("wt"), "forge/agent-1").unwrap();
+        std::fs::write(dir.path().join("wt/added.rs"), "").unwrap();
+        std::fs::write(dir.path().join("wt/README.md"), "changed\n").unwrap();
+        add_and_commit(&dir.path().join("wt"), "agent work", &guard).unwrap();
+        std::fs::write(dir.path().join("main.rs"), "").unwrap();
+        add_and_commit(dir.path(), "main work", &guard).unwrap();
+        let merge = Command::new("git").args(["merge", "-q", "--no-edit", "forge/agent-1"]).current_dir(dir.path()).status();
+        assert!(merge.unwrap().success());
+        std::fs::write(dir.path().join("notes.txt"), "untracked").unwrap();
+
+        revert_to(dir.path(), &before, "revert: agent work").unwrap();
+        assert!(!dir.path().join("added.rs").exists() && !dir.path().join("main.rs").exists());
+        assert_eq!(std::fs::read_to_string(dir.path().join("README.md")).unwrap(), "# test\n");
+        assert!(dir.path().join("notes.txt").exists());
+        let log = Command::new("git").args(["log", "-1", "--format=%s"]).current_dir(dir.path()).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&log.stdout), "revert: agent work\n");
+        remove_worktree(dir.path(), &dir.path().join("wt")).unwrap();
+    }
+
+    #[test]
+    fn push_with_retry_rebases_onto_moved_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |cwd: &Path, args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(cwd).output().unwrap();
+            assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        let (origin, ours, theirs) = (dir.path().join("origin"), dir.path().join("ours"), dir.path().join("theirs"));
+        std::fs::create_dir_all(&ours).unwrap();
+        init_repo(&ours);
+        git(dir.path(), &["clone", "-q", "--bare", "ours", "origin"]);
+        git(&ours, &["remote", "add", "origin", origin.to_str().unwrap()]);
+        git(&ours, &["fetch", "-q", "origin"]);
+        let branch = current_branch(&ours).unwrap();
+        git(&ours, &["branch", "-q", "-u", &format!("origin/{branch}")]);
+        git(dir.path(), &["clone", "-q", "origin", "theirs"]);
+        for (repo, file) in [(&theirs, "theirs.txt"), (&ours, "ours.txt")] {
+            git(repo, &["config", "user.email", "t@t"]);
+            git(repo, &["config", "user.name", "t"]);
+            std::fs::write(repo.join(file), "x").unwrap();
+            git(repo, &["add", file]);
+            git(repo, &["commit", "-qm", file]);
+        }
+        git(&theirs, &["push", "-q"]);
+
+        push_with_retry(&ours, 2).unwrap();
+        assert_eq!(git(&origin, &["log", "--format=%s", "-3"]), "ours.txt\ntheirs.txt\ninit");
+    }
+}
\ No newline at end of file
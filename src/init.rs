@@ -1,8 +1,13 @@
+use crate::analyze::{self, Analysis};
+use crate::claude_settings;
 use crate::config::ForgeConfig;
 use crate::context::ContextManager;
+use crate::design;
+use crate::mcp;
 use crate::skills;
 use crate::template;
-use std::path::Path;
+use crate::verify_template;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, thiserror::Error)]
 pub enum InitError {
@@ -14,51 +19,125 @@ pub enum InitError {
     Context(#[from] crate::context::ContextError),
     #[error("feature error: {0}")]
     Feature(#[from] crate::features::FeatureError),
-    #[error("project already initialized: forge.toml exists")]
+    #[error("project already initialized: forge.toml exists (--force re-initializes it)")]
     AlreadyInitialized,
 }
 
-/// Initialize a forge project in the given directory.
-pub fn init_project(project_dir: &Path, description: &str) -> Result<(), InitError> {
-    let config_path = project_dir.join("forge.toml");
-    if config_path.exists() {
-        return Err(InitError::AlreadyInitialized);
-    }
-
+/// Initialize a forge project in the given directory. With `force`, a
+/// project already there is re-initialized: forge.toml is rewritten (the old
+/// one kept as forge.toml.bak) and everything missing is recreated, but
+/// features.json, context/ and DESIGN.md are left as they are.
+pub fn init_project(project_dir: &Path, description: &str, force: bool) -> Result<(), InitError> {
     // Parse name from description (first word or slug)
     let name = slugify_name(description);
+    scaffold_project(project_dir, &ForgeConfig::scaffold(&name, ""), force)?;
+    write_design(project_dir, &design::skeleton(&name, description, &[]))?;
+    Ok(())
+}
+
+/// Initialize forge in a codebase that already exists: stack and scopes
+/// come from an analysis of it, and a DESIGN.md draft is written unless
+/// there is one. Without a description the project is named after its
+/// directory. `force` is as for [`init_project`].
+pub fn init_from_existing(project_dir: &Path, description: Option<&str>, force: bool) -> Result<Analysis, InitError> {
+    let analysis = analyze::analyze(project_dir);
+    let name = match description {
+        Some(description) => slugify_name(description),
+        None => {
+            let dir = std::fs::canonicalize(project_dir)?;
+            slugify_name(&dir.file_name().map(|n| n.to_string_lossy().replace(['_', '.'], " ")).unwrap_or_default())
+        }
+    };
+    let mut config = ForgeConfig::scaffold(&name, &analysis.stack());
+    config.scopes = analysis.scopes();
+    scaffold_project(project_dir, &config, force)?;
+    write_design(project_dir, &analysis.design_draft(&name))?;
+    Ok(analysis)
+}
+
+/// DESIGN.md, unless the project already has one.
+fn write_design(project_dir: &Path, content: &str) -> Result<(), std::io::Error> {
+    let path = project_dir.join("DESIGN.md");
+    if path.exists() {
+        return Ok(());
+    }
+    std::fs::write(path, content)
+}
+
+/// CLAUDE.md, AGENTS.md and the editor rules files, regenerated from `config`.
+fn write_agent_files(project_dir: &Path, config: &ForgeConfig) -> Result<(), std::io::Error> {
+    for (path, content) in template::agent_files(config) {
+        let path = project_dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+/// Directories every forge project has, relative to its root.
+pub fn project_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> =
+        ContextManager::new(project_dir).categories().iter().map(|c| Path::new("context").join(c)).collect();
+    dirs.extend(
+        ["context/packages", "feedback", "feedback/exec-memory", "scripts/verify", "references", ".forge"]
+            .map(PathBuf::from),
+    );
+    dirs
+}
+
+/// The [`project_dirs`] that don't exist.
+pub fn missing_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    project_dirs(project_dir).into_iter().filter(|d| !project_dir.join(d).is_dir()).collect()
+}
 
-    // Create forge.toml
-    let config = ForgeConfig::scaffold(&name, "");
+/// Create the [`missing_dirs`], returning them.
+pub fn create_missing_dirs(project_dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let missing = missing_dirs(project_dir);
+    for dir in &missing {
+        std::fs::create_dir_all(project_dir.join(dir))?;
+    }
+    Ok(missing)
+}
+
+/// Write `config` and everything else a new project needs. Without `force`
+/// an existing forge.toml is an error; with it, it's backed up and replaced,
+/// and an existing features.json is kept.
+fn scaffold_project(project_dir: &Path, config: &ForgeConfig, force: bool) -> Result<(), InitError> {
+    let config_path = project_dir.join("forge.toml");
+    if config_path.exists() {
+        if !force {
+            return Err(InitError::AlreadyInitialized);
+        }
+        std::fs::copy(&config_path, project_dir.join("forge.toml.bak"))?;
+    }
     config.save(project_dir)?;
 
     // Create directories
-    let ctx = ContextManager::new(project_dir);
-    ctx.init()?;
-    std::fs::create_dir_all(project_dir.join("feedback"))?;
-    std::fs::create_dir_all(project_dir.join("feedback/exec-memory"))?;
-    std::fs::create_dir_all(project_dir.join("scripts/verify"))?;
-    std::fs::create_dir_all(project_dir.join("context/packages"))?;
-
-    // Generate CLAUDE.md and AGENTS.md
-    let claude_md = template::generate_claude_md(&config);
-    std::fs::write(project_dir.join("CLAUDE.md"), &claude_md)?;
-    std::fs::write(project_dir.join("AGENTS.md"), &claude_md)?;
+    create_missing_dirs(project_dir)?;
+    verify_template::write_templates(project_dir, &config.project.stack)?;
+
+    // Generate CLAUDE.md, AGENTS.md and the editor rules files
+    write_agent_files(project_dir, config)?;
+    claude_settings::write(project_dir, config)?;
+    mcp::write_client_config(project_dir)?;
 
     // Create empty features.json
-    let features = crate::features::FeatureList {
-        features: vec![],
-    };
-    features.save(project_dir)?;
+    if !project_dir.join("features.json").exists() {
+        let features = crate::features::FeatureList {
+            features: vec![],
+        };
+        features.save(project_dir)?;
+    }
 
-    // Create references/ dir and add to .gitignore
-    std::fs::create_dir_all(project_dir.join("references"))?;
+    // Add generated dirs to .gitignore
     append_gitignore(project_dir, "references/")?;
     append_gitignore(project_dir, "context/packages/")?;
-    append_gitignore(project_dir, ".forge/")?;
+    write_runtime_ignore(project_dir, &config.git.commit_runtime)?;
 
     // Install skills
-    install_skills(project_dir)?;
+    install_skills(project_dir, false)?;
 
     // Sync CocoIndex context flow files (non-fatal)
     crate::context_flow::sync_context_flow(project_dir);
@@ -67,35 +146,30 @@ pub fn init_project(project_dir: &Path, description: &str) -> Result<(), InitErr
 }
 
 /// Install/update an existing forge project: skills, CLAUDE.md, directories, permissions.
-pub fn install_project(project_dir: &Path) -> Result<(), InitError> {
+/// Skill files edited since they were installed are kept unless `force`.
+pub fn install_project(project_dir: &Path, force: bool) -> Result<skills::SyncReport, InitError> {
     let config = ForgeConfig::load(project_dir)?;
 
     // Install/update skills
-    install_skills(project_dir)?;
+    let report = install_skills(project_dir, force)?;
 
     // Sync CocoIndex context flow files (non-fatal)
     crate::context_flow::sync_context_flow(project_dir);
 
-    // Regenerate CLAUDE.md and AGENTS.md from current config
-    let claude_md = template::generate_claude_md(&config);
-    std::fs::write(project_dir.join("CLAUDE.md"), &claude_md)?;
-    std::fs::write(project_dir.join("AGENTS.md"), &claude_md)?;
+    // Regenerate CLAUDE.md, AGENTS.md and the editor rules files from current config
+    write_agent_files(project_dir, &config)?;
+    claude_settings::write(project_dir, &config)?;
+    mcp::write_client_config(project_dir)?;
 
     // Ensure directories exist
-    let ctx = ContextManager::new(project_dir);
-    ctx.init()?;
-    std::fs::create_dir_all(project_dir.join("feedback"))?;
-    std::fs::create_dir_all(project_dir.join("feedback/exec-memory"))?;
-    std::fs::create_dir_all(project_dir.join("scripts/verify"))?;
-    std::fs::create_dir_all(project_dir.join(".forge"))?;
-    std::fs::create_dir_all(project_dir.join("context/packages"))?;
-    std::fs::create_dir_all(project_dir.join("references"))?;
+    create_missing_dirs(project_dir)?;
+    verify_template::write_templates(project_dir, &config.project.stack)?;
     append_gitignore(project_dir, "references/")?;
     append_gitignore(project_dir, "context/packages/")?;
-    append_gitignore(project_dir, ".forge/")?;
+    write_runtime_ignore(project_dir, &config.git.commit_runtime)?;
 
     // Regenerate context INDEX.md
-    ctx.write_index()?;
+    ContextManager::new(project_dir).write_index()?;
 
     // chmod +x on verify scripts
     #[cfg(unix)]
@@ -115,20 +189,35 @@ pub fn install_project(project_dir: &Path) -> Result<(), InitError> {
         }
     }
 
-    Ok(())
+    Ok(report)
 }
 
-/// Install all forge skills into .claude/skills/ and .agents/skills/.
-pub fn install_skills(project_dir: &Path) -> Result<(), std::io::Error> {
-    let dirs = [".claude/skills", ".agents/skills"];
-    for (skill_name, files) in skills::all_skills() {
-        for base in &dirs {
-            let skill_dir = project_dir.join(base).join(skill_name);
-            std::fs::create_dir_all(&skill_dir)?;
-            for &(filename, content) in &files {
-                std::fs::write(skill_dir.join(filename), content)?;
-            }
-        }
+/// Install all forge skills into .claude/skills/ and .agents/skills/,
+/// merged with the user's and the project's own skills.
+pub fn install_skills(project_dir: &Path, force: bool) -> Result<skills::SyncReport, std::io::Error> {
+    skills::sync_skills(project_dir, force)
+}
+
+/// First line of the .gitignore block that keeps `.forge/` out of commits.
+const RUNTIME_IGNORE_HEADER: &str = "# forge runtime state: logs, worktrees, indexes, exports ([git] commit_runtime)";
+
+/// Gitignore everything under `.forge/` except the `keep` entries, replacing
+/// the `.forge` lines any earlier forge wrote.
+pub fn write_runtime_ignore(project_dir: &Path, keep: &[String]) -> Result<(), std::io::Error> {
+    let gitignore = project_dir.join(".gitignore");
+    let existing = std::fs::read_to_string(&gitignore).unwrap_or_default();
+    let ours = |line: &str| {
+        let line = line.trim();
+        line == RUNTIME_IGNORE_HEADER || line.trim_start_matches('!').starts_with(".forge/")
+    };
+    let mut content: String = existing.lines().filter(|l| !ours(l)).map(|l| format!("{l}\n")).collect();
+    content.push_str(RUNTIME_IGNORE_HEADER);
+    content.push_str("\n.forge/*\n");
+    for entry in keep {
+        content.push_str(&format!("!.forge/{}\n", entry.trim_matches('/')));
+    }
+    if content != existing {
+        std::fs::write(gitignore, content)?;
     }
     Ok(())
 }
@@ -173,7 +262,7 @@ mod tests {
     #[test]
     fn init_creates_scaffold() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "My Test App").unwrap();
+        init_project(dir.path(), "My Test App", false).unwrap();
 
         // forge.toml
         assert!(dir.path().join("forge.toml").exists());
@@ -187,10 +276,23 @@ mod tests {
         let agents = std::fs::read_to_string(dir.path().join("AGENTS.md")).unwrap();
         assert_eq!(claude, agents);
         assert!(claude.contains("# my-test-app"));
+        let cursor = std::fs::read_to_string(dir.path().join(".cursor/rules/forge.mdc")).unwrap();
+        assert!(cursor.ends_with(&claude));
+        assert!(dir.path().join(".windsurf/rules/forge.md").exists());
+        assert!(dir.path().join(".github/instructions/forge.instructions.md").exists());
+
+        // Agent permissions profile
+        let settings = std::fs::read_to_string(dir.path().join(".claude/settings.json")).unwrap();
+        let settings: serde_json::Value = serde_json::from_str(&settings).unwrap();
+        assert!(settings["permissions"]["deny"].as_array().unwrap().contains(&serde_json::json!("Bash(rm -rf:*)")));
 
         // features.json
         assert!(dir.path().join("features.json").exists());
 
+        // DESIGN.md skeleton
+        let design = std::fs::read_to_string(dir.path().join("DESIGN.md")).unwrap();
+        assert!(design.starts_with("# my-test-app\n\nMy Test App\n\n## Goals\n"), "{design}");
+
         // context dirs
         assert!(dir.path().join("context/decisions").is_dir());
         assert!(dir.path().join("context/gotchas").is_dir());
@@ -201,12 +303,14 @@ mod tests {
         // feedback and scripts
         assert!(dir.path().join("feedback").is_dir());
         assert!(dir.path().join("scripts/verify").is_dir());
+        assert!(dir.path().join(verify_template::TEMPLATE).exists());
+        assert!(dir.path().join(verify_template::GLOBAL_BUILD).exists());
     }
 
     #[test]
     fn init_installs_skills() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
 
         // Skills installed to both .claude/skills/ and .agents/skills/
         for base in &[".claude/skills", ".agents/skills"] {
@@ -234,11 +338,58 @@ mod tests {
     #[test]
     fn init_fails_if_already_initialized() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
-        let result = init_project(dir.path(), "test again");
+        init_project(dir.path(), "test", false).unwrap();
+        let result = init_project(dir.path(), "test again", false);
         assert!(matches!(result, Err(InitError::AlreadyInitialized)));
     }
 
+    #[test]
+    fn force_reinitializes_keeping_features_and_context() {
+        let dir = tempfile::tempdir().unwrap();
+        init_project(dir.path(), "test", false).unwrap();
+        std::fs::write(dir.path().join("forge.toml"), "[project\nname = ").unwrap();
+        let features = "{\"features\": []}\n";
+        std::fs::write(dir.path().join("features.json"), features).unwrap();
+        std::fs::write(dir.path().join("context/decisions/orm.md"), "# ORM\n").unwrap();
+        std::fs::remove_dir_all(dir.path().join("feedback")).unwrap();
+        std::fs::remove_dir_all(dir.path().join(".claude")).unwrap();
+
+        init_project(dir.path(), "test again", true).unwrap();
+        assert_eq!(ForgeConfig::load(dir.path()).unwrap().project.name, "test-again");
+        assert_eq!(std::fs::read_to_string(dir.path().join("forge.toml.bak")).unwrap(), "[project\nname = ");
+        assert_eq!(std::fs::read_to_string(dir.path().join("features.json")).unwrap(), features);
+        assert!(dir.path().join("context/decisions/orm.md").exists());
+        assert!(dir.path().join("feedback/exec-memory").is_dir());
+        assert!(dir.path().join(".claude/skills/forge-protocol/SKILL.md").exists());
+        assert!(missing_dirs(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn init_from_existing_fills_stack_and_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("order_service");
+        for file in ["src/main.rs", "src/billing/mod.rs", "src/billing/invoice.rs"] {
+            std::fs::create_dir_all(project.join(file).parent().unwrap()).unwrap();
+            std::fs::write(project.join(file), "").unwrap();
+        }
+        std::fs::write(project.join("Cargo.toml"), "[package]\nname = \"orders\"\n").unwrap();
+
+        let analysis = init_from_existing(&project, None, false).unwrap();
+        assert_eq!(analysis.test_command.as_deref(), Some("cargo test"));
+        let config = ForgeConfig::load(&project).unwrap();
+        assert_eq!((config.project.name.as_str(), config.project.stack.as_str()), ("order-service", "Rust, cargo"));
+        assert_eq!(config.scopes["billing"].owns, ["src/billing/"]);
+        assert_eq!(config.scopes["src"].owns, ["src/*.rs"]);
+        let design = std::fs::read_to_string(project.join("DESIGN.md")).unwrap();
+        assert!(design.starts_with("# order-service\n"), "{design}");
+
+        // An existing DESIGN.md is left alone
+        std::fs::remove_file(project.join("forge.toml")).unwrap();
+        std::fs::write(project.join("DESIGN.md"), "# Ours\n").unwrap();
+        init_from_existing(&project, Some("Orders"), false).unwrap();
+        assert_eq!(std::fs::read_to_string(project.join("DESIGN.md")).unwrap(), "# Ours\n");
+    }
+
     #[test]
     fn slugify_name_works() {
         assert_eq!(slugify_name("My Test App"), "my-test-app");
@@ -253,7 +404,7 @@ mod tests {
     #[test]
     fn features_json_is_empty() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
         let features = crate::features::FeatureList::load(dir.path()).unwrap();
         assert!(features.features.is_empty());
     }
@@ -261,7 +412,7 @@ mod tests {
     #[test]
     fn install_on_existing_project() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
 
         // Delete skills from both paths
         let _ = std::fs::remove_dir_all(dir.path().join(".claude/skills"));
@@ -270,7 +421,7 @@ mod tests {
         assert!(!dir.path().join(".agents/skills/forge-planning/SKILL.md").exists());
 
         // Install restores them in both locations
-        install_project(dir.path()).unwrap();
+        install_project(dir.path(), false).unwrap();
         for base in &[".claude/skills", ".agents/skills"] {
             assert!(dir.path().join(base).join("forge-planning/SKILL.md").exists());
             assert!(dir.path().join(base).join("forge-protocol/SKILL.md").exists());
@@ -282,7 +433,7 @@ mod tests {
     #[test]
     fn install_regenerates_claude_md() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
 
         // Modify the config name
         let mut config = ForgeConfig::load(dir.path()).unwrap();
@@ -290,7 +441,7 @@ mod tests {
         config.save(dir.path()).unwrap();
 
         // Install regenerates CLAUDE.md with updated name
-        install_project(dir.path()).unwrap();
+        install_project(dir.path(), false).unwrap();
         let claude = std::fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap();
         assert!(claude.contains("# renamed-project"));
         let agents = std::fs::read_to_string(dir.path().join("AGENTS.md")).unwrap();
@@ -303,7 +454,7 @@ mod tests {
         use std::os::unix::fs::PermissionsExt;
 
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
 
         // Create a script without +x
         let script = dir.path().join("scripts/verify/check.sh");
@@ -312,7 +463,7 @@ mod tests {
         std::fs::set_permissions(&script, perms).unwrap();
 
         // Install should fix permissions
-        install_project(dir.path()).unwrap();
+        install_project(dir.path(), false).unwrap();
         let mode = std::fs::metadata(&script).unwrap().permissions().mode();
         assert!(mode & 0o111 != 0, "script should be executable after install");
     }
@@ -320,7 +471,7 @@ mod tests {
     #[test]
     fn init_creates_references_dir_and_gitignore() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
 
         assert!(dir.path().join("references").is_dir());
         let gitignore = std::fs::read_to_string(dir.path().join(".gitignore")).unwrap();
@@ -330,24 +481,43 @@ mod tests {
     #[test]
     fn install_creates_references_and_index() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
 
         // Add a context entry, then install to regenerate index
         let ctx = ContextManager::new(dir.path());
         ctx.write_entry("decisions", "use-vec", "# Use Vec<u8>\nSimpler.").unwrap();
-        install_project(dir.path()).unwrap();
+        install_project(dir.path(), false).unwrap();
 
         // INDEX.md should exist with the entry
         let index = std::fs::read_to_string(dir.path().join("context/INDEX.md")).unwrap();
         assert!(index.contains("use-vec"));
     }
 
+    #[test]
+    fn runtime_ignore_keeps_configured_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n.forge/\n*.log\n").unwrap();
+        write_runtime_ignore(dir.path(), &["events".into()]).unwrap();
+        write_runtime_ignore(dir.path(), &["events".into()]).unwrap();
+        let gitignore = std::fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert_eq!(gitignore, format!("target/\n*.log\n{RUNTIME_IGNORE_HEADER}\n.forge/*\n!.forge/events\n"));
+
+        let git = |args: &[&str]| {
+            std::process::Command::new("git").args(args).current_dir(dir.path()).output().unwrap()
+        };
+        git(&["init", "-q"]);
+        let ignored = |path: &str| git(&["check-ignore", "-q", path]).status.success();
+        assert!(ignored(".forge/logs/agent-1.log"));
+        assert!(ignored(".forge/worktrees/agent-1/src/main.rs"));
+        assert!(!ignored(".forge/events/forge.jsonl"));
+    }
+
     #[test]
     fn gitignore_not_duplicated() {
         let dir = tempfile::tempdir().unwrap();
-        init_project(dir.path(), "test").unwrap();
+        init_project(dir.path(), "test", false).unwrap();
         // Install again — should not duplicate
-        install_project(dir.path()).unwrap();
+        install_project(dir.path(), false).unwrap();
 
         let gitignore = std::fs::read_to_string(dir.path().join(".gitignore")).unwrap();
         let count = gitignore.matches("references/").count();
@@ -357,7 +527,7 @@ mod tests {
     #[test]
     fn install_fails_without_forge_toml() {
         let dir = tempfile::tempdir().unwrap();
-        let result = install_project(dir.path());
+        let result = install_project(dir.path(), false);
         assert!(result.is_err());
     }
 }
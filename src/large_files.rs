@@ -0,0 +1,145 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::context::ContextManager;
+use crate::git;
+
+/// The gotcha entry oversized files are recorded in, so agents learn to
+/// keep build output out of git.
+const GOTCHA_SLUG: &str = "large-files";
+
+/// A file over `[git] max_file_mb`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeFile {
+    pub path: String,
+    pub bytes: u64,
+    /// `.gitattributes` routes it through Git LFS, but the content was
+    /// stored anyway, so git-lfs isn't installed where it was added.
+    pub lfs: bool,
+}
+
+impl fmt::Display for LargeFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:.1} MB)", self.path, self.bytes as f64 / 1e6)
+    }
+}
+
+/// Files on one line, for messages.
+pub fn summary(files: &[LargeFile]) -> String {
+    files.iter().map(LargeFile::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// `[git] max_file_mb` in bytes; 0 for no limit.
+pub fn limit_bytes(max_file_mb: u64) -> u64 {
+    max_file_mb * 1_000_000
+}
+
+fn uses_lfs(dir: &Path, path: &str) -> bool {
+    git::run(dir, &["check-attr", "filter", "--", path]).is_ok_and(|out| out.trim_end().ends_with(": filter: lfs"))
+}
+
+/// Size and LFS check for each `(path, object)`, keeping those over `limit`.
+/// What's stored is what's measured: a file LFS took is a small pointer.
+fn over_limit(dir: &Path, objects: Vec<(String, String)>, limit: u64) -> Vec<LargeFile> {
+    objects
+        .into_iter()
+        .filter_map(|(path, object)| {
+            let bytes = git::run(dir, &["cat-file", "-s", &object]).ok()?.trim().parse().ok()?;
+            (bytes > limit).then(|| LargeFile { lfs: uses_lfs(dir, &path), path, bytes })
+        })
+        .collect()
+}
+
+/// Staged files added or changed that are over `limit` bytes.
+pub fn staged(dir: &Path, limit: u64) -> Vec<LargeFile> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let Ok(names) = git::run(dir, &["diff", "--cached", "--name-only", "--diff-filter=AM", "-z"]) else {
+        return Vec::new();
+    };
+    let objects = names.split('\0').filter(|p| !p.is_empty()).map(|p| (p.to_string(), format!(":{p}"))).collect();
+    over_limit(dir, objects, limit)
+}
+
+/// Files over `limit` bytes that `to` adds or changes since `from`.
+pub fn in_range(dir: &Path, from: &str, to: &str, limit: u64) -> Vec<LargeFile> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let Ok(raw) = git::run(dir, &["diff", "--raw", "--no-abbrev", "--diff-filter=AM", "-z", from, to]) else {
+        return Vec::new();
+    };
+    // `:100644 100644 <old> <new> M\0path\0`, repeated
+    let mut fields = raw.split('\0');
+    let mut objects = Vec::new();
+    while let (Some(meta), Some(path)) = (fields.next(), fields.next()) {
+        if let Some(object) = meta.split_whitespace().nth(3) {
+            objects.push((path.to_string(), object.to_string()));
+        }
+    }
+    over_limit(dir, objects, limit)
+}
+
+/// Add `files` to the `gotchas/large-files` context entry, once per path.
+pub fn record_gotcha(project_dir: &Path, files: &[LargeFile]) {
+    if files.is_empty() {
+        return;
+    }
+    let ctx = ContextManager::new(project_dir);
+    let mut content = ctx.read_entry("gotchas", GOTCHA_SLUG).unwrap_or_else(|_| {
+        "# Large files are kept out of commits\n\n\
+         Files over `[git] max_file_mb` are left out of the commits forge makes, and branches adding them \
+         aren't merged. Add build output to .gitignore; track large assets with Git LFS (`git lfs track`).\n\n\
+         Files caught so far:\n"
+            .to_string()
+    });
+    for file in files {
+        if content.contains(&format!("- `{}`", file.path)) {
+            continue;
+        }
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        let lfs = if file.lfs { "; .gitattributes puts it in LFS, but git-lfs isn't installed" } else { "" };
+        content.push_str(&format!("- `{}` ({:.1} MB{lfs})\n", file.path, file.bytes as f64 / 1e6));
+    }
+    let _ = ctx.write_entry("gotchas", GOTCHA_SLUG, &content);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let out = Command::new("git").args(args).current_dir(dir).output().unwrap();
+        assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+    }
+
+    #[test]
+    fn finds_big_files_in_a_branch_and_records_them() {
+        let dir = tempfile::tempdir().unwrap();
+        run(dir.path(), &["init", "-q", "-b", "main"]);
+        run(dir.path(), &["config", "user.email", "t@t"]);
+        run(dir.path(), &["config", "user.name", "t"]);
+        std::fs::write(dir.path().join("small.txt"), "x").unwrap();
+        run(dir.path(), &["add", "-A"]);
+        run(dir.path(), &["commit", "-qm", "init"]);
+        run(dir.path(), &["checkout", "-qb", "agent"]);
+        std::fs::write(dir.path().join("app.bin"), vec![0u8; 2_500]).unwrap();
+        std::fs::write(dir.path().join("small.txt"), "y").unwrap();
+        run(dir.path(), &["add", "-A"]);
+        run(dir.path(), &["commit", "-qm", "build"]);
+
+        let found = in_range(dir.path(), "main", "agent", 1_000);
+        assert_eq!(summary(&found), "app.bin (0.0 MB)");
+        assert!(in_range(dir.path(), "main", "agent", 0).is_empty());
+
+        record_gotcha(dir.path(), &found);
+        record_gotcha(dir.path(), &found);
+        let entry = ContextManager::new(dir.path()).read_entry("gotchas", GOTCHA_SLUG).unwrap();
+        assert_eq!(entry.matches("- `app.bin`").count(), 1, "{entry}");
+    }
+}
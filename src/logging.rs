@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry, fmt};
+
+/// Daily files kept under `.forge/logs/`; older ones are deleted.
+const KEEP_DAYS: usize = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn directive(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// One readable line per record
+    Text,
+    /// One JSON object per record, fields included
+    Json,
+}
+
+/// Where the run log rotates: `.forge/logs/forge.<date>.log`.
+pub fn log_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/logs")
+}
+
+/// Send forge's log records to stderr and, in a forge project, to a file
+/// under `.forge/logs/` rotated daily. The console is left alone when
+/// `console` is false, as the TUI owns the terminal then. `FORGE_LOG`, in
+/// `EnvFilter` syntax, overrides `level`.
+pub fn init(project_dir: &Path, level: LogLevel, format: LogFormat, console: bool) {
+    let filter = || EnvFilter::try_from_env("FORGE_LOG").unwrap_or_else(|_| EnvFilter::new(level.directive()));
+    let json = format == LogFormat::Json;
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+    if console {
+        let layer = fmt::layer().with_writer(std::io::stderr).with_target(false);
+        layers.push(if json {
+            layer.json().with_filter(filter()).boxed()
+        } else {
+            layer.without_time().with_filter(filter()).boxed()
+        });
+    }
+    layers.extend(file_layer(project_dir, format, filter()));
+    let _ = tracing_subscriber::registry().with(layers).try_init();
+}
+
+/// The `.forge/logs/` half of `init`; None outside a forge project.
+fn file_layer(
+    project_dir: &Path,
+    format: LogFormat,
+    filter: EnvFilter,
+) -> Option<Box<dyn Layer<Registry> + Send + Sync>> {
+    let layer = fmt::layer().with_writer(Mutex::new(file_appender(project_dir)?)).with_ansi(false);
+    Some(match format {
+        LogFormat::Json => layer.json().with_filter(filter).boxed(),
+        LogFormat::Text => layer.with_filter(filter).boxed(),
+    })
+}
+
+/// None outside a forge project, so commands run elsewhere don't create `.forge/`.
+fn file_appender(project_dir: &Path) -> Option<RollingFileAppender> {
+    if !project_dir.join(".forge").is_dir() {
+        return None;
+    }
+    std::fs::create_dir_all(log_dir(project_dir)).ok()?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("forge")
+        .filename_suffix("log")
+        .max_log_files(KEEP_DAYS)
+        .build(log_dir(project_dir))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_to_a_file_only_in_a_forge_project() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(file_appender(dir.path()).is_none());
+        assert!(!dir.path().join(".forge").exists(), "nothing created outside a project");
+
+        std::fs::create_dir_all(dir.path().join(".forge")).unwrap();
+        let layer = file_layer(dir.path(), LogFormat::Json, EnvFilter::new("info")).unwrap();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            tracing::info!(feature_id = "f001", "merged");
+            tracing::debug!("filtered out");
+        });
+        let logs: Vec<_> = std::fs::read_dir(log_dir(dir.path())).unwrap().flatten().map(|e| e.path()).collect();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].file_name().unwrap().to_string_lossy().starts_with("forge."));
+        let text = std::fs::read_to_string(&logs[0]).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 1, "{text}");
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["fields"]["message"], "merged");
+        assert_eq!(lines[0]["fields"]["feature_id"], "f001");
+    }
+}
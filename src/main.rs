@@ -1,19 +1,82 @@
+mod agent_log;
+mod analyze;
+mod api;
+mod audit;
+mod bench;
+mod bisect;
+mod chaos;
+mod checkpoint;
+mod claude_settings;
+mod clean;
+mod commit_message;
 mod config;
+mod config_validate;
+mod config_wizard;
 mod context;
+mod context_audit;
+mod context_budget;
+mod context_fetch;
 mod context_flow;
+mod context_index;
+mod context_lint;
+mod context_meta;
+mod context_package;
+mod context_reconcile;
+mod context_vault;
+mod cost;
+mod design;
+mod doctor;
+mod events;
 mod export;
+mod export_diff;
+mod export_parquet;
 mod features;
+mod features_backup;
+mod feedback;
 mod git;
 mod init;
+mod large_files;
+mod logging;
+mod mcp;
+mod merge_conflicts;
+mod notify;
+mod notify_desktop;
+mod notify_email;
+mod packet;
+mod plan;
+mod plan_import;
+mod plugins;
+mod pr;
+mod questions;
+mod rate_limit;
+mod redact;
+mod remote;
+mod replay;
+mod report;
+mod reviews;
+mod runaway;
 mod runner;
+mod runs;
+mod secret_scan;
+mod secrets;
 mod skills;
+mod stats;
+mod tags;
 mod template;
+mod transcript_patterns;
+mod transcripts;
 mod tui;
+mod tui_alerts;
+mod tui_keys;
 mod tui_orchestrator;
+mod tui_replay;
+mod upload;
 mod verify;
+mod verify_template;
+mod workspace;
 
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "forge", about = "Orchestrate autonomous coding agents")]
@@ -22,18 +85,67 @@ struct Cli {
     #[arg(short, long, default_value = ".")]
     project: PathBuf,
 
+    /// Least severe log records shown and written to .forge/logs/ (FORGE_LOG overrides)
+    #[arg(long, global = true, value_enum, default_value_t = logging::LogLevel::Info)]
+    log_level: logging::LogLevel,
+
+    /// Log record format, on the console and in .forge/logs/
+    #[arg(long, global = true, value_enum, default_value_t = logging::LogFormat::Text)]
+    log_format: logging::LogFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 /// All commands are pure orchestration — no LLM calls.
-/// Planning is a skill (/forge-planning), not a CLI command.
+/// Planning is a skill (/forge-planning); `forge plan` runs it headless.
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize a forge project: dirs, forge.toml, skills, CLAUDE.md
     Init {
         /// Project description
-        description: String,
+        #[arg(required_unless_present = "from_existing")]
+        description: Option<String>,
+        /// Analyze the codebase already here for the stack, scopes and a DESIGN.md draft
+        #[arg(long)]
+        from_existing: bool,
+        /// Re-initialize a project already here, keeping features.json, context/ and DESIGN.md
+        #[arg(long)]
+        force: bool,
+    },
+    /// Run the planning role headless against DESIGN.md and show the
+    /// features it proposes, saved to features.json once approved
+    Plan {
+        /// Save without asking (still refused if the list has problems)
+        #[arg(long)]
+        yes: bool,
+        /// Override the planning role's backend
+        #[arg(long)]
+        backend: Option<String>,
+        /// Override the planning role's model
+        #[arg(long)]
+        model: Option<String>,
+        /// Apply a [profiles.<name>] section from forge.toml (before --backend/--model)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Replan with the adjusting role: apply an instruction to features.json
+    /// and show the changes, saved once accepted
+    Adjust {
+        /// What to change, e.g. "cut scope on reporting, add OAuth milestone"
+        instruction: String,
+        /// Save without asking (still refused if the list has problems)
+        #[arg(long)]
+        yes: bool,
+        /// Override the adjusting role's backend
+        #[arg(long)]
+        backend: Option<String>,
+        /// Override the adjusting role's model
+        #[arg(long)]
+        model: Option<String>,
+        /// Apply a [profiles.<name>] section from forge.toml (before --backend/--model)
+        #[arg(long)]
+        profile: Option<String>,
     },
     /// Start the autonomous development loop
     Run {
@@ -52,15 +164,123 @@ enum Commands {
         /// Override model for all roles (e.g. sonnet, o3)
         #[arg(long)]
         model: Option<String>,
+        /// Give each TUI pane its own git worktree, merged back on exit (with --watch)
+        #[arg(long)]
+        worktrees: bool,
+        /// Apply a [profiles.<name>] section from forge.toml (before --backend/--model)
+        #[arg(long)]
+        profile: Option<String>,
+        /// A/B the executor: even-numbered agents use this model, the rest the
+        /// protocol role's (overrides [forge.roles.protocol_b])
+        #[arg(long)]
+        ab_model: Option<String>,
+        /// Test the run's failure handling: randomly kill agents, delay verify
+        /// and, with several agents, make merges conflict. Not for real work
+        #[arg(long, conflicts_with_all = ["watch", "workspace"])]
+        chaos: bool,
+        /// Seed for --chaos, to repeat a run's faults (default: from the clock)
+        #[arg(long, requires = "chaos")]
+        chaos_seed: Option<u64>,
+        /// Chance of each fault at each opportunity, 0 to 1
+        #[arg(long, requires = "chaos", default_value_t = 0.3)]
+        chaos_rate: f64,
+        /// Run every project in forge.workspace.toml, sharing its max_agents
+        /// (--max-sessions then counts workspace rounds)
+        #[arg(long, conflicts_with_all = ["watch", "agents"])]
+        workspace: bool,
+        /// Run on a clone of this repository, kept under ~/.cache/forge/repos,
+        /// and push the results (instead of --project)
+        #[arg(long, conflicts_with = "workspace")]
+        repo: Option<String>,
+        /// Branch to check out with --repo (default: the remote's)
+        #[arg(long, requires = "repo")]
+        branch: Option<String>,
+    },
+    /// Run the same features under several configurations, each in its own
+    /// worktree, and compare cost, sessions to done and verify pass rates
+    Bench {
+        /// Features to run (comma-separated, e.g. f010,f011)
+        #[arg(long, value_delimiter = ',', required = true)]
+        features: Vec<String>,
+        /// [profiles.<name>] sections to compare (comma-separated)
+        #[arg(long, value_delimiter = ',', required_unless_present = "roles_matrix")]
+        profile: Vec<String>,
+        /// Compare forge.toml as it is and every [profiles.*] section
+        #[arg(long, conflicts_with = "profile")]
+        roles_matrix: bool,
+        /// Revision the worktrees start from; before the features' own commits
+        /// if they're already done
+        #[arg(long, default_value = "HEAD")]
+        base: String,
+        /// Max sessions per configuration (default: 3 per feature)
+        #[arg(long)]
+        max_sessions: Option<usize>,
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Run all verify scripts
     Verify,
+    /// Find the commit that broke a verify script (git bisect run over
+    /// forge's commits) and the feature, agent and session it came from
+    Bisect {
+        /// Script that exits 0 on good commits, e.g. scripts/verify/f003.sh
+        script: PathBuf,
+        /// Last known good revision (default: the commit before forge's first)
+        #[arg(long)]
+        good: Option<String>,
+    },
     /// Show project status: features, context, progress
-    Status,
+    Status {
+        /// Summarize every project in forge.workspace.toml
+        #[arg(long)]
+        workspace: bool,
+    },
+    /// Add features to features.json
+    Feature {
+        #[command(subcommand)]
+        action: FeatureAction,
+    },
+    /// Add features to features.json from a plan kept elsewhere
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
     /// Install/update project dependencies (skills, CLAUDE.md, permissions)
-    Install,
+    Install {
+        /// Overwrite skill files edited since they were installed
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check the project and its environment for missing or broken pieces
+    Doctor {
+        /// Recreate missing directories
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Prune .forge/: old logs and exports, orphaned worktrees, a stale
+    /// cocoindex database, and claims no session is working on
+    Clean {
+        /// Days untouched before a log, export or cocoindex database goes
+        #[arg(long, default_value_t = 14)]
+        older_than: u64,
+        /// List what would go without removing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Also release claims whose agent is gone; refused while a `forge run` hasn't finished
+        #[arg(long)]
+        release_claims: bool,
+    },
     /// Stop all running agents gracefully
     Stop,
+    /// Serve forge's tools to agents over MCP (stdio), as .mcp.json registers it
+    Mcp,
+    /// Serve forge over HTTP: features, claims, status, stop, an event stream
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        addr: String,
+    },
     /// Show agent logs
     Logs {
         /// Agent ID (default: agent-1)
@@ -69,251 +289,2151 @@ enum Commands {
         /// Number of lines to show from the end
         #[arg(short, long, default_value_t = 50)]
         tail: usize,
+        /// Keep printing lines as the agent writes them
+        #[arg(short, long)]
+        follow: bool,
+        /// Every agent's log, interleaved by time and prefixed with the agent
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show who changed features.json, and what, from .forge/audit.jsonl
+    Audit {
+        /// Only changes to this feature
+        #[arg(long)]
+        feature: Option<String>,
+        /// Number of records to show from the end
+        #[arg(short, long, default_value_t = 20)]
+        tail: usize,
+    },
+    /// Summarize spend from the project's agent transcripts, at list prices
+    Cost {
+        /// Break down by these (repeatable; default: all)
+        #[arg(long, value_enum)]
+        by: Vec<cost::CostGroup>,
+        /// Only sessions started on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+        /// Only sessions started on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+        /// Print CSV instead
+        #[arg(long)]
+        csv: bool,
+    },
+    /// How the models forge.toml assigns have done on this project
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+    /// Read the session reviews archived under feedback/reviews/
+    Reviews {
+        #[command(subcommand)]
+        action: ReviewsAction,
+    },
+    /// Snapshot forge state with the commit it goes with, and roll back to one
+    Checkpoint {
+        #[command(subcommand)]
+        action: CheckpointAction,
+    },
+    /// Put back a copy of features.json from .forge/backups/features/, taken
+    /// before every save and agent session; lists them without a timestamp
+    RestoreFeatures {
+        /// The backup's timestamp, or enough of its start to pick one
+        timestamp: Option<String>,
+    },
+    /// Past `forge run`s from .forge/runs/: what each got done, and how two
+    /// compare
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+    },
+    /// Questions agents left in feedback/questions/; their features wait
+    /// until they're answered
+    Questions {
+        #[command(subcommand)]
+        action: QuestionsAction,
+    },
+    /// Mine agent transcripts for what to change in the protocol and skills
+    Transcripts {
+        #[command(subcommand)]
+        action: TranscriptsAction,
+    },
+    /// Add, list, show and remove context entries; INDEX.md is kept in sync
+    Context {
+        #[command(subcommand)]
+        action: ContextAction,
+    },
+    /// Check forge.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
     /// Export project data for analysis
     Export {
+        #[command(subcommand)]
+        action: Option<ExportAction>,
         /// Output directory (default: .forge/export/)
         #[arg(short, long)]
         output: Option<PathBuf>,
-        /// Skip Claude Code JSONL transcripts
+        /// Skip agent session transcripts (Claude, Codex)
         #[arg(long)]
         no_transcripts: bool,
         /// Git commits to include (default: 100)
         #[arg(long, default_value_t = 100)]
         git_commits: usize,
+        /// Also pack the export into one archive (.tar.gz, .tgz or .zip)
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Also write report.html, a browsable summary of the run
+        #[arg(long)]
+        html: bool,
+        /// Only artifacts from this time on (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only artifacts up to this time (a bare date includes the whole day)
+        #[arg(long)]
+        until: Option<String>,
+        /// Only artifacts for these features (comma-separated, e.g. f001,f002)
+        #[arg(long, value_delimiter = ',')]
+        features: Vec<String>,
+        /// Debugging packet for one feature: its entry, verify output, context
+        /// package and scope commits, plus everything else filtered to it
+        #[arg(long, conflicts_with = "features")]
+        feature: Option<String>,
+        /// Upload the archive to the [export.upload] bucket
+        #[arg(long)]
+        upload: bool,
+        /// Output format; parquet adds features/sessions/verify tables
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+    /// Step through a run from a `forge export` bundle: features over time,
+    /// sessions and verify outcomes
+    Replay {
+        /// The export: an --archive (.tar.gz, .tgz or .zip) or its directory
+        #[arg(long)]
+        from: PathBuf,
+        /// Print the timeline instead of opening the stepper
+        #[arg(long)]
+        print: bool,
+    },
+    /// `forge-<name>` plugins on PATH, and the events forge.toml hooks them to
+    Plugins,
+    /// Anything else runs `forge-<name>` from PATH with the rest of the arguments
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+enum VaultFormat {
+    /// Notes with [[wikilinks]] and an INDEX.md
+    Obsidian,
+    /// book.toml and src/SUMMARY.md, ready for `mdbook serve`
+    Mdbook,
+}
+
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+enum ExportFormat {
+    /// JSON and Markdown files
+    Json,
+    /// The JSON export plus features.parquet, sessions.parquet, verify.parquet
+    Parquet,
+}
+
+#[derive(Subcommand)]
+enum ContextAction {
+    /// Write an entry from a file, or from stdin with `-`
+    Add {
+        /// decisions, gotchas, patterns, poc, references, or one from `[context] categories`
+        category: String,
+        slug: String,
+        /// File to read the entry from (`-` for stdin)
+        source: PathBuf,
+        /// Replace an existing entry
+        #[arg(long)]
+        force: bool,
+        /// Tag for the frontmatter (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Related feature id (repeatable)
+        #[arg(long = "feature")]
+        features: Vec<String>,
+        /// forge.toml scope the entry is about (repeatable); packages for that scope list it first
+        #[arg(long = "scope")]
+        scopes: Vec<String>,
+        /// category/slug this entry replaces (repeatable)
+        #[arg(long)]
+        supersedes: Vec<String>,
+        /// Drop the entry from INDEX.md and packages after this date (YYYY-MM-DD)
+        #[arg(long)]
+        expires: Option<String>,
+        /// Drop the entry once this feature is done
+        #[arg(long)]
+        valid_until_feature: Option<String>,
+    },
+    /// Download a page into references/ as markdown, with its URL as the source
+    Fetch {
+        url: String,
+        /// Entry name (defaults to one made from the page title)
+        #[arg(long)]
+        slug: Option<String>,
+        /// Comma-separated tags for the frontmatter
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Replace an existing entry
+        #[arg(long)]
+        force: bool,
+    },
+    /// List entries with their one-line summaries
+    List {
+        /// Only this category
+        category: Option<String>,
+    },
+    /// Print an entry (`gotchas sqlx-nullable` or `gotchas/sqlx-nullable`)
+    Show {
+        category: String,
+        slug: Option<String>,
+    },
+    /// Delete an entry
+    Rm {
+        category: String,
+        slug: Option<String>,
+    },
+    /// Move an entry to another category, e.g. a recurring gotcha into patterns,
+    /// updating context_hints and other entries' links
+    Promote {
+        /// The entry to move (`gotchas/retry-backoff`)
+        entry: String,
+        /// Category to move it to
+        to: String,
+        /// New slug (defaults to the current one)
+        #[arg(long)]
+        slug: Option<String>,
+    },
+    /// Earlier versions of an entry, saved whenever it's overwritten or removed
+    Log {
+        category: String,
+        slug: Option<String>,
+        /// Print revision N (1 is the most recent)
+        #[arg(long, value_name = "N", conflicts_with_all = ["diff", "restore"])]
+        show: Option<usize>,
+        /// Diff revision N against the version that replaced it
+        #[arg(long, value_name = "N", conflicts_with = "restore")]
+        diff: Option<usize>,
+        /// Make revision N the current entry again
+        #[arg(long, value_name = "N")]
+        restore: Option<usize>,
+    },
+    /// Write the entries out as an Obsidian vault or an mdBook, with links between them
+    Export {
+        #[arg(long, value_enum)]
+        format: VaultFormat,
+        /// Output directory (default: .forge/export/context-<format>/)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Regenerate context/INDEX.md (and the embeddings index, if configured)
+    Reindex,
+    /// Rebuild context/packages/ for pending features
+    Packages,
+    /// Show `related`/`supersedes` links between entries
+    Graph {
+        /// Print Graphviz dot (`forge context graph --dot | dot -Tsvg`)
+        #[arg(long)]
+        dot: bool,
+    },
+    /// Flag stale entries: missing files/symbols, unused, contradictory decisions
+    Audit {
+        /// Flag entries no session has mentioned in this many sessions since
+        #[arg(long, default_value_t = 10)]
+        sessions: usize,
+        /// Print findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check entries against [context.lint]: length, required frontmatter,
+    /// a leading `# Title`, no absolute local paths, no secrets
+    Lint {
+        /// Print findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Ranked full-text search over entries and their tags
+    Search {
+        query: String,
+        /// Only this category
+        #[arg(long)]
+        category: Option<String>,
+        /// Most results to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Find near-duplicate entries and propose merging them
+    Dedupe {
+        /// Minimum word-shingle overlap (0.0-1.0) to call two entries duplicates
+        #[arg(long, default_value_t = 0.6)]
+        threshold: f64,
+        /// Merge each pair and point context_hints at the kept entry
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Report unknown keys, backends and models that won't run, scopes whose
+    /// `owns` match nothing, and roles left on the defaults
+    Validate {
+        /// Treat unknown keys as errors, like serde's deny_unknown_fields
+        #[arg(long)]
+        strict: bool,
+        /// Print issues as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Answer a few questions (backends, models, budget, scopes) and write
+    /// a validated forge.toml
+    Wizard,
+}
+
+#[derive(Subcommand)]
+enum FeatureAction {
+    /// Append a feature with the next free id, and write its verify script
+    /// from scripts/verify/_template.sh
+    Add {
+        /// What to build; the first line is its title
+        description: String,
+        /// Scope it belongs to
+        #[arg(long)]
+        scope: String,
+        #[arg(long = "type", value_enum, default_value_t = FeatureKind::Implement)]
+        kind: FeatureKind,
+        /// Features that must be done first, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        depends_on: Vec<String>,
+        #[arg(long, default_value_t = 1)]
+        priority: u32,
+        /// Verify command (default: ./scripts/verify/<id>.sh)
+        #[arg(long)]
+        verify: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Checklist items become features, nested items their dependencies, and
+    /// each heading a milestone with a review feature
+    Markdown {
+        /// Plan document, e.g. PLAN.md or TODO.md
+        path: PathBuf,
+        /// Scope for the features (default: the only scope in forge.toml)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Print the features instead of adding them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+enum FeatureKind {
+    Implement,
+    Review,
+    Poc,
+}
+
+#[derive(Subcommand)]
+enum StatsAction {
+    /// Per role, backend and model: first-try verify passes, sessions per
+    /// feature, cost per session and how often features ended blocked
+    Models {
+        /// Print the stats as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReviewsAction {
+    /// Recurring issues across the last reviews, as markdown for a readout
+    Summarize {
+        /// Reviews to cover, newest first
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum CheckpointAction {
+    /// Copy features.json, context/ and feedback/ and pin HEAD
+    Create {
+        /// Name to restore it by (default: the time)
+        name: Option<String>,
+        /// Why this is a good point, e.g. "all of M1 verified"
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Roll code and forge state back to a checkpoint; stop any run first
+    Restore {
+        name: String,
+    },
+    /// Checkpoints taken, oldest first
+    List,
+}
+
+#[derive(Subcommand)]
+enum RunsAction {
+    /// Runs recorded, oldest first
+    List,
+    /// Two runs' throughput, failures and cost side by side, and the
+    /// settings that changed between them
+    Compare {
+        /// A run id, or enough of its start to pick one
+        a: String,
+        b: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum QuestionsAction {
+    /// Questions waiting on an answer
+    List {
+        /// Answered ones too
+        #[arg(long)]
+        all: bool,
+    },
+    /// Answer a feature's question and put it back to pending; the next
+    /// session on it gets the answer in its prompt
+    Answer {
+        feature_id: String,
+        answer: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TranscriptsAction {
+    /// Tool usage, frequent errors, turns per feature type, files read most
+    Analyze {
+        /// Entries kept per list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportAction {
+    /// Compare two export directories: feature progress, context, cost, verify
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Print the diff as JSON
+        #[arg(long)]
+        json: bool,
     },
 }
 
 fn main() {
     let cli = Cli::parse();
+    // The TUI draws over the terminal; its log goes to the file alone
+    let console = !matches!(cli.command, Commands::Run { watch: true, .. });
+    logging::init(&cli.project, cli.log_level, cli.log_format, console);
 
     match cli.command {
-        Commands::Init { description } => cmd_init(&cli.project, &description),
-        Commands::Install => cmd_install(&cli.project),
+        Commands::Init { description, from_existing, force } => {
+            if from_existing {
+                cmd_init_existing(&cli.project, description.as_deref(), force)
+            } else {
+                cmd_init(&cli.project, description.as_deref().unwrap_or_default(), force)
+            }
+        }
+        Commands::Install { force } => cmd_install(&cli.project, force),
+        Commands::Doctor { fix } => cmd_doctor(&cli.project, fix),
+        Commands::Clean { older_than, dry_run, release_claims } => {
+            cmd_clean(&cli.project, older_than, dry_run, release_claims)
+        }
+        Commands::Plan { yes, backend, model, profile } => {
+            cmd_plan(&cli.project, yes, RunOverrides { profile, backend, model, ab_model: None })
+        }
+        Commands::Adjust { instruction, yes, backend, model, profile } => {
+            cmd_adjust(&cli.project, &instruction, yes, RunOverrides { profile, backend, model, ab_model: None })
+        }
         Commands::Run {
             agents,
             max_sessions,
             watch,
             backend,
             model,
-        } => cmd_run(&cli.project, agents, max_sessions, watch, backend, model),
+            worktrees,
+            profile,
+            ab_model,
+            chaos,
+            chaos_seed,
+            chaos_rate,
+            workspace,
+            repo,
+            branch,
+        } => {
+            let overrides = RunOverrides { profile, backend, model, ab_model };
+            let seed = chaos_seed.unwrap_or_else(chaos::Chaos::clock_seed);
+            let chaos = chaos.then(|| chaos::Chaos::new(seed, chaos_rate));
+            if workspace {
+                cmd_run_workspace(&cli.project, max_sessions, &overrides)
+            } else if let Some(url) = repo {
+                let project_dir = checkout_remote(&url, branch.as_deref());
+                cmd_run(&project_dir, agents, max_sessions, watch, worktrees, overrides, chaos);
+                push_remote(&project_dir, &url);
+            } else {
+                cmd_run(&cli.project, agents, max_sessions, watch, worktrees, overrides, chaos)
+            }
+        }
+        Commands::Bench { features, profile, roles_matrix, base, max_sessions, json } => {
+            cmd_bench(&cli.project, &features, profile, roles_matrix, &base, max_sessions, json)
+        }
         Commands::Verify => cmd_verify(&cli.project),
-        Commands::Status => cmd_status(&cli.project),
+        Commands::Bisect { script, good } => cmd_bisect(&cli.project, &script, good.as_deref()),
+        Commands::Status { workspace: false } => cmd_status(&cli.project),
+        Commands::Status { workspace: true } => cmd_status_workspace(&cli.project),
         Commands::Stop => cmd_stop(&cli.project),
-        Commands::Logs { agent, tail } => cmd_logs(&cli.project, &agent, tail),
+        Commands::Serve { addr } => cmd_serve(&cli.project, &addr),
+        Commands::Mcp => cmd_mcp(&cli.project),
+        Commands::Logs { agent, tail, follow, all } => cmd_logs(&cli.project, &agent, tail, follow, all),
+        Commands::Audit { feature, tail } => cmd_audit(&cli.project, feature.as_deref(), tail),
+        Commands::Cost { by, since, until, csv } => cmd_cost(&cli.project, &by, since, until, csv),
+        Commands::Stats { action: StatsAction::Models { json } } => cmd_stats_models(&cli.project, json),
+        Commands::Reviews { action: ReviewsAction::Summarize { last } } => cmd_reviews_summarize(&cli.project, last),
+        Commands::Checkpoint { action } => cmd_checkpoint(&cli.project, action),
+        Commands::RestoreFeatures { timestamp } => cmd_restore_features(&cli.project, timestamp.as_deref()),
+        Commands::Runs { action } => cmd_runs(&cli.project, action),
+        Commands::Questions { action } => cmd_questions(&cli.project, action),
+        Commands::Transcripts { action: TranscriptsAction::Analyze { top, json } } => {
+            cmd_transcripts_analyze(&cli.project, top, json)
+        }
+        Commands::Context { action } => cmd_context(&cli.project, action),
+        Commands::Feature { action } => cmd_feature(&cli.project, action),
+        Commands::Import { source: ImportSource::Markdown { path, scope, dry_run } } => {
+            cmd_import_markdown(&cli.project, &path, scope, dry_run)
+        }
+        Commands::Config {
+            action: ConfigAction::Validate { strict, json },
+        } => cmd_config_validate(&cli.project, strict, json),
+        Commands::Config {
+            action: ConfigAction::Wizard,
+        } => cmd_config_wizard(&cli.project),
+        Commands::Replay { from, print } => cmd_replay(&from, print),
         Commands::Export {
+            action: Some(ExportAction::Diff { old, new, json }),
+            ..
+        } => cmd_export_diff(&old, &new, json),
+        Commands::Export {
+            action: None,
             output,
             no_transcripts,
             git_commits,
-        } => cmd_export(&cli.project, output, no_transcripts, git_commits),
+            archive,
+            html,
+            since,
+            until,
+            features,
+            feature,
+            upload,
+            format,
+        } => {
+            let features = feature.iter().cloned().chain(features).collect();
+            let filter = match export::ExportFilter::new(since.as_deref(), until.as_deref(), features) {
+                Ok(f) => f,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let options = export::ExportOptions {
+                include_transcripts: !no_transcripts,
+                git_commits,
+                filter,
+                redactor: redact::Redactor::default(),
+                packet: feature,
+            };
+            cmd_export(&cli.project, output, archive, html, upload, format, options)
+        }
+        Commands::Plugins => cmd_plugins(&cli.project),
+        Commands::External(args) => cmd_external(&cli.project, &args),
     }
 }
 
-fn cmd_init(project_dir: &PathBuf, description: &str) {
-    match init::init_project(project_dir, description) {
+fn cmd_init(project_dir: &Path, description: &str, force: bool) {
+    match init::init_project(project_dir, description, force) {
         Ok(()) => {
             println!("Initialized forge project in {}", project_dir.display());
             println!();
             println!("Created:");
             println!("  forge.toml              project config");
             println!("  features.json           task list (empty — use /forge-planning to fill)");
+            println!("  DESIGN.md               design skeleton: goals, milestones, constraints, unknowns");
             println!("  CLAUDE.md               agent instructions");
             println!("  AGENTS.md               agent instructions (non-Claude)");
-            println!("  context/                decisions, gotchas, patterns, references");
+            println!("  .cursor/, .windsurf/,   the same instructions as editor rules");
+            println!("  .github/instructions/");
+            let categories = context::ContextManager::new(project_dir).categories().join(", ");
+            println!("  context/                {categories}");
             println!("  feedback/               test summaries");
             println!("  scripts/verify/         verify scripts");
             println!("  .claude/skills/         4 skills installed");
             println!("  .agents/skills/         4 skills installed (Codex)");
+            println!("  .claude/settings.json   agent permissions ([forge] permissions = \"standard\")");
+            println!("  .mcp.json               forge's MCP tools for agents (`forge mcp`)");
             println!();
             println!("Next steps:");
-            println!("  1. Write DESIGN.md with your project design");
-            println!("  2. Run /forge-planning in Claude Code to generate features");
+            println!("  1. Fill in the TODOs in DESIGN.md");
+            println!("  2. Run /forge-planning in Claude Code, or `forge plan`, to generate features");
             println!("  3. Run `forge run` to start the development loop");
         }
-        Err(e) => {
-            eprintln!("Error: {e}");
-            std::process::exit(1);
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_init_existing(project_dir: &Path, description: Option<&str>, force: bool) {
+    match init::init_from_existing(project_dir, description, force) {
+        Ok(analysis) => {
+            println!("Initialized forge project in {} from its code", project_dir.display());
+            println!();
+            println!("  Stack:   {}", analysis.stack());
+            println!("  Tests:   {}", analysis.test_command.as_deref().unwrap_or("none found"));
+            let scopes: Vec<&str> = analysis.modules.keys().map(String::as_str).collect();
+            println!("  Scopes:  {}", if scopes.is_empty() { "none found".to_string() } else { scopes.join(", ") });
+            println!();
+            println!("Next steps:");
+            println!("  1. Check the [scopes] in forge.toml and fill in the TODOs in DESIGN.md");
+            println!("  2. Run /forge-planning in Claude Code, or `forge plan`, to generate features");
+            println!("  3. Run `forge run` to start the development loop");
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_install(project_dir: &Path, force: bool) {
+    match init::install_project(project_dir, force) {
+        Ok(report) => {
+            println!("Installed forge project in {}", project_dir.display());
+            println!();
+            println!("Updated:");
+            println!("  .claude/skills/         skills reinstalled from binary");
+            println!("  .agents/skills/         skills reinstalled from binary (Codex)");
+            let list = |skills: &std::collections::BTreeSet<String>| {
+                skills.iter().map(String::as_str).collect::<Vec<_>>().join(", ")
+            };
+            if !report.changed.is_empty() {
+                println!("                          changed: {}", list(&report.changed));
+            }
+            if !report.added.is_empty() {
+                println!("                          new: {}", list(&report.added));
+            }
+            println!("  CLAUDE.md               regenerated from forge.toml");
+            println!("  AGENTS.md               regenerated from forge.toml");
+            println!("  editor rules            Cursor, Windsurf and Copilot, regenerated");
+            println!("  context/                directories ensured");
+            println!("  scripts/verify/         scripts marked executable");
+            println!();
+            if !report.kept.is_empty() {
+                println!("Kept (edited since installed; --force to replace):");
+                for path in &report.kept {
+                    println!("  {}", path.display());
+                }
+                println!();
+            }
+
+            // Check backend CLIs
+            let config = config::ForgeConfig::load(project_dir).unwrap_or_else(|_| {
+                config::ForgeConfig::scaffold("unknown", "")
+            });
+
+            // [backends.<name>] may point a name at a different executable
+            let mut backends = std::collections::BTreeSet::new();
+            for role in [
+                &config.forge.roles.protocol,
+                &config.forge.roles.orchestrating,
+                &config.forge.roles.planning,
+                &config.forge.roles.adjusting,
+            ] {
+                let name = role.backend.as_str();
+                backends.insert(config.backends.get(name).map_or(name, |def| def.command(name)));
+            }
+
+            let mut missing = Vec::new();
+            for backend in &backends {
+                if std::process::Command::new(backend)
+                    .arg("--version")
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .status()
+                    .is_err()
+                {
+                    missing.push(*backend);
+                }
+            }
+
+            if missing.is_empty() {
+                println!("Backends: all OK ({})", backends.into_iter().collect::<Vec<_>>().join(", "));
+            } else {
+                for name in &missing {
+                    eprintln!("Warning: backend '{}' not found in PATH", name);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// How `forge run` changes forge.toml's roles, applied in this order.
+struct RunOverrides {
+    profile: Option<String>,
+    backend: Option<String>,
+    model: Option<String>,
+    /// The executor's variant B model for an A/B run.
+    ab_model: Option<String>,
+}
+
+/// Skills synced, then forge.toml's roles with `overrides` applied.
+fn load_run_config(
+    project_dir: &Path,
+    agents: usize,
+    max_sessions: usize,
+    worktrees: bool,
+    overrides: &RunOverrides,
+) -> (config::ForgeConfig, runner::RunConfig) {
+    let RunOverrides { profile, backend, model, ab_model } = overrides;
+    // Sync skills to both .claude/skills/ and .agents/skills/ so existing
+    // projects work with Codex without requiring re-init.
+    match skills::sync_skills(project_dir, false) {
+        Ok(report) if !report.kept.is_empty() => {
+            let kept = report.kept.len();
+            eprintln!("Note: kept {kept} locally edited skill file(s); `forge install --force` replaces them");
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: failed to sync skills: {e}"),
+    }
+
+    // Load forge config to get role settings
+    let forge_config = config::ForgeConfig::load(project_dir).unwrap_or_else(|_| {
+        config::ForgeConfig::scaffold("unknown", "")
+    });
+    let forge_config = match profile {
+        Some(name) => forge_config.with_profile(name).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }),
+        None => forge_config,
+    };
+
+    // Roles without their own budget, timeout or retries get [forge]'s
+    let mut protocol = forge_config.forge.with_limits(&forge_config.forge.roles.protocol);
+    let mut orchestrating = forge_config.forge.with_limits(&forge_config.forge.roles.orchestrating);
+    let mut protocol_b = forge_config.forge.roles.protocol_b.as_ref().map(|b| forge_config.forge.with_limits(b));
+
+    // Apply CLI overrides
+    if let Some(b) = backend {
+        protocol.backend = b.clone();
+        orchestrating.backend = b.clone();
+    }
+    if let Some(m) = model {
+        protocol.model = m.clone();
+        orchestrating.model = m.clone();
+    }
+    if let Some(m) = ab_model {
+        protocol_b = Some(config::RoleSpec { model: m.clone(), ..protocol.clone() });
+    }
+
+    let secrets = secrets::resolve_all(&forge_config.secrets).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+
+    let run_config = runner::RunConfig {
+        project_dir: project_dir.to_path_buf(),
+        protocol,
+        orchestrating,
+        protocol_b,
+        max_sessions,
+        num_agents: agents,
+        worktrees,
+        backends: forge_config.backends.clone(),
+        secrets,
+        git: forge_config.git.clone(),
+        permissions: forge_config.forge.permissions,
+        runaway: forge_config.forge.runaway.clone(),
+        rate_limits: forge_config.forge.rate_limits.clone(),
+        max_total_cost: forge_config.forge.max_total_cost,
+        chaos: None,
+    };
+
+    (forge_config, run_config)
+}
+
+fn cmd_plan(project_dir: &Path, yes: bool, overrides: RunOverrides) {
+    let (forge_config, run_config) = load_run_config(project_dir, 1, 1, false, &overrides);
+    let role = proposal_role(&forge_config, &forge_config.forge.roles.planning, &overrides);
+    println!("forge plan: backend={}, model={}", role.backend, role.model);
+    let proposal = plan::propose(&run_config, &role, &mut |line| println!("{line}"));
+    review_proposal(project_dir, proposal, yes, "forge plan");
+}
+
+fn cmd_adjust(project_dir: &Path, instruction: &str, yes: bool, overrides: RunOverrides) {
+    if instruction.trim().is_empty() {
+        eprintln!("Error: say what to change, e.g. forge adjust \"cut scope on reporting\"");
+        std::process::exit(1);
+    }
+    let (forge_config, run_config) = load_run_config(project_dir, 1, 1, false, &overrides);
+    let role = proposal_role(&forge_config, &forge_config.forge.roles.adjusting, &overrides);
+    println!("forge adjust: backend={}, model={}", role.backend, role.model);
+    let proposal = plan::adjust(&run_config, &role, instruction, &mut |line| println!("{line}"));
+    review_proposal(project_dir, proposal, yes, "forge adjust");
+}
+
+/// `role` with [forge]'s limits and the command line's overrides.
+fn proposal_role(
+    forge_config: &config::ForgeConfig,
+    role: &config::RoleSpec,
+    overrides: &RunOverrides,
+) -> config::RoleSpec {
+    let mut role = forge_config.forge.with_limits(role);
+    if let Some(b) = &overrides.backend {
+        role.backend = b.clone();
+    }
+    if let Some(m) = &overrides.model {
+        role.model = m.clone();
+    }
+    role
+}
+
+/// Show what a proposal changes and save it once approved (or with `yes`).
+fn review_proposal(project_dir: &Path, proposal: Result<plan::Proposal, plan::PlanError>, yes: bool, command: &str) {
+    let proposal = proposal.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+    let staged = plan::proposal_path(project_dir);
+    let diff = proposal.diff();
+    if diff.is_empty() {
+        let _ = std::fs::remove_file(&staged);
+        println!("The proposal matches features.json; nothing to save.");
+        return;
+    }
+    println!("\nProposed changes to features.json:");
+    for line in &diff {
+        println!("  {line}");
+    }
+    if !proposal.problems.is_empty() {
+        eprintln!("\nNot saved; the proposal has problems:");
+        for problem in &proposal.problems {
+            eprintln!("  {problem}");
+        }
+        eprintln!("Fix {} and copy it over features.json, or run `{command}` again.", staged.display());
+        std::process::exit(1);
+    }
+    let approved = yes || {
+        let mut prompter = config_wizard::Prompter::new(std::io::stdin().lock(), std::io::stdout());
+        prompter.confirm("\nSave to features.json?", false).unwrap_or(false)
+    };
+    if !approved {
+        println!("Not saved; the proposal stays in {}.", staged.display());
+        return;
+    }
+    if let Err(e) = proposal.apply(project_dir) {
+        eprintln!("Error saving features.json: {e}");
+        std::process::exit(1);
+    }
+    println!("Saved {} feature(s) to features.json.", proposal.after.features.len());
+}
+
+fn cmd_run(
+    project_dir: &Path,
+    agents: usize,
+    max_sessions: usize,
+    watch: bool,
+    worktrees: bool,
+    overrides: RunOverrides,
+    chaos: Option<chaos::Chaos>,
+) {
+    let (forge_config, mut run_config) = load_run_config(project_dir, agents, max_sessions, worktrees, &overrides);
+    if let Some(chaos) = chaos {
+        eprintln!(
+            "CHAOS MODE: killing agents, delaying verify and forcing merge conflicts at rate {}; \
+             repeat with --chaos-seed {}",
+            chaos.rate, chaos.seed
+        );
+        run_config.chaos = Some(std::sync::Arc::new(chaos));
+    }
+
+    if watch {
+        // Reject bad [tui.keys] before taking over the terminal
+        let opts = match tui::TuiOptions::from_config(&forge_config.tui) {
+            Ok(o) => o,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        };
+        // TUI mode: spawn agents in interactive PTY panes
+        let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
+        rt.block_on(async {
+            if let Err(e) = tui::run_tui(&run_config, &opts).await {
+                eprintln!("TUI error: {e}");
+                std::process::exit(1);
+            }
+        });
+        return;
+    }
+
+    if worktrees {
+        eprintln!("Warning: --worktrees only applies to --watch (headless multi-agent always uses worktrees)");
+    }
+
+    // Headless mode (original behavior)
+    println!(
+        "forge run: {} agent(s), backend={}, model={}, max_sessions={}{}{}",
+        agents,
+        run_config.protocol.backend,
+        run_config.protocol.model,
+        max_sessions,
+        overrides.profile.as_deref().map(|p| format!(", profile={p}")).unwrap_or_default(),
+        run_config.protocol_b.as_ref().map(|b| format!(", variant b={}/{}", b.backend, b.model)).unwrap_or_default()
+    );
+    if run_config.protocol_b.is_some() && agents < 2 {
+        eprintln!("Warning: the A/B split gives variant b to even-numbered agents; with --agents 1 there are none");
+    }
+    println!();
+
+    let snapshot = runs::snapshot(&forge_config, &run_config, overrides.profile.as_deref());
+    let run = runs::start(project_dir, snapshot)
+        .inspect_err(|e| eprintln!("Warning: failed to record the run in .forge/runs/: {e}"))
+        .ok();
+    let outcome = if agents > 1 {
+        runner::run_multi_agent(&run_config)
+    } else {
+        runner::run_single_agent(&run_config)
+    };
+    runner::emit_run_finished(&run_config.project_dir, &outcome);
+    if let Some(run) = run
+        && let Err(e) = runs::finish(project_dir, run, outcome.name())
+    {
+        eprintln!("Warning: failed to record the run in .forge/runs/: {e}");
+    }
+
+    match outcome {
+        runner::RunOutcome::AllDone { sessions } => {
+            println!();
+            println!("All features done in {sessions} session(s).");
+        }
+        runner::RunOutcome::MaxSessions {
+            sessions,
+            remaining,
+        } => {
+            println!();
+            println!("Stopped after {sessions} session(s). {remaining} feature(s) remaining.");
+        }
+        runner::RunOutcome::Stopped { sessions } => {
+            println!();
+            println!("Stopped by request after {sessions} session(s).");
+        }
+        runner::RunOutcome::CostLimit { sessions, spent_usd, limit_usd } => {
+            eprintln!();
+            eprintln!(
+                "STOPPED after {sessions} session(s): project spend ${spent_usd:.2} reached \
+                 max_total_cost ${limit_usd:.2}. Raise it in forge.toml to continue."
+            );
+            std::process::exit(1);
+        }
+        runner::RunOutcome::SpawnError(e) => {
+            eprintln!();
+            eprintln!("Agent spawn failed: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The managed clone `forge run --repo` works in.
+fn checkout_remote(url: &str, branch: Option<&str>) -> PathBuf {
+    let dir = remote::checkout(&remote::cache_dir(), url, branch).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+    if !dir.join("forge.toml").exists() {
+        eprintln!("Error: {url} has no forge.toml; run `forge init` in it and push first.");
+        std::process::exit(1);
+    }
+    println!("Working in {}", dir.display());
+    dir
+}
+
+fn push_remote(project_dir: &Path, url: &str) {
+    match git::push_with_retry(project_dir, 3) {
+        Ok(()) => println!("Pushed to {url}"),
+        Err(e) => {
+            eprintln!("Error: pushing to {url}: {e}. The work is in {}", project_dir.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+fn load_workspace(project_dir: &Path) -> workspace::Workspace {
+    workspace::Workspace::load(project_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    })
+}
+
+fn cmd_run_workspace(project_dir: &Path, max_rounds: usize, overrides: &RunOverrides) {
+    let ws = load_workspace(project_dir);
+    // Each member keeps its own forge.toml roles; the CLI overrides apply to all
+    let members: Vec<runner::RunConfig> = ws
+        .member_dirs()
+        .map(|(_, dir)| load_run_config(&dir, 1, 1, false, overrides).1)
+        .collect();
+    println!(
+        "forge run --workspace: {} project(s), max_agents={}, max_rounds={max_rounds}",
+        ws.members.len(),
+        ws.max_agents
+    );
+    println!();
+
+    match workspace::run(&ws, &members, max_rounds) {
+        workspace::WorkspaceOutcome::AllDone { rounds } => {
+            println!();
+            println!("All features in the workspace done in {rounds} round(s).");
+        }
+        workspace::WorkspaceOutcome::MaxRounds { rounds, remaining } => {
+            println!();
+            println!("Stopped after {rounds} round(s). {remaining} feature(s) remaining.");
+        }
+        workspace::WorkspaceOutcome::Stuck { rounds, remaining } => {
+            println!();
+            println!("Stopped after {rounds} round(s): {remaining} feature(s) left, none claimable (blocked?).");
+        }
+        workspace::WorkspaceOutcome::Stopped { rounds } => {
+            println!();
+            println!("Stopped by request after {rounds} round(s).");
+        }
+        workspace::WorkspaceOutcome::SpawnError { member, error } => {
+            eprintln!();
+            eprintln!("Agent spawn failed in {member}: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_verify(project_dir: &Path) {
+    match verify::verify_all(project_dir) {
+        Ok(results) => {
+            if results.is_empty() {
+                println!("No features to verify (none are done or claimed).");
+                return;
+            }
+
+            let mut pass = 0;
+            let mut fail = 0;
+
+            for result in &results {
+                let status = if result.passed {
+                    pass += 1;
+                    "PASS"
+                } else {
+                    fail += 1;
+                    "FAIL"
+                };
+                println!("[{status}] {}", result.feature_id);
+                if !result.passed && !result.output.is_empty() {
+                    // Show first 5 lines of failure output
+                    for line in result.output.lines().take(5) {
+                        println!("  {line}");
+                    }
+                }
+            }
+
+            println!();
+            println!("{pass} passed, {fail} failed, {} total", results.len());
+
+            if fail > 0 {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_bisect(project_dir: &Path, script: &Path, good: Option<&str>) {
+    let good = match good {
+        Some(good) => Ok(good.to_string()),
+        None => bisect::before_forge(project_dir),
+    };
+    let culprit = good
+        .and_then(|good| {
+            println!("Bisecting {}..HEAD with {}", &good[..good.len().min(12)], script.display());
+            bisect::find_culprit(project_dir, script, &good)
+        })
+        .and_then(|commit| bisect::attribute(project_dir, &commit));
+    match culprit {
+        Ok(culprit) => print!("{culprit}"),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_doctor(project_dir: &Path, fix: bool) {
+    if fix {
+        match init::create_missing_dirs(project_dir) {
+            Ok(created) => {
+                for dir in created {
+                    println!("Created {}/", dir.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    let mut checks = doctor::check_project(project_dir);
+    checks.extend(doctor::check_environment(project_dir));
+    for check in &checks {
+        let status = match check.status {
+            doctor::Status::Ok => "ok  ",
+            doctor::Status::Warn => "warn",
+            doctor::Status::Fail => "FAIL",
+        };
+        println!("  {status}  {:<14} {}", check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("        {:<14} fix: {fix}", "");
+        }
+    }
+    if checks.iter().any(|c| c.status == doctor::Status::Fail) {
+        std::process::exit(1);
+    }
+}
+
+fn cmd_clean(project_dir: &Path, older_than_days: u64, dry_run: bool, release_claims: bool) {
+    let options = clean::CleanOptions { older_than_days, dry_run, release_claims };
+    let cleaned = clean::clean(project_dir, options).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+    if cleaned.is_empty() {
+        println!("Nothing to clean.");
+        return;
+    }
+    let (remove, release) = if dry_run { ("Would remove", "Would release") } else { ("Removed", "Released") };
+    for item in &cleaned {
+        match item.kind {
+            clean::Kind::Claim => println!("  {release} claim on {}", item.what),
+            _ => println!("  {remove} {} ({})", item.what, doctor::human_bytes(item.bytes)),
+        }
+    }
+    let bytes: u64 = cleaned.iter().map(|c| c.bytes).sum();
+    println!("{} {}", if dry_run { "Would free" } else { "Freed" }, doctor::human_bytes(bytes));
+}
+
+fn cmd_plugins(project_dir: &Path) {
+    let plugins = plugins::discover();
+    let hooked = config::ForgeConfig::load(project_dir).map(|c| c.plugins).unwrap_or_default();
+    if plugins.is_empty() && hooked.is_empty() {
+        println!("No {}<name> executables on PATH.", plugins::PREFIX);
+        return;
+    }
+    for plugin in &plugins {
+        let events = hooked.get(&plugin.name).map(|p| p.events.join(", ")).unwrap_or_default();
+        let events = if events.is_empty() { String::new() } else { format!("  hooks: {events}") };
+        println!("  {:<16} {}{events}", plugin.name, plugin.path.display());
+    }
+    for name in hooked.keys().filter(|name| !plugins.iter().any(|p| &p.name == *name)) {
+        println!("  {name:<16} [plugins.{name}] in forge.toml, but no {}{name} on PATH", plugins::PREFIX);
+    }
+}
+
+fn cmd_external(project_dir: &Path, args: &[String]) {
+    let (name, rest) = args.split_first().expect("clap passes the subcommand name");
+    let Some(plugin) = plugins::find(name) else {
+        eprintln!("Error: no such command '{name}', and no {}{name} on PATH (see `forge plugins`)", plugins::PREFIX);
+        std::process::exit(2);
+    };
+    match plugins::run(project_dir, &plugin, rest) {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("Error running {}: {e}", plugin.path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_stop(project_dir: &Path) {
+    match runner::request_stop(project_dir) {
+        Ok(()) => println!("Stop requested. Agents will stop after the current session."),
+        Err(e) => {
+            eprintln!("Error requesting stop: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_serve(project_dir: &Path, addr: &str) {
+    if !project_dir.join("features.json").exists() {
+        eprintln!("Error: no features.json in {}; run `forge init` first.", project_dir.display());
+        std::process::exit(1);
+    }
+    let token = std::env::var("FORGE_API_TOKEN").ok().filter(|t| !t.is_empty());
+    if token.is_none() && !addr.starts_with("127.0.0.1") && !addr.starts_with("localhost") {
+        eprintln!("Warning: serving on {addr} without FORGE_API_TOKEN; anyone who can reach it can claim and stop.");
+    }
+    if let Err(e) = api::serve(project_dir, addr, token) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn cmd_mcp(project_dir: &Path) {
+    // stdout carries the protocol; anything else goes to stderr
+    if let Err(e) = mcp::serve(project_dir, std::io::stdin().lock(), std::io::stdout().lock()) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn cmd_audit(project_dir: &Path, feature: Option<&str>, tail: usize) {
+    let records: Vec<audit::AuditRecord> =
+        audit::read(project_dir).into_iter().filter(|r| feature.is_none_or(|f| r.feature_id == f)).collect();
+    if records.is_empty() {
+        println!("No changes recorded in {}", audit::audit_path(project_dir).display());
+        return;
+    }
+    let short = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => s.lines().next().unwrap_or_default().to_string(),
+        other => other.to_string(),
+    };
+    for record in &records[records.len().saturating_sub(tail)..] {
+        let what = match record.action {
+            audit::Action::Updated => {
+                let changes: Vec<String> = record
+                    .changes
+                    .iter()
+                    .map(|(field, change)| format!("{field} {} -> {}", short(&change.from), short(&change.to)))
+                    .collect();
+                format!("updated {}", changes.join(", "))
+            }
+            audit::Action::Added => "added".to_string(),
+            audit::Action::Removed => "removed".to_string(),
+        };
+        println!(
+            "{}  {:<8} {} ({})  {what}",
+            record.ts.format("%Y-%m-%d %H:%M:%S"),
+            record.feature_id,
+            record.actor,
+            record.process
+        );
+    }
+}
+
+fn cmd_cost(
+    project_dir: &Path,
+    by: &[cost::CostGroup],
+    since: Option<chrono::NaiveDate>,
+    until: Option<chrono::NaiveDate>,
+    csv: bool,
+) {
+    use clap::ValueEnum as _;
+
+    let mut metrics = match transcripts::analyze_paths(&transcripts::claude_transcripts(project_dir)) {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            eprintln!("Error reading transcripts: {e}");
+            std::process::exit(1);
+        }
+    };
+    metrics.sessions.retain(|session| {
+        let day = cost::session_day(session).and_then(|d| d.parse::<chrono::NaiveDate>().ok());
+        since.is_none_or(|since| day.is_some_and(|d| d >= since))
+            && until.is_none_or(|until| day.is_some_and(|d| d <= until))
+    });
+    let features = features::FeatureList::load(project_dir).ok();
+    let groups = if by.is_empty() { cost::CostGroup::value_variants() } else { by };
+    let breakdown: Vec<(cost::CostGroup, Vec<cost::SpendRow>)> =
+        groups.iter().map(|&group| (group, cost::spend_by(&metrics, features.as_ref(), group))).collect();
+
+    if csv {
+        print!("{}", cost::spend_csv(&breakdown));
+        return;
+    }
+    if metrics.sessions.is_empty() {
+        println!("No sessions found for {}", project_dir.display());
+        return;
+    }
+    let total: f64 = metrics.sessions.iter().map(|s| s.cost_usd).sum();
+    println!("Total: ${total:.2} over {} session(s)", metrics.sessions.len());
+    for (group, rows) in &breakdown {
+        println!();
+        println!("By {}:", group.label());
+        if rows.is_empty() {
+            println!("  (none)");
+        }
+        let width = rows.iter().map(|r| r.key.len()).max().unwrap_or(0);
+        for row in rows {
+            println!(
+                "  {:<width$}  ${:>9.2}  {:>4} session(s)  {:>6.1}M tokens",
+                row.key,
+                row.cost_usd,
+                row.sessions,
+                row.tokens.total() as f64 / 1_000_000.0
+            );
+        }
+    }
+    let unpriced: std::collections::BTreeSet<&String> =
+        metrics.sessions.iter().flat_map(|s| s.unpriced_models.iter()).collect();
+    if !unpriced.is_empty() {
+        println!();
+        let names: Vec<&str> = unpriced.into_iter().map(String::as_str).collect();
+        println!("No price known for {}; their tokens are counted at $0", names.join(", "));
+    }
+}
+
+fn cmd_bench(
+    project_dir: &Path,
+    ids: &[String],
+    profiles: Vec<String>,
+    roles_matrix: bool,
+    base: &str,
+    max_sessions: Option<usize>,
+    json: bool,
+) {
+    let fail = |e: &dyn std::fmt::Display| -> ! {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    };
+    let list = features::FeatureList::load(project_dir).unwrap_or_else(|e| fail(&e));
+    let selected = bench::select(&list, ids).unwrap_or_else(|e| fail(&e));
+    let names = if roles_matrix {
+        let forge_config = config::ForgeConfig::load(project_dir).unwrap_or_else(|e| fail(&e));
+        bench::matrix(&forge_config)
+    } else {
+        profiles
+    };
+    let max_sessions = max_sessions.unwrap_or(selected.features.len() * 3);
+    let configs: Vec<(String, runner::RunConfig)> = names
+        .into_iter()
+        .map(|name| {
+            let profile = (name != "default").then(|| name.clone());
+            let overrides = RunOverrides { profile, backend: None, model: None, ab_model: None };
+            let (_, run_config) = load_run_config(project_dir, 1, max_sessions, false, &overrides);
+            (name, run_config)
+        })
+        .collect();
+
+    let results = bench::run(project_dir, &selected, &configs, base, &mut |name, run| {
+        eprintln!("forge bench: {name} (backend={}, model={})", run.protocol.backend, run.protocol.model);
+    })
+    .unwrap_or_else(|e| fail(&e));
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results).unwrap_or_default());
+        return;
+    }
+    let percent = |rate: Option<f64>| rate.map(|r| format!("{:.0}%", r * 100.0)).unwrap_or_else(|| "-".into());
+    println!();
+    println!(
+        "{:<14} {:<8} {:<20} {:>6} {:>8} {:>12} {:>11} {:>9}",
+        "config", "backend", "model", "done", "sessions", "sess/done", "verify pass", "cost"
+    );
+    for r in &results {
+        println!(
+            "{:<14} {:<8} {:<20} {:>6} {:>8} {:>12} {:>11} {:>9}",
+            r.config,
+            r.backend,
+            r.model,
+            format!("{}/{}", r.done, r.features),
+            r.sessions,
+            r.sessions_per_done().map(|n| format!("{n:.1}")).unwrap_or_else(|| "-".into()),
+            percent(r.verify_pass_rate()),
+            r.cost_usd.map(|c| format!("${c:.2}")).unwrap_or_else(|| "-".into()),
+        );
+    }
+    println!();
+    println!("Each configuration's commits are on its branch, e.g. {}", results.first().map_or("", |r| &r.branch));
+}
+
+fn cmd_stats_models(project_dir: &Path, json: bool) {
+    let events = events::merged_timeline(project_dir).unwrap_or_default();
+    let transcripts = transcripts::analyze_paths(&transcripts::claude_transcripts(project_dir)).unwrap_or_default();
+    let stats = stats::model_stats(&events, &transcripts);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats).unwrap_or_default());
+        return;
+    }
+    if stats.is_empty() {
+        println!("No sessions recorded in {}", events::events_dir(project_dir).display());
+        return;
+    }
+    let percent = |rate: Option<f64>| rate.map(|r| format!("{:.0}%", r * 100.0)).unwrap_or_else(|| "-".into());
+    println!(
+        "{:<14} {:<8} {:<20} {:>8} {:>8} {:>10} {:>12} {:>9} {:>8}",
+        "role", "backend", "model", "sessions", "features", "first try", "sess/feature", "avg cost", "blocked"
+    );
+    for s in &stats {
+        println!(
+            "{:<14} {:<8} {:<20} {:>8} {:>8} {:>10} {:>12} {:>9} {:>8}",
+            s.role,
+            s.backend,
+            s.variant.as_ref().map_or_else(|| s.model.clone(), |v| format!("{} ({v})", s.model)),
+            s.sessions,
+            s.features,
+            percent(s.first_try_rate()),
+            s.sessions_per_feature().map(|n| format!("{n:.1}")).unwrap_or_else(|| "-".into()),
+            s.avg_cost_usd.map(|c| format!("${c:.2}")).unwrap_or_else(|| "-".into()),
+            percent(s.blocked_rate()),
+        );
+    }
+}
+
+fn cmd_reviews_summarize(project_dir: &Path, last: usize) {
+    let reviews = reviews::load(project_dir);
+    if reviews.is_empty() {
+        println!("No reviews archived in {}", reviews::reviews_dir(project_dir).display());
+        return;
+    }
+    print!("{}", reviews::summarize(&reviews[reviews.len().saturating_sub(last)..]));
+}
+
+fn cmd_checkpoint(project_dir: &Path, action: CheckpointAction) {
+    let fail = |e: &dyn std::fmt::Display| -> ! {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    };
+    match action {
+        CheckpointAction::Create { name, note } => {
+            let checkpoint =
+                checkpoint::create(project_dir, name.as_deref(), note.as_deref()).unwrap_or_else(|e| fail(&e));
+            match &checkpoint.commit {
+                Some(commit) => println!("Checkpoint {} at {}", checkpoint.name, &commit[..commit.len().min(12)]),
+                None => println!("Checkpoint {} (not a git repo: forge state only)", checkpoint.name),
+            }
+        }
+        CheckpointAction::Restore { name } => {
+            let restored = checkpoint::restore(project_dir, &name).unwrap_or_else(|e| fail(&e));
+            if restored.reverted {
+                println!("Rolled the code back to {name} in one commit");
+            }
+            println!("Restored features.json, context/ and feedback/ from {name}");
+            println!("  Undo with `forge checkpoint restore {}`", restored.backup.name);
+        }
+        CheckpointAction::List => {
+            let checkpoints = checkpoint::list(project_dir);
+            if checkpoints.is_empty() {
+                println!("No checkpoints in {}", checkpoint::checkpoints_dir(project_dir).display());
+            }
+            for c in checkpoints {
+                let commit = c.commit.as_deref().map_or("-", |c| &c[..c.len().min(12)]);
+                let created = c.created.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                println!("  {:<28} {created}  {commit:<12}  {}", c.name, c.note.unwrap_or_default());
+            }
+        }
+    }
+}
+
+fn cmd_restore_features(project_dir: &Path, timestamp: Option<&str>) {
+    let Some(timestamp) = timestamp else {
+        let backups = features_backup::list(project_dir);
+        if backups.is_empty() {
+            println!("No features.json backups in {}", features_backup::backups_dir(project_dir).display());
+        }
+        for backup in backups {
+            let features = backup.features.map_or_else(|| "not valid JSON".to_string(), |n| format!("{n} feature(s)"));
+            println!("  {:<24} {features}", backup.timestamp);
+        }
+        return;
+    };
+    match features_backup::restore(project_dir, timestamp) {
+        Ok((backup, list)) => {
+            println!("Restored features.json from {} ({} feature(s))", backup.timestamp, list.features.len());
+            println!("  The file it replaced was backed up too: `forge restore-features` lists it");
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn cmd_runs(project_dir: &Path, action: RunsAction) {
+    let find = |id: &str| {
+        runs::find(project_dir, id).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        })
+    };
+    match action {
+        RunsAction::List => {
+            let runs = runs::list(project_dir);
+            if runs.is_empty() {
+                println!("No runs recorded in {}", runs::runs_dir(project_dir).display());
+            }
+            for run in runs {
+                let started = run.started_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                let agents = run.config["agents"].as_u64().unwrap_or(1);
+                let t = &run.totals;
+                println!(
+                    "  {:<18} {started}  {:>8}  {agents} agent(s)  {:<12} {} session(s), {} done, ${:.2}",
+                    run.id,
+                    runs::format_duration(run.duration()),
+                    run.outcome.as_deref().unwrap_or("unfinished"),
+                    t.sessions,
+                    t.completed.len(),
+                    t.cost_usd,
+                );
+            }
+        }
+        RunsAction::Compare { a, b } => print!("{}", runs::render_comparison(&find(&a), &find(&b))),
+    }
+}
+
+fn cmd_questions(project_dir: &Path, action: QuestionsAction) {
+    match action {
+        QuestionsAction::List { all } => {
+            let questions: Vec<_> = questions::list(project_dir).into_iter().filter(|q| all || q.is_open()).collect();
+            if questions.is_empty() {
+                println!("No open questions in {}", questions::questions_dir(project_dir).display());
+            }
+            for q in questions {
+                let asked = q.asked_at.map(|t| t.with_timezone(&chrono::Local).format(" (%Y-%m-%d %H:%M)").to_string());
+                println!("{}{}: {}", q.feature_id, asked.unwrap_or_default(), q.question);
+                if let Some(context) = &q.context {
+                    println!("  {}", context.replace('\n', "\n  "));
+                }
+                for option in &q.options {
+                    println!("  - {option}");
+                }
+                if let Some(answer) = &q.answer {
+                    println!("  answered: {}", answer.replace('\n', "\n  "));
+                }
+            }
+        }
+        QuestionsAction::Answer { feature_id, answer } => match questions::answer(project_dir, &feature_id, &answer) {
+            Ok(_) => println!("Answered {feature_id}; its next session gets the answer"),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+fn cmd_transcripts_analyze(project_dir: &Path, top: usize, json: bool) {
+    let paths = transcripts::claude_transcripts(project_dir);
+    let features = features::FeatureList::load(project_dir).ok();
+    let report = match transcript_patterns::analyze(project_dir, &paths, features.as_ref(), top) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error reading transcripts: {e}");
+            std::process::exit(1);
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+        return;
+    }
+    if report.sessions == 0 {
+        println!("No transcripts found for {}", project_dir.display());
+        return;
+    }
+    println!("{} session(s)", report.sessions);
+    let lists = [
+        ("Tools", &report.tools),
+        ("Tool sequences", &report.sequences),
+        ("Frequent errors", &report.errors),
+        ("Files read most", &report.files_read),
+    ];
+    for (title, counts) in lists {
+        println!();
+        println!("{title}:");
+        if counts.is_empty() {
+            println!("  (none)");
+        }
+        for count in counts {
+            println!("  {:>5}  {}", count.count, count.name);
+        }
+    }
+    println!();
+    println!("Executor sessions by feature type:");
+    if report.by_feature_type.is_empty() {
+        println!("  (none)");
+    }
+    for (kind, stats) in &report.by_feature_type {
+        println!(
+            "  {kind:<10} {:>4} session(s)  {:>6.1} turns  {:>6.1} tool calls  ${:.2} each",
+            stats.sessions, stats.avg_turns, stats.avg_tool_calls, stats.avg_cost_usd
+        );
+    }
+}
+
+fn cmd_logs(project_dir: &Path, agent: &str, tail: usize, follow: bool, all: bool) {
+    let agents = if all { agent_log::agents(project_dir) } else { vec![agent.to_string()] };
+    if all && agents.is_empty() {
+        eprintln!("No agent logs in {}", project_dir.join(".forge/logs").display());
+        std::process::exit(1);
+    }
+    let log_path = agent_log::log_path(project_dir, agent);
+    if !all && !log_path.exists() {
+        eprintln!("No log file found for agent '{agent}'");
+        eprintln!("  Expected: {}", log_path.display());
+        std::process::exit(1);
+    }
+
+    let width = agents.iter().map(String::len).max().unwrap_or(0);
+    let lines = agent_log::interleave(project_dir, &agents);
+    let mut followers: Vec<agent_log::Follower> =
+        agents.iter().map(|agent| agent_log::Follower::new(project_dir, agent, true)).collect();
+    for line in &lines[lines.len().saturating_sub(tail)..] {
+        println!("{}", line.display(all, width));
+    }
+    if !follow {
+        return;
+    }
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        // Pick up the logs of agents that started since
+        if all {
+            for agent in agent_log::agents(project_dir) {
+                if !followers.iter().any(|f| f.agent == agent) {
+                    followers.push(agent_log::Follower::new(project_dir, &agent, false));
+                }
+            }
+        }
+        let mut fresh: Vec<agent_log::LogLine> = followers.iter_mut().flat_map(|f| f.poll()).collect();
+        fresh.sort_by_key(|l| l.ts);
+        let width = followers.iter().map(|f| f.agent.len()).max().unwrap_or(width);
+        for line in fresh {
+            println!("{}", line.display(all, width));
+        }
+    }
+}
+
+/// `category slug` or `category/slug`.
+fn entry_ref(category: String, slug: Option<String>) -> (String, String) {
+    match slug {
+        Some(slug) => (category, slug),
+        None => match category.split_once('/') {
+            Some((cat, slug)) => (cat.to_string(), slug.to_string()),
+            None => {
+                eprintln!("Error: expected <category> <slug> or <category>/<slug>");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+fn cmd_feature(project_dir: &Path, action: FeatureAction) {
+    let FeatureAction::Add { description, scope, kind, depends_on, priority, verify } = action;
+    let fail = |e: &dyn std::fmt::Display| -> ! {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    };
+    let stack = config::ForgeConfig::load(project_dir).map(|c| c.project.stack).unwrap_or_else(|e| fail(&e));
+    let mut list = features::FeatureList::load(project_dir).unwrap_or_else(|e| fail(&e));
+    if let Some(missing) = depends_on.iter().find(|dep| !list.features.iter().any(|f| f.id == **dep)) {
+        fail(&format!("--depends-on {missing}: no such feature"));
+    }
+    let feature_type = match kind {
+        FeatureKind::Implement => features::FeatureType::Implement,
+        FeatureKind::Review => features::FeatureType::Review,
+        FeatureKind::Poc => features::FeatureType::Poc,
+    };
+    let id = list.next_id(&feature_type);
+    let feature = features::Feature {
+        verify: verify.unwrap_or_else(|| format!("./scripts/verify/{id}.sh")),
+        id,
+        feature_type,
+        scope,
+        description,
+        depends_on,
+        priority,
+        status: features::FeatureStatus::Pending,
+        claimed_by: None,
+        blocked_reason: None,
+        context_hints: vec![],
+    };
+    let script = verify_template::scaffold(project_dir, &stack, &feature).unwrap_or_else(|e| fail(&e));
+    println!("Added {}: {}", feature.id, feature.description.lines().next().unwrap_or(""));
+    match script {
+        Some(_) => println!("  Verify script: {} (from {}; fill in its TODO)", feature.verify, verify_template::TEMPLATE),
+        None => println!("  Verify: {}", feature.verify),
+    }
+    list.features.push(feature);
+    list.save(project_dir).unwrap_or_else(|e| fail(&e));
+}
+
+fn cmd_import_markdown(project_dir: &Path, path: &Path, scope: Option<String>, dry_run: bool) {
+    let fail = |e: &dyn std::fmt::Display| -> ! {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    };
+    let config = config::ForgeConfig::load(project_dir).unwrap_or_else(|e| fail(&e));
+    let scope = scope.unwrap_or_else(|| match config.scopes.keys().collect::<Vec<_>>().as_slice() {
+        [only] => only.to_string(),
+        _ => fail(&"forge.toml doesn't have exactly one scope; pass --scope"),
+    });
+    let markdown = std::fs::read_to_string(path).unwrap_or_else(|e| fail(&format!("{}: {e}", path.display())));
+    let plan = plan_import::parse(&markdown);
+    let mut list = features::FeatureList::load(project_dir).unwrap_or_else(|e| fail(&e));
+    let added = plan_import::to_features(&plan, &list, &scope);
+    if added.is_empty() {
+        println!("Nothing to import: {} has no checklist items that aren't features already", path.display());
+        return;
+    }
+    for feature in &added {
+        let deps =
+            if feature.depends_on.is_empty() { String::new() } else { format!(" (after {})", feature.depends_on.join(", ")) };
+        let done = if feature.status == features::FeatureStatus::Done { " [done]" } else { "" };
+        println!("  {} {}{deps}{done}", feature.id, feature.description.lines().next().unwrap_or(""));
+    }
+    if dry_run {
+        println!("Dry run: {} features not added", added.len());
+        return;
+    }
+    for feature in &added {
+        if let Err(e) = verify_template::scaffold(project_dir, &config.project.stack, feature) {
+            eprintln!("  Could not write {}: {e}", feature.verify);
+        }
+    }
+    let count = added.len();
+    list.features.extend(added);
+    list.save(project_dir).unwrap_or_else(|e| fail(&e));
+    println!("Imported {count} features from {}; verify scripts are in scripts/verify/", path.display());
+}
+
+fn cmd_context(project_dir: &Path, action: ContextAction) {
+    let ctx = context::ContextManager::new(project_dir);
+    let fail = |e: &dyn std::fmt::Display| -> ! {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    };
+
+    // Embedding costs a request, so only an explicit reindex refreshes it
+    let reindex = matches!(action, ContextAction::Reindex);
+    match action {
+        ContextAction::Add {
+            category,
+            slug,
+            source,
+            force,
+            tags,
+            features,
+            scopes,
+            supersedes,
+            expires,
+            valid_until_feature,
+        } => {
+            if !force && ctx.read_entry(&category, &slug).is_ok() {
+                fail(&format!("{category}/{slug} already exists (use --force to replace it)"));
+            }
+            let content = if source.as_os_str() == "-" {
+                let mut buf = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf).map(|_| buf)
+            } else {
+                std::fs::read_to_string(&source)
+            };
+            let content = content.unwrap_or_else(|e| fail(&e));
+            let meta = context_meta::Frontmatter {
+                created_at: None,
+                author: std::env::var("FORGE_AGENT_ID").ok().filter(|a| !a.is_empty()),
+                features,
+                scope: scopes,
+                tags,
+                supersedes,
+                expires,
+                valid_until_feature,
+                ..Default::default()
+            };
+            match ctx.write_entry_with_meta(&category, &slug, meta, &content) {
+                Ok(path) => println!("Wrote {}", path.display()),
+                Err(e) => fail(&e),
+            }
+        }
+        ContextAction::Fetch { url, slug, tags, force } => {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            match context_fetch::fetch_reference(&ctx, &url, slug.as_deref(), &tags, force) {
+                Ok(path) => println!("Wrote {}", path.display()),
+                Err(e) => fail(&e),
+            }
+        }
+        ContextAction::List { category } => {
+            let entries = match &category {
+                Some(cat) => ctx.list_category(cat),
+                None => ctx.list_all(),
+            };
+            let entries = entries.unwrap_or_else(|e| fail(&e));
+            if entries.is_empty() {
+                println!("No context entries");
+            }
+            for entry in &entries {
+                let tags = if entry.meta.tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", entry.meta.tags.join(", "))
+                };
+                println!("{}/{}: {}{tags}", entry.category, entry.slug, ctx.first_heading(&entry.path));
+            }
+            return;
+        }
+        ContextAction::Show { category, slug } => {
+            let (category, slug) = entry_ref(category, slug);
+            match ctx.read_entry(&category, &slug) {
+                Ok(content) => print!("{content}"),
+                Err(context::ContextError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                    fail(&format!("no context entry {category}/{slug}"))
+                }
+                Err(e) => fail(&e),
+            }
+            return;
+        }
+        ContextAction::Rm { category, slug } => {
+            let (category, slug) = entry_ref(category, slug);
+            match ctx.remove_entry(&category, &slug) {
+                Ok(()) => println!("Removed {category}/{slug}"),
+                Err(e) => fail(&e),
+            }
+        }
+        ContextAction::Log {
+            category,
+            slug,
+            show,
+            diff,
+            restore,
+        } => {
+            let (category, slug) = entry_ref(category, slug);
+            let history = ctx.history(&category, &slug).unwrap_or_else(|e| fail(&e));
+            let pick = |n: usize| -> &context::Revision {
+                history.get(n.wrapping_sub(1)).unwrap_or_else(|| {
+                    fail(&format!("{category}/{slug} has {} revisions, no revision {n}", history.len()))
+                })
+            };
+            let read = |path: &Path| std::fs::read_to_string(path).unwrap_or_else(|e| fail(&e));
+            if let Some(n) = show {
+                print!("{}", read(&pick(n).path));
+                return;
+            }
+            if let Some(n) = diff {
+                let old = pick(n);
+                // The version that replaced revision N: the next newer one, or the current file
+                let current = project_dir.join(format!("context/{category}/{slug}.md"));
+                let new_path = if n > 1 { pick(n - 1).path.clone() } else { current };
+                let new_label = if n > 1 { format!("revision {}", n - 1) } else { "current".into() };
+                let new_path = if new_path.exists() { new_path } else { PathBuf::from("/dev/null") };
+                let status = std::process::Command::new("diff")
+                    .args(["-u", "--label", &format!("revision {n}"), "--label", &new_label])
+                    .arg(&old.path)
+                    .arg(&new_path)
+                    .status();
+                match status {
+                    // 1 just means the files differ
+                    Ok(s) if s.code().is_some_and(|c| c <= 1) => {}
+                    Ok(s) => fail(&format!("diff exited with {s}")),
+                    Err(e) => fail(&format!("failed to run diff: {e}")),
+                }
+                return;
+            }
+            if let Some(n) = restore {
+                let content = read(&pick(n).path);
+                match ctx.write_entry(&category, &slug, &content) {
+                    Ok(_) => println!("Restored {category}/{slug} from revision {n}"),
+                    Err(e) => fail(&e),
+                }
+            } else {
+                if history.is_empty() {
+                    println!("No earlier versions of {category}/{slug}");
+                }
+                for (i, revision) in history.iter().enumerate() {
+                    println!("{:>3}  {}  {}", i + 1, revision.id, ctx.first_heading(&revision.path));
+                }
+                return;
+            }
+        }
+        ContextAction::Reindex => {}
+        ContextAction::Graph { dot } => {
+            let links = ctx.links().unwrap_or_else(|e| fail(&e));
+            if dot {
+                print!("{}", context::links_to_dot(&links));
+            } else if links.is_empty() {
+                println!("No links between context entries");
+            } else {
+                for link in &links {
+                    let missing = if link.dangling { " (missing)" } else { "" };
+                    println!("{} -> {} [{}]{missing}", link.from, link.to, link.kind.as_str());
+                }
+            }
+            return;
+        }
+        ContextAction::Packages => {
+            match context_flow::refresh_context(project_dir) {
+                Ok(true) => println!("Context packages refreshed in context/packages/"),
+                Ok(false) => println!("No pending features to package"),
+                Err(e) => fail(&e),
+            }
+            return;
+        }
+        ContextAction::Audit { sessions, json } => {
+            let transcripts = transcripts::sources()
+                .iter()
+                .flat_map(|s| s.discover(project_dir))
+                .collect();
+            let options = context_audit::AuditOptions {
+                unused_after_sessions: sessions,
+                transcripts,
+            };
+            let findings = context_audit::audit(project_dir, &options).unwrap_or_else(|e| fail(&e));
+            if json {
+                match serde_json::to_string_pretty(&findings) {
+                    Ok(text) => println!("{text}"),
+                    Err(e) => fail(&e),
+                }
+            } else if findings.is_empty() {
+                println!("No stale context found");
+            } else {
+                for finding in &findings {
+                    println!("{finding}");
+                }
+                println!();
+                println!("{} findings", findings.len());
+            }
+            return;
+        }
+        ContextAction::Lint { json } => {
+            let findings = context_lint::lint(project_dir, None).unwrap_or_else(|e| fail(&e));
+            if json {
+                match serde_json::to_string_pretty(&findings) {
+                    Ok(text) => println!("{text}"),
+                    Err(e) => fail(&e),
+                }
+            } else if findings.is_empty() {
+                println!("Context lint passed");
+            } else {
+                for finding in &findings {
+                    println!("{finding}");
+                }
+                println!();
+                println!("{} findings", findings.len());
+            }
+            if !findings.is_empty() {
+                std::process::exit(1);
+            }
+            return;
+        }
+        ContextAction::Search {
+            query,
+            category,
+            limit,
+        } => {
+            let hits = ctx
+                .search(&query, category.as_deref())
+                .unwrap_or_else(|e| fail(&e));
+            if hits.is_empty() {
+                println!("No matches for \"{query}\"");
+            }
+            for hit in hits.iter().take(limit) {
+                println!("{}/{} ({:.1})", hit.category, hit.slug, hit.score);
+                println!("    {}", hit.snippet);
+            }
+            return;
         }
-    }
-}
-
-fn cmd_install(project_dir: &PathBuf) {
-    match init::install_project(project_dir) {
-        Ok(()) => {
-            println!("Installed forge project in {}", project_dir.display());
-            println!();
-            println!("Updated:");
-            println!("  .claude/skills/         skills reinstalled from binary");
-            println!("  .agents/skills/         skills reinstalled from binary (Codex)");
-            println!("  CLAUDE.md               regenerated from forge.toml");
-            println!("  AGENTS.md               regenerated from forge.toml");
-            println!("  context/                directories ensured");
-            println!("  scripts/verify/         scripts marked executable");
-            println!();
-
-            // Check backend CLIs
-            let config = config::ForgeConfig::load(project_dir).unwrap_or_else(|_| {
-                config::ForgeConfig::scaffold("unknown", "")
-            });
-
-            let mut backends = std::collections::BTreeSet::new();
-            backends.insert(config.forge.roles.protocol.backend.as_str());
-            backends.insert(config.forge.roles.orchestrating.backend.as_str());
-            backends.insert(config.forge.roles.planning.backend.as_str());
-            backends.insert(config.forge.roles.adjusting.backend.as_str());
-
-            let mut missing = Vec::new();
-            for backend in &backends {
-                if std::process::Command::new(backend)
-                    .arg("--version")
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::null())
-                    .status()
-                    .is_err()
-                {
-                    missing.push(*backend);
+        ContextAction::Promote { entry, to, slug } => {
+            let (category, old_slug) = entry_ref(entry, None);
+            let rewritten = ctx
+                .promote(&category, &old_slug, &to, slug.as_deref())
+                .unwrap_or_else(|e| fail(&e));
+            let (old, new) = (
+                format!("{category}/{old_slug}"),
+                format!("{to}/{}", slug.as_deref().unwrap_or(&old_slug)),
+            );
+            let mut updated = 0;
+            if let Ok(mut features) = features::FeatureList::load(project_dir) {
+                updated = features.replace_context_hint(&old, &new);
+                if updated > 0 {
+                    features.save(project_dir).unwrap_or_else(|e| fail(&e));
                 }
             }
-
-            if missing.is_empty() {
-                println!("Backends: all OK ({})", backends.into_iter().collect::<Vec<_>>().join(", "));
-            } else {
-                for name in &missing {
-                    eprintln!("Warning: backend '{}' not found in PATH", name);
+            println!("Moved {old} to {new}");
+            println!("  {updated} features' context_hints updated");
+            for id in &rewritten {
+                println!("  links rewritten in {id}");
+            }
+        }
+        ContextAction::Export { format, output } => {
+            let name = match format {
+                VaultFormat::Obsidian => "obsidian",
+                VaultFormat::Mdbook => "mdbook",
+            };
+            let out = output.unwrap_or_else(|| project_dir.join(format!(".forge/export/context-{name}")));
+            let written = match format {
+                VaultFormat::Obsidian => context_vault::export_obsidian(project_dir, &out),
+                VaultFormat::Mdbook => {
+                    let project = config::ForgeConfig::load(project_dir)
+                        .map(|c| c.project.name)
+                        .unwrap_or_else(|_| "Project".into());
+                    context_vault::export_mdbook(project_dir, &out, &format!("{project} context"))
                 }
+            };
+            match written {
+                Ok(n) => println!("Exported {n} entries to {}", out.display()),
+                Err(e) => fail(&e),
             }
+            return;
         }
-        Err(e) => {
-            eprintln!("Error: {e}");
-            std::process::exit(1);
+        ContextAction::Dedupe { threshold, apply } => {
+            let pairs = ctx.near_duplicates(threshold).unwrap_or_else(|e| fail(&e));
+            if pairs.is_empty() {
+                println!("No near-duplicate entries");
+                return;
+            }
+            for pair in &pairs {
+                println!(
+                    "{cat}/{} ≈ {cat}/{} ({:.0}%), keep {cat}/{}",
+                    pair.remove,
+                    pair.keep,
+                    pair.similarity * 100.0,
+                    pair.keep,
+                    cat = pair.category
+                );
+            }
+            if !apply {
+                println!();
+                println!("Run with --apply to merge them");
+                return;
+            }
+            let mut features = features::FeatureList::load(project_dir).ok();
+            let mut merged = std::collections::HashSet::new();
+            for pair in &pairs {
+                let (old, new) = (
+                    format!("{}/{}", pair.category, pair.remove),
+                    format!("{}/{}", pair.category, pair.keep),
+                );
+                // An entry already merged away can't be merged again
+                if merged.contains(&old) || merged.contains(&new) {
+                    continue;
+                }
+                ctx.merge_entries(&pair.category, &pair.keep, &pair.remove)
+                    .unwrap_or_else(|e| fail(&e));
+                let updated = features.as_mut().map_or(0, |f| f.replace_context_hint(&old, &new));
+                println!("Merged {old} into {new} ({updated} features updated)");
+                merged.insert(old);
+            }
+            if let Some(features) = &features
+                && let Err(e) = features.save(project_dir)
+            {
+                fail(&e);
+            }
         }
     }
-}
-
-fn cmd_run(
-    project_dir: &PathBuf,
-    agents: usize,
-    max_sessions: usize,
-    watch: bool,
-    backend: Option<String>,
-    model: Option<String>,
-) {
-    // Sync skills to both .claude/skills/ and .agents/skills/ so existing
-    // projects work with Codex without requiring re-init.
-    if let Err(e) = skills::sync_skills(project_dir) {
-        eprintln!("Warning: failed to sync skills: {e}");
-    }
-
-    // Load forge config to get role settings
-    let forge_config = config::ForgeConfig::load(project_dir).unwrap_or_else(|_| {
-        config::ForgeConfig::scaffold("unknown", "")
-    });
-
-    let mut protocol = forge_config.forge.roles.protocol.clone();
-    let mut orchestrating = forge_config.forge.roles.orchestrating.clone();
 
-    // Apply CLI overrides
-    if let Some(ref b) = backend {
-        protocol.backend = b.clone();
-        orchestrating.backend = b.clone();
+    // Every change goes through here so INDEX.md never lags the entries
+    match ctx.write_index() {
+        Ok(()) => println!("Updated context/INDEX.md"),
+        Err(e) => fail(&e),
     }
-    if let Some(ref m) = model {
-        protocol.model = m.clone();
-        orchestrating.model = m.clone();
+    if reindex
+        && let Some((embedder, _)) = context_index::configured_embedder(project_dir)
+    {
+        match context_index::reindex(project_dir, &embedder) {
+            Ok(stats) => println!(
+                "Embeddings: {} embedded, {} unchanged",
+                stats.embedded, stats.unchanged
+            ),
+            Err(e) => fail(&e),
+        }
     }
+}
 
-    let run_config = runner::RunConfig {
-        project_dir: project_dir.clone(),
-        protocol,
-        orchestrating,
-        max_sessions,
-        num_agents: agents,
+fn cmd_config_validate(project_dir: &Path, strict: bool, json: bool) {
+    let issues = match config_validate::validate(project_dir, strict) {
+        Ok(issues) => issues,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
     };
-
-    if watch {
-        // TUI mode: spawn agents in interactive PTY panes
-        let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
-        rt.block_on(async {
-            if let Err(e) = tui::run_tui(&run_config).await {
-                eprintln!("TUI error: {e}");
+    if json {
+        match serde_json::to_string_pretty(&issues) {
+            Ok(text) => println!("{text}"),
+            Err(e) => {
+                eprintln!("Error: {e}");
                 std::process::exit(1);
             }
-        });
-        return;
+        }
+    } else if issues.is_empty() {
+        println!("forge.toml: OK");
+    } else {
+        for issue in &issues {
+            println!("{issue}");
+        }
     }
+    if issues.iter().any(|i| i.severity == config_validate::Severity::Error) {
+        std::process::exit(1);
+    }
+}
 
-    // Headless mode (original behavior)
-    println!(
-        "forge run: {} agent(s), backend={}, model={}, max_sessions={}",
-        agents, run_config.protocol.backend, run_config.protocol.model, max_sessions
-    );
-    println!();
-
-    let outcome = if agents > 1 {
-        runner::run_multi_agent(&run_config)
+fn cmd_config_wizard(project_dir: &Path) {
+    // Start from what's there, so rerunning the wizard edits rather than resets
+    let config = if project_dir.join("forge.toml").exists() {
+        config::ForgeConfig::load(project_dir).unwrap_or_else(|e| {
+            eprintln!("Error: {e} (fix it or move it aside to start over)");
+            std::process::exit(1);
+        })
     } else {
-        runner::run_single_agent(&run_config)
+        let name = project_dir
+            .canonicalize()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "my-project".into());
+        config::ForgeConfig::scaffold(&name, "")
     };
-
-    match outcome {
-        runner::RunOutcome::AllDone { sessions } => {
-            println!();
-            println!("All features done in {sessions} session(s).");
-        }
-        runner::RunOutcome::MaxSessions {
-            sessions,
-            remaining,
-        } => {
-            println!();
-            println!("Stopped after {sessions} session(s). {remaining} feature(s) remaining.");
+    let installed = config_wizard::installed_backends(&config.backends);
+    let mut prompter = config_wizard::Prompter::new(std::io::stdin().lock(), std::io::stdout());
+    let config = match config_wizard::run(&mut prompter, project_dir, config, &installed) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("Nothing written.");
+            return;
         }
-        runner::RunOutcome::Stopped { sessions } => {
-            println!();
-            println!("Stopped by request after {sessions} session(s).");
-        }
-        runner::RunOutcome::SpawnError(e) => {
-            eprintln!();
-            eprintln!("Agent spawn failed: {e}");
+        Err(e) => {
+            eprintln!("Error: {e}");
             std::process::exit(1);
         }
+    };
+    if let Err(e) = config.save(project_dir) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
     }
+    println!("Wrote forge.toml. Checking it:");
+    cmd_config_validate(project_dir, false, false);
 }
 
-fn cmd_verify(project_dir: &PathBuf) {
-    match verify::verify_all(project_dir) {
-        Ok(results) => {
-            if results.is_empty() {
-                println!("No features to verify (none are done or claimed).");
-                return;
-            }
-
-            let mut pass = 0;
-            let mut fail = 0;
-
-            for result in &results {
-                let status = if result.passed {
-                    pass += 1;
-                    "PASS"
-                } else {
-                    fail += 1;
-                    "FAIL"
-                };
-                println!("[{status}] {}", result.feature_id);
-                if !result.passed && !result.output.is_empty() {
-                    // Show first 5 lines of failure output
-                    for line in result.output.lines().take(5) {
-                        println!("  {line}");
-                    }
-                }
-            }
-
-            println!();
-            println!("{pass} passed, {fail} failed, {} total", results.len());
-
-            if fail > 0 {
+fn cmd_export_diff(old: &Path, new: &Path, json: bool) {
+    match export_diff::diff_exports(old, new) {
+        Ok(diff) if json => match serde_json::to_string_pretty(&diff) {
+            Ok(text) => println!("{text}"),
+            Err(e) => {
+                eprintln!("Error: {e}");
                 std::process::exit(1);
             }
-        }
+        },
+        Ok(diff) => print!("{diff}"),
         Err(e) => {
             eprintln!("Error: {e}");
             std::process::exit(1);
@@ -321,53 +2441,122 @@ fn cmd_verify(project_dir: &PathBuf) {
     }
 }
 
-fn cmd_stop(project_dir: &PathBuf) {
-    match runner::request_stop(project_dir) {
-        Ok(()) => println!("Stop requested. Agents will stop after the current session."),
+fn cmd_replay(from: &Path, print: bool) {
+    let replay = match replay::load(from) {
+        Ok(replay) => replay,
         Err(e) => {
-            eprintln!("Error requesting stop: {e}");
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    if !print && std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        if let Err(e) = tui_replay::run(&replay) {
+            eprintln!("Error: {e}");
             std::process::exit(1);
         }
+        return;
     }
+    let end = replay.at(replay.steps.len());
+    println!(
+        "{}: {} events, exported {}",
+        replay.project,
+        replay.steps.len(),
+        replay.exported_at
+    );
+    for step in &replay.steps {
+        let done = step.after.count(features::FeatureStatus::Done);
+        println!(
+            "{}  {:<44}  {done}/{} done",
+            step.event.ts.format("%Y-%m-%d %H:%M:%S"),
+            replay::describe(&step.event),
+            step.after.features.len()
+        );
+    }
+    let verify = end.verify.map(|(passed, failed)| format!(", last verify {passed}/{}", passed + failed));
+    println!(
+        "{} session(s), {}/{} done{}",
+        end.sessions_started,
+        end.count(features::FeatureStatus::Done),
+        end.features.len(),
+        verify.unwrap_or_default()
+    );
 }
 
-fn cmd_logs(project_dir: &PathBuf, agent: &str, tail: usize) {
-    let log_path = project_dir.join(".forge/logs").join(format!("{agent}.log"));
-    if !log_path.exists() {
-        eprintln!("No log file found for agent '{agent}'");
-        eprintln!("  Expected: {}", log_path.display());
+fn cmd_export(
+    project_dir: &Path,
+    output: Option<PathBuf>,
+    archive: Option<PathBuf>,
+    html: bool,
+    upload: bool,
+    format: ExportFormat,
+    mut options: export::ExportOptions,
+) {
+    // Packets get their own directory so a full export doesn't replace them
+    let output_dir = output.unwrap_or_else(|| match &options.packet {
+        Some(id) => project_dir.join(format!(".forge/export-{id}")),
+        None => project_dir.join(".forge/export"),
+    });
+
+    // Fail on a bad extension before doing the export
+    if let Some(path) = &archive
+        && let Err(e) = export::ArchiveFormat::from_path(path)
+    {
+        eprintln!("Error: {e}");
         std::process::exit(1);
     }
 
-    match std::fs::read_to_string(&log_path) {
-        Ok(content) => {
-            let lines: Vec<&str> = content.lines().collect();
-            let start = lines.len().saturating_sub(tail);
-            for line in &lines[start..] {
-                println!("{line}");
-            }
-        }
-        Err(e) => {
-            eprintln!("Error reading log: {e}");
+    // A broken forge.toml shouldn't block the export meant to debug it
+    let (export_config, secrets) = config::ForgeConfig::load(project_dir)
+        .map(|c| (c.export, c.secrets))
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: {e}; using default redaction rules");
+            (config::ExportConfig::default(), Default::default())
+        });
+    let uploader = match (&export_config.upload, upload) {
+        (Some(cfg), true) => Some((upload::uploader_for(cfg), cfg.prefix.clone())),
+        (None, true) => {
+            eprintln!("Error: --upload needs an [export.upload] section in forge.toml");
             std::process::exit(1);
         }
+        (_, false) => None,
+    };
+    // Uploads need a single file; default to a tarball next to the export dir
+    let archive = archive.or_else(|| uploader.as_ref().map(|_| output_dir.with_extension("tar.gz")));
+    if export_config.redact {
+        // Unresolvable secrets are still caught by name
+        let secrets = secrets.iter().map(|(name, s)| (name.clone(), secrets::resolve(name, s).ok())).collect();
+        options.redactor = match redact::Redactor::new(&export_config.redact_patterns) {
+            Ok(r) => r.with_secrets(&secrets),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        };
     }
-}
-
-fn cmd_export(
-    project_dir: &PathBuf,
-    output: Option<PathBuf>,
-    no_transcripts: bool,
-    git_commits: usize,
-) {
-    let output_dir = output.unwrap_or_else(|| project_dir.join(".forge/export"));
-    let include_transcripts = !no_transcripts;
 
-    match export::export_project(project_dir, &output_dir, include_transcripts, git_commits) {
+    match export::export_project(project_dir, &output_dir, &options) {
         Ok(manifest) => {
             println!("Exported to {}", output_dir.display());
             println!();
             println!("Sections: {}", manifest.sections.join(", "));
+            if !manifest.filter.is_empty() {
+                println!("Filter: {}", manifest.filter);
+            }
+            if let Some(packet) = &manifest.packet {
+                println!(
+                    "Feature: {} ({}, last verify {}), {} context entries",
+                    packet.feature_id,
+                    packet.status,
+                    packet.last_verify,
+                    packet.context.len()
+                );
+                if !packet.missing_context.is_empty() {
+                    println!("  Missing context: {}", packet.missing_context.join(", "));
+                }
+            }
+            if manifest.redactions > 0 {
+                println!("Redacted: {} secrets", manifest.redactions);
+            }
             println!(
                 "Features: {} total ({} done, {} pending)",
                 manifest.features.total, manifest.features.done, manifest.features.pending
@@ -381,14 +2570,85 @@ fn cmd_export(
             if !manifest.transcripts.is_empty() {
                 let total_bytes: u64 =
                     manifest.transcripts.iter().map(|t| t.size_bytes).sum();
+                let mut per_backend: std::collections::BTreeMap<&str, usize> = Default::default();
+                for t in &manifest.transcripts {
+                    *per_backend.entry(t.backend.as_str()).or_default() += 1;
+                }
+                let breakdown: Vec<String> =
+                    per_backend.iter().map(|(b, n)| format!("{b} {n}")).collect();
                 println!(
-                    "Transcripts: {} sessions ({:.1} MB)",
+                    "Transcripts: {} sessions, {} ({:.1} MB)",
                     manifest.transcripts.len(),
+                    breakdown.join(", "),
                     total_bytes as f64 / 1_048_576.0
                 );
             }
+            if let Some(cost) = &manifest.cost {
+                println!(
+                    "Cost: ${:.2} ({} features, ${:.2} orchestration)",
+                    cost.total_usd,
+                    cost.features.len(),
+                    cost.orchestration.cost_usd
+                );
+                for (label, usd) in &cost.milestones {
+                    println!("  {label}: ${usd:.2}");
+                }
+            }
+            if manifest.sections.iter().any(|s| s == "metrics") {
+                println!("Metrics: {}", output_dir.join("metrics.json").display());
+            }
             println!();
             println!("Manifest: {}", output_dir.join("manifest.json").display());
+
+            // Before the archive, so the report and tables travel with it
+            if html {
+                match report::write_html_report(&output_dir, &manifest) {
+                    Ok(path) => println!("Report: {}", path.display()),
+                    Err(e) => {
+                        eprintln!("Error writing report: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            if format == ExportFormat::Parquet {
+                match export_parquet::write_parquet_tables(&output_dir) {
+                    Ok(paths) => {
+                        for path in paths {
+                            println!("Parquet: {}", path.display());
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Error writing parquet tables: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            if let Some(path) = &archive {
+                match export::write_archive(&output_dir, path) {
+                    Ok(size) => println!(
+                        "Archive: {} ({:.1} MB)",
+                        path.display(),
+                        size as f64 / 1_048_576.0
+                    ),
+                    Err(e) => {
+                        eprintln!("Error writing archive: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            if let (Some(path), Some((uploader, prefix))) = (&archive, &uploader) {
+                let key = upload::object_key(prefix, path, chrono::Utc::now());
+                match uploader.upload(path, &key) {
+                    Ok(url) => println!("Uploaded: {url}"),
+                    Err(e) => {
+                        eprintln!("Error uploading to {}: {e}", uploader.provider());
+                        std::process::exit(1);
+                    }
+                }
+            }
         }
         Err(e) => {
             eprintln!("Error: {e}");
@@ -397,7 +2657,7 @@ fn cmd_export(
     }
 }
 
-fn cmd_status(project_dir: &PathBuf) {
+fn cmd_status(project_dir: &Path) {
     // Load features
     let features = match features::FeatureList::load(project_dir) {
         Ok(f) => f,
@@ -407,31 +2667,37 @@ fn cmd_status(project_dir: &PathBuf) {
         }
     };
 
+    if let Some(reason) = runner::cost_limit_reason(project_dir) {
+        println!("STOPPED: {reason}");
+        println!();
+    }
+
     let dag = render_feature_dag(&features);
     print!("{dag}");
 
     // Load context
     let ctx = context::ContextManager::new(project_dir);
-    match ctx.counts() {
-        Ok(ctx_counts) => {
-            let total: usize = ctx_counts.values().sum();
-            if total > 0 {
-                println!();
-                println!("Context: {total} entries");
-                let parts: Vec<String> = ctx_counts
-                    .iter()
-                    .filter(|(_, count)| **count > 0)
-                    .map(|(cat, count)| format!("{cat}: {count}"))
-                    .collect();
-                if !parts.is_empty() {
-                    println!("  {}", parts.join(", "));
-                }
+    if let Ok(ctx_counts) = ctx.counts() {
+        let total: usize = ctx_counts.values().sum();
+        if total > 0 {
+            println!();
+            println!("Context: {total} entries");
+            let parts: Vec<String> = ctx_counts
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(cat, count)| format!("{cat}: {count}"))
+                .collect();
+            if !parts.is_empty() {
+                println!("  {}", parts.join(", "));
             }
         }
-        Err(_) => {}
     }
 }
 
+fn cmd_status_workspace(project_dir: &Path) {
+    print!("{}", workspace::render_status(&load_workspace(project_dir)));
+}
+
 fn render_feature_dag(features: &features::FeatureList) -> String {
     use features::{FeatureList, FeatureStatus, FeatureType};
     use std::collections::HashMap;
@@ -447,6 +2713,9 @@ fn render_feature_dag(features: &features::FeatureList) -> String {
     if counts.blocked > 0 {
         out.push_str(&format!(", {} blocked", counts.blocked));
     }
+    if counts.waiting > 0 {
+        out.push_str(&format!(", {} waiting", counts.waiting));
+    }
     out.push_str(")\n");
 
     if counts.total > 0 {
@@ -496,7 +2765,8 @@ fn render_feature_dag(features: &features::FeatureList) -> String {
             let after = &desc[pos + 6..];
             let rest = after.trim_start_matches(|c: char| c == '.' || c == ',' || c.is_whitespace());
             if !rest.is_empty() {
-                let end = rest.find(|c: char| c == ':' || c == '.')
+                let end = rest
+                    .find([':', '.'])
                     .unwrap_or(rest.len())
                     .min(30);
                 let short = rest[..end].trim();
@@ -606,6 +2876,16 @@ fn render_feature_dag(features: &features::FeatureList) -> String {
         }
     }
 
+    // === Waiting on answers ===
+    let waiting: Vec<&features::Feature> =
+        features.features.iter().filter(|f| f.status == FeatureStatus::Waiting).collect();
+    if !waiting.is_empty() {
+        out.push_str("\nWaiting on answers (forge questions list):\n");
+        for f in &waiting {
+            out.push_str(&format!("  ? {}  {}\n", f.id, truncate(&f.description, 45)));
+        }
+    }
+
     // === Next up (grouped by milestone) ===
     let milestone_groups = features.milestone_claimable();
     if !milestone_groups.is_empty() {
@@ -0,0 +1,367 @@
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use serde_json::{Map, Value, json};
+
+use crate::context::ContextManager;
+use crate::context_meta::Frontmatter;
+use crate::events::{self, EventKind};
+use crate::features::FeatureList;
+use crate::verify;
+
+/// Where Claude Code finds a project's MCP servers.
+pub const CLIENT_CONFIG: &str = ".mcp.json";
+
+/// Spoken when the client doesn't say which version it wants.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Name, description and JSON Schema of each tool.
+fn tools() -> Value {
+    let id = json!({ "type": "string", "description": "Feature id, e.g. f003" });
+    json!([
+        {
+            "name": "list_features",
+            "description": "Features from features.json, optionally only those with one status.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": { "type": "string", "enum": ["pending", "claimed", "done", "blocked", "waiting"] },
+                },
+            },
+        },
+        {
+            "name": "claim_feature",
+            "description": "Claim a pending feature whose dependencies are done.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "feature_id": id, "agent": { "type": "string" } },
+                "required": ["feature_id"],
+            },
+        },
+        {
+            "name": "mark_done",
+            "description": "Run the feature's verify script and mark it done if it passes.",
+            "inputSchema": { "type": "object", "properties": { "feature_id": id }, "required": ["feature_id"] },
+        },
+        {
+            "name": "mark_blocked",
+            "description": "Mark a feature blocked, saying what it needs.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "feature_id": id, "reason": { "type": "string" } },
+                "required": ["feature_id", "reason"],
+            },
+        },
+        {
+            "name": "write_context",
+            "description": "Write a context entry (markdown) and regenerate context/INDEX.md.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "category": { "type": "string", "description": "decisions, gotchas, patterns, poc, references" },
+                    "slug": { "type": "string" },
+                    "content": { "type": "string" },
+                    "feature_id": id,
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["category", "slug", "content"],
+            },
+        },
+        {
+            "name": "search_context",
+            "description": "Ranked context entries matching a query, with the best line of each.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "category": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1 },
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "run_verify",
+            "description": "Run a feature's verify script and return whether it passed and its output.",
+            "inputSchema": { "type": "object", "properties": { "feature_id": id }, "required": ["feature_id"] },
+        },
+    ])
+}
+
+fn text(value: &Map<String, Value>, key: &str) -> Result<String, String> {
+    match value.get(key) {
+        Some(Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+        Some(_) => Err(format!("`{key}` must be a non-empty string")),
+        None => Err(format!("`{key}` is required")),
+    }
+}
+
+/// The agent a call acts for: `agent`, else `FORGE_AGENT_ID`, else `mcp`.
+fn agent(args: &Map<String, Value>) -> String {
+    args.get("agent")
+        .and_then(Value::as_str)
+        .map(String::from)
+        .or_else(|| std::env::var("FORGE_AGENT_ID").ok().filter(|a| !a.is_empty()))
+        .unwrap_or_else(|| "mcp".to_string())
+}
+
+/// Run tool `name`. `Ok` and `Err` both carry text for the agent; `Err`
+/// is reported as a failed call it can correct.
+pub fn call_tool(project_dir: &Path, name: &str, args: &Map<String, Value>) -> Result<String, String> {
+    let load = || FeatureList::load(project_dir).map_err(|e| e.to_string());
+    match name {
+        "list_features" => {
+            let status = args.get("status").and_then(Value::as_str);
+            let features: Vec<Value> = load()?
+                .features
+                .iter()
+                .map(|f| json!(f))
+                .filter(|f| status.is_none_or(|s| f["status"] == s))
+                .collect();
+            Ok(serde_json::to_string_pretty(&features).unwrap_or_default())
+        }
+        "claim_feature" => {
+            let id = text(args, "feature_id")?;
+            let agent = agent(args);
+            let mut list = load()?;
+            list.claim(&id, &agent).map_err(|e| e.to_string())?;
+            list.save(project_dir).map_err(|e| e.to_string())?;
+            events::emit(project_dir, &agent, EventKind::Claim { feature_id: id.clone() });
+            Ok(format!("Claimed {id} for {agent}"))
+        }
+        "mark_done" => {
+            let id = text(args, "feature_id")?;
+            let mut list = load()?;
+            let feature = list.features.iter().find(|f| f.id == id).ok_or(format!("feature not found: {id}"))?;
+            let result = verify::verify_feature(project_dir, feature).map_err(|e| e.to_string())?;
+            if !result.passed {
+                return Err(format!("{id} not marked done: verify failed\n{}", result.output.trim_end()));
+            }
+            list.mark_done(&id).map_err(|e| e.to_string())?;
+            list.save(project_dir).map_err(|e| e.to_string())?;
+            Ok(format!("{id} verified and marked done"))
+        }
+        "mark_blocked" => {
+            let id = text(args, "feature_id")?;
+            let reason = text(args, "reason")?;
+            let mut list = load()?;
+            list.mark_blocked(&id, &reason).map_err(|e| e.to_string())?;
+            list.save(project_dir).map_err(|e| e.to_string())?;
+            Ok(format!("{id} marked blocked"))
+        }
+        "write_context" => {
+            let (category, slug, content) = (text(args, "category")?, text(args, "slug")?, text(args, "content")?);
+            let strings = |key: &str| -> Vec<String> {
+                match args.get(key) {
+                    Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(String::from).collect(),
+                    Some(Value::String(s)) => vec![s.clone()],
+                    _ => Vec::new(),
+                }
+            };
+            let meta = Frontmatter {
+                author: Some(agent(args)),
+                features: strings("feature_id"),
+                tags: strings("tags"),
+                ..Default::default()
+            };
+            let ctx = ContextManager::new(project_dir);
+            let path = ctx.write_entry_with_meta(&category, &slug, meta, &content).map_err(|e| e.to_string())?;
+            ctx.write_index().map_err(|e| e.to_string())?;
+            let path = path.strip_prefix(project_dir).unwrap_or(&path);
+            Ok(format!("Wrote {}", path.display()))
+        }
+        "search_context" => {
+            let query = text(args, "query")?;
+            let category = args.get("category").and_then(Value::as_str);
+            let limit = args.get("limit").and_then(Value::as_u64).unwrap_or(10) as usize;
+            let hits = ContextManager::new(project_dir).search(&query, category).map_err(|e| e.to_string())?;
+            if hits.is_empty() {
+                return Ok(format!("No matches for \"{query}\""));
+            }
+            Ok(hits
+                .iter()
+                .take(limit)
+                .map(|hit| format!("{}/{} ({:.1})\n    {}", hit.category, hit.slug, hit.score, hit.snippet))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        "run_verify" => {
+            let id = text(args, "feature_id")?;
+            let list = load()?;
+            let feature = list.features.iter().find(|f| f.id == id).ok_or(format!("feature not found: {id}"))?;
+            let result = verify::verify_feature(project_dir, feature).map_err(|e| e.to_string())?;
+            let status = if result.passed { "PASS" } else { "FAIL" };
+            Ok(format!("[{status}] {id}\n{}", result.output.trim_end()))
+        }
+        _ => Err(format!("unknown tool: {name}")),
+    }
+}
+
+fn response(id: Value, result: Result<Value, (i64, String)>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+    }
+}
+
+/// Answer one JSON-RPC message; `None` for notifications, which get no reply.
+pub fn handle(project_dir: &Path, message: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(message) {
+        Ok(message) => message,
+        Err(e) => return Some(response(Value::Null, Err((-32700, format!("parse error: {e}"))))),
+    };
+    let id = message.get("id").cloned()?;
+    let params = message.get("params").and_then(Value::as_object).cloned().unwrap_or_default();
+    let result = match message.get("method").and_then(Value::as_str).unwrap_or_default() {
+        "initialize" => Ok(json!({
+            "protocolVersion": params.get("protocolVersion").and_then(Value::as_str).unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "forge", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or_default();
+            let args = params.get("arguments").and_then(Value::as_object).cloned().unwrap_or_default();
+            let (text, is_error) = match call_tool(project_dir, name, &args) {
+                Ok(text) => (text, false),
+                Err(text) => (text, true),
+            };
+            Ok(json!({ "content": [{ "type": "text", "text": text }], "isError": is_error }))
+        }
+        method => Err((-32601, format!("method not found: {method}"))),
+    };
+    Some(response(id, result))
+}
+
+/// Serve MCP over stdio, one JSON-RPC message per line, until stdin closes.
+pub fn serve(project_dir: &Path, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle(project_dir, &line) {
+            writeln!(output, "{reply}")?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Register `forge mcp` in `.mcp.json`, keeping any other servers there. A
+/// file that isn't valid JSON is left alone.
+pub fn write_client_config(project_dir: &Path) -> std::io::Result<()> {
+    let path = project_dir.join(CLIENT_CONFIG);
+    let mut config = match std::fs::read_to_string(&path) {
+        Ok(content) => match serde_json::from_str::<Value>(&content) {
+            Ok(Value::Object(map)) => map,
+            _ => {
+                tracing::warn!("not updating {CLIENT_CONFIG}: not a JSON object");
+                return Ok(());
+            }
+        },
+        Err(_) => Map::new(),
+    };
+    let servers = config.entry("mcpServers").or_insert_with(|| json!({}));
+    if !servers.is_object() {
+        *servers = json!({});
+    }
+    servers["forge"] = json!({ "command": "forge", "args": ["mcp"] });
+    let json = serde_json::to_string_pretty(&Value::Object(config)).map_err(std::io::Error::other)?;
+    std::fs::write(path, json + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{Feature, FeatureStatus, FeatureType};
+    use crate::init;
+
+    fn call(dir: &Path, name: &str, args: Value) -> Value {
+        let message = json!({
+            "jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": { "name": name, "arguments": args },
+        });
+        handle(dir, &message.to_string()).unwrap()["result"].clone()
+    }
+
+    #[test]
+    fn lists_tools_and_ignores_notifications() {
+        let dir = tempfile::tempdir().unwrap();
+        let init = handle(dir.path(), r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#).unwrap();
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+        assert!(handle(dir.path(), r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).is_none());
+        let list = handle(dir.path(), r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#).unwrap();
+        let tools = list["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert!(names.contains(&"claim_feature") && names.contains(&"run_verify"), "{names:?}");
+        let unknown = handle(dir.path(), r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#).unwrap();
+        assert_eq!(unknown["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn claims_and_marks_done_only_when_verify_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        init::init_project(dir.path(), "mcp", false).unwrap();
+        let feature = Feature {
+            id: "f001".into(),
+            feature_type: FeatureType::Implement,
+            scope: "core".into(),
+            description: "One".into(),
+            verify: "scripts/verify/f001.sh".into(),
+            depends_on: vec![],
+            priority: 1,
+            status: FeatureStatus::Pending,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        };
+        FeatureList { features: vec![feature] }.save(dir.path()).unwrap();
+        std::fs::write(dir.path().join("scripts/verify/f001.sh"), "echo nope; exit 1\n").unwrap();
+
+        let claimed = call(dir.path(), "claim_feature", json!({ "feature_id": "f001", "agent": "agent-2" }));
+        assert_eq!(claimed["content"][0]["text"], "Claimed f001 for agent-2");
+        let again = call(dir.path(), "claim_feature", json!({ "feature_id": "f001" }));
+        assert_eq!(again["isError"], true);
+
+        let refused = call(dir.path(), "mark_done", json!({ "feature_id": "f001" }));
+        assert_eq!(refused["isError"], true);
+        assert!(refused["content"][0]["text"].as_str().unwrap().contains("nope"));
+        std::fs::write(dir.path().join("scripts/verify/f001.sh"), "exit 0\n").unwrap();
+        assert_eq!(call(dir.path(), "mark_done", json!({ "feature_id": "f001" }))["isError"], false);
+        assert_eq!(FeatureList::load(dir.path()).unwrap().features[0].status, FeatureStatus::Done);
+
+        let missing = call(dir.path(), "mark_blocked", json!({ "feature_id": "f001" }));
+        assert_eq!(missing["content"][0]["text"], "`reason` is required");
+    }
+
+    #[test]
+    fn writes_and_finds_context() {
+        let dir = tempfile::tempdir().unwrap();
+        init::init_project(dir.path(), "mcp", false).unwrap();
+        let content = "# Retry with backoff\n\nThe payments API rate-limits bursts; back off exponentially.\n";
+        let wrote = call(
+            dir.path(),
+            "write_context",
+            json!({ "category": "gotchas", "slug": "payments-rate-limit", "content": content, "tags": ["payments"] }),
+        );
+        assert_eq!(wrote["content"][0]["text"], "Wrote context/gotchas/payments-rate-limit.md");
+        let entry = std::fs::read_to_string(dir.path().join("context/gotchas/payments-rate-limit.md")).unwrap();
+        assert!(entry.contains("payments"), "{entry}");
+        let found = call(dir.path(), "search_context", json!({ "query": "rate-limits" }));
+        assert!(found["content"][0]["text"].as_str().unwrap().starts_with("gotchas/payments-rate-limit"), "{found}");
+        let bad = call(dir.path(), "write_context", json!({ "category": "nope", "slug": "x", "content": "y" }));
+        assert_eq!(bad["isError"], true);
+    }
+
+    #[test]
+    fn client_config_keeps_other_servers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CLIENT_CONFIG), r#"{"mcpServers": {"db": {"command": "db-mcp"}}}"#).unwrap();
+        write_client_config(dir.path()).unwrap();
+        let written = std::fs::read_to_string(dir.path().join(CLIENT_CONFIG)).unwrap();
+        let config: Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(config["mcpServers"]["db"]["command"], "db-mcp");
+        assert_eq!(config["mcpServers"]["forge"]["args"], json!(["mcp"]));
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::git;
+
+/// Diff lines quoted per side of a conflicted file.
+const MAX_DIFF_LINES: usize = 120;
+
+/// `feedback/merge-conflicts/<name>.md`, named for the feature (or the
+/// agent, for work with no feature).
+pub fn report_path(project_dir: &Path, name: &str) -> PathBuf {
+    project_dir.join(format!("feedback/merge-conflicts/{name}.md"))
+}
+
+/// A file both sides changed, with each side's hunks since the fork.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// Why an agent branch didn't merge, written for whoever takes the feature
+/// up next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictReport {
+    pub feature_id: Option<String>,
+    pub branch: String,
+    /// The branch it was merged into.
+    pub into: String,
+    pub files: Vec<ConflictedFile>,
+    /// Features whose commits on `into` touched the conflicted files.
+    pub features: Vec<String>,
+}
+
+/// A diff from its first hunk on, cut to `MAX_DIFF_LINES`.
+fn hunks(diff: &str) -> String {
+    let lines: Vec<&str> = diff.lines().skip_while(|l| !l.starts_with("@@")).collect();
+    let mut out = lines.iter().take(MAX_DIFF_LINES).copied().collect::<Vec<_>>().join("\n");
+    if lines.len() > MAX_DIFF_LINES {
+        let _ = write!(out, "\n… {} more lines", lines.len() - MAX_DIFF_LINES);
+    }
+    out
+}
+
+impl ConflictReport {
+    /// Both sides of each conflicted path since `base`, where `branch` forked.
+    pub fn collect(repo_dir: &Path, branch: &str, base: &str, conflicts: &[String], feature_id: Option<&str>) -> Self {
+        let side = |to: &str, path: &str| hunks(&git::diff(repo_dir, &["-U3", base, to, "--", path]).unwrap_or_default());
+        let files = conflicts
+            .iter()
+            .map(|path| ConflictedFile { path: path.clone(), ours: side("HEAD", path), theirs: side(branch, path) })
+            .collect();
+        let features = git::trailer_values(repo_dir, "Forge-Feature", &format!("{base}..HEAD"), conflicts)
+            .into_iter()
+            .filter(|id| Some(id.as_str()) != feature_id)
+            .collect();
+        Self {
+            feature_id: feature_id.map(String::from),
+            branch: branch.to_string(),
+            into: git::current_branch(repo_dir).unwrap_or_else(|| "HEAD".into()),
+            files,
+            features,
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.feature_id.as_deref().unwrap_or_else(|| self.branch.rsplit('/').next().unwrap_or(&self.branch))
+    }
+
+    pub fn render(&self) -> String {
+        let (branch, into) = (&self.branch, &self.into);
+        let mut out = String::new();
+        let _ = writeln!(out, "# Merge conflict: {} ({branch} into {into})\n", self.name());
+        let _ = writeln!(
+            out,
+            "{branch} was not merged: {} file(s) conflict with work that landed on {into} after it forked.\n",
+            self.files.len()
+        );
+        let mut involved: Vec<String> = self.feature_id.iter().map(|id| format!("{id} (this branch)")).collect();
+        involved.extend(self.features.iter().map(|id| format!("{id} (on {into})")));
+        if !involved.is_empty() {
+            let _ = writeln!(out, "Features involved: {}\n", involved.join(", "));
+        }
+        for file in &self.files {
+            let _ = writeln!(out, "## {}\n", file.path);
+            for (label, diff) in [(format!("On {into}"), &file.ours), (format!("On {branch}"), &file.theirs)] {
+                let _ = writeln!(out, "### {label}\n\n```diff\n{diff}\n```\n");
+            }
+        }
+        let _ = writeln!(out, "## Resolving\n");
+        let _ = writeln!(
+            out,
+            "Start from {into}'s version of these files and redo this branch's change on top of it. \
+             Keep what the other features added; their verify commands must still pass."
+        );
+        out
+    }
+
+    /// Write the report to its `report_path`. Returns the path.
+    pub fn write(&self, project_dir: &Path) -> std::io::Result<PathBuf> {
+        let path = report_path(project_dir, self.name());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, self.render())?;
+        Ok(path)
+    }
+}
+
+/// Drop a report once the work it describes has merged.
+pub fn clear(project_dir: &Path, name: &str) {
+    let _ = std::fs::remove_file(report_path(project_dir, name));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_shows_both_sides_and_features() {
+        let report = ConflictReport {
+            feature_id: Some("f002".into()),
+            branch: "forge/agent-2".into(),
+            into: "main".into(),
+            files: vec![ConflictedFile {
+                path: "src/lib.rs".into(),
+                ours: "@@ -1 +1 @@\n-fn a() {}\n+fn a() -> u8 { 1 }".into(),
+                theirs: "@@ -1 +1 @@\n-fn a() {}\n+fn a() -> u16 { 2 }".into(),
+            }],
+            features: vec!["f001".into()],
+        };
+        let text = report.render();
+        assert!(text.starts_with("# Merge conflict: f002 (forge/agent-2 into main)\n"), "{text}");
+        assert!(text.contains("Features involved: f002 (this branch), f001 (on main)"), "{text}");
+        assert!(text.contains("### On main\n\n```diff\n@@ -1 +1 @@\n-fn a() {}\n+fn a() -> u8 { 1 }\n```"), "{text}");
+        assert!(text.contains("### On forge/agent-2\n\n```diff\n@@ -1 +1 @@\n-fn a() {}\n+fn a() -> u16 { 2 }\n```"), "{text}");
+
+        let long: String = (0..200).map(|n| format!("+line {n}\n")).collect();
+        let cut = hunks(&format!("diff --git a/x b/x\n@@ -0,0 +1,200 @@\n{long}"));
+        assert!(cut.starts_with("@@") && cut.ends_with("… 81 more lines"), "{cut}");
+    }
+}
@@ -0,0 +1,222 @@
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::config::{ForgeConfig, NotifyEvent, WebhookConfig, WebhookFormat};
+use crate::events::{Event, EventKind};
+use crate::notify_desktop;
+use crate::notify_email;
+use crate::secrets;
+
+/// Which `events` entry covers `event`, if any. Orchestrator sessions are
+/// housekeeping, so only executors' ends count.
+pub(crate) fn trigger(event: &Event) -> Option<NotifyEvent> {
+    Some(match &event.kind {
+        EventKind::SessionEnd { .. } if event.agent != "orchestrator" => NotifyEvent::SessionEnd,
+        EventKind::FeatureBlocked { .. } => NotifyEvent::FeatureBlocked,
+        EventKind::QuestionAsked { .. } => NotifyEvent::QuestionAsked,
+        EventKind::MilestoneDone { .. } => NotifyEvent::MilestoneDone,
+        EventKind::BudgetExceeded { .. } | EventKind::CostLimitReached { .. } => NotifyEvent::BudgetExceeded,
+        EventKind::RunFinished { .. } => NotifyEvent::RunFinished,
+        EventKind::Verify { failed, .. } if *failed > 0 => NotifyEvent::VerifyFailed,
+        _ => return None,
+    })
+}
+
+/// One line saying what happened, for chat messages.
+pub fn describe(project: &str, event: &Event) -> String {
+    format!("[forge {project}] {}", summary(event))
+}
+
+/// What happened, without the project.
+pub(crate) fn summary(event: &Event) -> String {
+    let agent = &event.agent;
+    match &event.kind {
+        EventKind::SessionEnd { feature_id, exit_code, timed_out, .. } => {
+            let on = feature_id.as_deref().map(|f| format!(" on {f}")).unwrap_or_default();
+            let how = match (timed_out, exit_code) {
+                (true, _) => "timed out".to_string(),
+                (false, Some(code)) => format!("exited {code}"),
+                (false, None) => "was killed".to_string(),
+            };
+            format!("{agent}'s session{on} {how}")
+        }
+        EventKind::FeatureBlocked { feature_id, reason } => match reason {
+            Some(reason) => format!("{feature_id} is blocked: {reason}"),
+            None => format!("{feature_id} is blocked"),
+        },
+        EventKind::QuestionAsked { feature_id, question } => format!("{feature_id} needs an answer: {question}"),
+        EventKind::MilestoneDone { feature_id, milestone } => format!("{milestone} is done ({feature_id} passed)"),
+        EventKind::BudgetExceeded { feature_id, budget } => {
+            let limit = budget.map(|b| format!(" ${b:.2}")).unwrap_or_default();
+            let on = feature_id.as_deref().map(|f| format!(" on {f}")).unwrap_or_default();
+            format!("{agent} hit its{limit} budget{on}")
+        }
+        EventKind::CostLimitReached { spent_usd, limit_usd } => {
+            format!("project spend ${spent_usd:.2} passed max_total_cost ${limit_usd:.2}; no new sessions")
+        }
+        EventKind::Verify { passed, failed, reopened } => {
+            let mut text = format!("verify failed: {failed} of {}", passed + failed);
+            if !reopened.is_empty() {
+                text.push_str(&format!(", reopened {}", reopened.join(", ")));
+            }
+            text
+        }
+        EventKind::RunFinished { outcome, sessions, remaining } => match outcome.as_str() {
+            "all_done" => format!("run finished: every feature done after {sessions} session(s)"),
+            "stopped" => format!("run stopped by request after {sessions} session(s), {remaining} feature(s) left"),
+            "cost_limit" => {
+                format!("run stopped at max_total_cost after {sessions} session(s), {remaining} feature(s) left")
+            }
+            "error" => format!("run failed after {sessions} session(s), {remaining} feature(s) left"),
+            _ => format!("run stopped after {sessions} session(s) with {remaining} feature(s) left"),
+        },
+        other => format!("{other:?}"),
+    }
+}
+
+/// `format`, or the one the URL's host implies.
+fn format_for(config: &WebhookConfig, url: &str) -> WebhookFormat {
+    config.format.unwrap_or(if url.contains("hooks.slack.com") {
+        WebhookFormat::Slack
+    } else if url.contains("discord.com/api/webhooks") || url.contains("discordapp.com/api/webhooks") {
+        WebhookFormat::Discord
+    } else {
+        WebhookFormat::Generic
+    })
+}
+
+/// The JSON body sent for `event`.
+pub fn payload(format: WebhookFormat, project: &str, event: &Event) -> Value {
+    let text = describe(project, event);
+    match format {
+        WebhookFormat::Slack => json!({ "text": text }),
+        WebhookFormat::Discord => json!({ "content": text }),
+        WebhookFormat::Generic => {
+            let mut body = serde_json::to_value(event).unwrap_or_else(|_| json!({}));
+            if let Value::Object(map) = &mut body {
+                map.insert("project".into(), json!(project));
+                map.insert("text".into(), json!(text));
+            }
+            body
+        }
+    }
+}
+
+/// `value` as a double-quoted string for a curl `-K` config.
+pub(crate) fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// POST `body` to `url` with curl. The URL goes through stdin so it stays
+/// out of `ps`.
+fn post(url: &str, body: &Value) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args(["-sS", "--fail", "--max-time", "10", "-K", "-", "-H", "Content-Type: application/json"])
+        .arg("--data-binary")
+        .arg(body.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "url = {}", curl_quote(url)).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Send `event` to the project's webhook, mail it and show it on the
+/// desktop, where their `events` list it. Failures are printed; a run never stops over a notification.
+pub fn notify(project_dir: &Path, event: &Event) {
+    let Some(trigger) = trigger(event) else {
+        return;
+    };
+    let Ok(config) = ForgeConfig::load(project_dir) else {
+        return;
+    };
+    if let Some(webhook) = &config.notifications.webhook
+        && webhook.events.contains(&trigger)
+    {
+        send_webhook(&config, webhook, event);
+    }
+    if let Some(email) = &config.notifications.email {
+        notify_email::notify(project_dir, &config, email, trigger, event);
+    }
+    if let Some(desktop) = &config.notifications.desktop {
+        notify_desktop::notify(&config.project.name, desktop, trigger, event);
+    }
+}
+
+fn send_webhook(config: &ForgeConfig, webhook: &WebhookConfig, event: &Event) {
+    let url = match &webhook.url_env {
+        Some(name) => secrets::env_or_secret(&config.secrets, name).unwrap_or_default(),
+        None => webhook.url.clone(),
+    };
+    if url.is_empty() {
+        warn!("Webhook skipped: no URL (set [notifications.webhook] url or url_env)");
+        return;
+    }
+    let body = payload(format_for(webhook, &url), &config.project.name, event);
+    if let Err(e) = post(&url, &body) {
+        warn!("Webhook failed (non-fatal): {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(agent: &str, kind: EventKind) -> Event {
+        Event { ts: Utc::now(), agent: agent.into(), kind }
+    }
+
+    #[test]
+    fn payload_follows_the_format() {
+        let blocked = event(
+            "agent-1",
+            EventKind::FeatureBlocked { feature_id: "f003".into(), reason: Some("needs an API key".into()) },
+        );
+        assert_eq!(
+            payload(WebhookFormat::Slack, "shop", &blocked),
+            json!({ "text": "[forge shop] f003 is blocked: needs an API key" })
+        );
+        let discord = payload(WebhookFormat::Discord, "shop", &blocked);
+        assert_eq!(discord["content"], "[forge shop] f003 is blocked: needs an API key");
+        let generic = payload(WebhookFormat::Generic, "shop", &blocked);
+        assert_eq!(generic["event"], "feature_blocked");
+        assert_eq!(generic["feature_id"], "f003");
+        assert_eq!(generic["project"], "shop");
+
+        let finished =
+            event("forge", EventKind::RunFinished { outcome: "max_sessions".into(), sessions: 12, remaining: 5 });
+        assert_eq!(describe("shop", &finished), "[forge shop] run stopped after 12 session(s) with 5 feature(s) left");
+    }
+
+    #[test]
+    fn only_listed_events_with_a_guessed_format() {
+        let end = EventKind::SessionEnd { feature_id: None, exit_code: Some(0), timed_out: false, output_bytes: None };
+        assert_eq!(trigger(&event("agent-1", end.clone())), Some(NotifyEvent::SessionEnd));
+        assert_eq!(trigger(&event("orchestrator", end)), None);
+        assert_eq!(trigger(&event("agent-1", EventKind::Claim { feature_id: "f001".into() })), None);
+        let verify = |failed| EventKind::Verify { passed: 3, failed, reopened: vec![] };
+        assert_eq!(trigger(&event("forge", verify(0))), None);
+        assert_eq!(trigger(&event("forge", verify(2))), Some(NotifyEvent::VerifyFailed));
+        assert_eq!(summary(&event("forge", verify(2))), "verify failed: 2 of 5");
+
+        let config: WebhookConfig = toml::from_str("url = \"https://hooks.slack.com/services/T/B/x\"").unwrap();
+        assert_eq!(config.events.len(), 6);
+        assert_eq!(format_for(&config, &config.url), WebhookFormat::Slack);
+        assert_eq!(format_for(&config, "https://discord.com/api/webhooks/1/x"), WebhookFormat::Discord);
+        assert_eq!(format_for(&config, "https://ci.example.com/forge"), WebhookFormat::Generic);
+    }
+}
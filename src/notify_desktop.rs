@@ -0,0 +1,25 @@
+use tracing::debug;
+
+use crate::config::{DesktopConfig, NotifyEvent};
+use crate::events::Event;
+use crate::notify;
+
+/// Post `event` as a desktop notification, if `desktop.events` lists it.
+/// Best effort: with no notification daemon (a CI box, an ssh session) it
+/// is only logged at debug level.
+pub fn notify(project: &str, desktop: &DesktopConfig, trigger: NotifyEvent, event: &Event) {
+    if !desktop.events.contains(&trigger) {
+        return;
+    }
+    show(&format!("forge {project}"), &notify::summary(event));
+}
+
+/// Post a desktop notification; failures are only logged at debug level.
+/// Also used for the TUI's `[alerts] desktop`.
+pub fn show(title: &str, body: &str) {
+    let mut notification = notify_rust::Notification::new();
+    notification.appname("forge").summary(title).body(body);
+    if let Err(e) = notification.show() {
+        debug!("Desktop notification failed: {e}");
+    }
+}
@@ -0,0 +1,226 @@
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use crate::config::{EmailConfig, ForgeConfig, NotifyEvent};
+use crate::events::{Event, EventKind};
+use crate::notify;
+use crate::secrets;
+
+/// Events waiting for the next digest, one JSON object per line.
+fn digest_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/email-digest.jsonl")
+}
+
+/// Mail `event`, or add it to the digest and mail that once it's due.
+pub fn notify(project_dir: &Path, config: &ForgeConfig, email: &EmailConfig, trigger: NotifyEvent, event: &Event) {
+    let project = &config.project.name;
+    let mail = if email.digest == 0 {
+        if !email.events.contains(&trigger) {
+            return;
+        }
+        let text = notify::describe(project, event);
+        Some((text.clone(), text + "\n"))
+    } else {
+        // Session ends are kept whether or not they're mailed: they pace the digest
+        if email.events.contains(&trigger) || trigger == NotifyEvent::SessionEnd {
+            queue(project_dir, event);
+        }
+        take_digest(project_dir, email.digest as usize, trigger == NotifyEvent::RunFinished)
+            .map(|batch| digest(project, email, &batch))
+    };
+    let Some((subject, body)) = mail else {
+        return;
+    };
+    if let Err(e) = send(project_dir, config, email, &message(email, &subject, &body, Utc::now())) {
+        warn!("Email failed (non-fatal): {e}");
+    }
+}
+
+fn queue(project_dir: &Path, event: &Event) {
+    let path = digest_path(project_dir);
+    let Ok(line) = serde_json::to_string(event) else {
+        return;
+    };
+    let _ = fs::create_dir_all(path.parent().expect("under .forge"));
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{line}");
+    }
+}
+
+/// The queued events, emptying the queue, once they span `every` executor
+/// sessions or the run has `finished`.
+fn take_digest(project_dir: &Path, every: usize, finished: bool) -> Option<Vec<Event>> {
+    let path = digest_path(project_dir);
+    let content = fs::read_to_string(&path).ok()?;
+    let events: Vec<Event> = content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+    let sessions = events.iter().filter(|e| notify::trigger(e) == Some(NotifyEvent::SessionEnd)).count();
+    if events.is_empty() || !(finished || sessions >= every) {
+        return None;
+    }
+    let _ = fs::remove_file(&path);
+    Some(events)
+}
+
+/// Subject and body summing up `events`.
+fn digest(project: &str, email: &EmailConfig, events: &[Event]) -> (String, String) {
+    let count = |kind: NotifyEvent| events.iter().filter(|e| notify::trigger(e) == Some(kind)).count();
+    let mut subject = format!("[forge {project}] {} session(s)", count(NotifyEvent::SessionEnd));
+    for (kind, label) in [
+        (NotifyEvent::FeatureBlocked, "blocked"),
+        (NotifyEvent::QuestionAsked, "question(s)"),
+        (NotifyEvent::MilestoneDone, "milestone(s) done"),
+        (NotifyEvent::BudgetExceeded, "over budget"),
+    ] {
+        let n = count(kind);
+        if n > 0 && email.events.contains(&kind) {
+            subject.push_str(&format!(", {n} {label}"));
+        }
+    }
+    let mut body = String::new();
+    for event in events.iter().filter(|e| notify::trigger(e).is_some_and(|t| email.events.contains(&t))) {
+        body.push_str(&format!("{}  {}\n", event.ts.format("%Y-%m-%d %H:%M"), notify::summary(event)));
+    }
+    if body.is_empty() {
+        body.push_str("Nothing to report beyond the sessions themselves.\n");
+    }
+    if let Some(finished) = events.iter().rev().find(|e| matches!(e.kind, EventKind::RunFinished { .. })) {
+        subject = notify::describe(project, finished);
+    }
+    (subject, body)
+}
+
+/// A plain-text message, CRLF-terminated as SMTP wants it.
+fn message(email: &EmailConfig, subject: &str, body: &str, date: DateTime<Utc>) -> String {
+    let headers = [
+        format!("From: {}", email.from),
+        format!("To: {}", email.to.join(", ")),
+        format!("Subject: {subject}"),
+        format!("Date: {}", date.to_rfc2822()),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=utf-8".to_string(),
+    ];
+    let mut out = headers.join("\r\n");
+    out.push_str("\r\n\r\n");
+    out.push_str(&body.replace("\r\n", "\n").replace('\n', "\r\n"));
+    out
+}
+
+/// Hand `message` to the SMTP server with curl. The config, password
+/// included, goes through stdin; the message through a file next to the
+/// digest, removed after.
+fn send(project_dir: &Path, config: &ForgeConfig, email: &EmailConfig, message: &str) -> Result<(), String> {
+    if email.to.is_empty() {
+        return Err("[notifications.email] to is empty".into());
+    }
+    let file = project_dir.join(format!(".forge/email-{}.eml", std::process::id()));
+    fs::create_dir_all(file.parent().expect("under .forge")).map_err(|e| e.to_string())?;
+    fs::write(&file, message).map_err(|e| e.to_string())?;
+
+    let mut curl_config = vec![
+        format!("url = {}", notify::curl_quote(&email.smtp_url)),
+        format!("mail-from = {}", notify::curl_quote(&email.from)),
+        format!("upload-file = {}", notify::curl_quote(&file.to_string_lossy())),
+    ];
+    curl_config.extend(email.to.iter().map(|to| format!("mail-rcpt = {}", notify::curl_quote(to))));
+    if let Some(username) = &email.username {
+        let password = email
+            .password_env
+            .as_deref()
+            .and_then(|name| secrets::env_or_secret(&config.secrets, name))
+            .unwrap_or_default();
+        curl_config.push(format!("user = {}", notify::curl_quote(&format!("{username}:{password}"))));
+    }
+    if email.smtp_url.starts_with("smtp://") {
+        curl_config.push("ssl".to_string());
+    }
+
+    let result = (|| {
+        let mut child = Command::new("curl")
+            .args(["-sS", "--max-time", "30", "-K", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("curl: {e}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", curl_config.join("\n")).map_err(|e| e.to_string())?;
+        }
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    })();
+    let _ = fs::remove_file(&file);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(digest: u32) -> EmailConfig {
+        toml::from_str(&format!(
+            "smtp_url = \"smtps://smtp.example.com\"\nfrom = \"forge@example.com\"\nto = [\"a@example.com\", \
+             \"b@example.com\"]\nevents = [\"feature_blocked\", \"run_finished\"]\ndigest = {digest}"
+        ))
+        .unwrap()
+    }
+
+    fn event(agent: &str, kind: EventKind) -> Event {
+        Event { ts: Utc::now(), agent: agent.into(), kind }
+    }
+
+    fn session_end() -> Event {
+        let kind = EventKind::SessionEnd {
+            feature_id: Some("f001".into()),
+            exit_code: Some(0),
+            timed_out: false,
+            output_bytes: None,
+        };
+        event("agent-1", kind)
+    }
+
+    #[test]
+    fn digest_waits_for_enough_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        queue(dir.path(), &session_end());
+        queue(dir.path(), &event("forge", EventKind::FeatureBlocked { feature_id: "f002".into(), reason: None }));
+        assert!(take_digest(dir.path(), 2, false).is_none());
+
+        queue(dir.path(), &session_end());
+        let batch = take_digest(dir.path(), 2, false).unwrap();
+        assert_eq!(batch.len(), 3);
+        assert!(take_digest(dir.path(), 2, true).is_none(), "queue emptied");
+
+        let (subject, body) = digest("shop", &email(2), &batch);
+        assert_eq!(subject, "[forge shop] 2 session(s), 1 blocked");
+        // session_end isn't listed, so only the blocked feature is spelled out
+        assert_eq!(body.lines().count(), 1);
+        assert!(body.ends_with("f002 is blocked\n"), "{body}");
+
+        let finished = EventKind::RunFinished { outcome: "all_done".into(), sessions: 2, remaining: 0 };
+        queue(dir.path(), &event("forge", finished));
+        let batch = take_digest(dir.path(), 2, true).unwrap();
+        let (subject, _) = digest("shop", &email(2), &batch);
+        assert_eq!(subject, "[forge shop] run finished: every feature done after 2 session(s)");
+    }
+
+    #[test]
+    fn message_has_headers_and_crlf_lines() {
+        let date = DateTime::parse_from_rfc3339("2026-03-01T09:30:00Z").unwrap().with_timezone(&Utc);
+        let message = message(&email(0), "[forge shop] f002 is blocked", "one\ntwo\n", date);
+        assert_eq!(
+            message,
+            "From: forge@example.com\r\nTo: a@example.com, b@example.com\r\nSubject: [forge shop] f002 is blocked\r\n\
+             Date: Sun, 1 Mar 2026 09:30:00 +0000\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\
+             \r\none\r\ntwo\r\n"
+        );
+    }
+}
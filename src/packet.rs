@@ -0,0 +1,210 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::config::ForgeConfig;
+use crate::context::ContextManager;
+use crate::export::{ExportError, ExportOptions};
+use crate::features::{Feature, FeatureList};
+use crate::git;
+
+/// What `feature/` in a single-feature export holds. The rest of the export
+/// (logs, transcripts, exec-memory, timeline, git log) is the usual filtered
+/// export narrowed to this feature.
+#[derive(Debug, Serialize)]
+pub struct FeaturePacket {
+    pub feature_id: String,
+    pub status: String,
+    /// `passed`, `failed`, or `not checked` in the last verify run.
+    pub last_verify: String,
+    /// Context hints copied into `feature/context/`.
+    pub context: Vec<String>,
+    /// Hints naming entries that don't exist (renamed or never written).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_context: Vec<String>,
+    /// Paths the feature's scope owns in forge.toml; empty if not configured.
+    pub scope_paths: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyOutcome<'a> {
+    command: &'a str,
+    status: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+/// The feature's result from `feedback/last-verify.json`. Only failures
+/// carry their output there.
+fn last_verify(project_dir: &Path, feature_id: &str) -> (&'static str, Option<String>) {
+    let Some(report) = std::fs::read_to_string(project_dir.join("feedback/last-verify.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    else {
+        return ("not checked", None);
+    };
+    let failure = report
+        .get("failures")
+        .and_then(|f| f.as_array())
+        .into_iter()
+        .flatten()
+        .find(|f| f.get("feature_id").and_then(|id| id.as_str()) == Some(feature_id));
+    if let Some(failure) = failure {
+        let output = failure.get("output").and_then(|o| o.as_str()).map(String::from);
+        return ("failed", output);
+    }
+    let passed = report
+        .get("passed")
+        .and_then(|p| p.as_array())
+        .is_some_and(|ids| ids.iter().any(|id| id.as_str() == Some(feature_id)));
+    if passed { ("passed", None) } else { ("not checked", None) }
+}
+
+/// Copy the entries named in `context_hints`, keeping their category dirs.
+fn copy_context_package(
+    project_dir: &Path,
+    feature: &Feature,
+    packet_dir: &Path,
+) -> Result<(Vec<String>, Vec<String>), ExportError> {
+    let ctx = ContextManager::new(project_dir);
+    let mut copied = Vec::new();
+    let mut missing = Vec::new();
+    for hint in &feature.context_hints {
+        let content = hint
+            .split_once('/')
+            .and_then(|(category, slug)| ctx.read_entry(category, slug).ok());
+        match content {
+            Some(content) => {
+                let dst = packet_dir.join("context").join(format!("{hint}.md"));
+                std::fs::create_dir_all(dst.parent().expect("hint has a category"))?;
+                std::fs::write(dst, content)?;
+                copied.push(hint.clone());
+            }
+            None => missing.push(hint.clone()),
+        }
+    }
+    Ok((copied, missing))
+}
+
+/// `git log --stat` limited to the scope's paths.
+fn scope_commits(project_dir: &Path, paths: &[String], commits: usize) -> Option<String> {
+    if paths.is_empty() {
+        return None;
+    }
+    let commits = format!("-{commits}");
+    let mut args = vec!["log", "--stat", "--format=%H %aI %an %s", &commits, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    git::run(project_dir, &args).ok()
+}
+
+/// Write `feature/` for one feature into an export directory.
+pub fn write_feature_packet(
+    project_dir: &Path,
+    output_dir: &Path,
+    feature_id: &str,
+    options: &ExportOptions,
+    redactions: &mut usize,
+) -> Result<FeaturePacket, ExportError> {
+    let list = FeatureList::load(project_dir).map_err(|_| ExportError::UnknownFeatures(feature_id.to_string()))?;
+    let feature = list
+        .features
+        .iter()
+        .find(|f| f.id == feature_id)
+        .ok_or_else(|| ExportError::UnknownFeatures(feature_id.to_string()))?;
+
+    let packet_dir = output_dir.join("feature");
+    std::fs::create_dir_all(&packet_dir)?;
+    std::fs::write(packet_dir.join("feature.json"), serde_json::to_string_pretty(feature)?)?;
+
+    let (status, output) = last_verify(project_dir, feature_id);
+    let output = output.map(|o| {
+        let (clean, count) = options.redactor.redact(&o);
+        *redactions += count;
+        clean.into_owned()
+    });
+    let outcome = VerifyOutcome {
+        command: &feature.verify,
+        status,
+        output,
+    };
+    std::fs::write(packet_dir.join("verify.json"), serde_json::to_string_pretty(&outcome)?)?;
+
+    let (context, missing_context) = copy_context_package(project_dir, feature, &packet_dir)?;
+
+    let scope_paths = ForgeConfig::load(project_dir)
+        .ok()
+        .and_then(|c| c.scope_owns(&feature.scope))
+        .unwrap_or_default();
+    if let Some(log) = scope_commits(project_dir, &scope_paths, options.git_commits) {
+        let (clean, count) = options.redactor.redact(&log);
+        *redactions += count;
+        std::fs::write(packet_dir.join("scope-commits.txt"), clean.as_bytes())?;
+    }
+
+    let packet = FeaturePacket {
+        feature_id: feature_id.to_string(),
+        status: serde_json::to_value(&feature.status)?
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        last_verify: status.to_string(),
+        context,
+        missing_context,
+        scope_paths,
+    };
+    std::fs::write(packet_dir.join("packet.json"), serde_json::to_string_pretty(&packet)?)?;
+    Ok(packet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::{VerifyReport, VerifyResult};
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let features = r#"{"features":[{"id":"f001","type":"implement","scope":"db","description":"Pool",
+            "verify":"./scripts/verify/f001.sh","status":"done","context_hints":["decisions/pool","gotchas/gone"]}]}"#;
+        std::fs::write(dir.path().join("features.json"), features).unwrap();
+        ContextManager::new(dir.path()).write_entry("decisions", "pool", "# Pool\nBounded at 10.\n").unwrap();
+        let failed = VerifyResult {
+            feature_id: "f001".into(),
+            passed: false,
+            output: "auth failed for sk-ant-REDACTED".into(),
+        };
+        VerifyReport::from_results(&[failed]).write(dir.path()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn writes_the_feature_its_verify_result_and_context() {
+        let dir = project();
+        let out = dir.path().join("export");
+        let (options, mut redactions) = (ExportOptions::default(), 0);
+        let packet = write_feature_packet(dir.path(), &out, "f001", &options, &mut redactions).unwrap();
+        assert_eq!(packet.status, "done");
+        assert_eq!(packet.last_verify, "failed");
+        assert_eq!(packet.context, ["decisions/pool"]);
+        assert_eq!(packet.missing_context, ["gotchas/gone"]);
+        assert!(packet.scope_paths.is_empty());
+        assert_eq!(redactions, 1);
+
+        let verify = std::fs::read_to_string(out.join("feature/verify.json")).unwrap();
+        assert!(verify.contains("[REDACTED:anthropic-key]") && !verify.contains("sk-ant"), "{verify}");
+        assert!(verify.contains("./scripts/verify/f001.sh"));
+        let context = std::fs::read_to_string(out.join("feature/context/decisions/pool.md")).unwrap();
+        assert!(context.contains("Bounded at 10."));
+        let feature = std::fs::read_to_string(out.join("feature/feature.json")).unwrap();
+        assert!(feature.contains("\"id\": \"f001\""));
+        assert!(out.join("feature/packet.json").exists());
+        assert!(!out.join("feature/scope-commits.txt").exists(), "no scope paths configured");
+    }
+
+    #[test]
+    fn unknown_feature_is_an_error() {
+        let dir = project();
+        let out = dir.path().join("export");
+        let err = write_feature_packet(dir.path(), &out, "f404", &ExportOptions::default(), &mut 0).unwrap_err();
+        assert!(matches!(err, ExportError::UnknownFeatures(id) if id == "f404"));
+        assert!(!out.exists());
+    }
+}
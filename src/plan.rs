@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+
+use crate::audit::{self, Action};
+use crate::config::RoleSpec;
+use crate::design;
+use crate::doctor;
+use crate::features::{FeatureError, FeatureList, FeatureStatus};
+use crate::pr::headline;
+use crate::runner::{self, RunConfig};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlanError {
+    #[error("no DESIGN.md to plan from (`forge init` writes a skeleton)")]
+    NoDesign,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to start the agent: {0}")]
+    Spawn(std::io::Error),
+    #[error("the agent proposed no feature list")]
+    NoProposal,
+    #[error("the proposed features.json doesn't parse: {0}")]
+    Invalid(serde_json::Error),
+}
+
+/// The list `forge plan` or `forge adjust` got back, kept here until it's
+/// approved.
+pub fn proposal_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/plan/features.json")
+}
+
+const PLAN_PROMPT: &str = "You are a forge planning agent. Follow the forge-planning skill. This session is headless: \
+     there is no user to discuss the design with, so take DESIGN.md as approved and decide open questions yourself. \
+     Generate the features for the phase DESIGN.md is in, with their verify scripts under scripts/verify/. \
+     Write the complete feature list to .forge/plan/features.json, not features.json; \
+     it is saved there once approved. Then exit.";
+
+const ADJUST_PROMPT: &str = "You are a forge adjusting agent. Follow the forge-adjusting skill. This session is \
+     headless: the user's instruction below is what they want changed, so apply it without asking. \
+     Write the complete revised feature list, unchanged features included, to .forge/plan/features.json, \
+     not features.json; it is saved there once accepted. Then exit.";
+
+/// What an agent came back with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+    pub before: FeatureList,
+    pub after: FeatureList,
+    /// Why the list shouldn't be saved as it is.
+    pub problems: Vec<String>,
+}
+
+/// Run the planning role headless against DESIGN.md and read back the
+/// list it proposes.
+pub fn propose(config: &RunConfig, role: &RoleSpec, on_line: &mut dyn FnMut(&str)) -> Result<Proposal, PlanError> {
+    let design = std::fs::read_to_string(config.project_dir.join("DESIGN.md")).map_err(|_| PlanError::NoDesign)?;
+    let mut prompt = PLAN_PROMPT.to_string();
+    if design.contains(design::TODO) {
+        prompt.push_str(" DESIGN.md still has TODOs: fill them where you can instead of stopping at Phase 0.");
+    }
+    run_for_proposal(config, role, "planning", "planner", &prompt, on_line)
+}
+
+/// Run the adjusting role headless on `instruction` ("cut scope on
+/// reporting, add an OAuth milestone") and read back the revised list.
+pub fn adjust(
+    config: &RunConfig,
+    role: &RoleSpec,
+    instruction: &str,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<Proposal, PlanError> {
+    let prompt = format!("{ADJUST_PROMPT}\n\nInstruction: {}", instruction.trim());
+    run_for_proposal(config, role, "adjusting", "adjuster", &prompt, on_line)
+}
+
+/// features.json is left as it was: an agent that writes there anyway has
+/// it put back, and what it wrote taken as the proposal.
+fn run_for_proposal(
+    config: &RunConfig,
+    role: &RoleSpec,
+    kind: &str,
+    agent_id: &str,
+    prompt: &str,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<Proposal, PlanError> {
+    let project_dir = config.project_dir.as_path();
+    let features = project_dir.join("features.json");
+    let staged = proposal_path(project_dir);
+    let original = std::fs::read(&features).ok();
+    if staged.exists() {
+        std::fs::remove_file(&staged)?;
+    }
+    std::fs::create_dir_all(staged.parent().expect("under .forge/plan"))?;
+
+    runner::run_one_off(config, role, kind, prompt, agent_id, on_line).map_err(PlanError::Spawn)?;
+
+    let written = std::fs::read(&features).ok();
+    if written != original {
+        if let Some(written) = &written
+            && !staged.exists()
+        {
+            std::fs::write(&staged, written)?;
+        }
+        match &original {
+            Some(original) => std::fs::write(&features, original)?,
+            None => std::fs::remove_file(&features)?,
+        }
+    }
+
+    let text = std::fs::read_to_string(&staged).map_err(|_| PlanError::NoProposal)?;
+    let after: FeatureList = serde_json::from_str(&text).map_err(PlanError::Invalid)?;
+    let before = FeatureList::load(project_dir).unwrap_or(FeatureList { features: vec![] });
+    let problems = problems(project_dir, &before, &after);
+    Ok(Proposal { before, after, problems })
+}
+
+/// doctor's complaints about the new list, verify scripts that don't
+/// exist, and done features changed or dropped: they're verified and
+/// committed, and may be depended on.
+fn problems(project_dir: &Path, before: &FeatureList, list: &FeatureList) -> Vec<String> {
+    let mut problems = doctor::feature_problems(list);
+    if list.features.is_empty() {
+        problems.push("no features".to_string());
+    }
+    let done = |id: &str| before.features.iter().any(|f| f.id == id && f.status == FeatureStatus::Done);
+    for (id, action, _) in audit::diff(before, list) {
+        match action {
+            Action::Removed if done(&id) => problems.push(format!("removes {id}, which is done")),
+            Action::Updated if done(&id) => problems.push(format!("changes {id}, which is done")),
+            _ => {}
+        }
+    }
+    for feature in &list.features {
+        let script = feature.verify.split_whitespace().find(|word| word.contains("scripts/verify/"));
+        if feature.verify.trim().is_empty() {
+            problems.push(format!("{} has no verify command", feature.id));
+        } else if let Some(script) = script
+            && !project_dir.join(script).exists()
+        {
+            problems.push(format!("{}'s verify script {script} doesn't exist", feature.id));
+        }
+    }
+    problems
+}
+
+impl Proposal {
+    /// One line per feature added, removed or changed, `+`, `-` or `~`
+    /// first.
+    pub fn diff(&self) -> Vec<String> {
+        let described = |list: &FeatureList, id: &str| {
+            list.features.iter().find(|f| f.id == id).map(|f| headline(&f.description)).unwrap_or_default()
+        };
+        audit::diff(&self.before, &self.after)
+            .into_iter()
+            .map(|(id, action, changes)| match action {
+                Action::Added => format!("+ {id}  {}", described(&self.after, &id)),
+                Action::Removed => format!("- {id}  {}", described(&self.before, &id)),
+                Action::Updated => {
+                    let fields: Vec<&str> = changes.keys().map(String::as_str).collect();
+                    format!("~ {id}  {}", fields.join(", "))
+                }
+            })
+            .collect()
+    }
+
+    /// Save the list over features.json, audited like any other save.
+    pub fn apply(&self, project_dir: &Path) -> Result<(), FeatureError> {
+        self.after.save(project_dir)?;
+        let _ = std::fs::remove_file(proposal_path(project_dir));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::{BackendDef, GitConfig, Permissions, RateLimitConfig, RunawayConfig};
+
+    #[test]
+    fn proposes_without_touching_features_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let old = r#"{"features":[{"id":"f001","type":"implement","scope":"a","description":"old","verify":"true"}]}"#;
+        std::fs::write(root.join("features.json"), old).unwrap();
+        // Writes features.json, as the skill would without the prompt's say-so
+        let new = r#"{"features":[{"id":"f002","type":"implement","scope":"a","description":"Parse input\nmore",
+                     "verify":"./scripts/verify/f002.sh","depends_on":["f009"]}]}"#;
+        let def = BackendDef {
+            command: Some("sh".into()),
+            headless_args: vec!["-c".into(), format!("echo planning; printf '%s' '{new}' > features.json")],
+            interactive_args: vec![],
+            model_flag: None,
+            max_turns_flag: None,
+            temperature_flag: None,
+            max_output_tokens_flag: None,
+            budget_flag: None,
+            env: BTreeMap::new(),
+        };
+        let config = RunConfig {
+            project_dir: root.to_path_buf(),
+            protocol: RoleSpec::default(),
+            orchestrating: RoleSpec::default(),
+            protocol_b: None,
+            max_sessions: 1,
+            num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::from([("fake".to_string(), def)]),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
+        };
+        let role = RoleSpec { backend: "fake".into(), ..RoleSpec::default() };
+        assert!(matches!(propose(&config, &role, &mut |_| {}), Err(PlanError::NoDesign)));
+
+        std::fs::write(root.join("DESIGN.md"), "# x\n").unwrap();
+        let mut lines = Vec::new();
+        let proposal = propose(&config, &role, &mut |line| lines.push(line.to_string())).unwrap();
+        assert_eq!(lines, ["planning"]);
+        assert_eq!(std::fs::read_to_string(root.join("features.json")).unwrap(), old, "put back until approved");
+        assert_eq!(proposal.diff(), ["+ f002  Parse input", "- f001  old"]);
+        assert_eq!(
+            proposal.problems,
+            ["unknown dependencies f002 -> f009", "f002's verify script ./scripts/verify/f002.sh doesn't exist"]
+        );
+
+        let done = old.replace(r#""verify""#, r#""status":"done","verify""#);
+        let done: FeatureList = serde_json::from_str(&done).unwrap();
+        assert_eq!(problems(root, &done, &proposal.after)[1], "removes f001, which is done");
+
+        proposal.apply(root).unwrap();
+        assert_eq!(FeatureList::load(root).unwrap().features[0].id, "f002");
+        assert!(!proposal_path(root).exists());
+    }
+}
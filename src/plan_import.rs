@@ -0,0 +1,250 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::features::{Feature, FeatureList, FeatureStatus, FeatureType};
+
+/// `- [ ] text`, `* [x] text`, `1. [ ] text`; the indent says what it's under.
+static CHECKBOX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)(?:[-*+]|\d+[.)])\s+\[([ xX])\]\s+(.+)$").expect("valid regex"));
+static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^#{1,6}\s+(.+?)\s*#*\s*$").expect("valid regex"));
+/// A heading that names its milestone already: `M2: Storage`, `M3.5 - search`.
+static LABELED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(M\d[A-Za-z0-9.-]*)\s*[:—–-]?\s*(.*)$").expect("valid regex"));
+
+/// A checklist item from a plan document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanItem {
+    /// The item's text, then any lines continuing it.
+    pub text: String,
+    pub done: bool,
+    /// Index of the item it's nested under.
+    pub parent: Option<usize>,
+    /// Index of the heading it's under.
+    pub milestone: Option<usize>,
+}
+
+/// Checklist items and the headings they sit under.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub headings: Vec<String>,
+    pub items: Vec<PlanItem>,
+}
+
+/// Read the checklist items out of a markdown plan. Indented text under an
+/// item that isn't a list item itself continues its description.
+pub fn parse(markdown: &str) -> Plan {
+    let mut plan = Plan::default();
+    // (indent, item index) of the items enclosing the current line
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if let Some(caps) = HEADING.captures(line) {
+            plan.headings.push(caps[1].to_string());
+            open.clear();
+        } else if let Some(caps) = CHECKBOX.captures(line) {
+            let indent = caps[1].replace('\t', "    ").len();
+            while open.last().is_some_and(|&(i, _)| i >= indent) {
+                open.pop();
+            }
+            plan.items.push(PlanItem {
+                text: caps[3].trim().to_string(),
+                done: &caps[2] != " ",
+                parent: open.last().map(|&(_, item)| item),
+                milestone: plan.headings.len().checked_sub(1),
+            });
+            open.push((indent, plan.items.len() - 1));
+        } else if line.trim().is_empty() || !line.starts_with([' ', '\t']) {
+            if !line.trim().is_empty() {
+                open.clear();
+            }
+        } else if let Some(&(indent, item)) = open.last()
+            && line.len() - line.trim_start().len() > indent
+            && !line.trim_start().starts_with(['-', '*', '+'])
+        {
+            let text = &mut plan.items[item].text;
+            text.push('\n');
+            text.push_str(line.trim());
+        }
+    }
+    plan
+}
+
+/// The review feature's description for a heading: its own `M<n>` label if
+/// it has one, else `M<number>` by position.
+fn review_description(heading: &str, number: usize) -> String {
+    match LABELED.captures(heading) {
+        Some(caps) if !caps[2].is_empty() => format!("{} review: {}", &caps[1], &caps[2]),
+        Some(caps) => format!("{} review", &caps[1]),
+        None => format!("M{number} review: {heading}"),
+    }
+}
+
+/// Features for a plan, to append to `list`. Each item becomes an implement
+/// feature that depends on the items nested under it. Each heading with items
+/// becomes a review feature that depends on its items; the next heading's
+/// items depend on that review. Items already in `list` (same first line)
+/// are reused, not added again.
+pub fn to_features(plan: &Plan, list: &FeatureList, scope: &str) -> Vec<Feature> {
+    let mut all = list.clone();
+    let existing = |description: &str| {
+        let first = description.lines().next();
+        list.features.iter().find(|f| f.description.lines().next() == first).map(|f| f.id.clone())
+    };
+    // Ids first, in document order, so dependencies can name them
+    let mut reserve = |feature_type: FeatureType, description: &str| -> (String, bool) {
+        if let Some(id) = existing(description) {
+            return (id, false);
+        }
+        let id = all.next_id(&feature_type);
+        all.features.push(Feature {
+            id: id.clone(),
+            feature_type,
+            scope: scope.to_string(),
+            description: description.to_string(),
+            verify: format!("./scripts/verify/{id}.sh"),
+            depends_on: vec![],
+            priority: 1,
+            status: FeatureStatus::Pending,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        });
+        (id, true)
+    };
+
+    // Groups in document order: items before any heading, then each heading
+    let mut groups: Vec<Option<usize>> = Vec::new();
+    for item in &plan.items {
+        if !groups.contains(&item.milestone) {
+            groups.push(item.milestone);
+        }
+    }
+    let ids: Vec<(String, bool)> = plan.items.iter().map(|i| reserve(FeatureType::Implement, &i.text)).collect();
+    let mut reviews = Vec::new();
+    let mut number = 0;
+    for group in &groups {
+        if let Some(h) = group {
+            number += 1;
+            reviews.push(Some(reserve(FeatureType::Review, &review_description(&plan.headings[*h], number))));
+        } else {
+            reviews.push(None);
+        }
+    }
+
+    let new = |id: &str, feature_type: FeatureType, depends_on: Vec<String>, done: bool, priority: usize| {
+        let template = all.features.iter().find(|f| f.id == id).expect("reserved above");
+        Feature {
+            feature_type,
+            depends_on,
+            priority: priority as u32,
+            status: if done { FeatureStatus::Done } else { FeatureStatus::Pending },
+            ..template.clone()
+        }
+    };
+    let mut added = Vec::new();
+    let mut previous_review: Option<&String> = None;
+    for (rank, group) in groups.iter().enumerate() {
+        let members: Vec<usize> = (0..plan.items.len()).filter(|&i| plan.items[i].milestone == *group).collect();
+        for &i in &members {
+            let children: Vec<String> =
+                members.iter().filter(|&&c| plan.items[c].parent == Some(i)).map(|&c| ids[c].0.clone()).collect();
+            let depends_on =
+                if children.is_empty() { previous_review.into_iter().cloned().collect() } else { children };
+            if ids[i].1 {
+                added.push(new(&ids[i].0, FeatureType::Implement, depends_on, plan.items[i].done, rank + 1));
+            }
+        }
+        if let Some((review, is_new)) = &reviews[rank] {
+            if *is_new {
+                let top =
+                    members.iter().filter(|&&i| plan.items[i].parent.is_none()).map(|&i| ids[i].0.clone()).collect();
+                let done = members.iter().all(|&i| plan.items[i].done);
+                added.push(new(review, FeatureType::Review, top, done, rank + 1));
+            }
+            previous_review = Some(review);
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "\
+# Orders plan
+
+Intro text.
+
+## M1: Storage
+
+- [x] Schema for orders
+- [ ] Query layer
+  Supports pagination.
+  - [ ] Filter by customer
+  - [ ] Sort by date
+
+## Payments
+
+1. [ ] Stripe client
+```
+- [ ] not an item
+```
+";
+
+    #[test]
+    fn parses_items_nesting_and_headings() {
+        let plan = parse(PLAN);
+        assert_eq!(plan.headings, ["Orders plan", "M1: Storage", "Payments"]);
+        let items: Vec<(&str, bool, Option<usize>, Option<usize>)> =
+            plan.items.iter().map(|i| (i.text.as_str(), i.done, i.parent, i.milestone)).collect();
+        assert_eq!(
+            items,
+            [
+                ("Schema for orders", true, None, Some(1)),
+                ("Query layer\nSupports pagination.", false, None, Some(1)),
+                ("Filter by customer", false, Some(1), Some(1)),
+                ("Sort by date", false, Some(1), Some(1)),
+                ("Stripe client", false, None, Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_features_with_milestones_and_dependencies() {
+        let list = FeatureList { features: vec![] };
+        let features = to_features(&parse(PLAN), &list, "core");
+        let summary: Vec<(&str, &str, Vec<&str>)> = features
+            .iter()
+            .map(|f| {
+                let deps = f.depends_on.iter().map(String::as_str).collect();
+                (f.id.as_str(), f.description.lines().next().unwrap(), deps)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("f001", "Schema for orders", vec![]),
+                ("f002", "Query layer", vec!["f003", "f004"]),
+                ("f003", "Filter by customer", vec![]),
+                ("f004", "Sort by date", vec![]),
+                ("r001", "M1 review: Storage", vec!["f001", "f002"]),
+                ("f005", "Stripe client", vec!["r001"]),
+                ("r002", "M2 review: Payments", vec!["f005"]),
+            ]
+        );
+        assert_eq!(features[0].status, FeatureStatus::Done);
+        assert_eq!(FeatureList::milestone_label(&features[6]), "M2");
+
+        // Importing again adds nothing
+        let list = FeatureList { features };
+        assert!(to_features(&parse(PLAN), &list, "core").is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::config::ForgeConfig;
+use crate::events::Event;
+
+/// `forge foo` runs `forge-foo` when forge has no `foo` of its own.
+pub const PREFIX: &str = "forge-";
+
+/// How long a hook gets before it's killed; the run waits on it.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A `forge-<name>` executable found on PATH.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+/// Every plugin on `path` (a PATH value), sorted by name. An earlier
+/// directory shadows a later one, as it does for the shell.
+fn discover_in(path: &std::ffi::OsStr) -> Vec<Plugin> {
+    let mut plugins: Vec<Plugin> = Vec::new();
+    for dir in std::env::split_paths(path) {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(name) = file_name.strip_prefix(PREFIX) else { continue };
+            if name.is_empty() || plugins.iter().any(|p| p.name == name) || !is_executable(&entry.path()) {
+                continue;
+            }
+            plugins.push(Plugin { name: name.to_string(), path: entry.path() });
+        }
+    }
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+/// Every plugin on PATH.
+pub fn discover() -> Vec<Plugin> {
+    std::env::var_os("PATH").map(|path| discover_in(&path)).unwrap_or_default()
+}
+
+pub fn find(name: &str) -> Option<Plugin> {
+    discover().into_iter().find(|p| p.name == name)
+}
+
+/// What a plugin reads on stdin, with `extra` merged in: which forge, and
+/// which project.
+fn context(project_dir: &Path, extra: Value) -> Value {
+    let project = ForgeConfig::load(project_dir).map(|c| c.project.name).unwrap_or_default();
+    let mut context = json!({
+        "forge_version": env!("CARGO_PKG_VERSION"),
+        "project_dir": project_dir.display().to_string(),
+        "project": project,
+    });
+    if let (Some(context), Value::Object(extra)) = (context.as_object_mut(), extra) {
+        context.extend(extra);
+    }
+    context
+}
+
+fn command(plugin: &Plugin, project_dir: &Path) -> Command {
+    let mut command = Command::new(&plugin.path);
+    command
+        .current_dir(project_dir)
+        .env("FORGE_PROJECT_DIR", project_dir)
+        .env("FORGE_VERSION", env!("CARGO_PKG_VERSION"))
+        .stdin(Stdio::piped());
+    command
+}
+
+/// Run `plugin` as a subcommand: `args` are passed through, the terminal
+/// is its own, and the project's context is on stdin as JSON.
+pub fn run(project_dir: &Path, plugin: &Plugin, args: &[String]) -> std::io::Result<ExitStatus> {
+    let mut child = command(plugin, project_dir).args(args).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that never reads stdin closes it early; that's fine
+        let _ = writeln!(stdin, "{}", context(project_dir, json!({ "args": args })));
+    }
+    child.wait()
+}
+
+/// Whether a plugin's `events` list takes `event`; `*` takes every one.
+fn subscribed(events: &[String], event: &Value) -> bool {
+    let name = event.get("event").and_then(Value::as_str).unwrap_or_default();
+    events.iter().any(|e| e == "*" || e == name)
+}
+
+/// Run `forge-<name> hook` for each `[plugins.<name>]` whose `events` take
+/// `event`, with it on stdin and its name in FORGE_EVENT. Failures are
+/// printed; a run never stops over a plugin.
+pub fn on_event(project_dir: &Path, event: &Event) {
+    let Ok(config) = ForgeConfig::load(project_dir) else {
+        return;
+    };
+    if config.plugins.is_empty() {
+        return;
+    }
+    let Ok(value) = serde_json::to_value(event) else {
+        return;
+    };
+    let name = value.get("event").and_then(Value::as_str).unwrap_or_default().to_string();
+    let installed = discover();
+    for (plugin_name, plugin_config) in &config.plugins {
+        if !subscribed(&plugin_config.events, &value) {
+            continue;
+        }
+        let Some(plugin) = installed.iter().find(|p| &p.name == plugin_name) else {
+            warn!(plugin = %plugin_name, "no {PREFIX}{plugin_name} on PATH for [plugins.{plugin_name}]");
+            continue;
+        };
+        if let Err(e) = hook(project_dir, plugin, &name, &value) {
+            warn!(plugin = %plugin_name, event = %name, "plugin hook failed: {e}");
+        }
+    }
+}
+
+fn hook(project_dir: &Path, plugin: &Plugin, name: &str, event: &Value) -> Result<(), String> {
+    let mut child = command(plugin, project_dir)
+        .arg("hook")
+        .env("FORGE_EVENT", name)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "{}", context(project_dir, json!({ "event": event })));
+    }
+    let deadline = Instant::now() + HOOK_TIMEOUT;
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => {
+                let code = status.code().map_or_else(|| "on a signal".to_string(), |c| c.to_string());
+                return Err(format!("exited {code}"));
+            }
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("killed after {}s", HOOK_TIMEOUT.as_secs()));
+            }
+            None => thread::sleep(Duration::from_millis(20)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventKind;
+
+    #[test]
+    fn discovers_plugins_and_hooks_subscribed_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let (first, second) = (root.join("bin1"), root.join("bin2"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        let script = |path: PathBuf, body: &str| {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        };
+        script(first.join("forge-lint"), r#"cat > "$FORGE_PROJECT_DIR/$1-$FORGE_EVENT.json""#);
+        script(second.join("forge-lint"), "exit 1");
+        script(second.join("forge-stats"), "exit 3");
+        std::fs::write(second.join("forge-notes"), "not executable").unwrap();
+
+        let path = std::env::join_paths([&first, &second]).unwrap();
+        let plugins = discover_in(&path);
+        let found: Vec<(&str, &Path)> = plugins.iter().map(|p| (p.name.as_str(), p.path.as_path())).collect();
+        assert_eq!(found, [("lint", first.join("forge-lint").as_path()), ("stats", &second.join("forge-stats"))]);
+
+        let status = run(root, &plugins[1], &["--since".into(), "1d".into()]).unwrap();
+        assert_eq!(status.code(), Some(3));
+
+        let event = Event {
+            ts: chrono::Utc::now(),
+            agent: "agent-1".into(),
+            kind: EventKind::FeatureBlocked { feature_id: "f001".into(), reason: None },
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert!(subscribed(&["feature_blocked".into()], &value));
+        assert!(subscribed(&["*".into()], &value));
+        assert!(!subscribed(&["session_end".into()], &value));
+
+        hook(root, &plugins[0], "feature_blocked", &value).unwrap();
+        let got: Value =
+            serde_json::from_str(&std::fs::read_to_string(root.join("hook-feature_blocked.json")).unwrap()).unwrap();
+        assert_eq!(got["event"]["feature_id"], "f001");
+        assert_eq!(got["project_dir"], root.display().to_string());
+        assert_eq!(hook(root, &plugins[1], "feature_blocked", &value), Err("exited 3".into()));
+    }
+}
@@ -0,0 +1,287 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::config::{GitConfig, GitWorkflow, PrUnit};
+use crate::events::{self, EventKind};
+use crate::features::{Feature, FeatureList, FeatureStatus, FeatureType};
+use crate::git;
+use crate::verify::VerifyResult;
+
+/// Lines of verify output quoted in a PR body.
+const VERIFY_TAIL_LINES: usize = 20;
+
+/// Branch a feature's pull request is opened from.
+pub fn feature_branch(feature_id: &str) -> String {
+    format!("forge/{feature_id}")
+}
+
+/// Branch a milestone's pull request is opened from, e.g. `forge/m2-pg`.
+pub fn milestone_branch(label: &str) -> String {
+    format!("forge/{}", label.to_lowercase())
+}
+
+/// One line of a description: the first, cut to fit a title.
+pub(crate) fn headline(description: &str) -> String {
+    let line = description.lines().next().unwrap_or("").trim();
+    if line.chars().count() > 72 {
+        let cut: String = line.chars().take(71).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+/// The `delivery` proof from `feedback/exec-memory/<id>.json`, one bullet
+/// per requirement.
+fn delivery_lines(project_dir: &Path, feature_id: &str) -> Vec<String> {
+    let path = project_dir.join(format!("feedback/exec-memory/{feature_id}.json"));
+    let Some(data) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+    else {
+        return Vec::new();
+    };
+    let text = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    data.get("delivery")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|entry| {
+            let mut line = format!("- {}", text(entry, "requirement"));
+            for (key, label) in [("implemented_in", "implemented in"), ("tested_by", "tested by"), ("verified_by", "verified by")] {
+                let value = text(entry, key);
+                if !value.is_empty() {
+                    let _ = write!(line, "\n  - {label} `{value}`");
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+/// One feature's part of a PR body: what was asked, the agent's delivery
+/// proof, and how verify went.
+pub fn feature_section(project_dir: &Path, feature: &Feature, verify: Option<&VerifyResult>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "## {}: {}\n", feature.id, headline(&feature.description));
+    let _ = writeln!(out, "Scope: `{}` · Type: {:?}\n", feature.scope, feature.feature_type);
+    if feature.description.lines().count() > 1 {
+        let _ = writeln!(out, "{}\n", feature.description.trim());
+    }
+
+    let delivery = delivery_lines(project_dir, &feature.id);
+    let _ = writeln!(out, "### Delivery");
+    if delivery.is_empty() {
+        let _ = writeln!(out, "_No delivery proof in feedback/exec-memory/{}.json._", feature.id);
+    } else {
+        let _ = writeln!(out, "{}", delivery.join("\n"));
+    }
+
+    let _ = writeln!(out, "\n### Verify");
+    match verify {
+        None => {
+            let _ = writeln!(out, "_Not run._");
+        }
+        Some(result) => {
+            let status = if result.passed { "PASS" } else { "FAIL" };
+            let _ = writeln!(out, "**{status}** `{}`", feature.verify);
+            let lines: Vec<&str> = result.output.trim_end().lines().collect();
+            if !lines.is_empty() {
+                let tail = &lines[lines.len().saturating_sub(VERIFY_TAIL_LINES)..];
+                let _ = writeln!(out, "\n<details><summary>Output</summary>\n\n```\n{}\n```\n</details>", tail.join("\n"));
+            }
+        }
+    }
+    out
+}
+
+fn footer(out: &mut String) {
+    let _ = writeln!(out, "\n---\n_Opened by forge._");
+}
+
+/// Title and body for one feature's pull request.
+pub fn feature_pr(project_dir: &Path, feature: &Feature, verify: Option<&VerifyResult>) -> (String, String) {
+    let title = format!("{}: {}", feature.id, headline(&feature.description));
+    let mut body = feature_section(project_dir, feature, verify);
+    footer(&mut body);
+    (title, body)
+}
+
+/// A milestone: the review feature and everything it depends on, directly
+/// or not, in features.json order.
+pub fn milestone_features<'a>(list: &'a FeatureList, review: &Feature) -> Vec<&'a Feature> {
+    let mut members = BTreeSet::new();
+    let mut stack = vec![review.id.as_str()];
+    while let Some(id) = stack.pop() {
+        if members.insert(id.to_string())
+            && let Some(feature) = list.features.iter().find(|f| f.id == id)
+        {
+            stack.extend(feature.depends_on.iter().map(String::as_str));
+        }
+    }
+    list.features.iter().filter(|f| members.contains(&f.id)).collect()
+}
+
+/// Title and body for a milestone's pull request, covering its
+/// `milestone_features`.
+pub fn milestone_pr(project_dir: &Path, list: &FeatureList, review: &Feature, results: &[VerifyResult]) -> (String, String) {
+    let label = FeatureList::milestone_label(review);
+    let title = format!("{label}: {}", headline(&review.description));
+    let features = milestone_features(list, review);
+
+    let mut body = String::new();
+    let _ = writeln!(body, "Milestone **{label}**, {} features:\n", features.len());
+    for feature in &features {
+        let _ = writeln!(body, "- `{}` {}", feature.id, headline(&feature.description));
+    }
+    for feature in features {
+        let verify = results.iter().find(|r| r.feature_id == feature.id);
+        let _ = writeln!(body);
+        body.push_str(&feature_section(project_dir, feature, verify));
+    }
+    footer(&mut body);
+    (title, body)
+}
+
+/// With `workflow = "pr"`, push and open a pull request for each finished
+/// unit from this session. `heads` are the session's features and the
+/// revision holding each one's work; milestones use the project's HEAD.
+/// Only features that are done and passed verify go out. Nothing here
+/// fails the run: problems are printed and recorded as events.
+pub fn publish(project_dir: &Path, config: &GitConfig, heads: &[(String, String)], results: &[VerifyResult]) {
+    if config.workflow != GitWorkflow::Pr || heads.is_empty() {
+        return;
+    }
+    if !git::has_remote(project_dir) {
+        warn!("workflow = \"pr\" but the repo has no remote; not opening pull requests.");
+        return;
+    }
+    let Ok(list) = FeatureList::load(project_dir) else {
+        return;
+    };
+    let Some(base) = config.pr_base.clone().or_else(|| git::current_branch(project_dir)) else {
+        warn!("Detached HEAD and no `pr_base`; not opening pull requests.");
+        return;
+    };
+    let passed = |id: &str| results.iter().any(|r| r.feature_id == id && r.passed);
+    let finished = heads.iter().filter_map(|(id, rev)| {
+        let feature = list.features.iter().find(|f| f.id == *id)?;
+        (feature.status == FeatureStatus::Done && passed(id)).then_some((feature, rev.as_str()))
+    });
+
+    for (feature, rev) in finished {
+        let (branch, title, body, features) = match config.pr_per {
+            PrUnit::Feature => {
+                let (title, body) = feature_pr(project_dir, feature, results.iter().find(|r| r.feature_id == feature.id));
+                (feature_branch(&feature.id), title, body, vec![feature.id.clone()])
+            }
+            PrUnit::Milestone if feature.feature_type == FeatureType::Review => {
+                let (title, body) = milestone_pr(project_dir, &list, feature, results);
+                (milestone_branch(&FeatureList::milestone_label(feature)), title, body, vec![feature.id.clone()])
+            }
+            PrUnit::Milestone => continue,
+        };
+        let rev = if config.pr_per == PrUnit::Milestone { "HEAD" } else { rev };
+        let result = git::set_branch(project_dir, &branch, rev)
+            .and_then(|()| git::push_branch(project_dir, &branch))
+            .and_then(|()| git::open_pr(project_dir, &branch, &base, &title, &body, config.pr_draft));
+        match &result {
+            Ok(url) => info!("Pull request for {branch}: {url}"),
+            Err(e) => warn!("Pull request for {branch} failed (non-fatal): {e}"),
+        }
+        events::emit(
+            project_dir,
+            events::FORGE,
+            EventKind::PullRequest {
+                branch,
+                features,
+                url: result.as_ref().ok().cloned(),
+                error: result.err(),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, feature_type: FeatureType, description: &str, depends_on: &[&str]) -> Feature {
+        Feature {
+            id: id.into(),
+            feature_type,
+            scope: "core".into(),
+            description: description.into(),
+            verify: format!("scripts/verify/{id}.sh"),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            priority: 1,
+            status: FeatureStatus::Done,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    fn passed(id: &str, output: &str) -> VerifyResult {
+        VerifyResult { feature_id: id.into(), passed: true, output: output.into() }
+    }
+
+    #[test]
+    fn feature_body_has_delivery_and_verify() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("feedback/exec-memory")).unwrap();
+        std::fs::write(
+            tmp.path().join("feedback/exec-memory/f001.json"),
+            r#"{"feature_id": "f001", "delivery": [{
+                "requirement": "Rejects bad versions",
+                "implemented_in": "src/parser.rs:42-85",
+                "tested_by": "parse_rejects_invalid_version",
+                "verified_by": ""
+            }]}"#,
+        )
+        .unwrap();
+        let f001 = feature("f001", FeatureType::Implement, "Version parser", &[]);
+        let output: String = (1..=30).map(|n| format!("line {n}\n")).collect();
+        let (title, body) = feature_pr(tmp.path(), &f001, Some(&passed("f001", &output)));
+        assert_eq!(title, "f001: Version parser");
+        assert!(body.contains("- Rejects bad versions\n  - implemented in `src/parser.rs:42-85`\n  - tested by `parse_rejects_invalid_version`\n"), "{body}");
+        assert!(!body.contains("verified by"), "{body}");
+        assert!(body.contains("**PASS** `scripts/verify/f001.sh`"), "{body}");
+        assert!(body.contains("line 11\n") && !body.contains("line 10\n"), "only the tail: {body}");
+
+        let f002 = feature("f002", FeatureType::Implement, "No proof", &[]);
+        let (_, body) = feature_pr(tmp.path(), &f002, None);
+        assert!(body.contains("_No delivery proof in feedback/exec-memory/f002.json._"), "{body}");
+        assert!(body.contains("_Not run._"), "{body}");
+    }
+
+    #[test]
+    fn milestone_body_covers_dependency_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let list = FeatureList {
+            features: vec![
+                feature("f001", FeatureType::Implement, "Schema", &[]),
+                feature("f002", FeatureType::Implement, "Queries", &["f001"]),
+                feature("f003", FeatureType::Implement, "Unrelated", &[]),
+                feature("f004", FeatureType::Review, "M2-PG review: postgres backend", &["f002"]),
+            ],
+        };
+        let results = [passed("f001", "ok"), passed("f002", "ok")];
+        let (title, body) = milestone_pr(tmp.path(), &list, &list.features[3], &results);
+        assert_eq!(title, "M2-PG: M2-PG review: postgres backend");
+        assert!(body.starts_with("Milestone **M2-PG**, 3 features:\n\n- `f001` Schema\n- `f002` Queries\n"), "{body}");
+        assert!(!body.contains("f003"), "{body}");
+        assert!(body.contains("## f002: Queries"), "{body}");
+        assert_eq!(milestone_branch("M2-PG"), "forge/m2-pg");
+    }
+
+    #[test]
+    fn long_titles_are_cut() {
+        let description = format!("{}\nmore detail", "word ".repeat(30));
+        let title = headline(&description);
+        assert!(title.ends_with('…') && title.chars().count() <= 72, "{title}");
+    }
+}
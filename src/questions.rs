@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::events::{self, EventKind};
+use crate::features::{FeatureError, FeatureList, FeatureStatus};
+
+#[derive(Debug, thiserror::Error)]
+pub enum QuestionError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("no question for {0} (see `forge questions list`)")]
+    NotFound(String),
+    #[error("feedback/questions/{0}.json isn't a valid question: {1}")]
+    Invalid(String, serde_json::Error),
+    #[error("{0}")]
+    Features(#[from] FeatureError),
+}
+
+/// `feedback/questions/<feature_id>.json`: what an agent needs a person to
+/// decide before it can go on. One per feature; asking again replaces it.
+pub fn questions_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join("feedback/questions")
+}
+
+pub fn question_path(project_dir: &Path, feature_id: &str) -> PathBuf {
+    questions_dir(project_dir).join(format!("{feature_id}.json"))
+}
+
+/// An agent writes `question` (and, if it helps, `context` and `options`);
+/// forge stamps `asked_at` when it parks the feature and the rest when
+/// it's answered.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Question {
+    /// From the file name; agents needn't repeat it.
+    #[serde(default, skip_serializing)]
+    pub feature_id: String,
+    pub question: String,
+    /// What the agent found, what it tried, why it can't choose.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// The choices it sees, if it sees any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asked_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answered_at: Option<DateTime<Utc>>,
+}
+
+impl Question {
+    pub fn is_open(&self) -> bool {
+        self.answer.is_none()
+    }
+
+    fn save(&self, project_dir: &Path) -> Result<(), QuestionError> {
+        std::fs::create_dir_all(questions_dir(project_dir))?;
+        let json = serde_json::to_string_pretty(self).map_err(|e| QuestionError::Invalid(self.feature_id.clone(), e))?;
+        std::fs::write(question_path(project_dir, &self.feature_id), json + "\n")?;
+        Ok(())
+    }
+}
+
+pub fn load(project_dir: &Path, feature_id: &str) -> Result<Question, QuestionError> {
+    let text = match std::fs::read_to_string(question_path(project_dir, feature_id)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(QuestionError::NotFound(feature_id.to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut question: Question =
+        serde_json::from_str(&text).map_err(|e| QuestionError::Invalid(feature_id.to_string(), e))?;
+    question.feature_id = feature_id.to_string();
+    Ok(question)
+}
+
+/// Every question that parses, by feature.
+pub fn list(project_dir: &Path) -> Vec<Question> {
+    let mut ids: Vec<String> = std::fs::read_dir(questions_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| Some(e.path().file_name()?.to_str()?.strip_suffix(".json")?.to_string()))
+        .collect();
+    ids.sort();
+    ids.iter().filter_map(|id| load(project_dir, id).ok()).collect()
+}
+
+/// After a session: park each of `feature_ids` that isn't done and has an
+/// open question as waiting, recorded as a `QuestionAsked` event. Returns
+/// the features parked.
+pub fn escalate(project_dir: &Path, feature_ids: &[String]) -> Vec<String> {
+    let Ok(mut features) = FeatureList::load(project_dir) else {
+        return Vec::new();
+    };
+    let mut parked = Vec::new();
+    for id in feature_ids {
+        let Ok(mut question) = load(project_dir, id) else { continue };
+        let status = features.features.iter().find(|f| &f.id == id).map(|f| f.status.clone());
+        if !question.is_open() || matches!(status, None | Some(FeatureStatus::Done | FeatureStatus::Waiting)) {
+            continue;
+        }
+        if features.mark_waiting(id).is_err() {
+            continue;
+        }
+        question.asked_at.get_or_insert_with(Utc::now);
+        if let Err(e) = question.save(project_dir) {
+            warn!("Failed to stamp the question for {id}: {e}");
+        }
+        parked.push((id.clone(), question.question));
+    }
+    if parked.is_empty() {
+        return Vec::new();
+    }
+    if let Err(e) = features.save(project_dir) {
+        warn!("Failed to park features waiting on answers: {e}");
+        return Vec::new();
+    }
+    parked
+        .into_iter()
+        .map(|(feature_id, question)| {
+            info!("{feature_id} is waiting on an answer: {question}");
+            let kind = EventKind::QuestionAsked { feature_id: feature_id.clone(), question };
+            events::emit(project_dir, events::FORGE, kind);
+            feature_id
+        })
+        .collect()
+}
+
+/// `forge questions answer`: record `answer` and put the feature back to
+/// pending if it was waiting, recorded as a `QuestionAnswered` event.
+pub fn answer(project_dir: &Path, feature_id: &str, answer: &str) -> Result<Question, QuestionError> {
+    let mut question = load(project_dir, feature_id)?;
+    question.answer = Some(answer.trim().to_string());
+    question.answered_at = Some(Utc::now());
+    question.save(project_dir)?;
+    let mut features = FeatureList::load(project_dir)?;
+    if features.features.iter().any(|f| f.id == feature_id && f.status == FeatureStatus::Waiting) {
+        features.reopen(feature_id)?;
+        features.save(project_dir)?;
+    }
+    events::emit(project_dir, events::FORGE, EventKind::QuestionAnswered { feature_id: feature_id.to_string() });
+    Ok(question)
+}
+
+/// The answered question on `feature_id`, for its next session's prompt;
+/// "" if there isn't one.
+pub fn prompt_note(project_dir: &Path, feature_id: &str) -> String {
+    let Ok(Question { question, answer: Some(answer), .. }) = load(project_dir, feature_id) else {
+        return String::new();
+    };
+    format!(
+        "\n\nYou asked a person about {feature_id} (feedback/questions/{feature_id}.json):\n> {}\nTheir answer:\n> {}\n\
+         Go with it. If you still need a decision, replace the question and exit again.",
+        question.trim().replace('\n', "\n> "),
+        answer.replace('\n', "\n> "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn questions_park_features_until_answered() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let features = r#"{"features":[
+            {"id":"f001","type":"implement","scope":"a","description":"x","verify":"true","status":"claimed"},
+            {"id":"f002","type":"implement","scope":"a","description":"y","verify":"true","status":"done"}
+        ]}"#;
+        std::fs::write(root.join("features.json"), features).unwrap();
+        std::fs::create_dir_all(questions_dir(root)).unwrap();
+        let asked = r#"{"question": "Postgres or SQLite?", "options": ["postgres", "sqlite"]}"#;
+        std::fs::write(question_path(root, "f001"), asked).unwrap();
+        std::fs::write(question_path(root, "f002"), r#"{"question": "Too late"}"#).unwrap();
+        assert_eq!(prompt_note(root, "f001"), "", "not answered yet");
+
+        let ids = ["f001".to_string(), "f002".to_string()];
+        assert_eq!(escalate(root, &ids), ["f001"], "f002 is already done");
+        assert_eq!(FeatureList::load(root).unwrap().features[0].status, FeatureStatus::Waiting);
+        assert!(escalate(root, &ids).is_empty(), "already waiting");
+        let open = list(root);
+        assert_eq!(open.len(), 2);
+        assert_eq!(open[0].feature_id, "f001");
+        assert!(open[0].is_open() && open[0].asked_at.is_some());
+        assert_eq!(open[0].options, ["postgres", "sqlite"]);
+
+        assert!(matches!(answer(root, "f009", "?"), Err(QuestionError::NotFound(_))));
+        let answered = answer(root, "f001", "SQLite, it ships\nwith the binary\n").unwrap();
+        assert_eq!(answered.answer.as_deref(), Some("SQLite, it ships\nwith the binary"));
+        let features = FeatureList::load(root).unwrap();
+        assert_eq!(features.features[0].status, FeatureStatus::Pending);
+        assert!(features.claimable_ids().contains(&"f001"));
+        let note = prompt_note(root, "f001");
+        assert!(note.contains("> Postgres or SQLite?\nTheir answer:\n> SQLite, it ships\n> with the binary"), "{note}");
+
+        let timeline = events::merged_timeline(root).unwrap();
+        assert!(matches!(&timeline[0].kind, EventKind::QuestionAsked { feature_id, .. } if feature_id == "f001"));
+        assert!(matches!(&timeline[1].kind, EventKind::QuestionAnswered { .. }));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{RateLimitConfig, RoleSpec};
+use crate::events::{self, EventKind};
+
+/// How backends say they're throttled: HTTP 429, Anthropic's
+/// `rate_limit_error` and `overloaded_error`, OpenAI's `rate_limit_exceeded`,
+/// Gemini's `RESOURCE_EXHAUSTED` and Claude's usage limit.
+static RATE_LIMITED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)rate[ _-]?limit(ed|s|_error|_exceeded)?\b|too many requests|\b429\b",
+        r"|overloaded_error|resource_exhausted|usage limit reached|quota exceeded",
+    ))
+    .expect("valid regex")
+});
+
+static RETRY_AFTER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)retry[ _-]?after"?\D{0,4}(\d+)"#).expect("valid regex"));
+
+pub fn is_rate_limited(line: &str) -> bool {
+    RATE_LIMITED.is_match(line)
+}
+
+/// Seconds a `retry-after` in `line` asks for.
+pub fn retry_after(line: &str) -> Option<u64> {
+    RETRY_AFTER.captures(line)?.get(1)?.as_str().parse().ok()
+}
+
+/// Rate-limit errors seen in a session's output.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sighting {
+    pub seen: bool,
+    pub retry_after: Option<u64>,
+}
+
+impl Sighting {
+    pub fn feed(&mut self, line: &str) {
+        self.seen |= is_rate_limited(line);
+        self.retry_after = retry_after(line).or(self.retry_after);
+    }
+
+    pub fn merge(self, other: Sighting) -> Sighting {
+        Sighting { seen: self.seen || other.seen, retry_after: self.retry_after.or(other.retry_after) }
+    }
+}
+
+/// Read `stream` (an agent's stderr) to the end on another thread; what it
+/// saw arrives once it closes.
+pub fn watch(stream: impl Read + Send + 'static) -> mpsc::Receiver<Sighting> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut sighting = Sighting::default();
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            sighting.feed(&line);
+        }
+        let _ = tx.send(sighting);
+    });
+    rx
+}
+
+/// A backend being left alone, in `.forge/rate-limits.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Backoff {
+    pub until: DateTime<Utc>,
+    /// Rate-limit errors in a row.
+    pub hits: u32,
+}
+
+/// Shared by every agent and every `forge run` on the project.
+pub fn state_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/rate-limits.json")
+}
+
+pub fn load(project_dir: &Path) -> BTreeMap<String, Backoff> {
+    std::fs::read_to_string(state_path(project_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(project_dir: &Path, state: &BTreeMap<String, Backoff>) {
+    let path = state_path(project_dir);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(state) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// How long `backend` is still being left alone.
+pub fn remaining(project_dir: &Path, backend: &str) -> Option<Duration> {
+    let backoff = load(project_dir).remove(backend)?;
+    (backoff.until - Utc::now()).to_std().ok().filter(|d| !d.is_zero())
+}
+
+/// Leave `backend` alone after a rate-limit error: for `retry_after` if it
+/// gave one, else `backoff_secs` doubled for each error in a row before.
+/// Recorded as a `RateLimited` event.
+pub fn back_off(
+    project_dir: &Path,
+    config: &RateLimitConfig,
+    backend: &str,
+    retry_after: Option<u64>,
+    agent_id: &str,
+    feature_id: Option<&str>,
+) -> Duration {
+    let mut state = load(project_dir);
+    let hits = state.get(backend).map_or(0, |b| b.hits) + 1;
+    let doubled = config.backoff_secs.saturating_mul(1u64 << (hits - 1).min(16));
+    let secs = retry_after.unwrap_or(doubled).min(config.max_backoff_secs);
+    let until = Utc::now() + chrono::Duration::seconds(secs as i64);
+    state.insert(backend.to_string(), Backoff { until, hits });
+    save(project_dir, &state);
+    let feature_id = feature_id.map(String::from);
+    let kind = EventKind::RateLimited { backend: backend.to_string(), feature_id, backoff_secs: secs };
+    events::emit(project_dir, agent_id, kind);
+    Duration::from_secs(secs)
+}
+
+/// A session on `backend` went through: the next error starts the backoff
+/// over.
+pub fn recovered(project_dir: &Path, backend: &str) {
+    let mut state = load(project_dir);
+    if state.remove(backend).is_some() {
+        save(project_dir, &state);
+    }
+}
+
+/// What a session of `role` should do now.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    /// Run on the role as it is, or on its backend's fallback.
+    Run(RoleSpec),
+    /// Its backend and the fallback are both backed off.
+    Wait(Duration),
+}
+
+pub fn route(project_dir: &Path, config: &RateLimitConfig, role: &RoleSpec) -> Route {
+    let Some(wait) = remaining(project_dir, &role.backend) else {
+        return Route::Run(role.clone());
+    };
+    let Some(fallback) = config.fallback.get(&role.backend) else {
+        return Route::Wait(wait);
+    };
+    match remaining(project_dir, &fallback.backend) {
+        Some(other) => Route::Wait(wait.min(other)),
+        None => Route::Run(RoleSpec {
+            backend: fallback.backend.clone(),
+            model: fallback.model.clone().unwrap_or_else(|| role.model.clone()),
+            ..role.clone()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Fallback;
+
+    #[test]
+    fn backs_off_throttled_backends_and_routes_to_fallbacks() {
+        let mut sighting = Sighting::default();
+        for line in ["compiling rate_limiter.rs", "all 12 tests passed"] {
+            sighting.feed(line);
+        }
+        assert!(!sighting.seen);
+        sighting.feed(r#"{"type":"error","error":{"type":"rate_limit_error","message":"slow down"}}"#);
+        sighting.feed("retry-after: 42");
+        assert_eq!(sighting, Sighting { seen: true, retry_after: Some(42) });
+        assert!(is_rate_limited("Error: 429 Too Many Requests"));
+        assert!(is_rate_limited("Claude AI usage limit reached|1760000000"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let config = RateLimitConfig {
+            backoff_secs: 60,
+            max_backoff_secs: 150,
+            fallback: BTreeMap::from([("claude".into(), Fallback { backend: "codex".into(), model: Some("o3".into()) })]),
+        };
+        let claude = RoleSpec { backend: "claude".into(), model: "opus".into(), retries: Some(2), ..RoleSpec::default() };
+        assert_eq!(route(root, &config, &claude), Route::Run(claude.clone()));
+
+        assert_eq!(back_off(root, &config, "claude", None, "agent-1", Some("f001")), Duration::from_secs(60));
+        assert_eq!(back_off(root, &config, "claude", None, "agent-1", Some("f001")), Duration::from_secs(120));
+        assert_eq!(back_off(root, &config, "claude", None, "agent-1", None), Duration::from_secs(150), "capped");
+        let Route::Run(rerouted) = route(root, &config, &claude) else { panic!("codex is free") };
+        assert_eq!((rerouted.backend.as_str(), rerouted.model.as_str(), rerouted.retries), ("codex", "o3", Some(2)));
+
+        back_off(root, &config, "codex", Some(30), "agent-2", None);
+        assert!(matches!(route(root, &config, &claude), Route::Wait(d) if d <= Duration::from_secs(30)));
+        let timeline = events::merged_timeline(root).unwrap();
+        assert!(matches!(&timeline[0].kind, EventKind::RateLimited { backoff_secs: 60, .. }));
+
+        recovered(root, "claude");
+        assert_eq!(load(root).keys().collect::<Vec<_>>(), ["codex"]);
+        assert_eq!(back_off(root, &config, "claude", None, "agent-1", None), Duration::from_secs(60), "starts over");
+    }
+}
@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+
+use crate::git;
+
+/// Where `forge run --repo` keeps its clones: `$XDG_CACHE_HOME/forge/repos`,
+/// else `~/.cache/forge/repos`.
+pub fn cache_dir() -> PathBuf {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::var("HOME").map(PathBuf::from).unwrap_or_default().join(".cache"));
+    base.join("forge/repos")
+}
+
+/// A repository URL as a directory name:
+/// `git@github.com:org/project.git` → `github.com-org-project`.
+pub fn slug(url: &str) -> String {
+    let url = url.trim_end_matches('/').trim_end_matches(".git");
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.rsplit_once('@').map_or(url, |(_, host)| host);
+    let parts: Vec<String> = url
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '_')
+        .filter(|p| !p.is_empty() && *p != ".." && *p != ".")
+        .map(String::from)
+        .collect();
+    parts.join("-")
+}
+
+/// A checkout of `url` at `branch` (the remote's default without one) in
+/// `cache`: cloned the first time, then fetched and rebased onto the remote
+/// so work a failed push left behind isn't lost.
+pub fn checkout(cache: &Path, url: &str, branch: Option<&str>) -> Result<PathBuf, String> {
+    let dir = cache.join(slug(url));
+    if !dir.join(".git").exists() {
+        std::fs::create_dir_all(cache).map_err(|e| format!("cannot create {}: {e}", cache.display()))?;
+        let target = dir.to_string_lossy();
+        let mut args = vec!["clone", "-q"];
+        if let Some(branch) = branch {
+            args.extend(["--branch", branch]);
+        }
+        args.extend([url, target.as_ref()]);
+        git::run(cache, &args)?;
+        return Ok(dir);
+    }
+
+    let origin = git::run(&dir, &["remote", "get-url", "origin"])?;
+    if origin != url {
+        return Err(format!("{} is a clone of {origin}, not {url}", dir.display()));
+    }
+    git::run(&dir, &["fetch", "-q", "origin"])?;
+    if let Some(branch) = branch {
+        git::run(&dir, &["checkout", "-q", branch])?;
+    }
+    git::pull(&dir)?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs() {
+        assert_eq!(slug("git@github.com:org/project.git"), "github.com-org-project");
+        assert_eq!(slug("https://token@github.com/org/project/"), "github.com-org-project");
+        assert_eq!(slug("/srv/git/../project.git"), "srv-git-project");
+    }
+
+    #[test]
+    fn clones_then_updates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let run = |dir: &Path, args: &[&str]| git::run(dir, args).unwrap();
+        let seed = tmp.path().join("seed");
+        std::fs::create_dir_all(&seed).unwrap();
+        run(&seed, &["init", "-q", "-b", "main"]);
+        run(&seed, &["config", "user.email", "t@t"]);
+        run(&seed, &["config", "user.name", "t"]);
+        std::fs::write(seed.join("forge.toml"), "[project]\nname = \"p\"\n").unwrap();
+        run(&seed, &["add", "-A"]);
+        run(&seed, &["commit", "-qm", "init"]);
+        run(&seed, &["branch", "-q", "next"]);
+        run(tmp.path(), &["clone", "-q", "--bare", "seed", "origin.git"]);
+        let url = tmp.path().join("origin.git").to_string_lossy().into_owned();
+        let cache = tmp.path().join("cache");
+
+        let dir = checkout(&cache, &url, Some("next")).unwrap();
+        assert!(dir.starts_with(&cache) && dir.join("forge.toml").exists());
+        assert_eq!(git::current_branch(&dir).as_deref(), Some("next"));
+
+        // The remote moves on; the next run starts from it
+        run(&seed, &["checkout", "-q", "next"]);
+        std::fs::write(seed.join("new.rs"), "").unwrap();
+        run(&seed, &["add", "-A"]);
+        run(&seed, &["commit", "-qm", "more"]);
+        run(&seed, &["push", "-q", &url, "next"]);
+        let again = checkout(&cache, &url, Some("next")).unwrap();
+        assert_eq!(again, dir);
+        assert!(dir.join("new.rs").exists());
+
+        run(&dir, &["remote", "set-url", "origin", "git@example.com:x.git"]);
+        let error = checkout(&cache, &url, None).unwrap_err();
+        assert!(error.contains("is a clone of git@example.com:x.git"), "{error}");
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::events::{self, Event, EventKind};
+use crate::export::{ArchiveFormat, ExportError};
+use crate::features::{FeatureList, FeatureStatus};
+use crate::notify;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("{0} is not a forge export (missing manifest.json)")]
+    NotAnExport(String),
+    #[error("the export has no timeline.jsonl: nothing to replay")]
+    NoTimeline,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("{0}")]
+    Export(#[from] ExportError),
+}
+
+/// The project as it stood at one point of the timeline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub features: BTreeMap<String, FeatureStatus>,
+    /// Agents with a session open, and the feature each is on.
+    pub sessions: BTreeMap<String, Option<String>>,
+    pub sessions_started: usize,
+    /// The last verify pass: passed, failed.
+    pub verify: Option<(usize, usize)>,
+}
+
+impl Snapshot {
+    pub fn count(&self, status: FeatureStatus) -> usize {
+        self.features.values().filter(|s| **s == status).count()
+    }
+
+    fn set(&mut self, feature_id: &str, status: FeatureStatus) {
+        self.features.insert(feature_id.to_string(), status);
+    }
+
+    fn apply(&mut self, event: &Event) {
+        match &event.kind {
+            EventKind::SessionStart { feature_id, .. } => {
+                self.sessions_started += 1;
+                if let Some(id) = feature_id
+                    && self.features.get(id).is_none_or(|s| *s == FeatureStatus::Pending)
+                {
+                    self.set(id, FeatureStatus::Claimed);
+                }
+                self.sessions.insert(event.agent.clone(), feature_id.clone());
+            }
+            EventKind::SessionEnd { .. } => {
+                self.sessions.remove(&event.agent);
+            }
+            EventKind::Claim { feature_id } => self.set(feature_id, FeatureStatus::Claimed),
+            EventKind::FeatureVerified { feature_id, passed: true } => self.set(feature_id, FeatureStatus::Done),
+            EventKind::FeatureBlocked { feature_id, .. }
+            | EventKind::RunawayKilled { feature_id: Some(feature_id), .. } => {
+                self.set(feature_id, FeatureStatus::Blocked)
+            }
+            EventKind::Verify { passed, failed, reopened } => {
+                self.verify = Some((*passed, *failed));
+                for id in reopened {
+                    self.set(id, FeatureStatus::Pending);
+                }
+            }
+            EventKind::MergeReverted { feature_id, .. }
+            | EventKind::ProtectedPaths { feature_id, .. }
+            | EventKind::SecretsFound { feature_id, .. }
+            | EventKind::LargeFiles { feature_id, .. }
+            | EventKind::QuestionAnswered { feature_id } => self.set(feature_id, FeatureStatus::Pending),
+            EventKind::QuestionAsked { feature_id, .. } => self.set(feature_id, FeatureStatus::Waiting),
+            _ => {}
+        }
+    }
+}
+
+/// One event, and the project just after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub event: Event,
+    pub after: Snapshot,
+}
+
+/// A run rebuilt from an export's timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub project: String,
+    pub exported_at: String,
+    /// Before the first event.
+    pub start: Snapshot,
+    pub steps: Vec<Step>,
+}
+
+impl Replay {
+    /// Rebuild from the events in `timeline` and the feature list saved at
+    /// export time. Features the timeline mentions start pending; the rest
+    /// keep the status they were exported with throughout.
+    pub fn build(project: String, exported_at: String, list: &FeatureList, timeline: Vec<Event>) -> Self {
+        let mut start = Snapshot::default();
+        for feature in &list.features {
+            start.set(&feature.id, feature.status.clone());
+        }
+        for event in &timeline {
+            for id in event.kind.feature_ids() {
+                start.set(id, FeatureStatus::Pending);
+            }
+        }
+        let mut state = start.clone();
+        let steps = timeline
+            .into_iter()
+            .map(|event| {
+                state.apply(&event);
+                Step { event, after: state.clone() }
+            })
+            .collect();
+        Replay { project, exported_at, start, steps }
+    }
+
+    /// The project after `position` steps: 0 is the start.
+    pub fn at(&self, position: usize) -> &Snapshot {
+        match position.checked_sub(1).and_then(|i| self.steps.get(i)) {
+            Some(step) => &step.after,
+            None if position == 0 => &self.start,
+            None => self.steps.last().map_or(&self.start, |s| &s.after),
+        }
+    }
+}
+
+/// Read an export: a `forge export` directory, or an archive of one from
+/// `--archive`.
+pub fn load(from: &Path) -> Result<Replay, ReplayError> {
+    if from.is_dir() {
+        return load_dir(from);
+    }
+    let format = ArchiveFormat::from_path(from)?;
+    let scratch = std::env::temp_dir().join(format!("forge-replay-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&scratch);
+    std::fs::create_dir_all(&scratch)?;
+    let replay = unpack(from, format, &scratch).and_then(|root| load_dir(&root));
+    let _ = std::fs::remove_dir_all(&scratch);
+    replay
+}
+
+/// Unpack `archive` into `dest`, returning the export's directory: the
+/// archive's top-level folder, or `dest` if it has none.
+fn unpack(archive: &Path, format: ArchiveFormat, dest: &Path) -> Result<PathBuf, ReplayError> {
+    let file = std::fs::File::open(archive)?;
+    match format {
+        ArchiveFormat::TarGz => tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(dest)?,
+        ArchiveFormat::Zip => zip::ZipArchive::new(file)?.extract(dest)?,
+    }
+    if dest.join("manifest.json").is_file() {
+        return Ok(dest.to_path_buf());
+    }
+    std::fs::read_dir(dest)?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.join("manifest.json").is_file())
+        .ok_or_else(|| ReplayError::NotAnExport(archive.display().to_string()))
+}
+
+fn load_dir(dir: &Path) -> Result<Replay, ReplayError> {
+    let manifest_path = dir.join("manifest.json");
+    if !manifest_path.is_file() {
+        return Err(ReplayError::NotAnExport(dir.display().to_string()));
+    }
+    // Plain JSON, so exports from older forge versions still load
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(manifest_path)?)?;
+    let field = |key: &str| manifest.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let timeline_path = dir.join("timeline.jsonl");
+    if !timeline_path.is_file() {
+        return Err(ReplayError::NoTimeline);
+    }
+    let timeline = events::read_stream(&timeline_path)?;
+    let list = FeatureList::load(dir).unwrap_or(FeatureList { features: vec![] });
+    // Exports of `--project .` carry no name; the directory has one
+    let project = match field("project_name") {
+        name if name.is_empty() => {
+            Path::new(&field("project_dir")).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        }
+        name => name,
+    };
+    Ok(Replay::build(project, field("exported_at"), &list, timeline))
+}
+
+/// One line for the timeline.
+pub fn describe(event: &Event) -> String {
+    let agent = &event.agent;
+    let on = |feature_id: &Option<String>| feature_id.as_deref().map(|f| format!(" on {f}")).unwrap_or_default();
+    match &event.kind {
+        EventKind::SessionStart { role, feature_id, model, variant, .. } => {
+            let model = model.as_deref().map(|m| format!(" ({m})")).unwrap_or_default();
+            let variant = variant.as_deref().map(|v| format!(" [{v}]")).unwrap_or_default();
+            format!("{agent}'s {role} session started{}{model}{variant}", on(feature_id))
+        }
+        EventKind::Claim { feature_id } => format!("{agent} claimed {feature_id}"),
+        EventKind::FeatureVerified { feature_id, passed: true } => format!("{feature_id} passed verify"),
+        EventKind::FeatureVerified { feature_id, passed: false } => format!("{feature_id} failed verify"),
+        EventKind::Verify { passed, failed: 0, .. } => format!("verify passed: {passed} of {passed}"),
+        EventKind::Merge { branch, ok: true, .. } => format!("merged {branch}"),
+        EventKind::Merge { branch, ok: false, error } => match error {
+            Some(error) => format!("failed to merge {branch}: {error}"),
+            None => format!("failed to merge {branch}"),
+        },
+        EventKind::MergeReverted { feature_id, regressed } => {
+            format!("reverted {feature_id}: it broke {}", regressed.join(", "))
+        }
+        EventKind::RunawayKilled { feature_id, reason } => format!("killed {agent}{}: {reason}", on(feature_id)),
+        EventKind::RateLimited { backend, feature_id, backoff_secs } => {
+            format!("{backend} rate limited {agent}{}; backing off {backoff_secs}s", on(feature_id))
+        }
+        EventKind::BackendRerouted { from, to, role, feature_id } => {
+            format!("{agent}'s {role} session{} moved from {from} to {to}", on(feature_id))
+        }
+        EventKind::ChaosInjected { fault, feature_id } => format!("chaos: {fault}{}", on(feature_id)),
+        EventKind::QuestionAnswered { feature_id } => format!("{feature_id}'s question was answered"),
+        _ => notify::summary(event),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{Duration, Utc};
+
+    use super::*;
+
+    #[test]
+    fn rebuilds_feature_states_from_an_archived_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let export = dir.path().join("export");
+        fs::create_dir_all(&export).unwrap();
+        fs::write(export.join("manifest.json"), r#"{"project_name":"demo","exported_at":"2026-10-01T00:00:00Z"}"#)
+            .unwrap();
+        fs::write(
+            export.join("features.json"),
+            r#"{"features":[
+                {"id":"f001","type":"implement","scope":"a","description":"x","verify":"true","status":"done"},
+                {"id":"f002","type":"implement","scope":"a","description":"y","verify":"true","status":"done"},
+                {"id":"f003","type":"implement","scope":"a","description":"z","verify":"true","status":"blocked"}]}"#,
+        )
+        .unwrap();
+        let t0 = Utc::now();
+        let event = |secs, agent: &str, kind| Event { ts: t0 + Duration::seconds(secs), agent: agent.into(), kind };
+        let start = |feature: &str| EventKind::SessionStart {
+            role: "executor".into(),
+            feature_id: Some(feature.into()),
+            backend: None,
+            model: Some("opus".into()),
+            variant: None,
+        };
+        let end = EventKind::SessionEnd { feature_id: None, exit_code: Some(0), timed_out: false, output_bytes: None };
+        let timeline = [
+            event(0, "agent-1", start("f002")),
+            event(1, "agent-2", start("f003")),
+            event(2, "forge", EventKind::FeatureVerified { feature_id: "f002".into(), passed: true }),
+            event(3, "agent-1", end.clone()),
+            event(4, "forge", EventKind::FeatureBlocked { feature_id: "f003".into(), reason: None }),
+            event(5, "forge", EventKind::Verify { passed: 1, failed: 1, reopened: vec!["f002".into()] }),
+        ];
+        let lines: Vec<String> = timeline.iter().map(|e| serde_json::to_string(e).unwrap() + "\n").collect();
+        fs::write(export.join("timeline.jsonl"), lines.concat()).unwrap();
+        let archive = dir.path().join("run.tar.gz");
+        crate::export::write_archive(&export, &archive).unwrap();
+
+        let replay = load(&archive).unwrap();
+        assert_eq!((replay.project.as_str(), replay.steps.len()), ("demo", 6));
+        let status = |position: usize, id: &str| replay.at(position).features[id].clone();
+        assert_eq!(status(0, "f001"), FeatureStatus::Done, "not in the timeline: as exported");
+        assert_eq!(status(0, "f002"), FeatureStatus::Pending);
+        assert_eq!(status(1, "f002"), FeatureStatus::Claimed);
+        assert_eq!(replay.at(2).sessions.len(), 2);
+        assert_eq!(status(3, "f002"), FeatureStatus::Done);
+        assert_eq!(replay.at(4).sessions.keys().collect::<Vec<_>>(), ["agent-2"]);
+        assert_eq!(status(5, "f003"), FeatureStatus::Blocked);
+        assert_eq!(status(6, "f002"), FeatureStatus::Pending, "reopened by verify");
+        assert_eq!(replay.at(99).verify, Some((1, 1)));
+        assert_eq!(describe(&replay.steps[0].event), "agent-1's executor session started on f002 (opus)");
+
+        fs::remove_file(export.join("timeline.jsonl")).unwrap();
+        assert!(matches!(load(&export), Err(ReplayError::NoTimeline)));
+        assert!(matches!(load(dir.path()), Err(ReplayError::NotAnExport(_))));
+    }
+}
@@ -0,0 +1,391 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::export::{ExportError, ExportManifest};
+use crate::features::{FeatureList, FeatureStatus};
+
+const STYLE: &str = r#"
+body { font: 14px/1.5 -apple-system, "Segoe UI", sans-serif; margin: 2em auto; max-width: 1100px; color: #222; }
+h1 { margin-bottom: 0; } h2 { border-bottom: 1px solid #ddd; padding-bottom: .2em; margin-top: 2em; }
+.meta { color: #666; } table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: .3em .6em; border-bottom: 1px solid #eee; vertical-align: top; }
+.cards { display: flex; gap: 1em; flex-wrap: wrap; } .card { background: #f6f6f6; border-radius: 6px; padding: .6em 1em; }
+.card b { display: block; font-size: 1.6em; }
+.st { border-radius: 3px; padding: 0 .4em; color: #fff; font-size: .85em; }
+.st-done, .pass { background: #2a9d46; } .st-pending { background: #888; } .st-claimed { background: #d08a00; }
+.st-blocked, .fail { background: #c62828; } .st-waiting { background: #8e44ad; }
+.filters button { margin-right: .4em; cursor: pointer; } .filters button.on { font-weight: bold; }
+pre { background: #f6f6f6; padding: .6em; overflow-x: auto; max-height: 20em; }
+code { font-size: .9em; } .forge { color: #6a3fb5; }
+"#;
+
+const FILTER_SCRIPT: &str = r#"
+function filterStatus(s) {
+  document.querySelectorAll('#features tbody tr').forEach(function (r) {
+    r.style.display = (s === 'all' || r.dataset.status === s) ? '' : 'none';
+  });
+  document.querySelectorAll('.filters button').forEach(function (b) {
+    b.classList.toggle('on', b.dataset.filter === s);
+  });
+}
+"#;
+
+/// Write `report.html` into an export directory: a self-contained page built
+/// from the files the export already contains.
+pub fn write_html_report(export_dir: &Path, manifest: &ExportManifest) -> Result<PathBuf, ExportError> {
+    let html = render_report(export_dir, manifest);
+    let path = export_dir.join("report.html");
+    std::fs::write(&path, html)?;
+    Ok(path)
+}
+
+fn render_report(export_dir: &Path, manifest: &ExportManifest) -> String {
+    let mut out = String::new();
+    let title = format!("forge report — {}", manifest.project_name);
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{STYLE}</style></head><body>\n",
+        escape(&title)
+    );
+    let _ = writeln!(out, "<h1>{}</h1>", escape(&title));
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">Exported {} by forge {} from <code>{}</code></p>",
+        escape(&manifest.exported_at),
+        escape(&manifest.forge_version),
+        escape(&manifest.project_dir)
+    );
+
+    render_summary(&mut out, manifest);
+    render_features(&mut out, export_dir);
+    render_verify(&mut out, export_dir);
+    render_cost(&mut out, manifest);
+    render_timeline(&mut out, export_dir);
+    render_logs(&mut out, export_dir, manifest);
+
+    let _ = writeln!(out, "<script>{FILTER_SCRIPT}</script>\n</body></html>");
+    out
+}
+
+fn render_summary(out: &mut String, manifest: &ExportManifest) {
+    let f = &manifest.features;
+    let _ = writeln!(out, "<h2>Summary</h2>\n<div class=\"cards\">");
+    for (label, value) in [
+        ("features", f.total),
+        ("done", f.done),
+        ("pending", f.pending),
+        ("claimed", f.claimed),
+        ("blocked", f.blocked),
+        ("waiting", f.waiting),
+        ("transcripts", manifest.transcripts.len()),
+    ] {
+        let _ = writeln!(out, "<div class=\"card\"><b>{value}</b>{label}</div>");
+    }
+    let _ = writeln!(out, "</div>");
+
+    if !manifest.context_counts.is_empty() {
+        let parts: Vec<String> = manifest
+            .context_counts
+            .iter()
+            .map(|(k, v)| format!("{} {v}", escape(k)))
+            .collect();
+        let _ = writeln!(out, "<p>Context: {}</p>", parts.join(", "));
+    }
+    if let Some(git) = &manifest.git {
+        let _ = writeln!(
+            out,
+            "<p>Git: branch <code>{}</code> at <code>{}</code>, {} commits included</p>",
+            escape(&git.branch),
+            escape(&git.latest_commit),
+            git.commits_included
+        );
+    }
+    let _ = writeln!(out, "<p>Sections: {}</p>", escape(&manifest.sections.join(", ")));
+    if !manifest.filter.is_empty() {
+        let _ = writeln!(out, "<p>Filtered to {}</p>", escape(&manifest.filter.to_string()));
+    }
+}
+
+fn status_name(status: &FeatureStatus) -> &'static str {
+    match status {
+        FeatureStatus::Pending => "pending",
+        FeatureStatus::Claimed => "claimed",
+        FeatureStatus::Done => "done",
+        FeatureStatus::Blocked => "blocked",
+        FeatureStatus::Waiting => "waiting",
+    }
+}
+
+fn render_features(out: &mut String, export_dir: &Path) {
+    let _ = writeln!(out, "<h2>Features</h2>");
+    let Ok(list) = FeatureList::load(export_dir) else {
+        let _ = writeln!(out, "<p>No features.json in this export.</p>");
+        return;
+    };
+
+    let _ = write!(out, "<p class=\"filters\">");
+    for filter in ["all", "pending", "claimed", "done", "blocked", "waiting"] {
+        let on = if filter == "all" { " class=\"on\"" } else { "" };
+        let _ = write!(
+            out,
+            "<button{on} data-filter=\"{filter}\" onclick=\"filterStatus('{filter}')\">{filter}</button>"
+        );
+    }
+    let _ = writeln!(out, "</p>");
+
+    let _ = writeln!(
+        out,
+        "<table id=\"features\"><thead><tr><th>ID</th><th>Status</th><th>Type</th><th>Scope</th>\
+         <th>P</th><th>Description</th><th>Depends on</th><th>Agent</th></tr></thead><tbody>"
+    );
+    for f in &list.features {
+        let status = status_name(&f.status);
+        let mut description = escape(&f.description);
+        if let Some(reason) = &f.blocked_reason {
+            let _ = write!(description, "<br><em>blocked: {}</em>", escape(reason));
+        }
+        let _ = writeln!(
+            out,
+            "<tr data-status=\"{status}\"><td><code>{}</code></td><td><span class=\"st st-{status}\">{status}</span></td>\
+             <td>{:?}</td><td>{}</td><td>{}</td><td>{description}</td><td>{}</td><td>{}</td></tr>",
+            escape(&f.id),
+            f.feature_type,
+            escape(&f.scope),
+            f.priority,
+            escape(&f.depends_on.join(", ")),
+            escape(f.claimed_by.as_deref().unwrap_or("")),
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+}
+
+fn render_verify(out: &mut String, export_dir: &Path) {
+    let _ = writeln!(out, "<h2>Verify results</h2>");
+    let report: Option<serde_json::Value> = std::fs::read_to_string(export_dir.join("feedback/last-verify.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+    let Some(report) = report else {
+        let _ = writeln!(out, "<p>No verify report in this export.</p>");
+        return;
+    };
+
+    let num = |k: &str| report.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+    let _ = writeln!(
+        out,
+        "<p><span class=\"st pass\">{} pass</span> <span class=\"st fail\">{} fail</span> of {}</p>",
+        num("pass"),
+        num("fail"),
+        num("total")
+    );
+    let failures = report
+        .get("failures")
+        .and_then(|f| f.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for failure in failures {
+        let id = failure.get("feature_id").and_then(|v| v.as_str()).unwrap_or("?");
+        let output = failure.get("output").and_then(|v| v.as_str()).unwrap_or("");
+        let _ = writeln!(
+            out,
+            "<details><summary><code>{}</code> failed</summary><pre>{}</pre></details>",
+            escape(id),
+            escape(output)
+        );
+    }
+}
+
+fn render_cost(out: &mut String, manifest: &ExportManifest) {
+    let Some(cost) = &manifest.cost else {
+        return;
+    };
+    let _ = writeln!(out, "<h2>Cost</h2>\n<p>Total ${:.2}</p>", cost.total_usd);
+    let _ = writeln!(
+        out,
+        "<table><thead><tr><th>Feature</th><th>Sessions</th><th>Output tokens</th><th>Cost</th></tr></thead><tbody>"
+    );
+    let rows = cost
+        .features
+        .iter()
+        .map(|(id, c)| (id.as_str(), c))
+        .chain([("(orchestration)", &cost.orchestration), ("(unattributed)", &cost.unattributed)]);
+    for (label, c) in rows.filter(|(_, c)| !c.sessions.is_empty()) {
+        let _ = writeln!(
+            out,
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>${:.2}</td></tr>",
+            escape(label),
+            c.sessions.len(),
+            c.tokens.output,
+            c.cost_usd
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+    for (label, usd) in &cost.milestones {
+        let _ = writeln!(out, "<p>Milestone {}: ${usd:.2}</p>", escape(label));
+    }
+}
+
+/// One commit from `git/log.txt` (`%H %aI %an %s`).
+struct Commit<'a> {
+    hash: &'a str,
+    date: &'a str,
+    rest: &'a str,
+}
+
+fn parse_git_log(text: &str) -> Vec<Commit<'_>> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            Some(Commit {
+                hash: parts.next()?,
+                date: parts.next()?,
+                rest: parts.next().unwrap_or(""),
+            })
+        })
+        .collect()
+}
+
+fn render_timeline(out: &mut String, export_dir: &Path) {
+    let _ = writeln!(out, "<h2>Timeline</h2>");
+    let Ok(log) = std::fs::read_to_string(export_dir.join("git/log.txt")) else {
+        let _ = writeln!(out, "<p>No git history in this export.</p>");
+        return;
+    };
+    let _ = writeln!(out, "<table><thead><tr><th>When</th><th>Commit</th><th>Author / message</th></tr></thead><tbody>");
+    for c in parse_git_log(&log) {
+        // Claims, merges and state syncs made by forge itself stand out
+        let class = if c.rest.contains("forge:") { " class=\"forge\"" } else { "" };
+        let _ = writeln!(
+            out,
+            "<tr{class}><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+            escape(c.date),
+            escape(&c.hash[..c.hash.len().min(8)]),
+            escape(c.rest)
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+    if let Ok(stat) = std::fs::read_to_string(export_dir.join("git/diff-stat.txt")) {
+        let _ = writeln!(out, "<details><summary>Diff stat</summary><pre>{}</pre></details>", escape(&stat));
+    }
+}
+
+fn render_logs(out: &mut String, export_dir: &Path, manifest: &ExportManifest) {
+    if manifest.logs.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "<h2>Agent logs</h2>");
+    for name in &manifest.logs {
+        let text = std::fs::read_to_string(export_dir.join("logs").join(name)).unwrap_or_default();
+        let lines: Vec<&str> = text.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(40)..];
+        let _ = writeln!(
+            out,
+            "<details><summary><code>{}</code> — {} lines</summary><pre>{}</pre></details>",
+            escape(name),
+            lines.len(),
+            escape(&tail.join("\n"))
+        );
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup_export(dir: &Path) -> ExportManifest {
+        let project = dir.join("project");
+        fs::create_dir_all(project.join("feedback")).unwrap();
+        fs::create_dir_all(project.join(".forge/logs")).unwrap();
+        fs::write(project.join("forge.toml"), "[project]\nname = \"demo\"\n").unwrap();
+        fs::write(
+            project.join("features.json"),
+            r#"{"features":[
+                {"id":"f001","type":"implement","scope":"api","description":"Add <b>users</b>","verify":"true","status":"done"},
+                {"id":"f002","type":"implement","scope":"api","description":"Auth","verify":"false","status":"blocked","blocked_reason":"needs keys"}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(
+            project.join("feedback/last-verify.json"),
+            r#"{"pass":1,"fail":1,"total":2,"failures":[{"feature_id":"f002","output":"exit 1 <oops>"}]}"#,
+        )
+        .unwrap();
+        fs::write(project.join(".forge/logs/agent-1.log"), "line one\nline two\n").unwrap();
+
+        let out = dir.join("export");
+        crate::export::export_project(
+            &project,
+            &out,
+            &crate::export::ExportOptions {
+                include_transcripts: false,
+                git_commits: 5,
+                ..Default::default()
+            },
+        ).unwrap()
+    }
+
+    #[test]
+    fn report_contains_all_sections() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manifest = setup_export(tmp.path());
+        let out = tmp.path().join("export");
+
+        let path = write_html_report(&out, &manifest).unwrap();
+        assert_eq!(path, out.join("report.html"));
+        let html = fs::read_to_string(path).unwrap();
+
+        assert!(html.contains("<h2>Summary</h2>"));
+        assert!(html.contains("<tr data-status=\"done\"><td><code>f001</code>"));
+        assert!(html.contains("data-filter=\"blocked\""));
+        assert!(html.contains("blocked: needs keys"));
+        assert!(html.contains("1 pass"));
+        assert!(html.contains("<code>f002</code> failed"));
+        assert!(html.contains("<code>agent-1.log</code> — 2 lines"));
+    }
+
+    #[test]
+    fn report_escapes_user_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manifest = setup_export(tmp.path());
+        let html = render_report(&tmp.path().join("export"), &manifest);
+        assert!(html.contains("Add &lt;b&gt;users&lt;/b&gt;"));
+        assert!(html.contains("exit 1 &lt;oops&gt;"));
+        assert!(!html.contains("<b>users</b>"));
+    }
+
+    #[test]
+    fn report_handles_missing_inputs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let manifest = setup_export(tmp.path());
+        let empty = tmp.path().join("empty");
+        fs::create_dir_all(&empty).unwrap();
+        let html = render_report(&empty, &manifest);
+        assert!(html.contains("No features.json in this export."));
+        assert!(html.contains("No verify report in this export."));
+        assert!(html.contains("No git history in this export."));
+    }
+
+    #[test]
+    fn parse_git_log_lines() {
+        let commits = parse_git_log("abc123 2026-01-02T03:04:05+00:00 Jane Doe forge: agent-1 claims f001\nbad\n");
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].date, "2026-01-02T03:04:05+00:00");
+        assert_eq!(commits[0].rest, "Jane Doe forge: agent-1 claims f001");
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+/// Where the orchestrating agent writes its review; rewritten every session.
+pub fn review_path(project_dir: &Path) -> PathBuf {
+    project_dir.join("feedback/session-review.md")
+}
+
+/// Every review forge has kept, one file per session, oldest first by name.
+pub fn reviews_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join("feedback/reviews")
+}
+
+/// An archived review: its file name (`20260301-103000-s3.md`) and text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Review {
+    pub name: String,
+    pub text: String,
+}
+
+/// Every archived review, oldest first.
+pub fn load(project_dir: &Path) -> Vec<Review> {
+    let mut reviews: Vec<Review> = std::fs::read_dir(reviews_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "md"))
+        .filter_map(|e| {
+            let text = std::fs::read_to_string(e.path()).ok()?;
+            Some(Review { name: e.file_name().to_string_lossy().to_string(), text })
+        })
+        .collect();
+    reviews.sort_by(|a, b| a.name.cmp(&b.name));
+    reviews
+}
+
+/// Copy this session's review into `feedback/reviews/`, unless it's the
+/// one already kept last (the orchestrator didn't get to write one).
+pub fn archive(project_dir: &Path, session: usize) -> Option<PathBuf> {
+    let text = std::fs::read_to_string(review_path(project_dir)).ok()?;
+    if text.trim().is_empty() || load(project_dir).last().is_some_and(|last| last.text == text) {
+        return None;
+    }
+    let dir = reviews_dir(project_dir);
+    std::fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!("{}-s{session}.md", Local::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, text).ok()?;
+    Some(path)
+}
+
+/// One `- name: STATUS — note` line from a review section.
+struct Finding<'a> {
+    name: &'a str,
+    status: &'a str,
+    note: &'a str,
+}
+
+fn finding(line: &str) -> Option<Finding<'_>> {
+    let (name, rest) = line.trim().strip_prefix("- ")?.split_once(':')?;
+    let rest = rest.trim();
+    let (status, note) = match rest.split_once('—').or_else(|| rest.split_once(" - ")) {
+        Some((status, note)) => (status.trim(), note.trim()),
+        None => (rest, ""),
+    };
+    let status = status.split_whitespace().next().unwrap_or("");
+    Some(Finding { name: name.trim(), status, note })
+}
+
+/// Lines under each `### heading`, by heading.
+fn sections(text: &str) -> BTreeMap<String, Vec<&str>> {
+    let mut out: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        match line.strip_prefix("### ") {
+            Some(heading) => current = heading.trim().to_lowercase(),
+            None => out.entry(current.clone()).or_default().push(line),
+        }
+    }
+    out
+}
+
+/// The path a `SEE:` line points to, without its `§ section` or dash note.
+fn see_target(line: &str) -> Option<&str> {
+    let rest = line.trim().trim_start_matches("- ").strip_prefix("SEE:")?.trim();
+    let end = rest.find(" §").or_else(|| rest.find(" —")).unwrap_or(rest.len());
+    Some(rest[..end].trim()).filter(|t| !t.is_empty())
+}
+
+#[derive(Default)]
+struct Tally {
+    /// Review names it came up in.
+    reviews: BTreeSet<String>,
+    statuses: BTreeMap<String, usize>,
+    latest: String,
+}
+
+impl Tally {
+    fn add(&mut self, review: &str, status: &str, note: &str) {
+        self.reviews.insert(review.to_string());
+        *self.statuses.entry(status.to_string()).or_default() += 1;
+        if !note.is_empty() {
+            self.latest = note.to_string();
+        }
+    }
+
+    fn line(&self, name: &str, of: usize) -> String {
+        let statuses: Vec<String> = self.statuses.iter().map(|(s, n)| format!("{s} {n}")).collect();
+        let mut line = format!("- {name}: {} of {of} reviews ({})", self.reviews.len(), statuses.join(", "));
+        if !self.latest.is_empty() {
+            line.push_str(&format!(". Latest: {}", self.latest));
+        }
+        line
+    }
+}
+
+/// A markdown readout of recurring issues across `reviews`: principles
+/// flagged, features failing verify again and again, tactics warnings and
+/// the context reviewers kept pointing agents to.
+pub fn summarize(reviews: &[Review]) -> String {
+    let mut principles: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut failing: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut tactics: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut pointers: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for review in reviews {
+        let name = review.name.as_str();
+        for (heading, lines) in sections(&review.text) {
+            for line in &lines {
+                if let Some(target) = see_target(line) {
+                    pointers.entry(target.to_string()).or_default().insert(name.to_string());
+                }
+                let Some(f) = finding(line) else { continue };
+                let flagged = matches!(f.status, "WARN" | "FAIL");
+                let tally = match heading.as_str() {
+                    "principle review" if flagged => &mut principles,
+                    "verify results" if f.status == "FAIL" => &mut failing,
+                    "tactics assessment" if flagged => &mut tactics,
+                    _ => continue,
+                };
+                tally.entry(f.name.to_string()).or_default().add(name, f.status, f.note);
+            }
+        }
+    }
+
+    let n = reviews.len();
+    let mut out = String::new();
+    let span = match (reviews.first(), reviews.last()) {
+        (Some(first), Some(last)) if n > 1 => format!(", {} to {}", stem(&first.name), stem(&last.name)),
+        (Some(only), _) => format!(", {}", stem(&only.name)),
+        _ => String::new(),
+    };
+    let _ = writeln!(out, "# Session reviews: {n}{span}");
+
+    let mut section = |title: &str, tallies: &BTreeMap<String, Tally>, min: usize| {
+        let mut rows: Vec<(&String, &Tally)> = tallies.iter().filter(|(_, t)| t.reviews.len() >= min).collect();
+        rows.sort_by(|a, b| b.1.reviews.len().cmp(&a.1.reviews.len()).then_with(|| a.0.cmp(b.0)));
+        let _ = writeln!(out, "\n## {title}\n");
+        if rows.is_empty() {
+            let _ = writeln!(out, "None.");
+        }
+        for (name, tally) in rows {
+            let _ = writeln!(out, "{}", tally.line(name, n));
+        }
+    };
+    section("Principles flagged", &principles, 1);
+    section("Features failing verify in more than one review", &failing, 2);
+    section("Tactics warnings", &tactics, 1);
+
+    let mut pointed: Vec<(&String, usize)> = pointers.iter().map(|(t, r)| (t, r.len())).collect();
+    pointed.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let _ = writeln!(out, "\n## Context pointed to most\n");
+    if pointed.is_empty() {
+        let _ = writeln!(out, "None.");
+    }
+    for (target, count) in pointed.into_iter().take(10) {
+        let _ = writeln!(out, "- {target}: {count} of {n} reviews");
+    }
+    out
+}
+
+/// `20260301-103000` from `20260301-103000-s3.md`.
+fn stem(name: &str) -> &str {
+    name.get(..15).unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(name: &str, verify: &str, principle: &str, see: &str) -> Review {
+        let text = format!(
+            "## Session Review\n\n### Verify Results\n{verify}\n\n### Principle Review\n- P1 Readability: OK\n\
+             {principle}\n\n### Tactics Assessment\n- f001: approach sound\n\n### For Next Session\n{see}\n"
+        );
+        Review { name: name.into(), text }
+    }
+
+    #[test]
+    fn archives_each_new_review_once() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(archive(dir.path(), 1).is_none());
+        std::fs::create_dir_all(dir.path().join("feedback")).unwrap();
+        std::fs::write(review_path(dir.path()), "## Session Review\n").unwrap();
+        assert!(archive(dir.path(), 1).is_some());
+        assert!(archive(dir.path(), 2).is_none(), "unchanged since the last one");
+        assert_eq!(load(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn summary_counts_recurring_issues() {
+        let reviews = [
+            review(
+                "20260301-100000-s1.md",
+                "- f002: FAIL — test_split assertion",
+                "- P2 Proof: WARN — no edge cases",
+                "- SEE: context/gotchas/split.md § \"Odd lengths\"",
+            ),
+            review(
+                "20260302-100000-s2.md",
+                "- f002: FAIL — still odd lengths",
+                "- P2 Proof: FAIL — no tests at all",
+                "- SEE: context/gotchas/split.md",
+            ),
+            review("20260303-100000-s3.md", "- f003: FAIL — typo", "- P3 Style: WARN", ""),
+        ];
+        let summary = summarize(&reviews);
+        assert!(summary.starts_with("# Session reviews: 3, 20260301-100000 to 20260303-100000\n"), "{summary}");
+        let p2 = "- P2 Proof: 2 of 3 reviews (FAIL 1, WARN 1). Latest: no tests at all\n";
+        assert!(summary.contains(p2), "{summary}");
+        assert!(summary.contains("- P3 Style: 1 of 3 reviews (WARN 1)\n"), "{summary}");
+        assert!(summary.contains("- f002: 2 of 3 reviews (FAIL 2). Latest: still odd lengths\n"), "{summary}");
+        assert!(!summary.contains("f003"), "failed once only");
+        assert!(summary.contains("## Tactics warnings\n\nNone.\n"), "{summary}");
+        assert!(summary.contains("- context/gotchas/split.md: 2 of 3 reviews\n"), "{summary}");
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::config::RunawayConfig;
+use crate::events::{self, EventKind};
+use crate::transcript_patterns::DIGITS;
+
+/// Sessions whose output sets the baseline, most recent first.
+const BASELINE_SESSIONS: usize = 20;
+/// Fewer ended sessions than this and there's no baseline yet.
+const BASELINE_MIN: usize = 3;
+
+/// Median output of the project's recent sessions, in bytes.
+pub fn baseline(project_dir: &Path) -> Option<u64> {
+    let mut sizes: Vec<u64> = events::merged_timeline(project_dir)
+        .unwrap_or_default()
+        .iter()
+        .rev()
+        .filter_map(|e| match e.kind {
+            EventKind::SessionEnd { output_bytes, .. } => output_bytes,
+            _ => None,
+        })
+        .take(BASELINE_SESSIONS)
+        .collect();
+    if sizes.len() < BASELINE_MIN {
+        return None;
+    }
+    sizes.sort_unstable();
+    Some(sizes[sizes.len() / 2])
+}
+
+/// Watches one session's output for the signs of an agent gone in circles:
+/// far more output than usual, one error over and over, or one tool call
+/// (with `--output-format stream-json`) or line repeated back to back.
+pub struct Detector {
+    config: RunawayConfig,
+    max_bytes: Option<u64>,
+    bytes: u64,
+    errors: HashMap<String, usize>,
+    last: Option<String>,
+    repeats: usize,
+}
+
+impl Detector {
+    pub fn new(config: &RunawayConfig, baseline: Option<u64>) -> Self {
+        let max_bytes = baseline.map(|b| ((b as f64 * config.output_factor) as u64).max(config.min_output_kb * 1024));
+        Detector { config: config.clone(), max_bytes, bytes: 0, errors: HashMap::new(), last: None, repeats: 0 }
+    }
+
+    /// Take the next line; returns why the session should stop, once.
+    pub fn feed(&mut self, line: &str) -> Option<String> {
+        self.bytes += line.len() as u64 + 1;
+        if let Some(max) = self.max_bytes
+            && self.bytes > max
+        {
+            self.max_bytes = None;
+            return Some(format!("{} KiB of output, over {} KiB", self.bytes / 1024, max / 1024));
+        }
+
+        let (repeated, errors) = match serde_json::from_str::<Value>(line) {
+            Ok(entry) => stream_json(&entry),
+            Err(_) => {
+                let trimmed = line.trim();
+                let error = trimmed.to_ascii_lowercase().contains("error").then(|| trimmed.to_string());
+                ((!trimmed.is_empty()).then(|| trimmed.to_string()), error.into_iter().collect())
+            }
+        };
+        for error in errors {
+            let error = DIGITS.replace_all(&error, "N").to_string();
+            let count = self.errors.entry(error.clone()).or_default();
+            *count += 1;
+            if *count == self.config.repeated_errors {
+                return Some(format!("the same error {count} times: {error}"));
+            }
+        }
+        let repeated = repeated?;
+        if self.last.as_ref() == Some(&repeated) {
+            self.repeats += 1;
+        } else {
+            self.last = Some(repeated);
+            self.repeats = 1;
+        }
+        if self.repeats == self.config.repeated_calls {
+            let what = self.last.as_deref().unwrap_or_default().chars().take(80).collect::<String>();
+            return Some(format!("{} times in a row: {what}", self.repeats));
+        }
+        None
+    }
+}
+
+/// From a stream-json line: its tool call, as `name input`, and the
+/// first line of each failed tool result.
+fn stream_json(entry: &Value) -> (Option<String>, Vec<String>) {
+    let blocks = entry.pointer("/message/content").and_then(Value::as_array).into_iter().flatten();
+    let (mut call, mut errors) = (None, Vec::new());
+    for block in blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("tool_use") => {
+                let name = block.get("name").and_then(Value::as_str).unwrap_or("unknown");
+                call = Some(format!("{name} {}", block.get("input").unwrap_or(&Value::Null)));
+            }
+            Some("tool_result") if block.get("is_error").and_then(Value::as_bool) == Some(true) => {
+                let text = match block.get("content") {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                    None => continue,
+                };
+                let mut lines = text.lines().map(str::trim);
+                if let Some(first) = lines.find(|l| !l.is_empty() && !l.starts_with("Exit code")) {
+                    errors.push(first.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    (call, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_on_volume_errors_and_loops() {
+        let config = RunawayConfig { repeated_errors: 3, repeated_calls: 4, min_output_kb: 1, ..Default::default() };
+
+        let mut volume = Detector::new(&config, Some(1000));
+        assert_eq!(volume.feed(&"x".repeat(9000)), None);
+        assert_eq!(volume.feed(&"y".repeat(2000)).unwrap(), "10 KiB of output, over 9 KiB");
+        assert_eq!(volume.feed("z"), None, "reported once");
+
+        let mut errors = Detector::new(&config, None);
+        for line in ["error: line 1 failed", "ok", "error: line 2 failed"] {
+            assert_eq!(errors.feed(line), None);
+        }
+        assert_eq!(errors.feed("error: line 3 failed").unwrap(), "the same error 3 times: error: line N failed");
+
+        let call =
+            r#"{"message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]}}"#;
+        let mut looping = Detector::new(&config, None);
+        for _ in 0..3 {
+            assert_eq!(looping.feed(call), None);
+        }
+        assert_eq!(looping.feed(call).unwrap(), r#"4 times in a row: Bash {"command":"ls"}"#);
+    }
+}
@@ -1,29 +1,97 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, LazyLock};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::thread;
-
-use crate::config::RoleSpec;
-use crate::features::{FeatureList, FeatureStatus};
-use crate::git;
+use std::time::{Duration, Instant, SystemTime};
+
+use regex::Regex;
+use tracing::{error, info, warn};
+
+use crate::agent_log;
+use crate::audit;
+use crate::chaos::{self, Chaos};
+use crate::commit_message::CommitMessage;
+use crate::config::{
+    BackendDef, ForgeConfig, GitConfig, MergeStrategy, Permissions, RateLimitConfig, RoleSpec, RunawayConfig,
+};
+use crate::context_budget::{self, BudgetReport};
+use crate::context_index;
+use crate::events::{self, EventKind};
+use crate::features::{FeatureList, FeatureStatus, FeatureType};
+use crate::features_backup;
+use crate::feedback;
+use crate::git::{self, CommitGuard};
+use crate::large_files::{self, LargeFile};
+use crate::merge_conflicts::{self, ConflictReport};
+use crate::pr;
+use crate::rate_limit::{self, Route, Sighting};
+use crate::runaway;
+use crate::secret_scan::{self, Finding, SecretScanner};
+use crate::tags;
 use crate::verify;
 
+/// How agents report stopping at `--max-budget-usd` and the like.
+static BUDGET_EXCEEDED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)exceeded.{0,30}budget|budget.{0,30}exceeded|error_max_budget").expect("valid regex")
+});
+
 #[derive(Debug)]
 pub enum RunOutcome {
     AllDone { sessions: usize },
     MaxSessions { sessions: usize, remaining: usize },
     Stopped { sessions: usize },
+    /// The project's spend reached `[forge] max_total_cost`.
+    CostLimit { sessions: usize, spent_usd: f64, limit_usd: f64 },
     SpawnError(std::io::Error),
 }
 
+impl RunOutcome {
+    /// As recorded in `run_finished` events and `.forge/runs/`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunOutcome::AllDone { .. } => "all_done",
+            RunOutcome::MaxSessions { .. } => "max_sessions",
+            RunOutcome::Stopped { .. } => "stopped",
+            RunOutcome::CostLimit { .. } => "cost_limit",
+            RunOutcome::SpawnError(_) => "error",
+        }
+    }
+}
+
 /// Configuration for a forge run.
+#[derive(Clone)]
 pub struct RunConfig {
     pub project_dir: PathBuf,
     pub protocol: RoleSpec,
     pub orchestrating: RoleSpec,
+    /// `[forge.roles.protocol_b]`: the executor of even-numbered agents in
+    /// an A/B run.
+    pub protocol_b: Option<RoleSpec>,
     pub max_sessions: usize,
     pub num_agents: usize,
+    /// Give each TUI pane its own git worktree (watch mode only; headless
+    /// multi-agent runs always use worktrees).
+    pub worktrees: bool,
+    /// `[backends.*]` from forge.toml.
+    pub backends: BTreeMap<String, BackendDef>,
+    /// Resolved `[secrets]`, set in every agent's environment.
+    pub secrets: BTreeMap<String, String>,
+    /// `[git]` from forge.toml.
+    pub git: GitConfig,
+    /// `[forge] permissions`: whether claude agents skip permission checks.
+    pub permissions: Permissions,
+    /// `[forge.runaway]`: when a looping session is killed.
+    pub runaway: RunawayConfig,
+    /// `[forge.rate_limits]`: backing off throttled backends.
+    pub rate_limits: RateLimitConfig,
+    /// `[forge] max_total_cost`: USD after which no new session starts.
+    pub max_total_cost: Option<f64>,
+    /// `forge run --chaos`: faults to inject.
+    pub chaos: Option<Arc<Chaos>>,
 }
 
 /// Runtime directory for forge state (.forge/).
@@ -31,6 +99,16 @@ fn runtime_dir(project_dir: &Path) -> PathBuf {
     project_dir.join(".forge")
 }
 
+/// Worktree directory for an agent (.forge/worktrees/{agent_id}).
+pub(crate) fn worktree_path(project_dir: &Path, agent_id: &str) -> PathBuf {
+    runtime_dir(project_dir).join("worktrees").join(agent_id)
+}
+
+/// Branch an agent's worktree is checked out on, by `[git] branch_template`.
+pub(crate) fn agent_branch(git_config: &GitConfig, agent_id: &str, feature_id: &str) -> String {
+    git_config.branch_template.replace("{agent}", agent_id).replace("{feature_id}", feature_id)
+}
+
 /// Check if a stop was requested.
 pub fn stop_requested(project_dir: &Path) -> bool {
     runtime_dir(project_dir).join("stop").exists()
@@ -45,71 +123,185 @@ pub fn request_stop(project_dir: &Path) -> Result<(), std::io::Error> {
 }
 
 /// Clear the stop sentinel.
-fn clear_stop(project_dir: &Path) {
+pub fn clear_stop(project_dir: &Path) {
     let _ = fs::remove_file(runtime_dir(project_dir).join("stop"));
 }
 
 /// Open a log file for an agent.
 fn open_log(project_dir: &Path, agent_id: &str) -> Option<std::fs::File> {
-    let log_dir = runtime_dir(project_dir).join("logs");
-    fs::create_dir_all(&log_dir).ok()?;
-    fs::File::create(log_dir.join(format!("{agent_id}.log"))).ok()
+    let path = agent_log::log_path(project_dir, agent_id);
+    fs::create_dir_all(path.parent()?).ok()?;
+    fs::File::create(path).ok()
 }
 
 /// Build the agent prompt for a feature.
 /// If a context package exists, embeds its contents directly so the agent
-/// doesn't need to explore the codebase for pre-compiled context.
-pub fn build_agent_prompt(project_dir: &Path, feature_id: &str) -> String {
+/// doesn't need to explore the codebase for pre-compiled context. Packages
+/// over the role's `context_tokens` are cut down; the report says what went.
+pub fn build_agent_prompt(
+    project_dir: &Path,
+    feature_id: &str,
+    role: &RoleSpec,
+) -> (String, Option<BudgetReport>) {
     let package_path = project_dir.join(format!("context/packages/{feature_id}.md"));
-    let context_block = std::fs::read_to_string(&package_path).unwrap_or_default();
-
-    if context_block.is_empty() {
+    let package = std::fs::read_to_string(&package_path).unwrap_or_default();
+    let feature = FeatureList::load(project_dir)
+        .ok()
+        .and_then(|list| list.features.into_iter().find(|f| f.id == feature_id));
+    let description = feature.as_ref().map(|f| f.description.clone()).unwrap_or_default();
+    let (mut context_block, report) = context_budget::fit_package(&package, &description, role.context_tokens);
+    if let Some(report) = &report {
+        context_block.push_str(&format!(
+            "\n> Cut to fit the context budget: {}. The full package is context/packages/{feature_id}.md.",
+            report.cut_summary()
+        ));
+    }
+    let principles = feature
+        .map(|f| format!("{}{}", scope_dir_note(project_dir, &f.scope), scope_principles(project_dir, &f.scope)))
+        .unwrap_or_default();
+    let conflict_note = if merge_conflicts::report_path(project_dir, feature_id).exists() {
+        format!("\n\nThe last attempt at {feature_id} didn't merge. Read feedback/merge-conflicts/{feature_id}.md first.")
+    } else {
+        String::new()
+    };
+    let questions_note = crate::questions::prompt_note(project_dir, feature_id);
+
+    let prompt = if context_block.is_empty() {
+        let similar = similar_context(project_dir, feature_id);
+        let similar_block = if similar.is_empty() {
+            String::new()
+        } else {
+            let paths: Vec<String> = similar.iter().map(|e| format!("context/{e}.md")).collect();
+            format!("\n\nContext entries similar to this feature: {}", paths.join(", "))
+        };
         format!(
             "You are a forge agent. Your assigned feature is {feature_id}. \
              Read features.json for details. Follow the forge-protocol skill. \
-             When done, set status to done and exit.",
+             When done, set status to done and exit.{principles}{conflict_note}{questions_note}{similar_block}",
         )
     } else {
         format!(
             "You are a forge agent. Your assigned feature is {feature_id}. \
-             Follow the forge-protocol skill.\n\n\
+             Follow the forge-protocol skill.{principles}{conflict_note}{questions_note}\n\n\
              ## Pre-compiled context (DO NOT use Explore agents — this has what you need)\n\n\
              {context_block}\n\n\
              Read features.json for your verify command. \
              When done, set status to done and exit.",
         )
+    };
+    (prompt, report)
+}
+
+/// Where the agent runs for a scope with a `dir`, and how to reach forge's
+/// files from there; "" for a scope without one.
+fn scope_dir_note(project_dir: &Path, scope: &str) -> String {
+    let Some(dir) = ForgeConfig::load(project_dir).ok().and_then(|c| c.scope_dir(scope).map(str::to_string)) else {
+        return String::new();
+    };
+    let up = vec![".."; Path::new(&dir).components().count()].join("/");
+    format!(
+        "\n\nYou work in `{dir}`, scope `{scope}`'s directory. features.json, context/, feedback/ and scripts/ \
+         are at the project root, `{up}`."
+    )
+}
+
+/// Where a session on `feature_id` runs under `base` (the project or a
+/// worktree of it): its scope's `dir`, if it has one.
+pub(crate) fn scope_cwd(project_dir: &Path, base: &Path, feature_id: &str) -> PathBuf {
+    let scope = FeatureList::load(project_dir)
+        .ok()
+        .and_then(|list| list.features.into_iter().find(|f| f.id == feature_id).map(|f| f.scope));
+    match (ForgeConfig::load(project_dir), scope) {
+        // A dir the agent is yet to create is worked on from the root
+        (Ok(config), Some(scope)) if config.scope_root(base, &scope).is_dir() => config.scope_root(base, &scope),
+        _ => base.to_path_buf(),
+    }
+}
+
+/// `[scopes.<scope>.principles]` as a prompt section, or "" if it has none.
+fn scope_principles(project_dir: &Path, scope: &str) -> String {
+    let Some(principles) = ForgeConfig::load(project_dir)
+        .ok()
+        .and_then(|c| c.scopes.get(scope).map(|s| s.principles.clone()))
+        .filter(|p| !p.is_empty())
+    else {
+        return String::new();
+    };
+    let rules: Vec<String> = principles.iter().map(|(name, rule)| format!("- {name}: {rule}")).collect();
+    format!("\n\n## Principles for scope `{scope}` (on top of CLAUDE.md's)\n\n{}", rules.join("\n"))
+}
+
+/// Record a trimmed context package on the agent's event stream, and say so
+/// on stdout in headless runs.
+pub(crate) fn report_context_budget(
+    project_dir: &Path,
+    agent_id: &str,
+    feature_id: &str,
+    report: Option<BudgetReport>,
+    print: bool,
+) {
+    let Some(report) = report else { return };
+    if print {
+        info!(
+            "Context package trimmed to ~{}/{} tokens (cut: {})",
+            report.tokens_after,
+            report.budget,
+            report.cut_summary()
+        );
     }
+    events::emit(
+        project_dir,
+        agent_id,
+        EventKind::ContextTrimmed {
+            feature_id: feature_id.to_string(),
+            report,
+        },
+    );
+}
+
+/// Top-k entries from the embeddings index, on top of the planner's
+/// context_hints. Empty when embeddings aren't configured or the lookup fails:
+/// a prompt without them still works.
+fn similar_context(project_dir: &Path, feature_id: &str) -> Vec<String> {
+    let Some((embedder, top_k)) = context_index::configured_embedder(project_dir) else {
+        return Vec::new();
+    };
+    let Some(feature) = FeatureList::load(project_dir)
+        .ok()
+        .and_then(|list| list.features.into_iter().find(|f| f.id == feature_id))
+    else {
+        return Vec::new();
+    };
+    context_index::similar_entries(project_dir, &feature, &embedder, top_k).unwrap_or_default()
 }
 
 /// Check that the agent followed protocol after its session.
 /// These are CLI-enforced gates that don't depend on the agent's self-reporting.
-fn check_protocol_compliance(project_dir: &Path, feature_id: &str) {
-    // Check 1: exec-memory was written (agent completed handoff)
-    let exec_memory = project_dir.join(format!("feedback/exec-memory/{feature_id}.json"));
-    if !exec_memory.exists() {
-        eprintln!("  WARN: No exec-memory for {feature_id} — agent skipped handoff protocol");
-    } else {
-        // Check 2: delivery proof exists in exec-memory
-        if let Ok(content) = fs::read_to_string(&exec_memory) {
-            if !content.contains("\"delivery\"") {
-                eprintln!(
-                    "  WARN: exec-memory for {feature_id} has no delivery proof — \
-                     requirements not mapped to code/tests"
-                );
-            }
-        }
+fn check_protocol_compliance(project_dir: &Path, feature_id: &str, session_start: SystemTime) {
+    // Check 1: exec-memory was written and holds a handoff, delivery proof included
+    let problems = feedback::check_handoff(project_dir, feature_id);
+    for problem in &problems {
+        warn!("exec-memory for {feature_id}: {problem}");
+    }
+    if !problems.is_empty() {
+        let kind = EventKind::HandoffInvalid { feature_id: feature_id.to_string(), problems };
+        events::emit(project_dir, events::FORGE, kind);
     }
 
-    // Check 3: feature status was updated (not left as "claimed")
-    if let Ok(features) = FeatureList::load(project_dir) {
-        if let Some(f) = features.features.iter().find(|f| f.id == feature_id) {
-            if f.status == FeatureStatus::Claimed {
-                eprintln!(
-                    "  WARN: {feature_id} still 'claimed' after session — \
-                     agent didn't mark done or blocked"
-                );
-            }
-        }
+    // Check 2: feature status was updated (not left as "claimed")
+    if let Ok(features) = FeatureList::load(project_dir)
+        && let Some(f) = features.features.iter().find(|f| f.id == feature_id)
+        && f.status == FeatureStatus::Claimed
+    {
+        warn!(
+            "{feature_id} still 'claimed' after session — \
+             agent didn't mark done or blocked"
+        );
+    }
+
+    // Check 3: context written this session passes lint
+    for finding in crate::context_lint::lint(project_dir, Some(session_start)).unwrap_or_default() {
+        warn!("context lint: {finding}");
     }
 }
 
@@ -129,16 +321,16 @@ pub fn run_single_agent(config: &RunConfig) -> RunOutcome {
             clear_stop(&config.project_dir);
             return RunOutcome::Stopped { sessions: session };
         }
+        if let Some(outcome) = cost_limit(config, session) {
+            return outcome;
+        }
 
         // Check if all features are done
         let features = match FeatureList::load(&config.project_dir) {
             Ok(f) => f,
             Err(e) => {
-                eprintln!("Error loading features: {e}");
-                return RunOutcome::SpawnError(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    e.to_string(),
-                ));
+                error!("Error loading features: {e}");
+                return RunOutcome::SpawnError(std::io::Error::other(e.to_string()));
             }
         };
 
@@ -162,7 +354,7 @@ pub fn run_single_agent(config: &RunConfig) -> RunOutcome {
         let (next, next_type) = match features.next_claimable() {
             Some(f) => (f.id.clone(), f.feature_type.clone()),
             None => {
-                eprintln!("No claimable features (all blocked or claimed)");
+                warn!("No claimable features (all blocked, claimed or waiting on `forge questions`)");
                 let remaining = features
                     .features
                     .iter()
@@ -177,99 +369,126 @@ pub fn run_single_agent(config: &RunConfig) -> RunOutcome {
 
         // Refresh CocoIndex context packages
         match crate::context_flow::refresh_context(&config.project_dir) {
-            Ok(true) => println!("  Context packages refreshed."),
+            Ok(true) => info!("Context packages refreshed."),
             Ok(false) => {}
-            Err(e) => eprintln!("  Context refresh warning: {e}"),
+            Err(e) => warn!("Context refresh warning: {e}"),
         }
 
-        println!("--- Session {session} ---");
-        println!("  Feature: {next}");
+        info!(session, feature = %next, "--- Session {session}: {next} ---");
 
         // --- Phase 1: Executor ---
+        let session_start = SystemTime::now();
+        let start_rev = git::rev_parse(&config.project_dir, "HEAD");
         // Use orchestrating role for review features (milestone gates),
         // protocol role for implement/poc features.
         let role = match next_type {
             crate::features::FeatureType::Review => &config.orchestrating,
             _ => &config.protocol,
         };
-        let prompt = build_agent_prompt(&config.project_dir, &next);
+        let (prompt, budget) = build_agent_prompt(&config.project_dir, &next, role);
+        report_context_budget(&config.project_dir, "agent-1", &next, budget, true);
 
         let mut log = open_log(&config.project_dir, "agent-1");
-
-        match spawn_agent(role, &config.project_dir, &prompt, "agent-1") {
-            Ok(mut child) => {
-                if let Some(stdout) = child.stdout.take() {
-                    let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        match line {
-                            Ok(line) => {
-                                println!("  [{next}] {line}");
-                                if let Some(ref mut f) = log {
-                                    let _ = writeln!(f, "{line}");
-                                }
-                            }
-                            Err(_) => break,
-                        }
-                    }
-                }
-                let status = child.wait();
-                println!(
-                    "  Agent exited: {}",
+        events::emit(&config.project_dir, "agent-1", EventKind::Claim { feature_id: next.clone() });
+
+        let cwd = scope_cwd(&config.project_dir, &config.project_dir, &next);
+        let executor = Session {
+            role,
+            kind: "executor",
+            cwd: &cwd,
+            prompt: &prompt,
+            agent_id: "agent-1",
+            feature_id: Some(&next),
+            variant: None,
+        };
+        let mut on_line = |line: &str| {
+            info!("[{next}] {line}");
+            if let Some(ref mut f) = log {
+                agent_log::write_line(f, Some(&next), line);
+            }
+        };
+        match run_session(config, &executor, &mut on_line) {
+            Ok(status) => {
+                info!(
+                    "Agent exited: {}",
                     status.map_or("unknown".into(), |s| s.to_string())
                 );
             }
             Err(e) => {
-                eprintln!("  Failed to spawn agent: {e}");
+                error!("Failed to spawn agent: {e}");
                 return RunOutcome::SpawnError(e);
             }
         }
 
         // --- Phase 1.5: Protocol compliance checks ---
-        check_protocol_compliance(&config.project_dir, &next);
+        audit::sync(&config.project_dir, "agent-1");
+        check_protocol_compliance(&config.project_dir, &next, session_start);
+        if let Some(start) = &start_rev {
+            if !config.git.protected.is_empty() {
+                revert_protected(config, &next, start);
+            }
+            flag_secrets(config, &next, start);
+        }
 
         // --- Phase 2: Verify ---
-        println!("  Running post-session verify...");
-        match verify::verify_all(&config.project_dir) {
+        info!("Running post-session verify...");
+        if let Some(chaos) = &config.chaos {
+            chaos.delay_verify(&config.project_dir);
+        }
+        let results = match verify::verify_all(&config.project_dir) {
             Ok(results) => {
                 for result in &results {
                     let status = if result.passed { "PASS" } else { "FAIL" };
-                    println!("  [{status}] {}", result.feature_id);
+                    info!(feature = %result.feature_id, passed = result.passed, "[{status}] {}", result.feature_id);
                 }
 
                 // Write feedback/last-verify.json
                 let report = verify::VerifyReport::from_results(&results);
                 if let Err(e) = report.write(&config.project_dir) {
-                    eprintln!("  Failed to write verify report: {e}");
+                    warn!("Failed to write verify report: {e}");
                 }
 
                 // Reopen features that failed verify
+                let mut reopened = Vec::new();
                 if let Ok(mut features) = FeatureList::load(&config.project_dir) {
-                    let mut changed = false;
                     for result in &results {
-                        if !result.passed {
-                            if let Ok(()) = features.reopen(&result.feature_id) {
-                                println!("  Reopened {} (verify failed)", result.feature_id);
-                                changed = true;
-                            }
+                        if !result.passed
+                            && let Ok(()) = features.reopen(&result.feature_id)
+                        {
+                            info!("Reopened {} (verify failed)", result.feature_id);
+                            reopened.push(result.feature_id.clone());
                         }
                     }
-                    if changed {
+                    if !reopened.is_empty() {
                         let _ = features.save(&config.project_dir);
                     }
                 }
+                emit_verify(&config.project_dir, &results, reopened);
+                results
+            }
+            Err(e) => {
+                error!("Verify error: {e}");
+                Vec::new()
             }
-            Err(e) => eprintln!("  Verify error: {e}"),
+        };
+
+        // --- Phase 2.5: Pull request, milestone tag ---
+        if let Some(head) = git::rev_parse(&config.project_dir, "HEAD") {
+            pr::publish(&config.project_dir, &config.git, &[(next.clone(), head)], &results);
         }
+        tags::tag_milestones(&config.project_dir, &config.git, std::slice::from_ref(&next), &results);
+        crate::questions::escalate(&config.project_dir, std::slice::from_ref(&next));
+        emit_feature_outcomes(&config.project_dir, std::slice::from_ref(&next), &results);
 
         // --- Phase 3: Git sync ---
-        if git::is_git_repo(&config.project_dir) {
-            if let Err(e) = git::pull(&config.project_dir) {
-                eprintln!("  Git pull warning: {e}");
-            }
+        if git::is_git_repo(&config.project_dir)
+            && let Err(e) = git::pull(&config.project_dir)
+        {
+            warn!("Git pull warning: {e}");
         }
 
         // --- Phase 4: Orchestrating review ---
-        println!("  Dispatching orchestrating review...");
+        info!("Dispatching orchestrating review...");
         let orch_prompt = format!(
             "You are a forge orchestrating agent. Follow the forge-orchestrating skill. \
              Review the last executor session: read feedback/last-verify.json, run git diff HEAD~1, \
@@ -278,29 +497,30 @@ pub fn run_single_agent(config: &RunConfig) -> RunOutcome {
              Write feedback/session-review.md and any context entries. Then commit and exit."
         );
 
-        match spawn_agent(&config.orchestrating, &config.project_dir, &orch_prompt, "orchestrator") {
-            Ok(mut child) => {
-                // Capture but don't print orchestrator output (it's housekeeping)
-                if let Some(stdout) = child.stdout.take() {
-                    let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        match line {
-                            Ok(line) => {
-                                if let Some(ref mut f) = log {
-                                    let _ = writeln!(f, "[orch] {line}");
-                                }
-                            }
-                            Err(_) => break,
-                        }
-                    }
-                }
-                let _ = child.wait();
+        let review = Session {
+            role: &config.orchestrating,
+            kind: "orchestrating",
+            cwd: &config.project_dir,
+            prompt: &orch_prompt,
+            agent_id: "orchestrator",
+            feature_id: Some(&next),
+            variant: None,
+        };
+        // Capture but don't print orchestrator output (it's housekeeping)
+        let mut on_line = |line: &str| {
+            if let Some(ref mut f) = log {
+                agent_log::write_line(f, Some(&next), &format!("[orch] {line}"));
             }
+        };
+        match run_session(config, &review, &mut on_line) {
+            Ok(_) => {}
             Err(e) => {
                 // Orchestrating failure is non-fatal — executor can continue without review
-                eprintln!("  Orchestrating dispatch failed (non-fatal): {e}");
+                warn!("Orchestrating dispatch failed (non-fatal): {e}");
             }
         }
+        crate::reviews::archive(&config.project_dir, session);
+        commit_state(&config.project_dir, &config.git, session, std::slice::from_ref(&next));
 
         session += 1;
     }
@@ -316,11 +536,8 @@ pub fn run_multi_agent(config: &RunConfig) -> RunOutcome {
 
     // Must be a git repo for worktrees
     if !git::is_git_repo(&config.project_dir) {
-        eprintln!("Multi-agent mode requires a git repository.");
-        return RunOutcome::SpawnError(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "not a git repo",
-        ));
+        error!("Multi-agent mode requires a git repository.");
+        return RunOutcome::SpawnError(std::io::Error::other("not a git repo"));
     }
 
     loop {
@@ -328,15 +545,15 @@ pub fn run_multi_agent(config: &RunConfig) -> RunOutcome {
             clear_stop(&config.project_dir);
             return RunOutcome::Stopped { sessions: session };
         }
+        if let Some(outcome) = cost_limit(config, session) {
+            return outcome;
+        }
 
         let features = match FeatureList::load(&config.project_dir) {
             Ok(f) => f,
             Err(e) => {
-                eprintln!("Error loading features: {e}");
-                return RunOutcome::SpawnError(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    e.to_string(),
-                ));
+                error!("Error loading features: {e}");
+                return RunOutcome::SpawnError(std::io::Error::other(e.to_string()));
             }
         };
 
@@ -372,22 +589,23 @@ pub fn run_multi_agent(config: &RunConfig) -> RunOutcome {
 
         // Refresh CocoIndex context packages
         match crate::context_flow::refresh_context(&config.project_dir) {
-            Ok(true) => println!("  Context packages refreshed."),
+            Ok(true) => info!("Context packages refreshed."),
             Ok(false) => {}
-            Err(e) => eprintln!("  Context refresh warning: {e}"),
+            Err(e) => warn!("Context refresh warning: {e}"),
         }
 
+        let session_start = SystemTime::now();
         let feature_entries: Vec<(String, crate::features::FeatureType)> = claimable
             .iter()
             .map(|f| (f.id.clone(), f.feature_type.clone()))
             .collect();
 
-        println!(
+        info!(
             "--- Session {session} ({} agents) ---",
             feature_entries.len()
         );
         for (fid, _) in &feature_entries {
-            println!("  Feature: {fid}");
+            info!(session, feature = %fid, "Feature: {fid}");
         }
 
         // Create worktrees and spawn agents in parallel
@@ -399,133 +617,221 @@ pub fn run_multi_agent(config: &RunConfig) -> RunOutcome {
 
         for (i, (feature_id, ftype)) in feature_entries.iter().enumerate() {
             let agent_id = format!("agent-{}", i + 1);
-            let branch = format!("forge/{agent_id}");
-            let wt_dir = wt_base.join(&agent_id);
-
-            // Clean up stale worktree if exists
-            if wt_dir.exists() {
-                let _ = git::remove_worktree(&config.project_dir, &wt_dir);
-            }
-
-            if let Err(e) = git::create_worktree(&config.project_dir, &wt_dir, &branch) {
-                eprintln!("  Failed to create worktree for {agent_id}: {e}");
-                continue;
+            let branch = agent_branch(&config.git, &agent_id, feature_id);
+            let wt_dir = worktree_path(&config.project_dir, &agent_id);
+
+            // A pooled worktree is reused as is; anything else stale goes
+            let reused = config.git.reuse_worktrees && reuse_worktree(&config.project_dir, &wt_dir, &branch);
+            if !reused {
+                if wt_dir.exists() {
+                    let _ = git::remove_worktree(&config.project_dir, &wt_dir);
+                }
+                if let Err(e) = git::create_worktree(&config.project_dir, &wt_dir, &branch) {
+                    warn!("Failed to create worktree for {agent_id}: {e}");
+                    continue;
+                }
             }
 
-            let prompt = build_agent_prompt(&config.project_dir, feature_id);
-
-            // Use orchestrating role for review features, protocol for implement/poc
-            let role = match ftype {
-                crate::features::FeatureType::Review => config.orchestrating.clone(),
-                _ => config.protocol.clone(),
+            // Use orchestrating role for review features, protocol (or its
+            // variant B) for implement/poc
+            let (role, variant) = match ftype {
+                crate::features::FeatureType::Review => (config.orchestrating.clone(), None),
+                _ => {
+                    let (role, variant) = executor_role(config, i + 1);
+                    (role.clone(), variant)
+                }
             };
-            let wt = wt_dir.clone();
+            let (prompt, budget) = build_agent_prompt(&config.project_dir, feature_id, &role);
+            report_context_budget(&config.project_dir, &agent_id, feature_id, budget, true);
+            let wt = scope_cwd(&config.project_dir, &wt_dir, feature_id);
             let fid = feature_id.clone();
-            let project_dir = config.project_dir.clone();
             let aid = agent_id.clone();
+            let run_config = config.clone();
             let handle = thread::spawn(move || {
-                let mut log = open_log(&project_dir, &aid);
-                match spawn_agent(&role, &wt, &prompt, &aid) {
-                    Ok(mut child) => {
-                        if let Some(stdout) = child.stdout.take() {
-                            let reader = BufReader::new(stdout);
-                            for line in reader.lines() {
-                                match line {
-                                    Ok(line) => {
-                                        println!("  [{fid}] {line}");
-                                        if let Some(ref mut f) = log {
-                                            let _ = writeln!(f, "{line}");
-                                        }
-                                    }
-                                    Err(_) => break,
-                                }
-                            }
-                        }
-                        let _ = child.wait();
-                    }
-                    Err(e) => {
-                        eprintln!("  Failed to spawn {aid}: {e}");
+                let mut log = open_log(&run_config.project_dir, &aid);
+                events::emit(&run_config.project_dir, &aid, EventKind::Claim { feature_id: fid.clone() });
+                let executor = Session {
+                    role: &role,
+                    kind: "executor",
+                    cwd: &wt,
+                    prompt: &prompt,
+                    agent_id: &aid,
+                    feature_id: Some(&fid),
+                    variant,
+                };
+                let mut on_line = |line: &str| {
+                    info!("[{fid}] {line}");
+                    if let Some(ref mut f) = log {
+                        agent_log::write_line(f, Some(&fid), line);
                     }
+                };
+                if let Err(e) = run_session(&run_config, &executor, &mut on_line) {
+                    error!("Failed to spawn {aid}: {e}");
                 }
             });
-            handles.push((handle, wt_dir, agent_id));
+            handles.push((handle, wt_dir, agent_id, feature_id.clone()));
         }
 
         // Wait for all agents
-        for (handle, _, agent_id) in &handles {
+        for (handle, _, agent_id, _) in &handles {
             if handle.is_finished() {
                 continue;
             }
-            println!("  Waiting for {agent_id}...");
+            info!("Waiting for {agent_id}...");
         }
         // Actually join them
-        let worktree_dirs: Vec<(PathBuf, String)> = handles
+        let mut heads = Vec::new();
+        let worktree_dirs: Vec<(PathBuf, String, String)> = handles
             .into_iter()
-            .map(|(handle, wt_dir, agent_id)| {
+            .map(|(handle, wt_dir, agent_id, feature_id)| {
                 let _ = handle.join();
-                (wt_dir, agent_id)
+                if let Some(rev) = git::rev_parse(&config.project_dir, &agent_branch(&config.git, &agent_id, &feature_id)) {
+                    heads.push((feature_id.clone(), rev));
+                }
+                (wt_dir, agent_id, feature_id)
             })
             .collect();
 
         // Merge worktree branches back into main
-        for (wt_dir, agent_id) in &worktree_dirs {
-            let branch = format!("forge/{agent_id}");
-            if let Err(e) = merge_worktree(&config.project_dir, wt_dir, &branch) {
-                eprintln!("  Merge failed for {agent_id}: {e}");
+        let mut merged = Vec::new();
+        let mut refused = Vec::new();
+        let guard = CommitGuard::new(&config.git, &config.secrets);
+        // With [git] revert_regressions, what passes before each merge
+        let mut passing = config.git.revert_regressions.then(|| passing_features(&config.project_dir));
+        for (wt_dir, agent_id, feature_id) in &worktree_dirs {
+            let branch = agent_branch(&config.git, agent_id, feature_id);
+            if let Some(refusal) = check_branch(&config.project_dir, &branch, &config.git, &guard) {
+                let error = refusal.describe();
+                warn!("Not merging {branch} ({feature_id}): {error}");
+                events::emit_merge(&config.project_dir, &branch, &Err(error));
+                refused.push((branch, wt_dir, feature_id.clone(), refusal));
+                continue;
+            }
+            let message = CommitMessage::for_feature_id(&config.project_dir, feature_id).agent(agent_id).session(session);
+            let clash = config
+                .chaos
+                .as_ref()
+                .and_then(|chaos| chaos.inject_conflict(&config.project_dir, &branch, feature_id, &config.git));
+            let before = git::rev_parse(&config.project_dir, "HEAD");
+            let result = merge_worktree(&config.project_dir, wt_dir, &branch, &message, &config.git);
+            if let Some(clash) = &clash {
+                chaos::undo_conflict(&config.project_dir, clash, &config.git);
+            }
+            events::emit_merge(&config.project_dir, &branch, &result);
+            if let Err(e) = &result {
+                warn!("Merge failed for {agent_id}: {e}");
+                continue;
+            }
+            audit::sync(&config.project_dir, agent_id);
+            if let (Some(passing), Some(before)) = (&mut passing, &before) {
+                let now = passing_features(&config.project_dir);
+                let regressed: Vec<String> = passing.difference(&now).cloned().collect();
+                if !regressed.is_empty() {
+                    let undo = message.retitled("revert", format!("undo {branch}, it broke verify for {}", regressed.join(", ")));
+                    match git::revert_to(&config.project_dir, before, &undo.render(&config.git)) {
+                        Ok(()) => {
+                            warn!("Reverted {branch} ({feature_id}): verify regressed for {}", regressed.join(", "));
+                            refused.push((branch, wt_dir, feature_id.clone(), Refusal::Regressed(regressed)));
+                            continue;
+                        }
+                        Err(e) => warn!("Failed to revert {branch}: {e}"),
+                    }
+                }
+                *passing = now;
             }
+            merged.push(branch);
         }
 
-        // Clean up worktrees
-        for (wt_dir, agent_id) in &worktree_dirs {
-            if let Err(e) = git::remove_worktree(&config.project_dir, wt_dir) {
-                eprintln!("  Failed to remove worktree for {agent_id}: {e}");
+        // Clean up worktrees, then the branches that landed. Pooled
+        // worktrees stay, their build output with them; those whose work
+        // landed start the next session from HEAD.
+        for (wt_dir, agent_id, feature_id) in &worktree_dirs {
+            let branch = agent_branch(&config.git, agent_id, feature_id);
+            if !config.git.reuse_worktrees {
+                if let Err(e) = git::remove_worktree(&config.project_dir, wt_dir) {
+                    warn!("Failed to remove worktree for {agent_id}: {e}");
+                }
+            } else if merged.contains(&branch)
+                && let Err(e) = recycle_worktree(&config.project_dir, wt_dir, &branch)
+            {
+                warn!("Failed to reset worktree for {agent_id}: {e}");
+            }
+        }
+        for (branch, wt_dir, feature_id, refusal) in &refused {
+            let pooled = config.git.reuse_worktrees.then_some(wt_dir.as_path());
+            match park_branch(&config.project_dir, branch, feature_id, refusal, pooled) {
+                Ok(parked) => info!("Work for {feature_id} kept on {parked}"),
+                Err(e) => warn!("Failed to set {branch} aside: {e}"),
+            }
+            reopen_refused(&config.project_dir, feature_id, refusal);
+        }
+        // A pooled worktree's branch was just reset; it can't be deleted
+        if config.git.delete_merged_branches && !config.git.reuse_worktrees {
+            for branch in &merged {
+                if let Err(e) = delete_merged_branch(&config.project_dir, branch, &config.git) {
+                    warn!("Failed to delete {branch}: {e}");
+                }
             }
         }
 
         // --- Protocol compliance checks ---
         for (fid, _) in &feature_entries {
-            check_protocol_compliance(&config.project_dir, fid);
+            check_protocol_compliance(&config.project_dir, fid, session_start);
         }
 
         // --- Verify ---
-        println!("  Running post-session verify...");
-        match verify::verify_all(&config.project_dir) {
+        info!("Running post-session verify...");
+        if let Some(chaos) = &config.chaos {
+            chaos.delay_verify(&config.project_dir);
+        }
+        let results = match verify::verify_all(&config.project_dir) {
             Ok(results) => {
                 for result in &results {
                     let status = if result.passed { "PASS" } else { "FAIL" };
-                    println!("  [{status}] {}", result.feature_id);
+                    info!(feature = %result.feature_id, passed = result.passed, "[{status}] {}", result.feature_id);
                 }
 
                 let report = verify::VerifyReport::from_results(&results);
                 if let Err(e) = report.write(&config.project_dir) {
-                    eprintln!("  Failed to write verify report: {e}");
+                    warn!("Failed to write verify report: {e}");
                 }
 
+                let mut reopened = Vec::new();
                 if let Ok(mut features) = FeatureList::load(&config.project_dir) {
-                    let mut changed = false;
                     for result in &results {
-                        if !result.passed {
-                            if let Ok(()) = features.reopen(&result.feature_id) {
-                                println!("  Reopened {} (verify failed)", result.feature_id);
-                                changed = true;
-                            }
+                        if !result.passed
+                            && let Ok(()) = features.reopen(&result.feature_id)
+                        {
+                            info!("Reopened {} (verify failed)", result.feature_id);
+                            reopened.push(result.feature_id.clone());
                         }
                     }
-                    if changed {
+                    if !reopened.is_empty() {
                         let _ = features.save(&config.project_dir);
                     }
                 }
+                emit_verify(&config.project_dir, &results, reopened);
+                results
             }
-            Err(e) => eprintln!("  Verify error: {e}"),
-        }
+            Err(e) => {
+                error!("Verify error: {e}");
+                Vec::new()
+            }
+        };
+
+        // --- Pull requests, milestone tags ---
+        pr::publish(&config.project_dir, &config.git, &heads, &results);
+        tags::tag_milestones(&config.project_dir, &config.git, &feature_ids, &results);
+        crate::questions::escalate(&config.project_dir, &feature_ids);
+        emit_feature_outcomes(&config.project_dir, &feature_ids, &results);
 
         // --- Git sync ---
         if let Err(e) = git::pull(&config.project_dir) {
-            eprintln!("  Git pull warning: {e}");
+            warn!("Git pull warning: {e}");
         }
 
         // --- Orchestrating review ---
-        println!("  Dispatching orchestrating review...");
+        info!("Dispatching orchestrating review...");
         let fids_str = feature_ids.join(", ");
         let orch_prompt = format!(
             "You are a forge orchestrating agent. Follow the forge-orchestrating skill. \
@@ -535,102 +841,899 @@ pub fn run_multi_agent(config: &RunConfig) -> RunOutcome {
              Write feedback/session-review.md and any context entries. Then commit and exit."
         );
 
-        match spawn_agent(
-            &config.orchestrating,
-            &config.project_dir,
-            &orch_prompt,
-            "orchestrator",
-        ) {
-            Ok(mut child) => {
-                if let Some(stdout) = child.stdout.take() {
-                    let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        if line.is_err() {
-                            break;
-                        }
-                    }
+        let review = Session {
+            role: &config.orchestrating,
+            kind: "orchestrating",
+            cwd: &config.project_dir,
+            prompt: &orch_prompt,
+            agent_id: "orchestrator",
+            feature_id: None,
+            variant: None,
+        };
+        if let Err(e) = run_session(config, &review, &mut |_| {}) {
+            warn!("Orchestrating dispatch failed (non-fatal): {e}");
+        }
+        crate::reviews::archive(&config.project_dir, session);
+        commit_state(&config.project_dir, &config.git, session, &feature_ids);
+
+        session += 1;
+    }
+}
+
+/// Pushes of forge state before giving up on a remote that keeps moving.
+const STATE_PUSH_ATTEMPTS: usize = 3;
+
+/// Files forge itself writes during a session: feature status, verify
+/// reports and exec-memory, context entries.
+pub(crate) const STATE_PATHS: &[&str] = &["features.json", "feedback", "context"];
+
+/// With `[git] commit_state`, commit forge's files as the session left them,
+/// and with `push_state` push the branch. Failures are only printed.
+fn commit_state(project_dir: &Path, git_config: &GitConfig, session: usize, feature_ids: &[String]) {
+    if !git_config.commit_state || !git::is_git_repo(project_dir) {
+        return;
+    }
+    let paths: Vec<&str> = STATE_PATHS.iter().copied().filter(|p| project_dir.join(p).exists()).collect();
+    let mut message = CommitMessage::new("chore", "update forge state").session(session);
+    match feature_ids {
+        [] => {}
+        [id] => message.feature_id = Some(id.clone()),
+        ids => message.summary = format!("update forge state for {}", ids.join(", ")),
+    }
+    match git::commit_paths(project_dir, &paths, &message.render(git_config)) {
+        Ok(true) => info!("Committed forge state"),
+        Ok(false) => {}
+        Err(e) => {
+            warn!("Committing forge state failed (non-fatal): {e}");
+            return;
+        }
+    }
+    if git_config.push_state
+        && let Err(e) = git::push_with_retry(project_dir, STATE_PUSH_ATTEMPTS)
+    {
+        warn!("Pushing forge state failed (non-fatal): {e}");
+    }
+}
+
+pub(crate) fn emit_verify(project_dir: &Path, results: &[verify::VerifyResult], reopened: Vec<String>) {
+    let passed = results.iter().filter(|r| r.passed).count();
+    events::emit(
+        project_dir,
+        events::FORGE,
+        EventKind::Verify {
+            passed,
+            failed: results.len() - passed,
+            reopened,
+        },
+    );
+}
+
+/// Events for how this session's features ended: verify's verdict, then
+/// blocked, or a milestone's review done and passing.
+fn emit_feature_outcomes(project_dir: &Path, feature_ids: &[String], results: &[verify::VerifyResult]) {
+    let Ok(list) = FeatureList::load(project_dir) else {
+        return;
+    };
+    let passed = |id: &str| results.iter().any(|r| r.feature_id == id && r.passed);
+    for feature in list.features.iter().filter(|f| feature_ids.contains(&f.id)) {
+        if results.iter().any(|r| r.feature_id == feature.id) {
+            let kind = EventKind::FeatureVerified { feature_id: feature.id.clone(), passed: passed(&feature.id) };
+            events::emit(project_dir, events::FORGE, kind);
+        }
+        let kind = match feature.status {
+            FeatureStatus::Blocked => EventKind::FeatureBlocked {
+                feature_id: feature.id.clone(),
+                reason: feature.blocked_reason.clone(),
+            },
+            FeatureStatus::Done if feature.feature_type == FeatureType::Review && passed(&feature.id) => {
+                EventKind::MilestoneDone {
+                    feature_id: feature.id.clone(),
+                    milestone: FeatureList::milestone_label(feature),
                 }
-                let _ = child.wait();
             }
-            Err(e) => {
-                eprintln!("  Orchestrating dispatch failed (non-fatal): {e}");
+            _ => continue,
+        };
+        events::emit(project_dir, events::FORGE, kind);
+    }
+}
+
+/// Once the project has spent `max_total_cost`, the outcome that ends the
+/// run, recorded as a `CostLimitReached` event. Checked before each round,
+/// so sessions already running finish first.
+fn cost_limit(config: &RunConfig, sessions: usize) -> Option<RunOutcome> {
+    let limit_usd = config.max_total_cost?;
+    let spent_usd = crate::cost::total_spent(&config.project_dir);
+    if spent_usd < limit_usd {
+        return None;
+    }
+    error!("Project spend ${spent_usd:.2} reached max_total_cost ${limit_usd:.2}; starting no more sessions");
+    events::emit(&config.project_dir, events::FORGE, EventKind::CostLimitReached { spent_usd, limit_usd });
+    Some(RunOutcome::CostLimit { sessions, spent_usd, limit_usd })
+}
+
+/// Why runs are refused, for `forge status`: the last `CostLimitReached`
+/// event, unless a session has started since (the limit was raised).
+pub fn cost_limit_reason(project_dir: &Path) -> Option<String> {
+    let timeline = events::merged_timeline(project_dir).unwrap_or_default();
+    for event in timeline.iter().rev() {
+        match &event.kind {
+            EventKind::SessionStart { .. } => return None,
+            EventKind::CostLimitReached { spent_usd, limit_usd } => {
+                let at = event.ts.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+                return Some(format!(
+                    "project spend ${spent_usd:.2} reached max_total_cost ${limit_usd:.2} at {at}; \
+                     raise [forge] max_total_cost in forge.toml to start more sessions"
+                ));
             }
+            _ => {}
         }
-
-        session += 1;
     }
+    None
+}
+
+/// Record how `forge run` ended.
+pub fn emit_run_finished(project_dir: &Path, outcome: &RunOutcome) {
+    let left = || {
+        FeatureList::load(project_dir)
+            .map(|list| list.features.iter().filter(|f| f.status != FeatureStatus::Done).count())
+            .unwrap_or(0)
+    };
+    let (sessions, remaining) = match outcome {
+        RunOutcome::AllDone { sessions } => (*sessions, 0),
+        RunOutcome::MaxSessions { sessions, remaining } => (*sessions, *remaining),
+        RunOutcome::Stopped { sessions } | RunOutcome::CostLimit { sessions, .. } => (*sessions, left()),
+        RunOutcome::SpawnError(_) => (0, left()),
+    };
+    let kind = EventKind::RunFinished { outcome: outcome.name().into(), sessions, remaining };
+    events::emit(project_dir, events::FORGE, kind);
 }
 
-/// Merge a worktree branch back into the current branch.
-fn merge_worktree(repo_dir: &Path, _wt_dir: &Path, branch: &str) -> Result<(), String> {
-    // First commit any changes in the worktree (the agent may have left uncommitted work)
+/// Merge a worktree branch back into the current branch by the configured
+/// `merge_strategy`, then reconcile the context entries the agent wrote
+/// there. Conflicts only under context/ don't abort the merge: our side is
+/// kept and the worktree's lines are appended by the reconcile step instead.
+/// `message` describes the merged work; it heads the merge or squash commit
+/// and the reconcile commit.
+pub(crate) fn merge_worktree(
+    repo_dir: &Path,
+    wt_dir: &Path,
+    branch: &str,
+    message: &CommitMessage,
+    git_config: &GitConfig,
+) -> Result<(), String> {
+    let subject = message.render(git_config);
+    let strategy = git_config.merge_strategy;
     // The worktree is on its own branch, so we merge that branch into main
+    let base = git::merge_base(repo_dir, "HEAD", branch);
+
+    // Rebase in the worktree (it has the branch checked out). Uncommitted
+    // work is stashed around it so the reconcile step still sees it.
+    let rebased = strategy == MergeStrategy::Rebase
+        && git::rev_parse(repo_dir, "HEAD").is_some_and(|head| {
+            git::run(wt_dir, &["rebase", "--autostash", &head]).is_ok() || {
+                let _ = git::run(wt_dir, &["rebase", "--abort"]);
+                false
+            }
+        });
+    let mut args = vec!["merge"];
+    match strategy {
+        MergeStrategy::Rebase if rebased => args.push("--ff-only"),
+        MergeStrategy::MergeCommit => args.push("--no-ff"),
+        MergeStrategy::Squash => args.push("--squash"),
+        _ => {}
+    }
+    args.push(branch);
+    if strategy != MergeStrategy::Squash {
+        args.extend(["-m", &subject]);
+    }
     let output = Command::new("git")
-        .args(["merge", branch, "--no-edit"])
+        .args(&args)
         .current_dir(repo_dir)
         .output()
         .map_err(|e| format!("git merge failed: {e}"))?;
 
+    let squash = strategy == MergeStrategy::Squash;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        // Abort the merge on conflict
-        let _ = Command::new("git")
-            .args(["merge", "--abort"])
-            .current_dir(repo_dir)
-            .output();
-        return Err(format!("merge conflict: {stderr}"));
+        let conflicts = git::conflicted_paths(repo_dir);
+        let context_only = !conflicts.is_empty() && conflicts.iter().all(|p| p.starts_with("context/"));
+        if !context_only
+            || !take_ours(repo_dir, &conflicts)
+            || !(squash || git::run(repo_dir, &["commit", "--no-edit"]).is_ok())
+        {
+            // Abort the merge on conflict; a squash leaves no MERGE_HEAD to abort
+            let abort: &[&str] = if squash { &["reset", "--merge"] } else { &["merge", "--abort"] };
+            let _ = git::run(repo_dir, abort);
+            let (Some(base), false) = (&base, conflicts.is_empty()) else {
+                return Err(format!("merge conflict: {stderr}"));
+            };
+            let report = ConflictReport::collect(repo_dir, branch, base, &conflicts, message.feature_id.as_deref());
+            let written = match report.write(repo_dir) {
+                Ok(path) => format!("; see {}", path.strip_prefix(repo_dir).unwrap_or(&path).display()),
+                Err(_) => String::new(),
+            };
+            return Err(format!("merge conflict in {}{written}", conflicts.join(", ")));
+        }
+    }
+    if let Some(id) = &message.feature_id {
+        merge_conflicts::clear(repo_dir, id);
+    }
+    // A squash stages the branch's changes without committing them
+    if squash
+        && git::run(repo_dir, &["diff", "--cached", "--quiet"]).is_err()
+        && git::run(repo_dir, &["commit", "-m", &subject]).is_err()
+    {
+        let _ = git::run(repo_dir, &["reset", "--merge"]);
+        return Err("squash commit failed".into());
+    }
+
+    match crate::context_reconcile::reconcile(repo_dir, wt_dir, base.as_deref(), branch) {
+        Ok(report) if !report.is_empty() => {
+            let reconcile = message.retitled("docs", format!("reconcile context from {branch}"));
+            let _ = git::commit_paths(repo_dir, &["context"], &reconcile.render(git_config));
+            events::emit(
+                repo_dir,
+                events::FORGE,
+                EventKind::ContextReconciled {
+                    branch: branch.to_string(),
+                    report,
+                },
+            );
+        }
+        Ok(_) => {}
+        Err(e) => return Err(format!("context reconcile failed: {e}")),
     }
     Ok(())
 }
 
-/// Build the command and arguments for spawning an agent interactively (no --print/exec).
-/// Used by the TUI --watch mode to spawn agents in a PTY.
-pub fn build_agent_command(role: &RoleSpec, prompt: &str) -> (String, Vec<String>) {
-    match role.backend.as_str() {
-        "claude" => (
-            "claude".to_string(),
-            vec![
-                "--model".to_string(),
-                role.model.clone(),
-                "--dangerously-skip-permissions".to_string(),
-                prompt.to_string(),
-            ],
+/// Resolve conflicted paths to our side, leaving the merge to be concluded.
+fn take_ours(repo_dir: &Path, paths: &[String]) -> bool {
+    paths.iter().all(|p| {
+        git::run(repo_dir, &["checkout", "--ours", "--", p]).is_ok() && git::run(repo_dir, &["add", "--", p]).is_ok()
+    })
+}
+
+/// Delete a merged agent branch. A squashed branch never becomes an
+/// ancestor of ours, so git has to be told it's safe.
+pub(crate) fn delete_merged_branch(repo_dir: &Path, branch: &str, git_config: &GitConfig) -> Result<(), String> {
+    git::delete_branch(repo_dir, branch, git_config.merge_strategy == MergeStrategy::Squash)
+}
+
+/// Why an agent branch isn't merged.
+#[derive(Debug)]
+pub(crate) enum Refusal {
+    /// `[git] protected` paths it changed.
+    Protected(Vec<String>),
+    /// What the secret scan found in lines it adds.
+    Secrets(Vec<Finding>),
+    /// Files it adds over `[git] max_file_mb`.
+    LargeFiles(Vec<LargeFile>),
+    /// Features whose verify it broke; it was merged, then reverted.
+    Regressed(Vec<String>),
+}
+
+impl Refusal {
+    fn kind(&self) -> &'static str {
+        match self {
+            Refusal::Protected(_) => "protected",
+            Refusal::Secrets(_) => "secrets",
+            Refusal::LargeFiles(_) => "large-files",
+            Refusal::Regressed(_) => "reverted",
+        }
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Refusal::Protected(paths) => format!("changes protected paths: {}", paths.join(", ")),
+            Refusal::Secrets(findings) => format!("adds what look like secrets: {}", secret_scan::summary(findings)),
+            Refusal::LargeFiles(files) => format!("adds files over the size limit: {}", large_files::summary(files)),
+            Refusal::Regressed(ids) => format!("broke verify for {}", ids.join(", ")),
+        }
+    }
+}
+
+/// Check what an agent branch changed since it forked against `[git]
+/// protected`, the file size limit and the secret scan.
+pub(crate) fn check_branch(repo_dir: &Path, branch: &str, git_config: &GitConfig, guard: &CommitGuard) -> Option<Refusal> {
+    let base = git::merge_base(repo_dir, "HEAD", branch)?;
+    if !git_config.protected.is_empty() {
+        let touched = git::protected_paths(&git::changed_paths(repo_dir, &base, Some(branch)), &git_config.protected);
+        if !touched.is_empty() {
+            return Some(Refusal::Protected(touched));
+        }
+    }
+    let oversized = large_files::in_range(repo_dir, &base, branch, guard.max_file_bytes);
+    if !oversized.is_empty() {
+        return Some(Refusal::LargeFiles(oversized));
+    }
+    match guard.scanner.scan_changes(repo_dir, &base, Some(branch)) {
+        Ok(findings) if !findings.is_empty() => Some(Refusal::Secrets(findings)),
+        Ok(_) => None,
+        Err(e) => Some(Refusal::Secrets(vec![Finding { path: branch.into(), line: 0, rule: e }])),
+    }
+}
+
+/// Move a refused agent branch to `forge/<kind>/<feature>`, e.g.
+/// `forge/secrets/f004`, so
+/// the agent's next worktree doesn't start from its work. Its worktree must
+/// be gone, or be passed as `pooled` to start over from HEAD. Returns where
+/// the work is now.
+pub(crate) fn park_branch(
+    repo_dir: &Path,
+    branch: &str,
+    feature_id: &str,
+    refusal: &Refusal,
+    pooled: Option<&Path>,
+) -> Result<String, String> {
+    let parked = format!("forge/{}/{feature_id}", refusal.kind());
+    git::set_branch(repo_dir, &parked, branch)?;
+    match pooled {
+        Some(wt_dir) => recycle_worktree(repo_dir, wt_dir, branch)?,
+        None => git::delete_branch(repo_dir, branch, true)?,
+    }
+    Ok(parked)
+}
+
+/// Features whose verify passes right now.
+fn passing_features(project_dir: &Path) -> BTreeSet<String> {
+    verify::verify_all(project_dir)
+        .map(|results| results.into_iter().filter(|r| r.passed).map(|r| r.feature_id).collect())
+        .unwrap_or_default()
+}
+
+/// Ready a pooled worktree from an earlier session: cleaned, and on
+/// `branch`, which starts at HEAD if it's new. False if it can't be reused.
+fn reuse_worktree(repo_dir: &Path, wt_dir: &Path, branch: &str) -> bool {
+    if !wt_dir.join(".git").exists() || git::reset_worktree(wt_dir, branch, None).is_err() {
+        return false;
+    }
+    if git::current_branch(wt_dir).as_deref() == Some(branch) {
+        return true;
+    }
+    // Another feature's branch, under a `{feature_id}` branch template
+    let start = if git::rev_parse(repo_dir, branch).is_some() { Some(branch.to_string()) } else { git::rev_parse(repo_dir, "HEAD") };
+    start.is_some_and(|start| git::reset_worktree(wt_dir, branch, Some(&start)).is_ok())
+}
+
+/// Point a pooled worktree's branch at the project's HEAD for its next
+/// session. Ignored files (build output) are kept.
+fn recycle_worktree(repo_dir: &Path, wt_dir: &Path, branch: &str) -> Result<(), String> {
+    let head = git::rev_parse(repo_dir, "HEAD").ok_or("no HEAD to reset to")?;
+    git::reset_worktree(wt_dir, branch, Some(&head))
+}
+
+/// Add a failed attempt to `feedback/exec-memory/<id>.json`, where the next
+/// agent on the feature reads what went wrong before.
+fn record_failed_attempt(project_dir: &Path, feature_id: &str, summary: &str, reason: &str) {
+    let path = project_dir.join(format!("feedback/exec-memory/{feature_id}.json"));
+    let mut memory = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .filter(serde_json::Value::is_object)
+        .unwrap_or_else(|| serde_json::json!({ "feature_id": feature_id }));
+    let attempts = memory
+        .as_object_mut()
+        .expect("checked above")
+        .entry("attempts")
+        .or_insert_with(|| serde_json::json!([]));
+    if let Some(attempts) = attempts.as_array_mut() {
+        attempts.push(serde_json::json!({
+            "number": attempts.len() + 1,
+            "summary": summary,
+            "failed_reason": reason,
+            "discoveries": [],
+        }));
+    }
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&memory) {
+        let _ = fs::write(&path, json + "\n");
+    }
+}
+
+/// Reopen a feature whose work was refused, saying why in its exec-memory
+/// and the event log.
+pub(crate) fn reopen_refused(project_dir: &Path, feature_id: &str, refusal: &Refusal) {
+    if let Ok(mut features) = FeatureList::load(project_dir)
+        && features.reopen(feature_id).is_ok()
+    {
+        let _ = features.save(project_dir);
+    }
+    let (summary, reason, event) = match refusal {
+        Refusal::Protected(paths) => (
+            "Work was not accepted: it changed protected paths",
+            format!(
+                "[git] protected in forge.toml forbids changing {}. Reach the goal without touching them, \
+                 or block the feature and explain why they must change.",
+                paths.join(", ")
+            ),
+            EventKind::ProtectedPaths { feature_id: feature_id.to_string(), paths: paths.clone() },
         ),
-        "codex" => (
-            "codex".to_string(),
-            vec![
-                "--model".to_string(),
-                role.model.clone(),
-                "--full-auto".to_string(),
-                prompt.to_string(),
-            ],
+        Refusal::Secrets(findings) => (
+            "Work was not accepted: it adds what look like secrets",
+            format!(
+                "The secret scan flagged {}. Read credentials from the environment instead of writing them \
+                 into files; for fake test keys, ask for the path to go in [git] secret_scan_skip.",
+                secret_scan::summary(findings)
+            ),
+            EventKind::SecretsFound {
+                feature_id: feature_id.to_string(),
+                findings: findings.iter().map(Finding::to_string).collect(),
+            },
+        ),
+        Refusal::LargeFiles(files) => {
+            large_files::record_gotcha(project_dir, files);
+            (
+                "Work was not accepted: it adds files over the size limit",
+                format!(
+                    "{} are over [git] max_file_mb. Add build output to .gitignore, or track large assets \
+                     with Git LFS (`git lfs track`), and take them out of the branch's history.",
+                    large_files::summary(files)
+                ),
+                EventKind::LargeFiles {
+                    feature_id: feature_id.to_string(),
+                    files: files.iter().map(LargeFile::to_string).collect(),
+                },
+            )
+        }
+        Refusal::Regressed(ids) => (
+            "Work was reverted: it broke other features",
+            format!(
+                "After the merge, verify failed for {}, which passed before, so the merge was reverted. \
+                 Run their verify commands too before marking this feature done.",
+                ids.join(", ")
+            ),
+            EventKind::MergeReverted { feature_id: feature_id.to_string(), regressed: ids.clone() },
         ),
-        _ => (role.backend.clone(), vec![prompt.to_string()]),
+    };
+    record_failed_attempt(project_dir, feature_id, summary, &reason);
+    events::emit(project_dir, events::FORGE, event);
+}
+
+/// In single-agent runs the agent commits straight to the project branch:
+/// put protected paths it changed since `start` back, and reopen the feature.
+fn revert_protected(config: &RunConfig, feature_id: &str, start: &str) {
+    let touched = git::protected_paths(&git::changed_paths(&config.project_dir, start, None), &config.git.protected);
+    if touched.is_empty() {
+        return;
+    }
+    info!("{feature_id} changed protected paths, reverting: {}", touched.join(", "));
+    if let Err(e) = git::restore_paths(&config.project_dir, start, &touched) {
+        warn!("Failed to revert protected paths: {e}");
+        return;
+    }
+    reopen_refused(&config.project_dir, feature_id, &Refusal::Protected(touched.clone()));
+    let message = CommitMessage::for_feature_id(&config.project_dir, feature_id)
+        .agent("agent-1")
+        .retitled("revert", "undo changes to protected paths");
+    let mut paths: Vec<&str> = touched.iter().map(String::as_str).collect();
+    paths.extend(["features.json", "feedback/exec-memory"]);
+    if let Err(e) = git::commit_paths(&config.project_dir, &paths, &message.render(&config.git)) {
+        warn!("Failed to commit the revert: {e}");
     }
 }
 
-/// Spawn an agent child process using the role's backend + model.
-fn spawn_agent(
+/// In single-agent runs the agent's commits are already on the project
+/// branch, so a secret can't be kept out: say so loudly and reopen the
+/// feature, which also keeps it out of any pull request.
+fn flag_secrets(config: &RunConfig, feature_id: &str, start: &str) {
+    let scanner = SecretScanner::new(&config.git, &config.secrets);
+    let findings = match scanner.scan_changes(&config.project_dir, start, None) {
+        Ok(findings) if !findings.is_empty() => findings,
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Secret scan failed: {e}");
+            return;
+        }
+    };
+    error!(feature = %feature_id, "{feature_id} added what look like secrets: {}", secret_scan::summary(&findings));
+    error!("They are in local history; rotate them and rewrite it before pushing.");
+    reopen_refused(&config.project_dir, feature_id, &Refusal::Secrets(findings));
+}
+
+/// A resolved agent invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// From `[backends.<name>] env`.
+    pub env: BTreeMap<String, String>,
+}
+
+/// Build the command and arguments for spawning an agent interactively (no --print/exec).
+/// Used by the TUI --watch mode to spawn agents in a PTY. A `[backends.<name>]`
+/// definition wins over the built-in flags for that name. Claude skips
+/// permission checks only with `Permissions::Bypass`; otherwise it follows
+/// `.claude/settings.json`.
+pub fn build_agent_command(
     role: &RoleSpec,
-    project_dir: &Path,
+    backends: &BTreeMap<String, BackendDef>,
+    permissions: Permissions,
     prompt: &str,
-    agent_id: &str,
-) -> Result<Child, std::io::Error> {
-    let (cmd, mut args) = build_agent_command(role, prompt);
+) -> AgentCommand {
+    if let Some(def) = backends.get(&role.backend) {
+        let template = if def.interactive_args.is_empty() { &def.headless_args } else { &def.interactive_args };
+        return custom_command(&role.backend, def, template, role, prompt);
+    }
+    let mut args = Vec::new();
+    let mut env = BTreeMap::new();
+    // Settings a CLI has no flag for are left out; `forge config validate` says which
+    match role.backend.as_str() {
+        "claude" => {
+            args.extend(["--model".to_string(), role.model.clone()]);
+            if permissions == Permissions::Bypass {
+                args.push("--dangerously-skip-permissions".to_string());
+            }
+            if let Some(tokens) = role.max_output_tokens {
+                env.insert("CLAUDE_CODE_MAX_OUTPUT_TOKENS".to_string(), tokens.to_string());
+            }
+        }
+        "codex" => {
+            args.extend(["--model".to_string(), role.model.clone(), "--full-auto".to_string()]);
+            if let Some(tokens) = role.max_output_tokens {
+                args.push("-c".to_string());
+                args.push(format!("model_max_output_tokens={tokens}"));
+            }
+        }
+        _ => {}
+    }
+    args.push(prompt.to_string());
+    AgentCommand { program: role.backend.clone(), args, env }
+}
+
+/// Fill a `[backends.<name>]` args template for one role and prompt.
+fn custom_command(name: &str, def: &BackendDef, template: &[String], role: &RoleSpec, prompt: &str) -> AgentCommand {
+    let mut args = Vec::new();
+    if let Some(flag) = &def.model_flag
+        && !role.model.is_empty()
+    {
+        args.push(flag.clone());
+        args.push(role.model.clone());
+    }
+    for (flag, value) in [
+        (&def.max_turns_flag, role.max_turns.map(|n| n.to_string())),
+        (&def.temperature_flag, role.temperature.map(|t| t.to_string())),
+        (&def.max_output_tokens_flag, role.max_output_tokens.map(|n| n.to_string())),
+        (&def.budget_flag, role.budget.map(|b| b.to_string())),
+    ] {
+        if let (Some(flag), Some(value)) = (flag, value) {
+            args.push(flag.clone());
+            args.push(value);
+        }
+    }
+    // Prompt last, so braces in it are never taken for placeholders
+    args.extend(template.iter().map(|a| a.replace("{model}", &role.model).replace("{prompt}", prompt)));
+    if !template.iter().any(|a| a.contains("{prompt}")) {
+        args.push(prompt.to_string());
+    }
+    AgentCommand { program: def.command(name).to_string(), args, env: def.env.clone() }
+}
 
-    // For headless mode, add --print (claude) or exec prefix (codex)
+/// The command for a session without a terminal: `--print` for claude,
+/// `exec` for codex, `headless_args` for a `[backends]` entry.
+fn build_headless_command(
+    role: &RoleSpec,
+    backends: &BTreeMap<String, BackendDef>,
+    permissions: Permissions,
+    prompt: &str,
+) -> AgentCommand {
+    if let Some(def) = backends.get(&role.backend) {
+        return custom_command(&role.backend, def, &def.headless_args, role, prompt);
+    }
+    let mut command = build_agent_command(role, backends, permissions, prompt);
     match role.backend.as_str() {
         "claude" => {
-            args.insert(0, "--print".to_string());
+            command.args.insert(0, "--print".to_string());
+            // Only print mode honours turn and spending limits
+            if let Some(turns) = role.max_turns {
+                command.args.splice(1..1, ["--max-turns".to_string(), turns.to_string()]);
+            }
+            if let Some(budget) = role.budget {
+                command.args.splice(1..1, ["--max-budget-usd".to_string(), budget.to_string()]);
+            }
         }
         "codex" => {
-            args.insert(0, "exec".to_string());
+            command.args.insert(0, "exec".to_string());
         }
         _ => {}
     }
+    command
+}
 
-    Command::new(&cmd)
-        .args(&args)
+/// One agent session: who runs it, where, and on what.
+struct Session<'a> {
+    role: &'a RoleSpec,
+    /// "executor", "orchestrating" or "planning", as recorded in events.
+    kind: &'a str,
+    cwd: &'a Path,
+    prompt: &'a str,
+    agent_id: &'a str,
+    feature_id: Option<&'a str>,
+    /// `a` or `b` for executors in an A/B run.
+    variant: Option<&'a str>,
+}
+
+/// The executor role for agent slot `slot` (1-based), and its variant in
+/// an A/B run: with `protocol_b` set, even slots get B.
+pub(crate) fn executor_role(config: &RunConfig, slot: usize) -> (&RoleSpec, Option<&'static str>) {
+    match &config.protocol_b {
+        Some(b) if slot.is_multiple_of(2) => (b, Some("b")),
+        Some(_) => (&config.protocol, Some("a")),
+        None => (&config.protocol, None),
+    }
+}
+
+/// One session of `role` outside the loop, with the role's timeout and
+/// retries, on no feature: how `forge plan` runs the planning agent.
+pub(crate) fn run_one_off(
+    config: &RunConfig,
+    role: &RoleSpec,
+    kind: &str,
+    prompt: &str,
+    agent_id: &str,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<Option<ExitStatus>, std::io::Error> {
+    let session =
+        Session { role, kind, cwd: &config.project_dir, prompt, agent_id, feature_id: None, variant: None };
+    run_session(config, &session, on_line)
+}
+
+/// Rate-limited attempts a session gets on top of its `retries`.
+const MAX_THROTTLED: u32 = 5;
+
+/// Run a session to the end, passing its stdout lines to `on_line`. It's
+/// killed once the role's `timeout_secs` pass, and started again up to
+/// `retries` times while it fails. An attempt that fails on a rate limit
+/// backs its backend off and doesn't count: the next one runs on the
+/// backend's fallback, or waits the backoff out. Only failing to spawn the
+/// first attempt is an error; the status is the last attempt's.
+fn run_session(
+    config: &RunConfig,
+    session: &Session,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<Option<ExitStatus>, std::io::Error> {
+    let attempts = session.role.retries.unwrap_or(0) + 1;
+    let timeout = session.role.timeout_secs.map(Duration::from_secs);
+    let baseline = config.runaway.enabled.then(|| runaway::baseline(&config.project_dir)).flatten();
+    // Agents write features.json themselves, and sometimes break it
+    if let Err(e) = features_backup::backup(&config.project_dir) {
+        warn!("failed to back up features.json: {e}");
+    }
+    let mut last = None;
+    let (mut attempt, mut throttled) = (1, 0);
+    while attempt <= attempts {
+        let role = match rate_limit::route(&config.project_dir, &config.rate_limits, session.role) {
+            Route::Run(role) => role,
+            Route::Wait(wait) => {
+                let secs = wait.as_secs();
+                warn!(agent = session.agent_id, "{} is rate limited; waiting {secs}s", session.role.backend);
+                if !wait_out(&config.project_dir, wait) {
+                    break;
+                }
+                continue;
+            }
+        };
+        if role.backend != session.role.backend {
+            info!(agent = session.agent_id, "{} is rate limited; running on {}", session.role.backend, role.backend);
+            let kind = EventKind::BackendRerouted {
+                from: session.role.backend.clone(),
+                to: role.backend.clone(),
+                role: session.kind.into(),
+                feature_id: session.feature_id.map(String::from),
+            };
+            events::emit(&config.project_dir, session.agent_id, kind);
+        }
+        if attempt > 1 {
+            info!(agent = session.agent_id, attempt, "Retrying {} (attempt {attempt}/{attempts})", session.agent_id);
+        }
+        let spawned = spawn_agent(
+            &role,
+            &config.backends,
+            config.permissions,
+            &config.secrets,
+            session.cwd,
+            session.prompt,
+            session.agent_id,
+        );
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) if attempt == 1 => return Err(e),
+            Err(e) => {
+                warn!("Failed to respawn {}: {e}", session.agent_id);
+                break;
+            }
+        };
+        let feature_id = session.feature_id.map(String::from);
+        events::emit(
+            &config.project_dir,
+            session.agent_id,
+            EventKind::SessionStart {
+                role: session.kind.into(),
+                feature_id: feature_id.clone(),
+                backend: Some(role.backend.clone()),
+                model: Some(role.model.clone()),
+                variant: session.variant.map(String::from),
+            },
+        );
+        let stderr = child.stderr.take().map(rate_limit::watch);
+        let mut sighting = Sighting::default();
+        let mut over_budget = false;
+        let mut output_bytes = 0;
+        let mut detector = config.runaway.enabled.then(|| runaway::Detector::new(&config.runaway, baseline));
+        let mut runaway = None;
+        let mut watch = |line: &str| {
+            if !over_budget && role.budget.is_some() && BUDGET_EXCEEDED.is_match(line) {
+                over_budget = true;
+            }
+            sighting.feed(line);
+            on_line(line);
+            output_bytes += line.len() as u64 + 1;
+            if runaway.is_none()
+                && let Some(detector) = &mut detector
+            {
+                runaway = detector.feed(line);
+            }
+            runaway.is_none()
+        };
+        // A chaos kill comes in as a timeout that's shorter than the real one
+        let kill_after = config.chaos.as_ref().and_then(|c| c.kill_after()).filter(|k| timeout.is_none_or(|t| *k < t));
+        let (status, timed_out) = wait_streaming(&mut child, kill_after.or(timeout), &mut watch);
+        let killed = timed_out && kill_after.is_some();
+        let timed_out = timed_out && !killed;
+        if killed {
+            let secs = kill_after.unwrap_or_default().as_secs_f64();
+            warn!(agent = session.agent_id, "chaos: killed {} after {secs:.1}s", session.agent_id);
+            chaos::record(&config.project_dir, chaos::KILL, session.feature_id);
+        }
+        if over_budget {
+            events::emit(
+                &config.project_dir,
+                session.agent_id,
+                EventKind::BudgetExceeded { feature_id: feature_id.clone(), budget: role.budget },
+            );
+        }
+        if let Some(reason) = &runaway {
+            warn!(agent = session.agent_id, "{} killed as a runaway: {reason}", session.agent_id);
+            block_runaway(&config.project_dir, session, reason);
+        }
+        events::emit(
+            &config.project_dir,
+            session.agent_id,
+            EventKind::SessionEnd {
+                feature_id,
+                exit_code: status.as_ref().ok().and_then(|s| s.code()),
+                timed_out,
+                output_bytes: Some(output_bytes),
+            },
+        );
+        if timed_out {
+            let secs = timeout.unwrap_or_default().as_secs();
+            warn!(agent = session.agent_id, "{} timed out after {secs}s and was killed", session.agent_id);
+        }
+        let succeeded = !timed_out && !killed && status.as_ref().is_ok_and(|s| s.success());
+        last = status.ok();
+        // Another attempt would only loop the same way
+        if succeeded || runaway.is_some() {
+            if succeeded {
+                rate_limit::recovered(&config.project_dir, &role.backend);
+            }
+            break;
+        }
+        // Only a failure says much: a working agent may well print "rate limit"
+        if let Some(stderr) = stderr {
+            sighting = sighting.merge(stderr.recv_timeout(Duration::from_secs(1)).unwrap_or_default());
+        }
+        if sighting.seen && !timed_out && !killed && throttled < MAX_THROTTLED {
+            throttled += 1;
+            let backoff = rate_limit::back_off(
+                &config.project_dir,
+                &config.rate_limits,
+                &role.backend,
+                sighting.retry_after,
+                session.agent_id,
+                session.feature_id,
+            );
+            warn!(agent = session.agent_id, "{} is rate limited; backing off {}s", role.backend, backoff.as_secs());
+            continue;
+        }
+        attempt += 1;
+    }
+    Ok(last)
+}
+
+/// Sleep through a rate-limit backoff, a second at a time so a stop
+/// request still gets through. False if one did.
+fn wait_out(project_dir: &Path, wait: Duration) -> bool {
+    let deadline = Instant::now() + wait;
+    while Instant::now() < deadline {
+        if stop_requested(project_dir) {
+            return false;
+        }
+        thread::sleep(deadline.saturating_duration_since(Instant::now()).min(Duration::from_secs(1)));
+    }
+    true
+}
+
+/// Record a session killed as a runaway and block the executor's feature,
+/// so the next session doesn't pick it up and loop the same way.
+fn block_runaway(project_dir: &Path, session: &Session, reason: &str) {
+    let feature_id = session.feature_id.map(String::from);
+    if session.kind == "executor"
+        && let Some(id) = &feature_id
+        && let Ok(mut features) = FeatureList::load(project_dir)
+        && features.mark_blocked(id, &format!("Runaway session killed: {reason}")).is_ok()
+    {
+        let _ = features.save(project_dir);
+    }
+    events::emit(project_dir, session.agent_id, EventKind::RunawayKilled { feature_id, reason: reason.to_string() });
+}
+
+/// Pass `child`'s stdout lines to `on_line` until it exits, killing it if
+/// `timeout` runs out first or `on_line` returns false. Returns its status
+/// and whether it ran out of time.
+fn wait_streaming(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    on_line: &mut dyn FnMut(&str) -> bool,
+) -> (std::io::Result<ExitStatus>, bool) {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    if let Some(stdout) = child.stdout.take() {
+        // Read on another thread so the deadline holds while the agent is quiet
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        loop {
+            let next = match deadline {
+                Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match next {
+                Ok(line) if on_line(&line) => {}
+                Ok(_) => {
+                    let _ = child.kill();
+                    return (child.wait(), false);
+                }
+                // Out of time, or stdout closed
+                Err(_) => break,
+            }
+        }
+    }
+    // stdout closes when the agent exits, or maybe earlier; keep to the deadline either way
+    let Some(deadline) = deadline else { return (child.wait(), false) };
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return (Ok(status), false),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                return (child.wait(), true);
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return (Err(e), false),
+        }
+    }
+}
+
+/// Spawn an agent child process using the role's backend + model.
+fn spawn_agent(
+    role: &RoleSpec,
+    backends: &BTreeMap<String, BackendDef>,
+    permissions: Permissions,
+    secrets: &BTreeMap<String, String>,
+    project_dir: &Path,
+    prompt: &str,
+    agent_id: &str,
+) -> Result<Child, std::io::Error> {
+    let command = build_headless_command(role, backends, permissions, prompt);
+    Command::new(&command.program)
+        .args(&command.args)
+        .envs(secrets)
+        .envs(&command.env)
         .current_dir(project_dir)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -641,7 +1744,7 @@ fn spawn_agent(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::RoleSpec;
+    use crate::config::{Fallback, RoleSpec};
     use crate::features::{Feature, FeatureList, FeatureStatus, FeatureType};
 
     fn setup_project(dir: &Path, features: Vec<Feature>) {
@@ -654,6 +1757,7 @@ mod tests {
         RoleSpec {
             backend: "echo".into(),
             model: "test".into(),
+            ..RoleSpec::default()
         }
     }
 
@@ -681,8 +1785,18 @@ mod tests {
             project_dir: dir.path().to_path_buf(),
             protocol: echo_role(),
             orchestrating: echo_role(),
+            protocol_b: None,
             max_sessions: 10,
             num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
         };
 
         match run_single_agent(&config) {
@@ -723,8 +1837,18 @@ mod tests {
             project_dir: dir.path().to_path_buf(),
             protocol: echo_role(),
             orchestrating: echo_role(),
+            protocol_b: None,
             max_sessions: 2,
             num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
         };
 
         match run_single_agent(&config) {
@@ -734,6 +1858,7 @@ mod tests {
             RunOutcome::AllDone { .. } => {}
             RunOutcome::SpawnError(_) => {}
             RunOutcome::Stopped { .. } => {}
+            RunOutcome::CostLimit { .. } => panic!("no max_total_cost set"),
         }
     }
 
@@ -741,13 +1866,206 @@ mod tests {
     fn spawn_agent_uses_role() {
         let dir = tempfile::tempdir().unwrap();
         let role = echo_role();
-        let result = spawn_agent(&role, dir.path(), "test prompt", "agent-1");
+        let (backends, secrets) = (BTreeMap::new(), BTreeMap::new());
+        let result = spawn_agent(&role, &backends, Permissions::Bypass, &secrets, dir.path(), "test prompt", "agent-1");
         assert!(result.is_ok());
         let mut child = result.unwrap();
         let status = child.wait().unwrap();
         assert!(status.success());
     }
 
+    #[test]
+    fn custom_backend_fills_templates() {
+        let def = BackendDef {
+            command: Some("acme-agent".into()),
+            headless_args: vec!["run".into(), "--task={prompt}".into()],
+            interactive_args: vec!["chat".into()],
+            model_flag: Some("-m".into()),
+            max_turns_flag: Some("--turns".into()),
+            temperature_flag: None,
+            max_output_tokens_flag: None,
+            budget_flag: None,
+            env: BTreeMap::from([("ACME_MODE".into(), "ci".into())]),
+        };
+        let backends = BTreeMap::from([("acme".to_string(), def)]);
+        let role = RoleSpec {
+            backend: "acme".into(),
+            model: "big".into(),
+            max_turns: Some(30),
+            temperature: Some(0.2),
+            ..RoleSpec::default()
+        };
+        let command = build_agent_command(&role, &backends, Permissions::Bypass, "do {model}");
+        assert_eq!(command.program, "acme-agent");
+        // No {prompt} in the interactive template, so it's appended; no temperature flag, so none passed
+        assert_eq!(command.args, vec!["-m", "big", "--turns", "30", "chat", "do {model}"]);
+        assert_eq!(command.env["ACME_MODE"], "ci");
+        // Built-ins are untouched when not redefined
+        let claude = RoleSpec::default();
+        assert_eq!(build_agent_command(&claude, &backends, Permissions::Bypass, "p").args[0], "--model");
+    }
+
+    #[test]
+    fn builtin_backends_map_limits() {
+        let none = BTreeMap::new();
+        let role = RoleSpec {
+            max_turns: Some(25),
+            max_output_tokens: Some(16000),
+            budget: Some(1.5),
+            ..RoleSpec::default()
+        };
+        let claude = build_agent_command(&role, &none, Permissions::Bypass, "p");
+        assert_eq!(claude.args, vec!["--model", "sonnet", "--dangerously-skip-permissions", "p"]);
+        // Otherwise .claude/settings.json decides
+        assert_eq!(build_agent_command(&role, &none, Permissions::Standard, "p").args, vec!["--model", "sonnet", "p"]);
+        assert_eq!(claude.env["CLAUDE_CODE_MAX_OUTPUT_TOKENS"], "16000");
+        assert_eq!(
+            build_headless_command(&role, &none, Permissions::Bypass, "p").args[..5],
+            ["--print", "--max-budget-usd", "1.5", "--max-turns", "25"]
+        );
+        let codex = build_agent_command(&RoleSpec { backend: "codex".into(), ..role }, &none, Permissions::Bypass, "p");
+        assert_eq!(
+            codex.args,
+            vec!["--model", "sonnet", "--full-auto", "-c", "model_max_output_tokens=16000", "p"]
+        );
+    }
+
+    #[test]
+    fn spawn_agent_runs_custom_backend_headless() {
+        let dir = tempfile::tempdir().unwrap();
+        let def = BackendDef {
+            command: Some("sh".into()),
+            headless_args: vec![
+                "-c".into(),
+                r#"test "$ACME_MODE" = ci && test "$ACME_API_KEY" = k-123 && test "$1" = "{model}: p""#.into(),
+                "sh".into(),
+                "{model}: {prompt}".into(),
+            ],
+            interactive_args: vec![],
+            model_flag: None,
+            max_turns_flag: None,
+            temperature_flag: None,
+            max_output_tokens_flag: None,
+            budget_flag: None,
+            env: BTreeMap::from([("ACME_MODE".into(), "ci".into())]),
+        };
+        let backends = BTreeMap::from([("acme".to_string(), def)]);
+        let role = RoleSpec { backend: "acme".into(), model: "big".into(), ..RoleSpec::default() };
+        let secrets = BTreeMap::from([("ACME_API_KEY".to_string(), "k-123".to_string())]);
+        let mut child =
+            spawn_agent(&role, &backends, Permissions::Bypass, &secrets, dir.path(), "p", "agent-1").unwrap();
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn sessions_time_out_and_retry() {
+        let dir = tempfile::tempdir().unwrap();
+        let def = BackendDef {
+            command: Some("sh".into()),
+            headless_args: vec!["-c".into(), "echo started; echo run >> runs; exec sleep 30".into()],
+            interactive_args: vec![],
+            model_flag: None,
+            max_turns_flag: None,
+            temperature_flag: None,
+            max_output_tokens_flag: None,
+            budget_flag: None,
+            env: BTreeMap::new(),
+        };
+        let config = RunConfig {
+            project_dir: dir.path().to_path_buf(),
+            protocol: RoleSpec::default(),
+            orchestrating: RoleSpec::default(),
+            protocol_b: None,
+            max_sessions: 1,
+            num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::from([("slow".to_string(), def)]),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
+        };
+        let role = RoleSpec { backend: "slow".into(), timeout_secs: Some(1), retries: Some(1), ..RoleSpec::default() };
+        let session = Session {
+            role: &role,
+            kind: "executor",
+            cwd: dir.path(),
+            prompt: "p",
+            agent_id: "agent-1",
+            feature_id: Some("f001"),
+            variant: None,
+        };
+        let mut lines = Vec::new();
+        let started = Instant::now();
+        let status = run_session(&config, &session, &mut |line| lines.push(line.to_string())).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!status.unwrap().success());
+        assert_eq!(lines, ["started", "started"]);
+        assert_eq!(fs::read_to_string(dir.path().join("runs")).unwrap(), "run\nrun\n");
+        let log = fs::read_to_string(events::events_dir(dir.path()).join("agent-1.jsonl")).unwrap();
+        assert_eq!(log.matches(r#""timed_out":true"#).count(), 2, "{log}");
+    }
+
+    #[test]
+    fn rate_limited_sessions_move_to_the_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let def = |script: &str| BackendDef {
+            command: Some("sh".into()),
+            headless_args: vec!["-c".into(), script.into()],
+            interactive_args: vec![],
+            model_flag: None,
+            max_turns_flag: None,
+            temperature_flag: None,
+            max_output_tokens_flag: None,
+            budget_flag: None,
+            env: BTreeMap::new(),
+        };
+        let throttled = "echo busy; echo 'Error: 429 Too Many Requests' >&2; exit 1";
+        let config = RunConfig {
+            project_dir: dir.path().to_path_buf(),
+            protocol: RoleSpec::default(),
+            orchestrating: RoleSpec::default(),
+            protocol_b: None,
+            max_sessions: 1,
+            num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::from([("busy".to_string(), def(throttled)), ("spare".to_string(), def("echo done"))]),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig {
+                fallback: BTreeMap::from([("busy".into(), Fallback { backend: "spare".into(), model: None })]),
+                ..RateLimitConfig::default()
+            },
+            max_total_cost: None,
+            chaos: None,
+        };
+        // No retries: the rate-limited attempt doesn't count as one
+        let role = RoleSpec { backend: "busy".into(), ..RoleSpec::default() };
+        let session = Session {
+            role: &role,
+            kind: "executor",
+            cwd: dir.path(),
+            prompt: "p",
+            agent_id: "agent-1",
+            feature_id: Some("f001"),
+            variant: None,
+        };
+        let mut lines = Vec::new();
+        let status = run_session(&config, &session, &mut |line| lines.push(line.to_string())).unwrap();
+        assert!(status.unwrap().success());
+        assert_eq!(lines, ["busy", "done"]);
+        let kinds: Vec<EventKind> = events::merged_timeline(dir.path()).unwrap().into_iter().map(|e| e.kind).collect();
+        assert!(matches!(&kinds[2], EventKind::RateLimited { backend, backoff_secs: 60, .. } if backend == "busy"));
+        assert!(matches!(&kinds[3], EventKind::BackendRerouted { to, .. } if to == "spare"));
+        assert!(matches!(&kinds[4], EventKind::SessionStart { backend: Some(b), .. } if b == "spare"));
+        assert!(rate_limit::remaining(dir.path(), "busy").is_some(), "still backed off");
+    }
+
     #[test]
     fn stop_sentinel_works() {
         let dir = tempfile::tempdir().unwrap();
@@ -785,8 +2103,18 @@ mod tests {
             project_dir: dir.path().to_path_buf(),
             protocol: echo_role(),
             orchestrating: echo_role(),
+            protocol_b: None,
             max_sessions: 100,
             num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
         };
 
         match run_single_agent(&config) {
@@ -795,6 +2123,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cost_limit_refuses_new_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        setup_project(
+            dir.path(),
+            vec![Feature {
+                id: "f001".into(),
+                feature_type: FeatureType::Implement,
+                scope: "test".into(),
+                description: "test".into(),
+                verify: "./scripts/verify/f001.sh".into(),
+                depends_on: vec![],
+                priority: 1,
+                status: FeatureStatus::Pending,
+                claimed_by: None,
+                blocked_reason: None,
+                context_hints: vec![],
+            }],
+        );
+
+        let config = RunConfig {
+            project_dir: dir.path().to_path_buf(),
+            protocol: echo_role(),
+            orchestrating: echo_role(),
+            protocol_b: None,
+            max_sessions: 100,
+            num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            // No transcripts, so nothing spent: a zero limit is already reached
+            max_total_cost: Some(0.0),
+            chaos: None,
+        };
+
+        let outcome = run_single_agent(&config);
+        assert!(matches!(outcome, RunOutcome::CostLimit { sessions: 0, limit_usd: 0.0, .. }), "{outcome:?}");
+        emit_run_finished(dir.path(), &outcome);
+        let reason = cost_limit_reason(dir.path()).unwrap();
+        assert!(reason.starts_with("project spend $0.00 reached max_total_cost $0.00 at "), "{reason}");
+        let timeline = events::merged_timeline(dir.path()).unwrap();
+        assert!(!timeline.iter().any(|e| matches!(e.kind, EventKind::SessionStart { .. })));
+
+        events::emit(dir.path(), "agent-1", EventKind::SessionStart {
+            role: "executor".into(),
+            feature_id: None,
+            backend: None,
+            model: None,
+            variant: None,
+        });
+        assert_eq!(cost_limit_reason(dir.path()), None, "raised and running again");
+    }
+
     #[test]
     fn writes_verify_report() {
         let dir = tempfile::tempdir().unwrap();
@@ -827,8 +2212,18 @@ mod tests {
             project_dir: dir.path().to_path_buf(),
             protocol: echo_role(),
             orchestrating: echo_role(),
+            protocol_b: None,
             max_sessions: 1,
             num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
         };
 
         run_single_agent(&config);
@@ -867,8 +2262,18 @@ mod tests {
             project_dir: dir.path().to_path_buf(),
             protocol: echo_role(),
             orchestrating: echo_role(),
+            protocol_b: None,
             max_sessions: 1,
             num_agents: 1,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
         };
 
         run_single_agent(&config);
@@ -876,4 +2281,251 @@ mod tests {
         let log_path = dir.path().join(".forge/logs/agent-1.log");
         assert!(log_path.exists());
     }
+
+    #[test]
+    fn prompt_trims_package_to_role_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("context/packages")).unwrap();
+        let package = format!(
+            "# Context Package: f001\n\n## Relevant Context\n- pool retries\n\n## Gotchas\n{}",
+            "- unrelated gotcha line\n".repeat(200)
+        );
+        fs::write(dir.path().join("context/packages/f001.md"), &package).unwrap();
+
+        let (prompt, report) = build_agent_prompt(dir.path(), "f001", &echo_role());
+        assert!(report.is_none());
+        assert!(prompt.contains(&package));
+
+        let role = RoleSpec {
+            context_tokens: 300,
+            ..echo_role()
+        };
+        let (prompt, report) = build_agent_prompt(dir.path(), "f001", &role);
+        assert_eq!(report.unwrap().cut_summary(), "Gotchas (truncated)");
+        assert!(prompt.contains("- pool retries"));
+        assert!(prompt.contains("Cut to fit the context budget: Gotchas (truncated)"));
+
+        report_context_budget(
+            dir.path(),
+            "agent-1",
+            "f001",
+            build_agent_prompt(dir.path(), "f001", &role).1,
+            false,
+        );
+        let events = events::read_stream(&events::events_dir(dir.path()).join("agent-1.jsonl")).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::ContextTrimmed { feature_id, .. } if feature_id == "f001"));
+    }
+
+    #[test]
+    fn prompt_embeds_scope_principles() {
+        let dir = tempfile::tempdir().unwrap();
+        setup_project(
+            dir.path(),
+            vec![Feature {
+                id: "f001".into(),
+                feature_type: FeatureType::Implement,
+                scope: "auth".into(),
+                description: "login".into(),
+                verify: "true".into(),
+                depends_on: vec![],
+                priority: 1,
+                status: FeatureStatus::Pending,
+                claimed_by: None,
+                blocked_reason: None,
+                context_hints: vec![],
+            }],
+        );
+        fs::write(
+            dir.path().join("forge.toml"),
+            "[project]\nname = \"t\"\n\n[scopes.auth.principles]\nsecrets = \"Never log tokens\"\n",
+        )
+        .unwrap();
+        let (prompt, _) = build_agent_prompt(dir.path(), "f001", &echo_role());
+        assert!(prompt.contains("## Principles for scope `auth` (on top of CLAUDE.md's)\n\n- secrets: Never log tokens"));
+
+        fs::write(dir.path().join("forge.toml"), "[project]\nname = \"t\"\n").unwrap();
+        assert!(!build_agent_prompt(dir.path(), "f001", &echo_role()).0.contains("Principles for scope"));
+    }
+
+    #[test]
+    fn commit_state_takes_only_forge_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(dir.path()).output().unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "t@t"]);
+        git(&["config", "user.name", "t"]);
+        fs::write(dir.path().join("features.json"), "{}").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-qm", "init"]);
+
+        fs::write(dir.path().join("features.json"), r#"{"features": []}"#).unwrap();
+        fs::create_dir_all(dir.path().join("feedback")).unwrap();
+        fs::write(dir.path().join("feedback/last-verify.json"), "{}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not forge's").unwrap();
+
+        commit_state(dir.path(), &GitConfig::default(), 2, &["f001".into()]);
+        assert_eq!(git(&["log", "--format=%s", "-1"]), "init", "off by default");
+
+        let git_config = GitConfig { commit_state: true, ..GitConfig::default() };
+        commit_state(dir.path(), &git_config, 2, &["f001".into()]);
+        assert_eq!(
+            git(&["log", "--format=%B", "-1"]),
+            "chore(f001): update forge state\n\nForge-Feature: f001\nForge-Session: 2"
+        );
+        assert_eq!(git(&["show", "--name-only", "--format=", "HEAD"]), "features.json\nfeedback/last-verify.json");
+        assert_eq!(git(&["status", "--porcelain"]), "?? notes.txt");
+    }
+
+    #[test]
+    fn merge_strategies_shape_history() {
+        let git = |cwd: &Path, args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(cwd).output().unwrap();
+            assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        for strategy in [MergeStrategy::Merge, MergeStrategy::MergeCommit, MergeStrategy::Squash, MergeStrategy::Rebase] {
+            let dir = tempfile::tempdir().unwrap();
+            let repo = dir.path().join("repo");
+            fs::create_dir_all(&repo).unwrap();
+            git(&repo, &["init", "-q"]);
+            git(&repo, &["config", "user.email", "t@t"]);
+            git(&repo, &["config", "user.name", "t"]);
+            fs::write(repo.join("a.txt"), "a").unwrap();
+            git(&repo, &["add", "-A"]);
+            git(&repo, &["commit", "-qm", "base"]);
+            let wt = dir.path().join("wt");
+            let branch = agent_branch(&GitConfig::default(), "agent-1", "f001");
+            git(&repo, &["worktree", "add", "-q", "-b", &branch, wt.to_str().unwrap()]);
+            fs::write(wt.join("b.txt"), "b").unwrap();
+            git(&wt, &["add", "-A"]);
+            git(&wt, &["commit", "-qm", "agent work"]);
+            fs::write(repo.join("c.txt"), "c").unwrap();
+            git(&repo, &["add", "-A"]);
+            git(&repo, &["commit", "-qm", "main work"]);
+
+            let config = GitConfig { merge_strategy: strategy, commit_trailers: false, ..GitConfig::default() };
+            let message = CommitMessage::new("feat", "add b").agent("agent-1");
+            merge_worktree(&repo, &wt, &branch, &message, &config).unwrap();
+            assert!(repo.join("b.txt").exists(), "{strategy:?}");
+            let parents = git(&repo, &["log", "-1", "--format=%P"]).split_whitespace().count();
+            let subjects = git(&repo, &["log", "--format=%s"]);
+            match strategy {
+                MergeStrategy::Merge | MergeStrategy::MergeCommit => assert_eq!(parents, 2, "{strategy:?}"),
+                MergeStrategy::Squash => assert_eq!(subjects, "feat: add b\nmain work\nbase"),
+                MergeStrategy::Rebase => assert_eq!(subjects, "agent work\nmain work\nbase"),
+            }
+            git(&repo, &["worktree", "remove", "--force", wt.to_str().unwrap()]);
+            delete_merged_branch(&repo, &branch, &config).unwrap();
+        }
+    }
+
+    #[test]
+    fn merge_keeps_both_sides_of_context_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        let git = |cwd: &Path, args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(cwd).output().unwrap();
+            assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+        };
+        git(&repo, &["init", "-q"]);
+        git(&repo, &["config", "user.email", "t@t"]);
+        git(&repo, &["config", "user.name", "t"]);
+        let ctx = crate::context::ContextManager::new(&repo);
+        ctx.write_entry("gotchas", "pool", "# Pool\nSize 10.").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-qm", "base"]);
+
+        let wt = dir.path().join("wt");
+        let branch = agent_branch(&GitConfig::default(), "agent-1", "f001");
+        git(&repo, &["worktree", "add", "-q", "-b", &branch, wt.to_str().unwrap()]);
+        crate::context::ContextManager::new(&wt)
+            .write_entry("gotchas", "pool", "# Pool\nSize 10.\nSet a timeout.")
+            .unwrap();
+        git(&wt, &["commit", "-qam", "agent gotcha"]);
+        ctx.write_entry("gotchas", "pool", "# Pool\nSize 20.").unwrap();
+        git(&repo, &["commit", "-qam", "main gotcha"]);
+
+        let message = CommitMessage::new("feat", "pool sizing").agent("agent-1");
+        merge_worktree(&repo, &wt, &branch, &message, &GitConfig::default()).unwrap();
+        let pool = ctx.read_entry("gotchas", "pool").unwrap();
+        assert!(pool.starts_with("# Pool\nSize 20."), "{pool}");
+        assert!(pool.ends_with("Set a timeout.\n"), "{pool}");
+        let status = Command::new("git").args(["status", "--porcelain"]).current_dir(&repo).output().unwrap();
+        let status = String::from_utf8_lossy(&status.stdout).into_owned();
+        assert!(!status.contains("context/gotchas"), "reconcile is committed: {status}");
+        let log = Command::new("git").args(["log", "-2", "--format=%s"]).current_dir(&repo).output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&log.stdout),
+            "docs: reconcile context from forge/agent-1\nfeat: pool sizing\n"
+        );
+        let events = events::read_stream(&events::events_dir(&repo).join("forge.jsonl")).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::ContextReconciled { report, .. } if report.appended == ["gotchas/pool"]));
+    }
+
+    #[test]
+    fn code_conflict_writes_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        let git = |cwd: &Path, args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(cwd).output().unwrap();
+            assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+        };
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.email", "t@t"]);
+        git(&repo, &["config", "user.name", "t"]);
+        fs::write(repo.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-qm", "base"]);
+
+        let wt = dir.path().join("wt");
+        let branch = agent_branch(&GitConfig::default(), "agent-2", "f002");
+        git(&repo, &["worktree", "add", "-q", "-b", &branch, wt.to_str().unwrap()]);
+        fs::write(wt.join("lib.rs"), "fn a() -> u16 { 2 }\n").unwrap();
+        git(&wt, &["commit", "-qam", "agent change"]);
+        fs::write(repo.join("lib.rs"), "fn a() -> u8 { 1 }\n").unwrap();
+        git(&repo, &["commit", "-qam", "feat(f001): narrow a\n\nForge-Feature: f001"]);
+
+        let message = CommitMessage { feature_id: Some("f002".into()), ..CommitMessage::new("feat", "widen a") };
+        let error = merge_worktree(&repo, &wt, &branch, &message, &GitConfig::default()).unwrap_err();
+        assert_eq!(error, "merge conflict in lib.rs; see feedback/merge-conflicts/f002.md");
+        let report = fs::read_to_string(repo.join("feedback/merge-conflicts/f002.md")).unwrap();
+        assert!(report.contains("Features involved: f002 (this branch), f001 (on main)"), "{report}");
+        assert!(report.contains("+fn a() -> u8 { 1 }") && report.contains("+fn a() -> u16 { 2 }"), "{report}");
+        assert!(build_agent_prompt(&repo, "f002", &echo_role()).0.contains("Read feedback/merge-conflicts/f002.md first."));
+    }
+
+    #[test]
+    fn ab_split_gives_even_agents_variant_b() {
+        let mut config = RunConfig {
+            project_dir: PathBuf::new(),
+            protocol: echo_role(),
+            orchestrating: echo_role(),
+            protocol_b: None,
+            max_sessions: 1,
+            num_agents: 4,
+            worktrees: false,
+            backends: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            git: GitConfig::default(),
+            permissions: Permissions::Bypass,
+            runaway: RunawayConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            max_total_cost: None,
+            chaos: None,
+        };
+        assert_eq!(executor_role(&config, 2), (&echo_role(), None));
+
+        let b = RoleSpec { model: "opus".into(), ..echo_role() };
+        config.protocol_b = Some(b.clone());
+        let split: Vec<_> = (1..=4).map(|slot| executor_role(&config, slot)).collect();
+        assert_eq!(
+            split,
+            [(&echo_role(), Some("a")), (&b, Some("b")), (&echo_role(), Some("a")), (&b, Some("b"))]
+        );
+    }
 }
@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::config::ForgeConfig;
+use crate::events::{self, EventKind};
+use crate::features::{FeatureList, FeatureStatus};
+use crate::runner::RunConfig;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RunsError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("no run '{0}' (see `forge runs list`)")]
+    NotFound(String),
+    #[error("'{0}' matches more than one run: {1}")]
+    Ambiguous(String, String),
+}
+
+/// `.forge/runs/<id>.json`: one per headless `forge run`, written when it
+/// starts and again when it ends.
+pub fn runs_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".forge/runs")
+}
+
+/// What a run got done, from the events recorded while it ran.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunTotals {
+    /// Executor sessions started.
+    pub sessions: usize,
+    /// Features done at the end that weren't at the start.
+    pub completed: Vec<String>,
+    pub verify_failures: usize,
+    pub reopened: usize,
+    pub blocked: usize,
+    pub failed_merges: usize,
+    pub rate_limited: usize,
+    /// What the project's Claude transcripts added up to over the run.
+    pub cost_usd: f64,
+    /// Features not done when it ended.
+    pub remaining: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunRecord {
+    /// When it started, local time; unique within the project.
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    /// None while it runs, or if it never got to the end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// As in the `run_finished` event: `all_done`, `max_sessions`, ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    /// The settings it ran with: `--agents`, `--max-sessions`, the profile,
+    /// `[forge]` with the roles as overridden on the command line, and `[git]`.
+    pub config: Value,
+    /// Done when it started, to tell what it completed.
+    #[serde(default)]
+    pub done_at_start: Vec<String>,
+    #[serde(default)]
+    pub spent_at_start: f64,
+    #[serde(default)]
+    pub totals: RunTotals,
+}
+
+impl RunRecord {
+    fn path(&self, project_dir: &Path) -> PathBuf {
+        runs_dir(project_dir).join(format!("{}.json", self.id))
+    }
+
+    fn save(&self, project_dir: &Path) -> Result<(), RunsError> {
+        std::fs::create_dir_all(runs_dir(project_dir))?;
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(self.path(project_dir), json + "\n")?;
+        Ok(())
+    }
+
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        Some(self.finished_at? - self.started_at)
+    }
+
+    /// Features completed per hour of the run.
+    pub fn throughput(&self) -> Option<f64> {
+        let hours = self.duration()?.num_seconds() as f64 / 3600.0;
+        (hours > 0.0).then(|| self.totals.completed.len() as f64 / hours)
+    }
+}
+
+fn done_ids(project_dir: &Path) -> Vec<String> {
+    FeatureList::load(project_dir)
+        .map(|list| list.features.into_iter().filter(|f| f.status == FeatureStatus::Done).map(|f| f.id).collect())
+        .unwrap_or_default()
+}
+
+/// The settings `run_config` was loaded with from `forge_config`, for
+/// `RunRecord::config`.
+pub fn snapshot(forge_config: &ForgeConfig, run_config: &RunConfig, profile: Option<&str>) -> Value {
+    let mut forge = forge_config.forge.clone();
+    forge.roles.protocol = run_config.protocol.clone();
+    forge.roles.orchestrating = run_config.orchestrating.clone();
+    forge.roles.protocol_b = run_config.protocol_b.clone();
+    json!({
+        "agents": run_config.num_agents,
+        "max_sessions": run_config.max_sessions,
+        "profile": profile,
+        "forge": forge,
+        "git": forge_config.git,
+    })
+}
+
+/// Record a run starting now with `config`.
+pub fn start(project_dir: &Path, config: Value) -> Result<RunRecord, RunsError> {
+    let dir = runs_dir(project_dir);
+    let mut id = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    while dir.join(format!("{id}.json")).exists() {
+        id.push('+');
+    }
+    let record = RunRecord {
+        id,
+        started_at: Utc::now(),
+        finished_at: None,
+        outcome: None,
+        config,
+        done_at_start: done_ids(project_dir),
+        spent_at_start: crate::cost::total_spent(project_dir),
+        totals: RunTotals::default(),
+    };
+    record.save(project_dir)?;
+    Ok(record)
+}
+
+/// Fill in how the run ended and what it got done, and save it.
+pub fn finish(project_dir: &Path, mut record: RunRecord, outcome: &str) -> Result<RunRecord, RunsError> {
+    let since = record.started_at;
+    let mut totals = RunTotals::default();
+    for event in events::merged_timeline(project_dir)?.iter().filter(|e| e.ts >= since) {
+        match &event.kind {
+            EventKind::SessionStart { role, .. } if role == "executor" => totals.sessions += 1,
+            EventKind::FeatureVerified { passed: false, .. } => totals.verify_failures += 1,
+            EventKind::Verify { reopened, .. } => totals.reopened += reopened.len(),
+            EventKind::FeatureBlocked { .. } => totals.blocked += 1,
+            EventKind::Merge { ok: false, .. } => totals.failed_merges += 1,
+            EventKind::RateLimited { .. } => totals.rate_limited += 1,
+            _ => {}
+        }
+    }
+    let done = done_ids(project_dir);
+    totals.completed = done.iter().filter(|id| !record.done_at_start.contains(id)).cloned().collect();
+    totals.remaining = FeatureList::load(project_dir).map_or(0, |list| list.features.len().saturating_sub(done.len()));
+    totals.cost_usd = (crate::cost::total_spent(project_dir) - record.spent_at_start).max(0.0);
+    record.totals = totals;
+    record.outcome = Some(outcome.to_string());
+    record.finished_at = Some(Utc::now());
+    record.save(project_dir)?;
+    Ok(record)
+}
+
+/// Every run recorded, oldest first.
+pub fn list(project_dir: &Path) -> Vec<RunRecord> {
+    let mut runs: Vec<RunRecord> = std::fs::read_dir(runs_dir(project_dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| serde_json::from_str(&std::fs::read_to_string(e.path()).ok()?).ok())
+        .collect();
+    runs.sort_by(|a, b| a.id.cmp(&b.id));
+    runs
+}
+
+/// The run `id`, or the one it's the start of.
+pub fn find(project_dir: &Path, id: &str) -> Result<RunRecord, RunsError> {
+    let mut matching: Vec<RunRecord> = list(project_dir).into_iter().filter(|r| r.id.starts_with(id)).collect();
+    if let Some(exact) = matching.iter().position(|r| r.id == id) {
+        return Ok(matching.swap_remove(exact));
+    }
+    match matching.len() {
+        0 => Err(RunsError::NotFound(id.to_string())),
+        1 => Ok(matching.remove(0)),
+        _ => {
+            let ids: Vec<&str> = matching.iter().map(|r| r.id.as_str()).collect();
+            Err(RunsError::Ambiguous(id.to_string(), ids.join(", ")))
+        }
+    }
+}
+
+/// `config` as dotted keys, `forge.roles.protocol.model` and so on.
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                flatten(&key, value, out);
+            }
+        }
+        Value::Null => {}
+        Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        other => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
+
+/// Settings that differ between two runs: key, `a`'s value, `b`'s.
+pub fn config_changes(a: &RunRecord, b: &RunRecord) -> Vec<(String, Option<String>, Option<String>)> {
+    let (mut old, mut new) = (BTreeMap::new(), BTreeMap::new());
+    flatten("", &a.config, &mut old);
+    flatten("", &b.config, &mut new);
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter(|k| old.get(*k) != new.get(*k))
+        .map(|k| (k.clone(), old.get(k).cloned(), new.get(k).cloned()))
+        .collect()
+}
+
+pub fn format_duration(duration: Option<chrono::Duration>) -> String {
+    match duration {
+        None => "-".into(),
+        Some(d) if d.num_hours() > 0 => format!("{}h {:02}m", d.num_hours(), d.num_minutes() % 60),
+        Some(d) => format!("{}m {:02}s", d.num_minutes(), d.num_seconds() % 60),
+    }
+}
+
+/// `forge runs compare`: the two runs' totals side by side, with the
+/// change in the rates, then the settings that changed between them.
+pub fn render_comparison(a: &RunRecord, b: &RunRecord) -> String {
+    let outcome = |r: &RunRecord| r.outcome.clone().unwrap_or_else(|| "unfinished".into());
+    let done = |r: &RunRecord| r.totals.completed.len();
+    let per_feature = |r: &RunRecord, n: f64| (done(r) > 0).then(|| n / done(r) as f64);
+    let sessions_per_feature = |r: &RunRecord| per_feature(r, r.totals.sessions as f64);
+    let cost_per_feature = |r: &RunRecord| per_feature(r, r.totals.cost_usd);
+    let num = |v: Option<f64>, unit: &str| v.map_or_else(|| "-".into(), |v| format!("{unit}{v:.2}"));
+    let change = |old: Option<f64>, new: Option<f64>| match (old, new) {
+        (Some(old), Some(new)) if old > 0.0 => format!("{:+.0}%", (new - old) / old * 100.0),
+        _ => String::new(),
+    };
+    let counts =
+        |label, n: fn(&RunTotals) -> usize| (label, n(&a.totals).to_string(), n(&b.totals).to_string(), String::new());
+    let rows = [
+        ("outcome", outcome(a), outcome(b), String::new()),
+        ("duration", format_duration(a.duration()), format_duration(b.duration()), String::new()),
+        counts("sessions", |t| t.sessions),
+        ("features done", done(a).to_string(), done(b).to_string(), change(Some(done(a) as f64), Some(done(b) as f64))),
+        ("features/hour", num(a.throughput(), ""), num(b.throughput(), ""), change(a.throughput(), b.throughput())),
+        (
+            "sessions/feature",
+            num(sessions_per_feature(a), ""),
+            num(sessions_per_feature(b), ""),
+            change(sessions_per_feature(a), sessions_per_feature(b)),
+        ),
+        counts("verify failures", |t| t.verify_failures),
+        counts("reopened", |t| t.reopened),
+        counts("blocked", |t| t.blocked),
+        counts("failed merges", |t| t.failed_merges),
+        counts("rate limited", |t| t.rate_limited),
+        ("cost", num(Some(a.totals.cost_usd), "$"), num(Some(b.totals.cost_usd), "$"), String::new()),
+        (
+            "cost/feature",
+            num(cost_per_feature(a), "$"),
+            num(cost_per_feature(b), "$"),
+            change(cost_per_feature(a), cost_per_feature(b)),
+        ),
+    ];
+    let mut out = format!("{:<18} {:<20} {}\n", "", a.id, b.id);
+    for (label, old, new, change) in rows {
+        let line = format!("{label:<18} {old:<20} {new:<20} {change}");
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+    let changes = config_changes(a, b);
+    if changes.is_empty() {
+        out.push_str("\nSame settings\n");
+    } else {
+        out.push_str("\nSettings changed:\n");
+        let show = |v: Option<String>| v.unwrap_or_else(|| "(unset)".into());
+        for (key, old, new) in changes {
+            let _ = writeln!(out, "  {key}: {} → {}", show(old), show(new));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_runs_and_compares_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let feature = |id: usize, status: &str| {
+            let fields = r#""type":"implement","scope":"a","description":"d","verify":"true""#;
+            format!(r#"{{"id":"f00{id}",{fields},"status":"{status}"}}"#)
+        };
+        let write_features = |statuses: [&str; 3]| {
+            let features: Vec<String> = statuses.iter().enumerate().map(|(i, status)| feature(i + 1, status)).collect();
+            std::fs::write(root.join("features.json"), format!(r#"{{"features":[{}]}}"#, features.join(","))).unwrap();
+        };
+        write_features(["done", "pending", "pending"]);
+        let first = start(root, json!({"agents": 1, "forge": {"roles": {"protocol": {"model": "sonnet"}}}})).unwrap();
+        assert_eq!(first.done_at_start, ["f001"]);
+        assert_eq!(list(root), std::slice::from_ref(&first), "saved as it starts");
+
+        let session = |role: &str| EventKind::SessionStart {
+            role: role.into(),
+            feature_id: Some("f002".into()),
+            backend: None,
+            model: None,
+            variant: None,
+        };
+        for kind in [
+            session("executor"),
+            session("orchestrating"),
+            EventKind::FeatureVerified { feature_id: "f002".into(), passed: false },
+            EventKind::Verify { passed: 1, failed: 1, reopened: vec!["f002".into()] },
+            session("executor"),
+        ] {
+            events::emit(root, "agent-1", kind);
+        }
+        write_features(["done", "done", "pending"]);
+        let mut first = finish(root, first, "max_sessions").unwrap();
+        assert_eq!(first.outcome.as_deref(), Some("max_sessions"));
+        assert_eq!(first.totals.sessions, 2);
+        assert_eq!(first.totals.completed, ["f002"]);
+        assert_eq!((first.totals.verify_failures, first.totals.reopened, first.totals.remaining), (1, 1, 1));
+
+        // Pretend it took two hours, then a faster one with another model
+        std::fs::remove_file(first.path(root)).unwrap();
+        first.id = "20261001-090000".into();
+        first.started_at = first.finished_at.unwrap() - chrono::Duration::hours(2);
+        first.save(root).unwrap();
+        let mut second = first.clone();
+        second.id = "20261002-090000".into();
+        second.config = json!({"agents": 3, "forge": {"roles": {"protocol": {"model": "opus"}}}});
+        second.totals.completed = vec!["f002".into(), "f003".into()];
+        second.save(root).unwrap();
+
+        assert!(matches!(find(root, "2026100"), Err(RunsError::Ambiguous(..))));
+        assert!(matches!(find(root, "1999"), Err(RunsError::NotFound(_))));
+        let (a, b) = (find(root, "20261001").unwrap(), find(root, "20261002").unwrap());
+        assert_eq!(a.throughput(), Some(0.5));
+        let text = render_comparison(&a, &b);
+        assert!(text.contains("features/hour      0.50                 1.00                 +100%"), "{text}");
+        assert!(text.contains("sessions/feature   2.00                 1.00                 -50%"), "{text}");
+        assert!(text.contains("  agents: 1 → 3\n  forge.roles.protocol.model: sonnet → opus"), "{text}");
+    }
+}
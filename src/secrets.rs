@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use crate::config::SecretRef;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("secret {0} needs exactly one of `env` or `keychain`")]
+    NoSource(String),
+    #[error("secret {name}: ${env} is not set")]
+    EnvNotSet { name: String, env: String },
+    #[error("secret {name}: keychain lookup failed: {error}")]
+    Keychain { name: String, error: String },
+}
+
+/// The keychain CLI and its args for looking up a service (and account).
+fn keychain_command(service: &str, account: Option<&str>) -> (&'static str, Vec<String>) {
+    let mut args: Vec<String>;
+    if cfg!(target_os = "macos") {
+        args = vec!["find-generic-password".into(), "-w".into(), "-s".into(), service.into()];
+        if let Some(account) = account {
+            args.extend(["-a".into(), account.into()]);
+        }
+        ("security", args)
+    } else {
+        args = vec!["lookup".into(), "service".into(), service.into()];
+        if let Some(account) = account {
+            args.extend(["account".into(), account.into()]);
+        }
+        ("secret-tool", args)
+    }
+}
+
+/// The value of one `[secrets]` entry.
+pub fn resolve(name: &str, secret: &SecretRef) -> Result<String, SecretError> {
+    match (&secret.env, &secret.keychain) {
+        (Some(env), None) => std::env::var(env)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| SecretError::EnvNotSet { name: name.into(), env: env.clone() }),
+        (None, Some(service)) => {
+            let (program, args) = keychain_command(service, secret.account.as_deref());
+            let keychain_error = |error: String| SecretError::Keychain { name: name.into(), error };
+            let output = Command::new(program)
+                .args(&args)
+                .output()
+                .map_err(|e| keychain_error(format!("{program}: {e}")))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                return Err(keychain_error(if stderr.is_empty() { format!("no entry for {service}") } else { stderr }));
+            }
+            let value = String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string();
+            if value.is_empty() {
+                return Err(keychain_error(format!("entry for {service} is empty")));
+            }
+            Ok(value)
+        }
+        _ => Err(SecretError::NoSource(name.into())),
+    }
+}
+
+/// Every secret's value by name, ready to add to an agent's environment.
+pub fn resolve_all(secrets: &BTreeMap<String, SecretRef>) -> Result<BTreeMap<String, String>, SecretError> {
+    secrets.iter().map(|(name, secret)| Ok((name.clone(), resolve(name, secret)?))).collect()
+}
+
+/// An API key held in env var `name`: through `[secrets]` when it's
+/// declared there, else read from forge's own environment.
+pub fn env_or_secret(secrets: &BTreeMap<String, SecretRef>, name: &str) -> Option<String> {
+    match secrets.get(name) {
+        Some(secret) => resolve(name, secret).ok(),
+        None => std::env::var(name).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(var: &str) -> SecretRef {
+        SecretRef { env: Some(var.into()), ..Default::default() }
+    }
+
+    #[test]
+    fn resolves_env_secrets() {
+        // Any variable set in the test environment will do
+        let path = std::env::var("PATH").unwrap();
+        let secrets = BTreeMap::from([("ACME_API_KEY".to_string(), env("PATH"))]);
+        assert_eq!(resolve_all(&secrets).unwrap()["ACME_API_KEY"], path);
+        assert_eq!(env_or_secret(&secrets, "ACME_API_KEY"), Some(path));
+
+        let missing = BTreeMap::from([("K".to_string(), env("FORGE_TEST_SECRET_UNSET"))]);
+        assert_eq!(resolve_all(&missing).unwrap_err().to_string(), "secret K: $FORGE_TEST_SECRET_UNSET is not set");
+        let both = SecretRef { keychain: Some("s".into()), ..env("X") };
+        assert!(matches!(resolve("K", &both), Err(SecretError::NoSource(_))));
+        assert!(matches!(resolve("K", &SecretRef::default()), Err(SecretError::NoSource(_))));
+    }
+
+    #[test]
+    fn keychain_lookup_args() {
+        let (program, args) = keychain_command("forge-anthropic", Some("ci"));
+        if cfg!(target_os = "macos") {
+            assert_eq!(program, "security");
+            assert_eq!(args, ["find-generic-password", "-w", "-s", "forge-anthropic", "-a", "ci"]);
+        } else {
+            assert_eq!(program, "secret-tool");
+            assert_eq!(args, ["lookup", "service", "forge-anthropic", "account", "ci"]);
+        }
+    }
+}
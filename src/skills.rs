@@ -1,24 +1,179 @@
-/// Embedded skill files. Written to .claude/skills/ and .agents/skills/ by forge init.
-///
-/// Each skill is a (relative_path, content) pair.
+//! Embedded skill files. Written to .claude/skills/ and .agents/skills/ by forge init,
+//! merged with any user skills.
+//!
+//! Each skill is a (relative_path, content) pair.
 
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::context_index::content_hash;
+
+/// Where user skills live, lowest precedence first: the user's own
+/// (`$XDG_CONFIG_HOME/forge/skills`, else `~/.config/forge/skills`), then the
+/// project's `skills/`. Each holds `<skill-name>/<file>` like the built-ins.
+pub fn user_skill_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    let config = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::var("HOME").map(PathBuf::from).unwrap_or_default().join(".config"));
+    vec![config.join("forge/skills"), project_dir.join("skills")]
+}
+
+/// Every file under `dir`, by path relative to `base`.
+fn collect_files(base: &Path, dir: &Path, out: &mut BTreeMap<PathBuf, Vec<u8>>) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(base, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            out.insert(relative.to_path_buf(), std::fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// The built-in skills with each of `user_dirs` laid over them in turn: a
+/// user file replaces the built-in (or earlier user) file at the same path,
+/// and new skills or files are added. Keyed by `<skill-name>/<file>`.
+pub fn merged_skills(user_dirs: &[PathBuf]) -> Result<BTreeMap<PathBuf, Vec<u8>>, std::io::Error> {
+    let mut files = BTreeMap::new();
+    for (skill_name, skill_files) in all_skills() {
+        for (filename, content) in skill_files {
+            files.insert(Path::new(skill_name).join(filename), content.as_bytes().to_vec());
+        }
+    }
+    for dir in user_dirs.iter().filter(|d| d.is_dir()) {
+        collect_files(dir, dir, &mut files)?;
+    }
+    Ok(files)
+}
+
+/// What forge wrote last into a skills directory, kept in
+/// `<dir>/.forge-skills.json` so the next install can tell its own files from
+/// ones edited since.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    /// Version and content hash of each skill as installed
+    #[serde(default)]
+    skills: BTreeMap<String, SkillVersion>,
+    /// Hash of each file as written, by `<skill-name>/<file>`
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SkillVersion {
+    version: String,
+    hash: String,
+}
+
+const MANIFEST: &str = ".forge-skills.json";
+
+impl Manifest {
+    fn load(dir: &Path) -> Self {
+        let json = std::fs::read_to_string(dir.join(MANIFEST)).unwrap_or_default();
+        serde_json::from_str(&json).unwrap_or_default()
+    }
+
+    fn save(&self, dir: &Path) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(dir.join(MANIFEST), json + "\n")
+    }
+}
+
+/// What a skill sync did.
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    /// Skills whose content differs from the copies installed before
+    pub changed: BTreeSet<String>,
+    /// Skills installed for the first time
+    pub added: BTreeSet<String>,
+    /// Files edited since forge installed them, left as they are
+    pub kept: Vec<PathBuf>,
+}
+
+/// Each skill's hash, over its files' paths and contents.
+fn skill_hashes(files: &BTreeMap<PathBuf, Vec<u8>>) -> BTreeMap<String, String> {
+    let mut contents: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    for (relative, content) in files {
+        let Some(skill) = relative.components().next() else { continue };
+        let buf = contents.entry(skill.as_os_str().to_string_lossy().into_owned()).or_default();
+        buf.extend(relative.to_string_lossy().as_bytes());
+        buf.push(0);
+        buf.extend(content);
+        buf.push(0);
+    }
+    contents.into_iter().map(|(skill, buf)| (skill, content_hash(buf))).collect()
+}
 
 /// Sync all skills to both .claude/skills/ and .agents/skills/.
 /// Called at the start of forge run to ensure existing projects have
-/// Codex-compatible skills without requiring re-init.
-pub fn sync_skills(project_dir: &Path) -> Result<(), std::io::Error> {
-    let dirs = [".claude/skills", ".agents/skills"];
-    for (skill_name, files) in all_skills() {
-        for base in &dirs {
-            let skill_dir = project_dir.join(base).join(skill_name);
-            std::fs::create_dir_all(&skill_dir)?;
-            for (filename, content) in &files {
-                std::fs::write(skill_dir.join(filename), content)?;
+/// Codex-compatible skills without requiring re-init. Files edited since
+/// forge installed them are kept unless `force`.
+pub fn sync_skills(project_dir: &Path, force: bool) -> Result<SyncReport, std::io::Error> {
+    sync_skills_from(project_dir, &user_skill_dirs(project_dir), force)
+}
+
+/// [`sync_skills`] with the user skill directories given.
+pub fn sync_skills_from(
+    project_dir: &Path,
+    user_dirs: &[PathBuf],
+    force: bool,
+) -> Result<SyncReport, std::io::Error> {
+    let files = merged_skills(user_dirs)?;
+    let hashes = skill_hashes(&files);
+    let mut report = SyncReport::default();
+    for base in [".claude/skills", ".agents/skills"] {
+        let dir = project_dir.join(base);
+        std::fs::create_dir_all(&dir)?;
+        let old = Manifest::load(&dir);
+        let existed: BTreeSet<&String> = hashes.keys().filter(|skill| dir.join(skill).is_dir()).collect();
+        let mut manifest = Manifest { skills: BTreeMap::new(), files: old.files.clone() };
+        for (relative, content) in &files {
+            let key = relative.to_string_lossy().replace('\\', "/");
+            let path = dir.join(relative);
+            let on_disk = std::fs::read(&path).ok();
+            // Only a file forge recorded writing can be told apart from a user's edit
+            let edited = match (old.files.get(&key), &on_disk) {
+                (Some(written), Some(current)) => content_hash(current) != *written && current != content,
+                _ => false,
+            };
+            if edited && !force {
+                report.kept.push(Path::new(base).join(relative));
+                continue;
             }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if on_disk.as_ref() != Some(content) {
+                std::fs::write(&path, content)?;
+            }
+            manifest.files.insert(key, content_hash(content));
         }
+        for (skill, hash) in &hashes {
+            match old.skills.get(skill) {
+                Some(installed) if installed.hash == *hash => {}
+                Some(_) => {
+                    report.changed.insert(skill.clone());
+                }
+                // Installed before forge kept versions
+                None if existed.contains(skill) => {
+                    report.changed.insert(skill.clone());
+                }
+                None => {
+                    report.added.insert(skill.clone());
+                }
+            }
+            let version = SkillVersion { version: env!("CARGO_PKG_VERSION").to_string(), hash: hash.clone() };
+            manifest.skills.insert(skill.clone(), version);
+        }
+        manifest.save(&dir)?;
     }
-    Ok(())
+    report.added.retain(|skill| !report.changed.contains(skill));
+    Ok(report)
 }
 
 pub fn forge_planning_files() -> Vec<(&'static str, &'static str)> {
@@ -146,7 +301,7 @@ mod tests {
     #[test]
     fn sync_skills_populates_both_dirs() {
         let dir = tempfile::tempdir().unwrap();
-        sync_skills(dir.path()).unwrap();
+        sync_skills_from(dir.path(), &[], false).unwrap();
 
         for base in &[".claude/skills", ".agents/skills"] {
             for (skill_name, files) in all_skills() {
@@ -161,10 +316,61 @@ mod tests {
     #[test]
     fn sync_skills_is_idempotent() {
         let dir = tempfile::tempdir().unwrap();
-        sync_skills(dir.path()).unwrap();
-        sync_skills(dir.path()).unwrap(); // second call should not fail
+        sync_skills_from(dir.path(), &[], false).unwrap();
+        sync_skills_from(dir.path(), &[], false).unwrap(); // second call should not fail
 
         let path = dir.path().join(".agents/skills/forge-protocol/SKILL.md");
         assert!(path.exists());
     }
+
+    #[test]
+    fn user_skills_override_and_extend_built_ins() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = dir.path().join("global");
+        let local = dir.path().join("project/skills");
+        for (base, file, content) in [
+            (&global, "forge-protocol/TESTING.md", "global testing\n"),
+            (&global, "team-review/SKILL.md", "global review\n"),
+            (&local, "forge-protocol/TESTING.md", "project testing\n"),
+        ] {
+            let path = base.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let project = dir.path().join("project");
+        sync_skills_from(&project, &[global, local], false).unwrap();
+
+        for base in [".claude/skills", ".agents/skills"] {
+            let read = |file: &str| std::fs::read_to_string(project.join(base).join(file)).unwrap();
+            assert_eq!(read("forge-protocol/TESTING.md"), "project testing\n");
+            assert_eq!(read("team-review/SKILL.md"), "global review\n");
+            assert_eq!(read("forge-protocol/SKILL.md"), forge_protocol_files()[0].1);
+        }
+    }
+
+    #[test]
+    fn keeps_edited_files_unless_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("user");
+        let report = sync_skills_from(dir.path(), std::slice::from_ref(&user), false).unwrap();
+        assert_eq!(report.added.len(), all_skills().len());
+        assert!(report.changed.is_empty() && report.kept.is_empty());
+
+        let testing = dir.path().join(".claude/skills/forge-protocol/TESTING.md");
+        std::fs::write(&testing, "our own testing rules\n").unwrap();
+        std::fs::create_dir_all(user.join("forge-planning")).unwrap();
+        std::fs::write(user.join("forge-planning/SKILL.md"), "---\nname: forge-planning\n---\n").unwrap();
+
+        let report = sync_skills_from(dir.path(), std::slice::from_ref(&user), false).unwrap();
+        assert_eq!(report.changed, BTreeSet::from(["forge-planning".to_string()]));
+        assert_eq!(report.kept, [PathBuf::from(".claude/skills/forge-protocol/TESTING.md")]);
+        assert_eq!(std::fs::read_to_string(&testing).unwrap(), "our own testing rules\n");
+        // The other copy wasn't edited, so it's still forge's
+        let codex = dir.path().join(".agents/skills/forge-protocol/TESTING.md");
+        assert_eq!(std::fs::read_to_string(codex).unwrap(), forge_protocol_files()[4].1);
+
+        let report = sync_skills_from(dir.path(), &[user], true).unwrap();
+        assert!(report.changed.is_empty() && report.kept.is_empty());
+        assert_eq!(std::fs::read_to_string(&testing).unwrap(), forge_protocol_files()[4].1);
+    }
 }
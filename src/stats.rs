@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::events::{Event, EventKind};
+use crate::transcripts::{SessionRole, TranscriptMetrics};
+
+/// How one role/backend/model combination has done on this project.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ModelStats {
+    pub role: String,
+    pub backend: String,
+    pub model: String,
+    /// `a` or `b` for executors in an A/B run, so the halves stay apart
+    /// even on the same model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub sessions: usize,
+    /// Features it had sessions on.
+    pub features: usize,
+    /// Features verify ruled on after its sessions, and of those the ones
+    /// that passed the first time.
+    pub verified: usize,
+    pub first_try_passed: usize,
+    /// Features marked blocked after one of its sessions.
+    pub blocked: usize,
+    /// Over its Claude transcripts; None for other backends.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_cost_usd: Option<f64>,
+}
+
+impl ModelStats {
+    pub fn first_try_rate(&self) -> Option<f64> {
+        (self.verified > 0).then(|| self.first_try_passed as f64 / self.verified as f64)
+    }
+
+    pub fn sessions_per_feature(&self) -> Option<f64> {
+        (self.features > 0).then(|| self.sessions as f64 / self.features as f64)
+    }
+
+    pub fn blocked_rate(&self) -> Option<f64> {
+        (self.features > 0).then(|| self.blocked as f64 / self.features as f64)
+    }
+}
+
+type Key = (String, String, String, Option<String>);
+
+#[derive(Default)]
+struct Tally {
+    sessions: usize,
+    features: BTreeSet<String>,
+    verified: BTreeSet<String>,
+    first_try_passed: usize,
+    blocked: BTreeSet<String>,
+}
+
+/// Tally `events` (in time order) by the role, backend and model of the
+/// session behind each verdict. A feature's outcome goes to whoever last
+/// had a session on it. Sessions recorded before forge noted the model
+/// show as `unknown`.
+pub fn model_stats(events: &[Event], transcripts: &TranscriptMetrics) -> Vec<ModelStats> {
+    let mut tallies: BTreeMap<Key, Tally> = BTreeMap::new();
+    let mut last: HashMap<String, Key> = HashMap::new();
+    for event in events {
+        match &event.kind {
+            EventKind::SessionStart { role, feature_id, backend, model, variant } => {
+                let known = |v: &Option<String>| v.clone().unwrap_or_else(|| "unknown".to_string());
+                let key = (role.clone(), known(backend), known(model), variant.clone());
+                let tally = tallies.entry(key.clone()).or_default();
+                tally.sessions += 1;
+                if let Some(id) = feature_id.as_ref().filter(|_| role == "executor") {
+                    tally.features.insert(id.clone());
+                    last.insert(id.clone(), key);
+                }
+            }
+            EventKind::FeatureVerified { feature_id, passed } => {
+                if let Some(tally) = last.get(feature_id).and_then(|key| tallies.get_mut(key))
+                    && tally.verified.insert(feature_id.clone())
+                    && *passed
+                {
+                    tally.first_try_passed += 1;
+                }
+            }
+            EventKind::FeatureBlocked { feature_id, .. } => {
+                if let Some(tally) = last.get(feature_id).and_then(|key| tallies.get_mut(key)) {
+                    tally.blocked.insert(feature_id.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tallies
+        .into_iter()
+        .map(|((role, backend, model, variant), tally)| {
+            let avg_cost_usd = (backend == "claude").then(|| avg_cost(transcripts, &role, &model)).flatten();
+            ModelStats {
+                sessions: tally.sessions,
+                features: tally.features.len(),
+                verified: tally.verified.len(),
+                first_try_passed: tally.first_try_passed,
+                blocked: tally.blocked.len(),
+                avg_cost_usd,
+                role,
+                backend,
+                model,
+                variant,
+            }
+        })
+        .collect()
+}
+
+/// Mean cost of the transcripts from `role` sessions whose model id
+/// contains `model` (`sonnet` in `claude-sonnet-4-5-20250929`).
+fn avg_cost(transcripts: &TranscriptMetrics, role: &str, model: &str) -> Option<f64> {
+    let role = match role {
+        "executor" => SessionRole::Executor,
+        "orchestrating" => SessionRole::Orchestrating,
+        _ => return None,
+    };
+    let model = model.to_ascii_lowercase();
+    let costs: Vec<f64> = transcripts
+        .sessions
+        .iter()
+        .filter(|s| s.role == role && s.model.as_deref().is_some_and(|m| m.to_ascii_lowercase().contains(&model)))
+        .map(|s| s.cost_usd)
+        .collect();
+    (!costs.is_empty()).then(|| costs.iter().sum::<f64>() / costs.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcripts::SessionMetrics;
+    use chrono::Utc;
+
+    fn event(kind: EventKind) -> Event {
+        Event { ts: Utc::now(), agent: "agent-1".into(), kind }
+    }
+
+    fn start(feature: &str, backend: &str, model: &str) -> Event {
+        event(EventKind::SessionStart {
+            role: "executor".into(),
+            feature_id: Some(feature.into()),
+            backend: Some(backend.into()),
+            model: Some(model.into()),
+            variant: None,
+        })
+    }
+
+    fn verified(feature: &str, passed: bool) -> Event {
+        event(EventKind::FeatureVerified { feature_id: feature.into(), passed })
+    }
+
+    #[test]
+    fn outcomes_go_to_the_model_that_had_the_feature() {
+        let events = [
+            start("f001", "claude", "sonnet"),
+            verified("f001", true),
+            start("f002", "claude", "sonnet"),
+            verified("f002", false),
+            start("f002", "claude", "sonnet"),
+            verified("f002", true),
+            start("f003", "codex", "o3"),
+            event(EventKind::FeatureBlocked { feature_id: "f003".into(), reason: None }),
+            event(EventKind::SessionStart {
+                role: "orchestrating".into(),
+                feature_id: None,
+                backend: None,
+                model: None,
+                variant: None,
+            }),
+            event(EventKind::SessionStart {
+                role: "executor".into(),
+                feature_id: Some("f004".into()),
+                backend: Some("claude".into()),
+                model: Some("sonnet".into()),
+                variant: Some("b".into()),
+            }),
+            verified("f004", true),
+        ];
+        let transcripts = TranscriptMetrics {
+            sessions: [1.0, 2.0, 3.0, 10.0]
+                .iter()
+                .zip([SessionRole::Executor, SessionRole::Executor, SessionRole::Executor, SessionRole::Orchestrating])
+                .map(|(&cost_usd, role)| SessionMetrics {
+                    role,
+                    model: Some("claude-sonnet-4-5".into()),
+                    cost_usd,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let stats = model_stats(&events, &transcripts);
+        let sonnet = stats.iter().find(|s| s.model == "sonnet" && s.variant.is_none()).unwrap();
+        assert_eq!((sonnet.sessions, sonnet.features, sonnet.verified, sonnet.first_try_passed), (3, 2, 2, 1));
+        assert_eq!(sonnet.first_try_rate(), Some(0.5));
+        assert_eq!(sonnet.sessions_per_feature(), Some(1.5));
+        assert_eq!(sonnet.avg_cost_usd, Some(2.0));
+
+        let o3 = stats.iter().find(|s| s.model == "o3").unwrap();
+        assert_eq!((o3.blocked_rate(), o3.first_try_rate(), o3.avg_cost_usd), (Some(1.0), None, None));
+        let orchestrating = stats.iter().find(|s| s.role == "orchestrating").unwrap();
+        assert_eq!((orchestrating.sessions, orchestrating.model.as_str()), (1, "unknown"));
+        let b = stats.iter().find(|s| s.variant.as_deref() == Some("b")).unwrap();
+        assert_eq!((b.model.as_str(), b.sessions, b.first_try_passed), ("sonnet", 1, 1));
+    }
+}
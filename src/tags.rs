@@ -0,0 +1,120 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use crate::config::GitConfig;
+use crate::events::{self, EventKind};
+use crate::features::{Feature, FeatureList, FeatureStatus, FeatureType};
+use crate::git;
+use crate::pr;
+use crate::verify::VerifyResult;
+
+/// `[git] tag_template` for a milestone label.
+pub fn tag_name(config: &GitConfig, label: &str) -> String {
+    config.tag_template.replace("{milestone}", &label.to_lowercase())
+}
+
+/// The tag's message: the milestone and the features it took.
+pub fn tag_message(list: &FeatureList, review: &Feature) -> String {
+    let label = FeatureList::milestone_label(review);
+    let features = pr::milestone_features(list, review);
+    let mut out = format!("{label}: {}\n\n{} features:\n", pr::headline(&review.description), features.len());
+    for feature in features {
+        let _ = writeln!(out, "- {} {}", feature.id, pr::headline(&feature.description));
+    }
+    out
+}
+
+/// With `[git] tag_milestones`, tag HEAD for each review feature among
+/// this session's that is done and passed verify. Problems are printed, not
+/// fatal; a tag that exists is left alone.
+pub fn tag_milestones(project_dir: &Path, config: &GitConfig, feature_ids: &[String], results: &[VerifyResult]) {
+    if !config.tag_milestones {
+        return;
+    }
+    let Ok(list) = FeatureList::load(project_dir) else {
+        return;
+    };
+    let passed = |id: &str| results.iter().any(|r| r.feature_id == id && r.passed);
+    let reviews = list.features.iter().filter(|f| {
+        feature_ids.contains(&f.id) && f.feature_type == FeatureType::Review && f.status == FeatureStatus::Done && passed(&f.id)
+    });
+    for review in reviews {
+        let tag = tag_name(config, &FeatureList::milestone_label(review));
+        match git::create_tag(project_dir, &tag, &tag_message(&list, review)) {
+            Ok(true) => info!("Tagged {tag}"),
+            Ok(false) => continue,
+            Err(e) => {
+                warn!("Tagging {tag} failed (non-fatal): {e}");
+                continue;
+            }
+        }
+        if config.push_state
+            && git::has_remote(project_dir)
+            && let Err(e) = git::push_tag(project_dir, &tag)
+        {
+            warn!("Pushing {tag} failed (non-fatal): {e}");
+        }
+        events::emit(project_dir, events::FORGE, EventKind::MilestoneTagged { tag, feature_id: review.id.clone() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn feature(id: &str, feature_type: FeatureType, description: &str, depends_on: &[&str]) -> Feature {
+        Feature {
+            id: id.into(),
+            feature_type,
+            scope: "core".into(),
+            description: description.into(),
+            verify: format!("scripts/verify/{id}.sh"),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            priority: 1,
+            status: FeatureStatus::Done,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    #[test]
+    fn tags_done_milestones_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let out = Command::new("git").args(args).current_dir(dir.path()).output().unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "t@t"]);
+        git(&["config", "user.name", "t"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        let list = FeatureList {
+            features: vec![
+                feature("f001", FeatureType::Implement, "Write path", &[]),
+                feature("f002", FeatureType::Review, "M4 review: writes land", &["f001"]),
+            ],
+        };
+        list.save(dir.path()).unwrap();
+        let ids = vec!["f001".to_string(), "f002".to_string()];
+        let results = [VerifyResult { feature_id: "f002".into(), passed: true, output: String::new() }];
+
+        tag_milestones(dir.path(), &GitConfig::default(), &ids, &results);
+        assert_eq!(git(&["tag"]), "", "off by default");
+        let config = GitConfig { tag_milestones: true, ..GitConfig::default() };
+        tag_milestones(dir.path(), &config, &ids, &[]);
+        assert_eq!(git(&["tag"]), "", "verify must pass");
+        tag_milestones(dir.path(), &config, &ids, &results);
+        tag_milestones(dir.path(), &config, &ids, &results);
+        assert_eq!(git(&["tag"]), "m4-complete");
+        assert_eq!(
+            git(&["tag", "-l", "--format=%(contents)", "m4-complete"]),
+            "M4: M4 review: writes land\n\n2 features:\n- f001 Write path\n- f002 M4 review: writes land"
+        );
+        let events = events::read_stream(&events::events_dir(dir.path()).join("forge.jsonl")).unwrap();
+        assert_eq!(events.len(), 1);
+    }
+}
@@ -34,6 +34,19 @@ pub fn generate_claude_md(config: &ForgeConfig) -> String {
     }
     lines.push(String::new());
 
+    let scoped: Vec<_> = config.scopes.iter().filter(|(_, s)| !s.principles.is_empty()).collect();
+    if !scoped.is_empty() {
+        lines.push("## Scope Principles (when your feature is in that scope)".into());
+        for (name, scope) in scoped {
+            lines.push(String::new());
+            lines.push(format!("### {name}"));
+            for (rule, text) in &scope.principles {
+                lines.push(format!("- {rule}: {text}"));
+            }
+        }
+        lines.push(String::new());
+    }
+
     lines.push("## Forge Agent".into());
     lines.push(String::new());
     lines.push("You are in a managed development loop. Follow the forge-protocol skill.".into());
@@ -47,6 +60,15 @@ pub fn generate_claude_md(config: &ForgeConfig) -> String {
     lines.push("- `context/patterns/` — code conventions.".into());
     lines.push("- `context/poc/` — POC outcomes (goal, result, learnings, design impact).".into());
     lines.push("- `context/references/` — external knowledge, read instead of re-searching.".into());
+    let extra: Vec<String> = config
+        .context
+        .categories()
+        .into_iter()
+        .filter(|c| !crate::context::DEFAULT_CATEGORIES.contains(&c.as_str()))
+        .collect();
+    for cat in &extra {
+        lines.push(format!("- `context/{cat}/` — project-specific entries."));
+    }
     lines.push("- `feedback/session-review.md` — last session's review (read first!).".into());
     lines.push("- `context/packages/{feature_id}.md` — pre-compiled context for your feature (if available).".into());
     lines.push(String::new());
@@ -56,7 +78,11 @@ pub fn generate_claude_md(config: &ForgeConfig) -> String {
     lines.push("2. Commit the claim. If push fails, pick another.".into());
     lines.push("3. Implement. Run the feature's `verify` command.".into());
     lines.push("4. Pass -> status \"done\". Fail -> fix and retry.".into());
-    lines.push("5. Discoveries -> write to context/{decisions,gotchas,patterns}/".into());
+    let discoveries: Vec<&str> = ["decisions", "gotchas", "patterns"]
+        .into_iter()
+        .chain(extra.iter().map(String::as_str))
+        .collect();
+    lines.push(format!("5. Discoveries -> write to context/{{{}}}/", discoveries.join(",")));
     lines.push(
         "6. External knowledge (web search, blog, doc) -> write to context/references/".into(),
     );
@@ -72,6 +98,25 @@ pub fn generate_claude_md(config: &ForgeConfig) -> String {
     lines.join("\n") + "\n"
 }
 
+/// Every instructions file forge writes, by path, all from the same
+/// template: CLAUDE.md and AGENTS.md for the agents, and the rules files
+/// Cursor, Windsurf and Copilot read for anyone working in the project
+/// with those editors.
+pub fn agent_files(config: &ForgeConfig) -> Vec<(&'static str, String)> {
+    let md = generate_claude_md(config);
+    let description = format!("Forge protocol and context for {}", config.project.name);
+    vec![
+        ("CLAUDE.md", md.clone()),
+        ("AGENTS.md", md.clone()),
+        (
+            ".cursor/rules/forge.mdc",
+            format!("---\ndescription: {description}\nglobs:\nalwaysApply: true\n---\n\n{md}"),
+        ),
+        (".windsurf/rules/forge.md", format!("---\ntrigger: always_on\ndescription: {description}\n---\n\n{md}")),
+        (".github/instructions/forge.instructions.md", format!("---\napplyTo: \"**\"\n---\n\n{md}")),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +130,33 @@ mod tests {
         assert!(md.contains("Stack: Rust, axum"));
     }
 
+    #[test]
+    fn claude_md_has_scope_principles() {
+        let mut config = ForgeConfig::scaffold("test", "Rust");
+        assert!(!generate_claude_md(&config).contains("## Scope Principles"));
+        let scope = crate::config::Scope {
+            dir: None,
+            owns: vec!["src/auth/".into()],
+            api: String::new(),
+            upstream: vec![],
+            principles: [("secrets".to_string(), "Never log tokens".to_string())].into(),
+        };
+        config.scopes.insert("auth".into(), scope);
+        let md = generate_claude_md(&config);
+        assert!(md.contains("## Scope Principles (when your feature is in that scope)\n\n### auth\n- secrets: Never log tokens\n\n## Forge Agent"));
+    }
+
+    #[test]
+    fn claude_md_lists_custom_categories() {
+        let mut config = ForgeConfig::scaffold("test", "Rust");
+        let md = generate_claude_md(&config);
+        assert!(md.contains("5. Discoveries -> write to context/{decisions,gotchas,patterns}/\n"));
+        config.context.categories = vec!["benchmarks".into()];
+        let md = generate_claude_md(&config);
+        assert!(md.contains("- `context/benchmarks/` — project-specific entries.\n"));
+        assert!(md.contains("5. Discoveries -> write to context/{decisions,gotchas,patterns,benchmarks}/\n"));
+    }
+
     #[test]
     fn claude_md_has_principles() {
         let config = ForgeConfig::scaffold("test", "Rust");
@@ -128,4 +200,18 @@ mod tests {
             "CLAUDE.md is {line_count} lines, should be <= 50"
         );
     }
+
+    #[test]
+    fn editor_rules_wrap_the_same_instructions() {
+        let config = ForgeConfig::scaffold("my-app", "Rust");
+        let md = generate_claude_md(&config);
+        let files = agent_files(&config);
+        assert_eq!(files.len(), 5);
+        for (path, content) in &files {
+            assert!(content.ends_with(&md), "{path}");
+        }
+        let (_, cursor) = files.iter().find(|(p, _)| p.ends_with(".mdc")).unwrap();
+        let frontmatter = "---\ndescription: Forge protocol and context for my-app\nglobs:\nalwaysApply: true\n---\n";
+        assert!(cursor.starts_with(frontmatter), "{cursor}");
+    }
 }
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::features::{FeatureList, FeatureType};
+use crate::transcripts::{self, SessionRole};
+
+/// Digit runs, so `line 42` and `line 17` count as one error.
+pub(crate) static DIGITS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+
+/// Error lines are cut here; the start says what went wrong.
+const ERROR_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+/// How sessions on one feature type went, on average.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TypeStats {
+    pub sessions: usize,
+    /// Assistant messages: a headless session has one prompt, so this is
+    /// how many times the model went back and forth with its tools.
+    pub avg_turns: f64,
+    pub avg_tool_calls: f64,
+    pub avg_cost_usd: f64,
+}
+
+/// What `forge transcripts analyze` found across the project's sessions.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct PatternReport {
+    pub sessions: usize,
+    /// Tool calls, busiest first.
+    pub tools: Vec<Count>,
+    /// One tool call followed by the next, e.g. `Edit -> Bash`.
+    pub sequences: Vec<Count>,
+    /// Failed tool results, first line with numbers masked.
+    pub errors: Vec<Count>,
+    /// Files opened with Read, relative to the project (worktrees folded in).
+    pub files_read: Vec<Count>,
+    /// Executor sessions by their feature's type.
+    pub by_feature_type: BTreeMap<String, TypeStats>,
+}
+
+/// What one transcript shows beyond `transcripts::SessionMetrics`.
+#[derive(Debug, Default)]
+struct SessionPatterns {
+    tools: Vec<String>,
+    errors: Vec<String>,
+    reads: Vec<String>,
+}
+
+fn scan(path: &Path, project_dir: &Path) -> std::io::Result<SessionPatterns> {
+    let mut patterns = SessionPatterns::default();
+    let file = std::fs::File::open(path)?;
+    for line in std::io::BufReader::new(file).lines() {
+        let Ok(entry) = serde_json::from_str::<Value>(&line?) else {
+            continue;
+        };
+        let blocks = entry.pointer("/message/content").and_then(Value::as_array).into_iter().flatten();
+        for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+                Some("tool_use") => {
+                    let name = block.get("name").and_then(Value::as_str).unwrap_or("unknown");
+                    if name == "Read"
+                        && let Some(file) = block.pointer("/input/file_path").and_then(Value::as_str)
+                    {
+                        patterns.reads.push(project_relative(file, project_dir));
+                    }
+                    patterns.tools.push(name.to_string());
+                }
+                Some("tool_result") if block.get("is_error").and_then(Value::as_bool) == Some(true) => {
+                    if let Some(error) = error_line(block.get("content")) {
+                        patterns.errors.push(error);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(patterns)
+}
+
+/// The first telling line of a tool result, as a string or text blocks.
+fn error_line(content: Option<&Value>) -> Option<String> {
+    let text = match content? {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => {
+            blocks.iter().filter_map(|b| b.get("text").and_then(Value::as_str)).collect::<Vec<_>>().join("\n")
+        }
+        _ => return None,
+    };
+    let lines: Vec<String> = text
+        .replace("<tool_use_error>", "")
+        .replace("</tool_use_error>", "")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| DIGITS.replace_all(l, "N").chars().take(ERROR_CHARS).collect())
+        .collect();
+    // Bash failures open with their exit code; what failed comes next
+    lines.iter().find(|l| *l != "Exit code N").or(lines.first()).cloned()
+}
+
+/// `file` relative to the project, with `.forge/worktrees/<agent>/` dropped
+/// so every agent's copy of a file counts as the one file.
+fn project_relative(file: &str, project_dir: &Path) -> String {
+    let root = project_dir.canonicalize().unwrap_or_else(|_| project_dir.to_path_buf());
+    let path = PathBuf::from(file);
+    let rel = path.strip_prefix(&root).map(Path::to_path_buf).unwrap_or(path);
+    let mut parts: Vec<_> = rel.components().collect();
+    if parts.len() > 3 && rel.starts_with(".forge/worktrees") {
+        parts.drain(..3);
+    }
+    parts.iter().collect::<PathBuf>().to_string_lossy().to_string()
+}
+
+fn top(counts: HashMap<String, usize>, limit: usize) -> Vec<Count> {
+    let mut counts: Vec<Count> = counts.into_iter().map(|(name, count)| Count { name, count }).collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts.truncate(limit);
+    counts
+}
+
+fn type_label(feature_type: &FeatureType) -> &'static str {
+    match feature_type {
+        FeatureType::Implement => "implement",
+        FeatureType::Review => "review",
+        FeatureType::Poc => "poc",
+    }
+}
+
+/// Mine `paths` (Claude transcripts of `project_dir`), keeping the `limit`
+/// most frequent of each list.
+pub fn analyze(
+    project_dir: &Path,
+    paths: &[PathBuf],
+    features: Option<&FeatureList>,
+    limit: usize,
+) -> std::io::Result<PatternReport> {
+    let feature_types: HashMap<&str, &FeatureType> =
+        features.into_iter().flat_map(|l| &l.features).map(|f| (f.id.as_str(), &f.feature_type)).collect();
+    let (mut tools, mut sequences, mut errors, mut reads) =
+        (HashMap::new(), HashMap::new(), HashMap::new(), HashMap::new());
+    let mut by_type: BTreeMap<String, (usize, usize, usize, f64)> = BTreeMap::new();
+
+    for path in paths {
+        let metrics = transcripts::analyze_session(path)?;
+        let patterns = scan(path, project_dir)?;
+        for tool in &patterns.tools {
+            *tools.entry(tool.clone()).or_default() += 1;
+        }
+        for pair in patterns.tools.windows(2) {
+            *sequences.entry(format!("{} -> {}", pair[0], pair[1])).or_default() += 1;
+        }
+        for error in patterns.errors {
+            *errors.entry(error).or_default() += 1;
+        }
+        for file in patterns.reads {
+            *reads.entry(file).or_default() += 1;
+        }
+        if metrics.role == SessionRole::Executor {
+            let kind = metrics
+                .feature_id
+                .as_deref()
+                .and_then(|id| feature_types.get(id))
+                .map_or("unknown", |t| type_label(t));
+            let entry = by_type.entry(kind.to_string()).or_default();
+            entry.0 += 1;
+            entry.1 += metrics.assistant_messages;
+            entry.2 += patterns.tools.len();
+            entry.3 += metrics.cost_usd;
+        }
+    }
+
+    let by_feature_type = by_type
+        .into_iter()
+        .map(|(kind, (sessions, turns, calls, cost))| {
+            let n = sessions as f64;
+            let stats = TypeStats {
+                sessions,
+                avg_turns: turns as f64 / n,
+                avg_tool_calls: calls as f64 / n,
+                avg_cost_usd: cost / n,
+            };
+            (kind, stats)
+        })
+        .collect();
+    Ok(PatternReport {
+        sessions: paths.len(),
+        tools: top(tools, limit),
+        sequences: top(sequences, limit),
+        errors: top(errors, limit),
+        files_read: top(reads, limit),
+        by_feature_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = r#"{"type":"user","message":{"role":"user","content":"Your assigned feature is f001. Go."}}
+{"type":"assistant","message":{"id":"m1","content":[{"type":"tool_use","name":"Read","input":{"file_path":"PROJECT/.forge/worktrees/agent-1/src/db.rs"}}]}}
+{"type":"assistant","message":{"id":"m2","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"PROJECT/src/db.rs"}}]}}
+{"type":"assistant","message":{"id":"m3","content":[{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","is_error":true,"content":"Exit code 101\nerror[E0308]: mismatched types at line 42\n  more"}]}}
+{"type":"assistant","message":{"id":"m4","content":[{"type":"tool_use","name":"Read","input":{"file_path":"PROJECT/src/db.rs"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","is_error":true,"content":[{"type":"text","text":"error[E0308]: mismatched types at line 7"}]}]}}
+"#;
+
+    #[test]
+    fn counts_tools_errors_and_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().canonicalize().unwrap();
+        let path = project.join("s1.jsonl");
+        std::fs::write(&path, TRANSCRIPT.replace("PROJECT", &project.to_string_lossy())).unwrap();
+        let list: FeatureList = serde_json::from_str(
+            r#"{"features":[{"id":"f001","type":"poc","scope":"a","description":"x","verify":"true"}]}"#,
+        )
+        .unwrap();
+
+        let report = analyze(&project, &[path], Some(&list), 10).unwrap();
+        let count = |name: &str, count| Count { name: name.into(), count };
+        assert_eq!(report.tools, [count("Read", 2), count("Bash", 1), count("Edit", 1)]);
+        assert_eq!(report.sequences[0], count("Bash -> Read", 1));
+        assert_eq!(report.errors, [count("error[EN]: mismatched types at line N", 2)]);
+        assert_eq!(report.files_read, [count("src/db.rs", 2)]);
+        let poc = &report.by_feature_type["poc"];
+        assert_eq!((poc.sessions, poc.avg_turns, poc.avg_tool_calls), (1, 4.0, 4.0));
+    }
+}
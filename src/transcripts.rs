@@ -0,0 +1,514 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+
+/// Token counts as reported in assistant `usage` blocks.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_creation + self.cache_read
+    }
+
+    pub(crate) fn add(&mut self, other: &TokenUsage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+    }
+
+    fn from_usage(usage: &Value) -> Self {
+        let n = |k: &str| usage.get(k).and_then(Value::as_u64).unwrap_or(0);
+        TokenUsage {
+            input: n("input_tokens"),
+            output: n("output_tokens"),
+            cache_creation: n("cache_creation_input_tokens"),
+            cache_read: n("cache_read_input_tokens"),
+        }
+    }
+}
+
+/// Which forge role ran the session, recognised from the prompt forge sent.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionRole {
+    Executor,
+    Orchestrating,
+    #[default]
+    Unknown,
+}
+
+/// What one Claude Code session did, distilled from its JSONL transcript.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionMetrics {
+    pub session_id: String,
+    pub role: SessionRole,
+    /// The feature an executor session was assigned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<i64>,
+    /// Prompts typed (or piped) by the user; tool results don't count.
+    pub turns: usize,
+    pub assistant_messages: usize,
+    pub tokens: TokenUsage,
+    /// At list prices per message model; see `cost::model_price`.
+    pub cost_usd: f64,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub unpriced_models: BTreeSet<String>,
+    pub tool_calls: BTreeMap<String, usize>,
+    pub files_touched: BTreeSet<String>,
+    /// Lines that weren't valid JSON (truncated writes, mostly).
+    #[serde(skip_serializing_if = "is_zero")]
+    pub unparsed_lines: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetricsTotals {
+    pub sessions: usize,
+    pub turns: usize,
+    pub tokens: TokenUsage,
+    pub cost_usd: f64,
+    pub tool_calls: BTreeMap<String, usize>,
+    pub duration_secs: i64,
+}
+
+/// Contents of `metrics.json` in an export.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TranscriptMetrics {
+    pub totals: MetricsTotals,
+    pub sessions: Vec<SessionMetrics>,
+}
+
+/// Tool input keys that name a file the tool read or wrote.
+const FILE_KEYS: [&str; 3] = ["file_path", "notebook_path", "path"];
+
+/// Stream one transcript line by line; these files get large.
+pub fn analyze_session(path: &Path) -> std::io::Result<SessionMetrics> {
+    let file = std::fs::File::open(path)?;
+    let session_id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut metrics = SessionMetrics {
+        session_id,
+        ..Default::default()
+    };
+
+    let mut first: Option<DateTime<FixedOffset>> = None;
+    let mut last: Option<DateTime<FixedOffset>> = None;
+    // One API response is written as several lines (one per content block),
+    // each repeating the same usage; count it once.
+    let mut seen_messages: HashSet<String> = HashSet::new();
+    let mut models: BTreeMap<String, usize> = BTreeMap::new();
+
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+            metrics.unparsed_lines += 1;
+            continue;
+        };
+
+        if let Some(ts) = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        {
+            first = Some(first.map_or(ts, |f| f.min(ts)));
+            last = Some(last.map_or(ts, |l| l.max(ts)));
+        }
+
+        let message = entry.get("message");
+        let content = message.and_then(|m| m.get("content"));
+        match entry.get("type").and_then(Value::as_str) {
+            Some("user") if !is_tool_result(content) => {
+                if metrics.turns == 0 {
+                    identify_role(&mut metrics, &prompt_text(content));
+                }
+                metrics.turns += 1;
+            }
+            Some("assistant") => {
+                let Some(message) = message else { continue };
+                let id = message.get("id").and_then(Value::as_str).map(str::to_string);
+                let first_sighting = id.map(|id| seen_messages.insert(id)).unwrap_or(true);
+                if first_sighting {
+                    metrics.assistant_messages += 1;
+                    let usage = message
+                        .get("usage")
+                        .map(TokenUsage::from_usage)
+                        .unwrap_or_default();
+                    metrics.tokens.add(&usage);
+                    if let Some(model) = message.get("model").and_then(Value::as_str) {
+                        *models.entry(model.to_string()).or_default() += 1;
+                        match crate::cost::model_price(model) {
+                            Some(price) => metrics.cost_usd += price.cost(&usage),
+                            None if usage.total() > 0 => {
+                                metrics.unpriced_models.insert(model.to_string());
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                for block in content.and_then(Value::as_array).into_iter().flatten() {
+                    if block.get("type").and_then(Value::as_str) != Some("tool_use") {
+                        continue;
+                    }
+                    let name = block.get("name").and_then(Value::as_str).unwrap_or("unknown");
+                    *metrics.tool_calls.entry(name.to_string()).or_default() += 1;
+                    if let Some(input) = block.get("input") {
+                        for key in FILE_KEYS {
+                            if let Some(file) = input.get(key).and_then(Value::as_str) {
+                                metrics.files_touched.insert(file.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    metrics.model = models
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(model, _)| model);
+    metrics.started_at = first.map(|t| t.to_rfc3339());
+    metrics.ended_at = last.map(|t| t.to_rfc3339());
+    metrics.duration_secs = first.zip(last).map(|(f, l)| (l - f).num_seconds());
+    Ok(metrics)
+}
+
+/// The text of a user prompt, whether sent as a string or as content blocks.
+fn prompt_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Match the prompts `runner` builds for executor and orchestrating agents.
+fn identify_role(metrics: &mut SessionMetrics, prompt: &str) {
+    const ASSIGNED: &str = "Your assigned feature is ";
+    if let Some(start) = prompt.find(ASSIGNED) {
+        let id: String = prompt[start + ASSIGNED.len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if !id.is_empty() {
+            metrics.role = SessionRole::Executor;
+            metrics.feature_id = Some(id);
+        }
+    } else if prompt.starts_with("You are a forge orchestrating agent") {
+        metrics.role = SessionRole::Orchestrating;
+    }
+}
+
+/// User entries that only carry tool output back to the model.
+fn is_tool_result(content: Option<&Value>) -> bool {
+    content
+        .and_then(Value::as_array)
+        .is_some_and(|blocks| {
+            !blocks.is_empty()
+                && blocks
+                    .iter()
+                    .all(|b| b.get("type").and_then(Value::as_str) == Some("tool_result"))
+        })
+}
+
+/// Analyze every `*.jsonl` in a directory, sorted by session id.
+pub fn analyze_dir(dir: &Path) -> std::io::Result<TranscriptMetrics> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_jsonl(p))
+        .collect();
+    paths.sort();
+    analyze_paths(&paths)
+}
+
+/// Analyze the given transcripts, in order.
+pub fn analyze_paths(paths: &[PathBuf]) -> std::io::Result<TranscriptMetrics> {
+    let mut metrics = TranscriptMetrics::default();
+    for path in paths {
+        let session = analyze_session(path)?;
+        let totals = &mut metrics.totals;
+        totals.sessions += 1;
+        totals.turns += session.turns;
+        totals.tokens.add(&session.tokens);
+        totals.cost_usd += session.cost_usd;
+        totals.duration_secs += session.duration_secs.unwrap_or(0);
+        for (tool, count) in &session.tool_calls {
+            *totals.tool_calls.entry(tool.clone()).or_default() += count;
+        }
+        metrics.sessions.push(session);
+    }
+    Ok(metrics)
+}
+
+/// Where one agent backend keeps its session transcripts on disk.
+pub trait TranscriptSource {
+    /// Backend name as used in `[forge.roles]`, e.g. "codex".
+    fn backend(&self) -> &'static str;
+    /// Transcript files recorded for `project_dir` (or a worktree inside it).
+    fn discover(&self, project_dir: &Path) -> Vec<PathBuf>;
+}
+
+/// `~/.claude/projects/<project path with / as ->/<session>.jsonl`.
+pub struct ClaudeSource {
+    projects_dir: PathBuf,
+}
+
+impl TranscriptSource for ClaudeSource {
+    fn backend(&self) -> &'static str {
+        "claude"
+    }
+
+    fn discover(&self, project_dir: &Path) -> Vec<PathBuf> {
+        let Ok(canonical) = project_dir.canonicalize() else {
+            return Vec::new();
+        };
+        let dir = self.projects_dir.join(canonical.to_string_lossy().replace('/', "-"));
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| is_jsonl(p))
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+/// `$CODEX_HOME/sessions/YYYY/MM/DD/rollout-*.jsonl`. Sessions from every
+/// project share one tree, so each file's `session_meta` line says which
+/// working directory it ran in.
+pub struct CodexSource {
+    sessions_dir: PathBuf,
+}
+
+impl TranscriptSource for CodexSource {
+    fn backend(&self) -> &'static str {
+        "codex"
+    }
+
+    fn discover(&self, project_dir: &Path) -> Vec<PathBuf> {
+        let Ok(canonical) = project_dir.canonicalize() else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        let mut stack = vec![self.sessions_dir.clone()];
+        while let Some(dir) = stack.pop() {
+            for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else if is_jsonl(&path)
+                    && codex_session_cwd(&path).is_some_and(|cwd| cwd.starts_with(&canonical))
+                {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+        paths
+    }
+}
+
+/// The `cwd` from a Codex rollout's first line. Older rollouts put the
+/// metadata at the top level instead of under `payload`.
+fn codex_session_cwd(path: &Path) -> Option<PathBuf> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut first = String::new();
+    std::io::BufReader::new(file).read_line(&mut first).ok()?;
+    let meta: Value = serde_json::from_str(&first).ok()?;
+    let cwd = meta
+        .pointer("/payload/cwd")
+        .or_else(|| meta.get("cwd"))?
+        .as_str()?;
+    Some(PathBuf::from(cwd))
+}
+
+fn is_jsonl(path: &Path) -> bool {
+    path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("jsonl")
+}
+
+/// Every backend forge can launch agents with, rooted at the user's home.
+pub fn sources() -> Vec<Box<dyn TranscriptSource>> {
+    let home = std::env::var("HOME").map(PathBuf::from).unwrap_or_default();
+    let codex_home = std::env::var("CODEX_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| home.join(".codex"));
+    vec![
+        Box::new(ClaudeSource {
+            projects_dir: home.join(".claude/projects"),
+        }),
+        Box::new(CodexSource {
+            sessions_dir: codex_home.join("sessions"),
+        }),
+    ]
+}
+
+/// The project's Claude transcripts, the one format token accounting and
+/// mining understand.
+pub fn claude_transcripts(project_dir: &Path) -> Vec<PathBuf> {
+    sources()
+        .iter()
+        .filter(|source| source.backend() == "claude")
+        .flat_map(|source| source.discover(project_dir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const TRANSCRIPT: &str = r#"{"type":"user","timestamp":"2026-03-01T10:00:00Z","message":{"role":"user","content":"You are a forge agent. Your assigned feature is f001. Read features.json for details."}}
+{"type":"assistant","timestamp":"2026-03-01T10:00:05Z","message":{"id":"msg_1","model":"claude-sonnet","content":[{"type":"text","text":"ok"}],"usage":{"input_tokens":100,"output_tokens":20,"cache_read_input_tokens":1000}}}
+{"type":"assistant","timestamp":"2026-03-01T10:00:06Z","message":{"id":"msg_1","model":"claude-sonnet","content":[{"type":"tool_use","name":"Read","input":{"file_path":"src/main.rs"}}],"usage":{"input_tokens":100,"output_tokens":20,"cache_read_input_tokens":1000}}}
+{"type":"user","timestamp":"2026-03-01T10:00:07Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"fn main"}]}}
+{"type":"assistant","timestamp":"2026-03-01T10:01:00Z","message":{"id":"msg_2","model":"claude-sonnet","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"src/lib.rs"}},{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}],"usage":{"input_tokens":50,"output_tokens":10,"cache_creation_input_tokens":5}}}
+{"type":"summary","summary":"done"}
+{"truncated
+"#;
+
+    #[test]
+    fn analyze_single_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("abc-123.jsonl");
+        fs::write(&path, TRANSCRIPT).unwrap();
+
+        let m = analyze_session(&path).unwrap();
+        assert_eq!(m.session_id, "abc-123");
+        assert_eq!(m.model.as_deref(), Some("claude-sonnet"));
+        assert_eq!(m.turns, 1);
+        assert_eq!(m.role, SessionRole::Executor);
+        assert_eq!(m.feature_id.as_deref(), Some("f001"));
+        assert_eq!(m.assistant_messages, 2);
+        // msg_1's usage is repeated on two lines but counted once
+        assert_eq!(
+            m.tokens,
+            TokenUsage {
+                input: 150,
+                output: 30,
+                cache_creation: 5,
+                cache_read: 1000
+            }
+        );
+        assert_eq!(m.tokens.total(), 1185);
+        // sonnet: 150 * 3 + 30 * 15 + 5 * 3.75 + 1000 * 0.3 per MTok
+        assert!((m.cost_usd - 0.00121875).abs() < 1e-12, "{}", m.cost_usd);
+        assert!(m.unpriced_models.is_empty());
+        assert_eq!(m.tool_calls.get("Read"), Some(&1));
+        assert_eq!(m.tool_calls.get("Edit"), Some(&1));
+        assert_eq!(m.tool_calls.get("Bash"), Some(&1));
+        assert_eq!(
+            m.files_touched.iter().collect::<Vec<_>>(),
+            vec!["src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(m.duration_secs, Some(60));
+        assert_eq!(m.started_at.as_deref(), Some("2026-03-01T10:00:00+00:00"));
+        assert_eq!(m.unparsed_lines, 1);
+    }
+
+    #[test]
+    fn analyze_dir_totals() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("a.jsonl"), TRANSCRIPT).unwrap();
+        fs::write(tmp.path().join("b.jsonl"), TRANSCRIPT).unwrap();
+        fs::write(tmp.path().join("notes.txt"), "ignored").unwrap();
+
+        let m = analyze_dir(tmp.path()).unwrap();
+        assert_eq!(m.totals.sessions, 2);
+        assert_eq!(m.totals.turns, 2);
+        assert_eq!(m.totals.tokens.output, 60);
+        assert_eq!(m.totals.tool_calls.get("Edit"), Some(&2));
+        assert_eq!(m.totals.duration_secs, 120);
+        assert_eq!(m.sessions[0].session_id, "a");
+    }
+
+    #[test]
+    fn orchestrating_role_and_unpriced_model() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("orch.jsonl");
+        fs::write(
+            &path,
+            r#"{"type":"user","message":{"content":[{"type":"text","text":"You are a forge orchestrating agent. Follow the forge-orchestrating skill."}]}}
+{"type":"assistant","message":{"id":"m1","model":"mystery-1","content":[],"usage":{"input_tokens":10,"output_tokens":1}}}
+"#,
+        )
+        .unwrap();
+        let m = analyze_session(&path).unwrap();
+        assert_eq!(m.role, SessionRole::Orchestrating);
+        assert_eq!(m.feature_id, None);
+        assert_eq!(m.cost_usd, 0.0);
+        assert!(m.unpriced_models.contains("mystery-1"));
+    }
+
+    #[test]
+    fn empty_transcript() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("empty.jsonl");
+        fs::write(&path, "").unwrap();
+        let m = analyze_session(&path).unwrap();
+        assert_eq!(m.turns, 0);
+        assert_eq!(m.duration_secs, None);
+        assert!(serde_json::to_string(&m).unwrap().contains("\"session_id\":\"empty\""));
+    }
+
+    #[test]
+    fn codex_sessions_matched_by_cwd() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(project.join(".forge/worktrees/agent-1")).unwrap();
+        let project = project.canonicalize().unwrap();
+        let day = tmp.path().join("sessions/2026/03/01");
+        fs::create_dir_all(&day).unwrap();
+        let meta = |cwd: &Path| {
+            format!(r#"{{"timestamp":"2026-03-01T10:00:00Z","type":"session_meta","payload":{{"id":"x","cwd":"{}"}}}}"#, cwd.display())
+        };
+        fs::write(day.join("rollout-a.jsonl"), meta(&project) + "\n{}\n").unwrap();
+        fs::write(day.join("rollout-b.jsonl"), meta(&project.join(".forge/worktrees/agent-1"))).unwrap();
+        fs::write(day.join("rollout-c.jsonl"), meta(tmp.path())).unwrap();
+        fs::write(day.join("rollout-d.jsonl"), format!(r#"{{"id":"y","cwd":"{}"}}"#, project.display())).unwrap();
+
+        let source = CodexSource {
+            sessions_dir: tmp.path().join("sessions"),
+        };
+        let names: Vec<String> = source
+            .discover(&project)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["rollout-a.jsonl", "rollout-b.jsonl", "rollout-d.jsonl"]);
+    }
+}
@@ -1,21 +1,28 @@
 use std::io::{self, Read as _};
 use std::os::unix::io::{FromRawFd, IntoRawFd, RawFd};
 use std::os::unix::process::CommandExt;
-use std::path::Path;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use tui_term::widget::{Cursor, PseudoTerminal};
 
-use crate::config::RoleSpec;
+use crate::commit_message::CommitMessage;
+use crate::config::{AlertConfig, GitConfig, TuiConfig, TuiLayout};
+use crate::events::{self, EventKind};
 use crate::features::{FeatureList, FeatureType, StatusCounts};
+use crate::git::{self, CommitGuard};
 use crate::runner::{self, RunConfig};
+use crate::tui_alerts::{self, Alert};
+use crate::tui_keys::{Action, KeyBindingError, KeyBindings};
+use crate::tui_orchestrator::{self, OrchestrationSignals, OrchestrationUpdate};
 
 /// Mark an FD as close-on-exec so it doesn't leak to child processes.
 fn set_cloexec(fd: RawFd) {
@@ -48,14 +55,22 @@ struct PtyPane {
     last_size: (u16, u16),
     feature_priority: Option<u32>,
     feature_type: Option<FeatureType>,
+    /// Dedicated git worktree the agent runs in (--worktrees mode).
+    worktree: Option<PathBuf>,
+    /// Whether the view tracks new output. Cleared by scrolling back
+    /// (scroll-lock); shared with the reader thread.
+    follow: Arc<AtomicBool>,
+    /// When the agent last printed anything; stamped by the reader thread.
+    last_output: Arc<Mutex<Instant>>,
+    /// Set once an idle alert fired; cleared when output resumes.
+    idle_alerted: bool,
 }
 
 impl PtyPane {
     fn new(
         rows: u16,
         cols: u16,
-        cmd: &str,
-        args: &[String],
+        cmd: &runner::AgentCommand,
         cwd: &Path,
         agent_id: String,
         feature_id: Option<String>,
@@ -95,8 +110,9 @@ impl PtyPane {
         set_cloexec(writer_fd);
 
         // Spawn child process with PTY slave as controlling terminal
-        let mut command = std::process::Command::new(cmd);
-        command.args(args);
+        let mut command = std::process::Command::new(&cmd.program);
+        command.args(&cmd.args);
+        command.envs(&cmd.env);
         command.current_dir(cwd);
         command.env("FORGE_AGENT_ID", &agent_id);
         unsafe {
@@ -133,6 +149,8 @@ impl PtyPane {
 
         let parser = Arc::new(RwLock::new(vt100::Parser::new(rows, cols, 10000)));
         let exited = Arc::new(AtomicBool::new(false));
+        let follow = Arc::new(AtomicBool::new(true));
+        let last_output = Arc::new(Mutex::new(Instant::now()));
 
         // Child exit handler thread
         {
@@ -148,6 +166,8 @@ impl PtyPane {
         {
             let parser = parser.clone();
             let exited = exited.clone();
+            let follow = follow.clone();
+            let last_output = last_output.clone();
             std::thread::spawn(move || {
                 let mut buf = [0u8; 65536];
                 let mut file = unsafe { std::fs::File::from_raw_fd(reader_fd) };
@@ -156,7 +176,10 @@ impl PtyPane {
                         Ok(0) | Err(_) => break,
                         Ok(n) => {
                             if let Ok(mut p) = parser.write() {
-                                p.process(&buf[..n]);
+                                process_output(&mut p, &buf[..n], follow.load(Ordering::Acquire));
+                            }
+                            if let Ok(mut t) = last_output.lock() {
+                                *t = Instant::now();
                             }
                         }
                     }
@@ -194,6 +217,10 @@ impl PtyPane {
             last_size: (rows, cols),
             feature_priority: None,
             feature_type: None,
+            worktree: None,
+            follow,
+            last_output,
+            idle_alerted: false,
         })
     }
 
@@ -214,6 +241,59 @@ impl PtyPane {
         !self.exited.load(Ordering::Acquire)
     }
 
+    fn is_following(&self) -> bool {
+        self.follow.load(Ordering::Acquire)
+    }
+
+    /// Rows scrolled back from the live bottom (0 when at the bottom).
+    fn scroll_offset(&self) -> usize {
+        self.parser
+            .read()
+            .map(|p| p.screen().scrollback())
+            .unwrap_or(0)
+    }
+
+    /// Move the view `lines` rows back in history (positive) or forward
+    /// (negative). Scrolling back locks the view; reaching the bottom follows again.
+    fn scroll(&self, lines: isize) {
+        if let Ok(mut parser) = self.parser.write() {
+            let screen = parser.screen_mut();
+            screen.set_scrollback(screen.scrollback().saturating_add_signed(lines));
+            if lines > 0 {
+                self.follow.store(false, Ordering::Release);
+            } else if screen.scrollback() == 0 {
+                self.follow.store(true, Ordering::Release);
+            }
+        }
+    }
+
+    fn set_follow(&self, follow: bool) {
+        if follow && let Ok(mut parser) = self.parser.write() {
+            parser.screen_mut().set_scrollback(0);
+        }
+        self.follow.store(follow, Ordering::Release);
+    }
+
+    /// Returns how long the agent has been silent if it just crossed
+    /// `threshold`; re-arms once output resumes.
+    fn check_idle(&mut self, threshold: Duration) -> Option<Duration> {
+        let silent = self.last_output.lock().map(|t| t.elapsed()).unwrap_or_default();
+        if silent < threshold {
+            self.idle_alerted = false;
+            return None;
+        }
+        if self.idle_alerted || !self.is_alive() {
+            return None;
+        }
+        self.idle_alerted = true;
+        Some(silent)
+    }
+
+    /// One screen minus a line of overlap, for PageUp/PageDown.
+    fn page_rows(&self) -> isize {
+        (self.last_size.0 as isize - 1).max(1)
+    }
+
     fn kill(&self) {
         if let Some(pid) = self.child_pid {
             unsafe {
@@ -235,22 +315,89 @@ impl Drop for PtyPane {
     }
 }
 
-/// Spawn a PTY pane running an agent with the given role and prompt.
+/// Lines currently held in the parser's scrollback buffer.
+fn scrollback_len(parser: &mut vt100::Parser) -> usize {
+    let screen = parser.screen_mut();
+    let offset = screen.scrollback();
+    screen.set_scrollback(usize::MAX);
+    let len = screen.scrollback();
+    screen.set_scrollback(offset);
+    len
+}
+
+/// Feed PTY output to the parser. vt100 keeps a scrolled-back view on the
+/// same lines by itself; a pane locked at the bottom needs the offset bumped
+/// by however many lines the output pushed into history.
+fn process_output(parser: &mut vt100::Parser, bytes: &[u8], follow: bool) {
+    if follow || parser.screen().scrollback() > 0 {
+        parser.process(bytes);
+        return;
+    }
+    let before = scrollback_len(parser);
+    parser.process(bytes);
+    let grown = scrollback_len(parser).saturating_sub(before);
+    parser.screen_mut().set_scrollback(grown);
+}
+
+/// While a pane is scroll-locked, navigation keys move through its history
+/// instead of reaching the agent. End resumes following; any other key does
+/// too and is then forwarded. Returns true if the key was consumed.
+fn handle_scroll_key(pane: &PtyPane, key: &KeyEvent) -> bool {
+    // Shift+PageUp scrolls back from live output, as in most terminals
+    if key.code == KeyCode::PageUp && key.modifiers == KeyModifiers::SHIFT {
+        pane.scroll(pane.page_rows());
+        return true;
+    }
+    if pane.is_following() {
+        return false;
+    }
+    match key.code {
+        _ if !key.modifiers.is_empty() => {
+            pane.set_follow(true);
+            return false;
+        }
+        KeyCode::PageUp => pane.scroll(pane.page_rows()),
+        KeyCode::PageDown => pane.scroll(-pane.page_rows()),
+        KeyCode::Up => pane.scroll(1),
+        KeyCode::Down => pane.scroll(-1),
+        KeyCode::Home => pane.scroll(isize::MAX),
+        KeyCode::End => pane.set_follow(true),
+        _ => {
+            pane.set_follow(true);
+            return false;
+        }
+    }
+    true
+}
+
+/// Title badge for a pane that is not following its output.
+fn scroll_indicator(following: bool, offset: usize) -> Option<String> {
+    match (following, offset) {
+        (true, _) => None,
+        (false, 0) => Some(" SCROLL-LOCK ".into()),
+        (false, n) => Some(format!(" SCROLL -{n} ")),
+    }
+}
+
+/// Undo terminal setup: mouse capture (for wheel scrolling) and raw mode.
+fn restore_terminal() {
+    let _ = crossterm::execute!(io::stdout(), crossterm::event::DisableMouseCapture);
+    ratatui::restore();
+}
+
+/// Spawn a PTY pane running an agent command (see `runner::build_agent_command`).
 fn spawn_pty_agent(
     rows: u16,
     cols: u16,
-    role: &RoleSpec,
+    command: &runner::AgentCommand,
     project_dir: &Path,
-    prompt: &str,
     agent_id: &str,
     feature_id: Option<String>,
 ) -> io::Result<PtyPane> {
-    let (cmd_name, args) = runner::build_agent_command(role, prompt);
     PtyPane::new(
         rows,
         cols,
-        &cmd_name,
-        &args,
+        command,
         project_dir,
         agent_id.to_string(),
         feature_id,
@@ -283,36 +430,182 @@ fn open_next_feature_pane(
     // Claim the feature so other panes don't pick the same one
     let _ = features.claim(&feature_id, &agent_id);
     let _ = features.save(&config.project_dir);
-    let prompt = runner::build_agent_prompt(&config.project_dir, &feature_id);
+
+    let worktree = if config.worktrees {
+        match prepare_pane_worktree(&config.project_dir, &agent_id, &feature_id, &config.git) {
+            Ok(wt) => Some(wt),
+            Err(_) => {
+                // Release the claim so the feature isn't stranded
+                let _ = features.reopen(&feature_id);
+                let _ = features.save(&config.project_dir);
+                return None;
+            }
+        }
+    } else {
+        None
+    };
+    let cwd = runner::scope_cwd(&config.project_dir, worktree.as_deref().unwrap_or(&config.project_dir), &feature_id);
 
     // Use orchestrating role for review features (milestone gates benefit from
-    // a different model), protocol role for implement/poc features.
-    let role = match ftype {
-        FeatureType::Review => &config.orchestrating,
-        _ => &config.protocol,
+    // a different model), protocol role (or its variant B) for implement/poc features.
+    let (role, variant) = match ftype {
+        FeatureType::Review => (&config.orchestrating, None),
+        _ => runner::executor_role(config, *next_agent_id as usize),
     };
+    let (prompt, budget) = runner::build_agent_prompt(&config.project_dir, &feature_id, role);
+    // stdout belongs to the TUI; the event stream still records the cut
+    runner::report_context_budget(&config.project_dir, &agent_id, &feature_id, budget, false);
 
+    let mut command = runner::build_agent_command(role, &config.backends, config.permissions, &prompt);
+    // As in headless runs, a backend's own env wins over [secrets]
+    for (name, value) in &config.secrets {
+        command.env.entry(name.clone()).or_insert_with(|| value.clone());
+    }
     match spawn_pty_agent(
         inner_rows,
         inner_cols,
-        role,
-        &config.project_dir,
-        &prompt,
+        &command,
+        &cwd,
         &agent_id,
         Some(feature_id.clone()),
     ) {
         Ok(mut pane) => {
+            events::emit(
+                &config.project_dir,
+                &agent_id,
+                EventKind::Claim {
+                    feature_id: feature_id.clone(),
+                },
+            );
+            events::emit(
+                &config.project_dir,
+                &agent_id,
+                EventKind::SessionStart {
+                    role: "executor".into(),
+                    feature_id: Some(feature_id.clone()),
+                    backend: Some(role.backend.clone()),
+                    model: Some(role.model.clone()),
+                    variant: variant.map(String::from),
+                },
+            );
             pane.feature_priority = Some(priority);
             pane.feature_type = Some(ftype);
+            pane.worktree = worktree;
             let idx = panes.len();
             panes.push(pane);
             *active_pane = Some(idx);
             Some(feature_id)
         }
-        Err(_) => None,
+        Err(_) => {
+            if let Some(wt) = &worktree {
+                let _ = git::remove_worktree(&config.project_dir, wt);
+            }
+            None
+        }
     }
 }
 
+/// Give a pane its own worktree on `forge/{agent_id}`, branched from HEAD.
+/// The claim is committed first so the branch (and its eventual merge) carries it.
+fn prepare_pane_worktree(
+    project_dir: &Path,
+    agent_id: &str,
+    feature_id: &str,
+    git_config: &GitConfig,
+) -> Result<PathBuf, String> {
+    let claim = CommitMessage::for_feature_id(project_dir, feature_id)
+        .agent(agent_id)
+        .retitled("chore", format!("claim for {agent_id}"));
+    git::commit_paths(project_dir, &["features.json"], &claim.render(git_config))?;
+
+    let wt_dir = runner::worktree_path(project_dir, agent_id);
+    let branch = runner::agent_branch(git_config, agent_id, feature_id);
+    if wt_dir.exists() {
+        let _ = git::remove_worktree(project_dir, &wt_dir);
+    }
+    // A merged leftover branch from an earlier run would pin the worktree to
+    // stale code. Unmerged leftovers are kept (git refuses to delete them).
+    let _ = git::delete_branch(project_dir, &branch, false);
+    if let Some(parent) = wt_dir.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    git::create_worktree(project_dir, &wt_dir, &branch)?;
+    Ok(wt_dir)
+}
+
+/// Tear down a pane's worktree. Uncommitted work is committed to the agent
+/// branch first. With `merge`, the branch is merged into the project branch
+/// and deleted; otherwise it is kept for manual inspection.
+/// Returns a one-line summary for the status bar.
+fn release_pane_worktree(
+    project_dir: &Path,
+    pane: &PtyPane,
+    merge: bool,
+    git_config: &GitConfig,
+    guard: &CommitGuard,
+) -> Option<String> {
+    let wt_dir = pane.worktree.as_deref()?;
+    let agent_id = &pane.agent_id;
+    let branch = runner::agent_branch(git_config, agent_id, pane.feature_id.as_deref().unwrap_or(agent_id));
+    let message = match &pane.feature_id {
+        Some(id) => CommitMessage::for_feature_id(project_dir, id),
+        None => CommitMessage::new("chore", format!("work from {agent_id}")),
+    }
+    .agent(agent_id);
+
+    let wip = message.retitled(&message.commit_type, format!("uncommitted work from {agent_id}"));
+    if let Err(e) = git::add_and_commit(wt_dir, &wip.render(git_config), guard)
+        && e.starts_with("secret scan")
+    {
+        // Removing the worktree would throw the uncommitted work away
+        return Some(format!("{agent_id}: not merged, {e}; worktree kept at {}", wt_dir.display()));
+    }
+    let refusal = if merge { runner::check_branch(project_dir, &branch, git_config, guard) } else { None };
+    let merged = if merge && refusal.is_none() {
+        // Feature state changed in the project dir since the claim (reopens,
+        // other claims) must be committed or git refuses to merge over it.
+        let sync = CommitMessage::new("chore", "sync feature state").render(git_config);
+        let _ = git::commit_paths(project_dir, &["features.json"], &sync);
+        let result = runner::merge_worktree(project_dir, wt_dir, &branch, &message, git_config);
+        events::emit_merge(project_dir, &branch, &result);
+        Some(result)
+    } else {
+        None
+    };
+    let _ = git::remove_worktree(project_dir, wt_dir);
+
+    if let Some(refusal) = refusal {
+        let feature_id = pane.feature_id.as_deref().unwrap_or(agent_id);
+        let kept = runner::park_branch(project_dir, &branch, feature_id, &refusal, None).unwrap_or(branch);
+        if let Some(id) = &pane.feature_id {
+            runner::reopen_refused(project_dir, id, &refusal);
+        }
+        return Some(format!("{agent_id}: not merged, {}; work kept on {kept}", refusal.describe()));
+    }
+    Some(match merged {
+        Some(Ok(())) => {
+            let _ = runner::delete_merged_branch(project_dir, &branch, git_config);
+            format!("{agent_id}: merged {branch}")
+        }
+        Some(Err(_)) => format!("{agent_id}: merge conflict, work kept on {branch}"),
+        None => format!("{agent_id}: closed, work kept on {branch}"),
+    })
+}
+
+/// PTY exits don't surface a status code, so none is recorded.
+fn emit_pane_end(project_dir: &Path, pane: &PtyPane) {
+    events::emit(
+        project_dir,
+        &pane.agent_id,
+        EventKind::SessionEnd {
+            feature_id: pane.feature_id.clone(),
+            exit_code: None,
+            timed_out: false,
+            output_bytes: None,
+        },
+    );
+}
+
 /// Route keyboard input to a PTY pane.
 fn handle_pane_key_event(sender: &std::sync::mpsc::Sender<Vec<u8>>, key: &KeyEvent) -> bool {
     let input_bytes = match key.code {
@@ -444,15 +737,48 @@ fn load_tui_status(project_dir: &Path) -> TuiStatus {
     TuiStatus { counts, milestones }
 }
 
+/// Watch-mode settings resolved from `[tui]` in forge.toml.
+pub struct TuiOptions {
+    pub keys: KeyBindings,
+    pub layout: TuiLayout,
+    pub alerts: AlertConfig,
+}
+
+impl TuiOptions {
+    pub fn from_config(tui: &TuiConfig) -> Result<Self, KeyBindingError> {
+        Ok(Self {
+            keys: KeyBindings::from_config(&tui.keys)?,
+            layout: tui.layout,
+            alerts: tui.alerts.clone(),
+        })
+    }
+}
+
+/// Text segments shown in the status bar, left to right.
+struct StatusBarInfo<'a> {
+    counts: &'a StatusCounts,
+    milestones: &'a str,
+    cocoindex_status: &'a str,
+    working_info: &'a str,
+    /// Latest background verify pass as (passed, total).
+    verify: Option<(usize, usize)>,
+    keys: &'a KeyBindings,
+}
+
 fn render_status_bar(
-    counts: &StatusCounts,
-    milestones: &str,
+    info: &StatusBarInfo,
     command_mode: bool,
-    cocoindex_status: &str,
-    working_info: &str,
     area: Rect,
     frame: &mut ratatui::Frame,
 ) {
+    let StatusBarInfo {
+        counts,
+        milestones,
+        cocoindex_status,
+        working_info,
+        verify,
+        keys,
+    } = *info;
     let pct = if counts.total > 0 {
         (counts.done as f64 / counts.total as f64) * 100.0
     } else {
@@ -483,6 +809,14 @@ fn render_status_bar(
         String::new()
     };
 
+    let (verify_span, verify_color) = match verify {
+        Some((passed, total)) => (
+            format!(" verify {passed}/{total} "),
+            if passed == total { Color::Green } else { Color::Red },
+        ),
+        None => (String::new(), Color::Gray),
+    };
+
     if command_mode {
         let bar = Line::from(vec![
             Span::styled(
@@ -500,6 +834,10 @@ fn render_status_bar(
                 working_span,
                 Style::default().fg(Color::Green).bg(Color::DarkGray),
             ),
+            Span::styled(
+                verify_span,
+                Style::default().fg(verify_color).bg(Color::DarkGray),
+            ),
             Span::styled(
                 " CMD ",
                 Style::default()
@@ -508,7 +846,18 @@ fn render_status_bar(
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                " 1-9:goto  j/k:switch  n:new  x:close  q:quit  esc:cancel ",
+                format!(
+                    " 1-9:goto  {}/{}:switch  {}:new  {}:close  {}:quit  {}:cancel  {}:scroll  {}:follow  {}:layout ",
+                    keys.label(Action::Next),
+                    keys.label(Action::Prev),
+                    keys.label(Action::New),
+                    keys.label(Action::Close),
+                    keys.label(Action::Quit),
+                    keys.label(Action::Cancel),
+                    keys.label(Action::Scroll),
+                    keys.label(Action::Follow),
+                    keys.label(Action::Layout),
+                ),
                 Style::default()
                     .fg(Color::Yellow)
                     .bg(Color::DarkGray),
@@ -534,7 +883,11 @@ fn render_status_bar(
                 Style::default().fg(Color::Green).bg(Color::DarkGray),
             ),
             Span::styled(
-                " Ctrl+G: command mode ",
+                verify_span,
+                Style::default().fg(verify_color).bg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!(" {}: command mode ", keys.prefix_label()),
                 Style::default().fg(Color::Gray).bg(Color::DarkGray),
             ),
         ]);
@@ -543,6 +896,117 @@ fn render_status_bar(
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NoticeLevel {
+    Info,
+    Success,
+    Warn,
+}
+
+struct Notification {
+    text: String,
+    level: NoticeLevel,
+    at: Instant,
+}
+
+/// Recent events (worktree merges, verify results, reopens), drawn as a
+/// stack over the top-right corner of the pane area until they expire.
+#[derive(Default)]
+struct Notifications {
+    items: VecDeque<Notification>,
+}
+
+impl Notifications {
+    const TTL: Duration = Duration::from_secs(8);
+    const MAX_VISIBLE: usize = 4;
+
+    fn push(&mut self, level: NoticeLevel, text: impl Into<String>) {
+        self.items.push_back(Notification {
+            text: text.into(),
+            level,
+            at: Instant::now(),
+        });
+        while self.items.len() > Self::MAX_VISIBLE {
+            self.items.pop_front();
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.items
+            .retain(|n| now.saturating_duration_since(n.at) < Self::TTL);
+    }
+}
+
+fn render_notifications(notes: &Notifications, area: Rect, frame: &mut ratatui::Frame) {
+    if notes.items.is_empty() || area.width < 8 || area.height < 3 {
+        return;
+    }
+    let longest = notes
+        .items
+        .iter()
+        .map(|n| n.text.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = (longest as u16 + 4).min(area.width);
+    let height = (notes.items.len() as u16 + 2).min(area.height);
+    let rect = Rect::new(area.x + area.width - width, area.y, width, height);
+
+    let lines: Vec<Line> = notes
+        .items
+        .iter()
+        .map(|n| {
+            let color = match n.level {
+                NoticeLevel::Info => Color::White,
+                NoticeLevel::Success => Color::Green,
+                NoticeLevel::Warn => Color::Yellow,
+            };
+            Line::from(Span::styled(n.text.clone(), Style::default().fg(color)))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" forge ")
+        .style(Style::default().fg(Color::Magenta));
+    frame.render_widget(Clear, rect);
+    frame.render_widget(Paragraph::new(lines).block(block), rect);
+}
+
+/// Fold a background orchestration pass into the notifications and the
+/// status bar's verify summary.
+fn apply_orchestration_update(
+    update: &OrchestrationUpdate,
+    notifications: &mut Notifications,
+    verify: &mut Option<(usize, usize)>,
+) {
+    if !update.verify_results.is_empty() {
+        let total = update.verify_results.len();
+        let passed = update.verify_results.iter().filter(|r| r.passed).count();
+        *verify = Some((passed, total));
+        if passed == total {
+            notifications.push(NoticeLevel::Success, format!("verify: {passed}/{total} passed"));
+        } else {
+            notifications.push(NoticeLevel::Warn, format!("verify: {} of {total} failed", total - passed));
+        }
+    }
+    if !update.reopened.is_empty() {
+        notifications.push(
+            NoticeLevel::Warn,
+            format!("reopened {}", update.reopened.join(", ")),
+        );
+    }
+    if update.all_done {
+        notifications.push(NoticeLevel::Success, "all features done");
+    }
+}
+
+/// Tell the orchestrator which features live panes are still working on.
+fn sync_in_flight(panes: &[PtyPane], signals: &OrchestrationSignals) {
+    if let Ok(mut in_flight) = signals.in_flight.lock() {
+        *in_flight = panes.iter().filter_map(|p| p.feature_id.clone()).collect();
+    }
+}
+
 /// Compute grid dimensions for N panes.
 /// 1-3 panes: single column (vertical stack)
 /// 4+ panes: 2 columns, rows = ceil(n/2)
@@ -551,7 +1015,7 @@ fn grid_dims(n: usize) -> (usize, usize) {
         (n, 1) // rows, cols
     } else {
         let cols = 2;
-        let rows = (n + cols - 1) / cols;
+        let rows = n.div_ceil(cols);
         (rows, cols)
     }
 }
@@ -567,7 +1031,7 @@ fn grid_rect(pane_area: Rect, index: usize, total: usize) -> Rect {
     // Last column gets remaining width, last row gets remaining height
     let x = pane_area.x + col as u16 * cell_w;
     let y = pane_area.y + row as u16 * cell_h;
-    let is_last_alone = index == total - 1 && total % cols != 0;
+    let is_last_alone = index == total - 1 && !total.is_multiple_of(cols);
     let w = if is_last_alone {
         // Last pane is alone in its row — span full width
         pane_area.width
@@ -594,21 +1058,127 @@ fn estimate_inner(total_rows: u16, total_cols: u16, nr_panes: u16) -> (u16, u16)
     (std::cmp::max(inner_rows, 1), std::cmp::max(inner_cols, 1))
 }
 
-/// Check if a key event is Ctrl+G (BEL, 0x07).
-fn is_ctrl_g(key: &KeyEvent) -> bool {
-    key.code == KeyCode::Char('g') && key.modifiers == KeyModifiers::CONTROL
+/// Estimate the inner pane size for the given layout (before first draw).
+fn estimate_pane_inner(layout: TuiLayout, total_rows: u16, total_cols: u16, nr_panes: u16) -> (u16, u16) {
+    match layout {
+        TuiLayout::Grid => estimate_inner(total_rows, total_cols, nr_panes),
+        // One full-size pane below the tab bar
+        TuiLayout::Tabs => estimate_inner(total_rows.saturating_sub(1), total_cols, 1),
+    }
+}
+
+/// Split the pane area into the tab bar row and the content below it.
+fn split_tabs_area(pane_area: Rect) -> (Rect, Rect) {
+    let bar = Rect::new(pane_area.x, pane_area.y, pane_area.width, pane_area.height.min(1));
+    let content = Rect::new(
+        pane_area.x,
+        pane_area.y + bar.height,
+        pane_area.width,
+        pane_area.height - bar.height,
+    );
+    (bar, content)
+}
+
+/// Where pane `index` is drawn, or None if it is hidden behind another tab.
+fn pane_rect(
+    layout: TuiLayout,
+    pane_area: Rect,
+    index: usize,
+    total: usize,
+    active: Option<usize>,
+) -> Option<Rect> {
+    match layout {
+        TuiLayout::Grid => Some(grid_rect(pane_area, index, total)),
+        TuiLayout::Tabs => (index == active.unwrap_or(0)).then(|| split_tabs_area(pane_area).1),
+    }
+}
+
+fn tab_label(index: usize, pane: &PtyPane) -> String {
+    match &pane.feature_id {
+        Some(fid) => format!(" {} {} {} ", index + 1, pane.agent_id, fid),
+        None => format!(" {} {} ", index + 1, pane.agent_id),
+    }
+}
+
+/// Tab color by pane state: exited, idle, scroll-locked, or running by feature type.
+fn tab_color(pane: &PtyPane) -> Color {
+    if !pane.is_alive() {
+        Color::DarkGray
+    } else if pane.idle_alerted {
+        Color::LightRed
+    } else if !pane.is_following() {
+        Color::Yellow
+    } else {
+        match pane.feature_type {
+            Some(FeatureType::Review) => Color::Magenta,
+            Some(FeatureType::Poc) => Color::Blue,
+            _ => Color::Green,
+        }
+    }
+}
+
+/// Visible tabs as (index, x offset, width), scrolled so the active tab fits
+/// when there are more tabs than the bar can show.
+fn tab_positions(labels: &[String], active: usize, width: u16) -> Vec<(usize, u16, u16)> {
+    if labels.is_empty() {
+        return Vec::new();
+    }
+    let active = active.min(labels.len() - 1);
+    let widths: Vec<u16> = labels.iter().map(|l| l.chars().count() as u16).collect();
+    let mut first = 0;
+    while first < active && widths[first..=active].iter().sum::<u16>() > width {
+        first += 1;
+    }
+    let mut x = 0u16;
+    let mut out = Vec::new();
+    for (i, &w) in widths.iter().enumerate().skip(first) {
+        if x >= width {
+            break;
+        }
+        out.push((i, x, w.min(width - x)));
+        x = x.saturating_add(w);
+    }
+    out
 }
 
-/// Main TUI entry point. Spawns agents in PTY panes and renders them.
-#[allow(clippy::unused_async)]
-pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
+fn render_tab_bar(panes: &[PtyPane], active: usize, area: Rect, frame: &mut ratatui::Frame) {
+    let labels: Vec<String> = panes.iter().enumerate().map(|(i, p)| tab_label(i, p)).collect();
+    let spans: Vec<Span> = tab_positions(&labels, active, area.width)
+        .into_iter()
+        .map(|(i, _, _)| {
+            let color = tab_color(&panes[i]);
+            let style = if i == active {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(color)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color).bg(Color::Black)
+            };
+            Span::styled(labels[i].clone(), style)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Main TUI entry point. Spawns agents in PTY panes and renders them, with
+/// verify + reopen running in the background as panes finish.
+pub async fn run_tui(config: &RunConfig, opts: &TuiOptions) -> io::Result<()> {
+    if config.worktrees && !git::is_git_repo(&config.project_dir) {
+        return Err(io::Error::other("--worktrees requires a git repository"));
+    }
+
     // Set up panic hook to restore terminal
     std::panic::set_hook(Box::new(|panic| {
-        ratatui::restore();
+        restore_terminal();
         eprintln!("Panic: {panic}");
     }));
 
+    let keys = &opts.keys;
+    let mut layout = opts.layout;
+
     let mut terminal = ratatui::init();
+    let _ = crossterm::execute!(io::stdout(), crossterm::event::EnableMouseCapture);
 
     let term_size = terminal.size()?;
 
@@ -618,6 +1188,10 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
     let mut status_tick = 0u32;
     let mut command_mode = false;
     let mut next_agent_id: u32 = 0;
+    let mut notifications = Notifications::default();
+    // Pane area from the last frame, for mapping mouse events to panes
+    let mut last_pane_area = Rect::default();
+    let mut verify_summary: Option<(usize, usize)> = None;
 
     // CocoIndex status tracking (non-blocking)
     #[derive(Clone, Copy, PartialEq)]
@@ -629,18 +1203,48 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
     let _ = crate::context_flow::refresh_context(&config.project_dir);
 
     // Open first pane with estimated inner size
-    let (est_rows, est_cols) = estimate_inner(term_size.height, term_size.width, 1);
+    let (est_rows, est_cols) = estimate_pane_inner(layout, term_size.height, term_size.width, 1);
     open_next_feature_pane(&mut panes, &mut active_pane, est_rows, est_cols, config, None, &mut next_agent_id);
 
     if panes.is_empty() {
-        ratatui::restore();
+        restore_terminal();
         eprintln!("No claimable features found. Nothing to do.");
         return Ok(());
     }
 
     let project_dir = config.project_dir.clone();
+    let guard = CommitGuard::new(&config.git, &config.secrets);
+
+    // Background verify + reopen; updates are drained once per frame
+    let signals = OrchestrationSignals::default();
+    sync_in_flight(&panes, &signals);
+    let (orch_tx, orch_rx) = std::sync::mpsc::channel::<OrchestrationUpdate>();
+    tui_orchestrator::run_orchestration(&project_dir, signals.clone(), move |update| {
+        let _ = orch_tx.send(update);
+    })
+    .await;
 
     loop {
+        while let Ok(update) = orch_rx.try_recv() {
+            apply_orchestration_update(&update, &mut notifications, &mut verify_summary);
+            let failed = update.verify_results.iter().filter(|r| !r.passed).count();
+            if failed > 0 {
+                tui_alerts::fire(&opts.alerts, &Alert::VerifyFailed {
+                    failed,
+                    total: update.verify_results.len(),
+                });
+            }
+            tui_status = load_tui_status(&project_dir);
+            // Reopened work with nobody left to pick it up gets a fresh pane
+            if panes.is_empty() && !update.reopened.is_empty() {
+                let ts = terminal.size()?;
+                let (r, c) = estimate_pane_inner(layout, ts.height, ts.width, 1);
+                open_next_feature_pane(&mut panes, &mut active_pane, r, c, config, None, &mut next_agent_id);
+                sync_in_flight(&panes, &signals);
+            }
+        }
+        notifications.prune(Instant::now());
+
         // Build working info string from live panes
         let working_info: String = panes
             .iter()
@@ -672,19 +1276,29 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
                 .split(frame.area());
 
             let pane_area = outer[0];
+            last_pane_area = pane_area;
             let status_area = outer[1];
 
             if panes.is_empty() {
-                let msg = Paragraph::new(
-                    "No active panes. Ctrl+G then n to spawn, or Ctrl+G then q to quit.",
-                )
+                let prefix = keys.prefix_label();
+                let msg = Paragraph::new(format!(
+                    "No active panes. {prefix} then {} to spawn, or {prefix} then {} to quit.",
+                    keys.label(Action::New),
+                    keys.label(Action::Quit),
+                ))
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(Color::Yellow));
                 frame.render_widget(msg, pane_area);
             } else {
                 let num_panes = panes.len();
+                if layout == TuiLayout::Tabs {
+                    let (bar, _) = split_tabs_area(pane_area);
+                    render_tab_bar(&panes, active_pane.unwrap_or(0), bar, frame);
+                }
                 for (index, pane) in panes.iter_mut().enumerate() {
-                    let chunk = grid_rect(pane_area, index, num_panes);
+                    let Some(chunk) = pane_rect(layout, pane_area, index, num_panes, active_pane) else {
+                        continue;
+                    };
 
                     let pane_num = index + 1;
                     let title = match (&pane.feature_id, pane.feature_priority, &pane.feature_type) {
@@ -699,6 +1313,17 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
                         (Some(fid), _, _) => format!(" [{}] {} — {} ", pane_num, pane.agent_id, fid),
                         _ => format!(" [{}] {} ", pane_num, pane.agent_id),
                     };
+                    let following = pane.is_following();
+                    let mut title = vec![Span::raw(title)];
+                    if let Some(badge) = scroll_indicator(following, pane.scroll_offset()) {
+                        title.push(Span::styled(
+                            badge,
+                            Style::default()
+                                .fg(Color::Black)
+                                .bg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                    }
 
                     let is_active = Some(index) == active_pane;
                     let border_style = if is_active {
@@ -711,14 +1336,14 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
 
                     let block = Block::default()
                         .borders(Borders::ALL)
-                        .title(title)
+                        .title(Line::from(title))
                         .style(border_style);
 
                     let inner = block.inner(chunk);
                     pane.resize_to_inner(inner);
 
                     let mut cursor = Cursor::default();
-                    if !is_active {
+                    if !is_active || !following {
                         cursor.hide();
                     }
 
@@ -732,7 +1357,16 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
                 }
             }
 
-            render_status_bar(&tui_status.counts, &tui_status.milestones, command_mode, &coco_str, &working_info, status_area, frame);
+            let info = StatusBarInfo {
+                counts: &tui_status.counts,
+                milestones: &tui_status.milestones,
+                cocoindex_status: &coco_str,
+                working_info: &working_info,
+                verify: verify_summary,
+                keys,
+            };
+            render_status_bar(&info, command_mode, status_area, frame);
+            render_notifications(&notifications, pane_area, frame);
         })?;
 
         if event::poll(Duration::from_millis(10))? {
@@ -741,69 +1375,127 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
                     if command_mode {
                         // Command mode: interpret next key as a command, then return to normal
                         command_mode = false;
-                        match key.code {
-                            // 1-9: jump to pane by number
-                            KeyCode::Char(c @ '1'..='9') => {
-                                let target = (c as usize) - ('1' as usize);
-                                if target < panes.len() {
-                                    active_pane = Some(target);
-                                }
+                        // 1-9: jump to pane by number
+                        if let KeyCode::Char(c @ '1'..='9') = key.code
+                            && key.modifiers.is_empty()
+                        {
+                            let target = (c as usize) - ('1' as usize);
+                            if target < panes.len() {
+                                active_pane = Some(target);
                             }
-                            // j or Down: next pane
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                if let Some(idx) = active_pane {
-                                    if idx < panes.len().saturating_sub(1) {
+                        } else {
+                            match keys.action(&key) {
+                                Some(Action::Next) => {
+                                    if let Some(idx) = active_pane
+                                        && idx < panes.len().saturating_sub(1)
+                                    {
                                         active_pane = Some(idx + 1);
                                     }
                                 }
-                            }
-                            // k or Up: previous pane
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                if let Some(idx) = active_pane {
-                                    active_pane = Some(idx.saturating_sub(1));
+                                Some(Action::Prev) => {
+                                    if let Some(idx) = active_pane {
+                                        active_pane = Some(idx.saturating_sub(1));
+                                    }
                                 }
-                            }
-                            // n: new pane
-                            KeyCode::Char('n') => {
-                                let ts = terminal.size()?;
-                                let nr = panes.len() as u16 + 1;
-                                let (r, c) = estimate_inner(ts.height, ts.width, nr);
-                                open_next_feature_pane(
-                                    &mut panes,
-                                    &mut active_pane,
-                                    r,
-                                    c,
-                                    config,
-                                    None,
-                                    &mut next_agent_id,
-                                );
-                            }
-                            // x: close active pane
-                            KeyCode::Char('x') => {
-                                if let Some(idx) = active_pane {
-                                    panes.remove(idx);
-                                    if panes.is_empty() {
-                                        active_pane = None;
-                                    } else {
-                                        active_pane = Some(idx % panes.len());
+                                Some(Action::New) => {
+                                    let ts = terminal.size()?;
+                                    let nr = panes.len() as u16 + 1;
+                                    let (r, c) = estimate_pane_inner(layout, ts.height, ts.width, nr);
+                                    open_next_feature_pane(
+                                        &mut panes,
+                                        &mut active_pane,
+                                        r,
+                                        c,
+                                        config,
+                                        None,
+                                        &mut next_agent_id,
+                                    );
+                                    sync_in_flight(&panes, &signals);
+                                }
+                                // Close the active pane
+                                Some(Action::Close) => {
+                                    if let Some(idx) = active_pane {
+                                        let pane = panes.remove(idx);
+                                        pane.kill();
+                                        emit_pane_end(&project_dir, &pane);
+                                        if let Some(msg) = release_pane_worktree(&project_dir, &pane, false, &config.git, &guard) {
+                                            notifications.push(NoticeLevel::Info, msg);
+                                        }
+                                        sync_in_flight(&panes, &signals);
+                                        if panes.is_empty() {
+                                            active_pane = None;
+                                        } else {
+                                            active_pane = Some(idx % panes.len());
+                                        }
                                     }
                                 }
+                                // Scroll back one page, locking the view
+                                Some(Action::Scroll) => {
+                                    if let Some(pane) = active_pane.and_then(|i| panes.get(i)) {
+                                        pane.scroll(pane.page_rows());
+                                    }
+                                }
+                                Some(Action::Follow) => {
+                                    if let Some(pane) = active_pane.and_then(|i| panes.get(i)) {
+                                        pane.set_follow(!pane.is_following());
+                                    }
+                                }
+                                Some(Action::Layout) => {
+                                    layout = layout.toggled();
+                                }
+                                Some(Action::Quit) => {
+                                    break;
+                                }
+                                // Cancel or an unbound key: leave command mode
+                                Some(Action::Cancel) | None => {}
                             }
-                            // q: quit
-                            KeyCode::Char('q') => {
-                                break;
-                            }
-                            // Esc or anything else: cancel command mode
-                            _ => {}
                         }
-                    } else if is_ctrl_g(&key) {
+                    } else if keys.is_prefix(&key) {
                         // Enter command mode
                         command_mode = true;
                     } else {
                         // Normal mode: forward everything to the active pane
-                        if let Some(idx) = active_pane {
-                            if idx < panes.len() {
-                                handle_pane_key_event(&panes[idx].sender, &key);
+                        // unless it is scroll-locked and the key navigates
+                        if let Some(idx) = active_pane
+                            && idx < panes.len()
+                            && !handle_scroll_key(&panes[idx], &key)
+                        {
+                            handle_pane_key_event(&panes[idx].sender, &key);
+                        }
+                    }
+                }
+                // Mouse wheel scrolls the pane under the pointer; clicking a
+                // tab switches to it
+                Event::Mouse(mouse) => {
+                    let pos = (mouse.column, mouse.row).into();
+                    let lines = match mouse.kind {
+                        MouseEventKind::ScrollUp => 3,
+                        MouseEventKind::ScrollDown => -3,
+                        _ => 0,
+                    };
+                    if lines != 0
+                        && let Some(pane) = (0..panes.len())
+                            .find(|&i| {
+                                pane_rect(layout, last_pane_area, i, panes.len(), active_pane)
+                                    .is_some_and(|r| r.contains(pos))
+                            })
+                            .map(|i| &panes[i])
+                    {
+                        pane.scroll(lines);
+                    }
+                    if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                        && layout == TuiLayout::Tabs
+                    {
+                        let (bar, _) = split_tabs_area(last_pane_area);
+                        if bar.contains(pos) {
+                            let labels: Vec<String> =
+                                panes.iter().enumerate().map(|(i, p)| tab_label(i, p)).collect();
+                            let x = mouse.column - bar.x;
+                            if let Some((i, _, _)) = tab_positions(&labels, active_pane.unwrap_or(0), bar.width)
+                                .into_iter()
+                                .find(|&(_, tx, w)| x >= tx && x < tx + w)
+                            {
+                                active_pane = Some(i);
                             }
                         }
                     }
@@ -820,6 +1512,19 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
         if status_tick >= 200 {
             status_tick = 0;
             tui_status = load_tui_status(&project_dir);
+
+            let idle_after = Duration::from_secs(opts.alerts.idle_secs);
+            for pane in panes.iter_mut() {
+                if let Some(silent) = pane.check_idle(idle_after) {
+                    let alert = Alert::AgentIdle {
+                        agent_id: pane.agent_id.clone(),
+                        idle_secs: silent.as_secs(),
+                    };
+                    if tui_alerts::fire(&opts.alerts, &alert) {
+                        notifications.push(NoticeLevel::Warn, alert.message());
+                    }
+                }
+            }
         }
 
         // Replace exited panes with next available features
@@ -827,7 +1532,22 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
         while i < panes.len() {
             if !panes[i].is_alive() {
                 let completed_id = panes[i].feature_id.clone();
-                panes.remove(i);
+                let pane = panes.remove(i);
+                emit_pane_end(&project_dir, &pane);
+                let alert = Alert::PaneExited {
+                    agent_id: pane.agent_id.clone(),
+                    feature_id: completed_id.clone(),
+                };
+                if tui_alerts::fire(&opts.alerts, &alert) {
+                    notifications.push(NoticeLevel::Info, alert.message());
+                }
+                if let Some(msg) = release_pane_worktree(&project_dir, &pane, true, &config.git, &guard) {
+                    notifications.push(NoticeLevel::Info, msg);
+                }
+                // Drop the finished feature from in-flight before the
+                // orchestrator picks up the exit
+                sync_in_flight(&panes, &signals);
+                signals.pane_finished.store(true, Ordering::Release);
                 // Non-blocking cocoindex refresh
                 {
                     let status = cocoindex_status.clone();
@@ -844,19 +1564,21 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
                 // Try to spawn a replacement — prefer DAG successors of completed feature
                 let ts = terminal.size()?;
                 let nr = panes.len() as u16 + 1;
-                let (r, c) = estimate_inner(ts.height, ts.width, nr);
+                let (r, c) = estimate_pane_inner(layout, ts.height, ts.width, nr);
                 if open_next_feature_pane(
                     &mut panes, &mut active_pane, r, c, config,
                     completed_id.as_deref(),
                     &mut next_agent_id,
-                ).is_none() {
+                ).is_some() {
+                    sync_in_flight(&panes, &signals);
+                } else {
                     // No more features — adjust active pane index
                     if panes.is_empty() {
                         active_pane = None;
-                    } else if let Some(active) = active_pane {
-                        if active >= panes.len() {
-                            active_pane = Some(panes.len() - 1);
-                        }
+                    } else if let Some(active) = active_pane
+                        && active >= panes.len()
+                    {
+                        active_pane = Some(panes.len() - 1);
                     }
                 }
                 // Don't increment i — the replacement (or shifted element) is at the same index
@@ -865,16 +1587,35 @@ pub async fn run_tui(config: &RunConfig) -> io::Result<()> {
             }
         }
 
-        // If all panes are gone and no features left, exit
+        // If all panes are gone and no features left, run a last verify pass
+        // (the orchestrator may not have seen the final exit yet), then exit
         if panes.is_empty() {
             tui_status = load_tui_status(&project_dir);
             if tui_status.counts.pending == 0 && tui_status.counts.claimed == 0 {
-                break;
+                match tui_orchestrator::orchestrate_once(&project_dir, &HashSet::new()) {
+                    Ok(update) if !update.reopened.is_empty() => {
+                        apply_orchestration_update(&update, &mut notifications, &mut verify_summary);
+                        tui_status = load_tui_status(&project_dir);
+                        let ts = terminal.size()?;
+                        let (r, c) = estimate_pane_inner(layout, ts.height, ts.width, 1);
+                        open_next_feature_pane(&mut panes, &mut active_pane, r, c, config, None, &mut next_agent_id);
+                        sync_in_flight(&panes, &signals);
+                    }
+                    _ => break,
+                }
             }
         }
     }
 
-    ratatui::restore();
+    signals.stop.store(true, Ordering::Relaxed);
+
+    // Quitting kills the remaining agents; keep their work on its branches
+    for pane in &panes {
+        pane.kill();
+        release_pane_worktree(&project_dir, pane, false, &config.git, &guard);
+    }
+
+    restore_terminal();
     Ok(())
 }
 
@@ -978,9 +1719,9 @@ mod tests {
                 }
             }
         }
-        for py in 0..area.height as usize {
-            for px in 0..area.width as usize {
-                assert!(covered[py][px], "pixel ({px},{py}) not covered");
+        for (py, row) in covered.iter().enumerate() {
+            for (px, cell) in row.iter().enumerate() {
+                assert!(*cell, "pixel ({px},{py}) not covered");
             }
         }
     }
@@ -1054,7 +1795,7 @@ mod tests {
         assert!(c4 < c1, "cols should shrink: {c4} vs {c1}");
     }
 
-    // ── is_ctrl_g tests ──────────────────────────────────────────────
+    // ── command-mode prefix tests ────────────────────────────────────
 
     fn make_key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
         KeyEvent {
@@ -1066,23 +1807,23 @@ mod tests {
     }
 
     #[test]
-    fn is_ctrl_g_true() {
-        assert!(is_ctrl_g(&make_key(KeyCode::Char('g'), KeyModifiers::CONTROL)));
+    fn default_prefix_ctrl_g() {
+        assert!(KeyBindings::default().is_prefix(&make_key(KeyCode::Char('g'), KeyModifiers::CONTROL)));
     }
 
     #[test]
-    fn is_ctrl_g_plain_g() {
-        assert!(!is_ctrl_g(&make_key(KeyCode::Char('g'), KeyModifiers::NONE)));
+    fn default_prefix_not_plain_g() {
+        assert!(!KeyBindings::default().is_prefix(&make_key(KeyCode::Char('g'), KeyModifiers::NONE)));
     }
 
     #[test]
-    fn is_ctrl_g_ctrl_h() {
-        assert!(!is_ctrl_g(&make_key(KeyCode::Char('h'), KeyModifiers::CONTROL)));
+    fn default_prefix_not_ctrl_h() {
+        assert!(!KeyBindings::default().is_prefix(&make_key(KeyCode::Char('h'), KeyModifiers::CONTROL)));
     }
 
     #[test]
-    fn is_ctrl_g_shift_g() {
-        assert!(!is_ctrl_g(&make_key(KeyCode::Char('g'), KeyModifiers::SHIFT)));
+    fn default_prefix_not_shift_g() {
+        assert!(!KeyBindings::default().is_prefix(&make_key(KeyCode::Char('g'), KeyModifiers::SHIFT)));
     }
 
     // ── handle_pane_key_event tests ──────────────────────────────────
@@ -1183,6 +1924,10 @@ mod tests {
             last_size: (24, 80),
             feature_priority: None,
             feature_type: None,
+            worktree: None,
+            follow: Arc::new(AtomicBool::new(true)),
+            last_output: Arc::new(Mutex::new(Instant::now())),
+            idle_alerted: false,
         }
     }
 
@@ -1270,15 +2015,259 @@ mod tests {
         assert_eq!(active, Some(1));
     }
 
+    // ── scroll-lock tests ────────────────────────────────────────────
+
+    fn scroll_pane(history: usize) -> PtyPane {
+        let mut pane = mock_pane("a1", false);
+        pane.last_size = (3, 10);
+        let mut parser = vt100::Parser::new(3, 10, 100);
+        for i in 0..history + 3 {
+            parser.process(format!("\r\nline{i}").as_bytes());
+        }
+        *pane.parser.write().unwrap() = parser;
+        pane
+    }
+
+    fn visible(pane: &PtyPane) -> String {
+        pane.parser.read().unwrap().screen().contents()
+    }
+
+    #[test]
+    fn process_output_follow_moves_view() {
+        let mut parser = vt100::Parser::new(3, 10, 100);
+        process_output(&mut parser, b"l1\r\nl2\r\nl3", true);
+        process_output(&mut parser, b"\r\nl4\r\nl5", true);
+        assert_eq!(parser.screen().contents(), "l3\nl4\nl5");
+        assert_eq!(parser.screen().scrollback(), 0);
+    }
+
+    #[test]
+    fn process_output_locked_at_bottom_pins_view() {
+        let mut parser = vt100::Parser::new(3, 10, 100);
+        process_output(&mut parser, b"l1\r\nl2\r\nl3", true);
+        process_output(&mut parser, b"\r\nl4\r\nl5", false);
+        assert_eq!(parser.screen().contents(), "l1\nl2\nl3");
+        assert_eq!(parser.screen().scrollback(), 2);
+    }
+
+    #[test]
+    fn process_output_locked_scrolled_back_stays_put() {
+        let mut parser = vt100::Parser::new(3, 10, 100);
+        process_output(&mut parser, b"l1\r\nl2\r\nl3\r\nl4", true);
+        parser.screen_mut().set_scrollback(1);
+        process_output(&mut parser, b"\r\nl5\r\nl6", false);
+        assert_eq!(parser.screen().contents(), "l1\nl2\nl3");
+    }
+
+    #[test]
+    fn scroll_back_locks_and_bottom_refollows() {
+        let pane = scroll_pane(10);
+        assert!(pane.is_following());
+
+        pane.scroll(4);
+        assert!(!pane.is_following());
+        assert_eq!(pane.scroll_offset(), 4);
+
+        pane.scroll(-2);
+        assert!(!pane.is_following());
+        pane.scroll(-5);
+        assert!(pane.is_following());
+        assert_eq!(pane.scroll_offset(), 0);
+    }
+
+    #[test]
+    fn scroll_clamps_to_history() {
+        let pane = scroll_pane(5);
+        pane.scroll(isize::MAX);
+        // 8 lines plus the blank first row, 3 on screen
+        assert_eq!(pane.scroll_offset(), 6);
+        assert!(visible(&pane).contains("line0"), "got: {}", visible(&pane));
+    }
+
+    #[test]
+    fn scroll_keys_only_consumed_when_locked() {
+        let pane = scroll_pane(10);
+        let page_up = make_key(KeyCode::PageUp, KeyModifiers::NONE);
+        // Following: PageUp goes to the agent
+        assert!(!handle_scroll_key(&pane, &page_up));
+
+        // Shift+PageUp enters scroll-lock
+        assert!(handle_scroll_key(&pane, &make_key(KeyCode::PageUp, KeyModifiers::SHIFT)));
+        assert!(!pane.is_following());
+        assert_eq!(pane.scroll_offset(), 2);
+
+        assert!(handle_scroll_key(&pane, &page_up));
+        assert!(handle_scroll_key(&pane, &make_key(KeyCode::Down, KeyModifiers::NONE)));
+        assert_eq!(pane.scroll_offset(), 3);
+
+        // End re-follows and is consumed
+        assert!(handle_scroll_key(&pane, &make_key(KeyCode::End, KeyModifiers::NONE)));
+        assert!(pane.is_following());
+        assert_eq!(pane.scroll_offset(), 0);
+    }
+
+    #[test]
+    fn typing_while_locked_refollows_and_forwards() {
+        let pane = scroll_pane(10);
+        pane.scroll(3);
+        assert!(!handle_scroll_key(&pane, &make_key(KeyCode::Char('y'), KeyModifiers::NONE)));
+        assert!(pane.is_following());
+        assert_eq!(pane.scroll_offset(), 0);
+    }
+
+    #[test]
+    fn follow_toggle_pins_at_bottom() {
+        let pane = scroll_pane(2);
+        pane.set_follow(false);
+        assert!(!pane.is_following());
+        assert_eq!(pane.scroll_offset(), 0);
+        pane.set_follow(true);
+        assert!(pane.is_following());
+    }
+
+    #[test]
+    fn scroll_indicator_badges() {
+        assert_eq!(scroll_indicator(true, 0), None);
+        assert_eq!(scroll_indicator(false, 0).as_deref(), Some(" SCROLL-LOCK "));
+        assert_eq!(scroll_indicator(false, 42).as_deref(), Some(" SCROLL -42 "));
+    }
+
+    // ── idle alert tests ─────────────────────────────────────────────
+
+    #[test]
+    fn check_idle_fires_once_and_rearms() {
+        let mut pane = mock_pane("a1", false);
+        let threshold = Duration::from_secs(60);
+        assert_eq!(pane.check_idle(threshold), None);
+
+        *pane.last_output.lock().unwrap() = Instant::now() - Duration::from_secs(90);
+        let silent = pane.check_idle(threshold).unwrap();
+        assert!(silent >= Duration::from_secs(90));
+        assert_eq!(tab_color(&pane), Color::LightRed);
+        // Still idle: no repeat
+        assert_eq!(pane.check_idle(threshold), None);
+
+        // Output resumes, then goes quiet again
+        *pane.last_output.lock().unwrap() = Instant::now();
+        assert_eq!(pane.check_idle(threshold), None);
+        assert!(!pane.idle_alerted);
+        *pane.last_output.lock().unwrap() = Instant::now() - Duration::from_secs(61);
+        assert!(pane.check_idle(threshold).is_some());
+    }
+
+    #[test]
+    fn check_idle_ignores_exited_panes() {
+        let mut pane = mock_pane("a1", true);
+        *pane.last_output.lock().unwrap() = Instant::now() - Duration::from_secs(90);
+        assert_eq!(pane.check_idle(Duration::from_secs(60)), None);
+    }
+
+    // ── tabs layout tests ────────────────────────────────────────────
+
+    #[test]
+    fn tabs_layout_shows_only_active_pane() {
+        let area = Rect::new(0, 0, 100, 40);
+        assert_eq!(pane_rect(TuiLayout::Tabs, area, 0, 3, Some(1)), None);
+        assert_eq!(
+            pane_rect(TuiLayout::Tabs, area, 1, 3, Some(1)),
+            Some(Rect::new(0, 1, 100, 39))
+        );
+        // Grid shows everything
+        assert!((0..3).all(|i| pane_rect(TuiLayout::Grid, area, i, 3, Some(1)).is_some()));
+    }
+
+    #[test]
+    fn tabs_estimate_is_full_size_minus_bar() {
+        let grid = estimate_pane_inner(TuiLayout::Grid, 80, 200, 8);
+        let tabs = estimate_pane_inner(TuiLayout::Tabs, 80, 200, 8);
+        // 80 rows - status bar - tab bar - borders
+        assert_eq!(tabs, (76, 198));
+        assert!(tabs.0 > grid.0 && tabs.1 > grid.1);
+    }
+
+    #[test]
+    fn tab_positions_fit_and_scroll_to_active() {
+        let labels: Vec<String> = (0..4).map(|i| format!(" tab{i} ")).collect(); // 6 wide
+        let all = tab_positions(&labels, 0, 100);
+        assert_eq!(all, vec![(0, 0, 6), (1, 6, 6), (2, 12, 6), (3, 18, 6)]);
+
+        // Only 2 fit: the bar scrolls so the active tab is visible
+        let scrolled = tab_positions(&labels, 3, 12);
+        assert_eq!(scrolled, vec![(2, 0, 6), (3, 6, 6)]);
+
+        // Partial last tab is clipped to the bar
+        assert_eq!(tab_positions(&labels, 0, 9), vec![(0, 0, 6), (1, 6, 3)]);
+        assert!(tab_positions(&[], 0, 10).is_empty());
+    }
+
+    #[test]
+    fn tab_color_reflects_state() {
+        let mut pane = mock_pane("a1", false);
+        assert_eq!(tab_color(&pane), Color::Green);
+        pane.feature_type = Some(FeatureType::Review);
+        assert_eq!(tab_color(&pane), Color::Magenta);
+        pane.follow.store(false, Ordering::Release);
+        assert_eq!(tab_color(&pane), Color::Yellow);
+        pane.exited.store(true, Ordering::Release);
+        assert_eq!(tab_color(&pane), Color::DarkGray);
+    }
+
+    #[test]
+    fn tab_bar_renders_agent_and_feature() {
+        let mut a = mock_pane("agent-1", false);
+        a.feature_id = Some("f001".into());
+        let b = mock_pane("agent-2", false);
+        let panes = vec![a, b];
+
+        let backend = TestBackend::new(60, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| render_tab_bar(&panes, 1, frame.area(), frame))
+            .unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let row: String = (0..buf.area.width).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert!(row.starts_with(" 1 agent-1 f001  2 agent-2 "), "got: {row}");
+        // Active tab is highlighted with its status color
+        assert_eq!(buf[(16, 0)].bg, Color::Green);
+        assert_eq!(buf[(0, 0)].bg, Color::Black);
+    }
+
+    #[test]
+    fn tui_options_from_config() {
+        let mut tui = TuiConfig::default();
+        assert_eq!(TuiOptions::from_config(&tui).unwrap().layout, TuiLayout::Grid);
+        tui.layout = TuiLayout::Tabs;
+        assert_eq!(TuiOptions::from_config(&tui).unwrap().layout.toggled(), TuiLayout::Grid);
+        tui.keys.insert("bogus".into(), crate::config::KeyList::One("b".into()));
+        assert!(TuiOptions::from_config(&tui).is_err());
+    }
+
     // ── render_status_bar tests ──────────────────────────────────────
 
     fn render_status_bar_to_string(counts: &StatusCounts, milestones: &str, command_mode: bool) -> String {
+        render_status_bar_with_verify(counts, milestones, command_mode, None)
+    }
+
+    fn render_status_bar_with_verify(
+        counts: &StatusCounts,
+        milestones: &str,
+        command_mode: bool,
+        verify: Option<(usize, usize)>,
+    ) -> String {
         let backend = TestBackend::new(120, 1);
         let mut terminal = Terminal::new(backend).unwrap();
         terminal
             .draw(|frame| {
                 let area = frame.area();
-                render_status_bar(counts, milestones, command_mode, "", "", area, frame);
+                let info = StatusBarInfo {
+                    counts,
+                    milestones,
+                    cocoindex_status: "",
+                    working_info: "",
+                    verify,
+                    keys: &KeyBindings::default(),
+                };
+                render_status_bar(&info, command_mode, area, frame);
             })
             .unwrap();
         let buf = terminal.backend().buffer().clone();
@@ -1299,6 +2288,7 @@ mod tests {
             claimed: 2,
             done: 4,
             blocked: 1,
+            waiting: 0,
         };
         let ms = "M3 \u{2713} | M4 2/6";
         let text = render_status_bar_to_string(&counts, ms, false);
@@ -1316,6 +2306,7 @@ mod tests {
             claimed: 1,
             done: 2,
             blocked: 1,
+            waiting: 0,
         };
         let text = render_status_bar_to_string(&counts, "", true);
         assert!(text.contains("CMD"), "got: {text}");
@@ -1323,6 +2314,95 @@ mod tests {
         assert!(text.contains("n:new"), "got: {text}");
     }
 
+    #[test]
+    fn status_bar_shows_verify_summary() {
+        let counts = StatusCounts {
+            total: 4,
+            pending: 1,
+            claimed: 0,
+            done: 3,
+            blocked: 0,
+            waiting: 0,
+        };
+        let text = render_status_bar_with_verify(&counts, "", false, Some((2, 3)));
+        assert!(text.contains("verify 2/3"), "got: {text}");
+        let text = render_status_bar_to_string(&counts, "", false);
+        assert!(!text.contains("verify"), "got: {text}");
+    }
+
+    // ── orchestration notification tests ─────────────────────────────
+
+    fn verify_result(id: &str, passed: bool) -> crate::verify::VerifyResult {
+        crate::verify::VerifyResult {
+            feature_id: id.into(),
+            passed,
+            output: String::new(),
+        }
+    }
+
+    #[test]
+    fn orchestration_update_failures_notify_and_update_summary() {
+        let mut notes = Notifications::default();
+        let mut verify = None;
+        let update = OrchestrationUpdate {
+            verify_results: vec![verify_result("f001", true), verify_result("f002", false)],
+            reopened: vec!["f002".into()],
+            all_done: false,
+        };
+        apply_orchestration_update(&update, &mut notes, &mut verify);
+
+        assert_eq!(verify, Some((1, 2)));
+        let texts: Vec<&str> = notes.items.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, vec!["verify: 1 of 2 failed", "reopened f002"]);
+        assert!(notes.items.iter().all(|n| n.level == NoticeLevel::Warn));
+    }
+
+    #[test]
+    fn orchestration_update_all_done_without_results_keeps_summary() {
+        let mut notes = Notifications::default();
+        let mut verify = Some((3, 3));
+        let update = OrchestrationUpdate {
+            verify_results: vec![],
+            reopened: vec![],
+            all_done: true,
+        };
+        apply_orchestration_update(&update, &mut notes, &mut verify);
+
+        assert_eq!(verify, Some((3, 3)));
+        assert_eq!(notes.items.len(), 1);
+        assert_eq!(notes.items[0].level, NoticeLevel::Success);
+    }
+
+    #[test]
+    fn notifications_cap_and_expire() {
+        let mut notes = Notifications::default();
+        for i in 0..6 {
+            notes.push(NoticeLevel::Info, format!("n{i}"));
+        }
+        assert_eq!(notes.items.len(), Notifications::MAX_VISIBLE);
+        assert_eq!(notes.items.front().unwrap().text, "n2");
+
+        notes.prune(Instant::now());
+        assert_eq!(notes.items.len(), Notifications::MAX_VISIBLE);
+        notes.prune(Instant::now() + Notifications::TTL);
+        assert!(notes.items.is_empty());
+    }
+
+    #[test]
+    fn notifications_render_over_pane_area() {
+        let backend = TestBackend::new(60, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut notes = Notifications::default();
+        notes.push(NoticeLevel::Warn, "reopened f007");
+        terminal
+            .draw(|frame| render_notifications(&notes, frame.area(), frame))
+            .unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let row: String = (0..buf.area.width).map(|x| buf[(x, 1)].symbol().to_string()).collect();
+        assert!(row.contains("reopened f007"), "got: {row}");
+        assert!(row.trim_end().ends_with('│'), "should hug the right edge: {row}");
+    }
+
     #[test]
     fn status_bar_zero_features() {
         let counts = StatusCounts {
@@ -1331,11 +2411,117 @@ mod tests {
             claimed: 0,
             done: 0,
             blocked: 0,
+            waiting: 0,
         };
         let text = render_status_bar_to_string(&counts, "", false);
         assert!(text.contains("0/0"), "got: {text}");
     }
 
+    // ── worktree isolation tests ─────────────────────────────────────
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn init_repo_with_features(dir: &Path) {
+        git(dir, &["init"]);
+        git(dir, &["config", "user.email", "test@test.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("features.json"), r#"{"features":[]}"#).unwrap();
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-m", "init"]);
+    }
+
+    #[test]
+    fn pane_worktree_merges_agent_work_on_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo_with_features(dir.path());
+
+        let wt = prepare_pane_worktree(dir.path(), "agent-1", "f001", &GitConfig::default()).unwrap();
+        assert!(wt.starts_with(dir.path().join(".forge/worktrees")));
+        // Agent leaves work uncommitted — release must still carry it over
+        std::fs::write(wt.join("feature.rs"), "fn f001() {}").unwrap();
+
+        let mut pane = mock_pane("agent-1", true);
+        pane.worktree = Some(wt.clone());
+        let msg = release_pane_worktree(dir.path(), &pane, true, &GitConfig::default(), &CommitGuard::default()).unwrap();
+
+        assert!(msg.contains("merged"), "got: {msg}");
+        assert!(dir.path().join("feature.rs").exists());
+        assert!(!wt.exists());
+    }
+
+    #[test]
+    fn pane_touching_protected_paths_is_not_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo_with_features(dir.path());
+        let git_config = GitConfig { protected: vec![".github/**".into()], ..GitConfig::default() };
+
+        let wt = prepare_pane_worktree(dir.path(), "agent-1", "f001", &git_config).unwrap();
+        std::fs::create_dir_all(wt.join(".github")).unwrap();
+        std::fs::write(wt.join(".github/ci.yml"), "on: push").unwrap();
+
+        let mut pane = mock_pane("agent-1", true);
+        pane.worktree = Some(wt);
+        pane.feature_id = Some("f001".into());
+        let msg = release_pane_worktree(dir.path(), &pane, true, &git_config, &CommitGuard::default()).unwrap();
+
+        assert_eq!(msg, "agent-1: not merged, changes protected paths: .github/ci.yml; work kept on forge/protected/f001");
+        assert!(!dir.path().join(".github/ci.yml").exists());
+        git(dir.path(), &["show", "forge/protected/f001:.github/ci.yml"]);
+        let memory = std::fs::read_to_string(dir.path().join("feedback/exec-memory/f001.json")).unwrap();
+        assert!(memory.contains("forbids changing .github/ci.yml"), "{memory}");
+    }
+
+    #[test]
+    fn pane_with_secret_keeps_its_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo_with_features(dir.path());
+
+        let wt = prepare_pane_worktree(dir.path(), "agent-1", "f001", &GitConfig::default()).unwrap();
+        std::fs::write(wt.join("client.rs"), "const KEY: &str = \"sk-ant-REDACTED\";\n")
+            .unwrap();
+
+        let mut pane = mock_pane("agent-1", true);
+        pane.worktree = Some(wt.clone());
+        let msg = release_pane_worktree(dir.path(), &pane, true, &GitConfig::default(), &CommitGuard::default()).unwrap();
+
+        assert!(msg.starts_with("agent-1: not merged, secret scan blocked the commit: client.rs:1 (anthropic-key)"), "{msg}");
+        assert!(wt.join("client.rs").exists());
+        assert!(!dir.path().join("client.rs").exists());
+    }
+
+    #[test]
+    fn pane_worktree_close_keeps_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo_with_features(dir.path());
+
+        let wt = prepare_pane_worktree(dir.path(), "agent-1", "f001", &GitConfig::default()).unwrap();
+        std::fs::write(wt.join("partial.rs"), "// wip").unwrap();
+
+        let mut pane = mock_pane("agent-1", false);
+        pane.worktree = Some(wt);
+        let msg = release_pane_worktree(dir.path(), &pane, false, &GitConfig::default(), &CommitGuard::default()).unwrap();
+
+        assert!(msg.contains("kept on forge/agent-1"), "got: {msg}");
+        assert!(!dir.path().join("partial.rs").exists());
+        // Work survives on the branch
+        git(dir.path(), &["show", "forge/agent-1:partial.rs"]);
+    }
+
+    #[test]
+    fn release_without_worktree_is_noop() {
+        let dir = tempfile::tempdir().unwrap();
+        let pane = mock_pane("agent-1", true);
+        assert!(release_pane_worktree(dir.path(), &pane, true, &GitConfig::default(), &CommitGuard::default()).is_none());
+    }
+
     // ── resize debounce tests ────────────────────────────────────────
 
     #[test]
@@ -1550,14 +2736,21 @@ mod tests {
 
     // ── integration: real PTY tests ──────────────────────────────────
 
+    fn command(program: &str, args: &[&str]) -> runner::AgentCommand {
+        runner::AgentCommand {
+            program: program.into(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: Default::default(),
+        }
+    }
+
     #[test]
     #[ignore] // requires real PTY — run with: cargo test -- --ignored
     fn pty_spawn_echo_roundtrip() {
         let pane = PtyPane::new(
             24,
             80,
-            "echo",
-            &["hello".into()],
+            &command("echo", &["hello"]),
             Path::new("/tmp"),
             "test-1".into(),
             None,
@@ -1579,8 +2772,7 @@ mod tests {
         let pane = PtyPane::new(
             24,
             80,
-            "cat",
-            &[],
+            &command("cat", &[]),
             Path::new("/tmp"),
             "pressure-1".into(),
             None,
@@ -1617,8 +2809,7 @@ mod tests {
         let pane = PtyPane::new(
             24,
             80,
-            "bash",
-            &["--norc".into(), "--noprofile".into()],
+            &command("bash", &["--norc", "--noprofile"]),
             Path::new("/tmp"),
             "resize-1".into(),
             None,
@@ -0,0 +1,115 @@
+use std::io::Write;
+
+use crate::config::AlertConfig;
+use crate::notify_desktop;
+
+/// Something worth pinging the user about while the terminal is in the background.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alert {
+    PaneExited {
+        agent_id: String,
+        feature_id: Option<String>,
+    },
+    VerifyFailed {
+        failed: usize,
+        total: usize,
+    },
+    AgentIdle {
+        agent_id: String,
+        idle_secs: u64,
+    },
+}
+
+impl Alert {
+    pub fn message(&self) -> String {
+        match self {
+            Alert::PaneExited {
+                agent_id,
+                feature_id: Some(fid),
+            } => format!("{agent_id} finished {fid}"),
+            Alert::PaneExited { agent_id, .. } => format!("{agent_id} exited"),
+            Alert::VerifyFailed { failed, total } => format!("verify failed: {failed} of {total}"),
+            Alert::AgentIdle {
+                agent_id,
+                idle_secs,
+            } => format!("{agent_id} idle for {} — waiting for input?", format_idle(*idle_secs)),
+        }
+    }
+
+    fn enabled(&self, cfg: &AlertConfig) -> bool {
+        match self {
+            Alert::PaneExited { .. } => cfg.pane_exited,
+            Alert::VerifyFailed { .. } => cfg.verify_failed,
+            Alert::AgentIdle { .. } => cfg.agent_idle,
+        }
+    }
+}
+
+fn format_idle(secs: u64) -> String {
+    if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Ring the bell and/or post a desktop notification, as configured.
+/// Returns whether the alert's trigger is enabled at all.
+pub fn fire(cfg: &AlertConfig, alert: &Alert) -> bool {
+    if !alert.enabled(cfg) {
+        return false;
+    }
+    if cfg.bell {
+        ring_bell();
+    }
+    if cfg.desktop {
+        let message = alert.message();
+        // Off the TUI loop, which shouldn't wait on the notification daemon
+        std::thread::spawn(move || notify_desktop::show("forge", &message));
+    }
+    true
+}
+
+/// BEL goes straight to the terminal; it doesn't disturb the drawn screen.
+fn ring_bell() {
+    let mut out = std::io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        let exited = Alert::PaneExited {
+            agent_id: "agent-1".into(),
+            feature_id: Some("f003".into()),
+        };
+        assert_eq!(exited.message(), "agent-1 finished f003");
+        let verify = Alert::VerifyFailed { failed: 2, total: 5 };
+        assert_eq!(verify.message(), "verify failed: 2 of 5");
+        let idle = Alert::AgentIdle {
+            agent_id: "agent-2".into(),
+            idle_secs: 300,
+        };
+        assert_eq!(idle.message(), "agent-2 idle for 5m — waiting for input?");
+    }
+
+    #[test]
+    fn disabled_triggers_do_not_fire() {
+        let cfg = AlertConfig {
+            bell: false,
+            desktop: false,
+            pane_exited: false,
+            ..AlertConfig::default()
+        };
+        let exited = Alert::PaneExited {
+            agent_id: "a".into(),
+            feature_id: None,
+        };
+        assert!(!fire(&cfg, &exited));
+        assert!(fire(&cfg, &Alert::VerifyFailed { failed: 1, total: 1 }));
+    }
+}
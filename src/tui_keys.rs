@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeyList;
+
+/// A key plus modifiers, parsed from strings like `"ctrl+g"`, `"j"`, `"down"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let lower = s.trim().to_ascii_lowercase();
+        if lower.is_empty() {
+            return Err("empty key".into());
+        }
+
+        // Split "ctrl+shift+x" into modifiers and the final key; a trailing "+"
+        // means the plus key itself ("ctrl++").
+        let (mods_part, key_part) = match lower.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None if lower == "+" => ("", "+"),
+            None => match lower.rsplit_once('+') {
+                Some((m, k)) => (m, k),
+                None => ("", lower.as_str()),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods_part.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match m {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier '{other}'")),
+            };
+        }
+
+        let code = match key_part {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "tab" => KeyCode::Tab,
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            k if k.len() > 1 && k.starts_with('f') && k[1..].parse::<u8>().is_ok() => {
+                let n: u8 = k[1..].parse().unwrap();
+                if !(1..=12).contains(&n) {
+                    return Err(format!("unknown key '{k}'"));
+                }
+                KeyCode::F(n)
+            }
+            k if k.chars().count() == 1 => {
+                // A bare "G" means shift+g; with modifiers case is ignored so
+                // "Ctrl+G" (as displayed) reads back as ctrl+g
+                let c = if mods_part.is_empty() {
+                    s.trim().chars().next().unwrap()
+                } else {
+                    k.chars().next().unwrap()
+                };
+                KeyCode::Char(c)
+            }
+            other => return Err(format!("unknown key '{other}'")),
+        };
+
+        Ok(Self::new(code, modifiers).normalized())
+    }
+
+    /// Fold shift into the character for letter keys, so `shift+g`, `G` and a
+    /// terminal's `Char('G') + SHIFT` all compare equal.
+    fn normalized(self) -> Self {
+        match self.code {
+            KeyCode::Char(c) if c.is_ascii_alphabetic() => {
+                let shifted = self.modifiers.contains(KeyModifiers::SHIFT) || c.is_ascii_uppercase();
+                let c = if shifted { c.to_ascii_uppercase() } else { c };
+                Self::new(KeyCode::Char(c), self.modifiers - KeyModifiers::SHIFT)
+            }
+            _ => self,
+        }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        *self == Self::new(key.code, key.modifiers).normalized()
+    }
+
+    /// Keys that would send text to the agent if they were the prefix.
+    fn is_plain_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_))
+            && (self.modifiers - KeyModifiers::SHIFT).is_empty()
+    }
+}
+
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            // Modified letters read better uppercase: Ctrl+G
+            KeyCode::Char(c) if !self.modifiers.is_empty() => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::Esc => write!(f, "esc"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// What a command-mode key does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Next,
+    Prev,
+    New,
+    Close,
+    /// Scroll the active pane back a page (enters scroll-lock).
+    Scroll,
+    /// Toggle follow / scroll-lock on the active pane.
+    Follow,
+    /// Switch between grid and tabs layout.
+    Layout,
+    Quit,
+    Cancel,
+}
+
+impl Action {
+    const ALL: [Action; 9] = [
+        Action::Next,
+        Action::Prev,
+        Action::New,
+        Action::Close,
+        Action::Scroll,
+        Action::Follow,
+        Action::Layout,
+        Action::Quit,
+        Action::Cancel,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::Next => "next",
+            Action::Prev => "prev",
+            Action::New => "new",
+            Action::Close => "close",
+            Action::Scroll => "scroll",
+            Action::Follow => "follow",
+            Action::Layout => "layout",
+            Action::Quit => "quit",
+            Action::Cancel => "cancel",
+        }
+    }
+
+    fn defaults(self) -> Vec<KeySpec> {
+        let plain = |c| KeySpec::new(KeyCode::Char(c), KeyModifiers::NONE);
+        match self {
+            Action::Next => vec![plain('j'), KeySpec::new(KeyCode::Down, KeyModifiers::NONE)],
+            Action::Prev => vec![plain('k'), KeySpec::new(KeyCode::Up, KeyModifiers::NONE)],
+            Action::New => vec![plain('n')],
+            Action::Close => vec![plain('x')],
+            Action::Scroll => vec![plain('[')],
+            Action::Follow => vec![plain('f')],
+            Action::Layout => vec![plain('t')],
+            Action::Quit => vec![plain('q')],
+            Action::Cancel => vec![KeySpec::new(KeyCode::Esc, KeyModifiers::NONE)],
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum KeyBindingError {
+    #[error("[tui.keys]: unknown action '{0}' (expected prefix, next, prev, new, close, scroll, follow, layout, quit, cancel)")]
+    UnknownAction(String),
+    #[error("[tui.keys] {action}: invalid key '{key}': {reason}")]
+    InvalidKey {
+        action: String,
+        key: String,
+        reason: String,
+    },
+    #[error("[tui.keys] {0}: at least one key is required")]
+    Empty(String),
+    #[error("[tui.keys] prefix: '{0}' would capture normal typing; use a modifier (e.g. ctrl+a) or a function key")]
+    PlainPrefix(String),
+    #[error("[tui.keys]: '{key}' is bound to both {first} and {second}")]
+    Conflict {
+        key: String,
+        first: String,
+        second: String,
+    },
+    #[error("[tui.keys] {action}: '{key}' is reserved for jumping to a pane (1-9)")]
+    Reserved { action: String, key: String },
+}
+
+/// Resolved key bindings for the TUI: the prefix that enters command mode and
+/// the command-mode keys for each action. Digits 1-9 always jump to a pane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    pub prefix: Vec<KeySpec>,
+    bindings: Vec<(Action, Vec<KeySpec>)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            prefix: vec![KeySpec::new(KeyCode::Char('g'), KeyModifiers::CONTROL)],
+            bindings: Action::ALL.iter().map(|&a| (a, a.defaults())).collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Build bindings from the `[tui.keys]` table, overriding defaults per action
+    /// and rejecting unknown actions, unparseable keys and conflicts.
+    pub fn from_config(keys: &BTreeMap<String, KeyList>) -> Result<Self, KeyBindingError> {
+        let mut resolved = Self::default();
+
+        for (action, list) in keys {
+            let specs = list
+                .keys()
+                .into_iter()
+                .map(|k| {
+                    KeySpec::parse(k).map_err(|reason| KeyBindingError::InvalidKey {
+                        action: action.clone(),
+                        key: k.to_string(),
+                        reason,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if specs.is_empty() {
+                return Err(KeyBindingError::Empty(action.clone()));
+            }
+
+            if action == "prefix" {
+                if let Some(plain) = specs.iter().find(|s| s.is_plain_text()) {
+                    return Err(KeyBindingError::PlainPrefix(plain.to_string()));
+                }
+                resolved.prefix = specs;
+                continue;
+            }
+
+            let slot = resolved
+                .bindings
+                .iter_mut()
+                .find(|(a, _)| a.name() == action)
+                .ok_or_else(|| KeyBindingError::UnknownAction(action.clone()))?;
+            slot.1 = specs;
+        }
+
+        resolved.check_conflicts()?;
+        Ok(resolved)
+    }
+
+    fn check_conflicts(&self) -> Result<(), KeyBindingError> {
+        let mut seen: Vec<(KeySpec, Action)> = Vec::new();
+        for (action, specs) in &self.bindings {
+            for spec in specs {
+                if spec.modifiers.is_empty()
+                    && matches!(spec.code, KeyCode::Char('1'..='9'))
+                {
+                    return Err(KeyBindingError::Reserved {
+                        action: action.name().into(),
+                        key: spec.to_string(),
+                    });
+                }
+                // Command mode would never see it
+                if self.prefix.contains(spec) {
+                    return Err(KeyBindingError::Conflict {
+                        key: spec.to_string(),
+                        first: "prefix".into(),
+                        second: action.name().into(),
+                    });
+                }
+                match seen.iter().find(|(s, _)| s == spec) {
+                    // The same key listed twice for one action is harmless
+                    Some((_, other)) if other != action => {
+                        return Err(KeyBindingError::Conflict {
+                            key: spec.to_string(),
+                            first: other.name().into(),
+                            second: action.name().into(),
+                        });
+                    }
+                    Some(_) => {}
+                    None => seen.push((*spec, *action)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_prefix(&self, key: &KeyEvent) -> bool {
+        self.prefix.iter().any(|p| p.matches(key))
+    }
+
+    /// Look up the command-mode action for a key.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, specs)| specs.iter().any(|s| s.matches(key)))
+            .map(|(a, _)| *a)
+    }
+
+    /// Primary key for an action, for help text.
+    pub fn label(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .and_then(|(_, specs)| specs.first())
+            .map(|s| s.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn prefix_label(&self) -> String {
+        self.prefix.first().map(|s| s.to_string()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEventKind, KeyEventState};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }
+    }
+
+    fn table(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyList> {
+        entries
+            .iter()
+            .map(|(a, ks)| {
+                (
+                    a.to_string(),
+                    KeyList::Many(ks.iter().map(|k| k.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_key_specs() {
+        assert_eq!(
+            KeySpec::parse("ctrl+g").unwrap(),
+            KeySpec::new(KeyCode::Char('g'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            KeySpec::parse("Alt+Down").unwrap(),
+            KeySpec::new(KeyCode::Down, KeyModifiers::ALT)
+        );
+        assert_eq!(KeySpec::parse("F2").unwrap().code, KeyCode::F(2));
+        assert_eq!(KeySpec::parse("shift+g").unwrap(), KeySpec::parse("G").unwrap());
+        assert_eq!(KeySpec::parse("ctrl++").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(KeySpec::parse("Ctrl+G").unwrap(), KeySpec::parse("ctrl+g").unwrap());
+        assert!(KeySpec::parse("hyper+g").is_err());
+        assert!(KeySpec::parse("f13").is_err());
+        assert!(KeySpec::parse("jk").is_err());
+    }
+
+    #[test]
+    fn key_spec_display() {
+        assert_eq!(KeySpec::parse("ctrl+g").unwrap().to_string(), "Ctrl+G");
+        assert_eq!(KeySpec::parse("j").unwrap().to_string(), "j");
+        assert_eq!(KeySpec::parse("esc").unwrap().to_string(), "esc");
+    }
+
+    #[test]
+    fn shifted_letter_matches_terminal_event() {
+        let spec = KeySpec::parse("G").unwrap();
+        assert!(spec.matches(&key(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(!spec.matches(&key(KeyCode::Char('g'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn defaults_match_builtin_keys() {
+        let kb = KeyBindings::default();
+        assert!(kb.is_prefix(&key(KeyCode::Char('g'), KeyModifiers::CONTROL)));
+        assert_eq!(kb.action(&key(KeyCode::Char('j'), KeyModifiers::NONE)), Some(Action::Next));
+        assert_eq!(kb.action(&key(KeyCode::Up, KeyModifiers::NONE)), Some(Action::Prev));
+        assert_eq!(kb.action(&key(KeyCode::Char('q'), KeyModifiers::NONE)), Some(Action::Quit));
+        assert_eq!(kb.action(&key(KeyCode::Esc, KeyModifiers::NONE)), Some(Action::Cancel));
+        assert_eq!(kb.action(&key(KeyCode::Char('z'), KeyModifiers::NONE)), None);
+    }
+
+    #[test]
+    fn config_overrides_prefix_and_actions() {
+        let kb = KeyBindings::from_config(&table(&[("prefix", &["ctrl+a"]), ("quit", &["Q"])])).unwrap();
+        assert!(kb.is_prefix(&key(KeyCode::Char('a'), KeyModifiers::CONTROL)));
+        assert!(!kb.is_prefix(&key(KeyCode::Char('g'), KeyModifiers::CONTROL)));
+        assert_eq!(kb.action(&key(KeyCode::Char('Q'), KeyModifiers::SHIFT)), Some(Action::Quit));
+        assert_eq!(kb.action(&key(KeyCode::Char('q'), KeyModifiers::NONE)), None);
+        // Untouched actions keep their defaults
+        assert_eq!(kb.action(&key(KeyCode::Char('n'), KeyModifiers::NONE)), Some(Action::New));
+        assert_eq!(kb.prefix_label(), "Ctrl+A");
+    }
+
+    #[test]
+    fn rejects_unknown_action() {
+        let err = KeyBindings::from_config(&table(&[("explode", &["e"])])).unwrap_err();
+        assert_eq!(err, KeyBindingError::UnknownAction("explode".into()));
+    }
+
+    #[test]
+    fn rejects_invalid_and_empty_keys() {
+        let err = KeyBindings::from_config(&table(&[("new", &["ctrl+nope"])])).unwrap_err();
+        assert!(matches!(err, KeyBindingError::InvalidKey { .. }), "got: {err}");
+        let err = KeyBindings::from_config(&table(&[("new", &[])])).unwrap_err();
+        assert_eq!(err, KeyBindingError::Empty("new".into()));
+    }
+
+    #[test]
+    fn rejects_plain_prefix() {
+        let err = KeyBindings::from_config(&table(&[("prefix", &["g"])])).unwrap_err();
+        assert!(matches!(err, KeyBindingError::PlainPrefix(_)), "got: {err}");
+    }
+
+    #[test]
+    fn detects_conflicts() {
+        // "n" is still the default for new
+        let err = KeyBindings::from_config(&table(&[("close", &["n"])])).unwrap_err();
+        assert_eq!(
+            err,
+            KeyBindingError::Conflict {
+                key: "n".into(),
+                first: "new".into(),
+                second: "close".into(),
+            }
+        );
+        // Moving new elsewhere frees the key
+        KeyBindings::from_config(&table(&[("close", &["n"]), ("new", &["a"])])).unwrap();
+
+        let err = KeyBindings::from_config(&table(&[("prefix", &["ctrl+a"]), ("next", &["ctrl+a"])])).unwrap_err();
+        assert_eq!(
+            err,
+            KeyBindingError::Conflict {
+                key: "Ctrl+A".into(),
+                first: "prefix".into(),
+                second: "next".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn digits_are_reserved_for_goto() {
+        let err = KeyBindings::from_config(&table(&[("quit", &["3"])])).unwrap_err();
+        assert!(matches!(err, KeyBindingError::Reserved { .. }), "got: {err}");
+        // With a modifier the digit is free
+        KeyBindings::from_config(&table(&[("quit", &["alt+3"])])).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::features::FeatureList;
@@ -13,11 +14,60 @@ pub struct OrchestrationUpdate {
     pub all_done: bool,
 }
 
-/// Run background orchestration: poll features.json, run verify on done features,
-/// reopen failed features. Returns when all features are done or stop is signaled.
+/// Shared state between the TUI loop and the background orchestrator.
+#[derive(Clone, Default)]
+pub struct OrchestrationSignals {
+    /// Set to stop the orchestrator loop.
+    pub stop: Arc<AtomicBool>,
+    /// Set when a pane exits, forcing a verify pass on the next tick.
+    pub pane_finished: Arc<AtomicBool>,
+    /// Features still being worked on by live panes. Their verify scripts are
+    /// expected to fail mid-session, so they are never verified or reopened.
+    pub in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+/// One verify + reopen pass over done/claimed features, skipping `in_flight`.
+pub fn orchestrate_once(
+    project_dir: &Path,
+    in_flight: &HashSet<String>,
+) -> Result<OrchestrationUpdate, Box<dyn std::error::Error>> {
+    let verify_results = verify::verify_all_except(project_dir, in_flight)?;
+
+    // Write verify report
+    let report = verify::VerifyReport::from_results(&verify_results);
+    let _ = report.write(project_dir);
+
+    // Reopen features that failed verify
+    let mut reopened = Vec::new();
+    if let Ok(mut features) = FeatureList::load(project_dir) {
+        for result in &verify_results {
+            if !result.passed && features.reopen(&result.feature_id).is_ok() {
+                reopened.push(result.feature_id.clone());
+            }
+        }
+        if !reopened.is_empty() {
+            let _ = features.save(project_dir);
+        }
+    }
+    crate::runner::emit_verify(project_dir, &verify_results, reopened.clone());
+
+    let all_done = FeatureList::load(project_dir)
+        .map(|f| f.all_done())
+        .unwrap_or(false);
+
+    Ok(OrchestrationUpdate {
+        verify_results,
+        reopened,
+        all_done,
+    })
+}
+
+/// Run background orchestration: poll features.json, run verify when features
+/// are marked done or a pane exits, reopen failed features.
+/// Returns when all features are done or stop is signaled.
 pub async fn run_orchestration(
     project_dir: &Path,
-    stop: Arc<AtomicBool>,
+    signals: OrchestrationSignals,
     on_update: impl Fn(OrchestrationUpdate) + Send + 'static,
 ) {
     let project_dir = project_dir.to_path_buf();
@@ -26,7 +76,7 @@ pub async fn run_orchestration(
         let mut last_done_count = 0usize;
 
         loop {
-            if stop.load(Ordering::Relaxed) {
+            if signals.stop.load(Ordering::Relaxed) {
                 break;
             }
 
@@ -38,51 +88,31 @@ pub async fn run_orchestration(
                 Err(_) => continue,
             };
 
-            let counts = features.status_counts();
-            let current_done = counts.done;
-
-            // If new features were marked done since last check, run verify
-            if current_done > last_done_count {
-                last_done_count = current_done;
+            let current_done = features.status_counts().done;
+            let pane_finished = signals.pane_finished.swap(false, Ordering::AcqRel);
 
-                let verify_results = match verify::verify_all(&project_dir) {
-                    Ok(r) => r,
+            // Run verify if new features were marked done or a pane exited
+            if current_done > last_done_count || pane_finished {
+                let in_flight = signals.in_flight.lock().map(|s| s.clone()).unwrap_or_default();
+                let update = match orchestrate_once(&project_dir, &in_flight) {
+                    Ok(u) => u,
                     Err(_) => continue,
                 };
 
-                // Write verify report
-                let report = verify::VerifyReport::from_results(&verify_results);
-                let _ = report.write(&project_dir);
-
-                // Reopen features that failed verify
-                let mut reopened = Vec::new();
-                if let Ok(mut features) = FeatureList::load(&project_dir) {
-                    for result in &verify_results {
-                        if !result.passed {
-                            if features.reopen(&result.feature_id).is_ok() {
-                                reopened.push(result.feature_id.clone());
-                            }
-                        }
-                    }
-                    if !reopened.is_empty() {
-                        let _ = features.save(&project_dir);
-                    }
-                }
-
-                let all_done = FeatureList::load(&project_dir)
-                    .map(|f| f.all_done())
-                    .unwrap_or(false);
+                // Track the post-reopen count so a re-completed feature triggers again
+                last_done_count = FeatureList::load(&project_dir)
+                    .map(|f| f.status_counts().done)
+                    .unwrap_or(current_done);
 
-                on_update(OrchestrationUpdate {
-                    verify_results,
-                    reopened,
-                    all_done,
-                });
+                let all_done = update.all_done;
+                on_update(update);
 
                 if all_done {
                     break;
                 }
+                continue;
             }
+            last_done_count = current_done;
 
             // Also check if all done without new completions
             if features.all_done() {
@@ -96,3 +126,72 @@ pub async fn run_orchestration(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{Feature, FeatureStatus, FeatureType};
+
+    fn make_feature(id: &str, status: FeatureStatus) -> Feature {
+        Feature {
+            id: id.into(),
+            feature_type: FeatureType::Implement,
+            scope: "test".into(),
+            description: "test feature".into(),
+            verify: format!("./scripts/verify/{id}.sh"),
+            depends_on: vec![],
+            priority: 1,
+            status,
+            claimed_by: Some("agent-1".into()),
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    fn setup(dir: &Path) {
+        std::fs::create_dir_all(dir.join("scripts/verify")).unwrap();
+        std::fs::write(dir.join("scripts/verify/f001.sh"), "exit 1").unwrap();
+        std::fs::write(dir.join("scripts/verify/f002.sh"), "touch f002.ran\nexit 1").unwrap();
+        std::fs::write(dir.join("scripts/verify/f003.sh"), "exit 0").unwrap();
+        FeatureList {
+            features: vec![
+                make_feature("f001", FeatureStatus::Done),
+                make_feature("f002", FeatureStatus::Claimed),
+                make_feature("f003", FeatureStatus::Done),
+            ],
+        }
+        .save(dir)
+        .unwrap();
+    }
+
+    #[test]
+    fn orchestrate_once_reopens_failed_done_features() {
+        let dir = tempfile::tempdir().unwrap();
+        setup(dir.path());
+
+        let update = orchestrate_once(dir.path(), &HashSet::new()).unwrap();
+        assert_eq!(update.verify_results.len(), 3);
+        assert_eq!(update.reopened, vec!["f001", "f002"]);
+        assert!(dir.path().join("f002.ran").exists());
+        assert!(!update.all_done);
+
+        let features = FeatureList::load(dir.path()).unwrap();
+        assert_eq!(features.features[0].status, FeatureStatus::Pending);
+        assert_eq!(features.features[2].status, FeatureStatus::Done);
+    }
+
+    #[test]
+    fn orchestrate_once_skips_in_flight_features() {
+        let dir = tempfile::tempdir().unwrap();
+        setup(dir.path());
+
+        let in_flight: HashSet<String> = ["f002".to_string()].into();
+        let update = orchestrate_once(dir.path(), &in_flight).unwrap();
+        assert!(update.verify_results.iter().all(|r| r.feature_id != "f002"));
+        assert!(!dir.path().join("f002.ran").exists(), "f002's script must not run mid-session");
+        assert_eq!(update.reopened, vec!["f001"]);
+
+        let features = FeatureList::load(dir.path()).unwrap();
+        assert_eq!(features.features[1].status, FeatureStatus::Claimed);
+    }
+}
@@ -0,0 +1,169 @@
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::features::FeatureStatus;
+use crate::replay::{self, Replay};
+
+/// Steps one key press moves with PageUp/PageDown.
+const PAGE: usize = 10;
+
+fn status_style(status: &FeatureStatus) -> Style {
+    let color = match status {
+        FeatureStatus::Pending => Color::DarkGray,
+        FeatureStatus::Claimed => Color::Yellow,
+        FeatureStatus::Done => Color::Green,
+        FeatureStatus::Blocked => Color::Red,
+        FeatureStatus::Waiting => Color::Magenta,
+    };
+    Style::default().fg(color)
+}
+
+/// Step through `replay`, from the start: ←/→ one event, PageUp/PageDown
+/// ten, Home/End to either end, q to quit.
+pub fn run(replay: &Replay) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut position = 0;
+    let last = replay.steps.len();
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| render(replay, position, frame.area(), frame)) {
+            break Err(e);
+        }
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e),
+        };
+        match step(position, last, key.code) {
+            Some(next) => position = next,
+            None => break Ok(()),
+        }
+    };
+    ratatui::restore();
+    result
+}
+
+/// Where `key` moves from `position`, kept within `0..=last`; None to quit.
+fn step(position: usize, last: usize, key: KeyCode) -> Option<usize> {
+    Some(match key {
+        KeyCode::Char('q') | KeyCode::Esc => return None,
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => (position + 1).min(last),
+        KeyCode::Left | KeyCode::Char('h') => position.saturating_sub(1),
+        KeyCode::PageDown => (position + PAGE).min(last),
+        KeyCode::PageUp => position.saturating_sub(PAGE),
+        KeyCode::Home | KeyCode::Char('g') => 0,
+        KeyCode::End | KeyCode::Char('G') => last,
+        _ => position,
+    })
+}
+
+/// The project after `position` steps: features on the left, open sessions
+/// and the timeline around the current event on the right.
+fn render(replay: &Replay, position: usize, area: Rect, frame: &mut ratatui::Frame) {
+    let snapshot = replay.at(position);
+    let current = position.checked_sub(1).and_then(|i| replay.steps.get(i));
+    let [header, body, footer] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)])
+        .areas(area);
+    let [left, right] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .areas(body);
+    let [sessions_area, timeline_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(snapshot.sessions.len().max(1) as u16 + 2), Constraint::Min(3)])
+        .areas(right);
+
+    let when = current.map(|s| s.event.ts.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_else(|| "start".into());
+    let title = format!(
+        " replay {} · step {position}/{} · {when} · {}/{} done",
+        replay.project,
+        replay.steps.len(),
+        snapshot.count(FeatureStatus::Done),
+        snapshot.features.len(),
+    );
+    let header_style = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+    frame.render_widget(Paragraph::new(title).style(header_style), header);
+
+    let touched = current.map(|s| s.event.kind.feature_ids()).unwrap_or_default();
+    let features: Vec<Line> = snapshot
+        .features
+        .iter()
+        .map(|(id, status)| {
+            let marker = if touched.contains(&id.as_str()) { "» " } else { "  " };
+            Line::from(vec![
+                Span::raw(format!("{marker}{id:<8} ")),
+                Span::styled(format!("{status:?}").to_lowercase(), status_style(status)),
+            ])
+        })
+        .collect();
+    let features_block = Block::default().borders(Borders::ALL).title(" features ");
+    frame.render_widget(Paragraph::new(features).block(features_block), left);
+
+    let sessions: Vec<Line> = match snapshot.sessions.is_empty() {
+        true => vec![Line::styled("none", Style::default().fg(Color::DarkGray))],
+        false => snapshot
+            .sessions
+            .iter()
+            .map(|(agent, feature)| Line::raw(format!("{agent:<14} {}", feature.as_deref().unwrap_or("-"))))
+            .collect(),
+    };
+    let verify = snapshot.verify.map(|(passed, failed)| format!(" · verify {passed}/{}", passed + failed));
+    let sessions_title = format!(" sessions ({} started{}) ", snapshot.sessions_started, verify.unwrap_or_default());
+    frame.render_widget(
+        Paragraph::new(sessions).block(Block::default().borders(Borders::ALL).title(sessions_title)),
+        sessions_area,
+    );
+
+    // Keep the current event in view, a third of the way down
+    let rows = timeline_area.height.saturating_sub(2) as usize;
+    let first = position.saturating_sub(rows / 3 + 1).min(replay.steps.len().saturating_sub(rows));
+    let timeline: Vec<Line> = replay
+        .steps
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(i, step)| {
+            let text = format!("{} {}", step.event.ts.format("%H:%M:%S"), replay::describe(&step.event));
+            let style = match (i + 1).cmp(&position) {
+                std::cmp::Ordering::Equal => Style::default().add_modifier(Modifier::REVERSED),
+                std::cmp::Ordering::Greater => Style::default().fg(Color::DarkGray),
+                std::cmp::Ordering::Less => Style::default(),
+            };
+            Line::styled(text, style)
+        })
+        .collect();
+    let timeline_block = Block::default().borders(Borders::ALL).title(" timeline ");
+    frame.render_widget(Paragraph::new(timeline).block(timeline_block), timeline_area);
+
+    let help = " ←/→ step  PgUp/PgDn ±10  Home/End  q quit";
+    frame.render_widget(Paragraph::new(help).style(Style::default().fg(Color::DarkGray)), footer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_stay_within_the_replay() {
+        assert_eq!(step(5, 5, KeyCode::Right), Some(5));
+        assert_eq!(step(4, 5, KeyCode::Char(' ')), Some(5));
+        assert_eq!(step(0, 5, KeyCode::Left), Some(0));
+        assert_eq!(step(3, 5, KeyCode::Char('h')), Some(2));
+        assert_eq!(step(3, 25, KeyCode::PageDown), Some(13));
+        assert_eq!(step(20, 25, KeyCode::PageDown), Some(25));
+        assert_eq!(step(13, 25, KeyCode::PageUp), Some(3));
+        assert_eq!(step(4, 25, KeyCode::PageUp), Some(0));
+        assert_eq!(step(7, 25, KeyCode::Home), Some(0));
+        assert_eq!(step(7, 25, KeyCode::Char('G')), Some(25));
+        assert_eq!(step(7, 25, KeyCode::Char('x')), Some(7));
+        assert_eq!(step(7, 25, KeyCode::Char('q')), None);
+        assert_eq!(step(7, 25, KeyCode::Esc), None);
+    }
+}
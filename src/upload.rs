@@ -0,0 +1,210 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{UploadConfig, UploadProvider};
+
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error("{tool} not found — install it to upload to {provider}")]
+    ToolMissing { tool: String, provider: String },
+    #[error("{tool} failed: {stderr}")]
+    Failed { tool: String, stderr: String },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One object-storage provider. Implementations shell out to the provider's
+/// own CLI, which picks up credentials from its usual environment variables
+/// (`AWS_*`, `GOOGLE_APPLICATION_CREDENTIALS`, ...), so forge never sees them.
+pub trait Uploader {
+    /// Provider name for messages, e.g. "s3".
+    fn provider(&self) -> &'static str;
+    /// The command that copies `file` to `key` in the bucket.
+    fn command(&self, file: &Path, key: &str) -> Command;
+    /// Where the object ends up, e.g. `s3://bucket/key`.
+    fn url(&self, key: &str) -> String;
+
+    fn upload(&self, file: &Path, key: &str) -> Result<String, UploadError> {
+        let mut cmd = self.command(file, key);
+        let tool = cmd.get_program().to_string_lossy().to_string();
+        let output = cmd.output().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => UploadError::ToolMissing {
+                tool: tool.clone(),
+                provider: self.provider().to_string(),
+            },
+            _ => UploadError::Io(e),
+        })?;
+        if !output.status.success() {
+            return Err(UploadError::Failed {
+                tool,
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(self.url(key))
+    }
+}
+
+/// Amazon S3 or any S3-compatible store (MinIO, R2) via `endpoint`.
+pub struct S3Uploader {
+    bucket: String,
+    endpoint: Option<String>,
+    region: Option<String>,
+}
+
+impl Uploader for S3Uploader {
+    fn provider(&self) -> &'static str {
+        "s3"
+    }
+
+    fn command(&self, file: &Path, key: &str) -> Command {
+        let mut cmd = Command::new("aws");
+        cmd.args(["s3", "cp", "--only-show-errors"]);
+        cmd.arg(file).arg(self.url(key));
+        if let Some(endpoint) = &self.endpoint {
+            cmd.args(["--endpoint-url", endpoint]);
+        }
+        if let Some(region) = &self.region {
+            cmd.args(["--region", region]);
+        }
+        cmd
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("s3://{}/{key}", self.bucket)
+    }
+}
+
+/// Google Cloud Storage via `gcloud storage`.
+pub struct GcsUploader {
+    bucket: String,
+}
+
+impl Uploader for GcsUploader {
+    fn provider(&self) -> &'static str {
+        "gcs"
+    }
+
+    fn command(&self, file: &Path, key: &str) -> Command {
+        let mut cmd = Command::new("gcloud");
+        cmd.args(["storage", "cp", "--quiet"]);
+        cmd.arg(file).arg(self.url(key));
+        cmd
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("gs://{}/{key}", self.bucket)
+    }
+}
+
+pub fn uploader_for(cfg: &UploadConfig) -> Box<dyn Uploader> {
+    match cfg.provider {
+        UploadProvider::S3 => Box::new(S3Uploader {
+            bucket: cfg.bucket.clone(),
+            endpoint: cfg.endpoint.clone(),
+            region: cfg.region.clone(),
+        }),
+        UploadProvider::Gcs => Box::new(GcsUploader {
+            bucket: cfg.bucket.clone(),
+        }),
+    }
+}
+
+/// `<prefix>/<timestamp>-<file name>`, so repeated nightly uploads of the
+/// same archive name never overwrite each other.
+pub fn object_key(prefix: &str, file: &Path, at: chrono::DateTime<chrono::Utc>) -> String {
+    let name = file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "forge-export".to_string());
+    let stamped = format!("{}-{name}", at.format("%Y%m%dT%H%M%SZ"));
+    match prefix.trim_matches('/') {
+        "" => stamped,
+        prefix => format!("{prefix}/{stamped}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect()
+    }
+
+    fn config(provider: UploadProvider) -> UploadConfig {
+        UploadConfig {
+            provider,
+            bucket: "team-exports".into(),
+            prefix: String::new(),
+            endpoint: None,
+            region: None,
+        }
+    }
+
+    #[test]
+    fn s3_command() {
+        let cfg = UploadConfig {
+            endpoint: Some("https://minio.local".into()),
+            region: Some("eu-west-1".into()),
+            ..config(UploadProvider::S3)
+        };
+        let uploader = uploader_for(&cfg);
+        let cmd = uploader.command(Path::new("out.tar.gz"), "nightly/x.tar.gz");
+        assert_eq!(cmd.get_program(), "aws");
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "s3",
+                "cp",
+                "--only-show-errors",
+                "out.tar.gz",
+                "s3://team-exports/nightly/x.tar.gz",
+                "--endpoint-url",
+                "https://minio.local",
+                "--region",
+                "eu-west-1"
+            ]
+        );
+    }
+
+    #[test]
+    fn gcs_command() {
+        let uploader = uploader_for(&config(UploadProvider::Gcs));
+        let cmd = uploader.command(Path::new("out.zip"), "x.zip");
+        assert_eq!(cmd.get_program(), "gcloud");
+        assert_eq!(args(&cmd), vec!["storage", "cp", "--quiet", "out.zip", "gs://team-exports/x.zip"]);
+        assert_eq!(uploader.provider(), "gcs");
+    }
+
+    #[test]
+    fn keys_are_timestamped_under_prefix() {
+        let at = chrono::DateTime::parse_from_rfc3339("2026-03-01T02:00:00Z")
+            .unwrap()
+            .to_utc();
+        let file = Path::new(".forge/export.tar.gz");
+        assert_eq!(object_key("/forge/nightly/", file, at), "forge/nightly/20260301T020000Z-export.tar.gz");
+        assert_eq!(object_key("", file, at), "20260301T020000Z-export.tar.gz");
+    }
+
+    struct Failing;
+
+    impl Uploader for Failing {
+        fn provider(&self) -> &'static str {
+            "test"
+        }
+        fn command(&self, _file: &Path, _key: &str) -> Command {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", "echo denied >&2; exit 1"]);
+            cmd
+        }
+        fn url(&self, key: &str) -> String {
+            key.to_string()
+        }
+    }
+
+    #[test]
+    fn failed_upload_reports_stderr() {
+        let err = Failing.upload(Path::new("x"), "k").unwrap_err();
+        assert_eq!(err.to_string(), "sh failed: denied");
+    }
+}
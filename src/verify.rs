@@ -1,8 +1,10 @@
 use serde::Serialize;
+use std::collections::HashSet;
 use std::path::Path;
 use std::process::Command;
 
-use crate::features::{FeatureList, FeatureStatus};
+use crate::config::ForgeConfig;
+use crate::features::{Feature, FeatureList, FeatureStatus};
 
 #[derive(Debug)]
 pub struct VerifyResult {
@@ -17,6 +19,8 @@ pub struct VerifyReport {
     pub pass: usize,
     pub fail: usize,
     pub total: usize,
+    /// Features whose verify passed, so the report covers every feature checked.
+    pub passed: Vec<String>,
     pub failures: Vec<VerifyFailure>,
 }
 
@@ -30,6 +34,11 @@ impl VerifyReport {
     pub fn from_results(results: &[VerifyResult]) -> Self {
         let pass = results.iter().filter(|r| r.passed).count();
         let fail = results.len() - pass;
+        let passed = results
+            .iter()
+            .filter(|r| r.passed)
+            .map(|r| r.feature_id.clone())
+            .collect();
         let failures = results
             .iter()
             .filter(|r| !r.passed)
@@ -42,6 +51,7 @@ impl VerifyReport {
             pass,
             fail,
             total: results.len(),
+            passed,
             failures,
         }
     }
@@ -50,7 +60,7 @@ impl VerifyReport {
         let feedback_dir = project_dir.join("feedback");
         std::fs::create_dir_all(&feedback_dir)?;
         let json = serde_json::to_string_pretty(self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(std::io::Error::other)?;
         std::fs::write(feedback_dir.join("last-verify.json"), json)?;
         Ok(())
     }
@@ -58,11 +68,11 @@ impl VerifyReport {
 
 /// Run verify script for a single feature. Returns None if feature has no verify command.
 pub fn run_verify(project_dir: &Path, verify_cmd: &str) -> Result<VerifyResult, std::io::Error> {
-    let output = Command::new("bash")
-        .arg("-c")
-        .arg(verify_cmd)
-        .current_dir(project_dir)
-        .output()?;
+    run(Command::new("bash").arg("-c").arg(verify_cmd).current_dir(project_dir))
+}
+
+fn run(command: &mut Command) -> Result<VerifyResult, std::io::Error> {
+    let output = command.output()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -77,31 +87,62 @@ pub fn run_verify(project_dir: &Path, verify_cmd: &str) -> Result<VerifyResult,
 
 /// Run all verify scripts for done/claimed features.
 pub fn verify_all(project_dir: &Path) -> Result<Vec<VerifyResult>, Box<dyn std::error::Error>> {
+    verify_all_except(project_dir, &HashSet::new())
+}
+
+/// `verify_all`, without running the scripts of the features in `skip`.
+pub fn verify_all_except(
+    project_dir: &Path,
+    skip: &HashSet<String>,
+) -> Result<Vec<VerifyResult>, Box<dyn std::error::Error>> {
     let features = FeatureList::load(project_dir)?;
+    let config = ForgeConfig::load(project_dir).ok();
     let mut results = Vec::new();
 
-    for feature in &features.features {
+    for feature in features.features.iter().filter(|f| !skip.contains(&f.id)) {
         if feature.status == FeatureStatus::Done || feature.status == FeatureStatus::Claimed {
-            let script_path = project_dir.join(&feature.verify);
-            if !script_path.exists() {
-                results.push(VerifyResult {
-                    feature_id: feature.id.clone(),
-                    passed: false,
-                    output: format!("verify script not found: {}", feature.verify),
-                });
-                continue;
-            }
-
-            let cmd = format!("bash {}", feature.verify);
-            let mut result = run_verify(project_dir, &cmd)?;
-            result.feature_id = feature.id.clone();
-            results.push(result);
+            results.push(verify_in_scope(project_dir, config.as_ref(), feature)?);
         }
     }
 
     Ok(results)
 }
 
+/// Run one feature's verify script, whatever its status. A missing script
+/// is a failure.
+pub fn verify_feature(project_dir: &Path, feature: &Feature) -> Result<VerifyResult, std::io::Error> {
+    verify_in_scope(project_dir, ForgeConfig::load(project_dir).ok().as_ref(), feature)
+}
+
+/// The script is found from the project root and run from the feature's
+/// scope `dir`, when it has one.
+fn verify_in_scope(
+    project_dir: &Path,
+    config: Option<&ForgeConfig>,
+    feature: &Feature,
+) -> Result<VerifyResult, std::io::Error> {
+    let script_path = project_dir.join(&feature.verify);
+    let failed = |output: String| VerifyResult { feature_id: feature.id.clone(), passed: false, output };
+    if !script_path.exists() {
+        return Ok(failed(format!("verify script not found: {}", feature.verify)));
+    }
+
+    let mut result = match config.and_then(|c| c.scope_dir(&feature.scope)) {
+        Some(dir) if !project_dir.join(dir).is_dir() => {
+            return Ok(failed(format!("scope {} dir not found: {dir}", feature.scope)));
+        }
+        Some(dir) => run(
+            Command::new("bash")
+                .arg(&script_path)
+                .current_dir(project_dir.join(dir))
+                .env("FORGE_PROJECT_DIR", project_dir),
+        )?,
+        None => run_verify(project_dir, &format!("bash {}", feature.verify))?,
+    };
+    result.feature_id = feature.id.clone();
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,6 +236,7 @@ mod tests {
         assert_eq!(report.pass, 1);
         assert_eq!(report.fail, 1);
         assert_eq!(report.total, 2);
+        assert_eq!(report.passed, vec!["f001"]);
         assert_eq!(report.failures.len(), 1);
         assert_eq!(report.failures[0].feature_id, "f002");
 
@@ -222,4 +264,30 @@ mod tests {
         assert!(!results[0].passed);
         assert!(results[0].output.contains("not found"));
     }
+
+    #[test]
+    fn verify_runs_from_the_scope_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("services/api")).unwrap();
+        std::fs::create_dir_all(dir.path().join("scripts/verify")).unwrap();
+        std::fs::write(
+            dir.path().join("forge.toml"),
+            "[project]\nname = \"t\"\n\n[scopes.api]\ndir = \"services/api\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("services/api/go.mod"), "").unwrap();
+        std::fs::write(
+            dir.path().join("scripts/verify/f001.sh"),
+            "test -f go.mod && test -f \"$FORGE_PROJECT_DIR/forge.toml\"",
+        )
+        .unwrap();
+        let mut feature = make_feature("f001", "./scripts/verify/f001.sh", FeatureStatus::Done);
+        feature.scope = "api".into();
+
+        let result = verify_feature(dir.path(), &feature).unwrap();
+        assert!(result.passed, "{}", result.output);
+        std::fs::remove_dir_all(dir.path().join("services")).unwrap();
+        let result = verify_feature(dir.path(), &feature).unwrap();
+        assert_eq!(result.output, "scope api dir not found: services/api");
+    }
 }
@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+use crate::features::{Feature, FeatureStatus, FeatureType};
+
+/// Per-feature script `forge feature add` copies, with `{{id}}` and
+/// `{{description}}` filled in. Edit it to change what new scripts start as.
+pub const TEMPLATE: &str = "scripts/verify/_template.sh";
+/// Build and style checks every feature's script runs first.
+pub const GLOBAL_BUILD: &str = "scripts/verify/_global/build.sh";
+
+/// Build and style commands, and the test command, for the toolchains a
+/// `[project] stack` names.
+pub(crate) fn commands(stack: &str) -> (Vec<&'static str>, Vec<&'static str>) {
+    let stack = stack.to_lowercase();
+    let names = |words: &[&str]| stack.split(|c: char| !c.is_alphanumeric()).any(|w| words.contains(&w));
+    let (mut build, mut test) = (Vec::new(), Vec::new());
+    if names(&["rust", "cargo"]) {
+        build.extend(["cargo build --all-targets", "cargo fmt --check", "cargo clippy --all-targets -- -D warnings"]);
+        test.push("cargo test");
+    }
+    if names(&["typescript", "javascript", "node", "npm", "pnpm", "yarn"]) {
+        build.extend(["npm run build --if-present", "npm run lint --if-present"]);
+        test.push(if names(&["pnpm"]) { "pnpm test" } else if names(&["yarn"]) { "yarn test" } else { "npm test" });
+    }
+    if names(&["python", "pip", "uv", "poetry"]) {
+        build.extend(["ruff check .", "ruff format --check ."]);
+        test.push("pytest");
+    }
+    if names(&["go", "golang"]) {
+        build.extend(["go build ./...", "go vet ./...", "test -z \"$(gofmt -l .)\""]);
+        test.push("go test ./...");
+    }
+    (build, test)
+}
+
+/// `_global/build.sh` for a stack.
+pub fn global_build(stack: &str) -> String {
+    let (build, _) = commands(stack);
+    let mut script = String::from(
+        "#!/usr/bin/env bash\n\
+         # Build and style checks (P3) that every feature's verify script runs first.\n\
+         set -euo pipefail\n\
+         cd \"$(dirname \"$0\")/../../..\"\n\n",
+    );
+    if build.is_empty() {
+        script.push_str("# TODO: build the project and check its style, failing on warnings\n");
+    }
+    for command in build {
+        script.push_str(command);
+        script.push('\n');
+    }
+    script
+}
+
+/// `_template.sh` for a stack.
+pub fn feature_template(stack: &str) -> String {
+    let (_, test) = commands(stack);
+    let mut script = String::from(
+        "#!/usr/bin/env bash\n\
+         # Verify {{id}}: {{description}}\n\
+         # Exit 0 only when the feature works: tests prove it, not that it builds (P2).\n\
+         set -euo pipefail\n\
+         cd \"$(dirname \"$0\")/../..\"\n\n\
+         scripts/verify/_global/build.sh\n\n\
+         # TODO: run only the tests that prove {{id}}, one per requirement in its description\n",
+    );
+    if test.is_empty() {
+        // Failing until it's written, so nothing passes unchecked
+        script.push_str("echo \"scripts/verify/{{id}}.sh: no checks yet\" >&2\nexit 1\n");
+    }
+    for command in test {
+        script.push_str(command);
+        script.push('\n');
+    }
+    script
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(path)?.permissions();
+    perms.set_mode(perms.mode() | 0o111);
+    std::fs::set_permissions(path, perms)
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn write_script(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    make_executable(path)
+}
+
+/// Write `_template.sh` and `_global/build.sh` for `stack`, leaving ones
+/// already there alone.
+pub fn write_templates(project_dir: &Path, stack: &str) -> std::io::Result<()> {
+    for (path, content) in [(GLOBAL_BUILD, global_build(stack)), (TEMPLATE, feature_template(stack))] {
+        let path = project_dir.join(path);
+        if !path.exists() {
+            write_script(&path, &content)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a feature's verify script from `_template.sh` (or the stack's
+/// default), unless it exists or `verify` isn't a script path. Returns the
+/// script written. A feature that's already done (a `[x]` plan item) gets
+/// one that passes, so `forge verify` doesn't reopen it.
+pub fn scaffold(project_dir: &Path, stack: &str, feature: &Feature) -> std::io::Result<Option<PathBuf>> {
+    let relative = feature.verify.trim_start_matches("./");
+    if !relative.ends_with(".sh") || relative.contains(char::is_whitespace) {
+        return Ok(None);
+    }
+    let path = project_dir.join(relative);
+    if path.exists() {
+        return Ok(None);
+    }
+    if feature.status == FeatureStatus::Done {
+        let script = format!(
+            "#!/usr/bin/env bash\n\
+             # Verify {}: done before forge tracked it; replace with a real check.\n\
+             exit 0\n",
+            feature.id
+        );
+        write_script(&path, &script)?;
+        return Ok(Some(path));
+    }
+    let template = std::fs::read_to_string(project_dir.join(TEMPLATE)).unwrap_or_else(|_| feature_template(stack));
+    let description = feature.description.lines().next().unwrap_or("").trim();
+    let mut script = template.replace("{{id}}", &feature.id).replace("{{description}}", description);
+    if feature.feature_type == FeatureType::Poc {
+        script.push_str(&format!("\n# The POC's outcome\ntest -f context/poc/{}.md\n", feature.id));
+    }
+    write_script(&path, &script)?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, feature_type: FeatureType) -> Feature {
+        Feature {
+            id: id.into(),
+            feature_type,
+            scope: "db".into(),
+            description: "Connection pool\nBounded at 10.".into(),
+            verify: format!("./scripts/verify/{id}.sh"),
+            depends_on: vec![],
+            priority: 1,
+            status: FeatureStatus::Pending,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    #[test]
+    fn templates_follow_the_stack() {
+        let rust = global_build("Rust, axum, sqlx");
+        assert!(rust.contains("cargo clippy --all-targets -- -D warnings\n"), "{rust}");
+        assert!(!rust.contains("go vet"), "{rust}");
+        assert!(global_build("Go").contains("go vet ./...\n"));
+        assert!(global_build("Haskell").contains("# TODO: build the project"));
+        assert!(feature_template("TypeScript, pnpm").contains("\npnpm test\n"));
+        assert!(feature_template("").ends_with("\nexit 1\n"));
+    }
+
+    #[test]
+    fn scaffolds_from_the_project_template() {
+        let dir = tempfile::tempdir().unwrap();
+        write_templates(dir.path(), "Python").unwrap();
+        std::fs::write(dir.path().join(TEMPLATE), "#!/bin/sh\n# {{id}}: {{description}}\npytest -k pool\n").unwrap();
+
+        let path = scaffold(dir.path(), "Python", &feature("f003", FeatureType::Implement)).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "#!/bin/sh\n# f003: Connection pool\npytest -k pool\n");
+        // Never overwritten
+        assert!(scaffold(dir.path(), "Python", &feature("f003", FeatureType::Implement)).unwrap().is_none());
+
+        let poc = scaffold(dir.path(), "Python", &feature("p001", FeatureType::Poc)).unwrap().unwrap();
+        assert!(std::fs::read_to_string(poc).unwrap().ends_with("test -f context/poc/p001.md\n"));
+
+        let done = Feature { status: FeatureStatus::Done, ..feature("f005", FeatureType::Implement) };
+        let done = scaffold(dir.path(), "Python", &done).unwrap().unwrap();
+        assert!(std::process::Command::new("sh").arg(&done).status().unwrap().success());
+
+        let inline = Feature { verify: "cargo test pool".into(), ..feature("f004", FeatureType::Implement) };
+        assert!(scaffold(dir.path(), "Python", &inline).unwrap().is_none());
+    }
+}
@@ -0,0 +1,358 @@
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use tracing::{info, warn};
+
+use crate::features::{FeatureList, FeatureStatus, StatusCounts};
+use crate::git;
+use crate::runner::{self, RunConfig, RunOutcome};
+
+pub const WORKSPACE_FILE: &str = "forge.workspace.toml";
+
+#[derive(Deserialize)]
+struct WorkspaceFile {
+    workspace: Workspace,
+}
+
+/// `forge.workspace.toml`: forge projects run together under one agent
+/// budget, e.g.
+///
+/// ```toml
+/// [workspace]
+/// members = ["api", "web", "../infra"]
+/// max_agents = 4
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct Workspace {
+    /// Project directories, relative to the workspace file.
+    pub members: Vec<String>,
+    /// Agents running at once across all members.
+    #[serde(default = "default_max_agents")]
+    pub max_agents: usize,
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+fn default_max_agents() -> usize {
+    4
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceError {
+    #[error("failed to read {WORKSPACE_FILE}: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse {WORKSPACE_FILE}: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("{WORKSPACE_FILE} lists no members")]
+    NoMembers,
+    #[error("workspace member '{0}' has no forge.toml")]
+    NotAProject(String),
+}
+
+impl Workspace {
+    pub fn load(root: &Path) -> Result<Self, WorkspaceError> {
+        let content = std::fs::read_to_string(root.join(WORKSPACE_FILE))?;
+        let mut workspace = toml::from_str::<WorkspaceFile>(&content)?.workspace;
+        if workspace.members.is_empty() {
+            return Err(WorkspaceError::NoMembers);
+        }
+        workspace.root = root.to_path_buf();
+        if let Some((name, _)) = workspace.member_dirs().find(|(_, dir)| !dir.join("forge.toml").exists()) {
+            return Err(WorkspaceError::NotAProject(name.to_string()));
+        }
+        Ok(workspace)
+    }
+
+    /// Each member's name (as listed) and directory.
+    pub fn member_dirs(&self) -> impl Iterator<Item = (&str, PathBuf)> {
+        self.members.iter().map(|m| (m.as_str(), self.root.join(m)))
+    }
+}
+
+/// Agents per member for one round: handed out one at a time in member
+/// order to those with claimable features left, until the budget or the
+/// work runs out. Every member with work gets an agent before any gets two.
+pub fn allocate(budget: usize, claimable: &[usize]) -> Vec<usize> {
+    let mut agents = vec![0; claimable.len()];
+    let mut left = budget;
+    while left > 0 {
+        let before = left;
+        for (given, want) in agents.iter_mut().zip(claimable) {
+            if left > 0 && *given < *want {
+                *given += 1;
+                left -= 1;
+            }
+        }
+        if left == before {
+            break;
+        }
+    }
+    agents
+}
+
+#[derive(Debug)]
+pub enum WorkspaceOutcome {
+    AllDone { rounds: usize },
+    MaxRounds { rounds: usize, remaining: usize },
+    /// Features are left but none can be claimed (blocked or waiting on
+    /// dependencies).
+    Stuck { rounds: usize, remaining: usize },
+    Stopped { rounds: usize },
+    SpawnError { member: String, error: std::io::Error },
+}
+
+fn remaining(list: &FeatureList) -> usize {
+    list.features.iter().filter(|f| f.status != FeatureStatus::Done).count()
+}
+
+/// Run every member in rounds. Each round splits the workspace's
+/// `max_agents` across members by `allocate`, runs one session per member
+/// at the same time, and waits for all of them, so agents freed by a
+/// finished project go to the others next round. `members` are the
+/// members' own run settings; only `num_agents` and `max_sessions` change.
+/// `forge stop` in the workspace root stops between rounds; in a member,
+/// it stops that member for the rest of the run.
+pub fn run(workspace: &Workspace, members: &[RunConfig], max_rounds: usize) -> WorkspaceOutcome {
+    let mut rounds = 0;
+    let mut stopped: Vec<String> = Vec::new();
+    loop {
+        if runner::stop_requested(&workspace.root) {
+            runner::clear_stop(&workspace.root);
+            return WorkspaceOutcome::Stopped { rounds };
+        }
+        // A member without features.json yet has nothing to do
+        let lists: Vec<Option<FeatureList>> = members.iter().map(|m| FeatureList::load(&m.project_dir).ok()).collect();
+        let left: usize = lists.iter().flatten().map(remaining).sum();
+        if left == 0 {
+            return WorkspaceOutcome::AllDone { rounds };
+        }
+        if rounds >= max_rounds {
+            return WorkspaceOutcome::MaxRounds { rounds, remaining: left };
+        }
+        let claimable: Vec<usize> = lists
+            .iter()
+            .zip(members)
+            .zip(&workspace.members)
+            .map(|((list, member), name)| {
+                if stopped.contains(name) {
+                    return 0;
+                }
+                let n = list.as_ref().map_or(0, |l| l.next_n_claimable(workspace.max_agents).len());
+                // Parallel agents need worktrees, so a git repo
+                if git::is_git_repo(&member.project_dir) { n } else { n.min(1) }
+            })
+            .collect();
+        let agents = allocate(workspace.max_agents, &claimable);
+        if agents.iter().all(|&n| n == 0) {
+            if !stopped.is_empty() {
+                return WorkspaceOutcome::Stopped { rounds };
+            }
+            return WorkspaceOutcome::Stuck { rounds, remaining: left };
+        }
+
+        let plan: Vec<String> = workspace
+            .members
+            .iter()
+            .zip(&agents)
+            .filter(|(_, n)| **n > 0)
+            .map(|(name, n)| format!("{name} ×{n}"))
+            .collect();
+        info!("Workspace round {}: {}", rounds + 1, plan.join(", "));
+
+        let outcomes: Vec<(String, RunOutcome)> = thread::scope(|s| {
+            let handles: Vec<_> = members
+                .iter()
+                .zip(&workspace.members)
+                .zip(&agents)
+                .filter(|(_, n)| **n > 0)
+                .map(|((member, name), &n)| {
+                    let config = RunConfig { num_agents: n, max_sessions: 1, ..member.clone() };
+                    let handle = s.spawn(move || {
+                        if n > 1 { runner::run_multi_agent(&config) } else { runner::run_single_agent(&config) }
+                    });
+                    (name.clone(), handle)
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|(name, h)| {
+                    let outcome = h
+                        .join()
+                        .unwrap_or_else(|_| RunOutcome::SpawnError(std::io::Error::other("member run panicked")));
+                    (name, outcome)
+                })
+                .collect()
+        });
+        for (member, outcome) in outcomes {
+            match outcome {
+                RunOutcome::SpawnError(error) => return WorkspaceOutcome::SpawnError { member, error },
+                // Someone ran `forge stop` in a member: leave it be, keep the rest going
+                RunOutcome::Stopped { .. } => {
+                    info!("{member}: stopped by request");
+                    stopped.push(member);
+                }
+                RunOutcome::CostLimit { spent_usd, limit_usd, .. } => {
+                    warn!("{member}: spent ${spent_usd:.2} of its max_total_cost ${limit_usd:.2}")
+                }
+                _ => {}
+            }
+        }
+        rounds += 1;
+    }
+}
+
+/// Feature progress per member and in total, for `forge status --workspace`.
+pub fn render_status(workspace: &Workspace) -> String {
+    let mut out = String::new();
+    let mut total = StatusCounts::default();
+    let width = workspace.members.iter().map(String::len).max().unwrap_or(0);
+    let mut lines = String::new();
+    for (name, dir) in workspace.member_dirs() {
+        let Ok(list) = FeatureList::load(&dir) else {
+            let _ = writeln!(lines, "  {name:width$}  no features.json");
+            continue;
+        };
+        let c = list.status_counts();
+        let pct = (c.done * 100).checked_div(c.total).unwrap_or(0);
+        let _ = write!(
+            lines,
+            "  {name:width$}  {} features: {} done, {} claimed, {} pending",
+            c.total, c.done, c.claimed, c.pending
+        );
+        if c.blocked > 0 {
+            let _ = write!(lines, ", {} blocked", c.blocked);
+        }
+        if c.waiting > 0 {
+            let _ = write!(lines, ", {} waiting", c.waiting);
+        }
+        let _ = writeln!(lines, " ({pct}%)");
+        total.total += c.total;
+        total.done += c.done;
+        total.claimed += c.claimed;
+        total.pending += c.pending;
+        total.blocked += c.blocked;
+        total.waiting += c.waiting;
+    }
+    let pct = (total.done * 100).checked_div(total.total).unwrap_or(0);
+    let _ = writeln!(
+        out,
+        "Workspace: {} projects, {} features ({} done, {} claimed, {} pending{}{}) {pct}%",
+        workspace.members.len(),
+        total.total,
+        total.done,
+        total.claimed,
+        total.pending,
+        if total.blocked > 0 { format!(", {} blocked", total.blocked) } else { String::new() },
+        if total.waiting > 0 { format!(", {} waiting", total.waiting) } else { String::new() },
+    );
+    out.push_str(&lines);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{Feature, FeatureType};
+
+    #[test]
+    fn allocate_spreads_before_stacking() {
+        assert_eq!(allocate(4, &[3, 3, 0]), vec![2, 2, 0]);
+        assert_eq!(allocate(4, &[1, 5, 2]), vec![1, 2, 1]);
+        assert_eq!(allocate(2, &[3, 3, 3]), vec![1, 1, 0]);
+        // Less work than budget: everything claimable gets an agent
+        assert_eq!(allocate(8, &[1, 2]), vec![1, 2]);
+        assert_eq!(allocate(3, &[0, 0]), vec![0, 0]);
+    }
+
+    fn feature(id: &str, status: FeatureStatus) -> Feature {
+        Feature {
+            id: id.into(),
+            feature_type: FeatureType::Implement,
+            scope: "core".into(),
+            description: id.into(),
+            verify: "true".into(),
+            depends_on: vec![],
+            priority: 1,
+            status,
+            claimed_by: None,
+            blocked_reason: None,
+            context_hints: vec![],
+        }
+    }
+
+    fn setup() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(WORKSPACE_FILE), "[workspace]\nmembers = [\"api\", \"web-app\"]\n").unwrap();
+        for member in ["api", "web-app"] {
+            std::fs::create_dir_all(tmp.path().join(member)).unwrap();
+            std::fs::write(tmp.path().join(member).join("forge.toml"), "[project]\nname = \"m\"\n").unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn load_checks_members() {
+        let tmp = setup();
+        let workspace = Workspace::load(tmp.path()).unwrap();
+        assert_eq!(workspace.max_agents, 4);
+        assert_eq!(workspace.member_dirs().nth(1).unwrap().1, tmp.path().join("web-app"));
+
+        std::fs::write(tmp.path().join(WORKSPACE_FILE), "[workspace]\nmembers = [\"api\", \"gone\"]\n").unwrap();
+        assert!(matches!(Workspace::load(tmp.path()), Err(WorkspaceError::NotAProject(m)) if m == "gone"));
+    }
+
+    #[test]
+    fn status_sums_members() {
+        let tmp = setup();
+        FeatureList { features: vec![feature("f001", FeatureStatus::Done), feature("f002", FeatureStatus::Pending)] }
+            .save(&tmp.path().join("api"))
+            .unwrap();
+        let status = render_status(&Workspace::load(tmp.path()).unwrap());
+        assert_eq!(
+            status,
+            "Workspace: 2 projects, 2 features (1 done, 0 claimed, 1 pending) 50%\n  \
+             api      2 features: 1 done, 0 claimed, 1 pending (50%)\n  \
+             web-app  no features.json\n"
+        );
+    }
+
+    #[test]
+    fn run_finishes_when_every_member_is_done() {
+        let tmp = setup();
+        FeatureList { features: vec![feature("f001", FeatureStatus::Done)] }.save(&tmp.path().join("api")).unwrap();
+        let workspace = Workspace::load(tmp.path()).unwrap();
+        let members: Vec<RunConfig> = workspace
+            .member_dirs()
+            .map(|(_, dir)| RunConfig {
+                project_dir: dir,
+                protocol: Default::default(),
+                orchestrating: Default::default(),
+                protocol_b: None,
+                max_sessions: 1,
+                num_agents: 1,
+                worktrees: false,
+                backends: Default::default(),
+                secrets: Default::default(),
+                git: Default::default(),
+                permissions: Default::default(),
+                runaway: Default::default(),
+                rate_limits: Default::default(),
+                max_total_cost: None,
+                chaos: None,
+            })
+            .collect();
+        assert!(matches!(run(&workspace, &members, 5), WorkspaceOutcome::AllDone { rounds: 0 }));
+
+        // Blocked work is reported, not spun on
+        FeatureList { features: vec![feature("f002", FeatureStatus::Blocked)] }.save(&tmp.path().join("web-app")).unwrap();
+        assert!(matches!(run(&workspace, &members, 5), WorkspaceOutcome::Stuck { rounds: 0, remaining: 1 }));
+
+        // A member stopped by `forge stop` gets no agents in later rounds
+        FeatureList { features: vec![feature("f003", FeatureStatus::Pending)] }.save(&tmp.path().join("api")).unwrap();
+        runner::request_stop(&tmp.path().join("api")).unwrap();
+        assert!(matches!(run(&workspace, &members, 5), WorkspaceOutcome::Stopped { rounds: 1 }));
+        assert_eq!(FeatureList::load(&tmp.path().join("api")).unwrap().features[0].status, FeatureStatus::Pending);
+    }
+}